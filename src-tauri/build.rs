@@ -6,6 +6,9 @@ fn main() {
     {
         println!("cargo:rustc-link-lib=framework=EventKit");
         println!("cargo:rustc-link-lib=framework=Foundation");
+        // On-device speech recognition for voice commands
+        println!("cargo:rustc-link-lib=framework=Speech");
+        println!("cargo:rustc-link-lib=framework=AVFoundation");
 
         // Ensure Info.plist is watched for changes
         println!("cargo:rerun-if-changed=Info.plist");
@@ -0,0 +1,764 @@
+use crate::chimes::ChimeFilter;
+use crate::levels::LevelRecorder;
+use parking_lot::Mutex;
+use rtrb::{Consumer, Producer, RingBuffer};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+
+pub const SOURCE_SAMPLE_RATE: u32 = 48_000;
+pub const TARGET_SAMPLE_RATE: u32 = 16_000;
+const FRAME_SIZE: usize = 480; // 10ms at 48 kHz
+pub const FRAME_MS: u64 = 10;
+const INPUT_QUEUE_CAPACITY: usize = SOURCE_SAMPLE_RATE as usize * 2; // 2s per source
+const PCM_QUEUE_CAPACITY: usize = TARGET_SAMPLE_RATE as usize * 2 * 2; // 2s of pcm_s16le
+const IDLE_WAIT: Duration = Duration::from_millis(2);
+
+/// Producer ends of the lock-free queues feeding the mixer thread.
+pub struct MixerInputs {
+    pub screen: Producer<f32>,
+    pub mic: Producer<f32>,
+}
+
+struct MixerShared {
+    stop: AtomicBool,
+    finished: AtomicBool,
+    frame_ready: Notify,
+    /// See `AudioMixer::tap_mic`.
+    mic_tap: Mutex<Option<Producer<f32>>>,
+    /// See `AudioMixer::tap_sources`.
+    source_tap: Mutex<Option<Producer<f32>>>,
+    options: MixerOptions,
+}
+
+/// How a mixer treats its sources, for [`AudioMixer::spawn_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MixerOptions {
+    /// Keep the sources apart; see [`AudioMixer::spawn_split`].
+    pub split: bool,
+    /// Silence notification sounds in the system audio before they reach the
+    /// transcript. They are counted either way; see [`ChimeFilter`].
+    pub filter_chimes: bool,
+}
+
+/// Queue depths and drop counters, updated by the mixer thread for diagnostics.
+#[derive(Debug, Default)]
+pub struct MixerStats {
+    screen_queued: AtomicUsize,
+    mic_queued: AtomicUsize,
+    pcm_queued: AtomicUsize,
+    dropped_input_samples: AtomicU64,
+    dropped_pcm_bytes: AtomicU64,
+    chimes: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MixerStatsSnapshot {
+    pub screen_queued: usize,
+    pub mic_queued: usize,
+    pub pcm_queued: usize,
+    pub dropped_input_samples: u64,
+    pub dropped_pcm_bytes: u64,
+    /// Notification sounds heard in the system audio.
+    pub chimes: u64,
+}
+
+impl MixerStats {
+    pub fn snapshot(&self) -> MixerStatsSnapshot {
+        MixerStatsSnapshot {
+            screen_queued: self.screen_queued.load(Ordering::Relaxed),
+            mic_queued: self.mic_queued.load(Ordering::Relaxed),
+            pcm_queued: self.pcm_queued.load(Ordering::Relaxed),
+            dropped_input_samples: self.dropped_input_samples.load(Ordering::Relaxed),
+            dropped_pcm_bytes: self.dropped_pcm_bytes.load(Ordering::Relaxed),
+            chimes: self.chimes.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn record_dropped_input(&self, samples: usize) {
+        if samples > 0 {
+            self.dropped_input_samples
+                .fetch_add(samples as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Sources left out of the mix, e.g. while the user is muted. Levels are
+/// still measured after filtering, so an excluded source reads as silence.
+#[derive(Debug, Default)]
+pub struct SourceFilter {
+    exclude_mic: AtomicBool,
+    exclude_screen: AtomicBool,
+}
+
+impl SourceFilter {
+    pub fn set(&self, exclude_mic: bool, exclude_screen: bool) {
+        self.exclude_mic.store(exclude_mic, Ordering::Relaxed);
+        self.exclude_screen.store(exclude_screen, Ordering::Relaxed);
+    }
+
+    fn gains(&self) -> (f32, f32) {
+        let gain = |excluded: &AtomicBool| {
+            if excluded.load(Ordering::Relaxed) {
+                0.0
+            } else {
+                1.0
+            }
+        };
+        (gain(&self.exclude_mic), gain(&self.exclude_screen))
+    }
+}
+
+/// Dedicated thread that mixes system and microphone audio into 16 kHz PCM frames.
+///
+/// Capture callbacks push raw samples into SPSC ring buffers, the mixer thread
+/// does all mixing/resampling without touching the tokio runtime, and the async
+/// side only drains encoded frames for WebSocket I/O.
+pub struct AudioMixer {
+    pcm_rx: Consumer<u8>,
+    frame_bytes: usize,
+    channels: u16,
+    shared: Arc<MixerShared>,
+    stats: Arc<MixerStats>,
+    filter: Arc<SourceFilter>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AudioMixer {
+    /// `levels`, when given, is reset and then fed the loudness of each source.
+    pub fn spawn(levels: Option<Arc<Mutex<LevelRecorder>>>) -> Result<(Self, MixerInputs), String> {
+        Self::spawn_with_options(levels, MixerOptions::default())
+    }
+
+    /// Like [`AudioMixer::spawn`], but keeps the sources apart: frames are
+    /// interleaved stereo with the microphone on the left and system audio on
+    /// the right, for transcribing each as its own channel.
+    pub fn spawn_split(
+        levels: Option<Arc<Mutex<LevelRecorder>>>,
+    ) -> Result<(Self, MixerInputs), String> {
+        let options = MixerOptions {
+            split: true,
+            ..MixerOptions::default()
+        };
+        Self::spawn_with_options(levels, options)
+    }
+
+    pub fn spawn_with_options(
+        levels: Option<Arc<Mutex<LevelRecorder>>>,
+        options: MixerOptions,
+    ) -> Result<(Self, MixerInputs), String> {
+        let (screen_tx, screen_rx) = RingBuffer::<f32>::new(INPUT_QUEUE_CAPACITY);
+        let (mixer, mic_tx) = Self::spawn_with(Some(screen_rx), levels, options)?;
+        Ok((
+            mixer,
+            MixerInputs {
+                screen: screen_tx,
+                mic: mic_tx,
+            },
+        ))
+    }
+
+    /// A mixer that only passes the microphone through, for sessions that do
+    /// not capture system audio.
+    pub fn spawn_mic_only(
+        levels: Option<Arc<Mutex<LevelRecorder>>>,
+    ) -> Result<(Self, Producer<f32>), String> {
+        Self::spawn_with(None, levels, MixerOptions::default())
+    }
+
+    fn spawn_with(
+        screen_rx: Option<Consumer<f32>>,
+        levels: Option<Arc<Mutex<LevelRecorder>>>,
+        options: MixerOptions,
+    ) -> Result<(Self, Producer<f32>), String> {
+        let channels: u16 = if options.split { 2 } else { 1 };
+        if let Some(levels) = &levels {
+            levels.lock().reset(SOURCE_SAMPLE_RATE, screen_rx.is_some());
+        }
+        let (mic_tx, mic_rx) = RingBuffer::<f32>::new(INPUT_QUEUE_CAPACITY);
+        let (pcm_tx, pcm_rx) = RingBuffer::<u8>::new(PCM_QUEUE_CAPACITY * channels as usize);
+
+        let shared = Arc::new(MixerShared {
+            stop: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            frame_ready: Notify::new(),
+            mic_tap: Mutex::new(None),
+            source_tap: Mutex::new(None),
+            options,
+        });
+
+        let stats = Arc::new(MixerStats::default());
+        let filter = Arc::new(SourceFilter::default());
+        let thread_shared = shared.clone();
+        let thread_stats = stats.clone();
+        let thread_filter = filter.clone();
+        let thread = std::thread::Builder::new()
+            .name("jilu-audio-mixer".into())
+            .spawn(move || {
+                mix_loop(
+                    screen_rx,
+                    mic_rx,
+                    pcm_tx,
+                    thread_shared,
+                    thread_stats,
+                    thread_filter,
+                    levels,
+                )
+            })
+            .map_err(|e| format!("Failed to spawn audio thread: {}", e))?;
+
+        let mixer = Self {
+            pcm_rx,
+            frame_bytes: pcm_frame_bytes() * channels as usize,
+            channels,
+            shared,
+            stats,
+            filter,
+            thread: Some(thread),
+        };
+        Ok((mixer, mic_tx))
+    }
+
+    /// 2 for a mixer from [`AudioMixer::spawn_split`], otherwise 1.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn stats(&self) -> Arc<MixerStats> {
+        self.stats.clone()
+    }
+
+    /// Switches sources in and out of the mix while it runs.
+    pub fn filter(&self) -> Arc<SourceFilter> {
+        self.filter.clone()
+    }
+
+    /// A copy of the microphone as it arrives, before any source is filtered
+    /// out, for listeners that must keep hearing it while muted. Replaces any
+    /// earlier tap; samples are dropped when the consumer falls behind.
+    pub fn tap_mic(&self) -> Consumer<f32> {
+        let (tx, rx) = RingBuffer::<f32>::new(INPUT_QUEUE_CAPACITY);
+        *self.shared.mic_tap.lock() = Some(tx);
+        rx
+    }
+
+    /// Microphone and system samples as they go into the mix, interleaved in
+    /// that order at [`SOURCE_SAMPLE_RATE`]. Sources filtered out are silent,
+    /// as is system audio for a mixer without it. Replaces any earlier tap;
+    /// samples are dropped when the consumer falls behind.
+    pub fn tap_sources(&self) -> Consumer<f32> {
+        let (tx, rx) = RingBuffer::<f32>::new(INPUT_QUEUE_CAPACITY * 2);
+        *self.shared.source_tap.lock() = Some(tx);
+        rx
+    }
+
+    /// Resolves once the mixer has produced at least one frame (or finished).
+    pub fn frame_ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        let shared = self.shared.clone();
+        async move { shared.frame_ready.notified().await }
+    }
+
+    pub fn pop_frame(&mut self) -> Option<Vec<u8>> {
+        let chunk = self.pcm_rx.read_chunk(self.frame_bytes).ok()?;
+        let (first, second) = chunk.as_slices();
+        let mut frame = Vec::with_capacity(self.frame_bytes);
+        frame.extend_from_slice(first);
+        frame.extend_from_slice(second);
+        chunk.commit_all();
+        Some(frame)
+    }
+
+    /// True once both capture sources have gone away and no more frames will arrive.
+    pub fn is_finished(&self) -> bool {
+        self.shared.finished.load(Ordering::Acquire) && self.pcm_rx.slots() < self.frame_bytes
+    }
+
+    pub fn stop(&mut self) {
+        self.shared.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for AudioMixer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Pushes as many samples as fit and returns how many had to be dropped.
+pub fn push_samples(producer: &mut Producer<f32>, samples: &[f32]) -> usize {
+    let writable = producer.slots().min(samples.len());
+    if writable > 0 {
+        if let Ok(chunk) = producer.write_chunk_uninit(writable) {
+            chunk.fill_from_iter(samples.iter().copied());
+        }
+    }
+    samples.len() - writable
+}
+
+/// Turns interleaved device audio into the mono `SOURCE_SAMPLE_RATE` stream
+/// the mixer takes, linearly resampling and carrying the read position across
+/// callbacks.
+pub struct InputConverter {
+    channels: usize,
+    step: f64,
+    position: f64,
+    previous: f32,
+    mono: Vec<f32>,
+    output: Vec<f32>,
+}
+
+impl InputConverter {
+    pub fn new(channels: usize, source_rate: u32) -> Self {
+        Self {
+            channels: channels.max(1),
+            step: f64::from(source_rate) / f64::from(SOURCE_SAMPLE_RATE),
+            position: 0.0,
+            previous: 0.0,
+            mono: Vec::with_capacity(4096),
+            output: Vec::with_capacity(4096),
+        }
+    }
+
+    pub fn convert(&mut self, samples: impl Iterator<Item = f32>) -> &[f32] {
+        self.mono.clear();
+        let mut sum = 0.0;
+        for (index, sample) in samples.enumerate() {
+            sum += sample;
+            if (index + 1) % self.channels == 0 {
+                self.mono.push(sum / self.channels as f32);
+                sum = 0.0;
+            }
+        }
+
+        self.output.clear();
+        // A position below zero falls between the previous callback's
+        // last sample and this one's first.
+        while self.position < self.mono.len() as f64 - 1.0 {
+            let index = self.position.floor();
+            let frac = (self.position - index) as f32;
+            let s0 = if index < 0.0 {
+                self.previous
+            } else {
+                self.mono[index as usize]
+            };
+            let s1 = self.mono[(index + 1.0) as usize];
+            self.output.push(s0 + (s1 - s0) * frac);
+            self.position += self.step;
+        }
+        if let Some(last) = self.mono.last() {
+            self.position -= self.mono.len() as f64;
+            self.previous = *last;
+        }
+        &self.output
+    }
+}
+
+/// Groups 10 ms mixer frames into larger WebSocket messages.
+pub struct AudioBatcher {
+    frames_per_batch: usize,
+    frames: usize,
+    buffer: Vec<u8>,
+    started: Option<Instant>,
+}
+
+impl AudioBatcher {
+    pub fn new(batch_ms: u64) -> Self {
+        let frames_per_batch = (batch_ms / FRAME_MS).max(1) as usize;
+        Self {
+            frames_per_batch,
+            frames: 0,
+            buffer: Vec::with_capacity(frames_per_batch * pcm_frame_bytes()),
+            started: None,
+        }
+    }
+
+    /// Changes how much audio a batch holds; one already fuller than that goes
+    /// out with the next frame.
+    pub fn set_batch_ms(&mut self, batch_ms: u64) {
+        self.frames_per_batch = (batch_ms / FRAME_MS).max(1) as usize;
+    }
+
+    /// Adds a frame, returning a full batch and how long its oldest frame waited.
+    pub fn push(&mut self, frame: &[u8]) -> Option<(Vec<u8>, Duration)> {
+        self.started.get_or_insert_with(Instant::now);
+        self.buffer.extend_from_slice(frame);
+        self.frames += 1;
+        if self.frames < self.frames_per_batch {
+            return None;
+        }
+        self.flush()
+    }
+
+    /// Returns whatever is buffered, even if the batch is not full.
+    pub fn flush(&mut self) -> Option<(Vec<u8>, Duration)> {
+        let started = self.started.take()?;
+        self.frames = 0;
+        let capacity = self.buffer.capacity();
+        let batch = std::mem::replace(&mut self.buffer, Vec::with_capacity(capacity));
+        Some((batch, started.elapsed()))
+    }
+}
+
+/// Playback duration of a pcm_s16le buffer at the target sample rate.
+pub fn pcm_duration(bytes: usize) -> Duration {
+    Duration::from_secs_f64(bytes as f64 / 2.0 / TARGET_SAMPLE_RATE as f64)
+}
+
+/// Cuts `pcm` into pieces of at most `max_bytes`, each ending on a whole frame
+/// of `channels` interleaved samples. Fits in one piece when it can.
+pub fn split_pcm(pcm: Vec<u8>, max_bytes: usize, channels: usize) -> Vec<Vec<u8>> {
+    if pcm.len() <= max_bytes {
+        return vec![pcm];
+    }
+    let frame = 2 * channels.max(1);
+    let piece = (max_bytes / frame).max(1) * frame;
+    pcm.chunks(piece).map(<[u8]>::to_vec).collect()
+}
+
+/// Splits interleaved pcm_s16le into one buffer per channel.
+pub fn deinterleave_pcm16(pcm: &[u8], channels: usize) -> Vec<Vec<u8>> {
+    let mut split = vec![Vec::with_capacity(pcm.len() / channels.max(1)); channels];
+    for frame in pcm.chunks_exact(2 * channels) {
+        for (channel, sample) in split.iter_mut().zip(frame.chunks_exact(2)) {
+            channel.extend_from_slice(sample);
+        }
+    }
+    split
+}
+
+fn pcm_frame_bytes() -> usize {
+    let ratio = SOURCE_SAMPLE_RATE as f32 / TARGET_SAMPLE_RATE as f32;
+    (FRAME_SIZE as f32 / ratio).ceil() as usize * 2
+}
+
+/// Turns frames of each source into the PCM that goes out.
+struct FrameEncoder {
+    split: bool,
+    mixed: Vec<f32>,
+    encoded: Vec<u8>,
+    encoded_system: Vec<u8>,
+    interleaved: Vec<u8>,
+}
+
+impl FrameEncoder {
+    fn new(split: bool) -> Self {
+        Self {
+            split,
+            mixed: Vec::with_capacity(FRAME_SIZE),
+            encoded: Vec::with_capacity(pcm_frame_bytes()),
+            encoded_system: Vec::new(),
+            interleaved: Vec::new(),
+        }
+    }
+
+    /// `system` is `None` for mixers without system audio.
+    fn encode(&mut self, mic: &[f32], system: Option<&[f32]>) -> &[u8] {
+        match system {
+            Some(system) if self.split => {
+                resample_into_pcm16(
+                    mic,
+                    SOURCE_SAMPLE_RATE,
+                    TARGET_SAMPLE_RATE,
+                    &mut self.encoded,
+                );
+                resample_into_pcm16(
+                    system,
+                    SOURCE_SAMPLE_RATE,
+                    TARGET_SAMPLE_RATE,
+                    &mut self.encoded_system,
+                );
+                self.interleaved.clear();
+                for (mic, system) in self
+                    .encoded
+                    .chunks_exact(2)
+                    .zip(self.encoded_system.chunks_exact(2))
+                {
+                    self.interleaved.extend_from_slice(mic);
+                    self.interleaved.extend_from_slice(system);
+                }
+                return &self.interleaved;
+            }
+            Some(system) => {
+                self.mixed.clear();
+                self.mixed
+                    .extend(system.iter().zip(mic).map(|(s, m)| (s + m) * 0.5));
+                resample_into_pcm16(
+                    &self.mixed,
+                    SOURCE_SAMPLE_RATE,
+                    TARGET_SAMPLE_RATE,
+                    &mut self.encoded,
+                );
+            }
+            None => {
+                resample_into_pcm16(
+                    mic,
+                    SOURCE_SAMPLE_RATE,
+                    TARGET_SAMPLE_RATE,
+                    &mut self.encoded,
+                );
+            }
+        }
+        &self.encoded
+    }
+}
+
+/// Queues a frame of PCM for the async side and returns how many bytes had
+/// to be dropped because it fell behind.
+fn send_pcm(
+    pcm_tx: &mut Producer<u8>,
+    pcm: &[u8],
+    shared: &MixerShared,
+    stats: &MixerStats,
+) -> usize {
+    let dropped = match pcm_tx.write_chunk_uninit(pcm.len()) {
+        Ok(chunk) => {
+            chunk.fill_from_iter(pcm.iter().copied());
+            0
+        }
+        Err(_) => {
+            stats
+                .dropped_pcm_bytes
+                .fetch_add(pcm.len() as u64, Ordering::Relaxed);
+            pcm.len()
+        }
+    };
+    shared.frame_ready.notify_one();
+    dropped
+}
+
+fn mix_loop(
+    mut screen_rx: Option<Consumer<f32>>,
+    mut mic_rx: Consumer<f32>,
+    mut pcm_tx: Producer<u8>,
+    shared: Arc<MixerShared>,
+    stats: Arc<MixerStats>,
+    filter: Arc<SourceFilter>,
+    levels: Option<Arc<Mutex<LevelRecorder>>>,
+) {
+    let squares = |a: &[f32], b: &[f32], gain: f32| {
+        a.iter()
+            .chain(b)
+            .map(|s| f64::from(*s * gain).powi(2))
+            .sum::<f64>()
+    };
+    let mut sources: Vec<f32> = Vec::with_capacity(FRAME_SIZE * 2);
+    let mut encoder = FrameEncoder::new(shared.options.split);
+    // Only system audio carries notification sounds.
+    let mut chimes = screen_rx
+        .is_some()
+        .then(|| ChimeFilter::new(shared.options.filter_chimes));
+    let mut dropped_bytes = 0usize;
+
+    while !shared.stop.load(Ordering::Acquire) {
+        let screen_queued = screen_rx.as_ref().map_or(0, Consumer::slots);
+        stats.screen_queued.store(screen_queued, Ordering::Relaxed);
+        stats.mic_queued.store(mic_rx.slots(), Ordering::Relaxed);
+        stats
+            .pcm_queued
+            .store(PCM_QUEUE_CAPACITY - pcm_tx.slots(), Ordering::Relaxed);
+
+        let screen_short = screen_rx.is_some() && screen_queued < FRAME_SIZE;
+        if screen_short || mic_rx.slots() < FRAME_SIZE {
+            if screen_rx.as_ref().is_none_or(Consumer::is_abandoned) && mic_rx.is_abandoned() {
+                break;
+            }
+            std::thread::sleep(IDLE_WAIT);
+            continue;
+        }
+
+        let Ok(mic) = mic_rx.read_chunk(FRAME_SIZE) else {
+            continue;
+        };
+        let (m0, m1) = mic.as_slices();
+        if let Some(tap) = shared.mic_tap.lock().as_mut() {
+            push_samples(tap, m0);
+            push_samples(tap, m1);
+        }
+        let (mic_gain, screen_gain) = filter.gains();
+        let mic_squares = levels.is_some().then(|| squares(m0, m1, mic_gain));
+        let mic_frame: Vec<f32> = m0.iter().chain(m1).map(|m| m * mic_gain).collect();
+        let system_frame = match screen_rx.as_mut().map(|rx| rx.read_chunk(FRAME_SIZE)) {
+            Some(Ok(screen)) => {
+                let (s0, s1) = screen.as_slices();
+                if let (Some(levels), Some(mic_squares)) = (&levels, mic_squares) {
+                    levels
+                        .lock()
+                        .push(mic_squares, squares(s0, s1, screen_gain), FRAME_SIZE);
+                }
+                let system: Vec<f32> = s0.iter().chain(s1).map(|s| s * screen_gain).collect();
+                screen.commit_all();
+                Some(system)
+            }
+            Some(Err(_)) => continue,
+            None => {
+                if let (Some(levels), Some(mic_squares)) = (&levels, mic_squares) {
+                    levels.lock().push(mic_squares, 0.0, FRAME_SIZE);
+                }
+                None
+            }
+        };
+        mic.commit_all();
+        if let Some(tap) = shared.source_tap.lock().as_mut() {
+            sources.clear();
+            match &system_frame {
+                Some(system) => {
+                    sources.extend(mic_frame.iter().zip(system).flat_map(|(m, s)| [*m, *s]))
+                }
+                None => sources.extend(mic_frame.iter().flat_map(|m| [*m, 0.0])),
+            }
+            push_samples(tap, &sources);
+        }
+
+        match (chimes.as_mut(), system_frame) {
+            (Some(chimes), Some(system_frame)) => {
+                if chimes.push(mic_frame, system_frame) {
+                    stats.chimes.fetch_add(1, Ordering::Relaxed);
+                }
+                while let Some((mic, system)) = chimes.pop() {
+                    dropped_bytes += send_pcm(
+                        &mut pcm_tx,
+                        encoder.encode(&mic, Some(&system)),
+                        &shared,
+                        &stats,
+                    );
+                }
+            }
+            (_, system_frame) => {
+                dropped_bytes += send_pcm(
+                    &mut pcm_tx,
+                    encoder.encode(&mic_frame, system_frame.as_deref()),
+                    &shared,
+                    &stats,
+                )
+            }
+        }
+    }
+
+    // Frames held back to filter chimes still belong to the recording.
+    if let Some(chimes) = chimes.as_mut() {
+        chimes.finish();
+        while let Some((mic, system)) = chimes.pop() {
+            dropped_bytes += send_pcm(
+                &mut pcm_tx,
+                encoder.encode(&mic, Some(&system)),
+                &shared,
+                &stats,
+            );
+        }
+    }
+
+    if dropped_bytes > 0 {
+        tracing::warn!(
+            "Audio mixer dropped {} bytes of PCM because the WebSocket side fell behind",
+            dropped_bytes
+        );
+    }
+
+    shared.finished.store(true, Ordering::Release);
+    shared.frame_ready.notify_one();
+}
+
+pub fn resample_to_pcm16(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<u8> {
+    let mut output = Vec::new();
+    resample_into_pcm16(samples, source_rate, target_rate, &mut output);
+    output
+}
+
+/// Resamples into a caller-owned buffer so the mixer thread never allocates per frame.
+fn resample_into_pcm16(samples: &[f32], source_rate: u32, target_rate: u32, output: &mut Vec<u8>) {
+    output.clear();
+    if source_rate == 0 || target_rate == 0 {
+        return;
+    }
+
+    if source_rate == target_rate {
+        output.reserve(samples.len() * 2);
+        for &sample in samples {
+            let clamped = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            output.extend_from_slice(&clamped.to_le_bytes());
+        }
+        return;
+    }
+
+    let ratio = source_rate as f32 / target_rate as f32;
+    let out_len = (samples.len() as f32 / ratio).ceil() as usize;
+    output.reserve(out_len * 2);
+
+    for n in 0..out_len {
+        let pos = n as f32 * ratio;
+        let idx = pos.floor() as usize;
+        let frac = pos - idx as f32;
+
+        let s0 = samples.get(idx).copied().unwrap_or(0.0);
+        let s1 = samples.get(idx + 1).copied().unwrap_or(s0);
+        let interp = s0 * (1.0 - frac) + s1 * frac;
+
+        let clamped = (interp.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        output.extend_from_slice(&clamped.to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(pcm: &[u8]) -> Vec<i16> {
+        pcm.chunks_exact(2)
+            .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect()
+    }
+
+    #[test]
+    fn mixes_mic_and_system_at_half_gain() {
+        let mut encoder = FrameEncoder::new(false);
+        let mic = vec![0.5; FRAME_SIZE];
+        let system = vec![-0.25; FRAME_SIZE];
+        let pcm = samples(encoder.encode(&mic, Some(&system)));
+        assert_eq!(pcm.len() * 2, pcm_frame_bytes());
+        let expected = (0.125 * i16::MAX as f32) as i16;
+        assert!(pcm.iter().all(|&sample| sample == expected));
+    }
+
+    #[test]
+    fn mic_only_frames_pass_through() {
+        let mut encoder = FrameEncoder::new(false);
+        let pcm = samples(encoder.encode(&vec![0.5; FRAME_SIZE], None));
+        let expected = (0.5 * i16::MAX as f32) as i16;
+        assert!(pcm.iter().all(|&sample| sample == expected));
+    }
+
+    #[test]
+    fn split_frames_interleave_mic_then_system() {
+        let mut encoder = FrameEncoder::new(true);
+        let mic = vec![0.5; FRAME_SIZE];
+        let system = vec![-0.5; FRAME_SIZE];
+        let pcm = samples(encoder.encode(&mic, Some(&system)));
+        assert_eq!(pcm.len(), pcm_frame_bytes());
+        let high = (0.5 * i16::MAX as f32) as i16;
+        for frame in pcm.chunks_exact(2) {
+            assert_eq!(frame, [high, -high]);
+        }
+        let channels = deinterleave_pcm16(encoder.encode(&mic, Some(&system)), 2);
+        assert_eq!(samples(&channels[0]), vec![high; pcm.len() / 2]);
+        assert_eq!(samples(&channels[1]), vec![-high; pcm.len() / 2]);
+    }
+
+    #[test]
+    fn loud_mixes_clip_instead_of_wrapping() {
+        let mut encoder = FrameEncoder::new(false);
+        let loud = vec![1.5; FRAME_SIZE];
+        let pcm = samples(encoder.encode(&loud, Some(&loud)));
+        assert!(pcm.iter().all(|&sample| sample == i16::MAX));
+        let pcm = samples(encoder.encode(&vec![-3.0; FRAME_SIZE], Some(&vec![-1.0; FRAME_SIZE])));
+        assert!(pcm.iter().all(|&sample| sample == -i16::MAX));
+
+        let same_rate = samples(&resample_to_pcm16(&[2.0, -2.0, 0.0], 16_000, 16_000));
+        assert_eq!(same_rate, [i16::MAX, -i16::MAX, 0]);
+    }
+}
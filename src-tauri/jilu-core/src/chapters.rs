@@ -0,0 +1,620 @@
+//! Splits long transcripts into sections where the conversation changes topic.
+//!
+//! Turns are grouped into blocks of roughly equal length and each gap between
+//! blocks is scored by how much the vocabulary on either side differs, as in
+//! TextTiling, leaving out the common words of whichever languages the
+//! meeting was held in. The deepest dips in similarity become chapter breaks, and each
+//! chapter is titled with the words that set it apart from the others.
+
+use crate::formatting::FILLER_WORDS;
+use crate::transcript::TranscriptTurnPayload;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+pub const CHAPTERS_FILE: &str = "chapters.json";
+/// Transcripts shorter than this are left in one piece.
+const MIN_WORDS: usize = 1_500;
+/// Words per block; gaps between blocks are the candidate breaks.
+const BLOCK_WORDS: usize = 150;
+/// Blocks compared on each side of a gap.
+const WINDOW_BLOCKS: usize = 3;
+/// Shortest chapter, in blocks.
+const MIN_CHAPTER_BLOCKS: usize = 4;
+/// Smallest dip in similarity taken as a change of topic, so a meeting that
+/// stays on one subject is not cut up by noise.
+const MIN_DEPTH: f64 = 0.15;
+const MAX_CHAPTERS: usize = 24;
+const TITLE_WORDS: usize = 3;
+
+/// Words in each language that say nothing about the topic. Words under three
+/// letters are never terms, so they are left out.
+const ENGLISH: &[&str] = &[
+    "about",
+    "actually",
+    "after",
+    "again",
+    "all",
+    "also",
+    "and",
+    "any",
+    "are",
+    "back",
+    "basically",
+    "because",
+    "been",
+    "before",
+    "being",
+    "but",
+    "can",
+    "could",
+    "did",
+    "does",
+    "doing",
+    "don",
+    "down",
+    "even",
+    "for",
+    "from",
+    "get",
+    "getting",
+    "going",
+    "gonna",
+    "got",
+    "had",
+    "has",
+    "have",
+    "her",
+    "here",
+    "him",
+    "his",
+    "how",
+    "into",
+    "its",
+    "just",
+    "kind",
+    "know",
+    "like",
+    "look",
+    "make",
+    "maybe",
+    "mean",
+    "more",
+    "much",
+    "need",
+    "not",
+    "now",
+    "okay",
+    "one",
+    "only",
+    "other",
+    "our",
+    "out",
+    "over",
+    "really",
+    "right",
+    "said",
+    "say",
+    "see",
+    "she",
+    "should",
+    "some",
+    "something",
+    "sort",
+    "still",
+    "sure",
+    "take",
+    "than",
+    "that",
+    "the",
+    "their",
+    "them",
+    "then",
+    "there",
+    "these",
+    "they",
+    "thing",
+    "things",
+    "think",
+    "this",
+    "those",
+    "through",
+    "too",
+    "want",
+    "was",
+    "way",
+    "well",
+    "were",
+    "what",
+    "when",
+    "where",
+    "which",
+    "who",
+    "why",
+    "will",
+    "with",
+    "would",
+    "yeah",
+    "yes",
+    "you",
+    "your",
+];
+
+const GERMAN: &[&str] = &[
+    "aber", "alle", "also", "auch", "auf", "aus", "bei", "bin", "bis", "bist", "dann", "das",
+    "dass", "dem", "den", "der", "des", "die", "doch", "dort", "durch", "eben", "ein", "eine",
+    "einem", "einen", "einer", "eines", "etwa", "für", "genau", "gibt", "gut", "habe", "haben",
+    "halt", "hat", "hier", "ich", "ihr", "ihre", "immer", "ist", "jetzt", "kann", "kein", "keine",
+    "mal", "man", "mehr", "mich", "mir", "mit", "muss", "nach", "naja", "nicht", "noch", "nur",
+    "oder", "okay", "schon", "sehr", "sein", "sich", "sie", "sind", "und", "uns", "unter", "vom",
+    "von", "vor", "war", "was", "weil", "wenn", "werden", "wie", "wir", "wird", "wirklich", "zum",
+    "zur",
+];
+const FRENCH: &[&str] = &[
+    "alors", "aussi", "aux", "avait", "avec", "avoir", "bien", "bon", "c'est", "cela", "ces",
+    "cette", "comme", "d'accord", "dans", "des", "donc", "elle", "elles", "est", "été", "était",
+    "être", "fait", "faire", "ils", "j'ai", "juste", "les", "leur", "mais", "même", "moi", "nous",
+    "oui", "par", "pas", "peu", "peut", "plus", "pour", "qu'il", "qu'on", "quand", "que", "qui",
+    "quoi", "sans", "ses", "son", "sont", "sur", "tous", "tout", "très", "une", "voilà", "voir",
+    "vous",
+];
+const SPANISH: &[&str] = &[
+    "algo", "así", "bien", "bueno", "como", "con", "creo", "cuando", "del", "desde", "digamos",
+    "donde", "ella", "ellos", "entonces", "era", "esa", "ese", "eso", "está", "están", "esta",
+    "este", "esto", "estoy", "hace", "hacer", "hay", "las", "los", "más", "mucho", "muy", "nada",
+    "nos", "para", "pero", "poco", "por", "porque", "pues", "que", "qué", "sea", "ser", "sin",
+    "sobre", "son", "también", "tenemos", "tiene", "todo", "una", "uno", "unos", "vale", "vamos",
+];
+const ITALIAN: &[&str] = &[
+    "alla", "allora", "anche", "ancora", "bene", "che", "chi", "cioè", "come", "comunque", "con",
+    "cosa", "così", "dal", "dalla", "degli", "dei", "del", "della", "delle", "dire", "dopo",
+    "ecco", "era", "essere", "fare", "fatto", "gli", "hai", "hanno", "insomma", "loro", "mai",
+    "molto", "nel", "nella", "noi", "non", "per", "perché", "però", "più", "poi", "proprio",
+    "quando", "quello", "questa", "questo", "quindi", "sia", "siamo", "solo", "sono", "stato",
+    "sul", "sulla", "tutti", "tutto", "una", "uno", "voi",
+];
+const PORTUGUESE: &[&str] = &[
+    "acho", "ainda", "aqui", "assim", "bem", "bom", "com", "como", "das", "dela", "dele", "depois",
+    "dos", "ela", "ele", "eles", "então", "era", "essa", "esse", "esta", "está", "estão", "este",
+    "estou", "fazer", "gente", "isso", "isto", "mais", "mas", "muito", "não", "nos", "para",
+    "pela", "pelo", "porque", "pra", "quando", "que", "sem", "ser", "seu", "sim", "sua", "também",
+    "tem", "tenho", "tipo", "todo", "tudo", "uma", "vai", "você", "vocês",
+];
+const DUTCH: &[&str] = &[
+    "aan",
+    "alle",
+    "als",
+    "ben",
+    "bij",
+    "daar",
+    "dan",
+    "dat",
+    "deze",
+    "die",
+    "dit",
+    "doen",
+    "door",
+    "dus",
+    "echt",
+    "een",
+    "eigenlijk",
+    "even",
+    "gaan",
+    "gewoon",
+    "goed",
+    "had",
+    "heb",
+    "hebben",
+    "heeft",
+    "het",
+    "hier",
+    "hij",
+    "hoe",
+    "jij",
+    "jullie",
+    "kan",
+    "kunnen",
+    "maar",
+    "meer",
+    "met",
+    "moet",
+    "naar",
+    "niet",
+    "nog",
+    "nou",
+    "ons",
+    "ook",
+    "over",
+    "toch",
+    "tot",
+    "uit",
+    "van",
+    "veel",
+    "voor",
+    "waar",
+    "want",
+    "was",
+    "wat",
+    "weet",
+    "wel",
+    "wij",
+    "wil",
+    "worden",
+    "wordt",
+    "zal",
+    "zich",
+    "zie",
+    "zijn",
+    "zoals",
+];
+
+/// Lists the meeting's language is picked from. Chinese and Japanese are written
+/// without spaces, so their transcripts rarely split into enough words to be
+/// divided at all.
+const STOP_WORDS: &[&[&str]] = &[ENGLISH, GERMAN, FRENCH, SPANISH, ITALIAN, PORTUGUESE, DUTCH];
+
+/// A titled section of a transcript, starting at turn `start_turn`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    pub start_turn: usize,
+    /// Seconds into the recording where the chapter starts, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+}
+
+/// Chapters for `turns`, or none when the transcript is too short or never
+/// clearly changes topic.
+pub fn detect(turns: &[TranscriptTurnPayload]) -> Vec<Chapter> {
+    let stop_words = stop_words(turns);
+    let blocks = blocks(turns, &stop_words);
+    let total_words: usize = blocks.iter().map(|block| block.words).sum();
+    if total_words < MIN_WORDS || blocks.len() < MIN_CHAPTER_BLOCKS * 2 {
+        return Vec::new();
+    }
+
+    let breaks = breaks(&blocks);
+    if breaks.is_empty() {
+        return Vec::new();
+    }
+
+    let mut starts = vec![0];
+    starts.extend(breaks.iter().copied());
+    let sections: Vec<HashMap<&str, usize>> = starts
+        .iter()
+        .enumerate()
+        .map(|(index, &start)| {
+            let end = starts.get(index + 1).copied().unwrap_or(blocks.len());
+            merged(&blocks[start..end])
+        })
+        .collect();
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(index, &block)| {
+            let start_turn = blocks[block].start_turn;
+            Chapter {
+                title: title(&sections, index).unwrap_or_else(|| format!("Part {}", index + 1)),
+                start_turn,
+                offset: turns[start_turn].offset.or(turns[start_turn].start),
+            }
+        })
+        .collect()
+}
+
+/// Checks chapters found some other way against `turns` and sets where each
+/// starts in the recording. Titles are trimmed; no chapters is fine.
+pub fn with_offsets(
+    chapters: Vec<Chapter>,
+    turns: &[TranscriptTurnPayload],
+) -> Result<Vec<Chapter>, String> {
+    if chapters.len() > MAX_CHAPTERS {
+        return Err(format!("At most {} chapters are kept", MAX_CHAPTERS));
+    }
+    let mut previous: Option<usize> = None;
+    chapters
+        .into_iter()
+        .map(|chapter| {
+            let Some(turn) = turns.get(chapter.start_turn) else {
+                return Err(format!(
+                    "Chapter '{}' starts past the end of the transcript",
+                    chapter.title
+                ));
+            };
+            if previous.is_some_and(|previous| chapter.start_turn <= previous) {
+                return Err("Chapters must start at increasing turns".to_string());
+            }
+            let title = chapter.title.trim().to_string();
+            if title.is_empty() {
+                return Err("Chapter titles can't be empty".to_string());
+            }
+            previous = Some(chapter.start_turn);
+            Ok(Chapter {
+                title,
+                start_turn: chapter.start_turn,
+                offset: turn.offset.or(turn.start),
+            })
+        })
+        .collect()
+}
+
+struct Block {
+    start_turn: usize,
+    words: usize,
+    /// Term counts, folded to lowercase unless written as an acronym.
+    terms: HashMap<String, usize>,
+}
+
+/// Groups whole turns into blocks of about `BLOCK_WORDS` words.
+fn blocks(turns: &[TranscriptTurnPayload], stop_words: &HashSet<&str>) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    for (index, turn) in turns.iter().enumerate() {
+        if blocks.last().is_none_or(|block| block.words >= BLOCK_WORDS) {
+            blocks.push(Block {
+                start_turn: index,
+                words: 0,
+                terms: HashMap::new(),
+            });
+        }
+        let Some(current) = blocks.last_mut() else {
+            continue;
+        };
+        for word in turn.text.split(|c: char| !c.is_alphanumeric() && c != '\'') {
+            if word.is_empty() {
+                continue;
+            }
+            current.words += 1;
+            if is_term(word, stop_words) {
+                let term = if is_acronym(word) {
+                    word.to_string()
+                } else {
+                    word.to_lowercase()
+                };
+                *current.terms.entry(term).or_insert(0) += 1;
+            }
+        }
+    }
+    // A short tail joins the block before it rather than standing alone.
+    if blocks.len() > 1 && blocks.last().is_some_and(|b| b.words < BLOCK_WORDS / 2) {
+        if let (Some(tail), Some(last)) = (blocks.pop(), blocks.last_mut()) {
+            last.words += tail.words;
+            for (term, count) in tail.terms {
+                *last.terms.entry(term).or_insert(0) += count;
+            }
+        }
+    }
+    blocks
+}
+
+/// Stop words of the languages spoken in `turns`: every list that matches at
+/// least half as many words as the best one, so both halves of a bilingual
+/// meeting are covered. English when no list matches at all.
+fn stop_words(turns: &[TranscriptTurnPayload]) -> HashSet<&'static str> {
+    let mut hits = vec![0usize; STOP_WORDS.len()];
+    for turn in turns {
+        for word in turn.text.split(|c: char| !c.is_alphanumeric() && c != '\'') {
+            let word = word.to_lowercase();
+            for (list, count) in STOP_WORDS.iter().zip(hits.iter_mut()) {
+                if list.contains(&word.as_str()) {
+                    *count += 1;
+                }
+            }
+        }
+    }
+    let best = hits.iter().copied().max().unwrap_or(0);
+    if best == 0 {
+        return ENGLISH.iter().copied().collect();
+    }
+    STOP_WORDS
+        .iter()
+        .zip(hits)
+        .filter(|&(_, count)| count * 2 >= best)
+        .flat_map(|(list, _)| list.iter().copied())
+        .collect()
+}
+
+fn is_term(word: &str, stop_words: &HashSet<&str>) -> bool {
+    word.chars().count() > 2
+        && !word.chars().all(char::is_numeric)
+        && !stop_words.contains(word.to_lowercase().as_str())
+        && !FILLER_WORDS
+            .iter()
+            .any(|filler| filler.eq_ignore_ascii_case(word))
+}
+
+/// "API" or "OKR" keep their capitals; any other word is folded to lowercase.
+fn is_acronym(word: &str) -> bool {
+    word.chars().all(|c| c.is_uppercase() || c.is_numeric())
+}
+
+/// Block indexes where a new chapter starts, in order.
+fn breaks(blocks: &[Block]) -> Vec<usize> {
+    // Similarity across the gap before each block from 1 onwards.
+    let similarity: Vec<f64> = (1..blocks.len())
+        .map(|gap| {
+            let before = merged(&blocks[gap.saturating_sub(WINDOW_BLOCKS)..gap]);
+            let after = merged(&blocks[gap..(gap + WINDOW_BLOCKS).min(blocks.len())]);
+            cosine(&before, &after)
+        })
+        .collect();
+
+    // How far similarity drops at each gap below the nearest peaks on either side.
+    let depth: Vec<f64> = (0..similarity.len())
+        .map(|i| {
+            let mut left = i;
+            while left > 0 && similarity[left - 1] >= similarity[left] {
+                left -= 1;
+            }
+            let mut right = i;
+            while right + 1 < similarity.len() && similarity[right + 1] >= similarity[right] {
+                right += 1;
+            }
+            (similarity[left] - similarity[i]) + (similarity[right] - similarity[i])
+        })
+        .collect();
+
+    let mean = depth.iter().sum::<f64>() / depth.len() as f64;
+    let spread =
+        (depth.iter().map(|d| (d - mean).powi(2)).sum::<f64>() / depth.len() as f64).sqrt();
+    let cutoff = (mean + spread / 2.0).max(MIN_DEPTH);
+
+    let mut candidates: Vec<(usize, f64)> = depth
+        .iter()
+        .enumerate()
+        .filter(|(_, &d)| d >= cutoff)
+        .map(|(i, &d)| (i + 1, d))
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let mut chosen: Vec<usize> = Vec::new();
+    for (block, _) in candidates {
+        if chosen.len() + 1 >= MAX_CHAPTERS {
+            break;
+        }
+        let fits = block >= MIN_CHAPTER_BLOCKS
+            && blocks.len() - block >= MIN_CHAPTER_BLOCKS
+            && chosen
+                .iter()
+                .all(|&other| other.abs_diff(block) >= MIN_CHAPTER_BLOCKS);
+        if fits {
+            chosen.push(block);
+        }
+    }
+    chosen.sort_unstable();
+    chosen
+}
+
+fn merged(blocks: &[Block]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for block in blocks {
+        for (term, count) in &block.terms {
+            *counts.entry(term.as_str()).or_insert(0) += count;
+        }
+    }
+    counts
+}
+
+fn cosine(a: &HashMap<&str, usize>, b: &HashMap<&str, usize>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, x)| b.get(term).map(|y| (*x * *y) as f64))
+        .sum();
+    let norm = |v: &HashMap<&str, usize>| v.values().map(|x| (x * x) as f64).sum::<f64>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// The words most particular to section `index`. Words every section uses say
+/// nothing about this one and are skipped.
+fn title(sections: &[HashMap<&str, usize>], index: usize) -> Option<String> {
+    let mut scored: Vec<(f64, &str)> = sections[index]
+        .iter()
+        .filter(|(_, &count)| count >= 2)
+        .map(|(term, &count)| {
+            let sharing = sections.iter().filter(|s| s.contains_key(term)).count();
+            let rarity = (sections.len() as f64 / sharing as f64).ln();
+            (count as f64 * rarity, *term)
+        })
+        .filter(|(score, _)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+    let words: Vec<String> = scored
+        .iter()
+        .take(TITLE_WORDS)
+        .map(|(_, word)| capitalize(word))
+        .collect();
+    (!words.is_empty()).then(|| words.join(", "))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Transcript;
+
+    fn turns(lines: &[&str]) -> Vec<TranscriptTurnPayload> {
+        let mut transcript = Transcript::default();
+        for (index, line) in lines.iter().enumerate() {
+            let speaker = if index % 2 == 0 { "S1" } else { "S2" };
+            transcript.append(Some(speaker), line);
+        }
+        transcript.turns().to_vec()
+    }
+
+    #[test]
+    fn stop_words_follow_the_language_spoken() {
+        let german = stop_words(&turns(&[
+            "Wir haben das Budget noch nicht geprüft, aber die Zahlen sind da.",
+        ]));
+        assert!(german.contains("nicht"));
+        assert!(!german.contains("would"));
+
+        let bilingual = stop_words(&turns(&[
+            "We should look at the budget before the launch.",
+            "Sí, pero el presupuesto está muy ajustado para este trimestre.",
+        ]));
+        assert!(bilingual.contains("should"));
+        assert!(bilingual.contains("pero"));
+
+        let unknown = stop_words(&turns(&["Бюджет утвержден"]));
+        assert!(unknown.contains("because"));
+    }
+
+    #[test]
+    fn chapters_from_elsewhere_get_offsets() {
+        let turns = turns(&["One.", "Two.", "Three."]);
+        let chapter = |title: &str, start_turn| Chapter {
+            title: title.to_string(),
+            start_turn,
+            offset: None,
+        };
+        let checked =
+            with_offsets(vec![chapter(" Intro ", 0), chapter("Budget", 2)], &turns).unwrap();
+        assert_eq!(checked[0].title, "Intro");
+        assert_eq!(checked[1].start_turn, 2);
+
+        assert!(with_offsets(vec![chapter("Late", 3)], &turns).is_err());
+        assert!(with_offsets(vec![chapter("A", 1), chapter("B", 1)], &turns).is_err());
+        assert!(with_offsets(vec![chapter(" ", 0)], &turns).is_err());
+        assert!(with_offsets(Vec::new(), &turns).unwrap().is_empty());
+    }
+
+    #[test]
+    fn german_meetings_are_titled_by_topic() {
+        let mut lines =
+            vec!["Wir müssen das Budget und die Kosten für das Marketing prüfen."; 80];
+        lines.extend(vec![
+            "Der Server und die Datenbank brauchen ein Update für die Sicherheit.";
+            80
+        ]);
+        let chapters = detect(&turns(&lines));
+        assert_eq!(chapters.len(), 2);
+        for chapter in &chapters {
+            for stop in ["Das", "Der", "Wir", "Und", "Für"] {
+                assert!(
+                    !chapter.title.split(", ").any(|word| word == stop),
+                    "{}",
+                    chapter.title
+                );
+            }
+        }
+        assert!(
+            chapters[0].title.contains("Budget"),
+            "{}",
+            chapters[0].title
+        );
+    }
+}
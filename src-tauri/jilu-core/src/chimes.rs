@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+/// Frames are the mixer's 10ms.
+const FRAME_MS: u32 = 10;
+/// Alert sounds are over within this; anything longer is speech or music.
+const MAX_CHIME_MS: u32 = 800;
+/// Shorter bursts are clicks and pops, which transcribe as nothing anyway.
+const MIN_CHIME_MS: u32 = 40;
+/// How far above the background a sound has to jump within one frame.
+const ONSET_DB: f32 = 18.0;
+/// The burst is over once it is back within this of the background.
+const RELEASE_DB: f32 = 6.0;
+/// Quieter sounds are left alone; nothing mistakes them for speech.
+const MIN_LEVEL_DB: f32 = -45.0;
+/// How much the pitch of a chime may wander, as the relative spread of the
+/// zero-crossing rate over its frames. Speech moves between voiced and
+/// unvoiced sounds and spreads far wider.
+const MAX_PITCH_SPREAD: f32 = 0.25;
+const SILENCE_DB: f32 = -100.0;
+
+fn level_db(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return SILENCE_DB;
+    }
+    let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+    (10.0 * mean_square.max(1e-10).log10()).max(SILENCE_DB)
+}
+
+fn zero_crossing_rate(samples: &[f32]) -> f32 {
+    let crossings = samples
+        .windows(2)
+        .filter(|pair| (pair[0] >= 0.0) != (pair[1] >= 0.0))
+        .count();
+    crossings as f32 / samples.len().max(1) as f32
+}
+
+/// A burst of sound being followed to see whether it is a chime.
+#[derive(Debug)]
+struct Burst {
+    start: u64,
+    frames: u32,
+    /// Zero-crossing rates of the frames well above the background.
+    rates: Vec<f32>,
+}
+
+impl Burst {
+    fn is_tonal(&self) -> bool {
+        let count = self.rates.len() as f32;
+        if count == 0.0 {
+            return false;
+        }
+        let mean = self.rates.iter().sum::<f32>() / count;
+        let variance = self.rates.iter().map(|r| (r - mean).powi(2)).sum::<f32>() / count;
+        mean > 0.0 && variance.sqrt() / mean <= MAX_PITCH_SPREAD
+    }
+}
+
+/// Spots notification sounds in a stream of frames: sudden, short and close
+/// to a single pitch, which speech almost never is.
+#[derive(Debug)]
+pub struct ChimeDetector {
+    frame: u64,
+    background_db: f32,
+    previous_db: f32,
+    burst: Option<Burst>,
+    /// A burst that outlasted a chime, ignored until it dies down.
+    sustained: bool,
+}
+
+impl Default for ChimeDetector {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            background_db: SILENCE_DB,
+            previous_db: SILENCE_DB,
+            burst: None,
+            sustained: false,
+        }
+    }
+}
+
+impl ChimeDetector {
+    /// Takes the next frame and returns the frames `start..end` of a chime
+    /// that has just ended, if any.
+    pub fn push(&mut self, samples: &[f32]) -> Option<std::ops::Range<u64>> {
+        let frame = self.frame;
+        self.frame += 1;
+        let level = level_db(samples);
+        let previous = std::mem::replace(&mut self.previous_db, level);
+        let quiet = level < self.background_db + RELEASE_DB;
+
+        if self.sustained {
+            self.sustained = !quiet;
+        } else if let Some(burst) = self.burst.as_mut() {
+            if quiet {
+                let burst = self.burst.take()?;
+                return (burst.frames * FRAME_MS >= MIN_CHIME_MS && burst.is_tonal())
+                    .then_some(burst.start..frame);
+            }
+            burst.frames += 1;
+            if level >= self.background_db + ONSET_DB / 2.0 {
+                burst.rates.push(zero_crossing_rate(samples));
+            }
+            if burst.frames * FRAME_MS > MAX_CHIME_MS {
+                self.burst = None;
+                self.sustained = true;
+            }
+            return None;
+        } else if level > MIN_LEVEL_DB
+            && level >= self.background_db + ONSET_DB
+            && previous < self.background_db + ONSET_DB / 2.0
+        {
+            self.burst = Some(Burst {
+                start: frame,
+                frames: 1,
+                rates: vec![zero_crossing_rate(samples)],
+            });
+            return None;
+        }
+
+        // Falls quickly to quiet stretches and rises slowly through loud
+        // ones, so it settles on the noise floor between sounds.
+        let rate = if level < self.background_db {
+            0.5
+        } else {
+            0.02
+        };
+        self.background_db += (level - self.background_db) * rate;
+        None
+    }
+}
+
+struct HeldFrame {
+    index: u64,
+    mic: Vec<f32>,
+    system: Vec<f32>,
+}
+
+/// Runs system audio past a [`ChimeDetector`] and, when filtering, silences
+/// the chimes it finds. Silencing one from its first frame means holding
+/// frames back until it is known to be over, so filtering delays everything
+/// by a little more than the longest chime; detecting alone adds no delay.
+///
+/// Frames go in with [`ChimeFilter::push`] and come out of
+/// [`ChimeFilter::pop`]; after [`ChimeFilter::finish`] the held frames come
+/// out too.
+pub struct ChimeFilter {
+    detector: ChimeDetector,
+    filtering: bool,
+    held: VecDeque<HeldFrame>,
+    next_index: u64,
+    finishing: bool,
+}
+
+impl ChimeFilter {
+    const HOLD_FRAMES: usize = (MAX_CHIME_MS / FRAME_MS) as usize + 2;
+
+    pub fn new(filtering: bool) -> Self {
+        Self {
+            detector: ChimeDetector::default(),
+            filtering,
+            held: VecDeque::with_capacity(Self::HOLD_FRAMES + 1),
+            next_index: 0,
+            finishing: false,
+        }
+    }
+
+    /// Adds a frame of each source and returns whether a chime just ended.
+    pub fn push(&mut self, mic: Vec<f32>, system: Vec<f32>) -> bool {
+        let chime = self.detector.push(&system);
+        if let (Some(chime), true) = (&chime, self.filtering) {
+            for frame in &mut self.held {
+                if chime.contains(&frame.index) {
+                    frame.system.fill(0.0);
+                }
+            }
+        }
+        self.held.push_back(HeldFrame {
+            index: self.next_index,
+            mic,
+            system,
+        });
+        self.next_index += 1;
+        chime.is_some()
+    }
+
+    /// The next microphone and system frame that is ready to go on.
+    pub fn pop(&mut self) -> Option<(Vec<f32>, Vec<f32>)> {
+        let ready = !self.filtering || self.finishing || self.held.len() > Self::HOLD_FRAMES;
+        if !ready {
+            return None;
+        }
+        self.held.pop_front().map(|frame| (frame.mic, frame.system))
+    }
+
+    /// Lets the held frames out; no more are coming.
+    pub fn finish(&mut self) {
+        self.finishing = true;
+    }
+}
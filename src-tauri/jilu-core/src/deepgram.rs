@@ -0,0 +1,294 @@
+//! Deepgram live streaming, as a [`RealtimeProvider`].
+//!
+//! Audio goes out as raw binary frames, interleaved when there are several
+//! channels, and results come back as `Results` messages. Deepgram has no
+//! custom dictionary with pronunciations or enrolled voices, so only the
+//! vocabulary's terms are passed on, as key terms.
+
+use crate::audio::TARGET_SAMPLE_RATE;
+use crate::formatting::clean_punctuation;
+use crate::provider::{
+    EventDecoder, FinalResult, ProviderEvent, ProviderKind, RealtimeProvider, SessionConfig,
+    WebSocketSettings, Word,
+};
+use crate::speechmatics::{RtReader, RtWriter};
+use futures_util::future::BoxFuture;
+use futures_util::StreamExt;
+use reqwest::Url;
+use serde::Deserialize;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+use tokio_tungstenite::{tungstenite::Message, Connector};
+use tracing::{error, warn};
+
+pub const DEFAULT_URL: &str = "wss://api.deepgram.com/v1/listen";
+const DEFAULT_MODEL: &str = "nova-3";
+
+/// Deepgram live streaming, at `url` or [`DEFAULT_URL`]. Query parameters
+/// already in `url`, such as `model`, win over Jilu's own.
+pub struct Deepgram {
+    pub api_key: String,
+    pub url: Option<String>,
+    pub tls: Option<Connector>,
+    pub websocket: WebSocketSettings,
+}
+
+/// Deepgram's name for a Speechmatics language code. Bilingual packs and
+/// automatic detection become its multilingual model.
+fn language_code(language: &str) -> &str {
+    match language {
+        "cmn" => "zh",
+        "auto" => "multi",
+        language if language.contains('_') => "multi",
+        language => language,
+    }
+}
+
+impl Deepgram {
+    fn listen_url(&self, config: &SessionConfig) -> Result<Url, String> {
+        let base = self
+            .url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_URL);
+        let mut url = Url::parse(base).map_err(|e| format!("Invalid Deepgram URL: {}", e))?;
+        let given: Vec<String> = url.query_pairs().map(|(key, _)| key.into_owned()).collect();
+        let channels = config.channels.len().max(1).to_string();
+        let mut params: Vec<(&str, String)> = vec![
+            ("model", DEFAULT_MODEL.to_string()),
+            ("language", language_code(&config.language).to_string()),
+            ("encoding", "linear16".to_string()),
+            ("sample_rate", TARGET_SAMPLE_RATE.to_string()),
+            ("channels", channels),
+            ("interim_results", config.partials.to_string()),
+            ("punctuate", "true".to_string()),
+            ("smart_format", "true".to_string()),
+        ];
+        if config.channels.len() > 1 {
+            params.push(("multichannel", "true".to_string()));
+        } else if config.diarization {
+            params.push(("diarize", "true".to_string()));
+        }
+        params.extend(
+            config
+                .additional_vocab
+                .iter()
+                .map(|entry| ("keyterm", entry.content.clone())),
+        );
+        {
+            let mut query = url.query_pairs_mut();
+            for (key, value) in params {
+                if !given.iter().any(|given| given == key) {
+                    query.append_pair(key, &value);
+                }
+            }
+        }
+        Ok(url)
+    }
+}
+
+impl RealtimeProvider for Deepgram {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Deepgram
+    }
+
+    fn connect<'a>(
+        &'a self,
+        config: &'a SessionConfig,
+    ) -> BoxFuture<'a, Result<(RtWriter, RtReader), String>> {
+        Box::pin(async move {
+            let url = self.listen_url(config)?;
+            let mut request = url
+                .as_str()
+                .into_client_request()
+                .map_err(|e| format!("Invalid Deepgram URL: {}", e))?;
+            let token = HeaderValue::from_str(&format!("Token {}", self.api_key))
+                .map_err(|_| "Invalid Deepgram API key".to_string())?;
+            request.headers_mut().insert(AUTHORIZATION, token);
+            let (ws_stream, _) = tokio_tungstenite::connect_async_tls_with_config(
+                request,
+                Some(self.websocket.config()),
+                false,
+                self.tls.clone(),
+            )
+            .await
+            .map_err(|e| {
+                error!("Deepgram connect failed: {}", e);
+                match e {
+                    tokio_tungstenite::tungstenite::Error::Http(response)
+                        if matches!(
+                            response.status(),
+                            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+                        ) =>
+                    {
+                        format!(
+                            "Invalid Deepgram API key: the endpoint rejected it (HTTP {})",
+                            response.status()
+                        )
+                    }
+                    e => e.to_string(),
+                }
+            })?;
+            Ok(ws_stream.split())
+        })
+    }
+
+    fn decoder(&self, config: &SessionConfig) -> Box<dyn EventDecoder> {
+        Box::new(DeepgramDecoder {
+            channels: config.channels.clone(),
+        })
+    }
+
+    fn audio(&self, batch: Vec<u8>, _channels: &[String]) -> Vec<Message> {
+        vec![Message::Binary(batch)]
+    }
+
+    fn end_of_stream(&self, _channels: &[String], _seq_no: u64) -> Vec<Message> {
+        vec![Message::Text(r#"{"type":"CloseStream"}"#.to_string())]
+    }
+
+    /// Deepgram closes a session after about ten seconds without audio.
+    fn keep_alive(&self) -> Option<Message> {
+        Some(Message::Text(r#"{"type":"KeepAlive"}"#.to_string()))
+    }
+
+    fn acknowledges_audio(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    /// `[channel, channel count]`.
+    #[serde(default)]
+    channel_index: Vec<usize>,
+    #[serde(default)]
+    start: f64,
+    #[serde(default)]
+    duration: f64,
+    #[serde(default)]
+    is_final: bool,
+    #[serde(default)]
+    channel: Option<DeepgramChannel>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramChannel {
+    #[serde(default)]
+    alternatives: Vec<DeepgramAlternative>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramAlternative {
+    #[serde(default)]
+    transcript: String,
+    #[serde(default)]
+    words: Vec<DeepgramWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeepgramWord {
+    word: String,
+    #[serde(default)]
+    punctuated_word: Option<String>,
+    start: f64,
+    end: f64,
+    #[serde(default)]
+    confidence: Option<f64>,
+    /// Only sent when transcribing with `language=multi`.
+    #[serde(default)]
+    language: Option<String>,
+    /// Zero-based, when diarizing.
+    #[serde(default)]
+    speaker: Option<u32>,
+}
+
+struct DeepgramDecoder {
+    channels: Vec<String>,
+}
+
+impl DeepgramDecoder {
+    /// The channel's label in multichannel sessions, otherwise `S1`, `S2`, ...
+    /// as Speechmatics numbers voices.
+    fn speaker(&self, message: &DeepgramMessage, word: &DeepgramWord) -> Option<String> {
+        if self.channels.len() > 1 {
+            let channel = message.channel_index.first()?;
+            return self.channels.get(*channel).cloned();
+        }
+        word.speaker.map(|speaker| format!("S{}", speaker + 1))
+    }
+}
+
+impl EventDecoder for DeepgramDecoder {
+    /// Sessions start as soon as the socket opens; there is no reply to wait for.
+    fn opened(&mut self, events: &mut Vec<ProviderEvent>) {
+        events.push(ProviderEvent::Started);
+    }
+
+    fn decode(&mut self, text: &str, events: &mut Vec<ProviderEvent>) {
+        let message = match serde_json::from_str::<DeepgramMessage>(text) {
+            Ok(message) => message,
+            Err(err) => {
+                warn!("Failed to parse Deepgram message: {}", err);
+                return;
+            }
+        };
+        match message.kind.as_str() {
+            "Results" => {
+                let Some(alternative) = message
+                    .channel
+                    .as_ref()
+                    .and_then(|channel| channel.alternatives.first())
+                else {
+                    return;
+                };
+                let transcript = alternative.transcript.trim();
+                if transcript.is_empty() {
+                    return;
+                }
+                let end = Some(message.start + message.duration);
+                if !message.is_final {
+                    events.push(ProviderEvent::Partial {
+                        text: clean_punctuation(transcript),
+                        end,
+                    });
+                    return;
+                }
+                let words = alternative
+                    .words
+                    .iter()
+                    .map(|word| Word {
+                        speaker: self.speaker(&message, word),
+                        text: clean_punctuation(
+                            word.punctuated_word.as_deref().unwrap_or(&word.word),
+                        ),
+                        span: Some((word.start, word.end)),
+                        confidence: word.confidence,
+                        language: word.language.clone(),
+                    })
+                    .collect();
+                events.push(ProviderEvent::Final(FinalResult {
+                    words,
+                    transcript: Some(transcript.to_string()),
+                    end,
+                }));
+            }
+            // Sent once the stream is closed and everything has been transcribed.
+            "Metadata" => events.push(ProviderEvent::Finished),
+            "Error" => events.push(ProviderEvent::Error(
+                message
+                    .description
+                    .or(message.message)
+                    .unwrap_or_else(|| "Deepgram reported an error".to_string()),
+            )),
+            _ => {}
+        }
+    }
+}
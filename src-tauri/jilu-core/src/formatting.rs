@@ -0,0 +1,172 @@
+use crate::transcript::TranscriptTurnPayload;
+use serde::{Deserialize, Serialize};
+
+/// Removed wherever they appear.
+pub const FILLER_WORDS: &[&str] = &["um", "umm", "uh", "uhh", "uhm", "erm", "er", "hmm", "mm"];
+/// Ordinary words elsewhere ("do you know the way"), so these only go when
+/// set off by punctuation on both sides.
+pub const FILLER_PHRASES: &[&[&str]] = &[&["you", "know"], &["i", "mean"]];
+const SENTENCE_END: [char; 3] = ['.', '!', '?'];
+const CLAUSE_END: [char; 6] = ['.', ',', '!', '?', ':', ';'];
+
+/// Clean-up applied to transcript text, live and on export.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FormattingSettings {
+    /// Curly quotes and apostrophes instead of straight ones.
+    pub smart_quotes: bool,
+    pub capitalize_sentences: bool,
+    /// Drops "um", "uh" and set-off phrases like ", you know,".
+    pub remove_fillers: bool,
+}
+
+impl Default for FormattingSettings {
+    fn default() -> Self {
+        Self {
+            smart_quotes: false,
+            capitalize_sentences: true,
+            remove_fillers: false,
+        }
+    }
+}
+
+pub fn clean_punctuation(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    push_clean_punctuation(text, &mut result);
+    result
+}
+
+/// Appends `text` to `out`, dropping the space Speechmatics puts before punctuation.
+///
+/// Double quotes are left alone: a space before one usually means it opens a quotation.
+pub fn push_clean_punctuation(text: &str, out: &mut String) {
+    for ch in text.chars() {
+        if matches!(ch, '.' | ',' | '!' | '?' | ':' | ';' | '\'') && out.ends_with(' ') {
+            out.pop();
+        }
+        out.push(ch);
+    }
+}
+
+/// Runs `text` through the enabled formatting steps. `sentence_start` says
+/// whether it opens a sentence or continues one already in the transcript.
+pub fn apply(text: &str, options: &FormattingSettings, sentence_start: bool) -> String {
+    let mut text = clean_punctuation(text.trim());
+    if options.remove_fillers {
+        text = remove_fillers(&text);
+    }
+    if options.capitalize_sentences {
+        text = capitalize_sentences(&text, sentence_start);
+    }
+    if options.smart_quotes {
+        text = smart_quotes(&text);
+    }
+    text
+}
+
+/// Formats saved turns for export; each turn is taken to start a sentence.
+pub fn apply_to_turns(turns: &mut [TranscriptTurnPayload], options: &FormattingSettings) {
+    // Typed notes are left as they were written.
+    for turn in turns.iter_mut().filter(|turn| !turn.note) {
+        turn.text = apply(&turn.text, options, true);
+    }
+}
+
+fn bare_word(word: &str) -> String {
+    word.trim_end_matches(CLAUSE_END).to_lowercase()
+}
+
+/// Number of words at the start of `words` that make up a filler.
+fn filler_len(words: &[&str], previous: Option<&String>) -> usize {
+    if FILLER_WORDS.contains(&bare_word(words[0]).as_str()) {
+        return 1;
+    }
+    let set_off_before = previous.is_none_or(|word| word.ends_with(CLAUSE_END));
+    if !set_off_before {
+        return 0;
+    }
+    FILLER_PHRASES
+        .iter()
+        .find(|phrase| {
+            words.len() >= phrase.len()
+                && phrase
+                    .iter()
+                    .zip(words)
+                    .all(|(filler, word)| bare_word(word) == *filler)
+                && (words.len() == phrase.len()
+                    || words[phrase.len() - 1].ends_with([',', '.', '!']))
+        })
+        .map_or(0, |phrase| phrase.len())
+}
+
+fn remove_fillers(text: &str) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut kept: Vec<String> = Vec::with_capacity(words.len());
+    let mut index = 0;
+    while index < words.len() {
+        let len = filler_len(&words[index..], kept.last());
+        if len == 0 {
+            kept.push(words[index].to_string());
+            index += 1;
+            continue;
+        }
+        // A filler that ended the sentence hands its full stop to the word before it.
+        let last = words[index + len - 1];
+        let ending = &last[last.trim_end_matches(CLAUSE_END).len()..];
+        if let Some(end) = ending.chars().find(|ch| SENTENCE_END.contains(ch)) {
+            if let Some(previous) = kept.last_mut() {
+                previous.truncate(previous.trim_end_matches([',', ';', ':']).len());
+                if !previous.ends_with(SENTENCE_END) {
+                    previous.push(end);
+                }
+            }
+        }
+        index += len;
+    }
+    kept.join(" ")
+}
+
+fn capitalize_sentences(text: &str, sentence_start: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut capitalize = sentence_start;
+    for ch in text.chars() {
+        if ch.is_whitespace() && ends_sentence(&out) {
+            capitalize = true;
+        }
+        if capitalize && ch.is_alphanumeric() {
+            out.extend(ch.to_uppercase());
+            capitalize = false;
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Whether `text` ends with a sentence, not an abbreviation like "e.g.".
+fn ends_sentence(text: &str) -> bool {
+    let Some(word) = text.rsplit(' ').next() else {
+        return false;
+    };
+    word.strip_suffix(SENTENCE_END)
+        .is_some_and(|stem| !stem.contains('.'))
+}
+
+fn smart_quotes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let opens = previous.is_none_or(|p| p.is_whitespace() || "([{—–-".contains(p));
+        let quoted = match ch {
+            '"' if opens => '“',
+            '"' => '”',
+            '\'' if opens && chars.peek().is_some_and(|next| next.is_alphanumeric()) => '‘',
+            '\'' => '’',
+            other => other,
+        };
+        out.push(quoted);
+        previous = Some(ch);
+    }
+    out
+}
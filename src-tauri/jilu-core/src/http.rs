@@ -0,0 +1,251 @@
+//! The one HTTP client every outbound request goes through, with retries,
+//! spacing between requests to a host, and a breaker that stops calling a
+//! host for a while once it keeps failing.
+//!
+//! Build requests on [`client`] and hand them to [`send`]; a host that is
+//! down then costs callers one quick error instead of a hung command.
+
+use parking_lot::Mutex;
+use reqwest::header::RETRY_AFTER;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Applies to every request that does not set its own timeout.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_IDLE_PER_HOST: usize = 4;
+/// Failures in a row, after which a host is left alone for `BREAKER_COOLDOWN`.
+const BREAKER_THRESHOLD: u32 = 5;
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(30);
+/// Longest `Retry-After` honoured; anything longer is the caller's problem.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How hard to try one request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Tries in all, including the first.
+    pub attempts: u32,
+    /// Wait before the first retry, doubling after each.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Least time between two requests to the same host.
+    pub min_interval: Duration,
+}
+
+impl RetryPolicy {
+    /// Once, for calls that are retried on a schedule of their own anyway.
+    pub const ONCE: Self = Self {
+        attempts: 1,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        min_interval: Duration::ZERO,
+    };
+
+    /// For reads and small requests that are safe to repeat.
+    pub const STANDARD: Self = Self {
+        attempts: 3,
+        base_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(8),
+        min_interval: Duration::from_millis(100),
+    };
+
+    fn delay(&self, retry: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(1 << retry.min(16))
+            .min(self.max_delay)
+    }
+}
+
+#[derive(Debug)]
+pub enum HttpError {
+    Request(reqwest::Error),
+    /// The host failed too often lately to be tried again yet.
+    CircuitOpen {
+        host: String,
+        retry_in: Duration,
+    },
+}
+
+impl HttpError {
+    /// Whether the host could not be reached at all.
+    pub fn is_unreachable(&self) -> bool {
+        match self {
+            Self::Request(err) => err.is_connect() || err.is_timeout(),
+            Self::CircuitOpen { .. } => true,
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Request(err) => write!(f, "{}", err),
+            Self::CircuitOpen { host, retry_in } => write!(
+                f,
+                "{} has been failing; trying again in {}s",
+                host,
+                retry_in.as_secs().max(1)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<reqwest::Error> for HttpError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err)
+    }
+}
+
+#[derive(Debug)]
+struct HostState {
+    /// Failed attempts in a row.
+    failures: u32,
+    open_until: Option<Instant>,
+    /// When the next request may go out.
+    next_slot: Instant,
+}
+
+fn hosts() -> &'static Mutex<HashMap<String, HostState>> {
+    static HOSTS: OnceLock<Mutex<HashMap<String, HostState>>> = OnceLock::new();
+    HOSTS.get_or_init(Default::default)
+}
+
+/// Shared client, created on first request so TLS setup stays off the launch path.
+pub fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(DEFAULT_TIMEOUT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .pool_max_idle_per_host(MAX_IDLE_PER_HOST)
+            .build()
+            .unwrap_or_else(|err| {
+                warn!("Using a default HTTP client: {}", err);
+                reqwest::Client::new()
+            })
+    })
+}
+
+/// Sends `request`, built on [`client`], under `policy`. Connection errors,
+/// timeouts, 429 and 5xx answers are retried while the request body can be
+/// sent again; any other answer is returned as it is, error statuses included.
+pub async fn send(request: RequestBuilder, policy: RetryPolicy) -> Result<Response, HttpError> {
+    let mut current = request.build()?;
+    let host = current.url().host_str().unwrap_or_default().to_string();
+
+    let mut retry = 0;
+    loop {
+        // Streamed bodies such as file uploads cannot be sent twice.
+        let spare = if retry + 1 < policy.attempts {
+            current.try_clone()
+        } else {
+            None
+        };
+        wait_for_slot(&host, policy.min_interval).await?;
+        let result = client().execute(current).await;
+        let retry_after = match &result {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let wait = retry_after(response).unwrap_or_else(|| policy.delay(retry));
+                hold_off(&host, wait);
+                Some(wait)
+            }
+            Ok(response) if response.status().is_server_error() => {
+                record(&host, false);
+                Some(policy.delay(retry))
+            }
+            Ok(_) => {
+                record(&host, true);
+                None
+            }
+            Err(err) if err.is_connect() || err.is_timeout() => {
+                record(&host, false);
+                Some(policy.delay(retry))
+            }
+            Err(_) => None,
+        };
+        match (retry_after, spare) {
+            (Some(wait), Some(spare)) => {
+                debug!(host, retry, "Retrying request in {:?}", wait);
+                tokio::time::sleep(wait).await;
+                current = spare;
+                retry += 1;
+            }
+            _ => return result.map_err(HttpError::from),
+        }
+    }
+}
+
+/// Waits until `host` may be sent another request, or fails straight away
+/// while its breaker is open.
+async fn wait_for_slot(host: &str, min_interval: Duration) -> Result<(), HttpError> {
+    let wait = {
+        let mut hosts = hosts().lock();
+        let now = Instant::now();
+        let state = hosts.entry(host.to_string()).or_insert(HostState {
+            failures: 0,
+            open_until: None,
+            next_slot: now,
+        });
+        if let Some(open_until) = state.open_until.filter(|until| *until > now) {
+            return Err(HttpError::CircuitOpen {
+                host: host.to_string(),
+                retry_in: open_until - now,
+            });
+        }
+        let slot = state.next_slot.max(now);
+        state.next_slot = slot + min_interval;
+        slot - now
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+    Ok(())
+}
+
+/// Counts an attempt against `host`'s breaker.
+fn record(host: &str, success: bool) {
+    let mut hosts = hosts().lock();
+    let Some(state) = hosts.get_mut(host) else {
+        return;
+    };
+    if success {
+        state.failures = 0;
+        state.open_until = None;
+        return;
+    }
+    state.failures += 1;
+    if state.failures >= BREAKER_THRESHOLD {
+        warn!(
+            host,
+            failures = state.failures,
+            "Not calling this host for {:?}",
+            BREAKER_COOLDOWN
+        );
+        state.open_until = Some(Instant::now() + BREAKER_COOLDOWN);
+    }
+}
+
+/// Keeps every request to `host` back for `wait`, as it asked.
+fn hold_off(host: &str, wait: Duration) {
+    if let Some(state) = hosts().lock().get_mut(host) {
+        state.next_slot = state.next_slot.max(Instant::now() + wait);
+    }
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs: u64 = response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs).min(MAX_RETRY_AFTER))
+}
@@ -0,0 +1,108 @@
+use crate::transcript::TurnSource;
+use serde::{Deserialize, Serialize};
+
+pub const LEVELS_FILE: &str = "levels.json";
+/// Length of one point in the history.
+const BUCKET_MS: u32 = 250;
+/// Summed squared RMS under which a stretch counts as silent.
+const SILENT_ENERGY: f64 = 1e-6;
+
+/// How loud each source was over a recording, for drawing the meeting back
+/// without decoding audio. Points are RMS from 0 to 1, one per `bucket_ms` of
+/// captured audio, starting when the recording did.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelHistory {
+    pub bucket_ms: u32,
+    /// The local microphone, i.e. you.
+    pub mic: Vec<f32>,
+    /// System audio, i.e. everyone else on the call. Empty for sessions that
+    /// only record a microphone.
+    pub system: Vec<f32>,
+}
+
+/// Collects `LevelHistory` from the mixer thread, a frame at a time.
+#[derive(Debug, Default)]
+pub struct LevelRecorder {
+    history: LevelHistory,
+    has_system: bool,
+    bucket_samples: usize,
+    samples: usize,
+    mic_squares: f64,
+    system_squares: f64,
+}
+
+impl LevelRecorder {
+    /// Starts over for a recording at `sample_rate`.
+    pub fn reset(&mut self, sample_rate: u32, has_system: bool) {
+        *self = Self {
+            history: LevelHistory {
+                bucket_ms: BUCKET_MS,
+                ..LevelHistory::default()
+            },
+            has_system,
+            bucket_samples: (sample_rate * BUCKET_MS / 1000) as usize,
+            ..Self::default()
+        };
+    }
+
+    /// Adds a frame of `samples` samples per source, given as the sum of
+    /// each source's squared samples.
+    pub fn push(&mut self, mic_squares: f64, system_squares: f64, samples: usize) {
+        if self.bucket_samples == 0 {
+            return;
+        }
+        self.mic_squares += mic_squares;
+        self.system_squares += system_squares;
+        self.samples += samples;
+        if self.samples < self.bucket_samples {
+            return;
+        }
+        let rms = |squares: f64| {
+            // Three decimals are plenty to draw with and keep the file small.
+            let level = (squares / self.samples as f64).sqrt().min(1.0);
+            ((level * 1000.0).round() / 1000.0) as f32
+        };
+        self.history.mic.push(rms(self.mic_squares));
+        if self.has_system {
+            self.history.system.push(rms(self.system_squares));
+        }
+        self.samples = 0;
+        self.mic_squares = 0.0;
+        self.system_squares = 0.0;
+    }
+
+    /// Which source was louder from `start` to `end` seconds into the
+    /// recording; `None` when that stretch is silent or not recorded.
+    pub fn dominant_source(&self, start: f64, end: f64) -> Option<TurnSource> {
+        let bucket_secs = f64::from(self.history.bucket_ms) / 1000.0;
+        if bucket_secs <= 0.0 {
+            return None;
+        }
+        let first = (start.max(0.0) / bucket_secs).floor() as usize;
+        let last = ((end / bucket_secs).ceil() as usize).max(first + 1);
+        let energy = |levels: &[f32]| -> f64 {
+            levels
+                .iter()
+                .skip(first)
+                .take(last - first)
+                .map(|level| f64::from(*level).powi(2))
+                .sum()
+        };
+        let mic = energy(&self.history.mic);
+        let system = energy(&self.history.system);
+        if mic + system <= SILENT_ENERGY {
+            return None;
+        }
+        // The microphone also picks up the call from speakers, but quieter.
+        Some(if mic >= system {
+            TurnSource::Mic
+        } else {
+            TurnSource::System
+        })
+    }
+
+    pub fn history(&self) -> LevelHistory {
+        self.history.clone()
+    }
+}
@@ -0,0 +1,31 @@
+//! The recording engine behind Jilu, usable without the app.
+//!
+//! - [`audio`] mixes microphone and system audio into 16 kHz PCM frames,
+//!   and [`chimes`] keeps notification sounds out of them.
+//! - [`provider`] is what a realtime transcription service has to offer;
+//!   [`speechmatics`] and [`deepgram`] speak their protocols.
+//! - [`transcript`] holds speaker turns as they are finalized, and
+//!   [`formatting`] cleans their text up.
+//! - [`realtime::transcribe`] ties these together: feed a mixer, get a
+//!   transcript back.
+//! - [`simulator`] stands in for Speechmatics with a scripted meeting.
+//! - [`http`] is the client outbound requests share, which retries them and
+//!   stops calling hosts that keep failing.
+//!
+//! Capturing audio is left to the embedder; push `f32` samples at
+//! [`audio::SOURCE_SAMPLE_RATE`] into the mixer's inputs from whatever
+//! source you have.
+
+pub mod audio;
+pub mod chapters;
+pub mod chimes;
+pub mod deepgram;
+pub mod formatting;
+pub mod http;
+pub mod levels;
+pub mod locale;
+pub mod provider;
+pub mod realtime;
+pub mod simulator;
+pub mod speechmatics;
+pub mod transcript;
@@ -0,0 +1,159 @@
+use std::borrow::Cow;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Conventions for text the backend renders itself (transcript text and
+/// exports), following the UI language. Unknown languages fall back to English.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Dutch,
+    Chinese,
+    Japanese,
+    Korean,
+}
+
+impl Locale {
+    /// Picks conventions by the primary subtag of a tag like `de-CH` or `pt_BR`.
+    pub fn from_tag(tag: &str) -> Self {
+        let language = tag.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "de" => Locale::German,
+            "fr" => Locale::French,
+            "es" => Locale::Spanish,
+            "it" => Locale::Italian,
+            "pt" => Locale::Portuguese,
+            "nl" => Locale::Dutch,
+            "zh" => Locale::Chinese,
+            "ja" => Locale::Japanese,
+            "ko" => Locale::Korean,
+            _ => Locale::English,
+        }
+    }
+
+    /// The Speechmatics language code for transcribing in this language.
+    pub fn transcription_language(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+            Locale::French => "fr",
+            Locale::Spanish => "es",
+            Locale::Italian => "it",
+            Locale::Portuguese => "pt",
+            Locale::Dutch => "nl",
+            Locale::Chinese => "cmn",
+            Locale::Japanese => "ja",
+            Locale::Korean => "ko",
+        }
+    }
+
+    fn speaker_word(self) -> &'static str {
+        match self {
+            Locale::English => "Speaker",
+            Locale::German => "Sprecher",
+            Locale::French => "Intervenant",
+            Locale::Spanish => "Hablante",
+            Locale::Italian => "Interlocutore",
+            Locale::Portuguese => "Orador",
+            Locale::Dutch => "Spreker",
+            Locale::Chinese => "说话人",
+            Locale::Japanese => "話者",
+            Locale::Korean => "화자",
+        }
+    }
+
+    fn unknown_speaker(self) -> &'static str {
+        match self {
+            Locale::English => "Unknown speaker",
+            Locale::German => "Unbekannt",
+            Locale::French => "Inconnu",
+            Locale::Spanish => "Desconocido",
+            Locale::Italian => "Sconosciuto",
+            Locale::Portuguese => "Desconhecido",
+            Locale::Dutch => "Onbekend",
+            Locale::Chinese => "未知",
+            Locale::Japanese => "不明",
+            Locale::Korean => "알 수 없음",
+        }
+    }
+
+    /// What labels a note typed during the recording.
+    pub fn note_word(self) -> &'static str {
+        match self {
+            Locale::English => "Note",
+            Locale::German => "Notiz",
+            Locale::French => "Note",
+            Locale::Spanish => "Nota",
+            Locale::Italian => "Nota",
+            Locale::Portuguese => "Nota",
+            Locale::Dutch => "Notitie",
+            Locale::Chinese => "笔记",
+            Locale::Japanese => "メモ",
+            Locale::Korean => "메모",
+        }
+    }
+
+    /// What goes between a speaker's name and their words.
+    fn label_separator(self) -> &'static str {
+        match self {
+            // French sets a narrow no-break space before a colon.
+            Locale::French => "\u{202f}: ",
+            Locale::Chinese | Locale::Japanese => "：",
+            _ => ": ",
+        }
+    }
+
+    /// Display name for a speaker. Provider labels such as `S2` and `UU` are
+    /// translated; names of enrolled speakers are kept as they are.
+    pub fn speaker_name(self, speaker: &str) -> Cow<'_, str> {
+        if speaker == "UU" {
+            return Cow::Borrowed(self.unknown_speaker());
+        }
+        match speaker.strip_prefix('S') {
+            Some(number) if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => {
+                Cow::Owned(format!("{} {}", self.speaker_word(), number))
+            }
+            _ => Cow::Borrowed(speaker),
+        }
+    }
+
+    /// `[Speaker 1]: ` in English, `[Intervenant 1] : ` in French, and so on.
+    pub fn speaker_prefix(self, speaker: &str) -> String {
+        format!("[{}]{}", self.speaker_name(speaker), self.label_separator())
+    }
+
+    pub fn note_prefix(self) -> String {
+        format!("[{}]{}", self.note_word(), self.label_separator())
+    }
+
+    pub fn note_label(self) -> String {
+        format!("{}{}", self.note_word(), self.label_separator())
+    }
+
+    /// Speaker and separator without brackets, as used for subtitle cues.
+    pub fn speaker_label(self, speaker: &str) -> String {
+        format!("{}{}", self.speaker_name(speaker), self.label_separator())
+    }
+}
+
+/// Accepts BCP 47 style tags such as `en`, `pt-BR` or `zh_Hant_TW`.
+pub fn validate_locale(tag: &str) -> Result<(), String> {
+    let mut subtags = tag.split(['-', '_']);
+    let primary = subtags.next().unwrap_or_default();
+    let valid = (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid locale '{}'", tag))
+    }
+}
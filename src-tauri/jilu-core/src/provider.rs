@@ -0,0 +1,306 @@
+//! What the recording pipeline needs from a realtime transcription service,
+//! so it can stream to any of them.
+//!
+//! A session is a WebSocket. Audio goes out as the messages
+//! [`RealtimeProvider::audio`] makes of each batch, and the session's
+//! [`EventDecoder`] turns what comes back into [`ProviderEvent`]s.
+
+use crate::deepgram::Deepgram;
+use crate::formatting::{clean_punctuation, push_clean_punctuation};
+use crate::speechmatics::{
+    AdditionalVocabularyEntry, KnownSpeaker, OperatingPoint, RtAuth, RtReader, RtWriter,
+    SpeakersResultEntry, Speechmatics,
+};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::{tungstenite::Message, Connector};
+
+/// How often [`RealtimeProvider::keep_alive`] messages go out.
+pub const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+
+const DEFAULT_MAX_AUDIO_MESSAGE_BYTES: usize = 64 << 10;
+const MIN_AUDIO_MESSAGE_BYTES: usize = 1 << 10;
+const MAX_AUDIO_MESSAGE_BYTES: usize = 16 << 20;
+const DEFAULT_WRITE_BUFFER_BYTES: usize = 128 << 10;
+const DEFAULT_MAX_MESSAGE_BYTES: usize = 64 << 20;
+const DEFAULT_MAX_FRAME_BYTES: usize = 16 << 20;
+
+/// The realtime services Jilu can transcribe with.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ProviderKind {
+    #[default]
+    Speechmatics,
+    Deepgram,
+}
+
+impl ProviderKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProviderKind::Speechmatics => "speechmatics",
+            ProviderKind::Deepgram => "deepgram",
+        }
+    }
+
+    /// The provider, reached at `rt_url` or at its own endpoint when that is
+    /// unset. `auth` only matters to Speechmatics.
+    pub fn provider(
+        self,
+        api_key: String,
+        rt_url: Option<String>,
+        auth: RtAuth,
+        tls: Option<Connector>,
+        websocket: WebSocketSettings,
+    ) -> Box<dyn RealtimeProvider> {
+        match self {
+            ProviderKind::Speechmatics => Box::new(Speechmatics {
+                api_key,
+                rt_url,
+                auth,
+                tls,
+                websocket,
+            }),
+            ProviderKind::Deepgram => Box::new(Deepgram {
+                api_key,
+                url: rt_url,
+                tls,
+                websocket,
+            }),
+        }
+    }
+}
+
+/// Message sizes and write buffering for a session's WebSocket. The defaults
+/// suit a good connection; a gateway with a smaller frame limit, or a link
+/// that stalls, may want smaller pieces and a bounded buffer.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WebSocketSettings {
+    /// Most PCM bytes sent in one audio message; bigger batches, such as the
+    /// backlog resent after a stall, go out in several.
+    pub max_audio_message_bytes: usize,
+    /// Bytes gathered before they are written to the socket; 0 writes every
+    /// message as soon as it is sent.
+    pub write_buffer_bytes: usize,
+    /// Most the write buffer may hold while writes are failing; `None` for no
+    /// limit. Must be above `write_buffer_bytes`.
+    pub max_write_buffer_bytes: Option<usize>,
+    /// Largest message accepted from the provider.
+    pub max_message_bytes: usize,
+    /// Largest single frame accepted from the provider.
+    pub max_frame_bytes: usize,
+}
+
+impl Default for WebSocketSettings {
+    fn default() -> Self {
+        Self {
+            max_audio_message_bytes: DEFAULT_MAX_AUDIO_MESSAGE_BYTES,
+            write_buffer_bytes: DEFAULT_WRITE_BUFFER_BYTES,
+            max_write_buffer_bytes: None,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            max_frame_bytes: DEFAULT_MAX_FRAME_BYTES,
+        }
+    }
+}
+
+impl WebSocketSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_AUDIO_MESSAGE_BYTES..=MAX_AUDIO_MESSAGE_BYTES)
+            .contains(&self.max_audio_message_bytes)
+        {
+            return Err(format!(
+                "Audio messages must allow between {} and {} bytes",
+                MIN_AUDIO_MESSAGE_BYTES, MAX_AUDIO_MESSAGE_BYTES
+            ));
+        }
+        if let Some(max) = self.max_write_buffer_bytes {
+            // Below this tungstenite refuses to open the socket at all.
+            if max <= self.write_buffer_bytes + self.max_audio_message_bytes {
+                return Err(
+                    "The write buffer limit must leave room for a full audio message".to_string(),
+                );
+            }
+        }
+        if self.max_frame_bytes == 0 || self.max_message_bytes < self.max_frame_bytes {
+            return Err("Incoming messages must allow at least one frame".to_string());
+        }
+        Ok(())
+    }
+
+    /// What tungstenite is opened with.
+    pub fn config(&self) -> WebSocketConfig {
+        WebSocketConfig {
+            write_buffer_size: self.write_buffer_bytes,
+            max_write_buffer_size: self.max_write_buffer_bytes.unwrap_or(usize::MAX),
+            max_message_size: Some(self.max_message_bytes),
+            max_frame_size: Some(self.max_frame_bytes),
+            ..WebSocketConfig::default()
+        }
+    }
+}
+
+/// What a session is asked to transcribe, whichever the provider. Anything a
+/// provider has no equivalent for is left out of its request.
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub language: String,
+    /// Language pack domain, e.g. `bilingual-en`.
+    pub domain: Option<String>,
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    pub partials: bool,
+    /// Longest the provider may hold a word back to get it right, in seconds.
+    pub max_delay: f32,
+    /// Only Speechmatics offers a choice of model accuracy.
+    pub operating_point: OperatingPoint,
+    /// Tells speakers apart by voice; channels take its place when named.
+    pub diarization: bool,
+    pub max_speakers: Option<u32>,
+    /// Enrolled voices to label by name instead of `S1`, `S2`, ...
+    pub known_speakers: Vec<KnownSpeaker>,
+    /// Asks for identifiers of the voices heard, reported as the session ends.
+    pub capture_speakers: bool,
+    /// Names of the interleaved audio channels, which then become the
+    /// speakers; empty for mono audio.
+    pub channels: Vec<String>,
+}
+
+impl SessionConfig {
+    pub fn new(language: impl Into<String>) -> Self {
+        Self {
+            language: language.into(),
+            domain: None,
+            additional_vocab: Vec::new(),
+            partials: true,
+            max_delay: 1.5,
+            operating_point: OperatingPoint::default(),
+            diarization: true,
+            max_speakers: None,
+            known_speakers: Vec::new(),
+            capture_speakers: false,
+            channels: Vec::new(),
+        }
+    }
+}
+
+/// Something a provider said about a session.
+#[derive(Debug)]
+pub enum ProviderEvent {
+    /// Recognition is under way.
+    Started,
+    /// Every audio message up to this one has been received.
+    AudioAcked(u64),
+    /// Text that may still change, and how far into the audio it reaches.
+    Partial {
+        text: String,
+        end: Option<f64>,
+    },
+    Final(FinalResult),
+    /// Identifiers of the voices heard in the session.
+    Speakers(Vec<SpeakersResultEntry>),
+    Error(String),
+    /// Nothing more is coming.
+    Finished,
+}
+
+/// Words the provider will not change any more.
+#[derive(Debug, Default)]
+pub struct FinalResult {
+    pub words: Vec<Word>,
+    /// The provider's own rendering, for results that came without words.
+    pub transcript: Option<String>,
+    /// Seconds into the session's audio the result covers.
+    pub end: Option<f64>,
+}
+
+/// A word or punctuation mark, already cleaned.
+#[derive(Debug)]
+pub struct Word {
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Seconds into the session's audio.
+    pub span: Option<(f64, f64)>,
+    /// How sure the provider is of the word, from 0 to 1.
+    pub confidence: Option<f64>,
+    /// The language the provider heard the word in, for those that say.
+    pub language: Option<String>,
+}
+
+/// Consecutive final words from one speaker, joined into a phrase.
+pub struct SpeakerRun<'m> {
+    pub speaker: Option<&'m str>,
+    pub text: String,
+    pub span: Option<(f64, f64)>,
+    /// The words the phrase was joined from.
+    pub words: &'m [Word],
+}
+
+impl FinalResult {
+    /// Joins the words into per-speaker runs, so formatting sees phrases
+    /// rather than one word at a time.
+    pub fn speaker_runs(&self) -> Vec<SpeakerRun<'_>> {
+        let mut runs: Vec<SpeakerRun<'_>> = Vec::new();
+        for (index, word) in self.words.iter().enumerate() {
+            let speaker = word.speaker.as_deref();
+            match runs.last_mut() {
+                Some(run) if run.speaker == speaker => {
+                    run.words = &self.words[index - run.words.len()..=index];
+                    run.text.push(' ');
+                    push_clean_punctuation(word.text.trim(), &mut run.text);
+                    if let Some((start, end)) = word.span {
+                        run.span = Some(run.span.map_or((start, end), |(first, last)| {
+                            (first.min(start), last.max(end))
+                        }));
+                    }
+                }
+                _ => runs.push(SpeakerRun {
+                    speaker,
+                    text: clean_punctuation(word.text.trim()),
+                    span: word.span,
+                    words: std::slice::from_ref(word),
+                }),
+            }
+        }
+        runs
+    }
+}
+
+/// A realtime transcription service.
+pub trait RealtimeProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    /// Opens a session and starts recognition.
+    fn connect<'a>(
+        &'a self,
+        config: &'a SessionConfig,
+    ) -> BoxFuture<'a, Result<(RtWriter, RtReader), String>>;
+
+    /// Reads one session's messages.
+    fn decoder(&self, config: &SessionConfig) -> Box<dyn EventDecoder>;
+
+    /// The messages that carry one batch of pcm_s16le audio, interleaved
+    /// when `channels` names more than one.
+    fn audio(&self, batch: Vec<u8>, channels: &[String]) -> Vec<Message>;
+
+    /// The messages that end a session after `seq_no` batches of audio.
+    fn end_of_stream(&self, channels: &[String], seq_no: u64) -> Vec<Message>;
+
+    /// Sent every [`KEEP_ALIVE_INTERVAL`], for providers that close a session
+    /// that goes quiet, as one does while muted.
+    fn keep_alive(&self) -> Option<Message> {
+        None
+    }
+
+    /// Whether sessions report [`ProviderEvent::AudioAcked`]. Without it
+    /// there is no telling whether all audio arrived before the end.
+    fn acknowledges_audio(&self) -> bool;
+}
+
+/// Turns a session's text messages into events.
+pub trait EventDecoder: Send {
+    /// Events for a session that has just opened, before any message.
+    fn opened(&mut self, _events: &mut Vec<ProviderEvent>) {}
+
+    fn decode(&mut self, text: &str, events: &mut Vec<ProviderEvent>);
+}
@@ -0,0 +1,187 @@
+//! Streams a mixer's audio to a realtime provider and collects the final transcript.
+
+use crate::audio::{self, AudioBatcher, AudioMixer};
+use crate::formatting::{self, FormattingSettings};
+use crate::provider::{ProviderEvent, ProviderKind, SessionConfig, WebSocketSettings};
+use crate::speechmatics::{AdditionalVocabularyEntry, OperatingPoint, RtAuth};
+use crate::transcript::Transcript;
+use futures_util::{SinkExt, StreamExt};
+use std::future::Future;
+use std::time::Duration;
+use tokio_tungstenite::{tungstenite::Message, Connector};
+
+const DEFAULT_AUDIO_BATCH_MS: u64 = 100;
+const END_OF_TRANSCRIPT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How to reach the provider and what to ask it for.
+#[derive(Clone)]
+pub struct RealtimeOptions {
+    pub provider: ProviderKind,
+    /// The provider's key.
+    pub api_key: String,
+    /// Realtime endpoint; `None` uses the provider's, such as
+    /// [`crate::speechmatics::DEFAULT_RT_URL`].
+    pub rt_url: Option<String>,
+    /// How Speechmatics sessions are authenticated.
+    pub auth: RtAuth,
+    /// Connector for endpoints behind a private CA or requiring a client certificate.
+    pub tls: Option<Connector>,
+    pub websocket: WebSocketSettings,
+    pub language: String,
+    pub domain: Option<String>,
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    pub operating_point: OperatingPoint,
+    pub formatting: FormattingSettings,
+    /// Audio is sent in batches of this many milliseconds.
+    pub audio_batch_ms: u64,
+}
+
+impl RealtimeOptions {
+    /// English on Speechmatics' default endpoint, with default formatting.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            provider: ProviderKind::default(),
+            api_key: api_key.into(),
+            rt_url: None,
+            auth: RtAuth::default(),
+            tls: None,
+            websocket: WebSocketSettings::default(),
+            language: "en".to_string(),
+            domain: None,
+            additional_vocab: Vec::new(),
+            operating_point: OperatingPoint::default(),
+            formatting: FormattingSettings::default(),
+            audio_batch_ms: DEFAULT_AUDIO_BATCH_MS,
+        }
+    }
+}
+
+/// Transcribes everything `mixer` produces until `stop` resolves or the
+/// mixer finishes, then waits for the provider's final results.
+///
+/// `on_text` sees each finalized phrase as it arrives, already formatted.
+/// Speakers are diarized, so turns in the returned transcript carry labels
+/// like `S1`.
+///
+/// ```no_run
+/// # async fn run() -> Result<(), String> {
+/// use jilu_core::audio::AudioMixer;
+/// use jilu_core::realtime::{transcribe, RealtimeOptions};
+///
+/// let (mixer, inputs) = AudioMixer::spawn(None)?;
+/// // Push 48 kHz mono samples into `inputs.mic` and `inputs.screen` with
+/// // `jilu_core::audio::push_samples` from your capture callbacks.
+/// # drop(inputs);
+/// let stop = tokio::time::sleep(std::time::Duration::from_secs(60));
+/// let transcript = transcribe(RealtimeOptions::new("api-key"), mixer, stop, |text| {
+///     println!("{}", text)
+/// })
+/// .await?;
+/// println!("{}", transcript.text());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn transcribe(
+    options: RealtimeOptions,
+    mut mixer: AudioMixer,
+    stop: impl Future<Output = ()>,
+    mut on_text: impl FnMut(&str) + Send + 'static,
+) -> Result<Transcript, String> {
+    let config = SessionConfig {
+        domain: options.domain,
+        additional_vocab: options.additional_vocab,
+        partials: false,
+        max_delay: 2.0,
+        operating_point: options.operating_point,
+        ..SessionConfig::new(options.language)
+    };
+    let provider = options.provider.provider(
+        options.api_key,
+        options.rt_url,
+        options.auth,
+        options.tls,
+        options.websocket,
+    );
+    let max_audio_bytes = options.websocket.max_audio_message_bytes;
+    let (mut write, mut read) = provider.connect(&config).await?;
+
+    let formatting = options.formatting;
+    let mut decoder = provider.decoder(&config);
+    let reader = tokio::spawn(async move {
+        let mut transcript = Transcript::default();
+        let mut events = Vec::new();
+        'read: while let Some(Ok(msg)) = read.next().await {
+            let Message::Text(text) = msg else { continue };
+            decoder.decode(&text, &mut events);
+            for event in events.drain(..) {
+                match event {
+                    ProviderEvent::Error(error) => return Err(error),
+                    ProviderEvent::Final(result) => {
+                        for run in result.speaker_runs() {
+                            let text = formatting::apply(
+                                &run.text,
+                                &formatting,
+                                transcript.starts_sentence(run.speaker),
+                            );
+                            transcript.append_at(run.speaker, &text, run.span);
+                            on_text(&text);
+                        }
+                    }
+                    ProviderEvent::Finished => break 'read,
+                    _ => {}
+                }
+            }
+        }
+        Ok(transcript)
+    });
+
+    let mut batcher = AudioBatcher::new(options.audio_batch_ms);
+    let mut seq_no: u64 = 0;
+    tokio::pin!(stop);
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            _ = mixer.frame_ready() => {}
+        }
+        while let Some(frame) = mixer.pop_frame() {
+            if let Some((batch, _)) = batcher.push(&frame) {
+                for piece in audio::split_pcm(batch, max_audio_bytes, 1) {
+                    for message in provider.audio(piece, &[]) {
+                        write
+                            .send(message)
+                            .await
+                            .map_err(|e| format!("Failed to send audio: {}", e))?;
+                    }
+                    seq_no += 1;
+                }
+            }
+        }
+        if mixer.is_finished() {
+            break;
+        }
+    }
+    mixer.stop();
+    if let Some((batch, _)) = batcher.flush() {
+        for piece in audio::split_pcm(batch, max_audio_bytes, 1) {
+            let mut sent = true;
+            for message in provider.audio(piece, &[]) {
+                sent = sent && write.send(message).await.is_ok();
+            }
+            if sent {
+                seq_no += 1;
+            }
+        }
+    }
+
+    for message in provider.end_of_stream(&[], seq_no) {
+        write
+            .send(message)
+            .await
+            .map_err(|e| format!("Failed to end stream: {}", e))?;
+    }
+
+    tokio::time::timeout(END_OF_TRANSCRIPT_TIMEOUT, reader)
+        .await
+        .map_err(|_| "Timed out waiting for the final transcript".to_string())?
+        .map_err(|e| format!("Transcript reader failed: {}", e))?
+}
@@ -0,0 +1,427 @@
+//! A stand-in for the Speechmatics realtime API that replays a scripted
+//! meeting, for UI work, demos and end-to-end tests without an API key.
+//!
+//! [`Simulator::start`] listens on a local port and speaks the same protocol
+//! as the real service: it acknowledges audio, sends partials and finals with
+//! speaker labels, and ends with `EndOfTranscript`. Point `rt_url` at
+//! [`Simulator::url`] with [`RtAuth::ApiKey`](crate::speechmatics::RtAuth)
+//! and any key will do.
+
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, warn};
+
+const MAX_SPEED: f64 = 20.0;
+const MAX_SPEAKERS: u32 = 10;
+
+/// The meeting replayed when no script is given.
+const DEMO_MEETING: &[(u32, &str)] = &[
+    (
+        1,
+        "Morning everyone, thanks for joining. Let's start with the launch timeline.",
+    ),
+    (
+        2,
+        "Sure. The beta build went out on Monday and we've had around forty sign-ups so far.",
+    ),
+    (1, "That's better than expected. Any problems reported?"),
+    (
+        2,
+        "Two crash reports, both on older laptops. I've got a fix ready for review.",
+    ),
+    (
+        3,
+        "I can review it this afternoon. Is it blocking the next build?",
+    ),
+    (
+        2,
+        "Not blocking, but I'd like it in before we open the beta wider.",
+    ),
+    (
+        1,
+        "Agreed. Let's hold the wider release until that fix is merged.",
+    ),
+    (
+        3,
+        "On marketing, the landing page copy is done and the screenshots are next.",
+    ),
+    (1, "Great. Can we have the screenshots by Thursday?"),
+    (
+        3,
+        "Thursday works. I'll share a draft in the channel tomorrow.",
+    ),
+    (
+        2,
+        "One more thing, the pricing page still shows last year's plans.",
+    ),
+    (
+        1,
+        "Good catch. I'll take that one. Anything else before we wrap up?",
+    ),
+    (3, "Nothing from me."),
+    (
+        1,
+        "Then let's call it there. Thanks everyone, same time next week.",
+    ),
+];
+
+/// How the replayed meeting sounds.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulatorConfig {
+    /// Playback speed; `2.0` speaks twice as fast as a person would.
+    pub speed: f64,
+    /// How many voices the demo meeting is spread across. Ignored with a script.
+    pub speakers: u32,
+    /// Speaking rate at normal speed.
+    pub words_per_minute: u32,
+    /// How often a partial goes out while a phrase is being spoken.
+    pub partial_interval_ms: u64,
+    /// Words are finalized at least this often, and at the end of each line.
+    pub final_interval_ms: u64,
+    /// Silence between lines.
+    pub pause_ms: u64,
+    /// Start over from the top once the script runs out.
+    pub repeat: bool,
+    /// Lines to replay instead of the demo meeting.
+    pub script: Option<Vec<ScriptLine>>,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            speakers: 3,
+            words_per_minute: 160,
+            partial_interval_ms: 300,
+            final_interval_ms: 1_500,
+            pause_ms: 700,
+            repeat: true,
+            script: None,
+        }
+    }
+}
+
+impl SimulatorConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(self.speed > 0.0 && self.speed <= MAX_SPEED) {
+            return Err(format!(
+                "Simulator speed must be above 0 and at most {}",
+                MAX_SPEED
+            ));
+        }
+        if !(1..=MAX_SPEAKERS).contains(&self.speakers) {
+            return Err(format!(
+                "Simulator speakers must be between 1 and {}",
+                MAX_SPEAKERS
+            ));
+        }
+        if self.words_per_minute == 0 {
+            return Err("Simulator words per minute must be above 0".to_string());
+        }
+        if self.partial_interval_ms == 0 || self.final_interval_ms == 0 {
+            return Err("Simulator intervals must be above 0 ms".to_string());
+        }
+        if let Some(script) = &self.script {
+            if script.iter().all(|line| line.text.trim().is_empty()) {
+                return Err("The simulator script has no words".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// The lines to replay, with speaker labels as Speechmatics gives them.
+    fn lines(&self) -> Vec<ScriptLine> {
+        match &self.script {
+            Some(script) => script.clone(),
+            None => DEMO_MEETING
+                .iter()
+                .map(|(speaker, text)| ScriptLine {
+                    speaker: format!("S{}", (speaker - 1) % self.speakers + 1),
+                    text: text.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn word_duration(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / (self.words_per_minute as f64 * self.speed))
+    }
+
+    fn pause(&self) -> Duration {
+        Duration::from_secs_f64(self.pause_ms as f64 / 1000.0 / self.speed)
+    }
+}
+
+/// One thing said in the scripted meeting.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScriptLine {
+    pub speaker: String,
+    pub text: String,
+}
+
+/// A local realtime endpoint replaying the configured meeting to each
+/// connection. Stops listening when dropped.
+pub struct Simulator {
+    url: String,
+    accept: tokio::task::JoinHandle<()>,
+}
+
+impl Simulator {
+    /// Starts listening on a free local port. Must be called inside a Tokio runtime.
+    pub async fn start(config: SimulatorConfig) -> Result<Self, String> {
+        config.validate()?;
+        let listener = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .map_err(|e| format!("Failed to start the simulator: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to start the simulator: {}", e))?
+            .port();
+        let config = Arc::new(config);
+        let accept = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, config.clone()));
+            }
+        });
+        Ok(Self {
+            url: format!("ws://127.0.0.1:{}/v2", port),
+            accept,
+        })
+    }
+
+    /// Realtime URL to connect to.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for Simulator {
+    fn drop(&mut self) {
+        self.accept.abort();
+    }
+}
+
+/// A word said but not yet final, timed in seconds since recognition started.
+struct Word {
+    text: String,
+    /// Speaker label, or the channel name when channels are diarized.
+    speaker: String,
+    start: f64,
+    end: f64,
+}
+
+fn transcript_message(message: &str, words: &[Word], by_channel: bool) -> Message {
+    let transcript = words
+        .iter()
+        .map(|word| word.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let results: Vec<_> = words
+        .iter()
+        .map(|word| {
+            if by_channel {
+                json!({
+                    "type": "word",
+                    "start_time": word.start,
+                    "end_time": word.end,
+                    "channel": word.speaker,
+                    "alternatives": [{ "content": word.text, "confidence": 1.0 }],
+                })
+            } else {
+                json!({
+                    "type": "word",
+                    "start_time": word.start,
+                    "end_time": word.end,
+                    "alternatives": [{
+                        "content": word.text,
+                        "confidence": 1.0,
+                        "speaker": word.speaker,
+                    }],
+                })
+            }
+        })
+        .collect();
+    Message::Text(
+        json!({
+            "message": message,
+            "metadata": {
+                "transcript": transcript,
+                "start_time": words.first().map_or(0.0, |word| word.start),
+                "end_time": words.last().map_or(0.0, |word| word.end),
+            },
+            "results": results,
+        })
+        .to_string(),
+    )
+}
+
+async fn serve(stream: TcpStream, config: Arc<SimulatorConfig>) {
+    let ws = match tokio_tungstenite::accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(err) => {
+            warn!("Simulator handshake failed: {}", err);
+            return;
+        }
+    };
+    let (mut write, mut read) = ws.split();
+
+    // Nothing is said until recognition has been asked for.
+    let channels: Vec<String> = loop {
+        match read.next().await {
+            Some(Ok(Message::Text(text))) if text.contains("StartRecognition") => {
+                let start: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+                break serde_json::from_value(
+                    start["transcription_config"]["channel_diarization_labels"].clone(),
+                )
+                .unwrap_or_default();
+            }
+            Some(Ok(_)) => continue,
+            _ => return,
+        }
+    };
+    let by_channel = !channels.is_empty();
+    let mut channel_seq_nos: Vec<u64> = vec![0; channels.len()];
+    let mut channels_ended = 0;
+    let started = json!({ "message": "RecognitionStarted", "id": "simulated" });
+    if write
+        .send(Message::Text(started.to_string()))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    debug!("Simulator session started");
+
+    let lines = config.lines();
+    let first_speaker = lines
+        .first()
+        .map(|line| line.speaker.clone())
+        .unwrap_or_default();
+    // With channels, the first line's speaker is heard on the first one and
+    // everyone else on the last.
+    let channel_of = |speaker: &str| match channels.as_slice() {
+        [] => speaker.to_string(),
+        [first, ..] if speaker == first_speaker => first.clone(),
+        [.., last] => last.clone(),
+    };
+    let word_duration = config.word_duration();
+    let pause = config.pause();
+    let start = Instant::now();
+    let mut line_index = 0;
+    let mut words_in_line: Vec<String> = Vec::new();
+    let mut next_word = start;
+    let mut spoken: Vec<Word> = Vec::new();
+    let mut last_final = start;
+    let mut seq_no: u64 = 0;
+    let mut tick = tokio::time::interval(Duration::from_millis(config.partial_interval_ms));
+
+    loop {
+        tokio::select! {
+            msg = read.next() => match msg {
+                Some(Ok(Message::Binary(_))) => {
+                    seq_no += 1;
+                    let added = json!({ "message": "AudioAdded", "seq_no": seq_no });
+                    if write.send(Message::Text(added.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                Some(Ok(Message::Text(text))) if text.contains("AddChannelAudio") => {
+                    let audio: serde_json::Value = serde_json::from_str(&text).unwrap_or_default();
+                    let channel = audio["channel"].as_str().unwrap_or_default();
+                    let Some(index) = channels.iter().position(|name| name == channel) else {
+                        continue;
+                    };
+                    channel_seq_nos[index] += 1;
+                    let added = json!({
+                        "message": "ChannelAudioAdded",
+                        "channel": channel,
+                        "seq_no": channel_seq_nos[index],
+                    });
+                    if write.send(Message::Text(added.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                // The session ends with its last channel.
+                Some(Ok(Message::Text(text)))
+                    if text.contains("EndOfChannel") && channels_ended + 1 < channels.len() =>
+                {
+                    channels_ended += 1;
+                }
+                Some(Ok(Message::Text(text)))
+                    if text.contains("EndOfStream") || text.contains("EndOfChannel") =>
+                {
+                    if !spoken.is_empty() {
+                        let _ = write
+                            .send(transcript_message("AddTranscript", &spoken, by_channel))
+                            .await;
+                    }
+                    let end = json!({ "message": "EndOfTranscript" });
+                    let _ = write.send(Message::Text(end.to_string())).await;
+                    let _ = write.close().await;
+                    return;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+            _ = tick.tick() => {
+                let now = Instant::now();
+                let mut line_ended = false;
+                while next_word <= now && !line_ended {
+                    if words_in_line.is_empty() {
+                        if line_index == lines.len() {
+                            if !config.repeat {
+                                break;
+                            }
+                            line_index = 0;
+                        }
+                        words_in_line = lines[line_index]
+                            .text
+                            .split_whitespace()
+                            .rev()
+                            .map(str::to_string)
+                            .collect();
+                    }
+                    let Some(text) = words_in_line.pop() else {
+                        line_index += 1;
+                        continue;
+                    };
+                    let word_start = next_word.duration_since(start).as_secs_f64();
+                    spoken.push(Word {
+                        text,
+                        speaker: channel_of(&lines[line_index].speaker),
+                        start: word_start,
+                        end: word_start + word_duration.as_secs_f64(),
+                    });
+                    next_word += word_duration;
+                    if words_in_line.is_empty() {
+                        line_index += 1;
+                        next_word += pause;
+                        line_ended = true;
+                    }
+                }
+                if spoken.is_empty() {
+                    continue;
+                }
+                let message = if line_ended
+                    || now.duration_since(last_final)
+                        >= Duration::from_millis(config.final_interval_ms)
+                {
+                    last_final = now;
+                    transcript_message("AddTranscript", &std::mem::take(&mut spoken), by_channel)
+                } else {
+                    transcript_message("AddPartialTranscript", &spoken, by_channel)
+                };
+                if write.send(message).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,808 @@
+//! Speechmatics realtime protocol: the messages exchanged over the WebSocket
+//! and the helpers to authenticate and connect.
+
+use crate::audio::{self, TARGET_SAMPLE_RATE};
+use crate::formatting::{clean_punctuation, push_clean_punctuation};
+use crate::http::{self, HttpError, RetryPolicy};
+use crate::provider::{
+    EventDecoder, FinalResult, ProviderEvent, ProviderKind, RealtimeProvider, SessionConfig,
+    WebSocketSettings, Word,
+};
+use base64::Engine;
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header::AUTHORIZATION, HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::{tungstenite::Message, Connector};
+use tracing::{debug, error, warn};
+
+pub const DEFAULT_RT_URL: &str = "wss://eu2.rt.speechmatics.com/v2";
+const JWT_URL: &str = "https://mp.speechmatics.com/v1/api_keys";
+/// A blocked key service often drops packets rather than refusing the
+/// connection, so don't wait for the OS to give up.
+const JWT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a realtime session is authenticated.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RtAuth {
+    /// A temporary key, or the API key itself when the key service at
+    /// `mp.speechmatics.com` can't be reached.
+    #[default]
+    Auto,
+    /// Always a temporary key; fails when the key service can't be reached.
+    TemporaryKey,
+    /// The API key, sent as a bearer token to the realtime endpoint. The key
+    /// service is never contacted.
+    ApiKey,
+}
+
+/// Speechmatics' accuracy tier. `Standard` is cheaper and a little faster.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum OperatingPoint {
+    Standard,
+    #[default]
+    Enhanced,
+}
+
+impl OperatingPoint {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OperatingPoint::Standard => "standard",
+            OperatingPoint::Enhanced => "enhanced",
+        }
+    }
+}
+
+/// The `max_delay` values Speechmatics accepts, in seconds.
+pub const MAX_DELAY_RANGE: std::ops::RangeInclusive<f32> = 0.7..=4.0;
+
+/// Why a temporary key could not be created.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthError {
+    /// `mp.speechmatics.com` could not be reached, or something in between
+    /// answered for it. Common on networks that only allow the realtime host.
+    EndpointUnreachable(String),
+    /// Speechmatics rejected the API key.
+    InvalidApiKey(String),
+    Other(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EndpointUnreachable(reason) => {
+                write!(f, "Speechmatics auth endpoint unreachable: {}", reason)
+            }
+            Self::InvalidApiKey(reason) => write!(f, "Invalid Speechmatics API key: {}", reason),
+            Self::Other(reason) => write!(f, "Failed to create a temporary key: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<HttpError> for AuthError {
+    fn from(err: HttpError) -> Self {
+        match err {
+            HttpError::Request(err) => err.into(),
+            HttpError::CircuitOpen { .. } => Self::EndpointUnreachable(err.to_string()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for AuthError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_connect() || err.is_timeout() {
+            Self::EndpointUnreachable(err.to_string())
+        } else {
+            Self::Other(err.to_string())
+        }
+    }
+}
+
+pub type RtStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+pub type RtWriter = futures_util::stream::SplitSink<RtStream, Message>;
+pub type RtReader = futures_util::stream::SplitStream<RtStream>;
+
+/// Custom vocabulary term, in the shape Speechmatics expects.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AdditionalVocabularyEntry {
+    pub content: String,
+    #[serde(default, alias = "soundsLike", skip_serializing_if = "Option::is_none")]
+    pub sounds_like: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpeechmaticsConfig {
+    pub message: String,
+    pub transcription_config: TranscriptionConfig,
+    pub audio_format: AudioFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranscriptionConfig {
+    pub language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    pub enable_partials: bool,
+    pub operating_point: String,
+    pub max_delay: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diarization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speaker_diarization_config: Option<SpeakerDiarizationConfig>,
+    /// Names of the audio channels, with `diarization` set to `channel`.
+    /// Audio then goes out as [`AddChannelAudioMessage`]s, one per channel.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_diarization_labels: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpeakerDiarizationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get_speakers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speakers: Option<Vec<KnownSpeaker>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_speakers: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct KnownSpeaker {
+    pub label: String,
+    pub speaker_identifiers: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakersResultMessage {
+    pub message: String,
+    #[serde(default)]
+    pub speakers: Option<Vec<SpeakersResultEntry>>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpeakersResultEntry {
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub speaker_identifiers: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioFormat {
+    #[serde(rename = "type")]
+    pub format_type: String,
+    pub encoding: String,
+    pub sample_rate: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EndOfStreamMessage {
+    pub message: String,
+    pub last_seq_no: u64,
+}
+
+/// Audio for one channel of a channel-diarized session.
+#[derive(Debug, Serialize)]
+pub struct AddChannelAudioMessage<'a> {
+    pub message: &'static str,
+    pub channel: &'a str,
+    /// pcm_s16le, base64 encoded.
+    pub data: String,
+}
+
+impl<'a> AddChannelAudioMessage<'a> {
+    pub fn new(channel: &'a str, pcm: &[u8]) -> Self {
+        Self {
+            message: "AddChannelAudio",
+            channel,
+            data: base64::engine::general_purpose::STANDARD.encode(pcm),
+        }
+    }
+}
+
+/// Ends one channel of a channel-diarized session, in place of `EndOfStream`.
+#[derive(Debug, Serialize)]
+pub struct EndOfChannelMessage<'a> {
+    pub message: &'static str,
+    pub channel: &'a str,
+    pub last_seq_no: u64,
+}
+
+/// String borrowed from the incoming WebSocket frame when it has no escapes.
+///
+/// serde only borrows `Cow` fields at the top level of a struct, so this newtype
+/// lets `Option`/`Vec` members avoid allocating per message too.
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct RtText<'a>(#[serde(borrow)] Cow<'a, str>);
+
+impl std::ops::Deref for RtText<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpeechmaticsMessage<'a> {
+    #[serde(borrow)]
+    pub message: RtText<'a>,
+    #[serde(default, borrow)]
+    pub results: Vec<SpeechmaticsResult<'a>>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default, borrow)]
+    pub metadata: Option<SpeechmaticsMetadata<'a>>,
+    /// Last audio message received, on `AudioAdded` and `ChannelAudioAdded`.
+    #[serde(default)]
+    pub seq_no: Option<u64>,
+    /// Channel the message is about, in channel-diarized sessions.
+    #[serde(default, borrow)]
+    pub channel: Option<RtText<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpeechmaticsResult<'a> {
+    #[serde(default, borrow)]
+    pub alternatives: Vec<SpeechmaticsAlternative<'a>>,
+    #[serde(default)]
+    pub start_time: Option<f64>,
+    #[serde(default)]
+    pub end_time: Option<f64>,
+    #[serde(default, borrow)]
+    pub channel: Option<RtText<'a>>,
+}
+
+impl SpeechmaticsResult<'_> {
+    pub fn span(&self) -> Option<(f64, f64)> {
+        Some((self.start_time?, self.end_time?))
+    }
+}
+
+impl SpeechmaticsMessage<'_> {
+    /// Who said `result`: its diarized speaker, or the channel it was heard
+    /// on when channels are diarized instead.
+    pub fn speaker_of<'s>(&'s self, result: &'s SpeechmaticsResult<'_>) -> Option<&'s str> {
+        result
+            .alternatives
+            .first()
+            .and_then(|alternative| alternative.speaker.as_deref())
+            .or(result.channel.as_deref())
+            .or(self.channel.as_deref())
+    }
+
+    /// The words of an `AddTranscript`, each with its speaker and timing.
+    pub fn final_result(&self, scratch: &mut String) -> FinalResult {
+        let words = self
+            .results
+            .iter()
+            .filter_map(|result| {
+                let first = result.alternatives.first()?;
+                first.write_text(scratch).then(|| Word {
+                    speaker: self.speaker_of(result).map(str::to_string),
+                    text: scratch.trim().to_string(),
+                    span: result.span(),
+                    confidence: first.confidence,
+                    language: first.language.as_deref().map(str::to_string),
+                })
+            })
+            .collect();
+        let metadata = self.metadata.as_ref();
+        FinalResult {
+            words,
+            transcript: metadata
+                .and_then(|m| m.transcript.as_deref())
+                .map(str::to_string),
+            end: metadata.and_then(|m| m.end_time),
+        }
+    }
+}
+
+/// The words of a finished batch job's `json-v2` transcript, which lists its
+/// results the way `AddTranscript` does.
+pub fn batch_result(json: &str) -> Result<FinalResult, String> {
+    #[derive(Deserialize)]
+    struct BatchTranscript<'a> {
+        #[serde(default, borrow)]
+        results: Vec<SpeechmaticsResult<'a>>,
+    }
+    let batch: BatchTranscript =
+        serde_json::from_str(json).map_err(|e| format!("Invalid batch transcript: {}", e))?;
+    let message = SpeechmaticsMessage {
+        message: RtText(Cow::Borrowed("AddTranscript")),
+        results: batch.results,
+        error: None,
+        metadata: None,
+        seq_no: None,
+        channel: None,
+    };
+    Ok(message.final_result(&mut String::new()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpeechmaticsAlternative<'a> {
+    #[serde(default, borrow)]
+    pub content: Option<AlternativeContent<'a>>,
+    #[serde(default, borrow)]
+    pub text: Option<RtText<'a>>,
+    #[serde(default, borrow)]
+    pub speaker: Option<RtText<'a>>,
+    #[serde(default)]
+    pub confidence: Option<f64>,
+    /// Set on every word, though it only varies within a bilingual pack.
+    #[serde(default, borrow)]
+    pub language: Option<RtText<'a>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum AlternativeContent<'a> {
+    Simple(#[serde(borrow)] RtText<'a>),
+    Parts(#[serde(borrow)] Vec<SpeechmaticsContent<'a>>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum SpeechmaticsContent<'a> {
+    Plain(#[serde(borrow)] RtText<'a>),
+    #[serde(rename_all = "camelCase")]
+    Rich {
+        #[serde(rename = "type")]
+        _kind: Option<String>,
+        #[serde(default, borrow)]
+        content: Option<RtText<'a>>,
+        #[serde(default, borrow)]
+        text: Option<RtText<'a>>,
+    },
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct SpeechmaticsMetadata<'a> {
+    #[serde(default, borrow)]
+    pub transcript: Option<RtText<'a>>,
+    /// Seconds into the audio stream covered by this message.
+    #[serde(default)]
+    pub end_time: Option<f64>,
+}
+
+pub fn extract_text(msg: &SpeechmaticsMessage, scratch: &mut String) -> Option<String> {
+    if let Some(meta) = &msg.metadata {
+        if let Some(transcript) = &meta.transcript {
+            if !transcript.trim().is_empty() {
+                return Some(clean_punctuation(transcript.trim()));
+            }
+        }
+    }
+
+    let mut partial = String::new();
+    for alt in msg.results.iter().filter_map(|r| r.alternatives.first()) {
+        if alt.write_text(scratch) {
+            if !partial.is_empty() {
+                partial.push(' ');
+            }
+            push_clean_punctuation(scratch, &mut partial);
+        }
+    }
+
+    if !partial.trim().is_empty() {
+        return Some(partial);
+    }
+
+    None
+}
+
+impl SpeechmaticsAlternative<'_> {
+    /// Writes the cleaned text into `out`, returning false if there is none.
+    ///
+    /// `out` is cleared first so the read loop can reuse one buffer per message.
+    pub fn write_text(&self, out: &mut String) -> bool {
+        out.clear();
+
+        if let Some(text) = &self.text {
+            push_clean_punctuation(text, out);
+        } else if let Some(content) = &self.content {
+            match content {
+                AlternativeContent::Simple(value) => push_clean_punctuation(value.trim(), out),
+                AlternativeContent::Parts(parts) => {
+                    for part in parts {
+                        match part {
+                            SpeechmaticsContent::Plain(value) => push_clean_punctuation(value, out),
+                            SpeechmaticsContent::Rich { content, text, .. } => {
+                                if let Some(value) = content.as_ref().or(text.as_ref()) {
+                                    push_clean_punctuation(value, out);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        !out.trim().is_empty()
+    }
+}
+
+/// Exchanges an API key for a short-lived realtime token.
+pub async fn create_jwt(api_key: String) -> Result<String, AuthError> {
+    let body = serde_json::json!({ "ttl": 60 });
+
+    let request = http::client()
+        .post(JWT_URL)
+        .query(&[("type", "rt")])
+        .header("Content-Type", "application/json")
+        .header("Authorization", format!("Bearer {}", api_key))
+        .json(&body)
+        .timeout(JWT_TIMEOUT);
+    let response = http::send(request, RetryPolicy::STANDARD).await?;
+
+    let status = response.status();
+    let response_text = response.text().await?;
+    let json = serde_json::from_str::<serde_json::Value>(&response_text);
+
+    if !status.is_success() {
+        let reason = format!("HTTP {}: {}", status, response_text.trim());
+        return Err(match (status.as_u16(), json) {
+            // Speechmatics answers in JSON; a proxy's block page does not.
+            (401 | 403, Ok(_)) => AuthError::InvalidApiKey(reason),
+            (_, Err(_)) => AuthError::EndpointUnreachable(format!("HTTP {}", status)),
+            _ => AuthError::Other(reason),
+        });
+    }
+
+    json.ok()
+        .and_then(|json| json["key_value"].as_str().map(str::to_string))
+        .ok_or_else(|| AuthError::Other("JWT token not found in response".to_string()))
+}
+
+fn rt_base(rt_url: Option<&str>) -> &str {
+    rt_url
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .unwrap_or(DEFAULT_RT_URL)
+}
+
+/// `rt_url`, or the default EU endpoint when unset, with `jwt` appended.
+pub fn build_rt_ws_url(rt_url: Option<&str>, jwt: &str) -> String {
+    let base = rt_base(rt_url);
+
+    if base.contains('?') {
+        format!("{}&jwt={}", base, jwt)
+    } else {
+        format!("{}?jwt={}", base, jwt)
+    }
+}
+
+/// Opens an authenticated WebSocket to the realtime API.
+///
+/// `tls` replaces the default connector, for endpoints that want a client
+/// certificate or are signed by a private CA; `websocket` replaces
+/// tungstenite's buffer and size limits.
+pub async fn open(
+    api_key: &str,
+    rt_url: Option<&str>,
+    auth: RtAuth,
+    tls: Option<Connector>,
+    websocket: Option<WebSocketConfig>,
+) -> Result<RtStream, String> {
+    let jwt = match auth {
+        RtAuth::ApiKey => None,
+        RtAuth::TemporaryKey | RtAuth::Auto => match create_jwt(api_key.to_string()).await {
+            Ok(jwt) => Some(jwt),
+            Err(AuthError::EndpointUnreachable(reason)) if auth == RtAuth::Auto => {
+                warn!(%reason, "Temporary key service unreachable; using the API key directly");
+                None
+            }
+            Err(err) => {
+                error!("create_jwt failed: {}", err);
+                return Err(err.to_string());
+            }
+        },
+    };
+    let request = match &jwt {
+        Some(jwt) => build_rt_ws_url(rt_url, jwt).into_client_request(),
+        None => rt_base(rt_url).into_client_request().map(|mut request| {
+            if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", api_key)) {
+                request.headers_mut().insert(AUTHORIZATION, value);
+            }
+            request
+        }),
+    }
+    .map_err(|e| format!("Invalid realtime URL: {}", e))?;
+
+    let (ws_stream, _) =
+        tokio_tungstenite::connect_async_tls_with_config(request, websocket, false, tls)
+            .await
+            .map_err(|e| {
+                error!("connect_async failed: {}", e);
+                match e {
+                    tokio_tungstenite::tungstenite::Error::Http(response)
+                        if matches!(
+                            response.status(),
+                            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+                        ) =>
+                    {
+                        if jwt.is_some() {
+                            format!(
+                                "Realtime endpoint rejected the temporary key (HTTP {})",
+                                response.status()
+                            )
+                        } else {
+                            format!(
+                        "Invalid Speechmatics API key: the realtime endpoint rejected it (HTTP {})",
+                        response.status()
+                    )
+                        }
+                    }
+                    e => e.to_string(),
+                }
+            })?;
+    Ok(ws_stream)
+}
+
+/// Connects to the realtime API and sends `StartRecognition`.
+pub async fn connect(
+    api_key: &str,
+    rt_url: Option<&str>,
+    auth: RtAuth,
+    tls: Option<Connector>,
+    websocket: Option<WebSocketConfig>,
+    config: &SpeechmaticsConfig,
+) -> Result<(RtWriter, RtReader), String> {
+    let ws_stream = open(api_key, rt_url, auth, tls, websocket).await?;
+    let (mut write, read) = ws_stream.split();
+
+    let config_msg =
+        serde_json::to_string(config).map_err(|e| format!("Failed to encode config: {}", e))?;
+    debug!(config = %config_msg, "Sending Speechmatics StartRecognition");
+    write
+        .send(Message::Text(config_msg))
+        .await
+        .map_err(|e| format!("Failed to send config: {}", e))?;
+    Ok((write, read))
+}
+
+impl SpeechmaticsConfig {
+    /// `StartRecognition` asking for what `config` describes.
+    pub fn for_session(config: &SessionConfig) -> Self {
+        let by_channel = !config.channels.is_empty();
+        let speaker_config = SpeakerDiarizationConfig {
+            // Makes the session end with a `SpeakersResult`.
+            get_speakers: config.capture_speakers.then_some(true),
+            speakers: (!config.known_speakers.is_empty()).then(|| config.known_speakers.clone()),
+            max_speakers: config.max_speakers,
+        };
+        let speaker_config_set = speaker_config.get_speakers.is_some()
+            || speaker_config.speakers.is_some()
+            || speaker_config.max_speakers.is_some();
+        Self {
+            message: "StartRecognition".to_string(),
+            transcription_config: TranscriptionConfig {
+                language: config.language.clone(),
+                domain: config.domain.clone(),
+                enable_partials: config.partials,
+                operating_point: config.operating_point.as_str().to_string(),
+                max_delay: config.max_delay,
+                diarization: if by_channel {
+                    Some("channel".to_string())
+                } else {
+                    config.diarization.then(|| "speaker".to_string())
+                },
+                speaker_diarization_config: (config.diarization
+                    && !by_channel
+                    && speaker_config_set)
+                    .then_some(speaker_config),
+                channel_diarization_labels: by_channel.then(|| config.channels.clone()),
+                additional_vocab: (!config.additional_vocab.is_empty())
+                    .then(|| config.additional_vocab.clone()),
+            },
+            audio_format: AudioFormat {
+                format_type: "raw".to_string(),
+                encoding: "pcm_s16le".to_string(),
+                sample_rate: TARGET_SAMPLE_RATE,
+            },
+        }
+    }
+}
+
+/// Speechmatics realtime, at `rt_url` or [`DEFAULT_RT_URL`].
+pub struct Speechmatics {
+    pub api_key: String,
+    pub rt_url: Option<String>,
+    pub auth: RtAuth,
+    pub tls: Option<Connector>,
+    pub websocket: WebSocketSettings,
+}
+
+impl RealtimeProvider for Speechmatics {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Speechmatics
+    }
+
+    fn connect<'a>(
+        &'a self,
+        config: &'a SessionConfig,
+    ) -> BoxFuture<'a, Result<(RtWriter, RtReader), String>> {
+        Box::pin(async move {
+            connect(
+                &self.api_key,
+                self.rt_url.as_deref(),
+                self.auth,
+                self.tls.clone(),
+                Some(self.websocket.config()),
+                &SpeechmaticsConfig::for_session(config),
+            )
+            .await
+        })
+    }
+
+    fn decoder(&self, config: &SessionConfig) -> Box<dyn EventDecoder> {
+        Box::new(SpeechmaticsDecoder {
+            channels: config.channels.len(),
+            channel_acks: Vec::new(),
+            scratch: String::new(),
+        })
+    }
+
+    fn audio(&self, batch: Vec<u8>, channels: &[String]) -> Vec<Message> {
+        if channels.is_empty() {
+            return vec![Message::Binary(batch)];
+        }
+        let split = audio::deinterleave_pcm16(&batch, channels.len());
+        channels
+            .iter()
+            .zip(split)
+            .filter_map(|(channel, pcm)| {
+                serde_json::to_string(&AddChannelAudioMessage::new(channel, &pcm)).ok()
+            })
+            .map(Message::Text)
+            .collect()
+    }
+
+    fn end_of_stream(&self, channels: &[String], seq_no: u64) -> Vec<Message> {
+        let payloads = if channels.is_empty() {
+            vec![serde_json::to_string(&EndOfStreamMessage {
+                message: "EndOfStream".to_string(),
+                last_seq_no: seq_no,
+            })]
+        } else {
+            channels
+                .iter()
+                .map(|channel| {
+                    serde_json::to_string(&EndOfChannelMessage {
+                        message: "EndOfChannel",
+                        channel,
+                        last_seq_no: seq_no,
+                    })
+                })
+                .collect()
+        };
+        payloads.into_iter().flatten().map(Message::Text).collect()
+    }
+
+    fn acknowledges_audio(&self) -> bool {
+        true
+    }
+}
+
+struct SpeechmaticsDecoder {
+    channels: usize,
+    /// Last audio message each channel acknowledged, in channel-diarized sessions.
+    channel_acks: Vec<(String, u64)>,
+    scratch: String,
+}
+
+impl EventDecoder for SpeechmaticsDecoder {
+    fn decode(&mut self, text: &str, events: &mut Vec<ProviderEvent>) {
+        let parsed = match serde_json::from_str::<SpeechmaticsMessage>(text) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                warn!("Failed to parse Speechmatics message: {}", err);
+                return;
+            }
+        };
+        if let Some(error) = parsed.error {
+            events.push(ProviderEvent::Error(error));
+            return;
+        }
+        match &*parsed.message {
+            "AudioAdded" => events.extend(parsed.seq_no.map(ProviderEvent::AudioAcked)),
+            "ChannelAudioAdded" => {
+                let (Some(seq_no), Some(channel)) = (parsed.seq_no, parsed.channel.as_deref())
+                else {
+                    return;
+                };
+                match self
+                    .channel_acks
+                    .iter_mut()
+                    .find(|(name, _)| name == channel)
+                {
+                    Some((_, acked)) => *acked = seq_no,
+                    None => self.channel_acks.push((channel.to_string(), seq_no)),
+                }
+                // Audio only counts once every channel has it.
+                if self.channel_acks.len() == self.channels {
+                    let acked = self.channel_acks.iter().map(|(_, acked)| *acked);
+                    events.push(ProviderEvent::AudioAcked(acked.min().unwrap_or_default()));
+                }
+            }
+            "RecognitionStarted" => events.push(ProviderEvent::Started),
+            "AddPartialTranscript" => {
+                if let Some(text) = extract_text(&parsed, &mut self.scratch) {
+                    let end = parsed.metadata.as_ref().and_then(|m| m.end_time);
+                    events.push(ProviderEvent::Partial { text, end });
+                }
+            }
+            "AddTranscript" => {
+                events.push(ProviderEvent::Final(parsed.final_result(&mut self.scratch)));
+            }
+            "SpeakersResult" => match serde_json::from_str::<SpeakersResultMessage>(text) {
+                Ok(message) => events.push(ProviderEvent::Speakers(
+                    message.speakers.unwrap_or_default(),
+                )),
+                Err(err) => warn!("Failed to parse SpeakersResult: {}", err),
+            },
+            "EndOfTranscript" => events.push(ProviderEvent::Finished),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_of(json: &str) -> Option<String> {
+        let message: SpeechmaticsMessage = serde_json::from_str(json).unwrap();
+        extract_text(&message, &mut String::new())
+    }
+
+    #[test]
+    fn extract_text_prefers_the_metadata_transcript() {
+        let json = r#"{
+            "message": "AddTranscript",
+            "metadata": { "transcript": " Hello , world . " },
+            "results": [{ "alternatives": [{ "content": "ignored" }] }]
+        }"#;
+        assert_eq!(text_of(json).as_deref(), Some("Hello, world."));
+    }
+
+    #[test]
+    fn extract_text_joins_results_without_metadata() {
+        let json = r#"{
+            "message": "AddPartialTranscript",
+            "results": [
+                { "alternatives": [{ "content": "Hello" }] },
+                { "alternatives": [{ "content": "," }] },
+                { "alternatives": [{ "text": "world" }] },
+                { "alternatives": [{ "content": [{ "type": "word", "content": "again" }, "?"] }] }
+            ]
+        }"#;
+        assert_eq!(text_of(json).as_deref(), Some("Hello, world again?"));
+    }
+
+    #[test]
+    fn extract_text_skips_empty_messages() {
+        let json = r#"{
+            "message": "AddTranscript",
+            "metadata": { "transcript": "  " },
+            "results": [{ "alternatives": [{ "content": " " }] }, { "alternatives": [] }]
+        }"#;
+        assert_eq!(text_of(json), None);
+        assert_eq!(text_of(r#"{ "message": "AddTranscript" }"#), None);
+    }
+}
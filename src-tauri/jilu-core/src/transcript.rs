@@ -0,0 +1,494 @@
+use crate::locale::Locale;
+use crate::provider::Word;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TranscriptTurnPayload {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    pub text: String,
+    /// Seconds from the start of the audio stream, when the provider reported timings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<f64>,
+    /// Seconds since the recording started, including muted stretches the
+    /// provider never heard; this is what citation links point at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+    /// Where the turn ends, on the same clock as `offset`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_offset: Option<f64>,
+    /// Wall-clock time of `offset` and `end_offset` (RFC 3339).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    /// Set on a turn that only marks audio the recording missed, e.g. while
+    /// the app restarted: about how many seconds that was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gap_secs: Option<f64>,
+    /// Set on a note typed during the recording rather than heard. Notes have
+    /// no speaker, and their time is when they were typed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub note: bool,
+    /// Which audio the turn was heard in. Certain when the microphone and
+    /// system audio are transcribed as separate channels, otherwise a guess
+    /// from which of them was louder at the time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<TurnSource>,
+    /// The recognised words, when the provider said how sure it was of them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TurnWord>,
+}
+
+/// Where a turn's speech came from.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TurnSource {
+    /// The local microphone: things you said.
+    Mic,
+    /// System audio: things you heard on the call.
+    System,
+}
+
+/// A word of a turn as the provider heard it, before formatting.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TurnWord {
+    pub text: String,
+    /// From 0 to 1.
+    pub confidence: f64,
+    /// Below the threshold the recording was started with, so worth a look.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub low_confidence: bool,
+}
+
+impl TurnWord {
+    /// `word` with its confidence, marked when below `threshold`; `None`
+    /// when the provider gave no confidence.
+    pub fn from_word(word: &Word, threshold: Option<f64>) -> Option<Self> {
+        let confidence = word.confidence?;
+        Some(Self {
+            text: word.text.clone(),
+            confidence,
+            low_confidence: threshold.is_some_and(|threshold| confidence < threshold),
+        })
+    }
+}
+
+impl TranscriptTurnPayload {
+    fn new(speaker: Option<&str>, text: &str) -> Self {
+        Self {
+            speaker: speaker.map(str::to_string),
+            text: text.to_string(),
+            start: None,
+            end: None,
+            offset: None,
+            end_offset: None,
+            started_at: None,
+            ended_at: None,
+            gap_secs: None,
+            note: false,
+            source: None,
+            words: Vec::new(),
+        }
+    }
+
+    /// Whether the turn is speech, rather than a note or a gap marker.
+    pub fn is_spoken(&self) -> bool {
+        self.gap_secs.is_none() && !self.note
+    }
+
+    /// `[Speaker 1]: ` or `[Note]: `, whatever leads the turn's text.
+    pub fn prefix(&self, locale: Locale) -> Option<String> {
+        if self.note {
+            return Some(locale.note_prefix());
+        }
+        self.speaker
+            .as_deref()
+            .map(|speaker| locale.speaker_prefix(speaker))
+    }
+
+    /// Like `prefix`, without brackets.
+    pub fn label(&self, locale: Locale) -> Option<String> {
+        if self.note {
+            return Some(locale.note_label());
+        }
+        self.speaker
+            .as_deref()
+            .map(|speaker| locale.speaker_label(speaker))
+    }
+}
+
+/// Live transcript for the active session.
+///
+/// Keeps the `[Speaker]: text` rendering up to date as turns are appended, so
+/// each final costs O(new text) instead of re-rendering the whole meeting.
+#[derive(Debug, Default)]
+pub struct Transcript {
+    turns: Vec<TranscriptTurnPayload>,
+    rendered: String,
+    /// Byte offset in `rendered` where each turn's speaker prefix starts.
+    turn_starts: Vec<usize>,
+    locale: Locale,
+    /// `(provider label, name)` for speakers renamed during the session.
+    speaker_names: Vec<(String, String)>,
+}
+
+impl Transcript {
+    /// Resets for a new session, releasing the previous session's allocations
+    /// rather than keeping hours of text capacity around.
+    pub fn clear(&mut self) {
+        *self = Self {
+            locale: self.locale,
+            ..Self::default()
+        };
+    }
+
+    /// Switches the conventions used for speaker prefixes, re-rendering what is there.
+    pub fn set_locale(&mut self, locale: Locale) {
+        if self.locale == locale {
+            return;
+        }
+        self.locale = locale;
+        self.render();
+    }
+
+    /// Starts from turns saved earlier, such as those of a recording that
+    /// was cut short, so new text carries on after them.
+    pub fn restore(&mut self, turns: Vec<TranscriptTurnPayload>) {
+        self.clear();
+        self.turns = turns;
+        self.render();
+    }
+
+    /// Ends the transcript so far with a turn marking missed audio. Text
+    /// that follows starts a new turn, whoever speaks.
+    pub fn mark_gap(&mut self, text: &str, gap_secs: f64) {
+        self.push_turn(TranscriptTurnPayload {
+            gap_secs: Some(gap_secs),
+            ..TranscriptTurnPayload::new(None, text.trim())
+        });
+    }
+
+    /// Ends the transcript so far with a note typed `offset` seconds into
+    /// the recording, returning its index. Speech that follows starts a new
+    /// turn, so notes sit between what was said before and after them.
+    pub fn add_note(&mut self, text: &str, offset: Option<f64>, at: Option<String>) -> usize {
+        self.push_turn(TranscriptTurnPayload {
+            offset,
+            end_offset: offset,
+            started_at: at.clone(),
+            ended_at: at,
+            note: true,
+            ..TranscriptTurnPayload::new(None, text.trim())
+        });
+        self.turns.len() - 1
+    }
+
+    fn render(&mut self) {
+        self.rendered.clear();
+        self.turn_starts.clear();
+        for turn in &self.turns {
+            if !self.rendered.is_empty() {
+                self.rendered.push_str("\n\n");
+            }
+            self.turn_starts.push(self.rendered.len());
+            if let Some(prefix) = turn.prefix(self.locale) {
+                self.rendered.push_str(&prefix);
+            }
+            self.rendered.push_str(&turn.text);
+        }
+    }
+
+    fn push_turn(&mut self, turn: TranscriptTurnPayload) {
+        if !self.turns.is_empty() {
+            self.rendered.push_str("\n\n");
+        }
+        self.turn_starts.push(self.rendered.len());
+        if let Some(prefix) = turn.prefix(self.locale) {
+            self.rendered.push_str(&prefix);
+        }
+        self.rendered.push_str(&turn.text);
+        self.turns.push(turn);
+    }
+
+    /// Calls speaker `from` `to`, in the turns so far and whenever the
+    /// provider labels someone `from` later on. Returns how many turns were
+    /// renamed.
+    pub fn rename_speaker(&mut self, from: &str, to: &str) -> usize {
+        let mut renamed = 0;
+        for turn in &mut self.turns {
+            if turn.speaker.as_deref() == Some(from) {
+                turn.speaker = Some(to.to_string());
+                renamed += 1;
+            }
+        }
+        // Provider labels already renamed to `from` follow it to `to`.
+        let mut known = false;
+        for (label, name) in &mut self.speaker_names {
+            if label == from || name == from {
+                *name = to.to_string();
+            }
+            known |= label == from;
+        }
+        if !known {
+            self.speaker_names.push((from.to_string(), to.to_string()));
+        }
+        self.speaker_names.retain(|(label, name)| label != name);
+        if renamed > 0 {
+            self.render();
+        }
+        renamed
+    }
+
+    /// The name provider label `speaker` was renamed to, if it was.
+    pub fn speaker_name(&self, speaker: &str) -> Option<&str> {
+        self.speaker_names
+            .iter()
+            .find(|(label, _)| label == speaker)
+            .map(|(_, name)| name.as_str())
+    }
+
+    /// Approximate heap footprint, used by the soak test to check growth.
+    pub fn heap_bytes(&self) -> usize {
+        self.rendered.capacity()
+            + self.turn_starts.capacity() * std::mem::size_of::<usize>()
+            + self.turns.capacity() * std::mem::size_of::<TranscriptTurnPayload>()
+            + self
+                .turns
+                .iter()
+                .map(|turn| {
+                    [&turn.speaker, &turn.started_at, &turn.ended_at]
+                        .into_iter()
+                        .flatten()
+                        .map(String::capacity)
+                        .sum::<usize>()
+                        + turn.text.capacity()
+                        + turn.words.capacity() * std::mem::size_of::<TurnWord>()
+                        + turn
+                            .words
+                            .iter()
+                            .map(|word| word.text.capacity())
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn turns(&self) -> &[TranscriptTurnPayload] {
+        &self.turns
+    }
+
+    pub fn text(&self) -> &str {
+        &self.rendered
+    }
+
+    /// Rendered text of the turns from `index` onwards.
+    pub fn text_from(&self, index: usize) -> &str {
+        match self.turn_starts.get(index) {
+            Some(&start) => &self.rendered[start..],
+            None => "",
+        }
+    }
+
+    /// Sets where turn `index` starts in the recording, and when that was,
+    /// unless already known.
+    pub fn mark_offset(&mut self, index: usize, offset: f64, at: Option<String>) {
+        if let Some(turn) = self.turns.get_mut(index) {
+            if turn.offset.is_none() {
+                turn.offset = Some(offset);
+                turn.started_at = at;
+            }
+        }
+    }
+
+    /// Moves the end of turn `index` out to `offset`, as more text joins it.
+    pub fn mark_end(&mut self, index: usize, offset: f64, at: Option<String>) {
+        if let Some(turn) = self.turns.get_mut(index) {
+            if turn.end_offset.is_none_or(|end| offset >= end) {
+                turn.end_offset = Some(offset);
+                turn.ended_at = at;
+            }
+        }
+    }
+
+    /// Records which audio turn `index` was heard in.
+    pub fn set_source(&mut self, index: usize, source: Option<TurnSource>) {
+        if let Some(turn) = self.turns.get_mut(index) {
+            turn.source = source;
+        }
+    }
+
+    /// Adds to the words of turn `index`.
+    pub fn add_words(&mut self, index: usize, words: impl IntoIterator<Item = TurnWord>) {
+        if let Some(turn) = self.turns.get_mut(index) {
+            turn.words.extend(words);
+        }
+    }
+
+    /// Whether the transcript so far ends on a finished sentence.
+    pub fn ends_sentence(&self) -> bool {
+        self.turns
+            .last()
+            .is_none_or(|turn| !turn.is_spoken() || turn.text.trim_end().ends_with(['.', '!', '?']))
+    }
+
+    /// Whether text from `speaker` would start a new sentence: it opens a new
+    /// turn or follows a finished one.
+    pub fn starts_sentence(&self, speaker: Option<&str>) -> bool {
+        let continues_turn = self.turns.last().is_some_and(|turn| {
+            turn.is_spoken() && turn.speaker.as_deref() == normalize_speaker(speaker)
+        });
+        !continues_turn || self.ends_sentence()
+    }
+
+    /// Appends text to the transcript, returning the index of the turn it landed in.
+    pub fn append(&mut self, speaker: Option<&str>, text: &str) -> Option<usize> {
+        self.append_at(speaker, text, None)
+    }
+
+    /// Like `append`, widening the turn's time span to cover `span` (start, end seconds).
+    pub fn append_at(
+        &mut self,
+        speaker: Option<&str>,
+        text: &str,
+        span: Option<(f64, f64)>,
+    ) -> Option<usize> {
+        let speaker = normalize_speaker(speaker);
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        if let Some(last) = self.turns.last_mut() {
+            if last.is_spoken() && last.speaker.as_deref() == speaker {
+                let attaches = trimmed.starts_with(['.', ',', '!', '?', ':', ';']);
+                if !last.text.is_empty() && !last.text.ends_with(char::is_whitespace) && !attaches {
+                    last.text.push(' ');
+                    self.rendered.push(' ');
+                }
+                last.text.push_str(trimmed);
+                self.rendered.push_str(trimmed);
+                if let Some((start, end)) = span {
+                    last.start.get_or_insert(start);
+                    last.end = Some(last.end.map_or(end, |prev| prev.max(end)));
+                }
+                return Some(self.turns.len() - 1);
+            }
+        }
+
+        self.push_turn(TranscriptTurnPayload {
+            start: span.map(|(start, _)| start),
+            end: span.map(|(_, end)| end),
+            ..TranscriptTurnPayload::new(speaker, trimmed)
+        });
+        Some(self.turns.len() - 1)
+    }
+}
+
+fn normalize_speaker(raw: Option<&str>) -> Option<&str> {
+    raw.map(str::trim).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_merges_text_from_the_same_speaker() {
+        let mut transcript = Transcript::default();
+        assert_eq!(transcript.append(Some("S1"), "Hello"), Some(0));
+        assert_eq!(transcript.append(Some(" S1 "), "there"), Some(0));
+        assert_eq!(transcript.append(Some("S1"), "."), Some(0));
+        assert_eq!(transcript.append(Some("S2"), "Hi"), Some(1));
+
+        let turns = transcript.turns();
+        assert_eq!(turns.len(), 2);
+        assert_eq!(turns[0].text, "Hello there.");
+        assert_eq!(turns[1].speaker.as_deref(), Some("S2"));
+        let locale = transcript.locale();
+        assert_eq!(
+            transcript.text(),
+            format!(
+                "{}Hello there.\n\n{}Hi",
+                locale.speaker_prefix("S1"),
+                locale.speaker_prefix("S2")
+            )
+        );
+        assert_eq!(
+            transcript.text_from(1),
+            format!("{}Hi", locale.speaker_prefix("S2"))
+        );
+    }
+
+    #[test]
+    fn append_ignores_empty_text() {
+        let mut transcript = Transcript::default();
+        assert_eq!(transcript.append(Some("S1"), "   "), None);
+        assert!(transcript.turns().is_empty());
+        assert_eq!(transcript.text(), "");
+
+        transcript.append(Some("S1"), "Hello");
+        assert_eq!(transcript.append(Some("S2"), "\n"), None);
+        assert_eq!(transcript.turns().len(), 1);
+        assert_eq!(transcript.turns()[0].text, "Hello");
+    }
+
+    #[test]
+    fn blank_speakers_are_unlabelled() {
+        let mut transcript = Transcript::default();
+        transcript.append(Some("  "), "Hello");
+        transcript.append(None, "again");
+        assert_eq!(transcript.turns().len(), 1);
+        assert_eq!(transcript.turns()[0].speaker, None);
+        assert_eq!(transcript.text(), "Hello again");
+    }
+
+    #[test]
+    fn notes_and_gaps_split_a_speaker_turn() {
+        let mut transcript = Transcript::default();
+        transcript.append(Some("S1"), "Before");
+        transcript.add_note("A note", Some(1.0), None);
+        transcript.append(Some("S1"), "after");
+        transcript.mark_gap("[missed audio]", 5.0);
+        transcript.append(Some("S1"), "later");
+        let texts: Vec<_> = transcript
+            .turns()
+            .iter()
+            .map(|turn| turn.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            ["Before", "A note", "after", "[missed audio]", "later"]
+        );
+    }
+
+    #[test]
+    fn append_at_widens_the_turn_span() {
+        let mut transcript = Transcript::default();
+        transcript.append_at(Some("S1"), "One", Some((1.0, 2.0)));
+        transcript.append_at(Some("S1"), "two", Some((2.5, 3.0)));
+        transcript.append_at(Some("S1"), "late", Some((2.6, 2.8)));
+        let turn = &transcript.turns()[0];
+        assert_eq!(turn.start, Some(1.0));
+        assert_eq!(turn.end, Some(3.0));
+    }
+
+    #[test]
+    fn renamed_speakers_keep_merging() {
+        let mut transcript = Transcript::default();
+        transcript.append(Some("S1"), "Hello");
+        assert_eq!(transcript.rename_speaker("S1", "Ada"), 1);
+        assert_eq!(transcript.speaker_name("S1"), Some("Ada"));
+        transcript.append(Some("Ada"), "again");
+        assert_eq!(transcript.turns().len(), 1);
+        assert_eq!(
+            transcript.text(),
+            format!("{}Hello again", transcript.locale().speaker_prefix("Ada"))
+        );
+    }
+}
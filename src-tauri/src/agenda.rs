@@ -0,0 +1,187 @@
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Share of an item's keywords that must come up before it counts as discussed.
+const COVERAGE_THRESHOLD: f32 = 0.6;
+const MIN_KEYWORD_LEN: usize = 3;
+const MAX_ITEMS: usize = 50;
+const STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "for",
+    "with",
+    "our",
+    "your",
+    "from",
+    "into",
+    "about",
+    "this",
+    "that",
+    "these",
+    "those",
+    "are",
+    "was",
+    "were",
+    "will",
+    "should",
+    "can",
+    "how",
+    "what",
+    "why",
+    "who",
+    "when",
+    "any",
+    "all",
+    "new",
+    "next",
+    "update",
+    "updates",
+    "review",
+    "discuss",
+    "discussion",
+    "item",
+    "items",
+    "agenda",
+    "other",
+    "aob",
+];
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgendaItem {
+    pub id: usize,
+    pub text: String,
+    pub covered: bool,
+    /// Seconds into the recording when the item was first covered.
+    pub covered_at: Option<f64>,
+    /// Set when the user ticked the item off by hand.
+    pub manual: bool,
+    #[serde(skip)]
+    keywords: Vec<String>,
+}
+
+/// The meeting's agenda and which items the transcript has touched on so far.
+#[derive(Debug, Default)]
+pub struct Agenda {
+    items: Vec<AgendaItem>,
+    /// Every keyword-sized word heard in this recording.
+    heard: HashSet<String>,
+}
+
+impl Agenda {
+    /// Builds an agenda from free text such as calendar event notes. Bulleted
+    /// or numbered lines are taken as items; without any, every line is one.
+    pub fn parse(text: &str) -> Self {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let bulleted: Vec<&str> = lines.iter().filter_map(|line| strip_bullet(line)).collect();
+        let entries = if bulleted.is_empty() { lines } else { bulleted };
+
+        let items = entries
+            .into_iter()
+            .filter_map(|entry| {
+                let keywords = keywords(entry);
+                (!keywords.is_empty()).then(|| (entry.to_string(), keywords))
+            })
+            .take(MAX_ITEMS)
+            .enumerate()
+            .map(|(id, (text, keywords))| AgendaItem {
+                id,
+                text,
+                covered: false,
+                covered_at: None,
+                manual: false,
+                keywords,
+            })
+            .collect();
+        Self {
+            items,
+            heard: HashSet::new(),
+        }
+    }
+
+    pub fn items(&self) -> &[AgendaItem] {
+        &self.items
+    }
+
+    /// Forgets what was heard so the agenda can be tracked again in a new recording.
+    pub fn reset_coverage(&mut self) {
+        self.heard.clear();
+        for item in &mut self.items {
+            item.covered = false;
+            item.covered_at = None;
+            item.manual = false;
+        }
+    }
+
+    /// Feeds final transcript text, returning the items it newly covers.
+    pub fn observe(&mut self, text: &str, at: Option<f64>) -> Vec<AgendaItem> {
+        if self.items.is_empty() {
+            return Vec::new();
+        }
+        self.heard.extend(words(text));
+        let heard = &self.heard;
+        let mut covered = Vec::new();
+        for item in self.items.iter_mut().filter(|item| !item.covered) {
+            let hits = item
+                .keywords
+                .iter()
+                .filter(|keyword| heard.contains(*keyword))
+                .count();
+            if hits as f32 >= item.keywords.len() as f32 * COVERAGE_THRESHOLD {
+                item.covered = true;
+                item.covered_at = at;
+                covered.push(item.clone());
+            }
+        }
+        covered
+    }
+
+    pub fn set_covered(&mut self, id: usize, covered: bool) -> Result<AgendaItem, String> {
+        let item = self
+            .items
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| format!("No agenda item {}", id))?;
+        item.covered = covered;
+        item.manual = covered;
+        if !covered {
+            item.covered_at = None;
+        }
+        Ok(item.clone())
+    }
+}
+
+fn strip_bullet(line: &str) -> Option<&str> {
+    if let Some(rest) = line
+        .strip_prefix(['-', '*', '•', '–'])
+        .or_else(|| line.strip_prefix("[ ]"))
+    {
+        return Some(rest.trim());
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        if let Some(rest) = line[digits..].strip_prefix(['.', ')']) {
+            return Some(rest.trim());
+        }
+    }
+    None
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_KEYWORD_LEN)
+        .map(str::to_lowercase)
+}
+
+fn keywords(text: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = words(text)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect();
+    keywords.sort();
+    keywords.dedup();
+    keywords
+}
@@ -0,0 +1,248 @@
+use crate::formatting::{FILLER_PHRASES, FILLER_WORDS};
+use crate::transcript::TranscriptTurnPayload;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const ANALYTICS_FILE: &str = "analytics.json";
+/// Gaps shorter than this are just the space between words.
+const PAUSE_MIN_SECS: f64 = 0.3;
+/// Past this the speaker has stopped rather than paused, e.g. someone else talked.
+const PAUSE_MAX_SECS: f64 = 5.0;
+
+/// Speaking habits of one speaker over a meeting.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerAnalytics {
+    pub speaker: Option<String>,
+    pub words: u64,
+    /// Time spent talking, counting short pauses but not the gaps between turns.
+    pub speaking_secs: f64,
+    pub words_per_minute: Option<f64>,
+    pub filler_count: u64,
+    /// Count per filler, e.g. `{"um": 4, "you know": 2}`.
+    pub fillers: BTreeMap<String, u64>,
+    pub pause_count: u64,
+    pub mean_pause_secs: Option<f64>,
+    pub longest_pause_secs: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingAnalytics {
+    pub speakers: Vec<SpeakerAnalytics>,
+    /// False when worked out from saved turns alone, which only know when each
+    /// turn started and ended; pauses are then not available.
+    pub word_timings: bool,
+}
+
+#[derive(Debug, Default)]
+struct SpeakerState {
+    stats: SpeakerAnalytics,
+    last_end: Option<f64>,
+    total_pause_secs: f64,
+    previous_word: Option<String>,
+    /// Whether the previous word opened a clause, so a filler phrase may start with it.
+    previous_opened_clause: bool,
+    /// Whether the next word opens a clause.
+    clause_start: bool,
+}
+
+/// Accumulates per-speaker metrics from final word timings during a recording.
+#[derive(Debug, Default)]
+pub struct SpeakingTracker {
+    speakers: Vec<SpeakerState>,
+}
+
+impl SpeakingTracker {
+    pub fn reset(&mut self) {
+        self.speakers.clear();
+    }
+
+    /// Counts what `from` said as said by `to`, adding it to `to`'s numbers
+    /// when both have spoken.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        let position = |speakers: &[SpeakerState], label: &str| {
+            speakers
+                .iter()
+                .position(|state| state.stats.speaker.as_deref() == Some(label))
+        };
+        let Some(index) = position(&self.speakers, from) else {
+            return;
+        };
+        if position(&self.speakers, to).is_none() {
+            self.speakers[index].stats.speaker = Some(to.to_string());
+            return;
+        }
+        let moved = self.speakers.remove(index);
+        let Some(target) = position(&self.speakers, to) else {
+            return;
+        };
+        let state = &mut self.speakers[target];
+        state.total_pause_secs += moved.total_pause_secs;
+        state.last_end = state
+            .last_end
+            .into_iter()
+            .chain(moved.last_end)
+            .reduce(f64::max);
+        let stats = &mut state.stats;
+        stats.words += moved.stats.words;
+        stats.speaking_secs += moved.stats.speaking_secs;
+        stats.filler_count += moved.stats.filler_count;
+        stats.pause_count += moved.stats.pause_count;
+        stats.longest_pause_secs = stats
+            .longest_pause_secs
+            .into_iter()
+            .chain(moved.stats.longest_pause_secs)
+            .reduce(f64::max);
+        for (filler, count) in moved.stats.fillers {
+            *stats.fillers.entry(filler).or_default() += count;
+        }
+    }
+
+    fn speaker(&mut self, speaker: Option<&str>) -> &mut SpeakerState {
+        let speaker = speaker.map(str::trim).filter(|s| !s.is_empty());
+        let index = match self
+            .speakers
+            .iter()
+            .position(|state| state.stats.speaker.as_deref() == speaker)
+        {
+            Some(index) => index,
+            None => {
+                self.speakers.push(SpeakerState {
+                    stats: SpeakerAnalytics {
+                        speaker: speaker.map(str::to_string),
+                        ..SpeakerAnalytics::default()
+                    },
+                    clause_start: true,
+                    ..SpeakerState::default()
+                });
+                self.speakers.len() - 1
+            }
+        };
+        &mut self.speakers[index]
+    }
+
+    /// Feeds one final result: a word or a punctuation mark with its timing.
+    pub fn observe(&mut self, speaker: Option<&str>, token: &str, start: f64, end: f64) {
+        let state = self.speaker(speaker);
+        let word: String = token
+            .trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+            .to_lowercase();
+        if word.is_empty() {
+            state.clause_start = true;
+            state.previous_word = None;
+            return;
+        }
+
+        let stats = &mut state.stats;
+        stats.words += 1;
+        match state.last_end {
+            Some(last_end) if start - last_end <= PAUSE_MAX_SECS => {
+                let gap = (start - last_end).max(0.0);
+                if gap >= PAUSE_MIN_SECS {
+                    stats.pause_count += 1;
+                    state.total_pause_secs += gap;
+                    stats.longest_pause_secs = Some(
+                        stats
+                            .longest_pause_secs
+                            .map_or(gap, |longest| longest.max(gap)),
+                    );
+                }
+                stats.speaking_secs += (end - last_end).max(0.0);
+            }
+            _ => stats.speaking_secs += (end - start).max(0.0),
+        }
+        state.last_end = Some(end.max(state.last_end.unwrap_or(end)));
+
+        let phrase = state.previous_word.as_deref().and_then(|previous| {
+            FILLER_PHRASES
+                .iter()
+                .find(|phrase| phrase.len() == 2 && phrase[0] == previous && phrase[1] == word)
+        });
+        if let Some(phrase) = phrase.filter(|_| state.previous_opened_clause) {
+            *stats.fillers.entry(phrase.join(" ")).or_default() += 1;
+            stats.filler_count += 1;
+        } else if FILLER_WORDS.contains(&word.as_str()) {
+            *stats.fillers.entry(word.clone()).or_default() += 1;
+            stats.filler_count += 1;
+        }
+
+        state.previous_opened_clause = state.clause_start;
+        state.clause_start = token.ends_with([',', '.', '!', '?', ';', ':']);
+        state.previous_word = Some(word);
+    }
+
+    pub fn report(&self) -> MeetingAnalytics {
+        MeetingAnalytics {
+            speakers: self
+                .speakers
+                .iter()
+                .map(|state| {
+                    let mut stats = state.stats.clone();
+                    stats.mean_pause_secs = (stats.pause_count > 0)
+                        .then(|| state.total_pause_secs / stats.pause_count as f64);
+                    finish(stats)
+                })
+                .collect(),
+            word_timings: true,
+        }
+    }
+}
+
+/// Approximate metrics for a meeting recorded without word timings.
+pub fn from_turns(turns: &[TranscriptTurnPayload]) -> MeetingAnalytics {
+    let mut speakers: Vec<SpeakerAnalytics> = Vec::new();
+    for turn in turns.iter().filter(|turn| turn.is_spoken()) {
+        let index = match speakers
+            .iter()
+            .position(|stats| stats.speaker == turn.speaker)
+        {
+            Some(index) => index,
+            None => {
+                speakers.push(SpeakerAnalytics {
+                    speaker: turn.speaker.clone(),
+                    ..SpeakerAnalytics::default()
+                });
+                speakers.len() - 1
+            }
+        };
+        let stats = &mut speakers[index];
+        let words: Vec<String> = turn
+            .text
+            .split_whitespace()
+            .map(|word| {
+                word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                    .to_lowercase()
+            })
+            .filter(|word| !word.is_empty())
+            .collect();
+        stats.words += words.len() as u64;
+        if let (Some(start), Some(end)) = (turn.start, turn.end) {
+            stats.speaking_secs += (end - start).max(0.0);
+        }
+        for (index, word) in words.iter().enumerate() {
+            if FILLER_WORDS.contains(&word.as_str()) {
+                *stats.fillers.entry(word.clone()).or_default() += 1;
+                stats.filler_count += 1;
+            } else if let Some(phrase) = FILLER_PHRASES.iter().find(|phrase| {
+                index >= 1
+                    && phrase.len() == 2
+                    && phrase[0] == words[index - 1]
+                    && phrase[1] == word
+            }) {
+                *stats.fillers.entry(phrase.join(" ")).or_default() += 1;
+                stats.filler_count += 1;
+            }
+        }
+    }
+    MeetingAnalytics {
+        speakers: speakers.into_iter().map(finish).collect(),
+        word_timings: false,
+    }
+}
+
+fn finish(mut stats: SpeakerAnalytics) -> SpeakerAnalytics {
+    stats.words_per_minute =
+        (stats.speaking_secs > 0.0).then(|| stats.words as f64 * 60.0 / stats.speaking_secs);
+    stats
+}
@@ -0,0 +1,55 @@
+use crate::audio::TARGET_SAMPLE_RATE;
+use hound::{SampleFormat, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// Tees the PCM sent to the transcription provider into a WAV file so a bad
+/// session can be replayed with identical audio. Audio held back while
+/// transcription is muted is written too. Channel-diarized sessions are
+/// dumped in stereo, as sent.
+pub struct AudioDump {
+    path: PathBuf,
+    writer: WavWriter<BufWriter<File>>,
+}
+
+impl AudioDump {
+    pub fn create(path: &Path, channels: u16) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create audio dump directory: {}", e))?;
+        }
+        let spec = WavSpec {
+            channels,
+            sample_rate: TARGET_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let writer = WavWriter::create(path, spec)
+            .map_err(|e| format!("Failed to create audio dump: {}", e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a pcm_s16le buffer exactly as it goes over the wire.
+    pub fn write_pcm(&mut self, pcm: &[u8]) -> Result<(), String> {
+        for sample in pcm.chunks_exact(2) {
+            self.writer
+                .write_sample(i16::from_le_bytes([sample[0], sample[1]]))
+                .map_err(|e| format!("Failed to write audio dump: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        self.writer
+            .finalize()
+            .map_err(|e| format!("Failed to finalize audio dump: {}", e))
+    }
+}
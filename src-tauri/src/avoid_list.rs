@@ -0,0 +1,119 @@
+use crate::calendar::CalendarAttendee;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+pub const MAX_AVOID_RULES: usize = 100;
+/// Keeps a pathological title pattern from costing more than a few
+/// milliseconds per event.
+const PATTERN_SIZE_LIMIT: usize = 1 << 16;
+
+/// Meetings that are never recorded. Every condition a rule sets has to
+/// match, so adding conditions narrows a rule; each rule blocks on its own.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AvoidRule {
+    /// Calendar id or name, e.g. `Personal`.
+    pub calendar: Option<String>,
+    /// Regular expression for the event title, e.g. `1:1 with HR`; case is ignored.
+    pub title_pattern: Option<String>,
+    /// Email or name of someone invited; case is ignored.
+    pub attendee: Option<String>,
+}
+
+/// The parts of a calendar event the avoid-list looks at. Takes both the
+/// frontend's cached events and the ones `fetch_calendar_events` returns.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventDetails {
+    pub title: String,
+    #[serde(alias = "calendar_id")]
+    pub calendar_id: String,
+    #[serde(alias = "calendar_name")]
+    pub calendar_name: String,
+    pub attendees: Vec<CalendarAttendee>,
+}
+
+fn set(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn title_regex(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid title pattern '{}': {}", pattern, e))
+}
+
+impl AvoidRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if set(&self.calendar).is_none()
+            && set(&self.title_pattern).is_none()
+            && set(&self.attendee).is_none()
+        {
+            return Err("Avoid-list rules need a calendar, title pattern or attendee".to_string());
+        }
+        if let Some(pattern) = set(&self.title_pattern) {
+            title_regex(pattern)?;
+        }
+        Ok(())
+    }
+
+    fn matches(&self, event: &EventDetails) -> bool {
+        if let Some(calendar) = set(&self.calendar) {
+            if calendar != event.calendar_id && !calendar.eq_ignore_ascii_case(&event.calendar_name)
+            {
+                return false;
+            }
+        }
+        if let Some(pattern) = set(&self.title_pattern) {
+            // Settings validation rejects bad patterns, so this only skips
+            // rules written to the file by hand.
+            if !title_regex(pattern).is_ok_and(|regex| regex.is_match(&event.title)) {
+                return false;
+            }
+        }
+        if let Some(attendee) = set(&self.attendee) {
+            let invited = event.attendees.iter().any(|person| {
+                [&person.email, &person.name]
+                    .into_iter()
+                    .flatten()
+                    .any(|value| value.trim().eq_ignore_ascii_case(attendee))
+            });
+            if !invited {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Names the conditions, for telling the user why a recording was refused.
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(calendar) = set(&self.calendar) {
+            parts.push(format!("calendar {}", calendar));
+        }
+        if let Some(pattern) = set(&self.title_pattern) {
+            parts.push(format!("title matching '{}'", pattern));
+        }
+        if let Some(attendee) = set(&self.attendee) {
+            parts.push(format!("with {}", attendee));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Refuses `event` when one of `rules` covers it.
+pub fn check(rules: &[AvoidRule], event: &EventDetails) -> Result<(), String> {
+    match rules.iter().find(|rule| rule.matches(event)) {
+        Some(rule) => Err(format!(
+            "'{}' is on the do-not-record list ({})",
+            event.title,
+            rule.describe()
+        )),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,105 @@
+//! Speechmatics batch jobs, for audio that was recorded without Jilu.
+
+use crate::settings::TranscriptionSettings;
+use jilu_core::http::{self, RetryPolicy};
+use std::path::Path;
+use std::time::Duration;
+
+const BATCH_URL: &str = "https://asr.api.speechmatics.com/v2";
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Long recordings take a while to upload.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Uploads `file` for transcription with the language, vocabulary and
+/// operating point in `settings`, returning the job's id.
+pub async fn submit(
+    api_key: &str,
+    file: &Path,
+    settings: &TranscriptionSettings,
+) -> Result<String, String> {
+    let bytes = tokio::fs::read(file)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+    let file_name = file
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "audio".to_string());
+
+    let mut transcription_config = serde_json::json!({
+        "language": settings.language,
+        "operating_point": settings.operating_point.as_str(),
+        "diarization": "speaker",
+    });
+    if !settings.additional_vocab.is_empty() {
+        transcription_config["additional_vocab"] = serde_json::json!(settings.additional_vocab);
+    }
+    if let Some(domain) = &settings.domain {
+        transcription_config["domain"] = serde_json::json!(domain);
+    }
+    let config = serde_json::json!({
+        "type": "transcription",
+        "transcription_config": transcription_config,
+    });
+    let form = reqwest::multipart::Form::new()
+        .text("config", config.to_string())
+        .part(
+            "data_file",
+            reqwest::multipart::Part::bytes(bytes).file_name(file_name),
+        );
+
+    let request = http::client()
+        .post(format!("{}/jobs", BATCH_URL))
+        .bearer_auth(api_key)
+        .multipart(form)
+        .timeout(UPLOAD_TIMEOUT);
+    // The upload is streamed, so it is only ever sent once.
+    let created: serde_json::Value = http::send(request, RetryPolicy::ONCE)
+        .await
+        .map_err(|e| format!("Failed to submit job: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to submit job: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid job response: {}", e))?;
+    created["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Job response had no id".to_string())
+}
+
+/// Waits for job `job_id` to finish, then fetches its transcript in
+/// `format`: `txt`, `srt` or `json-v2`.
+pub async fn transcript(api_key: &str, job_id: &str, format: &str) -> Result<String, String> {
+    let client = http::client();
+    loop {
+        let request = client
+            .get(format!("{}/jobs/{}", BATCH_URL, job_id))
+            .bearer_auth(api_key);
+        let status: serde_json::Value = http::send(request, RetryPolicy::STANDARD)
+            .await
+            .map_err(|e| format!("Failed to check job: {}", e))?
+            .error_for_status()
+            .map_err(|e| format!("Failed to check job: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid job status: {}", e))?;
+        match status["job"]["status"].as_str() {
+            Some("done") => break,
+            Some("running") | None => tokio::time::sleep(POLL_INTERVAL).await,
+            Some(other) => return Err(format!("Job {} ended with status '{}'", job_id, other)),
+        }
+    }
+
+    let request = client
+        .get(format!("{}/jobs/{}/transcript", BATCH_URL, job_id))
+        .query(&[("format", format)])
+        .bearer_auth(api_key);
+    http::send(request, RetryPolicy::STANDARD)
+        .await
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to fetch transcript: {}", e))
+}
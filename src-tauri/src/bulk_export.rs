@@ -0,0 +1,333 @@
+use crate::formatting::{self, FormattingSettings};
+use crate::locale::Locale;
+use crate::normalize::Normalizer;
+use crate::transcript::TranscriptTurnPayload;
+use crate::transcript_export::{self, ExportFormat};
+use crate::{analytics, chapters, costs, interview, levels, timeline};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+const NOTES_FILE: &str = "notes.md";
+/// Long titles are cut so folder names stay manageable.
+const MAX_SLUG_CHARS: usize = 60;
+/// Copied into each meeting's folder as they are, when the meeting has them.
+const RECORD_FILES: &[&str] = &[
+    timeline::TIMELINE_FILE,
+    analytics::ANALYTICS_FILE,
+    chapters::CHAPTERS_FILE,
+    levels::LEVELS_FILE,
+    interview::INTERVIEW_FILE,
+    costs::COST_FILE,
+];
+
+/// Inclusive `YYYY-MM-DD` bounds on the meeting date; either may be open.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+/// What the frontend knows about a meeting that the backend store does not.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingInfo {
+    pub id: String,
+    pub title: Option<String>,
+    /// Start of the meeting (RFC 3339).
+    pub date: Option<String>,
+    /// Personal and AI notes, as Markdown.
+    pub notes: Option<String>,
+}
+
+/// Payload of `bulk-export-progress`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportProgress {
+    pub done: usize,
+    pub total: usize,
+    pub meeting_id: String,
+}
+
+/// Index of an export, written to its root as `manifest.json`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub exported_at: String,
+    /// Folder the export was written to.
+    pub root: String,
+    pub format: String,
+    pub range: ExportRange,
+    /// True when the export was stopped part way; `meetings` lists what was done.
+    pub cancelled: bool,
+    pub meetings: Vec<ManifestEntry>,
+    pub skipped: Vec<SkippedMeeting>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    pub id: String,
+    pub title: Option<String>,
+    /// `YYYY-MM-DD`, when known.
+    pub date: Option<String>,
+    /// Relative to the export root.
+    pub folder: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedMeeting {
+    pub id: String,
+    pub reason: String,
+}
+
+/// One export run over a profile's meeting store.
+pub struct BulkExport {
+    pub meetings_dir: PathBuf,
+    pub destination: PathBuf,
+    pub format: ExportFormat,
+    pub range: ExportRange,
+    pub locale: Locale,
+    pub formatting: FormattingSettings,
+    /// Runs after `formatting`, when normalization is on.
+    pub normalizer: Option<Normalizer>,
+    pub infos: Vec<MeetingInfo>,
+}
+
+impl BulkExport {
+    /// Writes every meeting in range to a new folder under `destination`,
+    /// one sub-folder per meeting, and returns the manifest. Stops between
+    /// meetings once `cancel` is set.
+    pub fn run(
+        self,
+        cancel: &AtomicBool,
+        mut on_progress: impl FnMut(ExportProgress),
+    ) -> Result<Manifest, String> {
+        let from = parse_bound(self.range.from.as_deref())?;
+        let to = parse_bound(self.range.to.as_deref())?;
+        if !self.destination.is_absolute() {
+            return Err("Export destination must be an absolute path".to_string());
+        }
+        let root = self.destination.join(format!(
+            "jilu-export-{}",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+
+        let infos: HashMap<&str, &MeetingInfo> = self
+            .infos
+            .iter()
+            .map(|info| (info.id.as_str(), info))
+            .collect();
+        let mut meetings: Vec<(String, Option<NaiveDate>)> = list_meetings(&self.meetings_dir)?
+            .into_iter()
+            .map(|id| {
+                let date = infos
+                    .get(id.as_str())
+                    .and_then(|info| info.date.as_deref())
+                    .and_then(local_date)
+                    .or_else(|| recorded_on(&self.meetings_dir.join(&id)));
+                (id, date)
+            })
+            .filter(|(_, date)| match (from, to, date) {
+                (None, None, _) => true,
+                // Undated meetings cannot be placed in a range.
+                (_, _, None) => false,
+                (from, to, Some(date)) => {
+                    from.is_none_or(|from| *date >= from) && to.is_none_or(|to| *date <= to)
+                }
+            })
+            .collect();
+        meetings.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut manifest = Manifest {
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            root: root.to_string_lossy().into_owned(),
+            format: self.format.extension().to_string(),
+            range: self.range.clone(),
+            cancelled: false,
+            meetings: Vec::new(),
+            skipped: Vec::new(),
+        };
+        let mut folders = HashSet::new();
+        let total = meetings.len();
+        for (done, (id, date)) in meetings.into_iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                manifest.cancelled = true;
+                break;
+            }
+            let info = infos.get(id.as_str()).copied();
+            match self.export_meeting(&root, &id, date, info, &mut folders) {
+                Ok(Some(entry)) => manifest.meetings.push(entry),
+                Ok(None) => manifest.skipped.push(SkippedMeeting {
+                    id: id.clone(),
+                    reason: "Nothing saved for this meeting".to_string(),
+                }),
+                Err(reason) => manifest.skipped.push(SkippedMeeting {
+                    id: id.clone(),
+                    reason,
+                }),
+            }
+            on_progress(ExportProgress {
+                done: done + 1,
+                total,
+                meeting_id: id,
+            });
+        }
+
+        let contents = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        std::fs::write(root.join(MANIFEST_FILE), contents)
+            .map_err(|e| format!("Failed to write export manifest: {}", e))?;
+        Ok(manifest)
+    }
+
+    /// `None` when the meeting has neither a transcript nor notes.
+    fn export_meeting(
+        &self,
+        root: &Path,
+        id: &str,
+        date: Option<NaiveDate>,
+        info: Option<&MeetingInfo>,
+        folders: &mut HashSet<String>,
+    ) -> Result<Option<ManifestEntry>, String> {
+        let dir = self.meetings_dir.join(id);
+        let title = info
+            .and_then(|info| info.title.as_deref())
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        let notes = info
+            .and_then(|info| info.notes.as_deref())
+            .filter(|notes| !notes.trim().is_empty());
+        let transcript = match std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE))
+        {
+            Ok(contents) => Some(
+                serde_json::from_str::<Vec<TranscriptTurnPayload>>(&contents)
+                    .map_err(|e| format!("Unreadable transcript: {}", e))?,
+            ),
+            Err(_) => None,
+        };
+        if transcript.is_none() && notes.is_none() {
+            return Ok(None);
+        }
+
+        let folder = folder_name(id, date, title, folders);
+        let target = root.join(&folder);
+        std::fs::create_dir_all(&target)
+            .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+        let mut files = Vec::new();
+        let mut write = |name: String, contents: &[u8]| -> Result<(), String> {
+            std::fs::write(target.join(&name), contents)
+                .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+            files.push(name);
+            Ok(())
+        };
+
+        if let Some(mut turns) = transcript {
+            formatting::apply_to_turns(&mut turns, &self.formatting);
+            if let Some(normalizer) = &self.normalizer {
+                normalizer.apply_to_turns(&mut turns);
+            }
+            let chapters = crate::storage::saved_chapters(&dir)?;
+            let rendered = transcript_export::render(&turns, &chapters, self.format, self.locale)?;
+            write(
+                format!("transcript.{}", self.format.extension()),
+                rendered.as_bytes(),
+            )?;
+        }
+        if let Some(notes) = notes {
+            write(NOTES_FILE.to_string(), notes.as_bytes())?;
+        }
+        for name in RECORD_FILES {
+            if let Ok(contents) = std::fs::read(dir.join(name)) {
+                write(name.to_string(), &contents)?;
+            }
+        }
+
+        Ok(Some(ManifestEntry {
+            id: id.to_string(),
+            title: title.map(str::to_string),
+            date: date.map(|date| date.format("%Y-%m-%d").to_string()),
+            folder,
+            files,
+        }))
+    }
+}
+
+fn parse_bound(value: Option<&str>) -> Result<Option<NaiveDate>, String> {
+    value
+        .map(|value| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("Invalid date '{}'; expected YYYY-MM-DD", value))
+        })
+        .transpose()
+}
+
+fn list_meetings(meetings_dir: &Path) -> Result<Vec<String>, String> {
+    let entries = match std::fs::read_dir(meetings_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("Failed to read meeting store: {}", err)),
+    };
+    Ok(entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|id| crate::meeting_store::validate_meeting_id(id).is_ok())
+        .collect())
+}
+
+pub fn local_date(rfc3339: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .ok()
+        .map(|at| at.with_timezone(&Local).date_naive())
+}
+
+/// When the meeting was recorded, from the first event of its timeline.
+fn recorded_on(dir: &Path) -> Option<NaiveDate> {
+    let contents = std::fs::read_to_string(dir.join(timeline::TIMELINE_FILE)).ok()?;
+    let first: timeline::TimelineEvent = serde_json::from_str(contents.lines().next()?).ok()?;
+    local_date(&first.at)
+}
+
+/// `2024-05-02-weekly-sync`, made unique within the export.
+pub fn folder_name(
+    id: &str,
+    date: Option<NaiveDate>,
+    title: Option<&str>,
+    used: &mut HashSet<String>,
+) -> String {
+    let slug = title
+        .map(|title| {
+            title
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("-")
+                .to_lowercase()
+                .chars()
+                .take(MAX_SLUG_CHARS)
+                .collect::<String>()
+                .trim_end_matches('-')
+                .to_string()
+        })
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| id.to_string());
+    let base = match date {
+        Some(date) => format!("{}-{}", date.format("%Y-%m-%d"), slug),
+        None => slug,
+    };
+    let name = if used.contains(&base) {
+        format!("{}-{}", base, id)
+    } else {
+        base
+    };
+    used.insert(name.clone());
+    name
+}
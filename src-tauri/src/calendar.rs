@@ -22,6 +22,13 @@ pub struct CalendarEvent {
     pub title: String,
     pub start_time: String, // ISO 8601 timestamp
     pub end_time: String,   // ISO 8601 timestamp
+    /// IANA zone the event was scheduled in, e.g. `Europe/London`. `None` for
+    /// floating events, which happen at the same wall-clock time in any zone.
+    pub time_zone: Option<String>,
+    /// Start and end as wall-clock times in the event's zone (the Mac's own
+    /// for floating events), with the UTC offset in force on that date.
+    pub start_local: String,
+    pub end_local: String,
     pub attendees: Vec<CalendarAttendee>,
     pub notes: String,
     pub location: String,
@@ -41,6 +48,7 @@ mod macos_impl {
     use std::ffi::CStr;
     use std::os::raw::c_char;
     use std::sync::mpsc::channel;
+    use std::sync::OnceLock;
 
     const EK_ENTITY_TYPE_EVENT: usize = 0;
     const EK_AUTH_STATUS_NOT_DETERMINED: i64 = 0;
@@ -104,7 +112,7 @@ mod macos_impl {
 
         unsafe {
             let pool: id = msg_send![class!(NSAutoreleasePool), new];
-            let event_store = create_event_store()?;
+            let event_store = shared_event_store()?;
             let calendars_array: id =
                 msg_send![event_store, calendarsForEntityType:EK_ENTITY_TYPE_EVENT];
             let count: usize = msg_send![calendars_array, count];
@@ -154,7 +162,7 @@ mod macos_impl {
 
         unsafe {
             let pool: id = msg_send![class!(NSAutoreleasePool), new];
-            let event_store = create_event_store()?;
+            let event_store = shared_event_store()?;
             let (calendar_array, matched) = build_calendar_array(event_store, &calendar_ids);
 
             if matched == 0 {
@@ -222,6 +230,20 @@ mod macos_impl {
 
                 let start_iso = nsdate_to_iso_string(start_ns)?;
                 let end_iso = nsdate_to_iso_string(end_ns)?;
+                let event_zone: id = msg_send![event, timeZone];
+                let time_zone = if event_zone == nil {
+                    None
+                } else {
+                    Some(nsstring_to_string(msg_send![event_zone, name]))
+                        .filter(|name| !name.is_empty())
+                };
+                let render_zone: id = if event_zone == nil {
+                    msg_send![class!(NSTimeZone), localTimeZone]
+                } else {
+                    event_zone
+                };
+                let start_local = nsdate_to_zoned_string(start_ns, render_zone)?;
+                let end_local = nsdate_to_zoned_string(end_ns, render_zone)?;
                 let series_id = if event_id.is_empty() {
                     if calendar_identifier.is_empty() {
                         format!("event-{}", start_iso)
@@ -239,6 +261,9 @@ mod macos_impl {
                     title,
                     start_time: start_iso,
                     end_time: end_iso,
+                    time_zone,
+                    start_local,
+                    end_local,
                     attendees,
                     notes,
                     location,
@@ -265,6 +290,17 @@ mod macos_impl {
         )
     }
 
+    /// EKEventStore loads the whole calendar database on init, so reads share one
+    /// store that is created the first time calendars are queried.
+    unsafe fn shared_event_store() -> Result<id, String> {
+        static EVENT_STORE: OnceLock<usize> = OnceLock::new();
+        if let Some(&store) = EVENT_STORE.get() {
+            return Ok(store as id);
+        }
+        let store = create_event_store()?;
+        Ok(*EVENT_STORE.get_or_init(|| store as usize) as id)
+    }
+
     unsafe fn create_event_store() -> Result<id, String> {
         let event_store: id = msg_send![class!(EKEventStore), alloc];
         let event_store: id = msg_send![event_store, init];
@@ -320,6 +356,25 @@ mod macos_impl {
     }
 
     fn nsdate_to_iso_string(date: id) -> Result<String, String> {
+        Ok(nsdate_to_datetime(date)?.to_rfc3339())
+    }
+
+    /// Renders `date` in `zone`. The offset is looked up for that instant, so
+    /// events on either side of a DST change keep their wall-clock time.
+    fn nsdate_to_zoned_string(date: id, zone: id) -> Result<String, String> {
+        let datetime = nsdate_to_datetime(date)?;
+        if zone == nil {
+            return Ok(datetime.to_rfc3339());
+        }
+        let seconds: isize = unsafe { msg_send![zone, secondsFromGMTForDate: date] };
+        let offset = i32::try_from(seconds)
+            .ok()
+            .and_then(FixedOffset::east_opt)
+            .ok_or_else(|| format!("Invalid UTC offset {}s from Calendar", seconds))?;
+        Ok(datetime.with_timezone(&offset).to_rfc3339())
+    }
+
+    fn nsdate_to_datetime(date: id) -> Result<DateTime<Utc>, String> {
         if date == nil {
             return Err("Missing date value from Calendar event".to_string());
         }
@@ -336,9 +391,8 @@ mod macos_impl {
             }
 
             let nanos = fractional as u32;
-            let datetime = DateTime::<Utc>::from_timestamp(whole, nanos)
-                .ok_or_else(|| "Failed to convert NSDate to timestamp".to_string())?;
-            Ok(datetime.to_rfc3339())
+            DateTime::<Utc>::from_timestamp(whole, nanos)
+                .ok_or_else(|| "Failed to convert NSDate to timestamp".to_string())
         }
     }
 
@@ -0,0 +1,244 @@
+use crate::locale::Locale;
+use crate::settings::CaptionOutputSettings;
+use crate::share::{read_request, request_path, respond};
+use crate::transcript::TranscriptTurnPayload;
+use serde::Serialize;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::watch;
+
+/// Room screens fit about two lines; older words scroll off the front.
+const MAX_CAPTION_CHARS: usize = 160;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const PAGE: &str = include_str!("caption_page.html");
+
+/// What is on screen right now.
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Caption {
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+/// Live captions for AV systems: sent as OSC messages over UDP and served
+/// over HTTP for OBS browser sources, as configured. Everything stops when
+/// this is dropped.
+pub struct CaptionOutput {
+    feed: CaptionFeed,
+    stop_tx: watch::Sender<bool>,
+}
+
+/// Where the result reader puts caption text.
+#[derive(Clone)]
+pub struct CaptionFeed {
+    tx: watch::Sender<Caption>,
+    /// The current turn as finalized so far; partials are shown after it.
+    current: std::sync::Arc<parking_lot::Mutex<Caption>>,
+}
+
+impl CaptionOutput {
+    /// `None` when no output is configured.
+    pub async fn start(settings: &CaptionOutputSettings) -> Result<Option<Self>, String> {
+        if settings.osc_target.is_none() && settings.http_port.is_none() {
+            return Ok(None);
+        }
+        let (tx, rx) = watch::channel(Caption::default());
+        let (stop_tx, stop_rx) = watch::channel(false);
+
+        if let Some(target) = &settings.osc_target {
+            let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+                .await
+                .map_err(|e| format!("Failed to open OSC socket: {}", e))?;
+            socket
+                .connect(target)
+                .await
+                .map_err(|e| format!("Failed to reach OSC target {}: {}", target, e))?;
+            tokio::spawn(send_osc(
+                socket,
+                settings.osc_address.clone(),
+                rx.clone(),
+                stop_rx.clone(),
+            ));
+        }
+        if let Some(port) = settings.http_port {
+            let host = if settings.http_lan {
+                Ipv4Addr::UNSPECIFIED
+            } else {
+                Ipv4Addr::LOCALHOST
+            };
+            let listener = TcpListener::bind((host, port))
+                .await
+                .map_err(|e| format!("Failed to open caption server on port {}: {}", port, e))?;
+            tokio::spawn(serve_http(listener, rx, stop_rx));
+        }
+
+        Ok(Some(Self {
+            feed: CaptionFeed {
+                tx,
+                current: Default::default(),
+            },
+            stop_tx,
+        }))
+    }
+
+    pub fn feed(&self) -> CaptionFeed {
+        self.feed.clone()
+    }
+}
+
+impl Drop for CaptionOutput {
+    fn drop(&mut self) {
+        let _ = self.stop_tx.send(true);
+    }
+}
+
+impl CaptionFeed {
+    /// Shows the latest turn, replacing any partial.
+    pub fn set_final(&self, turn: &TranscriptTurnPayload, locale: Locale) {
+        let caption = Caption {
+            speaker: turn
+                .speaker
+                .as_deref()
+                .map(|speaker| locale.speaker_name(speaker).into_owned()),
+            text: tail(&turn.text),
+        };
+        *self.current.lock() = caption.clone();
+        self.tx.send_if_modified(|shown| replace(shown, caption));
+    }
+
+    /// Blanks the captions, e.g. for a turn whose speaker declined to be
+    /// recorded.
+    pub fn clear(&self) {
+        *self.current.lock() = Caption::default();
+        self.tx
+            .send_if_modified(|shown| replace(shown, Caption::default()));
+    }
+
+    /// Shows words not yet final after the current turn's text.
+    pub fn set_partial(&self, text: &str) {
+        let current = self.current.lock().clone();
+        let caption = Caption {
+            text: tail(&format!("{} {}", current.text, text)),
+            ..current
+        };
+        self.tx.send_if_modified(|shown| replace(shown, caption));
+    }
+}
+
+fn replace(shown: &mut Caption, caption: Caption) -> bool {
+    if *shown == caption {
+        return false;
+    }
+    *shown = caption;
+    true
+}
+
+/// The last `MAX_CAPTION_CHARS` or so of `text`, starting on a word.
+fn tail(text: &str) -> String {
+    let text = text.trim();
+    let chars = text.chars().count();
+    if chars <= MAX_CAPTION_CHARS {
+        return text.to_string();
+    }
+    let cut = text
+        .char_indices()
+        .nth(chars - MAX_CAPTION_CHARS)
+        .map_or(0, |(index, _)| index);
+    let rest = &text[cut..];
+    match rest.find(' ') {
+        Some(space) => rest[space + 1..].to_string(),
+        None => rest.to_string(),
+    }
+}
+
+async fn send_osc(
+    socket: UdpSocket,
+    address: String,
+    mut rx: watch::Receiver<Caption>,
+    mut stop_rx: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            changed = rx.changed() => if changed.is_err() { break },
+            _ = stop_rx.changed() => break,
+        }
+        let caption = rx.borrow_and_update().clone();
+        let packet = osc_message(
+            &address,
+            &[caption.speaker.as_deref().unwrap_or(""), &caption.text],
+        );
+        if let Err(err) = socket.send(&packet).await {
+            tracing::debug!("Failed to send OSC caption: {}", err);
+        }
+    }
+}
+
+/// An OSC 1.0 message with string arguments.
+fn osc_message(address: &str, args: &[&str]) -> Vec<u8> {
+    let mut packet = Vec::new();
+    push_osc_string(&mut packet, address);
+    push_osc_string(&mut packet, &format!(",{}", "s".repeat(args.len())));
+    for arg in args {
+        push_osc_string(&mut packet, arg);
+    }
+    packet
+}
+
+/// OSC strings are null-terminated and padded to a multiple of four bytes.
+fn push_osc_string(packet: &mut Vec<u8>, value: &str) {
+    packet.extend_from_slice(value.as_bytes());
+    let padding = 4 - value.len() % 4;
+    packet.extend(std::iter::repeat_n(0, padding));
+}
+
+async fn serve_http(
+    listener: TcpListener,
+    rx: watch::Receiver<Caption>,
+    mut stop_rx: watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let rx = rx.clone();
+                    tokio::spawn(async move {
+                        if let Err(err) = handle(stream, rx).await {
+                            tracing::debug!("Caption request failed: {}", err);
+                        }
+                    });
+                }
+                Err(err) => tracing::warn!("Caption server failed to accept: {}", err),
+            },
+            _ = stop_rx.changed() => break,
+        }
+    }
+}
+
+async fn handle(mut stream: TcpStream, rx: watch::Receiver<Caption>) -> std::io::Result<()> {
+    let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(request) => request?,
+        Err(_) => return Ok(()),
+    };
+    let Some(path) = request_path(&request) else {
+        return respond(&mut stream, "405 Method Not Allowed", "text/plain", "").await;
+    };
+    let caption = rx.borrow().clone();
+    match path {
+        "/" => respond(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE).await,
+        "/caption.txt" => {
+            respond(
+                &mut stream,
+                "200 OK",
+                "text/plain; charset=utf-8",
+                &caption.text,
+            )
+            .await
+        }
+        "/caption.json" => {
+            let body = serde_json::to_string(&caption).unwrap_or_default();
+            respond(&mut stream, "200 OK", "application/json", &body).await
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found").await,
+    }
+}
@@ -0,0 +1,732 @@
+use crate::apply_settings_patch;
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use crate::events;
+use crate::session::move_mic_to_backend;
+use crate::settings::Settings;
+use crate::state::{AppState, MAIN_SESSION};
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use crate::timeline::TimelineEventKind;
+use crate::{audio, audio_dump, device_test, health, native_mic, output_route};
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+use tauri::Emitter;
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+
+/// Payload of `capture-retargeted`: the captured display slept or went away,
+/// and capture carried on from another one.
+#[cfg(target_os = "macos")]
+#[derive(Debug, serde::Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureRetarget {
+    pub from_display: u32,
+    pub to_display: u32,
+}
+
+pub struct CaptureHandle {
+    pub stop_tx: std::sync::mpsc::Sender<()>,
+    pub task: std::thread::JoinHandle<()>,
+}
+
+pub fn create_audio_dump(
+    app: &AppHandle,
+    name: &str,
+    channels: u16,
+) -> Result<audio_dump::AudioDump, String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?
+        .join("audio-dumps");
+    let file_name = format!(
+        "{}-{}.wav",
+        name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let dump = audio_dump::AudioDump::create(&dir.join(file_name), channels)?;
+    info!("Dumping sent audio to {}", dump.path().display());
+    Ok(dump)
+}
+
+#[tauri::command]
+pub async fn push_mic_audio_chunk(
+    state: State<'_, AppState>,
+    samples: Vec<f32>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let session_id = session_id.as_deref().unwrap_or(MAIN_SESSION);
+    if let Some(session) = state.sessions.lock().get_mut(session_id) {
+        if session.native_mic.is_some() {
+            // The backend captures the mic itself now; chunks from a reopened window would double it.
+            return Ok(());
+        }
+        let mut mic_tx = session.mic_tx.lock();
+        if mic_tx.is_abandoned() {
+            return Err("Recording is no longer active".to_string());
+        }
+
+        let dropped = audio::push_samples(&mut mic_tx, &samples);
+        if dropped > 0 {
+            warn!("Mic queue full, dropped {} samples", dropped);
+            session.mixer_stats.record_dropped_input(dropped);
+        }
+        Ok(())
+    } else if let Some(probe) = state.capture.mic_probe.lock().as_mut() {
+        probe.push(&samples);
+        Ok(())
+    } else {
+        Err("Not currently recording".to_string())
+    }
+}
+
+/// Turns native mic mode on or off and picks its input. Turning it on
+/// mid-recording moves the running session's mic to the backend; turning it
+/// off applies from the next recording.
+#[tauri::command]
+pub async fn set_native_mic(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+    device: Option<String>,
+) -> Result<Settings, String> {
+    let device = device
+        .map(|device| device.trim().to_string())
+        .filter(|device| !device.is_empty());
+    if enabled {
+        let devices = native_mic::input_devices()?;
+        if let Some(device) = device.as_ref().filter(|device| !devices.contains(device)) {
+            return Err(format!("Microphone '{}' not found", device));
+        }
+    }
+    let updated = apply_settings_patch(
+        &app,
+        &state,
+        serde_json::json!({ "microphone": { "native": enabled, "device": device } }),
+    )?;
+    if enabled {
+        move_mic_to_backend(&app)?;
+    }
+    Ok(updated)
+}
+
+#[tauri::command]
+pub async fn list_native_mic_devices() -> Result<Vec<String>, String> {
+    native_mic::input_devices()
+}
+
+/// Measures the mic chunks the frontend pushes over `duration_ms`, so the
+/// settings screen can show whether the selected input hears anything.
+#[tauri::command]
+pub async fn measure_mic_level(
+    state: State<'_, AppState>,
+    duration_ms: u64,
+) -> Result<device_test::LevelReport, String> {
+    if state.sessions.is_recording() {
+        return Err("Stop the recording before testing the microphone".to_string());
+    }
+    {
+        let mut probe = state.capture.mic_probe.lock();
+        if probe.is_some() {
+            return Err("A microphone test is already running".to_string());
+        }
+        *probe = Some(device_test::LevelMeter::default());
+    }
+    let duration = duration_ms.clamp(device_test::MIN_MEASURE_MS, device_test::MAX_MEASURE_MS);
+    tokio::time::sleep(Duration::from_millis(duration)).await;
+    let meter = state.capture.mic_probe.lock().take().unwrap_or_default();
+    Ok(meter.report())
+}
+
+/// Plays a short tone and listens for it through system audio capture, which
+/// checks the output device and screen capture permission in one go.
+#[tauri::command]
+pub async fn play_test_tone(
+    state: State<'_, AppState>,
+) -> Result<device_test::ToneTestResult, String> {
+    if state.sessions.is_recording() || state.capture.screen.lock().is_some() {
+        return Err("Stop the recording before testing audio devices".to_string());
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Err("The audio test is only available on macOS".to_string())
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let path = std::env::temp_dir().join("jilu-test-tone.wav");
+        device_test::tone::write(&path)?;
+        let capacity = audio::SOURCE_SAMPLE_RATE as usize * 4;
+        let (producer, mut consumer) = rtrb::RingBuffer::<f32>::new(capacity);
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+        let capture = std::thread::spawn(move || sc_audio_loop(None, stop_rx, Some(producer)));
+
+        // Give the capture stream a moment to start before the tone does.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let played = device_test::tone::play(&path).await;
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let _ = stop_tx.send(());
+        let captured = tauri::async_runtime::spawn_blocking(move || capture.join()).await;
+        let _ = std::fs::remove_file(&path);
+        played?;
+        match captured {
+            Ok(Ok(Ok(()))) => {}
+            Ok(Ok(Err(err))) => return Err(format!("System audio capture failed: {}", err)),
+            _ => return Err("System audio capture stopped unexpectedly".to_string()),
+        }
+
+        let mut meter = device_test::LevelMeter::default();
+        if let Ok(chunk) = consumer.read_chunk(consumer.slots()) {
+            let (first, second) = chunk.as_slices();
+            meter.push(first);
+            meter.push(second);
+            chunk.commit_all();
+        }
+        let system_audio = meter.report();
+        info!(?system_audio, "Test tone finished");
+        Ok(device_test::ToneTestResult {
+            heard: !system_audio.silent,
+            system_audio,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn check_capture_available() -> health::CheckOutcome {
+    use screencapturekit::shareable_content::SCShareableContent;
+
+    match SCShareableContent::get() {
+        Ok(content) if !content.displays().is_empty() => (health::HealthStatus::Ok, None),
+        Ok(_) => (
+            health::HealthStatus::Error,
+            Some("No displays available to capture".to_string()),
+        ),
+        Err(err) => (
+            health::HealthStatus::Error,
+            Some(format!(
+                "Screen recording permission may be missing: {:?}",
+                err
+            )),
+        ),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn check_capture_available() -> health::CheckOutcome {
+    use cpal::traits::HostTrait;
+
+    match cpal::default_host().default_output_device() {
+        Some(_) => (health::HealthStatus::Ok, None),
+        None => (
+            health::HealthStatus::Error,
+            Some("No output device to capture".to_string()),
+        ),
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn check_capture_available() -> health::CheckOutcome {
+    match std::process::Command::new("parec")
+        .arg("--version")
+        .output()
+    {
+        Ok(output) if output.status.success() => (health::HealthStatus::Ok, None),
+        _ => (
+            health::HealthStatus::Error,
+            Some(
+                "parec was not found; install pulseaudio-utils (it works with PipeWire too)"
+                    .to_string(),
+            ),
+        ),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub fn check_capture_available() -> health::CheckOutcome {
+    (
+        health::HealthStatus::Skipped,
+        Some("System audio capture is only available on macOS 12+, Windows and Linux".to_string()),
+    )
+}
+
+/// The output device system audio is playing to, and whether the recording
+/// can hear it.
+#[tauri::command]
+pub async fn get_output_route() -> Result<output_route::OutputRoute, String> {
+    tauri::async_runtime::spawn_blocking(output_route::current)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+pub fn stop_capture(capture_state: Arc<Mutex<Option<CaptureHandle>>>) {
+    let handle = { capture_state.lock().take() };
+    if let Some(CaptureHandle { stop_tx, task }) = handle {
+        let _ = stop_tx.send(());
+        let _ = task.join();
+    }
+}
+
+pub fn spawn_screen_capture(
+    app: AppHandle,
+    capture_state: Arc<Mutex<Option<CaptureHandle>>>,
+    pcm_tx: Option<rtrb::Producer<f32>>,
+) -> Result<(), String> {
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = pcm_tx;
+        return Err(
+            "System audio capture is only available on macOS 12+, Windows and Linux".into(),
+        );
+    }
+
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+    {
+        let mut guard = capture_state.lock();
+        if guard.is_some() {
+            return Err("Capture already running".into());
+        }
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+        let app_handle = app.clone();
+        let capture_state_handle = capture_state.clone();
+
+        let task = std::thread::spawn(move || {
+            #[cfg(target_os = "macos")]
+            let result = sc_audio_loop(Some(app_handle.clone()), stop_rx, pcm_tx);
+            #[cfg(target_os = "windows")]
+            let result = loopback_audio_loop(Some(app_handle.clone()), stop_rx, pcm_tx);
+            #[cfg(target_os = "linux")]
+            let result = monitor_audio_loop(Some(app_handle.clone()), stop_rx, pcm_tx);
+            capture_state_handle.lock().take();
+
+            if let Err(err) = result {
+                let state = app_handle.state::<AppState>();
+                state
+                    .services
+                    .telemetry
+                    .lock()
+                    .record_error("screen_capture");
+                state
+                    .sessions
+                    .main
+                    .timeline
+                    .lock()
+                    .record(TimelineEventKind::CaptureError, Some(err.to_string()));
+                let _ = app_handle.emit(events::CAPTURE_ERROR, err.to_string());
+            }
+        });
+
+        *guard = Some(CaptureHandle { stop_tx, task });
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn sc_audio_loop(
+    app: Option<AppHandle>,
+    stop_rx: std::sync::mpsc::Receiver<()>,
+    pcm_tx: Option<rtrb::Producer<f32>>,
+) -> Result<(), anyhow::Error> {
+    use crate::audio::SOURCE_SAMPLE_RATE;
+    use core_foundation::error::CFError;
+    use screencapturekit::{
+        shareable_content::SCShareableContent,
+        stream::{
+            configuration::SCStreamConfiguration, content_filter::SCContentFilter,
+            output_trait::SCStreamOutputTrait, output_type::SCStreamOutputType, SCStream,
+        },
+    };
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::sync::Mutex as StdMutex;
+    use std::thread;
+    use std::time::Instant;
+
+    /// ScreenCaptureKit delivers audio, silent or not, for as long as the
+    /// display it captures is awake and attached.
+    const AUDIO_STALL: Duration = Duration::from_secs(3);
+    const STALL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    fn cf_error_to_anyhow(err: CFError) -> anyhow::Error {
+        anyhow::anyhow!("{err:?}")
+    }
+
+    let content = SCShareableContent::get().map_err(cf_error_to_anyhow)?;
+    let mut display = content
+        .displays()
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No displays found to capture"))?;
+
+    let config = SCStreamConfiguration::new()
+        .set_captures_audio(true)
+        .map_err(cf_error_to_anyhow)?
+        .set_excludes_current_process_audio(false)
+        .map_err(cf_error_to_anyhow)?
+        .set_sample_rate(SOURCE_SAMPLE_RATE)
+        .map_err(cf_error_to_anyhow)?
+        .set_channel_count(1)
+        .map_err(cf_error_to_anyhow)?;
+
+    struct AudioLevelOutput {
+        app: Option<AppHandle>,
+        last_emit: StdMutex<Instant>,
+        pcm_tx: Option<Arc<StdMutex<rtrb::Producer<f32>>>>,
+        last_audio: Arc<StdMutex<Instant>>,
+    }
+
+    impl SCStreamOutputTrait for AudioLevelOutput {
+        fn did_output_sample_buffer(
+            &self,
+            sample_buffer: screencapturekit::output::CMSampleBuffer,
+            of_type: SCStreamOutputType,
+        ) {
+            if of_type != SCStreamOutputType::Audio {
+                return;
+            }
+            *self.last_audio.lock().expect("audio clock mutex poisoned") = Instant::now();
+
+            let allow_level_emit = {
+                let mut last = self.last_emit.lock().expect("audio event mutex poisoned");
+                if last.elapsed() >= Duration::from_millis(30) {
+                    *last = Instant::now();
+                    true
+                } else {
+                    false
+                }
+            };
+
+            if let Ok(list) = sample_buffer.get_audio_buffer_list() {
+                let mut total = 0.0f64;
+                let mut buffers = 0usize;
+                let mut pcm_samples: Vec<f32> = Vec::new();
+
+                for idx in 0..list.num_buffers() {
+                    if let Some(buf) = list.get(idx) {
+                        let data = buf.data();
+                        if data.is_empty() {
+                            continue;
+                        }
+
+                        if data.len() % std::mem::size_of::<f32>() == 0 {
+                            let samples: &[f32] = bytemuck::cast_slice(data);
+                            if !samples.is_empty() {
+                                let sum = samples
+                                    .iter()
+                                    .map(|v| (*v as f64) * (*v as f64))
+                                    .sum::<f64>();
+                                total += sum / samples.len() as f64;
+                                buffers += 1;
+                                pcm_samples.extend_from_slice(samples);
+                            }
+                        } else if data.len() % std::mem::size_of::<i16>() == 0 {
+                            let samples: &[i16] = bytemuck::cast_slice(data);
+                            if !samples.is_empty() {
+                                let sum = samples
+                                    .iter()
+                                    .map(|v| {
+                                        let n = *v as f64 / i16::MAX as f64;
+                                        n * n
+                                    })
+                                    .sum::<f64>();
+                                total += sum / samples.len() as f64;
+                                buffers += 1;
+                                pcm_samples
+                                    .extend(samples.iter().map(|s| *s as f32 / i16::MAX as f32));
+                            }
+                        }
+                    }
+                }
+
+                if buffers > 0 && allow_level_emit {
+                    if let Some(app) = &self.app {
+                        let rms = (total / buffers as f64).sqrt().min(1.0);
+                        let _ = app.emit(events::AUDIO_LEVEL, rms);
+                    }
+                }
+
+                if !pcm_samples.is_empty() {
+                    if let Some(tx) = &self.pcm_tx {
+                        let mut producer = tx.lock().expect("audio queue mutex poisoned");
+                        crate::audio::push_samples(&mut producer, &pcm_samples);
+                    }
+                }
+            }
+        }
+    }
+
+    let pcm_tx = pcm_tx.map(|tx| Arc::new(StdMutex::new(tx)));
+    let last_audio = Arc::new(StdMutex::new(Instant::now()));
+    let open = |display| -> Result<SCStream, anyhow::Error> {
+        let filter = SCContentFilter::new().with_display_excluding_windows(display, &[]);
+        let mut stream = SCStream::new(&filter, &config);
+        stream.add_output_handler(
+            AudioLevelOutput {
+                app: app.clone(),
+                last_emit: StdMutex::new(Instant::now()),
+                pcm_tx: pcm_tx.clone(),
+                last_audio: last_audio.clone(),
+            },
+            SCStreamOutputType::Audio,
+        );
+        stream.start_capture().map_err(cf_error_to_anyhow)?;
+        Ok(stream)
+    };
+    // A display asleep in clamshell mode may still be listed.
+    let other_display = |current: u32| {
+        SCShareableContent::get()
+            .map(|content| content.displays())
+            .unwrap_or_default()
+            .into_iter()
+            .find(|candidate| candidate.display_id() != current)
+    };
+
+    let mut stream = open(&display)?;
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STARTED, ());
+    }
+    // Closing the lid or undocking stops the stream without an error; move
+    // to another display rather than record silence.
+    let mut warned_alone = false;
+    while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(STALL_CHECK_INTERVAL) {
+        {
+            let mut last_audio = last_audio.lock().expect("audio clock mutex poisoned");
+            if last_audio.elapsed() < AUDIO_STALL {
+                continue;
+            }
+            // Waits out another stall before the next attempt, whatever this one does.
+            *last_audio = Instant::now();
+        }
+        let from_display = display.display_id();
+        let Some(next) = other_display(from_display) else {
+            if !std::mem::replace(&mut warned_alone, true) {
+                warn!("Captured display stopped delivering audio and no other is available");
+            }
+            continue;
+        };
+        warned_alone = false;
+        stream.stop_capture().ok();
+        match open(&next) {
+            Ok(reopened) => {
+                stream = reopened;
+                display = next;
+                let retarget = CaptureRetarget {
+                    from_display,
+                    to_display: display.display_id(),
+                };
+                info!(?retarget, "Captured display went quiet; capturing another");
+                if let Some(app) = &app {
+                    let _ = app.emit(events::CAPTURE_RETARGETED, retarget);
+                }
+            }
+            Err(err) => warn!("Failed to capture display {}: {}", next.display_id(), err),
+        }
+    }
+    stream.stop_capture().ok();
+    thread::sleep(Duration::from_millis(150));
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STOPPED, ());
+    }
+    Ok(())
+}
+
+/// Records what the default output device plays through WASAPI loopback,
+/// which cpal opens when an input stream is built on an output device.
+#[cfg(target_os = "windows")]
+fn loopback_audio_loop(
+    app: Option<AppHandle>,
+    stop_rx: std::sync::mpsc::Receiver<()>,
+    pcm_tx: Option<rtrb::Producer<f32>>,
+) -> Result<(), String> {
+    use crate::audio::{push_samples, InputConverter, SOURCE_SAMPLE_RATE};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Instant;
+
+    /// How long the stream may go quiet before silence is filled in.
+    const SILENCE_FILL: Duration = Duration::from_millis(100);
+
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| "No output device to capture".to_string())?;
+    let supported = device
+        .default_output_config()
+        .map_err(|e| format!("Failed to read the output format: {}", e))?;
+    let sample_format = supported.sample_format();
+    let config: cpal::StreamConfig = supported.into();
+
+    let pcm_tx = pcm_tx.map(|tx| Arc::new(Mutex::new(tx)));
+    let last_data = Arc::new(Mutex::new(Instant::now()));
+    let mut converter = InputConverter::new(config.channels as usize, config.sample_rate.0);
+    let mut last_emit = Instant::now();
+    let mut deliver = {
+        let pcm_tx = pcm_tx.clone();
+        let last_data = last_data.clone();
+        let app = app.clone();
+        move |samples: &[f32]| {
+            if samples.is_empty() {
+                return;
+            }
+            *last_data.lock() = Instant::now();
+            if last_emit.elapsed() >= Duration::from_millis(30) {
+                last_emit = Instant::now();
+                if let Some(app) = &app {
+                    let mean = samples
+                        .iter()
+                        .map(|s| f64::from(*s) * f64::from(*s))
+                        .sum::<f64>()
+                        / samples.len() as f64;
+                    let _ = app.emit(events::AUDIO_LEVEL, mean.sqrt().min(1.0));
+                }
+            }
+            if let Some(tx) = &pcm_tx {
+                push_samples(&mut tx.lock(), samples);
+            }
+        }
+    };
+    let on_error = |err: cpal::StreamError| warn!("Loopback stream error: {}", err);
+
+    let stream = match sample_format {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                deliver(converter.convert(data.iter().copied()));
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let samples = data.iter().map(|s| *s as f32 / i16::MAX as f32);
+                deliver(converter.convert(samples));
+            },
+            on_error,
+            None,
+        ),
+        other => return Err(format!("Unsupported output sample format {:?}", other)),
+    }
+    .map_err(|e| format!("Failed to open loopback capture: {}", e))?;
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start loopback capture: {}", e))?;
+    info!(
+        device = %device.name().unwrap_or_default(),
+        rate = config.sample_rate.0,
+        channels = config.channels,
+        "Loopback capture started"
+    );
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STARTED, ());
+    }
+
+    // WASAPI sends nothing while the output is silent, and the mixer waits for
+    // both sources, so quiet stretches are filled in here.
+    let silence =
+        vec![0.0f32; SOURCE_SAMPLE_RATE as usize * SILENCE_FILL.as_millis() as usize / 1000];
+    while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(SILENCE_FILL) {
+        if last_data.lock().elapsed() < SILENCE_FILL {
+            continue;
+        }
+        if let Some(tx) = &pcm_tx {
+            push_samples(&mut tx.lock(), &silence);
+        }
+    }
+    drop(stream);
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STOPPED, ());
+    }
+    Ok(())
+}
+
+/// Records the default output's monitor source through `parec`, which talks to
+/// PulseAudio and to PipeWire's PulseAudio server alike.
+#[cfg(target_os = "linux")]
+fn monitor_audio_loop(
+    app: Option<AppHandle>,
+    stop_rx: std::sync::mpsc::Receiver<()>,
+    mut pcm_tx: Option<rtrb::Producer<f32>>,
+) -> Result<(), String> {
+    use crate::audio::{push_samples, FRAME_MS, SOURCE_SAMPLE_RATE};
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::TryRecvError;
+    use std::time::Instant;
+
+    let mut child = Command::new("parec")
+        .args([
+            "--device=@DEFAULT_MONITOR@",
+            "--format=float32le",
+            "--channels=1",
+            "--raw",
+            "--latency-msec=20",
+        ])
+        .arg(format!("--rate={}", SOURCE_SAMPLE_RATE))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start parec: {}", e))?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "parec has no output".to_string())?;
+    info!("Monitor source capture started");
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STARTED, ());
+    }
+
+    // The monitor keeps delivering silence while nothing plays, so reading a
+    // frame at a time also paces the stop check.
+    let frame_samples = (u64::from(SOURCE_SAMPLE_RATE) * FRAME_MS / 1000) as usize;
+    let mut bytes = vec![0u8; frame_samples * std::mem::size_of::<f32>()];
+    let mut samples = Vec::with_capacity(frame_samples);
+    let mut last_emit = Instant::now();
+    let result = loop {
+        match stop_rx.try_recv() {
+            Err(TryRecvError::Empty) => {}
+            _ => break Ok(()),
+        }
+        if let Err(err) = stdout.read_exact(&mut bytes) {
+            break Err(format!("parec stopped: {}", err));
+        }
+        samples.clear();
+        samples.extend(
+            bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+
+        if last_emit.elapsed() >= Duration::from_millis(30) {
+            last_emit = Instant::now();
+            if let Some(app) = &app {
+                let mean = samples
+                    .iter()
+                    .map(|s| f64::from(*s) * f64::from(*s))
+                    .sum::<f64>()
+                    / samples.len() as f64;
+                let _ = app.emit(events::AUDIO_LEVEL, mean.sqrt().min(1.0));
+            }
+        }
+        if let Some(producer) = pcm_tx.as_mut() {
+            push_samples(producer, &samples);
+        }
+    };
+
+    let _ = child.kill();
+    let _ = child.wait();
+    if let Some(app) = &app {
+        let _ = app.emit(events::CAPTURE_STOPPED, ());
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn start_sc_capture(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    spawn_screen_capture(app, state.capture.screen.clone(), None)
+}
+
+#[tauri::command]
+pub async fn stop_sc_capture(state: State<'_, AppState>) -> Result<(), String> {
+    stop_capture(state.capture.screen.clone());
+    Ok(())
+}
@@ -0,0 +1,475 @@
+use crate::audio::AudioMixer;
+use crate::batch;
+use crate::chapters;
+use crate::formatting;
+use crate::locale::Locale;
+use crate::normalize::Normalizer;
+use crate::profiles::{Profile, Profiles, PROFILES_FILE};
+use crate::settings::{ProviderKind, Settings, SettingsStore, TranscriptionSettings};
+use crate::transcript::{Transcript, TranscriptTurnPayload};
+use crate::transcript_export::{self, ExportFormat};
+use jilu_core::realtime::{self, RealtimeOptions};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Matches `identifier` in tauri.conf.json, which names the app's config and data directories.
+const APP_IDENTIFIER: &str = "com.example.jilu";
+const API_KEY_ENV: &str = "SPEECHMATICS_API_KEY";
+const DEEPGRAM_API_KEY_ENV: &str = "DEEPGRAM_API_KEY";
+
+const USAGE: &str = "\
+Usage:
+  jilu record [--event <id>] [--output <dir>] [--duration <secs>] [--api-key <key>]
+  jilu transcribe <file> [--format txt|srt|vtt|json] [--output <dir>] [--api-key <key>]
+  jilu export <meeting-id> [--format txt|srt|vtt|json] [--links] [--output <dir>]
+
+Without --output, transcribe and export print to stdout. The Speechmatics key
+can also be given in the SPEECHMATICS_API_KEY environment variable, or for
+recordings set to stream to Deepgram, the key in DEEPGRAM_API_KEY.
+--links prefixes each turn of a txt export with a jilu:// link to that moment.
+Recording stops on Ctrl+C or after --duration seconds.";
+
+#[derive(Debug)]
+enum CliCommand {
+    Record {
+        event_id: Option<String>,
+        output: PathBuf,
+        duration: Option<Duration>,
+        api_key: Option<String>,
+    },
+    Transcribe {
+        file: PathBuf,
+        format: ExportFormat,
+        output: Option<PathBuf>,
+        api_key: Option<String>,
+    },
+    Export {
+        meeting_id: String,
+        format: ExportFormat,
+        output: Option<PathBuf>,
+        links: bool,
+    },
+    Help,
+}
+
+/// Runs `jilu <command>` without the GUI and returns the exit code.
+///
+/// Returns `None` when the arguments are not a CLI command, so the app starts normally.
+pub fn run_from_args() -> Option<i32> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let is_command = matches!(
+        args.first().map(String::as_str),
+        Some("record" | "transcribe" | "export" | "help" | "--help" | "-h")
+    );
+    if !is_command {
+        return None;
+    }
+
+    crate::logging::Logging::init_cli();
+    let result = parse(&args).and_then(|command| {
+        let runtime = tokio::runtime::Runtime::new()
+            .map_err(|e| format!("Failed to start async runtime: {}", e))?;
+        runtime.block_on(execute(command))
+    });
+    Some(match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("jilu: {}", err);
+            1
+        }
+    })
+}
+
+fn parse(args: &[String]) -> Result<CliCommand, String> {
+    let mut positional = Vec::new();
+    let mut event_id = None;
+    let mut output = None;
+    let mut duration = None;
+    let mut format = None;
+    let mut api_key = None;
+    let mut links = false;
+
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = |name: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{} needs a value\n\n{}", name, USAGE))
+        };
+        match arg.as_str() {
+            "--event" => event_id = Some(value(arg)?),
+            "--output" | "-o" => output = Some(PathBuf::from(value(arg)?)),
+            "--format" | "-f" => format = Some(value(arg)?.parse::<ExportFormat>()?),
+            "--api-key" => api_key = Some(value(arg)?),
+            "--links" => links = true,
+            "--duration" => {
+                let secs: u64 = value(arg)?
+                    .parse()
+                    .map_err(|_| "--duration must be a whole number of seconds".to_string())?;
+                duration = Some(Duration::from_secs(secs));
+            }
+            other if other.starts_with('-') => {
+                return Err(format!("Unknown option '{}'\n\n{}", other, USAGE));
+            }
+            other => positional.push(other.to_string()),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let command = match args[0].as_str() {
+        "record" => CliCommand::Record {
+            event_id,
+            output: output.unwrap_or_else(|| PathBuf::from(".")),
+            duration,
+            api_key,
+        },
+        "transcribe" => CliCommand::Transcribe {
+            file: positional
+                .next()
+                .map(PathBuf::from)
+                .ok_or_else(|| format!("transcribe needs a file\n\n{}", USAGE))?,
+            format: format.unwrap_or(ExportFormat::Txt),
+            output,
+            api_key,
+        },
+        "export" => CliCommand::Export {
+            meeting_id: positional
+                .next()
+                .ok_or_else(|| format!("export needs a meeting id\n\n{}", USAGE))?,
+            format: format.unwrap_or(ExportFormat::Txt),
+            output,
+            links,
+        },
+        _ => CliCommand::Help,
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("Unexpected argument '{}'\n\n{}", extra, USAGE));
+    }
+    Ok(command)
+}
+
+async fn execute(command: CliCommand) -> Result<(), String> {
+    match command {
+        CliCommand::Record {
+            event_id,
+            output,
+            duration,
+            api_key,
+        } => record(event_id, &output, duration, api_key).await,
+        CliCommand::Transcribe {
+            file,
+            format,
+            output,
+            api_key,
+        } => {
+            let api_key = resolve_api_key(api_key, ProviderKind::Speechmatics)?;
+            transcribe(&file, format, output.as_deref(), api_key).await
+        }
+        CliCommand::Export {
+            meeting_id,
+            format,
+            output,
+            links,
+        } => export(&meeting_id, format, output.as_deref(), links),
+        CliCommand::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+fn resolve_api_key(flag: Option<String>, provider: ProviderKind) -> Result<String, String> {
+    let (env, name) = match provider {
+        ProviderKind::Speechmatics => (API_KEY_ENV, "Speechmatics"),
+        ProviderKind::Deepgram => (DEEPGRAM_API_KEY_ENV, "Deepgram"),
+    };
+    flag.or_else(|| std::env::var(env).ok())
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .ok_or_else(|| format!("No {} API key; pass --api-key or set {}", name, env))
+}
+
+fn config_dir() -> Result<PathBuf, String> {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Could not find the config directory".to_string())
+}
+
+fn data_dir() -> Result<PathBuf, String> {
+    dirs::data_dir()
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .ok_or_else(|| "Could not find the data directory".to_string())
+}
+
+/// The profile the app was last using, and its settings.
+fn active_profile() -> Result<(Profile, Settings), String> {
+    let config_dir = config_dir()?;
+    let profile = Profiles::load(config_dir.join(PROFILES_FILE))
+        .active()
+        .clone();
+    let settings = SettingsStore::load(profile.settings_path(&config_dir))
+        .get()
+        .clone();
+    Ok((profile, settings))
+}
+
+/// Writes to `<dir>/<stem>.<ext>`, or stdout without a directory.
+fn write_output(
+    dir: Option<&Path>,
+    stem: &str,
+    format: ExportFormat,
+    contents: &str,
+) -> Result<(), String> {
+    let Some(dir) = dir else {
+        print!("{}", contents);
+        return Ok(());
+    };
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.{}", stem, format.extension()));
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    eprintln!("Wrote {}", path.display());
+    Ok(())
+}
+
+fn file_stem(title: &str) -> String {
+    let slug: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M");
+    if slug.is_empty() {
+        format!("recording-{}", stamp)
+    } else {
+        format!("{}-{}", slug, stamp)
+    }
+}
+
+fn export(
+    meeting_id: &str,
+    format: ExportFormat,
+    output: Option<&Path>,
+    links: bool,
+) -> Result<(), String> {
+    if links && format != ExportFormat::Txt {
+        return Err("--links only applies to --format txt".to_string());
+    }
+    let (profile, settings) = active_profile()?;
+    let dir = crate::meeting_store::meeting_path(&profile, &data_dir()?, meeting_id)?;
+    let path = dir.join(transcript_export::TRANSCRIPT_FILE);
+    let contents = std::fs::read_to_string(&path).map_err(|e| {
+        format!(
+            "No saved transcript for meeting {} in profile '{}': {}",
+            meeting_id, profile.name, e
+        )
+    })?;
+    let mut turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    formatting::apply_to_turns(&mut turns, &settings.transcription.formatting);
+    if let Some(normalizer) = Normalizer::new(&settings.export_normalization, &settings.locale) {
+        normalizer.apply_to_turns(&mut turns);
+    }
+    let chapters = crate::storage::saved_chapters(&dir)?;
+    let locale = Locale::from_tag(&settings.locale);
+    let rendered = if links {
+        transcript_export::render_linked_text(&turns, &chapters, meeting_id, locale)
+    } else {
+        transcript_export::render(&turns, &chapters, format, locale)?
+    };
+    write_output(output, meeting_id, format, &rendered)
+}
+
+async fn transcribe(
+    file: &Path,
+    format: ExportFormat,
+    output: Option<&Path>,
+    api_key: String,
+) -> Result<(), String> {
+    let settings = active_profile()?.1.transcription;
+    let job_id = batch::submit(&api_key, file, &settings).await?;
+    eprintln!("Submitted job {}", job_id);
+
+    let provider_format = match format {
+        ExportFormat::Txt => "txt",
+        // Speechmatics only writes SRT subtitles.
+        ExportFormat::Srt | ExportFormat::Vtt => "srt",
+        ExportFormat::Json => "json-v2",
+    };
+    let mut transcript = batch::transcript(&api_key, &job_id, provider_format).await?;
+    if format == ExportFormat::Vtt {
+        transcript = transcript_export::srt_to_vtt(&transcript);
+    }
+
+    let stem = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| job_id.clone());
+    write_output(output, &stem, format, &transcript)
+}
+
+async fn record(
+    event_id: Option<String>,
+    output: &Path,
+    duration: Option<Duration>,
+    api_key: Option<String>,
+) -> Result<(), String> {
+    let (_, settings) = active_profile()?;
+    let api_key = resolve_api_key(api_key, settings.transcription.provider)?;
+    let title = match &event_id {
+        Some(id) => find_event_title(id)?,
+        None => "Recording".to_string(),
+    };
+    std::fs::create_dir_all(output)
+        .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+
+    let (mixer, inputs) = AudioMixer::spawn(None)?;
+    let capture = HeadlessCapture::start(inputs)?;
+    eprintln!("Recording '{}'. Press Ctrl+C to stop.", title);
+
+    let stop = async {
+        match duration {
+            Some(duration) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = tokio::time::sleep(duration) => {}
+                }
+            }
+            None => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    };
+    let result = stream_realtime(api_key, &settings.transcription, mixer, stop).await;
+    capture.stop();
+    let transcript = result?;
+
+    let stem = file_stem(&title);
+    let locale = Locale::from_tag(&settings.locale);
+    let chapters = chapters::detect(transcript.turns());
+    for format in [
+        ExportFormat::Txt,
+        ExportFormat::Json,
+        ExportFormat::Srt,
+        ExportFormat::Vtt,
+    ] {
+        match transcript_export::render(transcript.turns(), &chapters, format, locale) {
+            Ok(rendered) => write_output(Some(output), &stem, format, &rendered)?,
+            Err(err) => eprintln!("Skipping {}: {}", format.extension(), err),
+        }
+    }
+    Ok(())
+}
+
+fn find_event_title(event_id: &str) -> Result<String, String> {
+    let calendar_ids = crate::calendar::list_calendars()?
+        .into_iter()
+        .map(|calendar| calendar.id)
+        .collect();
+    let now = chrono::Utc::now();
+    let events = crate::calendar::fetch_events(
+        calendar_ids,
+        (now - chrono::Duration::days(1)).to_rfc3339(),
+        (now + chrono::Duration::days(1)).to_rfc3339(),
+    )?;
+    events
+        .into_iter()
+        .find(|event| event.id == event_id)
+        .map(|event| event.title)
+        .ok_or_else(|| format!("No calendar event {} within a day of now", event_id))
+}
+
+/// System audio from ScreenCaptureKit, with silence standing in for the microphone
+/// that the GUI would normally feed from the webview.
+struct HeadlessCapture {
+    stop: Arc<AtomicBool>,
+    silence: Option<std::thread::JoinHandle<()>>,
+    #[cfg(target_os = "macos")]
+    screen: Option<(std::sync::mpsc::Sender<()>, std::thread::JoinHandle<()>)>,
+}
+
+impl HeadlessCapture {
+    fn start(inputs: crate::audio::MixerInputs) -> Result<Self, String> {
+        let crate::audio::MixerInputs { screen, mut mic } = inputs;
+
+        #[cfg(not(target_os = "macos"))]
+        {
+            let _ = (screen, &mut mic);
+            Err("Recording is only available on macOS".to_string())
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            use crate::audio::{FRAME_MS, SOURCE_SAMPLE_RATE};
+
+            let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+            let screen_thread = std::thread::spawn(move || {
+                if let Err(err) = crate::capture::sc_audio_loop(None, stop_rx, Some(screen)) {
+                    eprintln!("jilu: system audio capture failed: {}", err);
+                }
+            });
+
+            let stop = Arc::new(AtomicBool::new(false));
+            let silence_stop = stop.clone();
+            let frame = vec![0.0f32; (SOURCE_SAMPLE_RATE as u64 * FRAME_MS / 1000) as usize];
+            let silence = std::thread::spawn(move || {
+                while !silence_stop.load(Ordering::Acquire) {
+                    crate::audio::push_samples(&mut mic, &frame);
+                    std::thread::sleep(Duration::from_millis(FRAME_MS));
+                }
+            });
+
+            Ok(Self {
+                stop,
+                silence: Some(silence),
+                screen: Some((stop_tx, screen_thread)),
+            })
+        }
+    }
+
+    fn stop(mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(thread) = self.silence.take() {
+            let _ = thread.join();
+        }
+        #[cfg(target_os = "macos")]
+        if let Some((stop_tx, thread)) = self.screen.take() {
+            let _ = stop_tx.send(());
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Streams mixer output to the realtime API until `stop` resolves, returning the final transcript.
+async fn stream_realtime(
+    api_key: String,
+    settings: &TranscriptionSettings,
+    mixer: AudioMixer,
+    stop: impl std::future::Future<Output = ()>,
+) -> Result<Transcript, String> {
+    let options = RealtimeOptions {
+        provider: settings.provider,
+        rt_url: settings.endpoint(),
+        auth: settings.auth,
+        websocket: settings.websocket,
+        language: settings.language.clone(),
+        domain: settings.domain.clone(),
+        additional_vocab: settings.additional_vocab.clone(),
+        operating_point: settings.operating_point,
+        formatting: settings.formatting.clone(),
+        audio_batch_ms: settings.audio_batch_ms,
+        ..RealtimeOptions::new(api_key)
+    };
+    let stop = async {
+        stop.await;
+        eprintln!("Waiting for the final transcript...");
+    };
+    realtime::transcribe(options, mixer, stop, |text| eprintln!("{}", text)).await
+}
@@ -0,0 +1,154 @@
+use crate::transcript::TranscriptTurnPayload;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::Path;
+
+pub const CONSENT_FILE: &str = "consent.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ConsentStatus {
+    Requested,
+    Granted,
+    Declined,
+}
+
+/// Whether one attendee agreed to being recorded.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendeeConsent {
+    /// Lowercased.
+    pub email: String,
+    pub name: Option<String>,
+    pub status: ConsentStatus,
+    /// Label the attendee's speech is diarized under, such as `S2` or their
+    /// enrolled name. Without it, turns labelled with their name are theirs.
+    pub speaker: Option<String>,
+    /// When the status last changed (RFC 3339).
+    pub updated_at: String,
+}
+
+/// Consent for a meeting's attendees, kept in the meeting's folder.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MeetingConsent {
+    /// Leave declined attendees' speech out of the saved transcript, and so
+    /// out of exports made from it.
+    pub exclude_declined: bool,
+    pub attendees: Vec<AttendeeConsent>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsentUpdate {
+    pub email: String,
+    pub name: Option<String>,
+    pub status: ConsentStatus,
+    /// Replaces the attendee's speaker label when given; an empty string clears it.
+    pub speaker: Option<String>,
+}
+
+impl MeetingConsent {
+    /// Consent saved at `path`; nothing tracked yet when there is none. A
+    /// file that is there but can't be read is an error, never "no consent".
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to read meeting consent: {}", e)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Failed to read meeting consent: {}", err)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| format!("Failed to save meeting consent: {}", e))
+    }
+
+    pub fn set(&mut self, update: ConsentUpdate) -> Result<(), String> {
+        let email = update.email.trim().to_lowercase();
+        if !email.contains('@') {
+            return Err(format!("'{}' is not an email address", update.email));
+        }
+        let name = update
+            .name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        let updated_at = chrono::Utc::now().to_rfc3339();
+        let index = match self.attendees.iter().position(|entry| entry.email == email) {
+            Some(index) => index,
+            None => {
+                self.attendees.push(AttendeeConsent {
+                    email,
+                    name: None,
+                    status: update.status,
+                    speaker: None,
+                    updated_at: updated_at.clone(),
+                });
+                self.attendees.len() - 1
+            }
+        };
+        let entry = &mut self.attendees[index];
+        entry.status = update.status;
+        entry.updated_at = updated_at;
+        if name.is_some() {
+            entry.name = name;
+        }
+        if let Some(speaker) = update.speaker {
+            let speaker = speaker.trim();
+            entry.speaker = (!speaker.is_empty()).then(|| speaker.to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether `turn` is speech this meeting should not keep.
+    pub fn excludes(&self, turn: &TranscriptTurnPayload) -> bool {
+        let Some(speaker) = turn.speaker.as_deref().map(str::trim) else {
+            return false;
+        };
+        self.exclude_declined
+            && self
+                .attendees
+                .iter()
+                .filter(|entry| entry.status == ConsentStatus::Declined)
+                .filter_map(|entry| entry.speaker.as_deref().or(entry.name.as_deref()))
+                .any(|label| label.eq_ignore_ascii_case(speaker))
+    }
+
+    /// Whether any speech at all is being left out, which speech not yet
+    /// attributed to a speaker might belong to.
+    pub fn excludes_anyone(&self) -> bool {
+        self.exclude_declined
+            && self
+                .attendees
+                .iter()
+                .any(|entry| entry.status == ConsentStatus::Declined)
+    }
+
+    /// `turns` without the speech this meeting should not keep.
+    pub fn redact<'a>(
+        &self,
+        turns: &'a [TranscriptTurnPayload],
+    ) -> Cow<'a, [TranscriptTurnPayload]> {
+        if !turns.iter().any(|turn| self.excludes(turn)) {
+            return Cow::Borrowed(turns);
+        }
+        Cow::Owned(
+            turns
+                .iter()
+                .filter(|turn| !self.excludes(turn))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+/// `turns` without the speech of attendees who declined, when the consent
+/// saved at `path` asks for that. Fails when the consent can't be read, so
+/// callers keep nothing rather than everything.
+pub fn redact<'a>(
+    path: &Path,
+    turns: &'a [TranscriptTurnPayload],
+) -> Result<Cow<'a, [TranscriptTurnPayload]>, String> {
+    Ok(MeetingConsent::load(path)?.redact(turns))
+}
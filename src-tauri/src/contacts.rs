@@ -0,0 +1,274 @@
+use crate::analytics::MeetingAnalytics;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub const CONTACTS_FILE: &str = "contacts.json";
+const MAX_NOTES_CHARS: usize = 20_000;
+/// How many of the latest meetings count as "recent" in a talk-time trend.
+const RECENT_MEETINGS: usize = 5;
+
+/// Someone on a meeting's invite, as the frontend knows them.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Attendee {
+    pub email: String,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct Attendance {
+    meeting_id: String,
+    title: String,
+    /// When the meeting took place (RFC 3339).
+    date: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct StoredContact {
+    name: Option<String>,
+    notes: String,
+    meetings: Vec<Attendance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct StoredContacts {
+    /// Keyed by lowercased email.
+    contacts: BTreeMap<String, StoredContact>,
+}
+
+impl StoredContacts {
+    fn remove_meeting(&mut self, meeting_id: &str) {
+        for contact in self.contacts.values_mut() {
+            contact
+                .meetings
+                .retain(|attendance| attendance.meeting_id != meeting_id);
+        }
+    }
+
+    /// Contacts with neither meetings nor notes have nothing left to show.
+    fn prune(&mut self) {
+        self.contacts
+            .retain(|_, contact| !contact.meetings.is_empty() || !contact.notes.is_empty());
+    }
+}
+
+/// One meeting in a contact's history.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactMeeting {
+    pub meeting_id: String,
+    pub title: String,
+    pub date: String,
+    /// `None` when the meeting has no transcript or none of its speakers
+    /// carries the contact's name.
+    pub speaking_secs: Option<f64>,
+    pub words: Option<u64>,
+    /// Their share of all speaking time in the meeting, from 0 to 1.
+    pub talk_share: Option<f64>,
+}
+
+/// How much someone talks lately compared with all their meetings.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TalkTrend {
+    /// Meetings with speaking data that the figures are based on.
+    pub meetings: usize,
+    pub overall_share: f64,
+    pub recent_share: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ContactHistory {
+    pub email: String,
+    pub name: Option<String>,
+    pub notes: String,
+    /// Newest first.
+    pub meetings: Vec<ContactMeeting>,
+    pub talk_trend: Option<TalkTrend>,
+}
+
+/// Who attended which meetings, plus free-form notes per person, for the
+/// active profile. Keyed by email so calendar invites line up across meetings.
+#[derive(Debug, Default)]
+pub struct ContactBook {
+    path: Option<PathBuf>,
+    stored: StoredContacts,
+}
+
+impl ContactBook {
+    pub fn load(path: PathBuf) -> Self {
+        let stored = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            stored,
+        }
+    }
+
+    /// Records a meeting's attendees. Calling it again for the same meeting
+    /// replaces what was recorded, so people removed from the invite drop out.
+    pub fn record_meeting(
+        &mut self,
+        meeting_id: &str,
+        title: &str,
+        date: &str,
+        attendees: &[Attendee],
+    ) -> Result<(), String> {
+        chrono::DateTime::parse_from_rfc3339(date)
+            .map_err(|e| format!("Invalid meeting date: {}", e))?;
+        let mut stored = self.stored.clone();
+        stored.remove_meeting(meeting_id);
+        for attendee in attendees {
+            let Some(email) = normalize_email(&attendee.email) else {
+                continue;
+            };
+            let contact = stored.contacts.entry(email).or_default();
+            if let Some(name) = attendee.name.as_deref().map(str::trim) {
+                if !name.is_empty() {
+                    contact.name = Some(name.to_string());
+                }
+            }
+            contact.meetings.push(Attendance {
+                meeting_id: meeting_id.to_string(),
+                title: title.trim().to_string(),
+                date: date.to_string(),
+            });
+        }
+        stored.prune();
+        self.save(stored)
+    }
+
+    /// Drops a deleted meeting from everyone's history.
+    pub fn forget_meeting(&mut self, meeting_id: &str) -> Result<(), String> {
+        let mut stored = self.stored.clone();
+        stored.remove_meeting(meeting_id);
+        stored.prune();
+        self.save(stored)
+    }
+
+    pub fn set_notes(&mut self, email: &str, notes: &str) -> Result<(), String> {
+        let email = normalize_email(email).ok_or_else(|| format!("Invalid email: {}", email))?;
+        if notes.chars().count() > MAX_NOTES_CHARS {
+            return Err(format!(
+                "Contact notes are limited to {} characters",
+                MAX_NOTES_CHARS
+            ));
+        }
+        let mut stored = self.stored.clone();
+        stored.contacts.entry(email).or_default().notes = notes.to_string();
+        self.save(stored)
+    }
+
+    /// Meeting ids `email` attended, to look up their analytics.
+    pub fn meeting_ids(&self, email: &str) -> Vec<String> {
+        normalize_email(email)
+            .and_then(|email| self.stored.contacts.get(&email))
+            .map(|contact| {
+                contact
+                    .meetings
+                    .iter()
+                    .map(|attendance| attendance.meeting_id.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// History of `email`, with speaking figures from `analytics` keyed by
+    /// meeting id. Speakers are matched to the contact by name.
+    pub fn history(
+        &self,
+        email: &str,
+        analytics: &BTreeMap<String, MeetingAnalytics>,
+    ) -> Result<ContactHistory, String> {
+        let key = normalize_email(email).ok_or_else(|| format!("Invalid email: {}", email))?;
+        let contact = self
+            .stored
+            .contacts
+            .get(&key)
+            .ok_or_else(|| format!("No meetings or notes for {}", key))?;
+
+        let mut meetings: Vec<ContactMeeting> = contact
+            .meetings
+            .iter()
+            .map(|attendance| {
+                let speaking = analytics.get(&attendance.meeting_id).and_then(|analytics| {
+                    let name = contact.name.as_deref()?;
+                    let speaker = analytics.speakers.iter().find(|speaker| {
+                        speaker
+                            .speaker
+                            .as_deref()
+                            .is_some_and(|label| label.trim().eq_ignore_ascii_case(name))
+                    })?;
+                    let total: f64 = analytics.speakers.iter().map(|s| s.speaking_secs).sum();
+                    Some((
+                        speaker.speaking_secs,
+                        speaker.words,
+                        (total > 0.0).then(|| speaker.speaking_secs / total),
+                    ))
+                });
+                ContactMeeting {
+                    meeting_id: attendance.meeting_id.clone(),
+                    title: attendance.title.clone(),
+                    date: attendance.date.clone(),
+                    speaking_secs: speaking.map(|(secs, _, _)| secs),
+                    words: speaking.map(|(_, words, _)| words),
+                    talk_share: speaking.and_then(|(_, _, share)| share),
+                }
+            })
+            .collect();
+        meetings.sort_by_key(|meeting| {
+            std::cmp::Reverse(chrono::DateTime::parse_from_rfc3339(&meeting.date).ok())
+        });
+
+        Ok(ContactHistory {
+            email: key,
+            name: contact.name.clone(),
+            notes: contact.notes.clone(),
+            talk_trend: talk_trend(&meetings),
+            meetings,
+        })
+    }
+
+    fn save(&mut self, stored: StoredContacts) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            }
+            let contents = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())?;
+            std::fs::write(path, contents)
+                .map_err(|e| format!("Failed to save contacts: {}", e))?;
+        }
+        self.stored = stored;
+        Ok(())
+    }
+}
+
+/// `meetings` must be newest first.
+fn talk_trend(meetings: &[ContactMeeting]) -> Option<TalkTrend> {
+    let shares: Vec<f64> = meetings
+        .iter()
+        .filter_map(|meeting| meeting.talk_share)
+        .collect();
+    if shares.is_empty() {
+        return None;
+    }
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    Some(TalkTrend {
+        meetings: shares.len(),
+        overall_share: mean(&shares),
+        recent_share: mean(&shares[..shares.len().min(RECENT_MEETINGS)]),
+    })
+}
+
+fn normalize_email(email: &str) -> Option<String> {
+    let email = email.trim().to_lowercase();
+    (email.contains('@') && !email.contains(char::is_whitespace)).then_some(email)
+}
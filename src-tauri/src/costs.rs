@@ -0,0 +1,210 @@
+use crate::settings::{CostSettings, ProviderKind};
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+pub const COST_FILE: &str = "cost.json";
+
+/// Audio streamed for one meeting and what it is estimated to have cost,
+/// summed over every recording made into it.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingCost {
+    pub currency: String,
+    pub providers: BTreeMap<String, ProviderCost>,
+    /// When audio was last added (RFC 3339).
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCost {
+    pub audio_secs: f64,
+    /// At the rate in force when each recording was made; `None` while the
+    /// provider has no rate.
+    pub cost: Option<f64>,
+}
+
+impl MeetingCost {
+    pub fn audio_hours(&self) -> f64 {
+        self.providers.values().map(|p| p.audio_secs).sum::<f64>() / 3600.0
+    }
+
+    /// `None` when no provider used for the meeting had a rate.
+    pub fn estimated_cost(&self) -> Option<f64> {
+        self.providers
+            .values()
+            .filter_map(|p| p.cost)
+            .reduce(|a, b| a + b)
+    }
+}
+
+/// Rate key for the realtime endpoint a recording used: the provider's name
+/// for its hosted endpoints, whichever region, or the host of a self-hosted one.
+pub fn provider_for(kind: ProviderKind, rt_url: Option<&str>) -> String {
+    let hosted_domain = match kind {
+        ProviderKind::Speechmatics => "speechmatics.com",
+        ProviderKind::Deepgram => "deepgram.com",
+    };
+    let host = rt_url.and_then(|url| {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        rest.split(['/', ':', '?'])
+            .next()
+            .filter(|host| !host.is_empty())
+    });
+    match host {
+        Some(host) if !host.ends_with(hosted_domain) => host.to_lowercase(),
+        _ => kind.name().to_string(),
+    }
+}
+
+pub fn load(dir: &Path) -> Result<Option<MeetingCost>, String> {
+    match std::fs::read_to_string(dir.join(COST_FILE)) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map(Some)
+            .map_err(|e| format!("Failed to read meeting cost: {}", e)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Adds a recording's streamed audio to the meeting in `dir`, priced at the
+/// current rate for `provider`.
+pub fn record(
+    dir: &Path,
+    provider: &str,
+    audio_secs: f64,
+    settings: &CostSettings,
+) -> Result<MeetingCost, String> {
+    let mut cost = load(dir)?.unwrap_or_default();
+    cost.currency = settings.currency.clone();
+    cost.updated_at = chrono::Utc::now().to_rfc3339();
+    let entry = cost.providers.entry(provider.to_string()).or_default();
+    entry.audio_secs += audio_secs;
+    if let Some(rate) = settings.hourly_rates.get(provider) {
+        entry.cost = Some(entry.cost.unwrap_or(0.0) + audio_secs / 3600.0 * rate);
+    }
+    let contents = serde_json::to_string_pretty(&cost).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(COST_FILE), contents)
+        .map_err(|e| format!("Failed to save meeting cost: {}", e))?;
+    Ok(cost)
+}
+
+/// Streamed audio and estimated cost across meetings, for `get_usage_stats`.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    /// From the settings; meetings priced in another currency are not added
+    /// to `estimated_cost`.
+    pub currency: String,
+    pub audio_hours: f64,
+    pub estimated_cost: f64,
+    /// Hours with no rate, or priced in another currency.
+    pub unpriced_hours: f64,
+    pub providers: Vec<ProviderStats>,
+    pub meetings: Vec<MeetingStats>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderStats {
+    pub provider: String,
+    pub audio_hours: f64,
+    pub estimated_cost: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingStats {
+    pub meeting_id: String,
+    pub audio_hours: f64,
+    pub estimated_cost: Option<f64>,
+    pub currency: String,
+    pub updated_at: String,
+}
+
+/// Which meetings `summarize` counts.
+#[derive(Debug, Default)]
+pub struct UsageFilter {
+    pub meeting_ids: Option<HashSet<String>>,
+    /// Inclusive, on the local date the meeting's audio was last added.
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+}
+
+pub fn summarize(meetings_dir: &Path, filter: &UsageFilter, currency: &str) -> UsageStats {
+    let mut stats = UsageStats {
+        currency: currency.to_string(),
+        ..Default::default()
+    };
+    let Ok(entries) = std::fs::read_dir(meetings_dir) else {
+        return stats;
+    };
+    let mut providers: BTreeMap<String, ProviderStats> = BTreeMap::new();
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(meeting_id) = entry.file_name().into_string() else {
+            continue;
+        };
+        if filter
+            .meeting_ids
+            .as_ref()
+            .is_some_and(|ids| !ids.contains(&meeting_id))
+        {
+            continue;
+        }
+        let cost = match load(&entry.path()) {
+            Ok(Some(cost)) => cost,
+            Ok(None) => continue,
+            Err(err) => {
+                tracing::warn!(meeting = %meeting_id, "{}", err);
+                continue;
+            }
+        };
+        let date = DateTime::parse_from_rfc3339(&cost.updated_at)
+            .ok()
+            .map(|at| at.with_timezone(&Local).date_naive());
+        let in_range = match date {
+            Some(date) => {
+                filter.since.is_none_or(|since| date >= since)
+                    && filter.until.is_none_or(|until| date <= until)
+            }
+            None => filter.since.is_none() && filter.until.is_none(),
+        };
+        if !in_range {
+            continue;
+        }
+
+        let same_currency = cost.currency == currency;
+        for (provider, usage) in &cost.providers {
+            let hours = usage.audio_secs / 3600.0;
+            let totals = providers
+                .entry(provider.clone())
+                .or_insert_with(|| ProviderStats {
+                    provider: provider.clone(),
+                    audio_hours: 0.0,
+                    estimated_cost: 0.0,
+                });
+            totals.audio_hours += hours;
+            stats.audio_hours += hours;
+            match usage.cost.filter(|_| same_currency) {
+                Some(amount) => {
+                    totals.estimated_cost += amount;
+                    stats.estimated_cost += amount;
+                }
+                None => stats.unpriced_hours += hours,
+            }
+        }
+        stats.meetings.push(MeetingStats {
+            meeting_id,
+            audio_hours: cost.audio_hours(),
+            estimated_cost: cost.estimated_cost(),
+            currency: cost.currency,
+            updated_at: cost.updated_at,
+        });
+    }
+    stats.providers = providers.into_values().collect();
+    stats
+        .meetings
+        .sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    stats
+}
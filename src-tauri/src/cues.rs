@@ -0,0 +1,144 @@
+use crate::settings::SpeakerCueSettings;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// Diarization can flip back and forth within a sentence; change cues closer
+/// together than this are dropped.
+const MIN_CHANGE_INTERVAL: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CueKind {
+    SpeakerChanged,
+    /// One of the configured people started speaking.
+    PersonSpeaking,
+}
+
+/// Payload of `speaker-cue`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerCue {
+    pub kind: CueKind,
+    pub speaker: String,
+}
+
+/// Follows who is speaking through a recording and decides when to cue.
+pub struct SpeakerCues {
+    settings: SpeakerCueSettings,
+    last_speaker: Option<String>,
+    last_change_cue: Option<Instant>,
+}
+
+impl SpeakerCues {
+    /// `None` when cues are turned off.
+    pub fn new(settings: SpeakerCueSettings) -> Option<Self> {
+        settings.enabled.then_some(Self {
+            settings,
+            last_speaker: None,
+            last_change_cue: None,
+        })
+    }
+
+    /// Cue for a run of speech by `speaker`, if it should get one. Runs
+    /// without a speaker label are ignored.
+    pub fn observe(&mut self, speaker: Option<&str>) -> Option<SpeakerCue> {
+        let speaker = speaker.filter(|speaker| *speaker != "UU")?;
+        if self.last_speaker.as_deref() == Some(speaker) {
+            return None;
+        }
+        let first = self.last_speaker.replace(speaker.to_string()).is_none();
+
+        let kind = if self
+            .settings
+            .people
+            .iter()
+            .any(|person| person.trim().eq_ignore_ascii_case(speaker))
+        {
+            CueKind::PersonSpeaking
+        } else if self.settings.every_change && !first {
+            let now = Instant::now();
+            if self
+                .last_change_cue
+                .is_some_and(|at| now.duration_since(at) < MIN_CHANGE_INTERVAL)
+            {
+                return None;
+            }
+            self.last_change_cue = Some(now);
+            CueKind::SpeakerChanged
+        } else {
+            return None;
+        };
+        Some(SpeakerCue {
+            kind,
+            speaker: speaker.to_string(),
+        })
+    }
+
+    /// Plays `cue` the ways the user asked for. Never blocks.
+    pub fn play(&self, app: &AppHandle, cue: &SpeakerCue) {
+        if self.settings.sound {
+            platform::play_sound(cue.kind);
+        }
+        if self.settings.haptic {
+            platform::haptic(app, cue.kind);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    #![allow(unexpected_cfgs)] // objc macros probe cfg(feature = "cargo-clippy"), which triggers this lint
+
+    use super::CueKind;
+    use cocoa::base::id;
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::AppHandle;
+
+    const NS_HAPTIC_FEEDBACK_PATTERN_GENERIC: isize = 0;
+    const NS_HAPTIC_FEEDBACK_PATTERN_LEVEL_CHANGE: isize = 2;
+    const NS_HAPTIC_FEEDBACK_PERFORMANCE_TIME_NOW: usize = 1;
+
+    /// Built-in system sounds, so nothing has to ship with the app. System
+    /// audio capture hears these too, but they are too short to transcribe.
+    pub fn play_sound(kind: CueKind) {
+        let path = match kind {
+            CueKind::SpeakerChanged => "/System/Library/Sounds/Tink.aiff",
+            CueKind::PersonSpeaking => "/System/Library/Sounds/Glass.aiff",
+        };
+        tauri::async_runtime::spawn(async move {
+            let played = tokio::process::Command::new("/usr/bin/afplay")
+                .arg(path)
+                .status()
+                .await;
+            if let Err(err) = played {
+                tracing::debug!("Failed to play speaker cue: {}", err);
+            }
+        });
+    }
+
+    pub fn haptic(app: &AppHandle, kind: CueKind) {
+        let pattern = match kind {
+            CueKind::SpeakerChanged => NS_HAPTIC_FEEDBACK_PATTERN_GENERIC,
+            CueKind::PersonSpeaking => NS_HAPTIC_FEEDBACK_PATTERN_LEVEL_CHANGE,
+        };
+        let _ = app.run_on_main_thread(move || unsafe {
+            let performer: id = msg_send![class!(NSHapticFeedbackManager), defaultPerformer];
+            let _: () = msg_send![
+                performer,
+                performFeedbackPattern: pattern
+                performanceTime: NS_HAPTIC_FEEDBACK_PERFORMANCE_TIME_NOW
+            ];
+        });
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::CueKind;
+    use tauri::AppHandle;
+
+    pub fn play_sound(_kind: CueKind) {}
+
+    pub fn haptic(_app: &AppHandle, _kind: CueKind) {}
+}
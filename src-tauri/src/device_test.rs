@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+/// Peaks below this (about -50 dBFS) count as silence.
+pub const SILENCE_PEAK: f32 = 0.003;
+pub const MIN_MEASURE_MS: u64 = 200;
+pub const MAX_MEASURE_MS: u64 = 10_000;
+
+/// Running peak and RMS over a stream of samples.
+#[derive(Debug, Default)]
+pub struct LevelMeter {
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelReport {
+    pub peak: f32,
+    pub rms: f32,
+    pub samples: u64,
+    /// No samples arrived at all, or none rose above the noise floor.
+    pub silent: bool,
+}
+
+impl LevelMeter {
+    pub fn push(&mut self, samples: &[f32]) {
+        for sample in samples {
+            self.peak = self.peak.max(sample.abs());
+            self.sum_squares += f64::from(*sample) * f64::from(*sample);
+        }
+        self.samples += samples.len() as u64;
+    }
+
+    pub fn report(&self) -> LevelReport {
+        let rms = if self.samples == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.samples as f64).sqrt() as f32
+        };
+        LevelReport {
+            peak: self.peak,
+            rms,
+            samples: self.samples,
+            silent: self.peak < SILENCE_PEAK,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ToneTestResult {
+    /// What system audio capture picked up while the tone played.
+    pub system_audio: LevelReport,
+    pub heard: bool,
+}
+
+/// Test tone playback, which relies on macOS's `afplay`.
+#[cfg(target_os = "macos")]
+pub mod tone {
+    use crate::audio::SOURCE_SAMPLE_RATE;
+    use hound::{SampleFormat, WavSpec, WavWriter};
+    use std::path::Path;
+    use std::time::Duration;
+
+    const TONE_FREQUENCY_HZ: f32 = 440.0;
+    const TONE_DURATION: Duration = Duration::from_millis(1200);
+    /// Roughly -12 dBFS, loud enough to measure without being unpleasant.
+    const TONE_AMPLITUDE: f32 = 0.25;
+    const FADE_SAMPLES: usize = SOURCE_SAMPLE_RATE as usize / 100;
+
+    /// Writes a short sine tone with faded edges so it does not click.
+    pub fn write(path: &Path) -> Result<(), String> {
+        let spec = WavSpec {
+            channels: 1,
+            sample_rate: SOURCE_SAMPLE_RATE,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = WavWriter::create(path, spec)
+            .map_err(|e| format!("Failed to create test tone: {}", e))?;
+        let total = (TONE_DURATION.as_secs_f32() * SOURCE_SAMPLE_RATE as f32) as usize;
+        for index in 0..total {
+            let fade = (index.min(total - 1 - index) as f32 / FADE_SAMPLES as f32).min(1.0);
+            let phase = index as f32 * TONE_FREQUENCY_HZ / SOURCE_SAMPLE_RATE as f32;
+            let sample = (phase * std::f32::consts::TAU).sin() * TONE_AMPLITUDE * fade;
+            writer
+                .write_sample((sample * i16::MAX as f32) as i16)
+                .map_err(|e| format!("Failed to write test tone: {}", e))?;
+        }
+        writer
+            .finalize()
+            .map_err(|e| format!("Failed to write test tone: {}", e))
+    }
+
+    /// Plays a sound file through the default output device and waits for it to finish.
+    pub async fn play(path: &Path) -> Result<(), String> {
+        let status = tokio::process::Command::new("/usr/bin/afplay")
+            .arg(path)
+            .kill_on_drop(true)
+            .status()
+            .await
+            .map_err(|e| format!("Failed to play test tone: {}", e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to play test tone: afplay exited with {}",
+                status
+            ))
+        }
+    }
+}
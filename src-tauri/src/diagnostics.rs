@@ -0,0 +1,279 @@
+use crate::apply_settings_patch;
+use crate::capture::check_capture_available;
+use crate::state::{AppState, MAIN_SESSION};
+use crate::{calendar, events, health, logging, output_route, resources, soak, telemetry};
+use jilu_core::http::{self, RetryPolicy};
+use jilu_core::speechmatics::{self, create_jwt, AuthError, RtAuth, DEFAULT_RT_URL};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_opener::OpenerExt;
+use tracing::{debug, info};
+
+#[tauri::command]
+pub async fn get_resource_usage(
+    state: State<'_, AppState>,
+) -> Result<resources::ResourceUsage, String> {
+    Ok(collect_resource_usage(&state))
+}
+
+fn collect_resource_usage(state: &AppState) -> resources::ResourceUsage {
+    let (rss_bytes, cpu_percent) = state
+        .services
+        .process_monitor
+        .lock()
+        .get_or_insert_with(resources::ProcessMonitor::default)
+        .sample();
+    let mixer = state
+        .sessions
+        .lock()
+        .get(MAIN_SESSION)
+        .map(|session| session.mixer_stats.snapshot());
+    let transcript = state.sessions.main.transcript.lock();
+
+    let mut usage = resources::ResourceUsage {
+        rss_bytes,
+        cpu_percent,
+        recording: mixer.is_some(),
+        transcript_turns: transcript.turns().len(),
+        transcript_bytes: transcript.text().len(),
+        ..Default::default()
+    };
+    if let Some(stats) = mixer {
+        usage.screen_queue_samples = stats.screen_queued;
+        usage.mic_queue_samples = stats.mic_queued;
+        usage.pcm_queue_bytes = stats.pcm_queued;
+        usage.dropped_input_samples = stats.dropped_input_samples;
+        usage.dropped_pcm_bytes = stats.dropped_pcm_bytes;
+        usage.notification_sounds = stats.chimes;
+    }
+    usage
+}
+
+/// Emits `resource-usage` periodically while a recording is active.
+pub fn spawn_resource_reporter(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(resources::REPORT_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            if !state.sessions.is_recording() {
+                continue;
+            }
+            let usage = collect_resource_usage(&state);
+            let _ = app.emit(events::RESOURCE_USAGE, usage);
+        }
+    });
+}
+
+/// Simulates a long recording at full speed and reports memory growth.
+#[tauri::command]
+pub async fn run_soak_test(
+    state: State<'_, AppState>,
+    hours: Option<f64>,
+) -> Result<soak::SoakReport, String> {
+    if state.sessions.is_recording() {
+        return Err("Cannot run a soak test while recording".to_string());
+    }
+    let hours = hours.unwrap_or(soak::DEFAULT_SOAK_HOURS);
+    tauri::async_runtime::spawn_blocking(move || soak::run(hours))
+        .await
+        .map_err(|e| format!("Soak test failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn set_log_level(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    level: String,
+) -> Result<(), String> {
+    apply_settings_patch(&app, &state, serde_json::json!({ "logLevel": level }))?;
+    info!("Log level set to {}", level);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn open_logs_folder(
+    app: AppHandle,
+    logging: State<'_, logging::Logging>,
+) -> Result<(), String> {
+    app.opener()
+        .open_path(logging.log_dir().to_string_lossy(), None::<&str>)
+        .map_err(|e| format!("Failed to open logs folder: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_telemetry_settings(
+    state: State<'_, AppState>,
+) -> Result<telemetry::TelemetrySettings, String> {
+    Ok(state.services.telemetry.lock().settings().clone())
+}
+
+#[tauri::command]
+pub async fn set_telemetry_settings(
+    state: State<'_, AppState>,
+    settings: telemetry::TelemetrySettings,
+) -> Result<(), String> {
+    state.services.telemetry.lock().update_settings(settings)
+}
+
+/// The report that would be sent next, for the user to inspect.
+#[tauri::command]
+pub async fn preview_telemetry(
+    state: State<'_, AppState>,
+) -> Result<telemetry::TelemetryReport, String> {
+    Ok(state.services.telemetry.lock().preview())
+}
+
+pub fn spawn_telemetry_reporter(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(telemetry::REPORT_INTERVAL_SECS));
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let (endpoint, report) = {
+                let state = app.state::<AppState>();
+                let mut telemetry = state.services.telemetry.lock();
+                let Some(endpoint) = telemetry.active_endpoint().map(str::to_string) else {
+                    continue;
+                };
+                (endpoint, telemetry.take_report())
+            };
+            if report.is_empty() {
+                continue;
+            }
+
+            // Failed reports go out again with the next one.
+            let request = http::client().post(&endpoint).json(&report);
+            let result = http::send(request, RetryPolicy::ONCE)
+                .await
+                .and_then(|response| response.error_for_status().map_err(Into::into));
+            if let Err(err) = result {
+                debug!("Telemetry upload failed: {}", err);
+                app.state::<AppState>()
+                    .services
+                    .telemetry
+                    .lock()
+                    .restore(report);
+            }
+        }
+    });
+}
+
+/// Checks every integration in parallel for the settings status panel.
+#[tauri::command]
+pub async fn run_health_checks(
+    app: AppHandle,
+    api_key: Option<String>,
+    rt_url: Option<String>,
+) -> Result<Vec<health::HealthCheck>, String> {
+    use health::HealthStatus;
+
+    let api_key = api_key
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+    let rt_url = rt_url
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_RT_URL.to_string());
+    let data_dir = app.path().app_data_dir().ok();
+    let auth = app
+        .state::<AppState>()
+        .stores
+        .settings
+        .lock()
+        .get()
+        .transcription
+        .auth;
+
+    let (api, endpoint, calendar, capture, output, disk) = tokio::join!(
+        health::timed("api_key", "Speechmatics API key", async {
+            let direct = |key: String| {
+                let rt_url = rt_url.clone();
+                async move {
+                    speechmatics::open(&key, Some(&rt_url), RtAuth::ApiKey, None, None)
+                        .await
+                        .map(drop)
+                }
+            };
+            match (api_key, auth) {
+                (Some(key), RtAuth::ApiKey) => health::from_result(
+                    direct(key)
+                        .await
+                        .map(|_| Some("Accepted by the realtime endpoint".to_string())),
+                ),
+                (Some(key), auth) => match create_jwt(key.clone()).await {
+                    Ok(_) => (HealthStatus::Ok, None),
+                    // Recordings fall back to the key itself, so check that works.
+                    Err(AuthError::EndpointUnreachable(reason)) if auth == RtAuth::Auto => {
+                        match direct(key).await {
+                            Ok(()) => (
+                                HealthStatus::Warning,
+                                Some(format!(
+                                    "Auth endpoint unreachable ({}); the realtime endpoint accepts the key directly",
+                                    reason
+                                )),
+                            ),
+                            Err(err) => (HealthStatus::Error, Some(err)),
+                        }
+                    }
+                    Err(err) => (HealthStatus::Error, Some(err.to_string())),
+                },
+                (None, _) => (
+                    HealthStatus::Skipped,
+                    Some("No API key configured".to_string()),
+                ),
+            }
+        }),
+        health::timed(
+            "endpoint",
+            "Transcription endpoint",
+            health::check_endpoint(&rt_url)
+        ),
+        health::timed(
+            "calendar",
+            "Calendar permission",
+            health::blocking(|| match calendar::check_calendar_access() {
+                Ok(true) => (HealthStatus::Ok, None),
+                Ok(false) => (
+                    HealthStatus::Warning,
+                    Some("Calendar access not granted".to_string()),
+                ),
+                Err(err) => (HealthStatus::Skipped, Some(err)),
+            })
+        ),
+        health::timed(
+            "capture",
+            "System audio capture",
+            health::blocking(check_capture_available)
+        ),
+        health::timed(
+            "output",
+            "Output device",
+            health::blocking(|| match output_route::current() {
+                Ok(route) => match route.warning {
+                    Some(warning) if !route.capturable => (
+                        HealthStatus::Warning,
+                        Some(format!("{}: {}", route.device, warning)),
+                    ),
+                    _ => (HealthStatus::Ok, Some(route.device)),
+                },
+                Err(err) => (HealthStatus::Skipped, Some(err)),
+            })
+        ),
+        health::timed(
+            "disk",
+            "Disk space",
+            health::blocking(move || match data_dir {
+                Some(dir) => health::check_disk_space(&dir),
+                None => (
+                    HealthStatus::Warning,
+                    Some("App data directory unavailable".to_string()),
+                ),
+            })
+        ),
+    );
+
+    Ok(vec![api, endpoint, calendar, capture, output, disk])
+}
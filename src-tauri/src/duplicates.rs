@@ -0,0 +1,270 @@
+//! Meetings recorded twice. When a session breaks and is started again for
+//! the same calendar event, the second recording often lands in a meeting of
+//! its own; `merge_meetings` puts the two back together.
+
+use crate::consent::{self, MeetingConsent};
+use crate::meeting_store;
+use crate::state::AppState;
+use crate::transcript::TranscriptTurnPayload;
+use crate::transcript_export::TRANSCRIPT_FILE;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tauri::{AppHandle, Manager, State};
+use tracing::{info, warn};
+
+/// Recordings of one event further apart than this are separate meetings.
+const MAX_RESTART_GAP_SECS: f64 = 15.0 * 60.0;
+/// Share of words two turns need in common to be the same speech heard twice.
+const SAME_SPEECH_SIMILARITY: f64 = 0.6;
+
+/// A meeting the frontend knows is linked to a calendar event.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedMeeting {
+    pub id: String,
+    pub calendar_event_id: String,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateMeetings {
+    /// The earlier recording, which a merge keeps.
+    pub keep_id: String,
+    /// The later recording, merged into `keep_id`.
+    pub duplicate_id: String,
+    pub calendar_event_id: String,
+    /// Seconds between the end of the first recording and the start of the
+    /// second; 0 when they overlap.
+    pub gap_secs: f64,
+}
+
+/// When a meeting's saved transcript starts and ends.
+fn recorded_span(dir: &Path) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let turns = load_turns(dir).ok()?;
+    let start = turns.iter().find_map(|turn| wall_time(&turn.started_at))?;
+    let end = turns
+        .iter()
+        .rev()
+        .find_map(|turn| wall_time(&turn.ended_at).or_else(|| wall_time(&turn.started_at)))?;
+    Some((start, end.max(start)))
+}
+
+fn wall_time(value: &Option<String>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_deref()?)
+        .ok()
+        .map(|at| at.with_timezone(&Utc))
+}
+
+fn load_turns(dir: &Path) -> Result<Vec<TranscriptTurnPayload>, String> {
+    match std::fs::read_to_string(dir.join(TRANSCRIPT_FILE)) {
+        Ok(contents) => {
+            serde_json::from_str(&contents).map_err(|e| format!("Failed to read transcript: {}", e))
+        }
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// A meeting with when its recording started and ended.
+type Recording<'a> = (&'a str, DateTime<Utc>, DateTime<Utc>);
+
+/// Pairs of meetings of one calendar event whose recordings overlap or
+/// follow each other within a few minutes.
+pub fn find(meetings_root: &Path, meetings: &[LinkedMeeting]) -> Vec<DuplicateMeetings> {
+    let mut by_event: HashMap<&str, Vec<Recording>> = HashMap::new();
+    for meeting in meetings {
+        if meeting_store::validate_meeting_id(&meeting.id).is_err() {
+            continue;
+        }
+        if let Some((start, end)) = recorded_span(&meetings_root.join(&meeting.id)) {
+            by_event
+                .entry(meeting.calendar_event_id.as_str())
+                .or_default()
+                .push((meeting.id.as_str(), start, end));
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (event, mut recordings) in by_event {
+        recordings.sort_by_key(|&(id, start, _)| (start, id));
+        for window in recordings.windows(2) {
+            let (keep_id, _, keep_end) = window[0];
+            let (duplicate_id, duplicate_start, _) = window[1];
+            let gap_secs = (duplicate_start - keep_end).num_milliseconds().max(0) as f64 / 1000.0;
+            if gap_secs <= MAX_RESTART_GAP_SECS {
+                pairs.push(DuplicateMeetings {
+                    keep_id: keep_id.to_string(),
+                    duplicate_id: duplicate_id.to_string(),
+                    calendar_event_id: event.to_string(),
+                    gap_secs,
+                });
+            }
+        }
+    }
+    pairs.sort_by(|a, b| a.keep_id.cmp(&b.keep_id));
+    pairs
+}
+
+/// Both transcripts in the order they were spoken, without the turns the
+/// second recording heard again while the first was still running. Turns of
+/// `duplicate` are moved onto the clock of `keep`.
+pub fn stitch(
+    keep: Vec<TranscriptTurnPayload>,
+    duplicate: Vec<TranscriptTurnPayload>,
+) -> Vec<TranscriptTurnPayload> {
+    // Where `keep`'s offsets start, in wall-clock time.
+    let anchor = keep.iter().find_map(|turn| {
+        let at = wall_time(&turn.started_at)?;
+        Some(at - chrono::Duration::milliseconds((turn.offset? * 1000.0) as i64))
+    });
+    let duplicate: Vec<TranscriptTurnPayload> = duplicate
+        .into_iter()
+        .filter(|turn| !keep.iter().any(|kept| same_speech(kept, turn)))
+        .map(|mut turn| {
+            let rebase = |at: &Option<String>| {
+                let at = wall_time(at)?;
+                Some((at - anchor?).num_milliseconds() as f64 / 1000.0)
+            };
+            turn.offset = rebase(&turn.started_at);
+            turn.end_offset = rebase(&turn.ended_at);
+            turn
+        })
+        .collect();
+
+    let keyed = |turns: Vec<TranscriptTurnPayload>, source: u8| {
+        // Turns without a time of their own go with the turn before them.
+        let mut last = turns.iter().find_map(|turn| wall_time(&turn.started_at));
+        turns
+            .into_iter()
+            .enumerate()
+            .map(|(index, turn)| {
+                if let Some(at) = wall_time(&turn.started_at) {
+                    last = Some(at);
+                }
+                ((last, source, index), turn)
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut merged = keyed(keep, 0);
+    merged.extend(keyed(duplicate, 1));
+    // Untimed transcripts have no `last`, which sorts them first; keep them
+    // in their own order, `keep` before `duplicate`.
+    merged.sort_by_key(|(key, _)| *key);
+    merged.into_iter().map(|(_, turn)| turn).collect()
+}
+
+/// Whether `turn` is speech `kept` already has: the two overlap in time and
+/// mostly share their words.
+fn same_speech(kept: &TranscriptTurnPayload, turn: &TranscriptTurnPayload) -> bool {
+    let span = |turn: &TranscriptTurnPayload| {
+        let start = wall_time(&turn.started_at)?;
+        Some((start, wall_time(&turn.ended_at).unwrap_or(start)))
+    };
+    let (Some((kept_start, kept_end)), Some((start, end))) = (span(kept), span(turn)) else {
+        return false;
+    };
+    if start > kept_end || end < kept_start {
+        return false;
+    }
+    let words = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(&kept.text), words(&turn.text));
+    let smaller = a.len().min(b.len());
+    smaller > 0 && a.intersection(&b).count() as f64 / smaller as f64 >= SAME_SPEECH_SIMILARITY
+}
+
+/// Moves what `duplicate_dir` holds beyond its transcript into `keep_dir`:
+/// its audio files and the consent of attendees `keep_dir` has none for.
+fn move_records(keep_dir: &Path, duplicate_dir: &Path) -> Result<(), String> {
+    if let Ok(entries) = std::fs::read_dir(duplicate_dir) {
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name();
+            let is_audio = name.to_string_lossy().starts_with("recording-");
+            let target = keep_dir.join(&name);
+            if is_audio && !target.exists() {
+                std::fs::rename(entry.path(), &target)
+                    .map_err(|e| format!("Failed to move {}: {}", name.to_string_lossy(), e))?;
+            }
+        }
+    }
+
+    let duplicate = MeetingConsent::load(&duplicate_dir.join(consent::CONSENT_FILE))?;
+    if duplicate.attendees.is_empty() {
+        return Ok(());
+    }
+    let path = keep_dir.join(consent::CONSENT_FILE);
+    let mut keep = MeetingConsent::load(&path)?;
+    keep.exclude_declined |= duplicate.exclude_declined;
+    for attendee in duplicate.attendees {
+        if !keep
+            .attendees
+            .iter()
+            .any(|entry| entry.email == attendee.email)
+        {
+            keep.attendees.push(attendee);
+        }
+    }
+    keep.save(&path)
+}
+
+/// Meetings of the same calendar event that look like one meeting recorded
+/// twice. `meetings` are those the frontend has linked to an event.
+#[tauri::command]
+pub async fn find_duplicate_meetings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meetings: Vec<LinkedMeeting>,
+) -> Result<Vec<DuplicateMeetings>, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = state.stores.profiles.lock().active().clone();
+    let root = meeting_store::meetings_root(&profile, &data_dir);
+    tauri::async_runtime::spawn_blocking(move || find(&root, &meetings))
+        .await
+        .map_err(|e| format!("Failed to look for duplicate meetings: {}", e))
+}
+
+/// Merges the transcript, audio and consent of `duplicate_id` into
+/// `keep_id`, removes what is left of `duplicate_id` from the meeting store,
+/// and returns the merged transcript.
+#[tauri::command]
+pub async fn merge_meetings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    keep_id: String,
+    duplicate_id: String,
+) -> Result<Vec<TranscriptTurnPayload>, String> {
+    if keep_id == duplicate_id {
+        return Err("Cannot merge a meeting into itself".to_string());
+    }
+    let recording = state.sessions.lock().values().any(|session| {
+        [&keep_id, &duplicate_id]
+            .iter()
+            .any(|id| session.meeting_id.as_ref() == Some(*id))
+    });
+    if recording {
+        return Err("Stop recording before merging this meeting".to_string());
+    }
+    let keep_dir = meeting_store::meeting_dir(&app, &keep_id)?;
+    let duplicate_dir = meeting_store::meeting_dir(&app, &duplicate_id)?;
+
+    let turns = stitch(load_turns(&keep_dir)?, load_turns(&duplicate_dir)?);
+    move_records(&keep_dir, &duplicate_dir)?;
+    let turns = consent::redact(&keep_dir.join(consent::CONSENT_FILE), &turns)?.into_owned();
+    crate::storage::save_transcript_turns(&keep_dir.join(TRANSCRIPT_FILE), &turns)?;
+    if let Err(err) = std::fs::remove_dir_all(&duplicate_dir) {
+        warn!("Left the merged meeting's folder behind: {}", err);
+    }
+    info!(
+        turns = turns.len(),
+        "Merged meeting {} into {}", duplicate_id, keep_id
+    );
+    Ok(turns)
+}
@@ -0,0 +1,329 @@
+use serde::Serialize;
+
+/// Version of the event contract below. Bumped when an event is renamed or
+/// removed, or its payload changes in a way existing listeners would misread;
+/// new events and new optional payload fields leave it as it is.
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+// Transcription
+pub const TRANSCRIPT_UPDATE: &str = "transcript-update";
+pub const SPEAKERS_RESULT: &str = "speakers-result";
+pub const INTERVIEW_QUESTION: &str = "interview-question";
+pub const SPEAKER_CUE: &str = "speaker-cue";
+pub const LANGUAGE_CHANGED: &str = "language-changed";
+pub const LANGUAGE_SWITCH_FAILED: &str = "language-switch-failed";
+pub const LANGUAGE_MISMATCH_SUSPECTED: &str = "language-mismatch-suspected";
+pub const VOCAB_UPDATED: &str = "vocab-updated";
+pub const VOCAB_UPDATE_FAILED: &str = "vocab-update-failed";
+pub const POWER_POLICY_CHANGED: &str = "power-policy-changed";
+
+// Session lifecycle
+pub const RECORDING_ERROR: &str = "recording-error";
+pub const RECORDING_ENDED: &str = "recording-ended";
+pub const RECORDING_SUSPENDED: &str = "recording-suspended";
+pub const RECORDING_PAUSED: &str = "recording-paused";
+pub const RECORDING_RESUMED: &str = "recording-resumed";
+pub const RECORDING_RESUME_FAILED: &str = "recording-resume-failed";
+pub const RECORDING_RECONNECTING: &str = "recording-reconnecting";
+pub const RECORDING_RECONNECTED: &str = "recording-reconnected";
+pub const RESUME_OFFERED: &str = "resume-offered";
+pub const RECORDING_STOPPED_IN_BACKGROUND: &str = "recording-stopped-in-background";
+pub const MUTE_STATUS_CHANGED: &str = "mute-status-changed";
+pub const SILENCE_WARNING: &str = "silence-warning";
+pub const SILENCE_CLEARED: &str = "silence-cleared";
+pub const SILENCE_AUTO_STOP: &str = "silence-auto-stop";
+pub const AGENDA_CHANGED: &str = "agenda-changed";
+pub const AGENDA_ITEM_COVERED: &str = "agenda-item-covered";
+pub const VOICE_COMMAND: &str = "voice-command";
+
+// Audio
+pub const AUDIO_LEVEL: &str = "audio-level";
+pub const CAPTURE_STARTED: &str = "capture-started";
+pub const CAPTURE_STOPPED: &str = "capture-stopped";
+pub const CAPTURE_ERROR: &str = "capture-error";
+pub const CAPTURE_RETARGETED: &str = "capture-retargeted";
+pub const MIC_CAPTURE_MOVED: &str = "mic-capture-moved";
+pub const OUTPUT_ROUTE_CHANGED: &str = "output-route-changed";
+
+// App
+pub const SETTINGS_CHANGED: &str = "settings-changed";
+pub const PROFILE_CHANGED: &str = "profile-changed";
+pub const ONBOARDING_CHANGED: &str = "onboarding-changed";
+pub const LAUNCH_INTENT: &str = "launch-intent";
+pub const GLOBAL_SHORTCUT_TRIGGERED: &str = "global-shortcut-triggered";
+pub const MUTE_SHORTCUT_TRIGGERED: &str = "mute-shortcut-triggered";
+pub const SHARING_STOPPED: &str = "sharing-stopped";
+pub const SCREEN_SHARE_CHANGED: &str = "screen-share-changed";
+pub const REMOTE_PAIRED: &str = "remote-paired";
+pub const PAIRING_STOPPED: &str = "pairing-stopped";
+pub const HOOK_FINISHED: &str = "hook-finished";
+pub const BULK_EXPORT_PROGRESS: &str = "bulk-export-progress";
+pub const LOCAL_RECORDING_FOUND: &str = "local-recording-found";
+pub const UPDATE_AVAILABLE: &str = "update-available";
+pub const UPDATE_DOWNLOAD_PROGRESS: &str = "update-download-progress";
+pub const UPDATE_INSTALLED: &str = "update-installed";
+pub const RESOURCE_USAGE: &str = "resource-usage";
+pub const STARTUP_PROFILE: &str = "startup-profile";
+
+/// Who an event is about, which decides the name it is sent under.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum EventScope {
+    /// Sent under its own name.
+    App,
+    /// About one recording. Sessions other than the main one send it as
+    /// `session/<id>/<name>`.
+    Session,
+}
+
+/// One event the backend emits.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSpec {
+    pub name: &'static str,
+    pub scope: EventScope,
+    /// Shape of the payload in TypeScript notation; `null` for events without one.
+    pub payload: &'static str,
+    pub description: &'static str,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSchema {
+    pub version: u32,
+    pub events: &'static [EventSpec],
+}
+
+const fn session(
+    name: &'static str,
+    payload: &'static str,
+    description: &'static str,
+) -> EventSpec {
+    EventSpec {
+        name,
+        scope: EventScope::Session,
+        payload,
+        description,
+    }
+}
+
+const fn app(name: &'static str, payload: &'static str, description: &'static str) -> EventSpec {
+    EventSpec {
+        name,
+        scope: EventScope::App,
+        payload,
+        description,
+    }
+}
+
+pub const EVENTS: &[EventSpec] = &[
+    session(
+        TRANSCRIPT_UPDATE,
+        "{ text?: string; delta_text?: string; is_partial: boolean; turns?: TranscriptTurn[]; turn_offset?: number; total_turns?: number }",
+        "New partial text, or the turns changed by a final result or a typed note starting at turn_offset, with delta_text rendering just those turns",
+    ),
+    session(
+        SPEAKERS_RESULT,
+        "CapturedSpeaker[]",
+        "Voice identifiers of the session's speakers, for enrolling them",
+    ),
+    session(
+        INTERVIEW_QUESTION,
+        "DetectedQuestion",
+        "The local speaker asked a question, once the turn after it has started",
+    ),
+    session(
+        SPEAKER_CUE,
+        "SpeakerCue",
+        "The speaker changed, or one of the configured people started speaking",
+    ),
+    session(
+        LANGUAGE_CHANGED,
+        "string",
+        "The session continues in this language",
+    ),
+    session(
+        LANGUAGE_SWITCH_FAILED,
+        "string",
+        "Why the language could not be changed; the old one stays",
+    ),
+    session(
+        LANGUAGE_MISMATCH_SUSPECTED,
+        "{ language: string; suggestedLanguage: string | null; averageConfidence: number | null; reason: 'lowConfidence' | 'providerLanguage' }",
+        "The first minute of speech does not sound like the transcription language",
+    ),
+    session(
+        VOCAB_UPDATED,
+        "number",
+        "The session continues with a vocabulary of this many entries",
+    ),
+    session(
+        VOCAB_UPDATE_FAILED,
+        "string",
+        "Why the vocabulary could not be changed; the old one stays",
+    ),
+    session(
+        POWER_POLICY_CHANGED,
+        "{ mode: 'normal' | 'saving' | 'hot'; batchMs: number; onBattery: boolean; lowPower: boolean; thermal: 'nominal' | 'fair' | 'serious' | 'critical' }",
+        "Audio now goes out every batchMs, to save power or back at the usual rate",
+    ),
+    session(RECORDING_ERROR, "string", "Transcription failed"),
+    session(
+        RECORDING_ENDED,
+        "null",
+        "The last results are in and saved; sent once per recording",
+    ),
+    session(
+        RECORDING_SUSPENDED,
+        "null",
+        "Paused because the system is going to sleep",
+    ),
+    session(
+        RECORDING_PAUSED,
+        "null",
+        "Stopped sending audio until resume_recording",
+    ),
+    session(
+        RECORDING_RESUMED,
+        "null",
+        "Carried on after a pause or system sleep",
+    ),
+    session(
+        RECORDING_RESUME_FAILED,
+        "string",
+        "Could not carry on after a pause or system sleep",
+    ),
+    session(
+        RECORDING_RECONNECTING,
+        "{ attempt: number; maxAttempts: number; delayMs: number }",
+        "The connection dropped; trying again after the delay",
+    ),
+    session(
+        RECORDING_RECONNECTED,
+        "null",
+        "Carried on after the connection dropped",
+    ),
+    session(
+        RESUME_OFFERED,
+        "{ meetingId: string | null; meetingTitle: string | null }",
+        "A recording stopped by sleep can be resumed",
+    ),
+    app(
+        RECORDING_STOPPED_IN_BACKGROUND,
+        "string | null",
+        "Stopped from the tray with the window closed; carries the meeting id",
+    ),
+    session(MUTE_STATUS_CHANGED, "boolean", "Muted or unmuted"),
+    session(
+        SILENCE_WARNING,
+        "SilenceWarning",
+        "No sound for a while; the recording may have been left running",
+    ),
+    session(SILENCE_CLEARED, "null", "Sound came back after a warning"),
+    session(
+        SILENCE_AUTO_STOP,
+        "null",
+        "Stopping because it stayed silent",
+    ),
+    app(AGENDA_CHANGED, "AgendaItem[]", "The agenda was set or edited"),
+    session(
+        AGENDA_ITEM_COVERED,
+        "AgendaItem",
+        "The transcript touched on an agenda item",
+    ),
+    app(VOICE_COMMAND, "VoiceCommand", "A spoken command was heard"),
+    app(
+        AUDIO_LEVEL,
+        "number",
+        "RMS of the captured system audio, at most every 30 ms",
+    ),
+    app(CAPTURE_STARTED, "null", "System audio capture started"),
+    app(CAPTURE_STOPPED, "null", "System audio capture stopped"),
+    app(CAPTURE_ERROR, "string", "System audio capture failed"),
+    app(
+        CAPTURE_RETARGETED,
+        "CaptureRetarget",
+        "The captured display slept or was disconnected; capture moved to another",
+    ),
+    app(
+        MIC_CAPTURE_MOVED,
+        "null",
+        "The backend took over the microphone when the window closed",
+    ),
+    app(
+        OUTPUT_ROUTE_CHANGED,
+        "OutputRoute",
+        "System audio now plays to another device",
+    ),
+    app(SETTINGS_CHANGED, "Settings", "Settings were changed"),
+    app(PROFILE_CHANGED, "Profile", "Another profile is active"),
+    app(
+        ONBOARDING_CHANGED,
+        "OnboardingState",
+        "An onboarding step was completed",
+    ),
+    app(
+        LAUNCH_INTENT,
+        "LaunchRequest",
+        "Asked from a URL, the command line or a shortcut to start or stop recording",
+    ),
+    app(
+        GLOBAL_SHORTCUT_TRIGGERED,
+        "null",
+        "The new meeting shortcut was pressed",
+    ),
+    app(MUTE_SHORTCUT_TRIGGERED, "null", "The mute shortcut was pressed"),
+    app(SHARING_STOPPED, "null", "The guest link closed"),
+    app(
+        SCREEN_SHARE_CHANGED,
+        "ScreenShareStatus",
+        "Screen sharing started or stopped; transcript text may be held back",
+    ),
+    app(REMOTE_PAIRED, "PairedDevice", "A phone paired as the remote"),
+    app(PAIRING_STOPPED, "null", "The phone remote closed"),
+    app(HOOK_FINISHED, "HookOutcome", "A hook command has run"),
+    app(
+        BULK_EXPORT_PROGRESS,
+        "ExportProgress",
+        "Progress of export_all_meetings",
+    ),
+    app(
+        LOCAL_RECORDING_FOUND,
+        "FoundRecording",
+        "Zoom, Teams or a watched folder has a new meeting recording to import",
+    ),
+    app(
+        UPDATE_AVAILABLE,
+        "UpdateInfo",
+        "An update can be installed; held back while recording",
+    ),
+    app(
+        UPDATE_DOWNLOAD_PROGRESS,
+        "{ downloaded: number; total: number | null }",
+        "Progress of install_update",
+    ),
+    app(
+        UPDATE_INSTALLED,
+        "UpdateInfo",
+        "The update is installed and the app restarts",
+    ),
+    app(
+        RESOURCE_USAGE,
+        "ResourceUsage",
+        "CPU, memory and queue use, periodically while recording",
+    ),
+    app(
+        STARTUP_PROFILE,
+        "StartupProfile",
+        "How long launch took; debug builds only",
+    ),
+];
+
+/// Every event the backend emits, so other frontends can check they match.
+#[tauri::command]
+pub async fn get_event_schema() -> Result<EventSchema, String> {
+    Ok(EventSchema {
+        version: EVENT_SCHEMA_VERSION,
+        events: EVENTS,
+    })
+}
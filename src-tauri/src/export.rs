@@ -0,0 +1,157 @@
+//! A finished meeting as a formatted document: title, date, attendees and
+//! the transcript turn by turn under each speaker's name, written as DOCX or
+//! PDF by writers of our own.
+
+mod docx;
+mod pdf;
+
+use crate::chapters::Chapter;
+use crate::locale::Locale;
+use crate::state::AppState;
+use crate::transcript::{TranscriptTurnPayload, TurnSource};
+use crate::{formatting, meeting_store, transcript_export};
+
+use serde::Deserialize;
+use tauri::{AppHandle, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    Docx,
+    Pdf,
+}
+
+impl std::str::FromStr for DocumentFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "docx" => Ok(DocumentFormat::Docx),
+            "pdf" => Ok(DocumentFormat::Pdf),
+            other => Err(format!(
+                "Unknown document format '{}'; expected docx or pdf",
+                other
+            )),
+        }
+    }
+}
+
+/// What the frontend knows about the meeting being exported.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentInfo {
+    pub id: String,
+    pub title: Option<String>,
+    /// Start of the meeting (RFC 3339).
+    pub date: Option<String>,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+}
+
+/// Everything a document shows, ready to lay out.
+pub struct MeetingDocument {
+    pub title: String,
+    /// Already formatted for reading, e.g. `Tuesday 5 March 2024, 15:30`.
+    pub date: Option<String>,
+    pub attendees: Vec<String>,
+    pub turns: Vec<TranscriptTurnPayload>,
+    pub chapters: Vec<Chapter>,
+    pub locale: Locale,
+}
+
+impl MeetingDocument {
+    /// The chapter that starts at turn `index`, if one does.
+    fn chapter_at(&self, index: usize) -> Option<&Chapter> {
+        self.chapters
+            .iter()
+            .find(|chapter| chapter.start_turn == index)
+    }
+
+    /// `00:12:05`, when the turn knows where it is in the recording.
+    fn timestamp(turn: &TranscriptTurnPayload) -> Option<String> {
+        turn.offset
+            .or(turn.start)
+            .map(transcript_export::clock_timestamp)
+    }
+
+    /// The line under the title: the date, then who attended.
+    fn attendee_line(&self) -> Option<String> {
+        (!self.attendees.is_empty()).then(|| format!("Attendees: {}", self.attendees.join(", ")))
+    }
+
+    pub fn render(&self, format: DocumentFormat) -> Vec<u8> {
+        match format {
+            DocumentFormat::Docx => docx::render(self),
+            DocumentFormat::Pdf => pdf::render(self),
+        }
+    }
+}
+
+/// Writes meeting `meeting.id`'s saved transcript to `path` as a DOCX or PDF
+/// document headed by its title, date and attendees. `source` keeps only
+/// what was said on the microphone, or only what came over the call.
+#[tauri::command]
+pub async fn export_meeting_document(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting: DocumentInfo,
+    format: String,
+    path: String,
+    source: Option<TurnSource>,
+) -> Result<(), String> {
+    let format: DocumentFormat = format.parse()?;
+    let path = std::path::PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err("Export path must be an absolute path".to_string());
+    }
+    let dir = meeting_store::meeting_dir(&app, &meeting.id)?;
+    let contents = std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE))
+        .map_err(|_| format!("No transcript saved for meeting {}", meeting.id))?;
+    let mut turns: Vec<TranscriptTurnPayload> =
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to read transcript: {}", e))?;
+    let mut chapters = crate::storage::saved_chapters(&dir)?;
+    if let Some(source) = source {
+        transcript_export::keep_source(&mut turns, &mut chapters, source);
+    }
+
+    let (locale, formatting, normalizer) = crate::storage::export_settings(&state);
+    formatting::apply_to_turns(&mut turns, &formatting);
+    if let Some(normalizer) = &normalizer {
+        normalizer.apply_to_turns(&mut turns);
+    }
+    let date = meeting
+        .date
+        .as_deref()
+        .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+        .map(|date| {
+            date.with_timezone(&chrono::Local)
+                .format("%A %-d %B %Y, %H:%M")
+                .to_string()
+        });
+    let document = MeetingDocument {
+        title: meeting
+            .title
+            .map(|title| title.trim().to_string())
+            .filter(|title| !title.is_empty())
+            .unwrap_or_else(|| "Meeting".to_string()),
+        date,
+        attendees: meeting
+            .attendees
+            .into_iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect(),
+        turns,
+        chapters,
+        locale,
+    };
+
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("document_export");
+    let bytes = tauri::async_runtime::spawn_blocking(move || document.render(format))
+        .await
+        .map_err(|e| format!("Export failed: {}", e))?;
+    std::fs::write(&path, bytes).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
@@ -0,0 +1,224 @@
+//! A minimal Word document: one `document.xml` of paragraphs with direct run
+//! formatting, in a zip archive whose entries are stored uncompressed.
+
+use super::MeetingDocument;
+use std::fmt::Write;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const RELATIONSHIPS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+/// Half-points, as Word counts font sizes.
+const TITLE_SIZE: u32 = 36;
+const HEADING_SIZE: u32 = 28;
+const GREY: &str = "6B6B6B";
+
+#[derive(Default, Clone, Copy)]
+struct RunStyle {
+    bold: bool,
+    size: Option<u32>,
+    color: Option<&'static str>,
+}
+
+pub fn render(doc: &MeetingDocument) -> Vec<u8> {
+    let mut body = String::new();
+    paragraph(
+        &mut body,
+        &[(
+            &doc.title,
+            RunStyle {
+                bold: true,
+                size: Some(TITLE_SIZE),
+                ..Default::default()
+            },
+        )],
+    );
+    let grey = RunStyle {
+        color: Some(GREY),
+        ..Default::default()
+    };
+    if let Some(date) = &doc.date {
+        paragraph(&mut body, &[(date, grey)]);
+    }
+    if let Some(attendees) = doc.attendee_line() {
+        paragraph(&mut body, &[(&attendees, RunStyle::default())]);
+    }
+
+    for (index, turn) in doc.turns.iter().enumerate() {
+        if let Some(chapter) = doc.chapter_at(index) {
+            paragraph(
+                &mut body,
+                &[(
+                    &chapter.title,
+                    RunStyle {
+                        bold: true,
+                        size: Some(HEADING_SIZE),
+                        ..Default::default()
+                    },
+                )],
+            );
+        }
+        let timestamp = MeetingDocument::timestamp(turn).map(|at| format!("{} ", at));
+        let speaker = turn.label(doc.locale);
+        let mut runs: Vec<(&str, RunStyle)> = Vec::new();
+        if let Some(timestamp) = &timestamp {
+            runs.push((timestamp, grey));
+        }
+        if let Some(speaker) = &speaker {
+            runs.push((
+                speaker,
+                RunStyle {
+                    bold: true,
+                    ..Default::default()
+                },
+            ));
+        }
+        runs.push((&turn.text, RunStyle::default()));
+        paragraph(&mut body, &runs);
+    }
+
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}<w:sectPr><w:pgSz w:w="11906" w:h="16838"/><w:pgMar w:top="1134" w:right="1134" w:bottom="1134" w:left="1134" w:header="709" w:footer="709" w:gutter="0"/></w:sectPr></w:body></w:document>"#,
+        body
+    );
+    let core = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>{}</dc:title><dc:creator>Jilu</dc:creator></cp:coreProperties>"#,
+        escape(&doc.title)
+    );
+
+    let mut zip = ZipWriter::default();
+    zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes());
+    zip.add("_rels/.rels", RELATIONSHIPS.as_bytes());
+    zip.add("docProps/core.xml", core.as_bytes());
+    zip.add("word/document.xml", document.as_bytes());
+    zip.finish()
+}
+
+fn paragraph(out: &mut String, runs: &[(&str, RunStyle)]) {
+    out.push_str("<w:p>");
+    for (text, style) in runs {
+        out.push_str("<w:r>");
+        if style.bold || style.size.is_some() || style.color.is_some() {
+            out.push_str("<w:rPr>");
+            if style.bold {
+                out.push_str("<w:b/>");
+            }
+            if let Some(color) = style.color {
+                let _ = write!(out, r#"<w:color w:val="{}"/>"#, color);
+            }
+            if let Some(size) = style.size {
+                let _ = write!(out, r#"<w:sz w:val="{}"/>"#, size);
+            }
+            out.push_str("</w:rPr>");
+        }
+        // Line breaks within a turn become breaks within its paragraph.
+        for (index, line) in text.split('\n').enumerate() {
+            if index > 0 {
+                out.push_str("<w:br/>");
+            }
+            let _ = write!(out, r#"<w:t xml:space="preserve">{}</w:t>"#, escape(line));
+        }
+        out.push_str("</w:r>");
+    }
+    out.push_str("</w:p>");
+}
+
+/// Text as XML character data, without the control characters XML 1.0 forbids.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' => out.push(' '),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Writes a zip archive of stored entries, which every DOCX reader accepts.
+#[derive(Default)]
+struct ZipWriter {
+    out: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl ZipWriter {
+    fn add(&mut self, name: &str, data: &[u8]) {
+        let offset = self.out.len() as u32;
+        let crc = crc32(data);
+        let size = data.len() as u32;
+
+        // Local file header.
+        self.out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // flags
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // stored
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // time
+        self.out.extend_from_slice(&0x21u16.to_le_bytes()); // date: 1980-01-01
+        self.out.extend_from_slice(&crc.to_le_bytes());
+        self.out.extend_from_slice(&size.to_le_bytes());
+        self.out.extend_from_slice(&size.to_le_bytes());
+        self.out
+            .extend_from_slice(&(name.len() as u16).to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // extra length
+        self.out.extend_from_slice(name.as_bytes());
+        self.out.extend_from_slice(data);
+
+        // Its central directory entry.
+        self.central
+            .extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        self.central.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        self.central.extend_from_slice(&0u16.to_le_bytes());
+        self.central.extend_from_slice(&0u16.to_le_bytes());
+        self.central.extend_from_slice(&0u16.to_le_bytes());
+        self.central.extend_from_slice(&0x21u16.to_le_bytes());
+        self.central.extend_from_slice(&crc.to_le_bytes());
+        self.central.extend_from_slice(&size.to_le_bytes());
+        self.central.extend_from_slice(&size.to_le_bytes());
+        self.central
+            .extend_from_slice(&(name.len() as u16).to_le_bytes());
+        self.central.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let offset = self.out.len() as u32;
+        let size = self.central.len() as u32;
+        self.out.append(&mut self.central);
+        self.out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        self.out.extend_from_slice(&[0; 4]); // disk numbers
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&self.entries.to_le_bytes());
+        self.out.extend_from_slice(&size.to_le_bytes());
+        self.out.extend_from_slice(&offset.to_le_bytes());
+        self.out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        self.out
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |mut crc, byte| {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+        crc
+    })
+}
@@ -0,0 +1,448 @@
+//! A PDF of the meeting set in the standard Helvetica fonts, which every
+//! reader has, so nothing needs embedding. Text is laid out here: words are
+//! measured with the fonts' published widths, wrapped and paginated onto A4.
+
+use super::MeetingDocument;
+use std::fmt::Write;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+const TEXT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+
+const TITLE_SIZE: f32 = 18.0;
+const HEADING_SIZE: f32 = 13.0;
+const BODY_SIZE: f32 = 10.0;
+const FOOTER_SIZE: f32 = 8.0;
+/// Line height as a multiple of the font size.
+const LEADING: f32 = 1.4;
+const PARAGRAPH_GAP: f32 = 6.0;
+const GREY: &str = "0.42 g";
+
+/// Glyph widths of `' '..='~'` in thousandths of the font size, from the
+/// Adobe font metrics of each font.
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+/// Close enough for the accented letters and punctuation outside ASCII.
+const OTHER_WIDTH: u16 = 556;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Style {
+    bold: bool,
+    size: f32,
+    grey: bool,
+}
+
+impl Style {
+    const fn body() -> Self {
+        Self {
+            bold: false,
+            size: BODY_SIZE,
+            grey: false,
+        }
+    }
+
+    fn width(&self, text: &str) -> f32 {
+        let widths = if self.bold {
+            &HELVETICA_BOLD
+        } else {
+            &HELVETICA
+        };
+        let units: u32 = text
+            .chars()
+            .map(|c| match c {
+                ' '..='~' => widths[c as usize - 32] as u32,
+                _ => OTHER_WIDTH as u32,
+            })
+            .sum();
+        units as f32 * self.size / 1000.0
+    }
+
+    fn line_height(&self) -> f32 {
+        self.size * LEADING
+    }
+}
+
+struct Word<'a> {
+    text: &'a str,
+    style: Style,
+    space_before: bool,
+}
+
+/// Text of one style placed at `x` on a line.
+struct Piece {
+    x: f32,
+    style: Style,
+    text: String,
+}
+
+/// Breaks `spans` into lines that fit `TEXT_WIDTH`. A span may end with a
+/// space to keep it apart from the next, as speaker labels do.
+fn wrap(spans: &[(&str, Style)]) -> Vec<Vec<Piece>> {
+    let mut words: Vec<Option<Word>> = Vec::new();
+    let mut pending_space = false;
+    for (text, style) in spans {
+        for (line_index, line) in text.split('\n').enumerate() {
+            if line_index > 0 {
+                // A forced break.
+                words.push(None);
+                pending_space = false;
+            }
+            for (index, part) in line.split([' ', '\t']).enumerate() {
+                if index > 0 {
+                    pending_space = true;
+                }
+                if !part.is_empty() {
+                    words.push(Some(Word {
+                        text: part,
+                        style: *style,
+                        space_before: pending_space,
+                    }));
+                    pending_space = false;
+                }
+            }
+        }
+    }
+
+    let mut lines: Vec<Vec<Piece>> = vec![Vec::new()];
+    let mut x = 0.0;
+    for word in words {
+        let Some(word) = word else {
+            lines.push(Vec::new());
+            x = 0.0;
+            continue;
+        };
+        let mut text = word.text;
+        loop {
+            let line = lines.last_mut().expect("there is always a line");
+            let space = if word.space_before && !line.is_empty() {
+                word.style.width(" ")
+            } else {
+                0.0
+            };
+            let width = word.style.width(text);
+            if x + space + width <= TEXT_WIDTH {
+                place(line, x, space, text, word.style);
+                x += space + width;
+                break;
+            }
+            if !line.is_empty() {
+                lines.push(Vec::new());
+                x = 0.0;
+                continue;
+            }
+            // A word wider than the page, e.g. a long link: break it where
+            // it fills the line.
+            let mut split = text.len();
+            while split > 0 && word.style.width(&text[..split]) > TEXT_WIDTH {
+                split = text[..split]
+                    .char_indices()
+                    .next_back()
+                    .map_or(0, |(at, _)| at);
+            }
+            if split == 0 {
+                split = text.chars().next().map_or(text.len(), char::len_utf8);
+            }
+            place(line, 0.0, 0.0, &text[..split], word.style);
+            text = &text[split..];
+            if text.is_empty() {
+                x = word.style.width(&line.last().expect("just placed").text);
+                break;
+            }
+            lines.push(Vec::new());
+            x = 0.0;
+        }
+    }
+    lines
+}
+
+/// Adds `text` to the end of `line`, joining the last piece when it has the
+/// same style.
+fn place(line: &mut Vec<Piece>, x: f32, space: f32, text: &str, style: Style) {
+    match line.last_mut() {
+        Some(last) if last.style == style => {
+            if space > 0.0 {
+                last.text.push(' ');
+            }
+            last.text.push_str(text);
+        }
+        _ => line.push(Piece {
+            x: x + space,
+            style,
+            text: text.to_string(),
+        }),
+    }
+}
+
+#[derive(Default)]
+struct Pages {
+    pages: Vec<String>,
+    /// Baseline of the next line on the last page, from the bottom.
+    y: f32,
+}
+
+impl Pages {
+    fn new() -> Self {
+        let mut pages = Self::default();
+        pages.new_page();
+        pages
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(String::new());
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Starts a new page unless `height` still fits on this one.
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            self.new_page();
+        }
+    }
+
+    fn skip(&mut self, gap: f32) {
+        self.y -= gap;
+    }
+
+    fn paragraph(&mut self, spans: &[(&str, Style)]) {
+        let height = spans
+            .iter()
+            .map(|(_, style)| style.line_height())
+            .fold(0.0, f32::max);
+        for line in wrap(spans) {
+            self.reserve(height);
+            self.y -= height;
+            let baseline = self.y + height - height / LEADING;
+            let page = self.pages.last_mut().expect("there is always a page");
+            for piece in line {
+                show_text(page, &piece, MARGIN + piece.x, baseline);
+            }
+        }
+    }
+
+    /// The content stream of each page, numbered at its foot.
+    fn finish(self) -> Vec<String> {
+        let count = self.pages.len();
+        self.pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut page)| {
+                let style = Style {
+                    bold: false,
+                    size: FOOTER_SIZE,
+                    grey: true,
+                };
+                let text = format!("{} / {}", index + 1, count);
+                let x = (PAGE_WIDTH - style.width(&text)) / 2.0;
+                show_text(&mut page, &Piece { x, style, text }, x, MARGIN / 2.0);
+                page
+            })
+            .collect()
+    }
+}
+
+fn show_text(page: &mut String, piece: &Piece, x: f32, y: f32) {
+    let font = if piece.style.bold { "F2" } else { "F1" };
+    let colour = if piece.style.grey { GREY } else { "0 g" };
+    let _ = writeln!(
+        page,
+        "BT {} /{} {} Tf {:.2} {:.2} Td {} Tj ET",
+        colour,
+        font,
+        piece.style.size,
+        x,
+        y,
+        literal(&piece.text)
+    );
+}
+
+/// `text` as a PDF string literal in WinAnsiEncoding, with characters the
+/// encoding lacks shown as `?`.
+fn literal(text: &str) -> String {
+    let mut out = String::from("(");
+    for c in text.chars() {
+        match win_ansi(c) {
+            b @ (b'(' | b')' | b'\\') => {
+                out.push('\\');
+                out.push(b as char);
+            }
+            b @ 0x20..=0x7e => out.push(b as char),
+            b => {
+                let _ = write!(out, "\\{:03o}", b);
+            }
+        }
+    }
+    out.push(')');
+    out
+}
+
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => c as u8,
+        '€' => 0x80,
+        '‚' => 0x82,
+        'ƒ' => 0x83,
+        '„' => 0x84,
+        '…' => 0x85,
+        '†' => 0x86,
+        '‡' => 0x87,
+        'ˆ' => 0x88,
+        '‰' => 0x89,
+        'Š' => 0x8a,
+        '‹' => 0x8b,
+        'Œ' => 0x8c,
+        'Ž' => 0x8e,
+        '‘' => 0x91,
+        '’' => 0x92,
+        '“' => 0x93,
+        '”' => 0x94,
+        '•' => 0x95,
+        '–' => 0x96,
+        '—' => 0x97,
+        '˜' => 0x98,
+        '™' => 0x99,
+        'š' => 0x9a,
+        '›' => 0x9b,
+        'œ' => 0x9c,
+        'ž' => 0x9e,
+        'Ÿ' => 0x9f,
+        _ => b'?',
+    }
+}
+
+/// `text` as a PDF text string in UTF-16, for the document's metadata.
+fn text_string(text: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for unit in text.encode_utf16() {
+        let _ = write!(out, "{:04X}", unit);
+    }
+    out.push('>');
+    out
+}
+
+pub fn render(doc: &MeetingDocument) -> Vec<u8> {
+    let body = Style::body();
+    let grey = Style { grey: true, ..body };
+    let bold = Style { bold: true, ..body };
+
+    let mut pages = Pages::new();
+    pages.paragraph(&[(
+        &doc.title,
+        Style {
+            bold: true,
+            size: TITLE_SIZE,
+            grey: false,
+        },
+    )]);
+    pages.skip(PARAGRAPH_GAP);
+    if let Some(date) = &doc.date {
+        pages.paragraph(&[(date, grey)]);
+    }
+    if let Some(attendees) = doc.attendee_line() {
+        pages.paragraph(&[(&attendees, body)]);
+    }
+    pages.skip(2.0 * PARAGRAPH_GAP);
+
+    for (index, turn) in doc.turns.iter().enumerate() {
+        if let Some(chapter) = doc.chapter_at(index) {
+            let heading = Style {
+                bold: true,
+                size: HEADING_SIZE,
+                grey: false,
+            };
+            pages.skip(PARAGRAPH_GAP);
+            // Keep a heading on the page of the turn under it.
+            pages.reserve(heading.line_height() + body.line_height());
+            pages.paragraph(&[(&chapter.title, heading)]);
+            pages.skip(PARAGRAPH_GAP / 2.0);
+        }
+        let timestamp = MeetingDocument::timestamp(turn).map(|at| format!("{} ", at));
+        let speaker = turn.label(doc.locale).map(|mut label| {
+            // The label and the words after it stay apart however the
+            // locale separates them.
+            if !label.ends_with(' ') {
+                label.push(' ');
+            }
+            label
+        });
+        let mut spans: Vec<(&str, Style)> = Vec::new();
+        if let Some(timestamp) = &timestamp {
+            spans.push((timestamp, grey));
+        }
+        if let Some(speaker) = &speaker {
+            spans.push((speaker, bold));
+        }
+        spans.push((&turn.text, body));
+        pages.paragraph(&spans);
+        pages.skip(PARAGRAPH_GAP);
+    }
+
+    let contents = pages.finish();
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..contents.len())
+                .map(|index| format!("{} 0 R", 6 + 2 * index))
+                .collect::<Vec<_>>()
+                .join(" "),
+            contents.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        format!("<< /Title {} /Producer (Jilu) >>", text_string(&doc.title)).into_bytes(),
+    ];
+    for (index, content) in contents.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                7 + 2 * index
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend_from_slice(content.as_bytes());
+        stream.extend_from_slice(b"\nendstream");
+        objects.push(stream);
+    }
+
+    // The binary comment marks the file as binary for tools that check.
+    let mut out = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        out.extend_from_slice(object);
+        out.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = out.len();
+    let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(table, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref
+    );
+    out.extend_from_slice(table.as_bytes());
+    out
+}
@@ -0,0 +1,286 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const BLOCK_SIZE: usize = 4096;
+const BITS_PER_SAMPLE: u32 = 16;
+const MAX_FIXED_ORDER: usize = 4;
+/// Rice parameters are 4 bits and 15 is reserved for escapes.
+const MAX_RICE_PARAM: u32 = 14;
+
+/// Writes 16-bit mono FLAC, compressed with the format's fixed predictors.
+/// Light on CPU, and still about half the size of WAV for speech.
+pub struct FlacWriter {
+    file: BufWriter<File>,
+    sample_rate: u32,
+    block: Vec<i16>,
+    frame_number: u64,
+    total_samples: u64,
+    min_frame_bytes: u32,
+    max_frame_bytes: u32,
+}
+
+impl FlacWriter {
+    pub fn create(path: &Path, sample_rate: u32) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create FLAC file: {}", e))?;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+            sample_rate,
+            block: Vec::with_capacity(BLOCK_SIZE),
+            frame_number: 0,
+            total_samples: 0,
+            min_frame_bytes: 0,
+            max_frame_bytes: 0,
+        };
+        writer.write(b"fLaC")?;
+        // Written again with the totals once they are known.
+        writer.write(&writer.stream_info())?;
+        Ok(writer)
+    }
+
+    pub fn write_sample(&mut self, sample: i16) -> Result<(), String> {
+        self.block.push(sample);
+        if self.block.len() == BLOCK_SIZE {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+
+    pub fn finalize(mut self) -> Result<(), String> {
+        if !self.block.is_empty() {
+            self.write_frame()?;
+        }
+        let stream_info = self.stream_info();
+        self.file
+            .seek(SeekFrom::Start(4))
+            .map_err(|e| format!("Failed to finalize FLAC file: {}", e))?;
+        self.write(&stream_info)?;
+        self.file
+            .flush()
+            .map_err(|e| format!("Failed to finalize FLAC file: {}", e))
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.file
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write FLAC file: {}", e))
+    }
+
+    /// The only metadata block: STREAMINFO, flagged as the last one.
+    fn stream_info(&self) -> Vec<u8> {
+        let mut bits = BitWriter::default();
+        bits.put(1, 1);
+        bits.put(0, 7);
+        bits.put(34, 24);
+        bits.put(BLOCK_SIZE as u64, 16);
+        bits.put(BLOCK_SIZE as u64, 16);
+        bits.put(self.min_frame_bytes as u64, 24);
+        bits.put(self.max_frame_bytes as u64, 24);
+        bits.put(self.sample_rate as u64, 20);
+        bits.put(0, 3); // one channel
+        bits.put((BITS_PER_SAMPLE - 1) as u64, 5);
+        bits.put(self.total_samples >> 32, 4);
+        bits.put(self.total_samples & 0xFFFF_FFFF, 32);
+        // No MD5 signature; all zeroes means unknown.
+        for _ in 0..4 {
+            bits.put(0, 32);
+        }
+        bits.bytes
+    }
+
+    fn write_frame(&mut self) -> Result<(), String> {
+        let mut bits = BitWriter::default();
+        bits.put(0b11_1111_1111_1110, 14);
+        bits.put(0, 1);
+        bits.put(0, 1); // fixed block size
+        bits.put(0b0111, 4); // block size follows as 16 bits
+        bits.put(0b0000, 4); // sample rate from STREAMINFO
+        bits.put(0b0000, 4); // mono
+        bits.put(0b100, 3); // 16 bits per sample
+        bits.put(0, 1);
+        bits.put_utf8(self.frame_number);
+        bits.put((self.block.len() - 1) as u64, 16);
+        let header_crc = crc8(&bits.bytes);
+        bits.put(header_crc as u64, 8);
+        write_subframe(&mut bits, &self.block);
+        bits.align();
+        let frame_crc = crc16(&bits.bytes);
+        bits.put(frame_crc as u64, 16);
+
+        let frame_bytes = bits.bytes.len() as u32;
+        self.min_frame_bytes = match self.frame_number {
+            0 => frame_bytes,
+            _ => self.min_frame_bytes.min(frame_bytes),
+        };
+        self.max_frame_bytes = self.max_frame_bytes.max(frame_bytes);
+        self.frame_number += 1;
+        self.total_samples += self.block.len() as u64;
+        self.block.clear();
+        self.write(&bits.bytes)
+    }
+}
+
+/// Picks the cheapest of a constant, a fixed predictor or the raw samples.
+fn write_subframe(bits: &mut BitWriter, samples: &[i16]) {
+    if samples.iter().all(|sample| *sample == samples[0]) {
+        bits.put(0, 1);
+        bits.put(0b000000, 6);
+        bits.put(0, 1);
+        bits.put_signed(samples[0] as i64, BITS_PER_SAMPLE);
+        return;
+    }
+
+    let mut best: Option<(usize, Vec<i64>, u32, u64)> = None;
+    let mut residuals: Vec<i64> = samples.iter().map(|sample| *sample as i64).collect();
+    for order in 0..=MAX_FIXED_ORDER.min(samples.len() - 1) {
+        if order > 0 {
+            // Each order's residual is the difference of the previous order's.
+            for index in (order..samples.len()).rev() {
+                residuals[index] -= residuals[index - 1];
+            }
+        }
+        let (param, rice_bits) = rice_param(&residuals[order..]);
+        let cost = order as u64 * BITS_PER_SAMPLE as u64 + 10 + rice_bits;
+        if best
+            .as_ref()
+            .is_none_or(|(_, _, _, best_cost)| cost < *best_cost)
+        {
+            best = Some((order, residuals[order..].to_vec(), param, cost));
+        }
+    }
+
+    match best {
+        Some((order, residuals, param, cost))
+            if cost < samples.len() as u64 * BITS_PER_SAMPLE as u64 =>
+        {
+            bits.put(0, 1);
+            bits.put(0b001000 | order as u64, 6);
+            bits.put(0, 1);
+            for sample in &samples[..order] {
+                bits.put_signed(*sample as i64, BITS_PER_SAMPLE);
+            }
+            bits.put(0b00, 2); // Rice coding with 4-bit parameters
+            bits.put(0, 4); // a single partition
+            bits.put(param as u64, 4);
+            for residual in residuals {
+                let folded = zigzag(residual);
+                bits.put_unary(folded >> param);
+                bits.put(folded & ((1 << param) - 1), param);
+            }
+        }
+        _ => {
+            bits.put(0, 1);
+            bits.put(0b000001, 6);
+            bits.put(0, 1);
+            for sample in samples {
+                bits.put_signed(*sample as i64, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// The Rice parameter that codes `residuals` in the fewest bits, and that count.
+fn rice_param(residuals: &[i64]) -> (u32, u64) {
+    (0..=MAX_RICE_PARAM)
+        .map(|param| {
+            let bits = residuals
+                .iter()
+                .map(|residual| (zigzag(*residual) >> param) + 1 + param as u64)
+                .sum::<u64>();
+            (param, bits)
+        })
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+fn crc8(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0u8, |mut crc, byte| {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x07
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0u16, |mut crc, byte| {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x8005
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Packs values most significant bit first.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    /// Appends the low `count` bits of `value`; `count` is at most 32.
+    fn put(&mut self, value: u64, count: u32) {
+        if count == 0 {
+            return;
+        }
+        self.pending = (self.pending << count) | (value & ((1 << count) - 1));
+        self.pending_bits += count;
+        while self.pending_bits >= 8 {
+            self.pending_bits -= 8;
+            self.bytes.push((self.pending >> self.pending_bits) as u8);
+        }
+        self.pending &= (1 << self.pending_bits) - 1;
+    }
+
+    fn put_signed(&mut self, value: i64, count: u32) {
+        self.put(value as u64, count);
+    }
+
+    /// `value` zeroes and a one.
+    fn put_unary(&mut self, mut value: u64) {
+        while value >= 32 {
+            self.put(0, 32);
+            value -= 32;
+        }
+        self.put(1, value as u32 + 1);
+    }
+
+    /// Frame numbers use the same variable-length coding as UTF-8.
+    fn put_utf8(&mut self, value: u64) {
+        if value < 0x80 {
+            self.put(value, 8);
+            return;
+        }
+        let mut len = 2;
+        while value >= 1 << (5 * len + 1) {
+            len += 1;
+        }
+        let lead = (0xFF00u16 >> len) as u64 & 0xFF;
+        self.put(lead | (value >> (6 * (len - 1))), 8);
+        for index in (0..len - 1).rev() {
+            self.put(0x80 | ((value >> (6 * index)) & 0x3F), 8);
+        }
+    }
+
+    fn align(&mut self) {
+        if self.pending_bits > 0 {
+            self.put(0, 8 - self.pending_bits);
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use serde::Serialize;
+use std::future::Future;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sysinfo::Disks;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const LOW_DISK_WARNING_BYTES: u64 = 1024 * 1024 * 1024;
+const LOW_DISK_ERROR_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+/// One row of the settings "system status" panel.
+#[derive(Debug, Serialize, Clone)]
+pub struct HealthCheck {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub status: HealthStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Outcome of a single check before it is labelled and timed.
+pub type CheckOutcome = (HealthStatus, Option<String>);
+
+/// Runs a check, recording how long it took.
+pub async fn timed<F>(id: &'static str, label: &'static str, check: F) -> HealthCheck
+where
+    F: Future<Output = CheckOutcome>,
+{
+    let started = Instant::now();
+    let (status, detail) = check.await;
+    HealthCheck {
+        id,
+        label,
+        status,
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+pub fn from_result(result: Result<Option<String>, String>) -> CheckOutcome {
+    match result {
+        Ok(detail) => (HealthStatus::Ok, detail),
+        Err(err) => (HealthStatus::Error, Some(err)),
+    }
+}
+
+/// Opens a TCP connection to the host behind `url`.
+pub async fn check_endpoint(url: &str) -> CheckOutcome {
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed,
+        Err(err) => return (HealthStatus::Error, Some(format!("Invalid URL: {}", err))),
+    };
+    let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+        return (HealthStatus::Error, Some(format!("Invalid URL: {}", url)));
+    };
+
+    let connect = tokio::net::TcpStream::connect((host, port));
+    match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+        Ok(Ok(_)) => (
+            HealthStatus::Ok,
+            Some(format!("{}:{} reachable", host, port)),
+        ),
+        Ok(Err(err)) => (
+            HealthStatus::Error,
+            Some(format!("Cannot reach {}: {}", host, err)),
+        ),
+        Err(_) => (
+            HealthStatus::Error,
+            Some(format!("Timed out connecting to {}", host)),
+        ),
+    }
+}
+
+/// Free space on the disk holding `path`.
+pub fn check_disk_space(path: &Path) -> CheckOutcome {
+    let disks = Disks::new_with_refreshed_list();
+    let Some(disk) = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    else {
+        return (
+            HealthStatus::Warning,
+            Some("Could not determine the disk for the data directory".to_string()),
+        );
+    };
+
+    let available = disk.available_space();
+    let detail = Some(format!(
+        "{:.1} GB free on {}",
+        available as f64 / 1e9,
+        disk.mount_point().display()
+    ));
+    let status = if available < LOW_DISK_ERROR_BYTES {
+        HealthStatus::Error
+    } else if available < LOW_DISK_WARNING_BYTES {
+        HealthStatus::Warning
+    } else {
+        HealthStatus::Ok
+    };
+    (status, detail)
+}
+
+/// Runs a synchronous check on the blocking pool so slow OS calls run in parallel.
+pub async fn blocking<F>(check: F) -> CheckOutcome
+where
+    F: FnOnce() -> CheckOutcome + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(check)
+        .await
+        .unwrap_or_else(|err| (HealthStatus::Error, Some(err.to_string())))
+}
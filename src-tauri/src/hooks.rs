@@ -0,0 +1,122 @@
+use crate::settings::{HookConfig, HookEvent};
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const MAX_STDERR_BYTES: usize = 4 * 1024;
+
+/// Meeting metadata handed to a hook as JSON on stdin; the main fields are
+/// also set as `JILU_*` environment variables.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HookPayload {
+    pub meeting_id: Option<String>,
+    pub meeting_title: Option<String>,
+    pub transcript_path: Option<String>,
+    pub summary: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookOutcome {
+    pub event: HookEvent,
+    pub command: String,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Tail of stderr, to help users debug their scripts.
+    pub stderr: String,
+}
+
+impl HookOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none() && !self.timed_out && self.exit_code == Some(0)
+    }
+}
+
+fn event_name(event: HookEvent) -> &'static str {
+    match event {
+        HookEvent::RecordingStarted => "recording-started",
+        HookEvent::RecordingStopped => "recording-stopped",
+        HookEvent::SummaryReady => "summary-ready",
+    }
+}
+
+/// Runs one hook to completion, killing it if it outlives `timeout`.
+pub async fn run(
+    hook: &HookConfig,
+    event: HookEvent,
+    payload: &HookPayload,
+    timeout: Duration,
+) -> HookOutcome {
+    let started = Instant::now();
+    let mut outcome = HookOutcome {
+        event,
+        command: hook.command.clone(),
+        exit_code: None,
+        timed_out: false,
+        error: None,
+        duration_ms: 0,
+        stderr: String::new(),
+    };
+
+    let mut command = Command::new(hook.command.trim());
+    command
+        .args(&hook.args)
+        .env("JILU_EVENT", event_name(event))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let vars = [
+        ("JILU_MEETING_ID", &payload.meeting_id),
+        ("JILU_MEETING_TITLE", &payload.meeting_title),
+        ("JILU_TRANSCRIPT_PATH", &payload.transcript_path),
+    ];
+    for (name, value) in vars {
+        if let Some(value) = value {
+            command.env(name, value);
+        }
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            outcome.error = Some(format!("Failed to start: {}", err));
+            return outcome;
+        }
+    };
+
+    let mut body = serde_json::json!({ "event": event_name(event) });
+    if let (Some(body), Ok(serde_json::Value::Object(fields))) =
+        (body.as_object_mut(), serde_json::to_value(payload))
+    {
+        body.extend(fields);
+    }
+    let stdin = child.stdin.take();
+    // The timeout covers writing stdin too: a hook that never reads it would
+    // otherwise hang us once the pipe fills.
+    let finished = async move {
+        if let Some(mut stdin) = stdin {
+            // A hook that ignores stdin closes the pipe early; that's fine.
+            let _ = stdin.write_all(body.to_string().as_bytes()).await;
+        }
+        child.wait_with_output().await
+    };
+
+    // Dropping the child on timeout kills it (`kill_on_drop`).
+    match tokio::time::timeout(timeout, finished).await {
+        Ok(Ok(output)) => {
+            outcome.exit_code = output.status.code();
+            let start = output.stderr.len().saturating_sub(MAX_STDERR_BYTES);
+            outcome.stderr = String::from_utf8_lossy(&output.stderr[start..]).into_owned();
+        }
+        Ok(Err(err)) => outcome.error = Some(format!("Failed to wait for hook: {}", err)),
+        Err(_) => outcome.timed_out = true,
+    }
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
+    outcome
+}
@@ -0,0 +1,523 @@
+use crate::settings::HookEvent;
+use crate::state::{AppState, MAIN_SESSION};
+use crate::storage::{save_transcript_turns, saved_meeting_analytics};
+use crate::transcript::TranscriptTurnPayload;
+use crate::{
+    calendar, consent, contacts, events, hooks, meeting_store, pairing, session, share,
+    transcript_export, updater,
+};
+use serde::Deserialize;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_updater::UpdaterExt;
+use tracing::{debug, info, warn};
+
+/// The consent file of the meeting being recorded, whose declined
+/// attendees the live feeds leave out.
+fn recording_consent(
+    app: &AppHandle,
+    state: &AppState,
+) -> Result<Option<std::path::PathBuf>, String> {
+    let meeting_id = state
+        .sessions
+        .lock()
+        .get(MAIN_SESSION)
+        .and_then(|session| session.meeting_id.clone());
+    meeting_id
+        .map(|id| Ok(meeting_store::meeting_dir(app, &id)?.join(consent::CONSENT_FILE)))
+        .transpose()
+}
+
+/// Opens a read-only guest link to the live transcript. Guests on the same
+/// network can follow along in a browser until the link expires, sharing is
+/// stopped or the recording ends.
+#[tauri::command]
+pub async fn start_sharing(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ttl_minutes: Option<u64>,
+) -> Result<share::ShareStatus, String> {
+    if !state.sessions.lock().contains_key(MAIN_SESSION) {
+        return Err("Start recording before sharing the transcript".to_string());
+    }
+    let ttl_minutes = ttl_minutes.unwrap_or(share::DEFAULT_TTL_MINUTES);
+    if !(1..=share::MAX_TTL_MINUTES).contains(&ttl_minutes) {
+        return Err(format!(
+            "Link lifetime must be between 1 and {} minutes",
+            share::MAX_TTL_MINUTES
+        ));
+    }
+    if let Some(previous) = state.services.share.lock().take() {
+        previous.stop();
+    }
+
+    let (session, server) = share::ShareSession::start(
+        state.sessions.main.transcript.clone(),
+        recording_consent(&app, &state)?,
+        Duration::from_secs(ttl_minutes * 60),
+    )
+    .await?;
+    let status = session.status();
+    let url = session.url().to_string();
+    *state.services.share.lock() = Some(session);
+    state.services.telemetry.lock().record_feature("share_link");
+    info!("Sharing live transcript for {} minutes", ttl_minutes);
+
+    let share_state = state.services.share.clone();
+    tauri::async_runtime::spawn(async move {
+        server.await;
+        // Expired, unless it was stopped or replaced in the meantime.
+        let mut current = share_state.lock();
+        if current.as_ref().is_some_and(|session| session.url() == url) {
+            current.take();
+            drop(current);
+            let _ = app.emit(events::SHARING_STOPPED, ());
+        }
+    });
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_sharing(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(session) = state.services.share.lock().take() {
+        session.stop();
+        let _ = app.emit(events::SHARING_STOPPED, ());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_sharing_status(
+    state: State<'_, AppState>,
+) -> Result<Option<share::ShareStatus>, String> {
+    Ok(state
+        .services
+        .share
+        .lock()
+        .as_ref()
+        .map(share::ShareSession::status))
+}
+
+/// Offers a QR code that pairs one phone on the same network as a remote: it
+/// follows the live transcript and can mute the recording. The pairing ends
+/// when it expires, is stopped or the recording ends.
+#[tauri::command]
+pub async fn start_pairing(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    ttl_minutes: Option<u64>,
+) -> Result<pairing::PairingStatus, String> {
+    if !state.sessions.lock().contains_key(MAIN_SESSION) {
+        return Err("Start recording before pairing a phone".to_string());
+    }
+    let ttl_minutes = ttl_minutes.unwrap_or(share::DEFAULT_TTL_MINUTES);
+    if !(1..=share::MAX_TTL_MINUTES).contains(&ttl_minutes) {
+        return Err(format!(
+            "Pairing lifetime must be between 1 and {} minutes",
+            share::MAX_TTL_MINUTES
+        ));
+    }
+    if let Some(previous) = state.services.pairing.lock().take() {
+        previous.stop();
+    }
+
+    let mute_app = app.clone();
+    let paired_app = app.clone();
+    let hooks = pairing::RemoteHooks {
+        set_muted: Box::new(move |muted| session::set_main_muted(&mute_app, muted)),
+        on_paired: Box::new(move |device| {
+            info!("Phone paired as remote");
+            let _ = paired_app.emit(events::REMOTE_PAIRED, device);
+        }),
+    };
+    let (session, server) = pairing::PairingSession::start(
+        state.sessions.main.transcript.clone(),
+        recording_consent(&app, &state)?,
+        state.sessions.main.is_muted.clone(),
+        Duration::from_secs(ttl_minutes * 60),
+        hooks,
+    )
+    .await?;
+    let status = session.status();
+    let url = session.url().to_string();
+    *state.services.pairing.lock() = Some(session);
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("phone_remote");
+
+    let pairing_state = state.services.pairing.clone();
+    tauri::async_runtime::spawn(async move {
+        server.await;
+        let mut current = pairing_state.lock();
+        if current.as_ref().is_some_and(|session| session.url() == url) {
+            current.take();
+            drop(current);
+            let _ = app.emit(events::PAIRING_STOPPED, ());
+        }
+    });
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_pairing(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(session) = state.services.pairing.lock().take() {
+        session.stop();
+        let _ = app.emit(events::PAIRING_STOPPED, ());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_pairing_status(
+    state: State<'_, AppState>,
+) -> Result<Option<pairing::PairingStatus>, String> {
+    Ok(state
+        .services
+        .pairing
+        .lock()
+        .as_ref()
+        .map(pairing::PairingSession::status))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MeetingAttendeesArgs {
+    meeting_id: String,
+    title: String,
+    /// Start of the meeting (RFC 3339).
+    date: String,
+    attendees: Vec<contacts::Attendee>,
+}
+
+/// Records who was invited to a meeting so it shows up in their contact history.
+#[tauri::command]
+pub async fn record_meeting_attendees(
+    state: State<'_, AppState>,
+    args: MeetingAttendeesArgs,
+) -> Result<(), String> {
+    state.stores.contacts.lock().record_meeting(
+        &args.meeting_id,
+        &args.title,
+        &args.date,
+        &args.attendees,
+    )
+}
+
+#[tauri::command]
+pub async fn forget_meeting_attendees(
+    state: State<'_, AppState>,
+    meeting_id: String,
+) -> Result<(), String> {
+    state.stores.contacts.lock().forget_meeting(&meeting_id)
+}
+
+/// Recording consent of a meeting's attendees.
+#[tauri::command]
+pub async fn get_meeting_consent(
+    app: AppHandle,
+    meeting_id: String,
+) -> Result<consent::MeetingConsent, String> {
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    consent::MeetingConsent::load(&dir.join(consent::CONSENT_FILE))
+}
+
+/// Records whether an attendee was asked, agreed or declined to be recorded.
+#[tauri::command]
+pub async fn set_attendee_consent(
+    app: AppHandle,
+    meeting_id: String,
+    update: consent::ConsentUpdate,
+) -> Result<consent::MeetingConsent, String> {
+    update_consent(&app, &meeting_id, |consent| consent.set(update))
+}
+
+/// Chooses whether declined attendees' speech is left out of the meeting's
+/// saved transcript and its exports.
+#[tauri::command]
+pub async fn set_consent_exclusion(
+    app: AppHandle,
+    meeting_id: String,
+    exclude_declined: bool,
+) -> Result<consent::MeetingConsent, String> {
+    update_consent(&app, &meeting_id, |consent| {
+        consent.exclude_declined = exclude_declined;
+        Ok(())
+    })
+}
+
+/// Applies `change` to a meeting's consent and rewrites its saved transcript,
+/// so speech a decline now excludes is gone from it as well.
+fn update_consent(
+    app: &AppHandle,
+    meeting_id: &str,
+    change: impl FnOnce(&mut consent::MeetingConsent) -> Result<(), String>,
+) -> Result<consent::MeetingConsent, String> {
+    let dir = meeting_store::meeting_dir(app, meeting_id)?;
+    let path = dir.join(consent::CONSENT_FILE);
+    let mut meeting_consent = consent::MeetingConsent::load(&path)?;
+    change(&mut meeting_consent)?;
+    meeting_consent.save(&path)?;
+    let transcript_path = dir.join(transcript_export::TRANSCRIPT_FILE);
+    if let Ok(contents) = std::fs::read_to_string(&transcript_path) {
+        let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to read meeting transcript: {}", e))?;
+        save_transcript_turns(&transcript_path, &turns)?;
+    }
+    Ok(meeting_consent)
+}
+
+#[tauri::command]
+pub async fn set_contact_notes(
+    state: State<'_, AppState>,
+    email: String,
+    notes: String,
+) -> Result<(), String> {
+    state.stores.contacts.lock().set_notes(&email, &notes)
+}
+
+/// Every meeting `email` was invited to, with how much they spoke in each and notes about them.
+#[tauri::command]
+pub async fn get_contact_history(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    email: String,
+) -> Result<contacts::ContactHistory, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = state.stores.profiles.lock().active().clone();
+    let meeting_ids = state.stores.contacts.lock().meeting_ids(&email);
+    let mut analytics = std::collections::BTreeMap::new();
+    for meeting_id in meeting_ids {
+        // Meetings that were never recorded simply have no speaking figures.
+        let Ok(dir) = meeting_store::meeting_path(&profile, &data_dir, &meeting_id) else {
+            continue;
+        };
+        if let Ok(report) = saved_meeting_analytics(&dir, &meeting_id) {
+            analytics.insert(meeting_id, report);
+        }
+    }
+    state.stores.contacts.lock().history(&email, &analytics)
+}
+
+/// Lets the frontend trigger `summaryReady` hooks once it has generated notes.
+#[tauri::command]
+pub async fn notify_summary_ready(
+    app: AppHandle,
+    meeting_id: String,
+    title: Option<String>,
+    summary: String,
+) -> Result<(), String> {
+    fire_hooks(
+        &app,
+        HookEvent::SummaryReady,
+        hooks::HookPayload {
+            meeting_id: Some(meeting_id),
+            meeting_title: title,
+            transcript_path: None,
+            summary: Some(summary),
+        },
+    );
+    Ok(())
+}
+
+/// Starts every enabled hook for `event` in the background; results arrive as `hook-finished`.
+pub fn fire_hooks(app: &AppHandle, event: HookEvent, payload: hooks::HookPayload) {
+    let automation = app
+        .state::<AppState>()
+        .stores
+        .settings
+        .lock()
+        .get()
+        .automation
+        .clone();
+    let timeout = Duration::from_secs(automation.timeout_secs);
+    for hook in automation
+        .hooks
+        .into_iter()
+        .filter(|hook| hook.enabled && hook.event == event)
+    {
+        let app = app.clone();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            let outcome = hooks::run(&hook, event, &payload, timeout).await;
+            if outcome.succeeded() {
+                info!(command = %outcome.command, ?event, "Hook finished");
+            } else {
+                warn!(
+                    command = %outcome.command,
+                    ?event,
+                    exit_code = ?outcome.exit_code,
+                    timed_out = outcome.timed_out,
+                    error = ?outcome.error,
+                    "Hook failed"
+                );
+            }
+            let _ = app.emit(events::HOOK_FINISHED, outcome);
+        });
+    }
+}
+
+/// Checks for a release right away. Unlike the background check this ignores
+/// staged rollouts, so a user who asks always gets the latest version.
+#[tauri::command]
+pub async fn check_for_updates(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<updater::UpdateInfo>, String> {
+    if !updater::is_configured(&app) {
+        return Err("Updates are not configured for this build".to_string());
+    }
+    let update = app
+        .updater()
+        .map_err(|e| format!("Failed to start update check: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?;
+    let info = update.as_ref().map(updater::UpdateInfo::from_update);
+    let mut pending = state.services.update.lock();
+    pending.update = update;
+    pending.deferred = false;
+    Ok(info)
+}
+
+/// Downloads and installs the pending update, then restarts the app.
+#[tauri::command]
+pub async fn install_update(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    if state.sessions.is_recording() {
+        return Err("Stop the recording before installing an update".to_string());
+    }
+    let update = {
+        let mut pending = state.services.update.lock();
+        if pending.installing {
+            return Err("An update is already being installed".to_string());
+        }
+        let update = pending
+            .update
+            .clone()
+            .ok_or_else(|| "No update available".to_string())?;
+        pending.installing = true;
+        update
+    };
+    info!(version = %update.version, "Installing update");
+
+    let mut downloaded = 0u64;
+    let progress_app = app.clone();
+    let result = update
+        .download_and_install(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let _ = progress_app.emit(
+                    events::UPDATE_DOWNLOAD_PROGRESS,
+                    updater::DownloadProgress { downloaded, total },
+                );
+            },
+            || debug!("Update downloaded"),
+        )
+        .await;
+    if let Err(err) = result {
+        state.services.update.lock().installing = false;
+        return Err(format!("Failed to install update: {}", err));
+    }
+    let _ = app.emit(
+        events::UPDATE_INSTALLED,
+        updater::UpdateInfo::from_update(&update),
+    );
+    app.restart()
+}
+
+/// Emits `update-available` for the pending update, or holds it until the
+/// current recording stops.
+pub fn announce_update(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let recording = state.sessions.is_recording();
+    let mut pending = state.services.update.lock();
+    let Some(info) = pending
+        .update
+        .as_ref()
+        .map(updater::UpdateInfo::from_update)
+    else {
+        return;
+    };
+    pending.deferred = recording;
+    if !recording {
+        let _ = app.emit(events::UPDATE_AVAILABLE, info);
+    }
+}
+
+pub fn spawn_update_checker(app: AppHandle) {
+    if !updater::is_configured(&app) {
+        info!("Update checks disabled: no updater signing key configured");
+        return;
+    }
+    let bucket = match app.path().app_config_dir() {
+        Ok(dir) => updater::load_or_create_bucket(&dir.join(updater::BUCKET_FILE)),
+        Err(_) => 0,
+    };
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(updater::INITIAL_DELAY_SECS)).await;
+        let mut ticker = tokio::time::interval(Duration::from_secs(updater::CHECK_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            let enabled = {
+                let state = app.state::<AppState>();
+                let auto_check = state.stores.settings.lock().get().updates.auto_check;
+                auto_check && state.services.update.lock().update.is_none()
+            };
+            if !enabled {
+                continue;
+            }
+            let update = match app.updater() {
+                Ok(updater) => updater.check().await,
+                Err(err) => Err(err),
+            };
+            match update {
+                Ok(Some(update)) if updater::in_rollout(&update, bucket) => {
+                    info!(version = %update.version, "Update available");
+                    app.state::<AppState>().services.update.lock().update = Some(update);
+                    announce_update(&app);
+                }
+                Ok(Some(update)) => {
+                    debug!(version = %update.version, bucket, "Update not yet rolled out to this install");
+                }
+                Ok(None) => {}
+                Err(err) => debug!("Update check failed: {}", err),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn request_calendar_permission() -> Result<bool, String> {
+    calendar::request_calendar_access()
+}
+
+#[tauri::command]
+pub async fn check_calendar_permission() -> Result<bool, String> {
+    calendar::check_calendar_access()
+}
+
+#[tauri::command]
+pub async fn list_calendars() -> Result<Vec<calendar::Calendar>, String> {
+    calendar::list_calendars()
+}
+
+#[tauri::command]
+pub async fn fetch_calendar_events(
+    state: State<'_, AppState>,
+    calendar_ids: Vec<String>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<calendar::CalendarEvent>, String> {
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("calendar_sync");
+    calendar::fetch_events(calendar_ids, start_date, end_date).inspect_err(|_| {
+        state.services.telemetry.lock().record_error("calendar");
+    })
+}
@@ -0,0 +1,139 @@
+//! Remembers the meeting the main session is recording, so a recording cut
+//! short by a crash or a forced restart can carry on in the same meeting.
+//!
+//! The marker is written when a recording into a meeting starts and removed
+//! when it stops; one still there at launch means the app went away mid-meeting.
+
+use crate::state::AppState;
+use crate::transcript::{Transcript, TranscriptTurnPayload};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tracing::warn;
+
+const MARKER_FILE: &str = "active-recording.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedSession {
+    pub meeting_id: String,
+    pub meeting_title: Option<String>,
+    pub preset_id: Option<String>,
+    /// Profile the meeting belongs to.
+    pub profile_id: String,
+    /// When the recording started (RFC 3339).
+    pub started_at: String,
+}
+
+fn marker_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(MARKER_FILE))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Notes that the main session is recording into `meeting_id`.
+pub fn remember(
+    app: &AppHandle,
+    meeting_id: &str,
+    meeting_title: Option<&str>,
+    preset_id: Option<&str>,
+) {
+    let profile_id = app
+        .state::<AppState>()
+        .stores
+        .profiles
+        .lock()
+        .active()
+        .id
+        .clone();
+    let session = InterruptedSession {
+        meeting_id: meeting_id.to_string(),
+        meeting_title: meeting_title.map(str::to_string),
+        preset_id: preset_id.map(str::to_string),
+        profile_id,
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let written = marker_path(app).and_then(|path| {
+        let contents = serde_json::to_string(&session).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    });
+    if let Err(err) = written {
+        warn!("A crash would not offer to resume this recording: {}", err);
+    }
+}
+
+/// Clears the marker once the recording has stopped properly.
+pub fn forget(app: &AppHandle) {
+    if let Ok(path) = marker_path(app) {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+/// The recording the app went away in the middle of, if it belongs to the
+/// active profile.
+pub fn load(app: &AppHandle) -> Option<InterruptedSession> {
+    let contents = std::fs::read_to_string(marker_path(app).ok()?).ok()?;
+    let session: InterruptedSession = serde_json::from_str(&contents)
+        .map_err(|err| warn!("Ignoring unreadable recording marker: {}", err))
+        .ok()?;
+    let profile_id = app
+        .state::<AppState>()
+        .stores
+        .profiles
+        .lock()
+        .active()
+        .id
+        .clone();
+    (session.profile_id == profile_id).then_some(session)
+}
+
+/// Starts `transcript` from the turns saved at `path` before the app went
+/// away, followed by a gap marker. Returns the gap in seconds, or `None`
+/// when nothing had been saved.
+pub fn restore_transcript(
+    transcript: &mut Transcript,
+    path: &std::path::Path,
+    session: &InterruptedSession,
+) -> Option<f64> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+        .map_err(|err| warn!("Not carrying on the saved transcript: {}", err))
+        .ok()?;
+    if turns.is_empty() {
+        return None;
+    }
+    let gap = gap_secs(&turns, &session.started_at);
+    transcript.restore(turns);
+    transcript.mark_gap(&gap_text(gap), gap);
+    Some(gap)
+}
+
+/// Seconds between the end of `turns` and now, or since `started_at` when
+/// no turn says when it ended.
+fn gap_secs(turns: &[TranscriptTurnPayload], started_at: &str) -> f64 {
+    let last_heard = turns
+        .iter()
+        .rev()
+        .find_map(|turn| turn.ended_at.as_deref().or(turn.started_at.as_deref()))
+        .unwrap_or(started_at);
+    chrono::DateTime::parse_from_rfc3339(last_heard)
+        .map(|at| (chrono::Utc::now() - at.with_timezone(&chrono::Utc)).num_milliseconds())
+        .map(|ms| ms.max(0) as f64 / 1000.0)
+        .unwrap_or_default()
+}
+
+/// What the transcript says where audio is missing.
+fn gap_text(gap_secs: f64) -> String {
+    let minutes = (gap_secs / 60.0).round() as u64;
+    if minutes < 1 {
+        "[Recording interrupted; resumed after less than a minute]".to_string()
+    } else if minutes == 1 {
+        "[Recording interrupted; resumed after about a minute]".to_string()
+    } else {
+        format!(
+            "[Recording interrupted; resumed after about {} minutes]",
+            minutes
+        )
+    }
+}
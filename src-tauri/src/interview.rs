@@ -0,0 +1,180 @@
+use crate::transcript::TranscriptTurnPayload;
+use serde::Serialize;
+use std::fmt::Write;
+
+pub const INTERVIEW_FILE: &str = "interview.json";
+
+/// Openers that make a sentence a question even when the provider left off the `?`.
+const QUESTION_OPENERS: &[&str] = &[
+    "what",
+    "why",
+    "how",
+    "when",
+    "where",
+    "who",
+    "which",
+    "can you",
+    "could you",
+    "would you",
+    "do you",
+    "did you",
+    "have you",
+    "are you",
+    "is there",
+    "tell me",
+    "walk me through",
+    "describe",
+];
+
+/// Interview-mode settings for one recording.
+#[derive(Debug, Clone, Default)]
+pub struct InterviewConfig {
+    /// Label of the enrolled local user, the interviewer. Without one, a
+    /// question from any speaker starts a new pair.
+    pub local_speaker: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QaPair {
+    pub question: String,
+    pub asked_by: Option<String>,
+    /// Seconds into the recording, when timings are known.
+    pub asked_at: Option<f64>,
+    pub answer: String,
+    pub answered_by: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InterviewDocument {
+    pub local_speaker: Option<String>,
+    pub pairs: Vec<QaPair>,
+}
+
+impl InterviewConfig {
+    fn asks_questions(&self, turn: &TranscriptTurnPayload) -> bool {
+        match &self.local_speaker {
+            Some(local) => turn.speaker.as_deref() == Some(local.as_str()),
+            None => true,
+        }
+    }
+
+    fn is_question_turn(&self, turn: &TranscriptTurnPayload) -> bool {
+        turn.is_spoken() && self.asks_questions(turn) && is_question(&turn.text)
+    }
+
+    /// Splits `turns` into question/answer pairs. Talk before the first
+    /// question, the interviewer's own non-question remarks and typed notes
+    /// are left out.
+    pub fn segment(&self, turns: &[TranscriptTurnPayload]) -> InterviewDocument {
+        let mut pairs: Vec<QaPair> = Vec::new();
+        for turn in turns.iter().filter(|turn| turn.is_spoken()) {
+            if self.is_question_turn(turn) {
+                pairs.push(QaPair {
+                    question: turn.text.clone(),
+                    asked_by: turn.speaker.clone(),
+                    asked_at: turn.start,
+                    answer: String::new(),
+                    answered_by: Vec::new(),
+                });
+                continue;
+            }
+            if self.local_speaker.is_some() && self.asks_questions(turn) {
+                continue;
+            }
+            let Some(pair) = pairs.last_mut() else {
+                continue;
+            };
+            if !pair.answer.is_empty() {
+                pair.answer.push_str("\n\n");
+            }
+            pair.answer.push_str(&turn.text);
+            if let Some(speaker) = &turn.speaker {
+                if !pair.answered_by.contains(speaker) {
+                    pair.answered_by.push(speaker.clone());
+                }
+            }
+        }
+        InterviewDocument {
+            local_speaker: self.local_speaker.clone(),
+            pairs,
+        }
+    }
+}
+
+/// Whether any sentence in `text` reads as a question.
+pub fn is_question(text: &str) -> bool {
+    if text.contains('?') {
+        return true;
+    }
+    text.split(['.', '!'])
+        .map(|sentence| sentence.trim().to_lowercase())
+        .any(|sentence| {
+            QUESTION_OPENERS.iter().any(|opener| {
+                sentence
+                    .strip_prefix(opener)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+            })
+        })
+}
+
+/// Reports questions as their turns complete during a live recording.
+///
+/// The last turn can still grow, so a turn is only checked once a later one exists.
+#[derive(Debug, Default)]
+pub struct QuestionTracker {
+    config: InterviewConfig,
+    checked: usize,
+}
+
+/// Payload of `interview-question`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedQuestion {
+    pub turn_index: usize,
+    pub text: String,
+    pub speaker: Option<String>,
+    pub start: Option<f64>,
+}
+
+impl QuestionTracker {
+    pub fn new(config: InterviewConfig) -> Self {
+        Self { config, checked: 0 }
+    }
+
+    pub fn completed(&mut self, turns: &[TranscriptTurnPayload]) -> Vec<DetectedQuestion> {
+        let complete = turns.len().saturating_sub(1);
+        let mut found = Vec::new();
+        while self.checked < complete {
+            let turn = &turns[self.checked];
+            if self.config.is_question_turn(turn) {
+                found.push(DetectedQuestion {
+                    turn_index: self.checked,
+                    text: turn.text.clone(),
+                    speaker: turn.speaker.clone(),
+                    start: turn.start,
+                });
+            }
+            self.checked += 1;
+        }
+        found
+    }
+}
+
+pub fn render_markdown(document: &InterviewDocument) -> String {
+    let mut out = String::from("# Interview\n");
+    for (index, pair) in document.pairs.iter().enumerate() {
+        let _ = write!(out, "\n## Q{}. {}\n\n", index + 1, pair.question);
+        if pair.answer.is_empty() {
+            out.push_str("_No answer recorded._\n");
+            continue;
+        }
+        if !pair.answered_by.is_empty() {
+            let _ = writeln!(out, "**{}:**\n", pair.answered_by.join(", "));
+        }
+        out.push_str(&pair.answer);
+        out.push('\n');
+    }
+    out
+}
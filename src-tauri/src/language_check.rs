@@ -0,0 +1,172 @@
+//! Spots a recording transcribed in a language nobody is speaking, which
+//! otherwise goes on for the whole meeting as a transcript of plausible
+//! looking nonsense.
+//!
+//! The first minute of speech decides. The provider may say outright which
+//! language it heard each word in; otherwise a wrong language shows as words
+//! it is consistently unsure of.
+
+use jilu_core::locale::Locale;
+use jilu_core::provider::Word;
+use serde::Serialize;
+
+/// Seconds of speech looked at before deciding.
+const WINDOW_SECS: f64 = 60.0;
+/// Seconds of speech after which a recording that has still said too little
+/// to judge is left alone.
+const GIVE_UP_SECS: f64 = 300.0;
+/// Words needed to judge; a few mumbled ones say nothing.
+const MIN_WORDS: usize = 30;
+/// Mean word confidence below which the language is suspect. Speech in the
+/// right language rarely averages under 0.8, even on a poor line.
+const LOW_CONFIDENCE: f64 = 0.5;
+/// Share of the words the provider tagged that must be in one other language.
+const OTHER_LANGUAGE_SHARE: f64 = 0.5;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MismatchReason {
+    /// The words came back with little confidence.
+    LowConfidence,
+    /// The provider tagged most words with another language.
+    ProviderLanguage,
+}
+
+/// Payload of `language-mismatch-suspected`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageMismatch {
+    /// What the recording is transcribed in.
+    pub language: String,
+    /// A language to switch to: the one the provider heard, or else the UI
+    /// language when it differs.
+    pub suggested_language: Option<String>,
+    pub average_confidence: Option<f64>,
+    pub reason: MismatchReason,
+}
+
+#[derive(Debug)]
+pub struct LanguageCheck {
+    language: String,
+    /// Where the first word was heard, in seconds of sent audio.
+    first_heard: Option<f64>,
+    confidence_sum: f64,
+    scored: usize,
+    tagged: usize,
+    /// Tagged words in languages other than `language`, by language.
+    other: Vec<(String, usize)>,
+    done: bool,
+}
+
+impl LanguageCheck {
+    pub fn new(language: &str) -> Self {
+        Self {
+            language: language.to_string(),
+            first_heard: None,
+            confidence_sum: 0.0,
+            scored: 0,
+            tagged: 0,
+            other: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Starts over in `language`, after switching to it.
+    pub fn restart(&mut self, language: &str) {
+        *self = Self::new(language);
+    }
+
+    /// Takes in a final result's words, whose timings start `time_offset`
+    /// seconds into the sent audio. Returns a suspected mismatch at most once.
+    pub fn observe(
+        &mut self,
+        words: &[Word],
+        time_offset: f64,
+        locale: Locale,
+    ) -> Option<LanguageMismatch> {
+        if self.done {
+            return None;
+        }
+        let mut heard_until = None;
+        // Punctuation comes back with full confidence and no language.
+        for word in words
+            .iter()
+            .filter(|word| word.text.chars().any(char::is_alphanumeric))
+        {
+            if let Some((start, end)) = word.span {
+                self.first_heard.get_or_insert(start + time_offset);
+                heard_until = Some(end + time_offset);
+            }
+            if let Some(confidence) = word.confidence {
+                self.confidence_sum += confidence;
+                self.scored += 1;
+            }
+            if let Some(language) = word.language.as_deref() {
+                self.tagged += 1;
+                if !self.expects(language) {
+                    let language = base_language(language);
+                    match self.other.iter_mut().find(|(other, _)| *other == language) {
+                        Some((_, count)) => *count += 1,
+                        None => self.other.push((language.to_string(), 1)),
+                    }
+                }
+            }
+        }
+
+        let heard = heard_until? - self.first_heard?;
+        if heard < WINDOW_SECS {
+            return None;
+        }
+        if self.scored.max(self.tagged) < MIN_WORDS {
+            self.done = heard >= GIVE_UP_SECS;
+            return None;
+        }
+        self.done = true;
+
+        let average_confidence =
+            (self.scored > 0).then(|| self.confidence_sum / self.scored as f64);
+        let heard_language = self
+            .other
+            .iter()
+            .max_by_key(|(_, count)| *count)
+            .filter(|(_, count)| *count as f64 >= self.tagged as f64 * OTHER_LANGUAGE_SHARE);
+        let reason = match heard_language {
+            Some(_) if self.tagged >= MIN_WORDS => MismatchReason::ProviderLanguage,
+            _ if self.scored >= MIN_WORDS
+                && average_confidence.is_some_and(|average| average < LOW_CONFIDENCE) =>
+            {
+                MismatchReason::LowConfidence
+            }
+            _ => return None,
+        };
+        let ui_language = locale.transcription_language();
+        let suggested_language = match heard_language {
+            Some((language, _)) => Some(language.clone()),
+            None => (!self.expects(ui_language)).then(|| ui_language.to_string()),
+        };
+        Some(LanguageMismatch {
+            language: self.language.clone(),
+            suggested_language,
+            average_confidence,
+            reason,
+        })
+    }
+
+    /// Whether `language` is one the recording is transcribed in; a
+    /// bilingual pack like `cmn_en` expects both of its languages.
+    fn expects(&self, language: &str) -> bool {
+        let language = base_language(language);
+        self.language
+            .split('_')
+            .any(|expected| base_language(expected) == language)
+    }
+}
+
+/// `en` for `en-US`, and the Speechmatics code for languages providers
+/// name differently.
+fn base_language(tag: &str) -> &str {
+    match tag.split('-').next().unwrap_or(tag) {
+        "zh" => "cmn",
+        language => language,
+    }
+}
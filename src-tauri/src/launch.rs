@@ -0,0 +1,295 @@
+use reqwest::Url;
+use serde::Serialize;
+
+pub const URL_SCHEME: &str = "jilu";
+/// Apps an x-callback-url may report back to. Any web page can open a
+/// `jilu://` link, so web and file URLs are never called back.
+const CALLBACK_SCHEMES: &[&str] = &["shortcuts", "raycast", "alfred"];
+
+/// Where to report back to the app that sent an x-callback-url request.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XCallback {
+    pub success: Option<String>,
+    pub error: Option<String>,
+}
+
+/// What a launch asked the app to do, parsed from its command line or a
+/// `jilu://` URL (which is how Shortcuts and AppleScript drive the app).
+///
+/// A second launch forwards this to the running instance instead of starting a new one.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum LaunchIntent {
+    Show,
+    #[serde(rename_all = "camelCase")]
+    StartRecording {
+        event_id: Option<String>,
+        /// Recording preset to start with.
+        preset_id: Option<String>,
+    },
+    /// Ad-hoc recording into a new untitled meeting without opening the window.
+    QuickCapture {
+        #[serde(skip)]
+        callback: XCallback,
+    },
+    /// Open a meeting, optionally at a moment in its recording.
+    #[serde(rename_all = "camelCase")]
+    OpenMeeting {
+        meeting_id: String,
+        at: Option<f64>,
+    },
+    StopRecording,
+    ToggleMute,
+    /// Hand the latest transcript to an x-callback-url `x-success` URL, once
+    /// the user has agreed in the window.
+    #[serde(rename_all = "camelCase")]
+    SendTranscript {
+        #[serde(skip)]
+        callback: String,
+        /// Scheme of the app asking, e.g. `shortcuts`, to name it in the prompt.
+        app: String,
+    },
+}
+
+/// A launch intent, and whether the user has to agree to it in the window first.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LaunchRequest {
+    #[serde(flatten)]
+    pub intent: LaunchIntent,
+    pub confirm: bool,
+}
+
+impl LaunchRequest {
+    /// Asked for on the command line, so by someone already running code as the user.
+    pub fn trusted(intent: LaunchIntent) -> Self {
+        Self {
+            intent,
+            confirm: false,
+        }
+    }
+
+    /// Asked for by a `jilu://` link. Any web page can open one, so starting,
+    /// stopping or muting a recording waits for the user to agree.
+    pub fn from_url(intent: LaunchIntent) -> Self {
+        let confirm = matches!(
+            intent,
+            LaunchIntent::StartRecording { .. }
+                | LaunchIntent::QuickCapture { .. }
+                | LaunchIntent::StopRecording
+                | LaunchIntent::ToggleMute
+        );
+        Self { intent, confirm }
+    }
+}
+
+/// Parses `jilu [--show] [--record [--event <id>] [--preset <id>]] [--quick-capture] [--stop] [--toggle-mute]`. `args` excludes the program name.
+///
+/// Returns `None` when nothing beyond a plain launch was requested.
+pub fn parse<I>(args: I) -> Option<LaunchRequest>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut show = false;
+    let mut record = false;
+    let mut quick_capture = false;
+    let mut stop = false;
+    let mut toggle_mute = false;
+    let mut event_id = None;
+    let mut preset_id = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--show" => show = true,
+            "--record" => record = true,
+            "--quick-capture" => quick_capture = true,
+            "--stop" => stop = true,
+            "--toggle-mute" => toggle_mute = true,
+            "--event" => event_id = args.next().filter(|id| !id.trim().is_empty()),
+            "--preset" => preset_id = args.next().filter(|id| !id.trim().is_empty()),
+            other if other.starts_with("jilu://") => {
+                // Some platforms pass opened URLs on the command line.
+                return Url::parse(other)
+                    .ok()
+                    .and_then(|url| parse_url(&url).ok())
+                    .map(LaunchRequest::from_url);
+            }
+            other => {
+                if let Some(id) = other.strip_prefix("--event=") {
+                    event_id = Some(id.to_string()).filter(|id| !id.trim().is_empty());
+                } else if let Some(id) = other.strip_prefix("--preset=") {
+                    preset_id = Some(id.to_string()).filter(|id| !id.trim().is_empty());
+                }
+            }
+        }
+    }
+
+    let intent = if stop {
+        LaunchIntent::StopRecording
+    } else if toggle_mute {
+        LaunchIntent::ToggleMute
+    } else if quick_capture {
+        LaunchIntent::QuickCapture {
+            callback: XCallback::default(),
+        }
+    } else if record || event_id.is_some() || preset_id.is_some() {
+        LaunchIntent::StartRecording {
+            event_id,
+            preset_id,
+        }
+    } else if show {
+        LaunchIntent::Show
+    } else {
+        return None;
+    };
+    Some(LaunchRequest::trusted(intent))
+}
+
+/// Parses a `jilu://` URL:
+///
+/// - `jilu://show`
+/// - `jilu://record[?event=<id>][&preset=<id>]`
+/// - `jilu://quick-capture[?x-success=<url>&x-error=<url>]`
+/// - `jilu://meeting/<id>[?t=<seconds>]`
+/// - `jilu://stop`
+/// - `jilu://toggle-mute`
+/// - `jilu://transcript?x-success=<url>`
+pub fn parse_url(url: &Url) -> Result<LaunchIntent, String> {
+    if url.scheme() != URL_SCHEME {
+        return Err(format!("Not a {}:// URL: {}", URL_SCHEME, url));
+    }
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+            .filter(|value| !value.trim().is_empty())
+    };
+    match url.host_str().unwrap_or_default() {
+        "" | "show" => Ok(LaunchIntent::Show),
+        "record" => Ok(LaunchIntent::StartRecording {
+            event_id: query("event"),
+            preset_id: query("preset"),
+        }),
+        "quick-capture" => Ok(LaunchIntent::QuickCapture {
+            callback: XCallback {
+                success: query("x-success")
+                    .map(|url| allowed_callback(&url).map(|_| url))
+                    .transpose()?,
+                error: query("x-error")
+                    .map(|url| allowed_callback(&url).map(|_| url))
+                    .transpose()?,
+            },
+        }),
+        "meeting" => {
+            let meeting_id = url
+                .path_segments()
+                .and_then(|mut segments| segments.next())
+                .filter(|id| !id.is_empty())
+                .ok_or("jilu://meeting needs a meeting id")?
+                .to_string();
+            if !meeting_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                return Err(format!("Invalid meeting id: {}", meeting_id));
+            }
+            let at = query("t")
+                .map(|t| {
+                    t.parse::<f64>()
+                        .map_err(|_| format!("Invalid time '{}'", t))
+                })
+                .transpose()?
+                .filter(|t| t.is_finite() && *t >= 0.0);
+            Ok(LaunchIntent::OpenMeeting { meeting_id, at })
+        }
+        "stop" => Ok(LaunchIntent::StopRecording),
+        "toggle-mute" => Ok(LaunchIntent::ToggleMute),
+        "transcript" => {
+            let callback = query("x-success").ok_or("jilu://transcript needs an x-success URL")?;
+            let app = allowed_callback(&callback)?.scheme().to_string();
+            Ok(LaunchIntent::SendTranscript { callback, app })
+        }
+        other => Err(format!("Unknown action: {}", other)),
+    }
+}
+
+/// Link that opens `meeting_id` at `seconds` into its recording.
+pub fn citation_url(meeting_id: &str, seconds: f64) -> String {
+    format!(
+        "{}://meeting/{}?t={}",
+        URL_SCHEME,
+        meeting_id,
+        seconds.max(0.0).floor() as u64
+    )
+}
+
+/// `callback` parsed, when it points at an app allowed to be called back.
+fn allowed_callback(callback: &str) -> Result<Url, String> {
+    let url = Url::parse(callback).map_err(|e| format!("Invalid callback URL: {}", e))?;
+    if !CALLBACK_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "Callbacks to {}: URLs are not allowed; use one of {}",
+            url.scheme(),
+            CALLBACK_SCHEMES
+                .iter()
+                .map(|scheme| format!("{}://", scheme))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    Ok(url)
+}
+
+/// `callback` with `params` appended to its query string.
+pub fn callback_url(callback: &str, params: &[(&str, &str)]) -> Result<Url, String> {
+    let mut url = allowed_callback(callback)?;
+    url.query_pairs_mut().extend_pairs(params);
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn command_line_flags_are_trusted() {
+        let request = parse(args("--record --preset focus")).unwrap();
+        assert!(!request.confirm);
+        assert_eq!(
+            request.intent,
+            LaunchIntent::StartRecording {
+                event_id: None,
+                preset_id: Some("focus".to_string()),
+            }
+        );
+        assert!(!parse(args("--stop")).unwrap().confirm);
+    }
+
+    #[test]
+    fn links_that_change_recording_need_confirming() {
+        for link in [
+            "jilu://record",
+            "jilu://quick-capture",
+            "jilu://stop",
+            "jilu://toggle-mute",
+        ] {
+            assert!(parse(args(link)).unwrap().confirm, "{}", link);
+        }
+        for link in ["jilu://show", "jilu://meeting/abc?t=12"] {
+            assert!(!parse(args(link)).unwrap().confirm, "{}", link);
+        }
+    }
+
+    #[test]
+    fn requests_serialize_flat() {
+        let request = LaunchRequest::from_url(LaunchIntent::StopRecording);
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "action": "stopRecording", "confirm": true })
+        );
+    }
+}
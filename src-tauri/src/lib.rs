@@ -1,1320 +1,765 @@
+mod agenda;
+mod analytics;
+mod audio_dump;
+mod avoid_list;
+mod batch;
+mod bulk_export;
 mod calendar;
+mod captions;
+mod capture;
+mod cli;
+mod consent;
+mod contacts;
+mod costs;
+mod cues;
+mod device_test;
+mod diagnostics;
+mod duplicates;
+mod events;
+mod export;
+mod flac;
+mod health;
+mod hooks;
+mod integrations;
+mod interrupted;
+mod interview;
+mod language_check;
+mod launch;
+mod live_transcript;
+mod logging;
+mod meeting_package;
+mod meeting_store;
+mod mute;
+mod native_mic;
+mod normalize;
+mod onboarding;
+mod output_route;
+mod pairing;
 mod power;
-
-use futures_util::{SinkExt, StreamExt};
-use parking_lot::Mutex;
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+mod profiles;
+mod projects;
+mod quiet_hours;
+mod recent_audio;
+mod recorder;
+mod recording_imports;
+mod resources;
+mod screen_share;
+mod search;
+mod session;
+mod session_config;
+mod settings;
+mod share;
+mod shortcuts;
+mod silence;
+mod slug;
+mod soak;
+mod speaker_ids;
+mod startup;
+mod state;
+mod storage;
+mod stream_health;
+mod telemetry;
+mod timeline;
+mod tls;
+mod transcript_export;
+mod transcription;
+mod tray;
+mod updater;
+mod usage;
+mod voice_commands;
+
+use diagnostics::{spawn_resource_reporter, spawn_telemetry_reporter};
+use integrations::spawn_update_checker;
+use jilu_core::{audio, chapters, formatting, levels, locale, transcript};
+use session::{
+    continue_recording_headless, handle_power_event, stop_recording, toggle_session_mute,
+};
+use settings::{Settings, SettingsStore};
+use shortcuts::apply_shortcuts;
+use state::{AppState, MAIN_SESSION};
+use std::sync::atomic::Ordering;
 use tauri::{
-    menu::MenuBuilder,
-    menu::MenuItemBuilder,
-    tray::{TrayIconBuilder, TrayIconEvent},
-    AppHandle, Emitter, Manager, State, Window,
+    menu::{CheckMenuItemBuilder, Menu, MenuBuilder, MenuItemBuilder, SubmenuBuilder},
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, RunEvent, State, WindowEvent,
 };
-use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
-use tokio::sync::{mpsc, oneshot};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use power::WakeLock;
-
-const SOURCE_SAMPLE_RATE: u32 = 48_000;
-const TARGET_SAMPLE_RATE: u32 = 16_000;
-const FRAME_SIZE: usize = 480; // 10ms at 48 kHz
-const MIN_BUFFER_THRESHOLD: usize = FRAME_SIZE / 2;
-const DEFAULT_RT_URL: &str = "wss://eu2.rt.speechmatics.com/v2";
-
-#[derive(Default)]
-pub struct AppState {
-    capture_state: Arc<Mutex<Option<CaptureHandle>>>,
-    recording: Arc<Mutex<Option<RecordingSession>>>,
-    is_muted: Arc<Mutex<bool>>,
-    transcript: Arc<Mutex<String>>,
-    wake_lock: Arc<Mutex<Option<WakeLock>>>,
-}
-
-struct CaptureHandle {
-    stop_tx: std::sync::mpsc::Sender<()>,
-    task: std::thread::JoinHandle<()>,
-}
-
-struct RecordingSession {
-    mic_tx: mpsc::UnboundedSender<Vec<f32>>,
-    stop_tx: Option<oneshot::Sender<()>>,
-    task: tauri::async_runtime::JoinHandle<()>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct StartRecordingArgs {
-    #[serde(alias = "apiKey")]
-    api_key: String,
-    #[serde(alias = "additionalVocab", alias = "additional_vocab")]
-    additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
-    #[serde(alias = "speakerProfile", alias = "speaker_profile")]
-    speaker_profile: Option<SpeakerProfileArg>,
-    #[serde(default, alias = "rtUrl", alias = "rt_url")]
-    rt_url: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct AdditionalVocabularyEntry {
-    content: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    sounds_like: Option<Vec<String>>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-struct SpeakerProfileArg {
-    label: String,
-    #[serde(alias = "speakerIdentifiers")]
-    speaker_identifiers: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Clone)]
-struct TranscriptTurnPayload {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    speaker: Option<String>,
-    text: String,
-}
-
-#[derive(Debug, Serialize, Clone)]
-struct TranscriptUpdate {
-    text: String,
-    is_partial: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    turns: Option<Vec<TranscriptTurnPayload>>,
-}
-
-#[derive(Debug, Serialize)]
-struct SpeechmaticsConfig {
-    message: String,
-    transcription_config: TranscriptionConfig,
-    audio_format: AudioFormat,
-}
-
-#[derive(Debug, Serialize)]
-struct TranscriptionConfig {
-    language: String,
-    enable_partials: bool,
-    operating_point: String,
-    max_delay: f32,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    diarization: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    speaker_diarization_config: Option<SpeakerDiarizationConfig>,
-}
-
-#[derive(Debug, Serialize)]
-struct SpeakerDiarizationConfig {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    get_speakers: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    speakers: Option<Vec<KnownSpeaker>>,
-}
-
-#[derive(Debug, Serialize)]
-struct KnownSpeaker {
-    label: String,
-    speaker_identifiers: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct SpeakersResultMessage {
-    message: String,
-    #[serde(default)]
-    speakers: Option<Vec<SpeakersResultEntry>>,
-    #[serde(default)]
-    error: Option<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct SpeakersResultEntry {
-    #[allow(dead_code)]
-    #[serde(default)]
-    label: Option<String>,
-    #[serde(default)]
-    speaker_identifiers: Option<Vec<String>>,
-}
-
-#[derive(Debug, Serialize)]
-struct AudioFormat {
-    #[serde(rename = "type")]
-    format_type: String,
-    encoding: String,
-    sample_rate: u32,
-}
-
-#[derive(Debug, Serialize)]
-struct EndOfStreamMessage {
-    message: String,
-    last_seq_no: u32,
-}
-
-#[derive(Debug, Deserialize)]
-struct SpeechmaticsMessage {
-    message: String,
-    #[serde(default)]
-    results: Vec<SpeechmaticsResult>,
-    #[serde(default)]
-    error: Option<String>,
-    #[serde(default)]
-    metadata: Option<SpeechmaticsMetadata>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct SpeechmaticsResult {
-    #[serde(default)]
-    alternatives: Vec<SpeechmaticsAlternative>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-struct SpeechmaticsAlternative {
-    #[serde(default)]
-    content: Option<AlternativeContent>,
-    #[serde(default)]
-    text: Option<String>,
-    #[serde(default)]
-    speaker: Option<String>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[serde(untagged)]
-enum AlternativeContent {
-    Simple(String),
-    Parts(Vec<SpeechmaticsContent>),
-}
-
-#[derive(Debug, Deserialize, Clone)]
-#[serde(untagged)]
-enum SpeechmaticsContent {
-    Plain(String),
-    #[serde(rename_all = "camelCase")]
-    Rich {
-        #[serde(rename = "type")]
-        _kind: Option<String>,
-        #[serde(default)]
-        content: Option<String>,
-        #[serde(default)]
-        text: Option<String>,
-    },
-}
-
-#[derive(Debug, Deserialize, Default, Clone)]
-struct SpeechmaticsMetadata {
-    #[serde(default)]
-    transcript: Option<String>,
-}
-
-fn extract_text(msg: &SpeechmaticsMessage) -> Option<String> {
-    if let Some(meta) = &msg.metadata {
-        if let Some(transcript) = &meta.transcript {
-            if !transcript.trim().is_empty() {
-                return Some(clean_punctuation(transcript.trim()));
-            }
-        }
-    }
-
-    let partial: String = msg
-        .results
-        .iter()
-        .filter_map(|r| r.alternatives.first())
-        .filter_map(|alt| alt.text())
-        .collect::<Vec<_>>()
-        .join(" ");
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_opener::OpenerExt;
+use tracing::{debug, info, warn};
 
-    if !partial.trim().is_empty() {
-        return Some(clean_punctuation(&partial));
-    }
-
-    None
+#[tauri::command]
+async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String> {
+    Ok(state.stores.settings.lock().get().clone())
 }
 
-impl SpeechmaticsAlternative {
-    fn text(&self) -> Option<String> {
-        if let Some(text) = &self.text {
-            let cleaned = clean_punctuation(text);
-            if cleaned.trim().is_empty() {
-                return None;
-            }
-            return Some(cleaned);
-        }
-
-        self.content.as_ref().and_then(|content| match content {
-            AlternativeContent::Simple(value) => {
-                let trimmed = value.trim();
-                if trimmed.is_empty() {
-                    None
-                } else {
-                    Some(clean_punctuation(trimmed))
-                }
-            }
-            AlternativeContent::Parts(parts) => {
-                let mut combined = String::new();
-                for part in parts {
-                    match part {
-                        SpeechmaticsContent::Plain(value) => combined.push_str(value),
-                        SpeechmaticsContent::Rich { content, text, .. } => {
-                            if let Some(value) = content {
-                                combined.push_str(value);
-                            } else if let Some(value) = text {
-                                combined.push_str(value);
-                            }
-                        }
-                    }
-                }
-                let cleaned = clean_punctuation(&combined);
-                if cleaned.trim().is_empty() {
-                    None
-                } else {
-                    Some(cleaned)
-                }
-            }
-        })
-    }
+/// Merges `patch` into the stored settings; `null` resets a field to its default.
+#[tauri::command]
+async fn update_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    apply_settings_patch(&app, &state, patch)
 }
 
+/// Sets the UI language, e.g. `de` or `pt-BR`. Speaker prefixes in the
+/// transcript text and exports follow its conventions.
 #[tauri::command]
-async fn start_recording(
+async fn set_locale(
     app: AppHandle,
-    window: Window,
     state: State<'_, AppState>,
-    args: StartRecordingArgs,
-) -> Result<(), String> {
-    // Clear any stale recording state before starting
-    let mut recording_guard = state.recording.lock();
-    if recording_guard.is_some() {
-        // Clean up stale state
-        recording_guard.take();
-    }
-    drop(recording_guard);
+    locale: String,
+) -> Result<Settings, String> {
+    let locale = locale.trim();
+    locale::validate_locale(locale)?;
+    apply_settings_patch(&app, &state, serde_json::json!({ "locale": locale }))
+}
+
+fn apply_settings_patch(
+    app: &AppHandle,
+    state: &AppState,
+    patch: serde_json::Value,
+) -> Result<Settings, String> {
+    let (previous, updated) = {
+        let mut store = state.stores.settings.lock();
+        let previous = store.get().clone();
+        (previous, store.update(patch)?)
+    };
 
-    {
-        let mut transcript = state.transcript.lock();
-        transcript.clear();
+    if updated.log_level != previous.log_level {
+        if let Some(logging) = app.try_state::<logging::Logging>() {
+            logging.set_filter(&updated.log_level)?;
+        }
     }
-    {
-        let mut muted = state.is_muted.lock();
-        *muted = false;
+    if updated.shortcuts != previous.shortcuts {
+        apply_shortcuts(app, &updated.shortcuts)?;
     }
-
-    stop_capture(state.capture_state.clone());
-    release_wake_lock(&state.wake_lock);
-
-    let (screen_tx, screen_rx) = mpsc::unbounded_channel::<Vec<f32>>();
-    spawn_screen_capture(app.clone(), state.capture_state.clone(), Some(screen_tx))?;
-
-    let (mic_tx, mic_rx) = mpsc::unbounded_channel::<Vec<f32>>();
-    let (stop_tx, stop_rx) = oneshot::channel();
-    let wake_lock_state = state.wake_lock.clone();
-
-    {
-        let wake_lock = WakeLock::acquire("Meeting Transcriber is recording")
-            .map(Some)
-            .map_err(|err| {
-                eprintln!("Failed to acquire wake lock: {}", err);
-                err
-            })
-            .unwrap_or(None);
-        let mut guard = wake_lock_state.lock();
-        *guard = wake_lock;
+    if updated.screen_share_privacy.hide_transcript {
+        screen_share::watch(app);
+    }
+    if updated.locale != previous.locale {
+        state
+            .sessions
+            .main
+            .transcript
+            .lock()
+            .set_locale(locale::Locale::from_tag(&updated.locale));
     }
 
-    let transcript_state = state.transcript.clone();
-    let is_muted = state.is_muted.clone();
-    let StartRecordingArgs {
-        api_key,
-        additional_vocab,
-        speaker_profile,
-        rt_url,
-    } = args;
-    let additional_vocab = additional_vocab.unwrap_or_default();
-    let speaker_profile_clone = speaker_profile.clone();
+    let _ = app.emit(events::SETTINGS_CHANGED, &updated);
+    Ok(updated)
+}
 
-    let task = tauri::async_runtime::spawn(async move {
-        if let Err(err) = run_transcription(
-            api_key,
-            additional_vocab,
-            speaker_profile_clone,
-            rt_url,
-            screen_rx,
-            mic_rx,
-            stop_rx,
-            window.clone(),
-            transcript_state,
-            is_muted,
-            wake_lock_state.clone(),
-        )
-        .await
-        {
-            let _ = window.emit("recording-error", err.clone());
-            let _ = window.emit("recording-ended", ());
+fn load_settings(app: &AppHandle) {
+    let Ok(dir) = app.path().app_config_dir() else {
+        warn!("Config directory unavailable; using default settings");
+        return;
+    };
+    let state = app.state::<AppState>();
+    let path = state.stores.profiles.lock().active().settings_path(&dir);
+    *state.stores.onboarding.lock() =
+        onboarding::Onboarding::load(path.with_file_name(onboarding::ONBOARDING_FILE));
+    *state.stores.contacts.lock() =
+        contacts::ContactBook::load(path.with_file_name(contacts::CONTACTS_FILE));
+    *state.stores.projects.lock() =
+        projects::Projects::load(path.with_file_name(projects::PROJECTS_FILE));
+    let store = SettingsStore::load(path);
+    let loaded = store.get().clone();
+    *state.stores.settings.lock() = store;
+
+    if let Some(logging) = app.try_state::<logging::Logging>() {
+        if let Err(err) = logging.set_filter(&loaded.log_level) {
+            warn!("{}", err);
         }
-    });
-
-    *state.recording.lock() = Some(RecordingSession {
-        mic_tx,
-        stop_tx: Some(stop_tx),
-        task,
-    });
-
-    Ok(())
+    }
+    if let Err(err) = apply_shortcuts(app, &loaded.shortcuts) {
+        warn!("{}", err);
+    }
 }
 
 #[tauri::command]
-async fn push_mic_audio_chunk(state: State<'_, AppState>, samples: Vec<f32>) -> Result<(), String> {
-    if let Some(session) = state.recording.lock().as_ref() {
-        let payload = if *state.is_muted.lock() {
-            vec![0.0; samples.len()]
-        } else {
-            samples
-        };
-
-        session
-            .mic_tx
-            .send(payload)
-            .map_err(|_| "Recording is no longer active".to_string())
-    } else {
-        Err("Not currently recording".to_string())
-    }
+async fn get_onboarding_state(
+    state: State<'_, AppState>,
+) -> Result<onboarding::OnboardingState, String> {
+    Ok(state.stores.onboarding.lock().state())
 }
 
 #[tauri::command]
-async fn stop_recording(state: State<'_, AppState>) -> Result<(), String> {
-    stop_capture(state.capture_state.clone());
-
-    let mut session_opt = { state.recording.lock().take() };
-    if let Some(mut session) = session_opt.take() {
-        if let Some(stop) = session.stop_tx.take() {
-            let _ = stop.send(());
+async fn complete_step(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    step: onboarding::OnboardingStep,
+) -> Result<onboarding::OnboardingState, String> {
+    if state.stores.onboarding.lock().complete(step)? {
+        let _ = app.emit(
+            events::ONBOARDING_CHANGED,
+            state.stores.onboarding.lock().state(),
+        );
+    }
+    Ok(state.stores.onboarding.lock().state())
+}
+
+fn mark_onboarding_step(app: &AppHandle, state: &AppState, step: onboarding::OnboardingStep) {
+    let mut onboarding = state.stores.onboarding.lock();
+    match onboarding.complete(step) {
+        Ok(true) => {
+            let _ = app.emit(events::ONBOARDING_CHANGED, onboarding.state());
         }
-        let _ = session.task.await;
-        release_wake_lock(&state.wake_lock);
-        Ok(())
-    } else {
-        release_wake_lock(&state.wake_lock);
-        Err("Not recording".to_string())
+        Ok(false) => {}
+        Err(err) => warn!("{}", err),
     }
 }
 
 #[tauri::command]
-async fn mute_recording(state: State<'_, AppState>) -> Result<(), String> {
-    let mut is_muted = state.is_muted.lock();
-    *is_muted = true;
-    Ok(())
+async fn list_projects(state: State<'_, AppState>) -> Result<Vec<projects::Project>, String> {
+    Ok(state.stores.projects.lock().list().to_vec())
 }
 
 #[tauri::command]
-async fn unmute_recording(state: State<'_, AppState>) -> Result<(), String> {
-    let mut is_muted = state.is_muted.lock();
-    *is_muted = false;
-    Ok(())
+async fn create_project(
+    state: State<'_, AppState>,
+    project: projects::ProjectInput,
+) -> Result<projects::Project, String> {
+    state.stores.projects.lock().create(project)
 }
 
 #[tauri::command]
-async fn toggle_mute(state: State<'_, AppState>, window: Window) -> Result<bool, String> {
-    let mut is_muted = state.is_muted.lock();
-    *is_muted = !*is_muted;
-    let new_state = *is_muted;
-    drop(is_muted);
-
-    let _ = window.emit("mute-status-changed", new_state);
-
-    Ok(new_state)
+async fn update_project(
+    state: State<'_, AppState>,
+    project_id: String,
+    project: projects::ProjectInput,
+) -> Result<projects::Project, String> {
+    state.stores.projects.lock().update(&project_id, project)
 }
 
 #[tauri::command]
-async fn get_mute_status(state: State<'_, AppState>) -> Result<bool, String> {
-    Ok(*state.is_muted.lock())
-}
-
-fn release_wake_lock(wake_lock_state: &Arc<Mutex<Option<WakeLock>>>) {
-    let mut guard = wake_lock_state.lock();
-    if let Some(mut lock) = guard.take() {
-        lock.release();
-    }
+async fn delete_project(state: State<'_, AppState>, project_id: String) -> Result<(), String> {
+    state.stores.projects.lock().delete(&project_id)
 }
 
-struct WakeLockGuard {
-    wake_lock_state: Arc<Mutex<Option<WakeLock>>>,
+/// Puts a meeting in a project, or takes it out of its project when `project_id` is omitted.
+/// Takes effect from the meeting's next recording.
+#[tauri::command]
+async fn set_meeting_project(
+    state: State<'_, AppState>,
+    meeting_id: String,
+    project_id: Option<String>,
+) -> Result<(), String> {
+    state
+        .stores
+        .projects
+        .lock()
+        .assign(&meeting_id, project_id.as_deref())
 }
 
-impl Drop for WakeLockGuard {
-    fn drop(&mut self) {
-        release_wake_lock(&self.wake_lock_state);
-    }
+#[tauri::command]
+async fn get_meeting_project(
+    state: State<'_, AppState>,
+    meeting_id: String,
+) -> Result<Option<projects::Project>, String> {
+    Ok(state
+        .stores
+        .projects
+        .lock()
+        .for_meeting(&meeting_id)
+        .cloned())
 }
 
-fn stop_capture(capture_state: Arc<Mutex<Option<CaptureHandle>>>) {
-    let handle = { capture_state.lock().take() };
-    if let Some(CaptureHandle { stop_tx, task }) = handle {
-        let _ = stop_tx.send(());
-        let _ = task.join();
-    }
+#[tauri::command]
+async fn list_profiles(state: State<'_, AppState>) -> Result<profiles::ProfileList, String> {
+    Ok(state.stores.profiles.lock().list().clone())
 }
 
-fn spawn_screen_capture(
+#[tauri::command]
+async fn create_profile(
     app: AppHandle,
-    capture_state: Arc<Mutex<Option<CaptureHandle>>>,
-    pcm_tx: Option<mpsc::UnboundedSender<Vec<f32>>>,
-) -> Result<(), String> {
-    #[cfg(not(target_os = "macos"))]
-    {
-        let _ = pcm_tx;
-        return Err("ScreenCaptureKit capture is only available on macOS 12+".into());
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        let mut guard = capture_state.lock();
-        if guard.is_some() {
-            return Err("Capture already running".into());
-        }
-        let (stop_tx, stop_rx) = std::sync::mpsc::channel();
-        let app_handle = app.clone();
-        let capture_state_handle = capture_state.clone();
-
-        let task = std::thread::spawn(move || {
-            let result = sc_audio_loop(app_handle.clone(), stop_rx, pcm_tx);
-            capture_state_handle.lock().take();
-
-            if let Err(err) = result {
-                let _ = app_handle.emit("capture-error", err.to_string());
-            }
-        });
-
-        *guard = Some(CaptureHandle { stop_tx, task });
-        Ok(())
-    }
+    state: State<'_, AppState>,
+    name: String,
+    storage_dir: Option<String>,
+) -> Result<profiles::Profile, String> {
+    let profile = state.stores.profiles.lock().create(&name, storage_dir)?;
+    refresh_tray(&app);
+    Ok(profile)
 }
 
-#[cfg(target_os = "macos")]
-fn sc_audio_loop(
+/// Makes another profile active, reloading its settings and shortcuts.
+/// The frontend reloads on `profile-changed` to pick up the profile's keys and library.
+#[tauri::command]
+async fn switch_profile(
     app: AppHandle,
-    stop_rx: std::sync::mpsc::Receiver<()>,
-    pcm_tx: Option<mpsc::UnboundedSender<Vec<f32>>>,
-) -> Result<(), anyhow::Error> {
-    use core_foundation::error::CFError;
-    use screencapturekit::{
-        shareable_content::SCShareableContent,
-        stream::{
-            configuration::SCStreamConfiguration, content_filter::SCContentFilter,
-            output_trait::SCStreamOutputTrait, output_type::SCStreamOutputType, SCStream,
-        },
-    };
-    use std::sync::Mutex as StdMutex;
-    use std::thread;
-
-    fn cf_error_to_anyhow(err: CFError) -> anyhow::Error {
-        anyhow::anyhow!("{err:?}")
-    }
-
-    let content = SCShareableContent::get().map_err(cf_error_to_anyhow)?;
-    let display = content
-        .displays()
-        .into_iter()
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("No displays found to capture"))?;
-
-    let config = SCStreamConfiguration::new()
-        .set_captures_audio(true)
-        .map_err(cf_error_to_anyhow)?
-        .set_excludes_current_process_audio(false)
-        .map_err(cf_error_to_anyhow)?
-        .set_sample_rate(SOURCE_SAMPLE_RATE)
-        .map_err(cf_error_to_anyhow)?
-        .set_channel_count(1)
-        .map_err(cf_error_to_anyhow)?;
-
-    let filter = SCContentFilter::new().with_display_excluding_windows(&display, &[]);
-
-    struct AudioLevelOutput {
-        app: AppHandle,
-        last_emit: StdMutex<Instant>,
-        pcm_tx: Option<mpsc::UnboundedSender<Vec<f32>>>,
-    }
-
-    impl SCStreamOutputTrait for AudioLevelOutput {
-        fn did_output_sample_buffer(
-            &self,
-            sample_buffer: screencapturekit::output::CMSampleBuffer,
-            of_type: SCStreamOutputType,
-        ) {
-            if of_type != SCStreamOutputType::Audio {
-                return;
-            }
-
-            let allow_level_emit = {
-                let mut last = self.last_emit.lock().expect("audio event mutex poisoned");
-                if last.elapsed() >= Duration::from_millis(30) {
-                    *last = Instant::now();
-                    true
-                } else {
-                    false
-                }
-            };
-
-            if let Ok(list) = sample_buffer.get_audio_buffer_list() {
-                let mut total = 0.0f64;
-                let mut buffers = 0usize;
-                let mut pcm_samples: Vec<f32> = Vec::new();
-
-                for idx in 0..list.num_buffers() {
-                    if let Some(buf) = list.get(idx) {
-                        let data = buf.data();
-                        if data.is_empty() {
-                            continue;
-                        }
-
-                        if data.len() % std::mem::size_of::<f32>() == 0 {
-                            let samples: &[f32] = bytemuck::cast_slice(data);
-                            if !samples.is_empty() {
-                                let sum = samples
-                                    .iter()
-                                    .map(|v| (*v as f64) * (*v as f64))
-                                    .sum::<f64>();
-                                total += sum / samples.len() as f64;
-                                buffers += 1;
-                                pcm_samples.extend_from_slice(samples);
-                            }
-                        } else if data.len() % std::mem::size_of::<i16>() == 0 {
-                            let samples: &[i16] = bytemuck::cast_slice(data);
-                            if !samples.is_empty() {
-                                let sum = samples
-                                    .iter()
-                                    .map(|v| {
-                                        let n = *v as f64 / i16::MAX as f64;
-                                        n * n
-                                    })
-                                    .sum::<f64>();
-                                total += sum / samples.len() as f64;
-                                buffers += 1;
-                                pcm_samples
-                                    .extend(samples.iter().map(|s| *s as f32 / i16::MAX as f32));
-                            }
-                        }
-                    }
-                }
-
-                if buffers > 0 && allow_level_emit {
-                    let rms = (total / buffers as f64).sqrt().min(1.0);
-                    let _ = self.app.emit("audio-level", rms);
-                }
-
-                if !pcm_samples.is_empty() {
-                    if let Some(tx) = &self.pcm_tx {
-                        let _ = tx.send(pcm_samples);
-                    }
-                }
-            }
-        }
-    }
-
-    let mut stream = SCStream::new(&filter, &config);
-    stream.add_output_handler(
-        AudioLevelOutput {
-            app: app.clone(),
-            last_emit: StdMutex::new(Instant::now()),
-            pcm_tx,
-        },
-        SCStreamOutputType::Audio,
-    );
-
-    stream.start_capture().map_err(cf_error_to_anyhow)?;
-    let _ = app.emit("capture-started", ());
-    let _ = stop_rx.recv();
-    stream.stop_capture().ok();
-    thread::sleep(Duration::from_millis(150));
-    let _ = app.emit("capture-stopped", ());
-    Ok(())
-}
-
-fn normalize_speaker(raw: Option<String>) -> Option<String> {
-    raw.and_then(|s| {
-        let trimmed = s.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    })
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<profiles::Profile, String> {
+    activate_profile(&app, &state, &profile_id)
 }
 
-fn append_turn(turns: &mut Vec<TranscriptTurnPayload>, speaker: Option<String>, text: &str) {
-    let speaker = normalize_speaker(speaker);
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        return;
+fn activate_profile(
+    app: &AppHandle,
+    state: &AppState,
+    id: &str,
+) -> Result<profiles::Profile, String> {
+    if state.sessions.is_recording() {
+        return Err("Stop the current recording before switching profiles".to_string());
     }
-
-    if let Some(last) = turns.last_mut() {
-        if last.speaker == speaker {
-            if !last.text.is_empty() && !last.text.ends_with(char::is_whitespace) {
-                last.text.push(' ');
-            }
-            last.text.push_str(trimmed);
-            return;
-        }
+    if state.stores.profiles.lock().active().id == id {
+        return state
+            .stores
+            .profiles
+            .lock()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown profile: {}", id));
     }
 
-    turns.push(TranscriptTurnPayload {
-        speaker,
-        text: trimmed.to_string(),
-    });
+    let profile = state.stores.profiles.lock().set_active(id)?;
+    load_settings(app);
+    state.sessions.main.transcript.lock().clear();
+    refresh_tray(app);
+    info!(profile = %profile.id, "Switched profile");
+    let _ = app.emit(events::PROFILE_CHANGED, &profile);
+    Ok(profile)
 }
 
-fn render_turns_to_text(turns: &[TranscriptTurnPayload]) -> String {
-    let mut out = String::new();
-
-    for (idx, turn) in turns.iter().enumerate() {
-        if idx > 0 {
-            out.push_str("\n\n");
-        }
-
-        if let Some(speaker) = &turn.speaker {
-            out.push_str(&format!("[{}]: ", speaker));
-        }
-
-        out.push_str(&turn.text);
-    }
-
-    out
+/// Handles `jilu record|transcribe|export ...` without starting the GUI.
+///
+/// Returns the exit code, or `None` when the arguments are for the app itself.
+pub fn run_cli() -> Option<i32> {
+    cli::run_from_args()
 }
 
-async fn run_transcription(
-    api_key: String,
-    additional_vocab: Vec<AdditionalVocabularyEntry>,
-    speaker_profile: Option<SpeakerProfileArg>,
-    rt_url: Option<String>,
-    mut screen_rx: mpsc::UnboundedReceiver<Vec<f32>>,
-    mut mic_rx: mpsc::UnboundedReceiver<Vec<f32>>,
-    mut stop_rx: oneshot::Receiver<()>,
-    window: Window,
-    transcript_state: Arc<Mutex<String>>,
-    is_muted: Arc<Mutex<bool>>,
-    wake_lock_state: Arc<Mutex<Option<WakeLock>>>,
-) -> Result<(), String> {
-    let _wake_lock_guard = WakeLockGuard {
-        wake_lock_state: wake_lock_state.clone(),
-    };
-    let jwt = create_jwt(api_key).await.map_err(|e| {
-        eprintln!("create_jwt failed: {}", e);
-        e.to_string()
-    })?;
-    let ws_url = build_rt_ws_url(rt_url.as_deref(), &jwt);
-    let (ws_stream, _) = connect_async(&ws_url).await.map_err(|e| {
-        eprintln!("connect_async failed: {}", e);
-        e.to_string()
-    })?;
-    let (mut write, mut read) = ws_stream.split();
-
-    let speaker_config = speaker_profile.as_ref().map(|profile| SpeakerDiarizationConfig {
-        get_speakers: None,
-        speakers: Some(vec![KnownSpeaker {
-            label: profile.label.clone(),
-            speaker_identifiers: profile.speaker_identifiers.clone(),
-        }]),
-    });
-
-    let config = SpeechmaticsConfig {
-        message: "StartRecognition".to_string(),
-        transcription_config: TranscriptionConfig {
-            language: "en".to_string(),
-            enable_partials: true,
-            operating_point: "enhanced".to_string(),
-            max_delay: 1.5,
-            diarization: Some("speaker".to_string()),
-            speaker_diarization_config: speaker_config,
-            additional_vocab: if additional_vocab.is_empty() {
-                None
-            } else {
-                Some(additional_vocab)
-            },
-        },
-        audio_format: AudioFormat {
-            format_type: "raw".to_string(),
-            encoding: "pcm_s16le".to_string(),
-            sample_rate: TARGET_SAMPLE_RATE,
-        },
-    };
-
-    if cfg!(debug_assertions) {
-        match serde_json::to_string_pretty(&config) {
-            Ok(pretty) => println!("Speechmatics StartRecognition config:\n{}", pretty),
-            Err(err) => eprintln!("Failed to serialize config for logging: {}", err),
-        }
-    }
-
-    let config_msg =
-        serde_json::to_string(&config).map_err(|e| format!("Failed to encode config: {}", e))?;
-    write
-        .send(Message::Text(config_msg))
-        .await
-        .map_err(|e| format!("Failed to send config: {}", e))?;
-
-    let read_window = window.clone();
-    let mut transcript_turns: Vec<TranscriptTurnPayload> = Vec::new();
-    let transcript_state_clone = transcript_state.clone();
-    let read_handle = tauri::async_runtime::spawn(async move {
-        while let Some(msg) = read.next().await {
-            if let Ok(Message::Text(text)) = msg {
-                match serde_json::from_str::<SpeechmaticsMessage>(&text) {
-                    Ok(parsed) => {
-                        if let Some(error) = parsed.error {
-                            let _ = read_window.emit("recording-error", error);
-                            continue;
-                        }
-
-                        match parsed.message.as_str() {
-                            "AddPartialTranscript" => {
-                                if let Some(text) = extract_text(&parsed) {
-                                    if !text.trim().is_empty() {
-                                        let _ = read_window.emit(
-                                            "transcript-update",
-                                            TranscriptUpdate {
-                                                text: text.clone(),
-                                                is_partial: true,
-                                                turns: None,
-                                            },
-                                        );
-                                    }
-                                }
-                            }
-                            "AddTranscript" => {
-                                let mut appended = false;
-
-                                for result in &parsed.results {
-                                    if let Some(first) = result.alternatives.first() {
-                                        let speaker = first.speaker.clone();
-
-                                        if let Some(text) = first.text() {
-                                            let cleaned = text.trim();
-                                            if cleaned.is_empty() {
-                                                continue;
-                                            }
-
-                                            append_turn(&mut transcript_turns, speaker, cleaned);
-                                            appended = true;
-                                        }
-                                    }
-                                }
-
-                                if !appended {
-                                    if let Some(meta_text) =
-                                        parsed.metadata.as_ref().and_then(|m| m.transcript.as_ref())
-                                    {
-                                        let cleaned = meta_text.trim();
-                                        if !cleaned.is_empty() {
-                                            append_turn(&mut transcript_turns, None, cleaned);
-                                            appended = true;
-                                        }
-                                    }
-                                }
-
-                                if appended && !transcript_turns.is_empty() {
-                                    let final_transcript =
-                                        render_turns_to_text(&transcript_turns);
-
-                                    {
-                                        let mut transcript = transcript_state_clone.lock();
-                                        *transcript = final_transcript.clone();
-                                    }
-
-                                    let _ = read_window.emit(
-                                        "transcript-update",
-                                        TranscriptUpdate {
-                                            text: final_transcript.clone(),
-                                            is_partial: false,
-                                            turns: Some(transcript_turns.clone()),
-                                        },
-                                    );
-                                }
-                            }
-                            "EndOfTranscript" => break,
-                            _ => {}
-                        }
-                    }
-                    Err(err) => {
-                        eprintln!("Failed to parse Speechmatics message: {}", err);
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        // Must be registered first so a second launch exits before doing any setup.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            let request = launch::parse(argv.into_iter().skip(1))
+                .unwrap_or(launch::LaunchRequest::trusted(launch::LaunchIntent::Show));
+            forward_launch_intent(app, request);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(
+            tauri_plugin_stronghold::Builder::new(|password| password.as_bytes().to_vec()).build(),
+        )
+        .manage(AppState::default())
+        .on_window_event(|window, event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main"
+                    && window
+                        .app_handle()
+                        .state::<AppState>()
+                        .sessions
+                        .is_recording()
+                {
+                    api.prevent_close();
+                    let tray_available = window
+                        .app_handle()
+                        .state::<AppState>()
+                        .services
+                        .tray_available
+                        .load(Ordering::Relaxed);
+                    if tray_available {
+                        let _ = window.hide();
+                    } else {
+                        // Nothing could bring a hidden window back.
+                        let _ = window.minimize();
                     }
+                    continue_recording_headless(window.app_handle());
                 }
             }
-        }
-    });
-
-    let mut screen_buf: Vec<f32> = Vec::new();
-    let mut mic_buf: Vec<f32> = Vec::new();
-    let mut seq_no: u32 = 0;
-
-    loop {
-        tokio::select! {
-          _ = &mut stop_rx => break,
-          Some(chunk) = screen_rx.recv() => {
-            screen_buf.extend_from_slice(&chunk);
-          },
-          Some(chunk) = mic_rx.recv() => {
-            mic_buf.extend_from_slice(&chunk);
-          },
-          else => break,
-        }
-
-        while screen_buf.len() >= MIN_BUFFER_THRESHOLD && mic_buf.len() >= MIN_BUFFER_THRESHOLD {
-            if screen_buf.len() < FRAME_SIZE || mic_buf.len() < FRAME_SIZE {
-                break;
+        })
+        .setup(|app| {
+            match app.path().app_log_dir() {
+                Ok(dir) => match logging::Logging::init(&dir) {
+                    Ok(logging) => {
+                        app.manage(logging);
+                    }
+                    Err(err) => eprintln!("File logging disabled: {}", err),
+                },
+                Err(err) => eprintln!("File logging disabled: {}", err),
             }
-
-            let mut mixed = Vec::with_capacity(FRAME_SIZE);
-            for i in 0..FRAME_SIZE {
-                let s = screen_buf[i];
-                let m = mic_buf[i];
-                mixed.push((s + m) * 0.5);
+            info!(version = env!("CARGO_PKG_VERSION"), "Jilu starting");
+            *app.state::<AppState>().services.pending_launch.lock() =
+                launch::parse(std::env::args().skip(1));
+            setup_url_handler(app);
+            if let Ok(dir) = app.path().app_config_dir() {
+                *app.state::<AppState>().stores.profiles.lock() =
+                    profiles::Profiles::load(dir.join(profiles::PROFILES_FILE));
             }
-
-            truncate_buffer(&mut screen_buf, FRAME_SIZE);
-            truncate_buffer(&mut mic_buf, FRAME_SIZE);
-
-            let pcm = resample_to_pcm16(&mixed, SOURCE_SAMPLE_RATE, TARGET_SAMPLE_RATE);
-            if *is_muted.lock() {
-                seq_no += 1;
-                continue;
+            load_settings(app.handle());
+            mark_startup(app.handle(), "builder");
+            match setup_tray(app) {
+                Ok(()) => app
+                    .state::<AppState>()
+                    .services
+                    .tray_available
+                    .store(tray::host_likely(), Ordering::Relaxed),
+                Err(err) => warn!("Running without a tray icon: {}", err),
             }
-
-            if write.send(Message::Binary(pcm)).await.is_err() {
-                eprintln!("WebSocket write failed, stopping audio processing");
-                break;
+            mark_startup(app.handle(), "tray");
+            let power_handle = app.handle().clone();
+            power::observe_sleep(move |event| handle_power_event(&power_handle, event));
+            spawn_resource_reporter(app.handle().clone());
+            if let Ok(dir) = app.path().app_config_dir() {
+                *app.state::<AppState>().services.telemetry.lock() =
+                    telemetry::Telemetry::load(dir.join(telemetry::CONFIG_FILE));
             }
-            seq_no += 1;
-        }
-    }
-
-    tokio::time::sleep(Duration::from_millis(2500)).await;
-
-    let end_of_stream = EndOfStreamMessage {
-        message: "EndOfStream".to_string(),
-        last_seq_no: seq_no,
-    };
-
-    if let Ok(payload) = serde_json::to_string(&end_of_stream) {
-        let _ = write.send(Message::Text(payload)).await;
-    }
-    let _ = write.close().await;
-    let _ = read_handle.await;
-    let _ = window.emit("recording-ended", ());
-    Ok(())
-}
-
-fn truncate_buffer(buffer: &mut Vec<f32>, frame: usize) {
-    if buffer.len() > frame {
-        buffer.drain(0..frame);
-    } else {
-        buffer.clear();
-    }
+            spawn_telemetry_reporter(app.handle().clone());
+            spawn_update_checker(app.handle().clone());
+            recording_imports::spawn_watcher(app.handle().clone());
+            mark_startup(app.handle(), "background_tasks");
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            session::check_recording_allowed,
+            quiet_hours::quiet_hours_status,
+            session::start_recording,
+            session::stop_recording,
+            session::list_recording_sessions,
+            capture::push_mic_audio_chunk,
+            capture::set_native_mic,
+            capture::list_native_mic_devices,
+            capture::measure_mic_level,
+            capture::play_test_tone,
+            storage::get_transcript,
+            storage::get_transcript_turns,
+            storage::get_full_transcript,
+            session::get_interview_qa,
+            storage::get_meeting_analytics,
+            storage::get_meeting_speakers,
+            storage::get_meeting_chapters,
+            storage::save_meeting_chapters,
+            storage::get_level_history,
+            integrations::record_meeting_attendees,
+            integrations::forget_meeting_attendees,
+            integrations::get_meeting_consent,
+            integrations::set_attendee_consent,
+            integrations::set_consent_exclusion,
+            integrations::set_contact_notes,
+            integrations::get_contact_history,
+            integrations::start_sharing,
+            integrations::stop_sharing,
+            integrations::get_sharing_status,
+            screen_share::get_screen_share_status,
+            screen_share::set_screen_sharing,
+            search::search_transcripts,
+            integrations::start_pairing,
+            integrations::stop_pairing,
+            integrations::get_pairing_status,
+            session::set_agenda,
+            session::get_agenda,
+            session::set_agenda_item_covered,
+            session::add_manual_note,
+            session::rename_speaker,
+            session::export_interview,
+            storage::save_transcript,
+            storage::create_directory,
+            storage::write_file,
+            storage::read_file,
+            storage::rename_directory,
+            storage::directory_exists,
+            storage::get_home_directory,
+            shortcuts::register_global_shortcut,
+            shortcuts::unregister_global_shortcut,
+            session::mute_recording,
+            session::unmute_recording,
+            session::toggle_mute,
+            session::switch_language,
+            session::pause_recording,
+            session::resume_recording,
+            session::get_interrupted_session,
+            session::resume_interrupted_session,
+            session::discard_interrupted_session,
+            session::get_mute_status,
+            capture::get_output_route,
+            session::set_mute_mode,
+            session::get_mute_mode,
+            session::update_session_config,
+            storage::export_transcript,
+            storage::export_all_meetings,
+            storage::cancel_bulk_export,
+            storage::export_meeting_package,
+            export::export_meeting_document,
+            duplicates::find_duplicate_meetings,
+            duplicates::merge_meetings,
+            storage::normalize_export_text,
+            diagnostics::get_resource_usage,
+            session::get_stream_health,
+            diagnostics::run_soak_test,
+            diagnostics::set_log_level,
+            diagnostics::open_logs_folder,
+            storage::get_session_timeline,
+            diagnostics::get_telemetry_settings,
+            diagnostics::set_telemetry_settings,
+            diagnostics::preview_telemetry,
+            integrations::check_for_updates,
+            integrations::install_update,
+            diagnostics::run_health_checks,
+            events::get_event_schema,
+            storage::get_provider_usage,
+            storage::get_meeting_cost,
+            storage::get_usage_stats,
+            get_settings,
+            update_settings,
+            set_locale,
+            integrations::notify_summary_ready,
+            get_onboarding_state,
+            take_launch_intent,
+            complete_quick_capture,
+            complete_transcript_request,
+            complete_step,
+            list_projects,
+            create_project,
+            update_project,
+            delete_project,
+            set_meeting_project,
+            get_meeting_project,
+            list_profiles,
+            create_profile,
+            switch_profile,
+            transcription::enroll_speaker_rt,
+            transcription::enroll_current_speaker,
+            shortcuts::register_mute_shortcut,
+            shortcuts::get_shortcut_status,
+            integrations::request_calendar_permission,
+            integrations::check_calendar_permission,
+            integrations::list_calendars,
+            integrations::fetch_calendar_events,
+            recording_imports::import_recording,
+            capture::start_sc_capture,
+            capture::stop_sc_capture,
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let RunEvent::Ready = event {
+                mark_startup(app_handle, "ready");
+                report_startup_profile(app_handle);
+            }
+        });
 }
 
-fn resample_to_pcm16(samples: &[f32], source_rate: u32, target_rate: u32) -> Vec<u8> {
-    if source_rate == 0 || target_rate == 0 {
-        return Vec::new();
+/// Handles a launch forwarded from a second instance or a `jilu://` URL; the window is already loaded.
+///
+/// Requests that need confirming are shown in the window, which asks before acting.
+fn forward_launch_intent(app_handle: &AppHandle, request: launch::LaunchRequest) {
+    info!(?request, "Launch request received");
+    if request.confirm {
+        show_main_window(app_handle);
     }
-
-    if source_rate == target_rate {
-        let mut direct = Vec::with_capacity(samples.len() * 2);
-        for &sample in samples {
-            let clamped = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-            direct.extend_from_slice(&clamped.to_le_bytes());
+    match &request.intent {
+        launch::LaunchIntent::Show => show_main_window(app_handle),
+        launch::LaunchIntent::StartRecording { .. } | launch::LaunchIntent::OpenMeeting { .. } => {
+            show_main_window(app_handle);
+            let _ = app_handle.emit(events::LAUNCH_INTENT, request);
         }
-        return direct;
-    }
-
-    let ratio = source_rate as f32 / target_rate as f32;
-    let out_len = (samples.len() as f32 / ratio).ceil() as usize;
-    let mut output = Vec::with_capacity(out_len * 2);
-
-    for n in 0..out_len {
-        let pos = n as f32 * ratio;
-        let idx = pos.floor() as usize;
-        let frac = pos - idx as f32;
-
-        let s0 = samples.get(idx).copied().unwrap_or(0.0);
-        let s1 = samples.get(idx + 1).copied().unwrap_or(s0);
-        let interp = s0 * (1.0 - frac) + s1 * frac;
-
-        let clamped = (interp.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
-        output.extend_from_slice(&clamped.to_le_bytes());
-    }
-
-    output
-}
-
-async fn create_jwt(api_key: String) -> Result<String, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let body = serde_json::json!({ "ttl": 60 });
-
-    let response = client
-        .post("https://mp.speechmatics.com/v1/api_keys")
-        .query(&[("type", "rt")])
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .json(&body)
-        .send()
-        .await?;
-
-    let status = response.status();
-    let response_text = response.text().await?;
-
-    if !status.is_success() {
-        return Err(format!(
-            "Failed to get JWT token. Status: {}, Response: {}",
-            status, response_text
-        )
-        .into());
-    }
-
-    let json: serde_json::Value = serde_json::from_str(&response_text)?;
-    let jwt = json["key_value"]
-        .as_str()
-        .ok_or("JWT token not found in response")?
-        .to_string();
-
-    Ok(jwt)
-}
-
-fn build_rt_ws_url(rt_url: Option<&str>, jwt: &str) -> String {
-    let base = rt_url
-        .map(|value| value.trim())
-        .filter(|value| !value.is_empty())
-        .unwrap_or(DEFAULT_RT_URL);
-
-    if base.contains('?') {
-        format!("{}&jwt={}", base, jwt)
-    } else {
-        format!("{}?jwt={}", base, jwt)
-    }
-}
-
-#[tauri::command]
-async fn enroll_speaker_rt(
-    api_key: String,
-    samples: Vec<f32>,
-    sample_rate: u32,
-    rt_url: Option<String>,
-) -> Result<Vec<String>, String> {
-    if samples.is_empty() {
-        return Err("No samples provided".to_string());
-    }
-
-    let jwt = create_jwt(api_key)
-        .await
-        .map_err(|e| format!("Failed to create JWT: {}", e))?;
-    let ws_url = build_rt_ws_url(rt_url.as_deref(), &jwt);
-    let (ws_stream, _) = connect_async(&ws_url)
-        .await
-        .map_err(|e| format!("Failed to connect to Speechmatics RT: {}", e))?;
-    let (mut write, mut read) = ws_stream.split();
-
-    let config = SpeechmaticsConfig {
-        message: "StartRecognition".to_string(),
-        transcription_config: TranscriptionConfig {
-            language: "en".to_string(),
-            enable_partials: false,
-            operating_point: "enhanced".to_string(),
-            max_delay: 1.5,
-            diarization: Some("speaker".to_string()),
-            speaker_diarization_config: Some(SpeakerDiarizationConfig {
-                get_speakers: Some(true),
-                speakers: None,
-            }),
-            additional_vocab: None,
-        },
-        audio_format: AudioFormat {
-            format_type: "raw".to_string(),
-            encoding: "pcm_s16le".to_string(),
-            sample_rate: TARGET_SAMPLE_RATE,
-        },
-    };
-
-    let config_msg =
-        serde_json::to_string(&config).map_err(|e| format!("Failed to encode config: {}", e))?;
-    write
-        .send(Message::Text(config_msg))
-        .await
-        .map_err(|e| format!("Failed to send config: {}", e))?;
-
-    let pcm = resample_to_pcm16(&samples, sample_rate, TARGET_SAMPLE_RATE);
-    let mut seq_no: u32 = 0;
-    for chunk in pcm.chunks(320) {
-        if write
-            .send(Message::Binary(chunk.to_vec()))
-            .await
-            .is_err()
-        {
-            return Err("Failed to stream audio to Speechmatics".to_string());
+        launch::LaunchIntent::StopRecording => {
+            let _ = app_handle.emit(events::LAUNCH_INTENT, request);
         }
-        seq_no += 1;
-    }
-
-    let end_of_stream = EndOfStreamMessage {
-        message: "EndOfStream".to_string(),
-        last_seq_no: seq_no,
-    };
-
-    if let Ok(payload) = serde_json::to_string(&end_of_stream) {
-        let _ = write.send(Message::Text(payload)).await;
-    }
-
-    let mut identifiers: Vec<String> = Vec::new();
-    while let Some(msg) = read.next().await {
-        if let Ok(Message::Text(text)) = msg {
-            if let Ok(parsed) = serde_json::from_str::<SpeakersResultMessage>(&text) {
-                if parsed.message == "SpeakersResult" {
-                    if let Some(entries) = parsed.speakers {
-                        for entry in entries {
-                            if let Some(ids) = entry.speaker_identifiers {
-                                identifiers.extend(ids.into_iter().filter(|id| !id.is_empty()));
-                            }
-                        }
-                    }
-                    break;
-                }
-
-                if let Some(err) = parsed.error {
-                    return Err(format!("Speechmatics error: {}", err));
+        launch::LaunchIntent::ToggleMute if request.confirm => {
+            let _ = app_handle.emit(events::LAUNCH_INTENT, request);
+        }
+        launch::LaunchIntent::ToggleMute => {
+            let _ = app_handle.emit(events::MUTE_SHORTCUT_TRIGGERED, ());
+        }
+        launch::LaunchIntent::SendTranscript { callback, .. } => {
+            // Any page can open the link, so nothing leaves until the user agrees.
+            *app_handle
+                .state::<AppState>()
+                .services
+                .transcript_request
+                .lock() = Some(callback.clone());
+            show_main_window(app_handle);
+            let _ = app_handle.emit(events::LAUNCH_INTENT, request);
+        }
+        launch::LaunchIntent::QuickCapture { callback } => {
+            if app_handle
+                .state::<AppState>()
+                .sessions
+                .lock()
+                .contains_key(MAIN_SESSION)
+            {
+                if let Some(error) = &callback.error {
+                    open_callback(app_handle, error, &[("errorMessage", "Already recording")]);
                 }
+                return;
             }
+            *app_handle.state::<AppState>().services.quick_capture.lock() = Some(callback.clone());
+            let _ = app_handle.emit(events::LAUNCH_INTENT, request);
         }
     }
-
-    if identifiers.is_empty() {
-        return Err("Speechmatics did not return any speaker identifiers.".to_string());
-    }
-
-    Ok(identifiers)
 }
 
-#[tauri::command]
-async fn create_directory(path: String) -> Result<(), String> {
-    std::fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))
-}
-
-#[tauri::command]
-async fn write_file(path: String, content: String) -> Result<(), String> {
-    std::fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))
-}
-
-#[tauri::command]
-async fn read_file(path: String) -> Result<String, String> {
-    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
-}
-
-#[tauri::command]
-async fn rename_directory(old_path: String, new_path: String) -> Result<(), String> {
-    if !std::path::Path::new(&old_path).exists() {
-        return Err("Source directory does not exist".to_string());
-    }
-
-    if std::path::Path::new(&new_path).exists() {
-        return Err("Destination directory already exists".to_string());
+fn open_callback(app_handle: &AppHandle, callback: &str, params: &[(&str, &str)]) {
+    let result = launch::callback_url(callback, params).and_then(|url| {
+        app_handle
+            .opener()
+            .open_url(url.as_str(), None::<&str>)
+            .map_err(|e| format!("Failed to open callback URL: {}", e))
+    });
+    if let Err(err) = result {
+        warn!("{}", err);
     }
-
-    std::fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename directory: {}", e))
-}
-
-#[tauri::command]
-async fn directory_exists(path: String) -> Result<bool, String> {
-    Ok(std::path::Path::new(&path).exists())
-}
-
-#[tauri::command]
-async fn get_home_directory() -> Result<String, String> {
-    dirs::home_dir()
-        .and_then(|path| path.to_str().map(|s| s.to_string()))
-        .ok_or_else(|| "Failed to get home directory".to_string())
-}
-
-#[tauri::command]
-async fn register_global_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<(), String> {
-    let shortcut_obj: Shortcut = shortcut
-        .parse()
-        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
-
-    let _ = app.global_shortcut().unregister_all();
-
-    app.global_shortcut()
-        .on_shortcut(shortcut_obj, move |app, _shortcut, _event| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-                let _ = window.emit("global-shortcut-triggered", ());
-            }
-        })
-        .map_err(|e| format!("Failed to register shortcut: {:?}", e))?;
-
-    Ok(())
 }
 
+/// Answers a `jilu://transcript` request: sends the main recording's
+/// transcript to the asking app if `approved`, otherwise drops the request.
 #[tauri::command]
-async fn unregister_global_shortcut(app: tauri::AppHandle) -> Result<(), String> {
-    app.global_shortcut()
-        .unregister_all()
-        .map_err(|e| format!("Failed to unregister shortcuts: {:?}", e))?;
+async fn complete_transcript_request(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    approved: bool,
+) -> Result<(), String> {
+    let Some(callback) = state.services.transcript_request.lock().take() else {
+        return Err("No app is waiting for the transcript".to_string());
+    };
+    if approved {
+        let transcript = state.sessions.main.transcript.lock().text().to_string();
+        info!("Sending the transcript to the app that asked for it");
+        open_callback(&app, &callback, &[("transcript", &transcript)]);
+    }
     Ok(())
 }
 
+/// Reports how a quick capture went to whoever asked for it: the new meeting's
+/// id on success, otherwise the error.
 #[tauri::command]
-async fn register_mute_shortcut(app: tauri::AppHandle, shortcut: String) -> Result<(), String> {
-    let shortcut_obj: Shortcut = shortcut
-        .parse()
-        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
-
-    app.global_shortcut()
-        .on_shortcut(shortcut_obj, move |app, _shortcut, _event| {
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.emit("mute-shortcut-triggered", ());
+async fn complete_quick_capture(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting_id: Option<String>,
+    error: Option<String>,
+) -> Result<(), String> {
+    let Some(callback) = state.services.quick_capture.lock().take() else {
+        return Ok(());
+    };
+    match (meeting_id, error) {
+        (Some(meeting_id), None) => {
+            info!(%meeting_id, "Quick capture started");
+            if let Some(success) = &callback.success {
+                open_callback(&app, success, &[("meetingId", &meeting_id)]);
             }
-        })
-        .map_err(|e| format!("Failed to register mute shortcut: {:?}", e))?;
-
+        }
+        (_, error) => {
+            let error = error.unwrap_or_else(|| "Recording did not start".to_string());
+            warn!("Quick capture failed: {}", error);
+            if let Some(target) = &callback.error {
+                open_callback(&app, target, &[("errorMessage", &error)]);
+            }
+        }
+    }
     Ok(())
 }
 
-#[tauri::command]
-async fn get_transcript(state: State<'_, AppState>) -> Result<String, String> {
-    Ok(state.transcript.lock().clone())
-}
-
-#[tauri::command]
-async fn save_transcript(state: State<'_, AppState>, filename: String) -> Result<String, String> {
-    let transcript = state.transcript.lock().clone();
-
-    let path = std::env::current_dir()
-        .map_err(|e| e.to_string())?
-        .join(&filename);
-
-    std::fs::write(&path, transcript.as_bytes())
-        .map_err(|e| format!("Failed to save file: {}", e))?;
-
-    Ok(format!("Transcript saved to {}", path.display()))
-}
-
-#[tauri::command]
-async fn start_sc_capture(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    spawn_screen_capture(app, state.capture_state.clone(), None)
-}
-
-#[tauri::command]
-async fn stop_sc_capture(state: State<'_, AppState>) -> Result<(), String> {
-    stop_capture(state.capture_state.clone());
-    Ok(())
-}
+/// Registers the `jilu://` handler. A URL that launched the app is kept for
+/// the frontend like a command-line intent.
+fn setup_url_handler(app: &tauri::App) {
+    let app_handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            match launch::parse_url(&url) {
+                Ok(intent) => {
+                    forward_launch_intent(&app_handle, launch::LaunchRequest::from_url(intent))
+                }
+                Err(err) => warn!("Ignoring {}: {}", url, err),
+            }
+        }
+    });
 
-fn clean_punctuation(text: &str) -> String {
-    let mut result = text.to_string();
-    let punctuation = [" .", " ,", " !", " ?", " :", " ;", " '", " \""];
-    for punc in &punctuation {
-        result = result.replace(punc, &punc[1..]);
+    let launch_urls = app
+        .deep_link()
+        .get_current()
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let intent = launch_urls
+        .iter()
+        .filter_map(|url| launch::parse_url(url).ok())
+        .find(|intent| {
+            matches!(
+                intent,
+                launch::LaunchIntent::Show
+                    | launch::LaunchIntent::StartRecording { .. }
+                    | launch::LaunchIntent::OpenMeeting { .. }
+                    | launch::LaunchIntent::QuickCapture { .. }
+                    | launch::LaunchIntent::SendTranscript { .. }
+            )
+        });
+    if let Some(intent) = intent {
+        match &intent {
+            launch::LaunchIntent::QuickCapture { callback } => {
+                *app.state::<AppState>().services.quick_capture.lock() = Some(callback.clone());
+            }
+            launch::LaunchIntent::SendTranscript { callback, .. } => {
+                *app.state::<AppState>().services.transcript_request.lock() =
+                    Some(callback.clone());
+            }
+            _ => {}
+        }
+        app.state::<AppState>()
+            .services
+            .pending_launch
+            .lock()
+            .get_or_insert(launch::LaunchRequest::from_url(intent));
     }
-    result
 }
 
+/// Returns the intent this process was started with, once.
 #[tauri::command]
-async fn request_calendar_permission() -> Result<bool, String> {
-    calendar::request_calendar_access()
+async fn take_launch_intent(
+    state: State<'_, AppState>,
+) -> Result<Option<launch::LaunchRequest>, String> {
+    Ok(state.services.pending_launch.lock().take())
 }
 
-#[tauri::command]
-async fn check_calendar_permission() -> Result<bool, String> {
-    calendar::check_calendar_access()
+fn mark_startup(app_handle: &AppHandle, phase: &'static str) {
+    app_handle
+        .state::<AppState>()
+        .services
+        .startup_profile
+        .lock()
+        .mark(phase);
 }
 
-#[tauri::command]
-async fn list_calendars() -> Result<Vec<calendar::Calendar>, String> {
-    calendar::list_calendars()
+#[cfg(debug_assertions)]
+fn report_startup_profile(app_handle: &AppHandle) {
+    let profile = app_handle
+        .state::<AppState>()
+        .services
+        .startup_profile
+        .lock()
+        .clone();
+    debug!(phases = ?profile.phases, "Startup profile");
+    let _ = app_handle.emit(events::STARTUP_PROFILE, profile);
 }
 
-#[tauri::command]
-async fn fetch_calendar_events(
-    calendar_ids: Vec<String>,
-    start_date: String,
-    end_date: String,
-) -> Result<Vec<calendar::CalendarEvent>, String> {
-    calendar::fetch_events(calendar_ids, start_date, end_date)
-}
+#[cfg(not(debug_assertions))]
+fn report_startup_profile(_app_handle: &AppHandle) {}
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .plugin(tauri_plugin_notification::init())
-        .plugin(
-            tauri_plugin_stronghold::Builder::new(|password| password.as_bytes().to_vec()).build(),
-        )
-        .manage(AppState::default())
-        .setup(|app| {
-            setup_tray(app)?;
-            Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
-            start_recording,
-            stop_recording,
-            push_mic_audio_chunk,
-            get_transcript,
-            save_transcript,
-            create_directory,
-            write_file,
-            read_file,
-            rename_directory,
-            directory_exists,
-            get_home_directory,
-            register_global_shortcut,
-            unregister_global_shortcut,
-            mute_recording,
-            unmute_recording,
-            toggle_mute,
-            get_mute_status,
-            enroll_speaker_rt,
-            register_mute_shortcut,
-            request_calendar_permission,
-            check_calendar_permission,
-            list_calendars,
-            fetch_calendar_events,
-            start_sc_capture,
-            stop_sc_capture
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
-
-fn setup_tray(app: &mut tauri::App) -> tauri::Result<()> {
+fn setup_tray(app: &mut tauri::App) -> Result<(), String> {
     let app_handle = app.handle();
-
-    let tray_menu = MenuBuilder::new(app_handle)
-        .item(
-            &MenuItemBuilder::with_id("tray-show", "Show Jilu").build(app_handle)?,
-        )
-        .item(
-            &MenuItemBuilder::with_id("tray-new-meeting", "New Meeting")
-                .build(app_handle)?,
-        )
-        .item(
-            &MenuItemBuilder::with_id("tray-toggle-mute", "Toggle Mute")
-                .build(app_handle)?,
-        )
-        .separator()
-        .item(&MenuItemBuilder::with_id("tray-quit", "Quit").build(app_handle)?)
-        .build()?;
+    let tray_menu = build_tray_menu(app_handle).map_err(|e| e.to_string())?;
 
     let mut tray_builder = TrayIconBuilder::with_id("main-tray")
         .menu(&tray_menu)
-        .tooltip("Jilu")
-        .on_tray_icon_event(|tray, event| match event {
-            TrayIconEvent::Click { .. } => {
+        .tooltip(tray_tooltip(app_handle))
+        // The menu opens on a right click on Windows and on any click on macOS.
+        .show_menu_on_left_click(cfg!(target_os = "macos"))
+        .on_tray_icon_event(|tray, event| {
+            // Each click arrives pressed and released; Linux sends neither,
+            // and has "Show Jilu" in the menu instead.
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
                 show_main_window(tray.app_handle());
             }
-            _ => {}
         })
         .on_menu_event(|app_handle, event| match event.id().as_ref() {
             "tray-show" => {
@@ -1322,27 +767,202 @@ fn setup_tray(app: &mut tauri::App) -> tauri::Result<()> {
             }
             "tray-new-meeting" => {
                 show_main_window(app_handle);
-                let _ = app_handle.emit("global-shortcut-triggered", ());
+                let _ = app_handle.emit(events::GLOBAL_SHORTCUT_TRIGGERED, ());
             }
             "tray-toggle-mute" => {
-                let _ = app_handle.emit("mute-shortcut-triggered", ());
+                if !app_handle.state::<AppState>().sessions.is_recording() {
+                    let _ = app_handle.emit(events::MUTE_SHORTCUT_TRIGGERED, ());
+                    return;
+                }
+                // Handled here rather than in the window, which may be closed.
+                match toggle_session_mute(&app_handle.state::<AppState>(), MAIN_SESSION) {
+                    Ok(muted) => {
+                        let _ = app_handle.emit(events::MUTE_STATUS_CHANGED, muted);
+                        refresh_tray(app_handle);
+                    }
+                    Err(err) => warn!("Failed to toggle mute from tray: {}", err),
+                }
+            }
+            "tray-toggle-pause" => {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    let state = app_handle.state::<AppState>();
+                    let result = if tray::TrayState::of(&state) == tray::TrayState::Paused {
+                        session::resume_recording(app_handle.clone(), state, None).await
+                    } else {
+                        session::pause_recording(app_handle.clone(), state, None).await
+                    };
+                    if let Err(err) = result {
+                        warn!("Failed to pause or resume from tray: {}", err);
+                    }
+                });
+            }
+            "tray-stop-recording" => {
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    stop_recording_from_tray(&app_handle).await;
+                });
             }
             "tray-quit" => {
-                app_handle.exit(0);
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    // Save what was recorded before the process goes away.
+                    if app_handle.state::<AppState>().sessions.is_recording() {
+                        stop_recording_from_tray(&app_handle).await;
+                    }
+                    app_handle.exit(0);
+                });
+            }
+            id => {
+                if let Some(profile_id) = id.strip_prefix(TRAY_PROFILE_PREFIX) {
+                    let state = app_handle.state::<AppState>();
+                    if let Err(err) = activate_profile(app_handle, &state, profile_id) {
+                        warn!("Failed to switch profile from tray: {}", err);
+                    }
+                    // The clicked item toggles itself; redraw so only the active one is checked.
+                    refresh_tray(app_handle);
+                }
             }
-            _ => {}
         });
 
     if let Some(icon) = app_handle.default_window_icon().cloned() {
         tray_builder = tray_builder.icon(icon);
     }
 
-    let tray = tray_builder.build(app)?;
+    // Without an AppIndicator library, Linux fails here by panicking.
+    let tray = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| tray_builder.build(app)))
+        .map_err(|_| "no AppIndicator library is installed".to_string())?
+        .map_err(|e| e.to_string())?;
     app.manage(tray);
 
     Ok(())
 }
 
+const TRAY_PROFILE_PREFIX: &str = "tray-profile:";
+
+fn build_tray_menu(app_handle: &AppHandle) -> tauri::Result<Menu> {
+    let profiles = app_handle
+        .state::<AppState>()
+        .stores
+        .profiles
+        .lock()
+        .list()
+        .clone();
+    let active_name = profiles
+        .profiles
+        .iter()
+        .find(|p| p.id == profiles.active)
+        .map(|p| p.name.as_str())
+        .unwrap_or_default();
+
+    let mut profile_menu = SubmenuBuilder::new(app_handle, format!("Profile: {}", active_name));
+    for profile in &profiles.profiles {
+        profile_menu = profile_menu.item(
+            &CheckMenuItemBuilder::with_id(
+                format!("{}{}", TRAY_PROFILE_PREFIX, profile.id),
+                &profile.name,
+            )
+            .checked(profile.id == profiles.active)
+            .build(app_handle)?,
+        );
+    }
+
+    let tray_state = tray::TrayState::of(&app_handle.state::<AppState>());
+    let recording = tray_state != tray::TrayState::Idle;
+    let mute_label = match tray_state {
+        tray::TrayState::Idle | tray::TrayState::Paused => "Toggle Mute",
+        tray::TrayState::Recording => "Mute",
+        tray::TrayState::Muted => "Unmute",
+    };
+    let pause_label = if tray_state == tray::TrayState::Paused {
+        "Resume Recording"
+    } else {
+        "Pause Recording"
+    };
+    let mut menu = MenuBuilder::new(app_handle);
+    if cfg!(target_os = "linux") {
+        // AppIndicator shows no tooltip, so what it would say goes here.
+        menu = menu
+            .item(
+                &MenuItemBuilder::with_id("tray-status", tray_tooltip(app_handle))
+                    .enabled(false)
+                    .build(app_handle)?,
+            )
+            .separator();
+    }
+    menu.item(&MenuItemBuilder::with_id("tray-show", "Show Jilu").build(app_handle)?)
+        .item(
+            &MenuItemBuilder::with_id("tray-new-meeting", "New Meeting")
+                .enabled(!recording)
+                .build(app_handle)?,
+        )
+        .item(
+            &MenuItemBuilder::with_id("tray-stop-recording", "Stop Recording")
+                .enabled(recording)
+                .build(app_handle)?,
+        )
+        .item(
+            &MenuItemBuilder::with_id("tray-toggle-pause", pause_label)
+                .enabled(recording)
+                .build(app_handle)?,
+        )
+        .item(&MenuItemBuilder::with_id("tray-toggle-mute", mute_label).build(app_handle)?)
+        .separator()
+        .item(&profile_menu.build()?)
+        .separator()
+        .item(&MenuItemBuilder::with_id("tray-quit", "Quit").build(app_handle)?)
+        .build()
+}
+
+fn tray_tooltip(app_handle: &AppHandle) -> String {
+    let state = app_handle.state::<AppState>();
+    let profiles = state.stores.profiles.lock();
+    let name = if profiles.list().profiles.len() > 1 {
+        format!("Jilu ({})", profiles.active().name)
+    } else {
+        "Jilu".to_string()
+    };
+    match tray::TrayState::of(&state).describe() {
+        Some(status) => format!("{} - {}", name, status),
+        None => name,
+    }
+}
+
+/// Rebuilds the tray menu so the profile list and check mark stay current.
+fn refresh_tray(app_handle: &AppHandle) {
+    let Some(tray) = app_handle.try_state::<TrayIcon>() else {
+        return;
+    };
+    match build_tray_menu(app_handle) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(err) => warn!("Failed to rebuild tray menu: {}", err),
+    }
+    let _ = tray.set_tooltip(Some(tray_tooltip(app_handle)));
+    if let Some(base) = app_handle.default_window_icon() {
+        let state = tray::TrayState::of(&app_handle.state::<AppState>());
+        let _ = tray.set_icon(Some(tray::icon(base, state)));
+    }
+}
+
+/// Stops the main session without the window, then lets the window finish
+/// the meeting (title, export, notes) if it is still around.
+async fn stop_recording_from_tray(app_handle: &AppHandle) {
+    let meeting_id = app_handle
+        .state::<AppState>()
+        .sessions
+        .lock()
+        .get(MAIN_SESSION)
+        .and_then(|session| session.meeting_id.clone());
+    match stop_recording(app_handle.clone(), app_handle.state::<AppState>(), None).await {
+        Ok(()) => {
+            let _ = app_handle.emit(events::RECORDING_STOPPED_IN_BACKGROUND, meeting_id);
+        }
+        Err(err) => warn!("Failed to stop recording from tray: {}", err),
+    }
+}
+
 fn show_main_window(app_handle: &AppHandle) {
     if let Some(window) = app_handle.get_webview_window("main") {
         let _ = window.show();
@@ -0,0 +1,103 @@
+use crate::consent::MeetingConsent;
+use crate::locale::Locale;
+use crate::settings::{LiveTranscriptFormat, LiveTranscriptSettings};
+use crate::transcript::TranscriptTurnPayload;
+use crate::transcript_export::clock_timestamp;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Writes each turn to the live transcript file once it is finished, i.e.
+/// once the next turn has started. Every turn is written straight through,
+/// so a reader following the file sees it immediately.
+pub struct LiveTranscriptFile {
+    file: File,
+    format: LiveTranscriptFormat,
+    locale: Locale,
+    /// Turns already in the file, or left out of it for lack of consent.
+    written: usize,
+}
+
+impl LiveTranscriptFile {
+    /// Empties the configured file for a new recording; `None` when live
+    /// output is off.
+    pub fn create(
+        settings: &LiveTranscriptSettings,
+        title: Option<&str>,
+        locale: Locale,
+    ) -> Result<Option<Self>, String> {
+        let Some(path) = &settings.path else {
+            return Ok(None);
+        };
+        let path = PathBuf::from(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut file = File::create(&path)
+            .map_err(|e| format!("Failed to open live transcript {}: {}", path.display(), e))?;
+        if settings.format == LiveTranscriptFormat::Text {
+            let started = chrono::Local::now().format("%Y-%m-%d %H:%M");
+            let heading = match title.map(str::trim).filter(|title| !title.is_empty()) {
+                Some(title) => format!("# {} ({})\n\n", title, started),
+                None => format!("# Recording started {}\n\n", started),
+            };
+            file.write_all(heading.as_bytes())
+                .map_err(|e| format!("Failed to write live transcript: {}", e))?;
+        }
+        Ok(Some(Self {
+            file,
+            format: settings.format,
+            locale,
+            written: 0,
+        }))
+    }
+
+    /// Appends the turns of `turns` not yet written that can no longer
+    /// change: all but the last, or every one once `finished`. Speech
+    /// `consent` excludes is skipped.
+    pub fn write_final(
+        &mut self,
+        turns: &[TranscriptTurnPayload],
+        finished: bool,
+        consent: &MeetingConsent,
+    ) -> Result<(), String> {
+        let end = if finished {
+            turns.len()
+        } else {
+            turns.len().saturating_sub(1)
+        };
+        for turn in turns.iter().take(end).skip(self.written) {
+            if consent.excludes(turn) {
+                self.written += 1;
+                continue;
+            }
+            let line = self.line(turn)?;
+            self.file
+                .write_all(line.as_bytes())
+                .map_err(|e| format!("Failed to write live transcript: {}", e))?;
+            self.written += 1;
+        }
+        Ok(())
+    }
+
+    fn line(&self, turn: &TranscriptTurnPayload) -> Result<String, String> {
+        match self.format {
+            LiveTranscriptFormat::Jsonl => serde_json::to_string(turn)
+                .map(|json| json + "\n")
+                .map_err(|e| e.to_string()),
+            LiveTranscriptFormat::Text => {
+                let mut line = String::new();
+                if let Some(offset) = turn.offset.or(turn.start) {
+                    line.push_str(&format!("[{}] ", clock_timestamp(offset)));
+                }
+                if let Some(prefix) = turn.prefix(self.locale) {
+                    line.push_str(&prefix);
+                }
+                line.push_str(&turn.text);
+                line.push('\n');
+                Ok(line)
+            }
+        }
+    }
+}
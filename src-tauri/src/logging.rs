@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const DEFAULT_FILTER: &str = "info";
+const MAX_LOG_FILES: usize = 7;
+
+/// Handle to the global subscriber: owns the file writer and allows changing
+/// the level filter at runtime.
+pub struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    log_dir: PathBuf,
+    _guard: WorkerGuard,
+}
+
+impl Logging {
+    /// Installs JSON logging to daily-rotated files in `log_dir`.
+    ///
+    /// `RUST_LOG` overrides the default `info` filter. Debug builds also log to stderr.
+    pub fn init(log_dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(log_dir)
+            .map_err(|e| format!("Failed to create log directory: {}", e))?;
+
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("jilu")
+            .filename_suffix("log")
+            .max_log_files(MAX_LOG_FILES)
+            .build(log_dir)
+            .map_err(|e| format!("Failed to open log file: {}", e))?;
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        let initial =
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+        let (filter, handle) = reload::Layer::new(initial);
+
+        let stderr = cfg!(debug_assertions).then(|| fmt::layer().with_writer(std::io::stderr));
+
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().json().with_writer(writer))
+            .with(stderr)
+            .try_init()
+            .map_err(|e| format!("Failed to install logger: {}", e))?;
+
+        Ok(Self {
+            filter: handle,
+            log_dir: log_dir.to_path_buf(),
+            _guard: guard,
+        })
+    }
+
+    /// Plain stderr logging for command-line runs, quiet unless `RUST_LOG` says otherwise.
+    pub fn init_cli() {
+        let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer().with_writer(std::io::stderr))
+            .try_init();
+    }
+
+    /// Replaces the active filter, e.g. `info,jilu_lib::audio=debug`.
+    pub fn set_filter(&self, directives: &str) -> Result<(), String> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| format!("Invalid log level '{}': {}", directives, e))?;
+        self.filter
+            .reload(filter)
+            .map_err(|e| format!("Failed to update log level: {}", e))
+    }
+
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+}
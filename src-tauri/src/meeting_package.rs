@@ -0,0 +1,336 @@
+//! A meeting bundled for people who don't use Jilu: its audio, the
+//! transcript in several formats, the summary and action items, and an
+//! `index.html` that plays the audio alongside the transcript.
+
+use crate::bulk_export::{self, MANIFEST_FILE};
+use crate::consent::{self, ConsentStatus, MeetingConsent};
+use crate::formatting::{self, FormattingSettings};
+use crate::locale::Locale;
+use crate::normalize::Normalizer;
+use crate::transcript::TranscriptTurnPayload;
+use crate::transcript_export::{self, ExportFormat};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const VIEWER: &str = include_str!("package_viewer.html");
+const VIEWER_FILE: &str = "index.html";
+const SUMMARY_FILE: &str = "summary.md";
+const NOTES_FILE: &str = "notes.md";
+const ACTION_ITEMS_FILE: &str = "action-items.md";
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "flac"];
+/// Summary headings whose bullets are the action items, lowercased.
+const ACTION_HEADINGS: &[&str] = &["action item", "next step", "to-do", "todo"];
+
+/// What the frontend knows about the meeting being packaged.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageInfo {
+    pub id: String,
+    pub title: Option<String>,
+    /// Start of the meeting (RFC 3339).
+    pub date: Option<String>,
+    /// The AI summary, as Markdown.
+    pub summary: Option<String>,
+    /// Personal notes, as Markdown.
+    pub notes: Option<String>,
+}
+
+/// Written to the package as `manifest.json`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageManifest {
+    pub exported_at: String,
+    pub id: String,
+    pub title: Option<String>,
+    /// Folder the package was written to.
+    pub root: String,
+    pub files: Vec<String>,
+    pub action_items: Vec<String>,
+    /// Why the audio was left out, when it was.
+    pub audio_withheld: Option<String>,
+}
+
+pub struct MeetingPackage {
+    pub meeting_dir: PathBuf,
+    pub destination: PathBuf,
+    pub locale: Locale,
+    pub formatting: FormattingSettings,
+    /// Runs after `formatting`, when normalization is on.
+    pub normalizer: Option<Normalizer>,
+    pub info: PackageInfo,
+}
+
+/// Everything `index.html` shows, embedded in the page.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ViewerData<'a> {
+    title: &'a str,
+    date: Option<String>,
+    summary: Option<&'a str>,
+    action_items: &'a [String],
+    /// Played together; the first has the controls.
+    audio: Vec<String>,
+    turns: Vec<ViewerTurn<'a>>,
+}
+
+#[derive(Serialize)]
+struct ViewerTurn<'a> {
+    speaker: Option<&'a str>,
+    text: &'a str,
+    /// Seconds into the audio.
+    time: Option<f64>,
+}
+
+impl MeetingPackage {
+    /// Writes the package to a new folder under `destination`.
+    pub fn write(self) -> Result<PackageManifest, String> {
+        if !self.destination.is_absolute() {
+            return Err("Export destination must be an absolute path".to_string());
+        }
+        let info = &self.info;
+        let title = info
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        let date = info.date.as_deref().and_then(bulk_export::local_date);
+        let summary = info
+            .summary
+            .as_deref()
+            .filter(|summary| !summary.trim().is_empty());
+        let notes = info
+            .notes
+            .as_deref()
+            .filter(|notes| !notes.trim().is_empty());
+        let mut turns = saved_turns(&self.meeting_dir)?;
+        if turns.is_empty() && summary.is_none() && notes.is_none() {
+            return Err("Nothing has been saved for this meeting yet".to_string());
+        }
+
+        let mut name = format!(
+            "{}-package",
+            bulk_export::folder_name(&info.id, date, title, &mut HashSet::new())
+        );
+        if self.destination.join(&name).exists() {
+            name = format!("{}-{}", name, Local::now().format("%Y%m%d-%H%M%S"));
+        }
+        let root = self.destination.join(&name);
+        std::fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+        let mut files = Vec::new();
+
+        let audio_withheld = audio_withheld(&self.meeting_dir);
+        let audio = match audio_withheld {
+            Some(_) => Vec::new(),
+            None => audio_files(&self.meeting_dir),
+        };
+        for path in &audio {
+            let Some(file) = path.file_name() else {
+                continue;
+            };
+            std::fs::copy(path, root.join(file))
+                .map_err(|e| format!("Failed to copy {}: {}", path.display(), e))?;
+            files.push(file.to_string_lossy().into_owned());
+        }
+
+        let mut write = |name: &str, contents: &[u8]| -> Result<(), String> {
+            std::fs::write(root.join(name), contents)
+                .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+            files.push(name.to_string());
+            Ok(())
+        };
+
+        formatting::apply_to_turns(&mut turns, &self.formatting);
+        if let Some(normalizer) = &self.normalizer {
+            normalizer.apply_to_turns(&mut turns);
+        }
+        if !turns.is_empty() {
+            let chapters = crate::storage::saved_chapters(&self.meeting_dir)?;
+            for format in [
+                ExportFormat::Txt,
+                ExportFormat::Json,
+                ExportFormat::Srt,
+                ExportFormat::Vtt,
+            ] {
+                match transcript_export::render(&turns, &chapters, format, self.locale) {
+                    Ok(rendered) => write(
+                        &format!("transcript.{}", format.extension()),
+                        rendered.as_bytes(),
+                    )?,
+                    // Subtitles need timings, which not every transcript has.
+                    Err(err) if format.is_subtitles() => {
+                        warn!("Package has no subtitles: {}", err)
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        let action_items = summary.map(action_items).unwrap_or_default();
+        if let Some(summary) = summary {
+            write(SUMMARY_FILE, summary.as_bytes())?;
+        }
+        if !action_items.is_empty() {
+            let list: String = action_items
+                .iter()
+                .map(|item| format!("- [ ] {}\n", item))
+                .collect();
+            write(ACTION_ITEMS_FILE, list.as_bytes())?;
+        }
+        if let Some(notes) = notes {
+            write(NOTES_FILE, notes.as_bytes())?;
+        }
+
+        let data = ViewerData {
+            title: title.unwrap_or("Meeting"),
+            date: date.map(|date| date.format("%A %-d %B %Y").to_string()),
+            summary,
+            action_items: &action_items,
+            audio: first_recording(&audio),
+            turns: turns
+                .iter()
+                .map(|turn| ViewerTurn {
+                    speaker: if turn.note {
+                        Some(self.locale.note_word())
+                    } else {
+                        turn.speaker.as_deref()
+                    },
+                    text: &turn.text,
+                    time: turn.offset.or(turn.start),
+                })
+                .collect(),
+        };
+        // Keeps a `</script>` in the transcript from ending the data block.
+        let data = serde_json::to_string(&data)
+            .map_err(|e| e.to_string())?
+            .replace("</", "<\\/");
+        write(
+            VIEWER_FILE,
+            VIEWER.replace("{{PACKAGE_DATA}}", &data).as_bytes(),
+        )?;
+
+        let manifest = PackageManifest {
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            id: info.id.clone(),
+            title: title.map(str::to_string),
+            root: root.to_string_lossy().into_owned(),
+            files,
+            action_items,
+            audio_withheld,
+        };
+        let contents = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        std::fs::write(root.join(MANIFEST_FILE), contents)
+            .map_err(|e| format!("Failed to write package manifest: {}", e))?;
+        Ok(manifest)
+    }
+}
+
+/// The saved transcript, which already leaves out declined attendees.
+fn saved_turns(dir: &Path) -> Result<Vec<TranscriptTurnPayload>, String> {
+    match std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE)) {
+        Ok(contents) => {
+            serde_json::from_str(&contents).map_err(|e| format!("Unreadable transcript: {}", e))
+        }
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// The audio still has the voices the transcript leaves out, so it stays
+/// behind when someone declined to be recorded.
+fn audio_withheld(dir: &Path) -> Option<String> {
+    let consent = MeetingConsent::load(&dir.join(consent::CONSENT_FILE))
+        .map_err(|err| warn!("{}", err))
+        .unwrap_or_default();
+    let declined = consent
+        .attendees
+        .iter()
+        .any(|entry| entry.status == ConsentStatus::Declined);
+    (consent.exclude_declined && declined)
+        .then(|| "An attendee declined to be recorded".to_string())
+}
+
+/// Audio saved by the recorder, oldest recording first.
+fn audio_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let named = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("recording-"));
+            let audio = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext));
+            named && audio
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// The files of the first recording, which the transcript's times start
+/// from: one mixed file, or a file per source.
+fn first_recording(audio: &[PathBuf]) -> Vec<String> {
+    let stem = |path: &PathBuf| {
+        let stem = path.file_stem()?.to_str()?;
+        Some(
+            stem.strip_suffix("-mic")
+                .or_else(|| stem.strip_suffix("-system"))
+                .unwrap_or(stem)
+                .to_string(),
+        )
+    };
+    let Some(first) = audio.first().and_then(stem) else {
+        return Vec::new();
+    };
+    audio
+        .iter()
+        .filter(|path| stem(path).as_ref() == Some(&first))
+        .filter_map(|path| path.file_name()?.to_str().map(str::to_string))
+        .collect()
+}
+
+/// Bullets under the summary's action item heading, if it has one.
+fn action_items(summary: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut in_section = false;
+    for line in summary.lines().map(str::trim) {
+        let heading = line.starts_with('#') || (line.starts_with("**") && line.ends_with("**"));
+        if heading {
+            let heading = line.to_lowercase();
+            in_section = ACTION_HEADINGS.iter().any(|name| heading.contains(name));
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        let Some(item) = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))
+            .or_else(|| line.strip_prefix("+ "))
+            .or_else(|| {
+                let (number, rest) = line.split_once(". ")?;
+                number.chars().all(|c| c.is_ascii_digit()).then_some(rest)
+            })
+        else {
+            continue;
+        };
+        let item = ["[ ] ", "[x] ", "[X] "]
+            .iter()
+            .find_map(|checkbox| item.strip_prefix(checkbox))
+            .unwrap_or(item)
+            .trim();
+        if !item.is_empty() {
+            items.push(item.to_string());
+        }
+    }
+    items
+}
@@ -0,0 +1,90 @@
+use crate::profiles::Profile;
+use crate::state::AppState;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Directory holding backend-side data for one meeting in the active profile, created on demand.
+pub fn meeting_dir(app: &AppHandle, meeting_id: &str) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = app
+        .state::<AppState>()
+        .stores
+        .profiles
+        .lock()
+        .active()
+        .clone();
+    let dir = meeting_path(&profile, &data_dir, meeting_id)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create meeting directory: {}", e))?;
+    Ok(dir)
+}
+
+/// Location of a meeting's directory without creating it; also used by the CLI.
+pub fn meeting_path(
+    profile: &Profile,
+    data_dir: &Path,
+    meeting_id: &str,
+) -> Result<PathBuf, String> {
+    validate_meeting_id(meeting_id)?;
+    Ok(meetings_root(profile, data_dir).join(meeting_id))
+}
+
+/// Directory holding every meeting directory of `profile`.
+pub fn meetings_root(profile: &Profile, data_dir: &Path) -> PathBuf {
+    profile.data_dir(data_dir).join("meetings")
+}
+
+/// Meeting ids double as directory names, so only letters, digits, `-` and `_` are allowed.
+pub fn validate_meeting_id(meeting_id: &str) -> Result<(), String> {
+    let valid = !meeting_id.is_empty()
+        && meeting_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid meeting id: {}", meeting_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::DEFAULT_PROFILE_ID;
+
+    fn profile(id: &str, storage_dir: Option<&str>) -> Profile {
+        Profile {
+            id: id.to_string(),
+            name: id.to_string(),
+            storage_dir: storage_dir.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn meetings_live_under_their_profile() {
+        let data = Path::new("/data");
+        assert_eq!(
+            meeting_path(&profile(DEFAULT_PROFILE_ID, None), data, "m1"),
+            Ok(PathBuf::from("/data/meetings/m1"))
+        );
+        assert_eq!(
+            meeting_path(&profile("work", None), data, "m1"),
+            Ok(PathBuf::from("/data/profiles/work/meetings/m1"))
+        );
+        assert_eq!(
+            meeting_path(&profile("work", Some("/shared/jilu")), data, "m1"),
+            Ok(PathBuf::from("/shared/jilu/meetings/m1"))
+        );
+    }
+
+    #[test]
+    fn meeting_ids_cannot_leave_the_meetings_folder() {
+        for id in ["", "..", "../m1", "a/b", "m 1"] {
+            assert!(validate_meeting_id(id).is_err(), "{:?}", id);
+        }
+        assert!(validate_meeting_id("2024-05-01_standup").is_ok());
+    }
+}
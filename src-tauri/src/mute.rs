@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+/// What muting a recording does. Muting for the meeting and muting for the
+/// transcript are different things, so each is its own mode.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MuteMode {
+    /// Leave the microphone out; everyone else is still transcribed.
+    #[default]
+    Mic,
+    /// Leave system audio out; only the microphone is transcribed.
+    System,
+    /// Stop sending audio to the provider. Audio is still captured and kept
+    /// in the local dump when one is enabled.
+    Transcription,
+}
+
+impl MuteMode {
+    /// `(exclude_mic, exclude_system)` for the mixer while muted in this mode.
+    pub fn excluded_sources(self) -> (bool, bool) {
+        match self {
+            Self::Mic => (true, false),
+            Self::System => (false, true),
+            Self::Transcription => (false, false),
+        }
+    }
+
+    pub fn pauses_transcription(self) -> bool {
+        self == Self::Transcription
+    }
+
+    /// Name used in the session timeline.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Mic => "mic",
+            Self::System => "system",
+            Self::Transcription => "transcription",
+        }
+    }
+}
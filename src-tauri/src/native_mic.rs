@@ -0,0 +1,166 @@
+use crate::audio::MixerStats;
+use parking_lot::Mutex;
+use rtrb::Producer;
+use std::sync::Arc;
+
+/// Microphone capture owned by the backend, used when native mic mode is on
+/// or once the webview that normally feeds the mic is hidden.
+pub struct NativeMic {
+    stop_tx: std::sync::mpsc::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl NativeMic {
+    /// Records `device`, by name, or the system default input when `None`.
+    pub fn start(
+        mic_tx: Arc<Mutex<Producer<f32>>>,
+        stats: Arc<MixerStats>,
+        device: Option<String>,
+    ) -> Result<Self, String> {
+        let (stop_tx, stop_rx) = std::sync::mpsc::channel();
+        let (ready_tx, ready_rx) = std::sync::mpsc::sync_channel(1);
+        // The input stream is not `Send`, so it lives and dies on its own thread.
+        let thread = std::thread::Builder::new()
+            .name("native-mic".into())
+            .spawn(
+                move || match platform::open(mic_tx, stats, device.as_deref()) {
+                    // Capture runs for as long as `_stream` is held.
+                    Ok(_stream) => {
+                        let _ = ready_tx.send(Ok(()));
+                        let _ = stop_rx.recv();
+                        tracing::info!("Native microphone capture stopped");
+                    }
+                    Err(err) => {
+                        let _ = ready_tx.send(Err(err));
+                    }
+                },
+            )
+            .map_err(|e| format!("Failed to start microphone thread: {}", e))?;
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self { stop_tx, thread }),
+            Ok(Err(err)) => {
+                let _ = thread.join();
+                Err(err)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err("Microphone thread exited unexpectedly".to_string())
+            }
+        }
+    }
+
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(());
+        let _ = self.thread.join();
+    }
+}
+
+/// Names of the inputs `NativeMic::start` can record.
+pub fn input_devices() -> Result<Vec<String>, String> {
+    platform::input_devices()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::audio::{push_samples, InputConverter, MixerStats};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use parking_lot::Mutex;
+    use rtrb::Producer;
+    use std::sync::Arc;
+
+    pub fn input_devices() -> Result<Vec<String>, String> {
+        let devices = cpal::default_host()
+            .input_devices()
+            .map_err(|e| format!("Failed to list microphones: {}", e))?;
+        Ok(devices.filter_map(|device| device.name().ok()).collect())
+    }
+
+    fn find_device(name: Option<&str>) -> Result<cpal::Device, String> {
+        let host = cpal::default_host();
+        let Some(name) = name else {
+            return host
+                .default_input_device()
+                .ok_or_else(|| "No microphone available".to_string());
+        };
+        host.input_devices()
+            .map_err(|e| format!("Failed to list microphones: {}", e))?
+            .find(|device| device.name().is_ok_and(|found| found == name))
+            .ok_or_else(|| format!("Microphone '{}' not found", name))
+    }
+
+    pub fn open(
+        mic_tx: Arc<Mutex<Producer<f32>>>,
+        stats: Arc<MixerStats>,
+        device: Option<&str>,
+    ) -> Result<cpal::Stream, String> {
+        let device = find_device(device)?;
+        let supported = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to read microphone format: {}", e))?;
+        let sample_format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+        let mut converter = InputConverter::new(config.channels as usize, config.sample_rate.0);
+        let mut deliver = move |samples: &[f32]| {
+            let mut producer = mic_tx.lock();
+            let dropped = push_samples(&mut producer, samples);
+            if dropped > 0 {
+                stats.record_dropped_input(dropped);
+            }
+        };
+        let on_error = |err: cpal::StreamError| tracing::warn!("Microphone stream error: {}", err);
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => device.build_input_stream(
+                &config,
+                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                    deliver(converter.convert(data.iter().copied()));
+                },
+                on_error,
+                None,
+            ),
+            cpal::SampleFormat::I16 => device.build_input_stream(
+                &config,
+                move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                    let samples = data.iter().map(|s| *s as f32 / i16::MAX as f32);
+                    deliver(converter.convert(samples));
+                },
+                on_error,
+                None,
+            ),
+            other => return Err(format!("Unsupported microphone sample format {:?}", other)),
+        }
+        .map_err(|e| format!("Failed to open microphone: {}", e))?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start microphone: {}", e))?;
+        tracing::info!(
+            device = %device.name().unwrap_or_default(),
+            rate = config.sample_rate.0,
+            channels = config.channels,
+            "Native microphone capture started"
+        );
+        Ok(stream)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use crate::audio::MixerStats;
+    use parking_lot::Mutex;
+    use rtrb::Producer;
+    use std::sync::Arc;
+
+    pub struct Stream;
+
+    pub fn input_devices() -> Result<Vec<String>, String> {
+        Err("Native microphone capture is only available on macOS".to_string())
+    }
+
+    pub fn open(
+        _mic_tx: Arc<Mutex<Producer<f32>>>,
+        _stats: Arc<MixerStats>,
+        _device: Option<&str>,
+    ) -> Result<Stream, String> {
+        Err("Native microphone capture is only available on macOS".to_string())
+    }
+}
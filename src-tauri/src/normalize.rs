@@ -0,0 +1,337 @@
+use crate::locale::{self, Locale};
+use jilu_core::transcript::TranscriptTurnPayload;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// `3/5/2024` or `3/5/24`, which the recognizer writes month first, and ISO `2024-03-05`.
+static NUMERIC_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(?:(\d{1,2})/(\d{1,2})/(\d{4}|\d{2})|(\d{4})-(\d{2})-(\d{2}))\b").unwrap()
+});
+/// `March 5th, 2024`, with or without the year.
+static SPELLED_DATE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\b({})\s+(\d{{1,2}})(?:st|nd|rd|th)?\b(?:,?\s+(\d{{4}})\b)?",
+        MONTHS.join("|")
+    ))
+    .unwrap()
+});
+/// `3 p.m.`, `3:30pm` or `11:05 AM`.
+static TWELVE_HOUR_TIME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(\d{1,2})(?::(\d{2}))?\s?([ap])\.?m\b\.?").unwrap());
+/// `$1,234.50`, `€12` or `£3.5 million`.
+static CURRENCY_AMOUNT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"([$€£¥])\s?(\d{1,3}(?:,\d{3})+|\d+)(?:\.(\d+))?\b",
+        r"(\s+(?:thousand|million|billion|trillion)\b)?"
+    ))
+    .unwrap()
+});
+
+/// Rewrites dates, times and currency amounts in exported transcripts the
+/// way the reader's locale writes them. The recognizer writes them the
+/// American way whatever the meeting, which reads oddly in minutes for
+/// someone in Berlin or Paris.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportNormalizationSettings {
+    pub enabled: bool,
+    /// Tag like `de-CH` or `en-GB`; `None` follows the UI language. The
+    /// region matters for English, where only the US writes the month first.
+    pub locale: Option<String>,
+    pub dates: bool,
+    /// Switches 12-hour times to the 24-hour clock outside English.
+    pub times: bool,
+    /// Decimal and thousands separators, and where the symbol goes. The
+    /// currency itself is never converted.
+    pub currencies: bool,
+}
+
+impl Default for ExportNormalizationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            locale: None,
+            dates: true,
+            times: true,
+            currencies: true,
+        }
+    }
+}
+
+impl ExportNormalizationSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.locale {
+            Some(tag) => locale::validate_locale(tag),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateOrder {
+    DayMonthYear,
+    MonthDayYear,
+    YearMonthDay,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SymbolPlacement {
+    /// `$1,234.50`
+    Before,
+    /// `€ 1.234,50`
+    BeforeSpaced,
+    /// `1.234,50 €`
+    After,
+}
+
+/// How one locale writes what the recognizer wrote the American way.
+#[derive(Debug, Clone)]
+pub struct Normalizer {
+    order: DateOrder,
+    date_separator: char,
+    english: bool,
+    twenty_four_hour: bool,
+    decimal: char,
+    thousands: char,
+    symbol: SymbolPlacement,
+    dates: bool,
+    times: bool,
+    currencies: bool,
+}
+
+impl Normalizer {
+    /// `None` when normalization is off. `ui_locale` stands in for an unset
+    /// locale of its own.
+    pub fn new(settings: &ExportNormalizationSettings, ui_locale: &str) -> Option<Self> {
+        if !settings.enabled {
+            return None;
+        }
+        let tag = settings.locale.as_deref().unwrap_or(ui_locale);
+        let region = tag
+            .split(['-', '_'])
+            .nth(1)
+            .unwrap_or_default()
+            .to_ascii_uppercase();
+        let mut normalizer = Self {
+            order: DateOrder::DayMonthYear,
+            date_separator: '/',
+            english: false,
+            twenty_four_hour: true,
+            decimal: ',',
+            thousands: '.',
+            symbol: SymbolPlacement::After,
+            dates: settings.dates,
+            times: settings.times,
+            currencies: settings.currencies,
+        };
+        match Locale::from_tag(tag) {
+            Locale::English => {
+                if matches!(region.as_str(), "" | "US" | "CA" | "PH") {
+                    normalizer.order = DateOrder::MonthDayYear;
+                }
+                normalizer.english = true;
+                normalizer.twenty_four_hour = false;
+                normalizer.decimal = '.';
+                normalizer.thousands = ',';
+                normalizer.symbol = SymbolPlacement::Before;
+            }
+            Locale::German => normalizer.date_separator = '.',
+            Locale::French => normalizer.thousands = '\u{202f}',
+            Locale::Dutch => {
+                normalizer.date_separator = '-';
+                normalizer.symbol = SymbolPlacement::BeforeSpaced;
+            }
+            Locale::Spanish | Locale::Italian | Locale::Portuguese => {}
+            Locale::Chinese | Locale::Japanese | Locale::Korean => {
+                normalizer.order = DateOrder::YearMonthDay;
+                normalizer.date_separator = '-';
+                normalizer.decimal = '.';
+                normalizer.thousands = ',';
+                normalizer.symbol = SymbolPlacement::Before;
+            }
+        }
+        Some(normalizer)
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if self.dates {
+            text = NUMERIC_DATE
+                .replace_all(&text, |caps: &Captures| self.numeric_date(caps))
+                .into_owned();
+            text = SPELLED_DATE
+                .replace_all(&text, |caps: &Captures| self.spelled_date(caps))
+                .into_owned();
+        }
+        if self.times && self.twenty_four_hour {
+            text =
+                replace_with_context(&TWELVE_HOUR_TIME, &text, |caps, rest| self.time(caps, rest));
+        }
+        if self.currencies && !self.english {
+            text = CURRENCY_AMOUNT
+                .replace_all(&text, |caps: &Captures| self.currency(caps))
+                .into_owned();
+        }
+        text
+    }
+
+    pub fn apply_to_turns(&self, turns: &mut [TranscriptTurnPayload]) {
+        for turn in turns {
+            turn.text = self.apply(&turn.text);
+        }
+    }
+
+    fn format_date(&self, year: &str, month: u32, day: u32) -> String {
+        let sep = self.date_separator;
+        match self.order {
+            DateOrder::MonthDayYear => format!("{}{}{}{}{}", month, sep, day, sep, year),
+            DateOrder::DayMonthYear => format!("{:02}{}{:02}{}{}", day, sep, month, sep, year),
+            DateOrder::YearMonthDay => format!("{}{}{:02}{}{:02}", year, sep, month, sep, day),
+        }
+    }
+
+    fn numeric_date(&self, caps: &Captures) -> String {
+        let original = caps[0].to_string();
+        let number = |index: usize| caps.get(index).and_then(|m| m.as_str().parse::<u32>().ok());
+        let (year, mut month, mut day) = match (number(1), number(2), caps.get(3)) {
+            (Some(month), Some(day), Some(year)) => (year.as_str(), month, day),
+            _ => match (caps.get(4), number(5), number(6)) {
+                (Some(year), Some(month), Some(day)) => (year.as_str(), month, day),
+                _ => return original,
+            },
+        };
+        // Already day first, as in 25/12/2024.
+        if month > 12 && caps.get(1).is_some() {
+            std::mem::swap(&mut month, &mut day);
+        }
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return original;
+        }
+        // "24-03-05" would read as anything but a date.
+        if self.order == DateOrder::YearMonthDay && year.len() == 2 {
+            return original;
+        }
+        self.format_date(year, month, day)
+    }
+
+    fn spelled_date(&self, caps: &Captures) -> String {
+        let original = caps[0].to_string();
+        let Ok(day) = caps[2].parse::<u32>() else {
+            return original;
+        };
+        if !(1..=31).contains(&day) || self.order == DateOrder::MonthDayYear {
+            return original;
+        }
+        let month_name = &caps[1];
+        let year = caps.get(3).map(|m| m.as_str());
+        if self.english {
+            // The text stays English, so only the order changes.
+            return match year {
+                Some(year) => format!("{} {} {}", day, month_name, year),
+                None => format!("{} {}", day, month_name),
+            };
+        }
+        // Other locales get numbers, but a bare "March 5" has no year to
+        // write and stays as it is.
+        let month = MONTHS.iter().position(|name| *name == month_name);
+        match (month, year) {
+            (Some(month), Some(year)) => self.format_date(year, month as u32 + 1, day),
+            _ => original,
+        }
+    }
+
+    fn time(&self, caps: &Captures, rest: &str) -> String {
+        let original = caps[0].to_string();
+        let hour = caps[1].parse::<u32>().unwrap_or_default();
+        let minute = caps
+            .get(2)
+            .map_or(Some(0), |m| m.as_str().parse::<u32>().ok());
+        let Some(minute) = minute.filter(|minute| *minute < 60) else {
+            return original;
+        };
+        if !(1..=12).contains(&hour) {
+            return original;
+        }
+        let afternoon = caps[3].eq_ignore_ascii_case("p");
+        let hour = match (hour, afternoon) {
+            (12, false) => 0,
+            (12, true) => 12,
+            (hour, true) => hour + 12,
+            (hour, false) => hour,
+        };
+        // "at 3 p.m. Then" used the last dot to end the sentence too.
+        let ends_sentence = original.ends_with('.')
+            && (rest.is_empty()
+                || rest.starts_with(char::is_whitespace)
+                    && rest.trim_start().starts_with(char::is_uppercase));
+        let stop = if ends_sentence { "." } else { "" };
+        format!("{:02}:{:02}{}", hour, minute, stop)
+    }
+
+    fn currency(&self, caps: &Captures) -> String {
+        let symbol = &caps[1];
+        let integer = caps[2].replace(',', "");
+        let mut amount = if caps[2].contains(',') || integer.len() > 4 {
+            group_thousands(&integer, self.thousands)
+        } else {
+            integer
+        };
+        if let Some(fraction) = caps.get(3) {
+            amount.push(self.decimal);
+            amount.push_str(fraction.as_str());
+        }
+        if let Some(scale) = caps.get(4) {
+            amount.push_str(scale.as_str());
+        }
+        match self.symbol {
+            SymbolPlacement::Before => format!("{}{}", symbol, amount),
+            SymbolPlacement::BeforeSpaced => format!("{}\u{a0}{}", symbol, amount),
+            SymbolPlacement::After => format!("{}\u{a0}{}", amount, symbol),
+        }
+    }
+}
+
+fn group_thousands(digits: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Like [`Regex::replace_all`], but also hands `replace` the text after the match.
+fn replace_with_context(
+    regex: &Regex,
+    text: &str,
+    mut replace: impl FnMut(&Captures, &str) -> String,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for caps in regex.captures_iter(text) {
+        let whole = caps.get(0).expect("group 0 always matches");
+        out.push_str(&text[last..whole.start()]);
+        out.push_str(&replace(&caps, &text[whole.end()..]));
+        last = whole.end();
+    }
+    out.push_str(&text[last..]);
+    out
+}
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+pub const ONBOARDING_FILE: &str = "onboarding.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum OnboardingStep {
+    Permissions,
+    ApiKey,
+    Calendar,
+    TestRecording,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 4] = [
+        OnboardingStep::Permissions,
+        OnboardingStep::ApiKey,
+        OnboardingStep::Calendar,
+        OnboardingStep::TestRecording,
+    ];
+
+    /// Steps that must be done before `start_recording` is allowed.
+    pub const REQUIRED_TO_RECORD: [OnboardingStep; 1] = [OnboardingStep::ApiKey];
+
+    fn label(self) -> &'static str {
+        match self {
+            OnboardingStep::Permissions => "grant capture permissions",
+            OnboardingStep::ApiKey => "add a Speechmatics API key",
+            OnboardingStep::Calendar => "choose calendars",
+            OnboardingStep::TestRecording => "make a test recording",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct StoredOnboarding {
+    completed: BTreeSet<OnboardingStep>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    pub completed: Vec<OnboardingStep>,
+    /// First step still to do, in setup order.
+    pub next_step: Option<OnboardingStep>,
+    pub ready_to_record: bool,
+    pub is_complete: bool,
+}
+
+/// First-run progress for the active profile, persisted so setup resumes after a restart.
+#[derive(Debug, Default)]
+pub struct Onboarding {
+    path: Option<PathBuf>,
+    stored: StoredOnboarding,
+}
+
+impl Onboarding {
+    pub fn load(path: PathBuf) -> Self {
+        let stored = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            stored,
+        }
+    }
+
+    pub fn state(&self) -> OnboardingState {
+        let completed = &self.stored.completed;
+        OnboardingState {
+            completed: completed.iter().copied().collect(),
+            next_step: OnboardingStep::ALL
+                .into_iter()
+                .find(|step| !completed.contains(step)),
+            ready_to_record: self.missing_to_record().is_empty(),
+            is_complete: OnboardingStep::ALL
+                .iter()
+                .all(|step| completed.contains(step)),
+        }
+    }
+
+    pub fn is_done(&self, step: OnboardingStep) -> bool {
+        self.stored.completed.contains(&step)
+    }
+
+    /// Marks a step done. Returns whether anything changed.
+    pub fn complete(&mut self, step: OnboardingStep) -> Result<bool, String> {
+        if self.is_done(step) {
+            return Ok(false);
+        }
+        let mut stored = self.stored.clone();
+        stored.completed.insert(step);
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            }
+            let contents = serde_json::to_string_pretty(&stored).map_err(|e| e.to_string())?;
+            std::fs::write(path, contents)
+                .map_err(|e| format!("Failed to save onboarding progress: {}", e))?;
+        }
+        self.stored = stored;
+        Ok(true)
+    }
+
+    fn missing_to_record(&self) -> Vec<OnboardingStep> {
+        OnboardingStep::REQUIRED_TO_RECORD
+            .into_iter()
+            .filter(|step| !self.is_done(*step))
+            .collect()
+    }
+
+    pub fn require_ready_to_record(&self) -> Result<(), String> {
+        let missing = self.missing_to_record();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let steps: Vec<&str> = missing.iter().map(|step| step.label()).collect();
+        Err(format!(
+            "Finish setup before recording: {}",
+            steps.join(", ")
+        ))
+    }
+}
@@ -0,0 +1,190 @@
+use serde::Serialize;
+
+/// How the default output device is attached.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum OutputTransport {
+    BuiltIn,
+    Usb,
+    Hdmi,
+    DisplayPort,
+    Thunderbolt,
+    Bluetooth,
+    AirPlay,
+    /// Several devices combined, e.g. a multi-output device.
+    Aggregate,
+    /// A software device such as a loopback driver.
+    Virtual,
+    Other,
+}
+
+/// The output device system audio is playing to. ScreenCaptureKit takes
+/// audio from apps before it reaches the device, so this is what the
+/// recording hears as long as `capturable` holds.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputRoute {
+    pub device: String,
+    pub transport: OutputTransport,
+    pub capturable: bool,
+    /// Why the route may be missing from, or sound different in, the recording.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl OutputRoute {
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn new(device: String, transport: OutputTransport) -> Self {
+        let (capturable, warning) = match transport {
+            OutputTransport::AirPlay => (
+                false,
+                Some(
+                    "AirPlay streams audio from the sending app, so screen capture may not hear it; \
+                     play the meeting through this Mac instead"
+                        .to_string(),
+                ),
+            ),
+            OutputTransport::Aggregate | OutputTransport::Virtual => (
+                true,
+                Some(
+                    "Audio is captured before it reaches this device, so routing or effects it adds \
+                     are not in the recording"
+                        .to_string(),
+                ),
+            ),
+            OutputTransport::Usb
+            | OutputTransport::Hdmi
+            | OutputTransport::DisplayPort
+            | OutputTransport::Thunderbolt => (
+                true,
+                Some(
+                    "Levels are measured before the device's own volume, so they will not follow \
+                     its volume control"
+                        .to_string(),
+                ),
+            ),
+            _ => (true, None),
+        };
+        Self {
+            device,
+            transport,
+            capturable,
+            warning,
+        }
+    }
+}
+
+/// The system's default output device right now.
+pub fn current() -> Result<OutputRoute, String> {
+    platform::current()
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{OutputRoute, OutputTransport};
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+    use std::ffi::c_void;
+
+    type AudioObjectID = u32;
+    type OSStatus = i32;
+
+    #[repr(C)]
+    struct AudioObjectPropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    const fn four_cc(code: &[u8; 4]) -> u32 {
+        u32::from_be_bytes(*code)
+    }
+
+    const SYSTEM_OBJECT: AudioObjectID = 1;
+    const SCOPE_GLOBAL: u32 = four_cc(b"glob");
+    const ELEMENT_MAIN: u32 = 0;
+    const DEFAULT_OUTPUT_DEVICE: u32 = four_cc(b"dOut");
+    const DEVICE_NAME: u32 = four_cc(b"lnam");
+    const TRANSPORT_TYPE: u32 = four_cc(b"tran");
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectGetPropertyData(
+            object: AudioObjectID,
+            address: *const AudioObjectPropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> OSStatus;
+    }
+
+    /// Reads a fixed-size property of `object` into a `T`.
+    fn property<T: Default>(object: AudioObjectID, selector: u32) -> Result<T, String> {
+        let address = AudioObjectPropertyAddress {
+            selector,
+            scope: SCOPE_GLOBAL,
+            element: ELEMENT_MAIN,
+        };
+        let mut value = T::default();
+        let mut size = std::mem::size_of::<T>() as u32;
+        // SAFETY: `value` is a valid `T` and `size` says how much room it has.
+        let status = unsafe {
+            AudioObjectGetPropertyData(
+                object,
+                &address,
+                0,
+                std::ptr::null(),
+                &mut size,
+                &mut value as *mut T as *mut c_void,
+            )
+        };
+        if status != 0 {
+            return Err(format!("CoreAudio error {}", status));
+        }
+        Ok(value)
+    }
+
+    fn transport(code: u32) -> OutputTransport {
+        match &code.to_be_bytes() {
+            b"bltn" => OutputTransport::BuiltIn,
+            b"usb " => OutputTransport::Usb,
+            b"hdmi" => OutputTransport::Hdmi,
+            b"dprt" => OutputTransport::DisplayPort,
+            b"thun" => OutputTransport::Thunderbolt,
+            b"blue" | b"blea" => OutputTransport::Bluetooth,
+            b"airp" => OutputTransport::AirPlay,
+            b"grup" => OutputTransport::Aggregate,
+            b"virt" => OutputTransport::Virtual,
+            _ => OutputTransport::Other,
+        }
+    }
+
+    pub fn current() -> Result<OutputRoute, String> {
+        let device: AudioObjectID = property(SYSTEM_OBJECT, DEFAULT_OUTPUT_DEVICE)
+            .map_err(|e| format!("Failed to read the output device: {}", e))?;
+        if device == 0 {
+            return Err("No output device".to_string());
+        }
+        let name_ref: usize = property(device, DEVICE_NAME)
+            .map_err(|e| format!("Failed to read the output device name: {}", e))?;
+        let name = if name_ref == 0 {
+            "Unknown device".to_string()
+        } else {
+            // SAFETY: CoreAudio hands over a retained CFString we now own.
+            unsafe { CFString::wrap_under_create_rule(name_ref as CFStringRef) }.to_string()
+        };
+        let code: u32 = property(device, TRANSPORT_TYPE).unwrap_or(0);
+        Ok(OutputRoute::new(name, transport(code)))
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::OutputRoute;
+
+    pub fn current() -> Result<OutputRoute, String> {
+        Err("Output routes are only available on macOS".to_string())
+    }
+}
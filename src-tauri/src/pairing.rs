@@ -0,0 +1,367 @@
+use crate::share::{
+    self, cookie, header, read_request, respond, strip_token, tls_acceptor, TranscriptFeed,
+    SECURITY_HEADERS,
+};
+use crate::transcript::Transcript;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+const COOKIE: &str = "jilu_remote";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+const QR_SIZE: u32 = 240;
+const PAGE: &str = include_str!("pairing_page.html");
+
+/// What the frontend shows while a phone can pair or is paired.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingStatus {
+    pub url: String,
+    /// SHA-256 of the server certificate, as browsers show it.
+    pub fingerprint: String,
+    /// QR code of `url` with the fingerprint attached, for the phone to scan.
+    pub qr_svg: String,
+    pub expires_at: String,
+    pub device: Option<PairedDevice>,
+}
+
+/// Payload of `remote-paired`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PairedDevice {
+    pub paired_at: String,
+    pub user_agent: Option<String>,
+}
+
+struct Device {
+    /// Kept in the phone's cookie; nothing else is let in once it is set.
+    secret: String,
+    info: PairedDevice,
+}
+
+/// A remote control for one phone on the local network: the live transcript
+/// and mute buttons, served over TLS with a certificate made for the
+/// session. The first device to open the link is paired and the link stops
+/// working for anyone else.
+pub struct PairingSession {
+    url: String,
+    fingerprint: String,
+    qr_svg: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    device: Arc<Mutex<Option<Device>>>,
+    stop_tx: watch::Sender<bool>,
+}
+
+/// What the remote acts on, and who to tell once a phone is paired.
+pub struct RemoteHooks {
+    pub set_muted: Box<dyn Fn(bool) + Send + Sync>,
+    pub on_paired: Box<dyn Fn(PairedDevice) + Send + Sync>,
+}
+
+#[derive(Clone)]
+struct Server {
+    token: Arc<str>,
+    ttl: Duration,
+    transcript: Arc<Mutex<Transcript>>,
+    consent: Option<PathBuf>,
+    is_muted: Arc<Mutex<bool>>,
+    hooks: Arc<RemoteHooks>,
+    device: Arc<Mutex<Option<Device>>>,
+    stop_rx: watch::Receiver<bool>,
+    deadline: Instant,
+}
+
+/// Whether a request comes from the paired phone.
+enum Access {
+    Paired,
+    /// Pairs the first device to open the page, with this secret.
+    Pairing(String),
+    Refused,
+}
+
+impl PairingSession {
+    /// Opens the listener and returns the session along with the server
+    /// future, which finishes once pairing has expired or been stopped.
+    pub async fn start(
+        transcript: Arc<Mutex<Transcript>>,
+        consent: Option<PathBuf>,
+        is_muted: Arc<Mutex<bool>>,
+        ttl: Duration,
+        hooks: RemoteHooks,
+    ) -> Result<(Self, impl Future<Output = ()>), String> {
+        let token = share::new_token()?;
+        // Listens on the interface the pairing URL names, like the guest link.
+        let host = share::lan_address().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let listener = TcpListener::bind((host, 0))
+            .await
+            .map_err(|e| format!("Failed to open pairing server: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to open pairing server: {}", e))?
+            .port();
+        let (acceptor, fingerprint) = tls_acceptor(host)?;
+        let url = format!("https://{}/remote/{}", SocketAddr::new(host, port), token);
+        // Apps can pin the certificate from the fragment, which browsers never send.
+        let pin = fingerprint.replace(':', "").to_lowercase();
+        let qr_svg = qrcode::QrCode::new(format!("{}#sha256={}", url, pin))
+            .map_err(|e| format!("Failed to make the pairing QR code: {}", e))?
+            .render::<qrcode::render::svg::Color>()
+            .min_dimensions(QR_SIZE, QR_SIZE)
+            .build();
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(ttl)
+                .map_err(|e| format!("Invalid pairing lifetime: {}", e))?;
+
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let device = Arc::new(Mutex::new(None));
+        let server = Server {
+            token: token.into(),
+            ttl,
+            transcript,
+            consent,
+            is_muted,
+            hooks: Arc::new(hooks),
+            device: device.clone(),
+            stop_rx,
+            deadline: Instant::now() + ttl,
+        };
+        let session = Self {
+            url,
+            fingerprint,
+            qr_svg,
+            expires_at,
+            device,
+            stop_tx,
+        };
+        Ok((session, server.run(listener, acceptor)))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn status(&self) -> PairingStatus {
+        PairingStatus {
+            url: self.url.clone(),
+            fingerprint: self.fingerprint.clone(),
+            qr_svg: self.qr_svg.clone(),
+            expires_at: self.expires_at.to_rfc3339(),
+            device: self
+                .device
+                .lock()
+                .as_ref()
+                .map(|device| device.info.clone()),
+        }
+    }
+
+    /// Closes the listener and the phone's connection.
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(true);
+    }
+}
+
+/// Method and path of a request, without the query string.
+fn request_target(request: &str) -> Option<(&str, &str)> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    Some((method, target.split(['?', '#']).next().unwrap_or(target)))
+}
+
+impl Server {
+    async fn run(self, listener: TcpListener, acceptor: tokio_native_tls::TlsAcceptor) {
+        let acceptor = Arc::new(acceptor);
+        let mut stop_rx = self.stop_rx.clone();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let server = self.clone();
+                        let acceptor = acceptor.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = server.accept(stream, &acceptor).await {
+                                tracing::debug!("Remote connection ended: {}", err);
+                            }
+                        });
+                    }
+                    Err(err) => tracing::warn!("Pairing server failed to accept: {}", err),
+                },
+                _ = tokio::time::sleep_until(self.deadline) => break,
+                _ = stop_rx.changed() => break,
+            }
+        }
+    }
+
+    async fn accept(
+        self,
+        stream: TcpStream,
+        acceptor: &tokio_native_tls::TlsAcceptor,
+    ) -> std::io::Result<()> {
+        let stream = match tokio::time::timeout(REQUEST_TIMEOUT, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => stream,
+            // Phones that have not trusted the certificate yet hang up here.
+            Ok(Err(err)) => return Err(std::io::Error::other(err)),
+            Err(_) => return Ok(()),
+        };
+        self.handle(stream).await
+    }
+
+    fn access(&self, request: &str, may_pair: bool) -> Access {
+        let mut device = self.device.lock();
+        match device.as_ref() {
+            Some(paired) => match cookie(request, COOKIE) {
+                Some(value) if strip_token(value, &paired.secret) == Some("") => Access::Paired,
+                _ => Access::Refused,
+            },
+            None if may_pair => {
+                let Ok(secret) = share::new_token() else {
+                    return Access::Refused;
+                };
+                let info = PairedDevice {
+                    paired_at: chrono::Utc::now().to_rfc3339(),
+                    user_agent: header(request, "user-agent").map(str::to_string),
+                };
+                *device = Some(Device {
+                    secret: secret.clone(),
+                    info: info.clone(),
+                });
+                drop(device);
+                (self.hooks.on_paired)(info);
+                Access::Pairing(secret)
+            }
+            None => Access::Refused,
+        }
+    }
+
+    async fn handle<S: AsyncRead + AsyncWrite + Unpin>(self, mut stream: S) -> std::io::Result<()> {
+        let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(request) => request?,
+            Err(_) => return Ok(()),
+        };
+        let Some((method, path)) = request_target(&request) else {
+            return respond(&mut stream, "400 Bad Request", "text/plain", "").await;
+        };
+        let Some(rest) = path
+            .strip_prefix("/remote/")
+            .and_then(|rest| strip_token(rest, &self.token))
+        else {
+            // Wrong tokens look exactly like unknown paths.
+            return respond(&mut stream, "404 Not Found", "text/plain", "Not found").await;
+        };
+        let route = (method, rest);
+        if !matches!(
+            route,
+            ("GET", "" | "/events") | ("POST", "/mute" | "/unmute")
+        ) {
+            return respond(&mut stream, "404 Not Found", "text/plain", "Not found").await;
+        }
+        let secret = match self.access(&request, route == ("GET", "")) {
+            Access::Paired => None,
+            Access::Pairing(secret) => Some(secret),
+            Access::Refused => {
+                return respond(
+                    &mut stream,
+                    "403 Forbidden",
+                    "text/plain",
+                    "This link is paired with another device",
+                )
+                .await;
+            }
+        };
+        match route {
+            ("GET", "") => self.page(stream, secret).await,
+            ("GET", "/events") => self.write_events(stream).await,
+            ("POST", muted) => {
+                (self.hooks.set_muted)(muted == "/mute");
+                respond(&mut stream, "204 No Content", "text/plain", "").await
+            }
+            _ => unreachable!("routes are matched above"),
+        }
+    }
+
+    /// The remote page, pairing the phone with a cookie on its first visit.
+    async fn page<S: AsyncWrite + Unpin>(
+        &self,
+        mut stream: S,
+        secret: Option<String>,
+    ) -> std::io::Result<()> {
+        let cookie = secret
+            .map(|secret| {
+                format!(
+                    "Set-Cookie: {}={}; Path=/remote/{}; Max-Age={}; Secure; HttpOnly; SameSite=Strict\r\n",
+                    COOKIE,
+                    secret,
+                    self.token,
+                    self.ttl.as_secs()
+                )
+            })
+            .unwrap_or_default();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n{}{}\r\n{}",
+            PAGE.len(),
+            cookie,
+            SECURITY_HEADERS,
+            PAGE
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    async fn write_events<S: AsyncWrite + Unpin>(&self, mut stream: S) -> std::io::Result<()> {
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n{}\r\n",
+            SECURITY_HEADERS
+        );
+        stream.write_all(head.as_bytes()).await?;
+
+        let mut stop_rx = self.stop_rx.clone();
+        let mut feed = TranscriptFeed::new(self.consent.clone());
+        let mut sent_muted = None;
+        let mut last_write = Instant::now();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = poll.tick() => {}
+                _ = tokio::time::sleep_until(self.deadline) => break,
+                _ = stop_rx.changed() => break,
+            }
+            let muted = *self.is_muted.lock();
+            if sent_muted != Some(muted) {
+                sent_muted = Some(muted);
+                let event = format!("event: mute\ndata: {{\"muted\":{}}}\n\n", muted);
+                stream.write_all(event.as_bytes()).await?;
+                last_write = Instant::now();
+            }
+            let update = feed.update(&self.transcript);
+            let update = match update {
+                Ok(update) => update,
+                Err(err) => {
+                    tracing::warn!("Ending the remote's transcript: {}", err);
+                    break;
+                }
+            };
+            if let Some(update) = update {
+                let data = serde_json::to_string(&update).unwrap_or_default();
+                stream
+                    .write_all(format!("event: transcript\ndata: {}\n\n", data).as_bytes())
+                    .await?;
+                last_write = Instant::now();
+            } else if last_write.elapsed() >= KEEPALIVE_INTERVAL {
+                stream.write_all(b": keepalive\n\n").await?;
+                last_write = Instant::now();
+            }
+        }
+        stream.write_all(b"event: ended\ndata: {}\n\n").await?;
+        stream.shutdown().await
+    }
+}
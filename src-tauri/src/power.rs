@@ -1,7 +1,98 @@
+use serde::Serialize;
+
+/// Batch lengths used while saving power: on battery or in Low Power Mode,
+/// and while the Mac is running hot.
+const SAVING_BATCH_MS: u64 = 250;
+const HOT_BATCH_MS: u64 = 500;
+
+/// How hard the system says it is working to stay cool, as macOS reports it.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum Thermal {
+    #[default]
+    Nominal,
+    Fair,
+    Serious,
+    Critical,
+}
+
+/// What `power_state` found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PowerState {
+    pub on_battery: bool,
+    pub low_power: bool,
+    pub thermal: Thermal,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PowerMode {
+    Normal,
+    /// On battery or in Low Power Mode.
+    Saving,
+    /// Thermal pressure is serious or critical.
+    Hot,
+}
+
+/// How a recording sends audio given the power state; payload of
+/// `power-policy-changed`.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerPolicy {
+    pub mode: PowerMode,
+    /// Audio sent per message. Larger batches wake the network and the
+    /// provider less often, at the cost of captions arriving a little later.
+    pub batch_ms: u64,
+    pub on_battery: bool,
+    pub low_power: bool,
+    pub thermal: Thermal,
+}
+
+impl PowerPolicy {
+    /// The policy for `state`, never sending more often than every `batch_ms`.
+    pub fn new(state: PowerState, batch_ms: u64) -> Self {
+        let mode = if matches!(state.thermal, Thermal::Serious | Thermal::Critical) {
+            PowerMode::Hot
+        } else if state.on_battery || state.low_power {
+            PowerMode::Saving
+        } else {
+            PowerMode::Normal
+        };
+        Self {
+            mode,
+            batch_ms: match mode {
+                PowerMode::Normal => batch_ms,
+                PowerMode::Saving => batch_ms.max(SAVING_BATCH_MS),
+                PowerMode::Hot => batch_ms.max(HOT_BATCH_MS),
+            },
+            on_battery: state.on_battery,
+            low_power: state.low_power,
+            thermal: state.thermal,
+        }
+    }
+}
+
+/// System sleep transitions, as reported by `observe_sleep`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub enum PowerEvent {
+    WillSleep,
+    DidWake,
+}
+
 #[cfg(target_os = "macos")]
 mod platform {
-    use core_foundation::base::TCFType;
+    #![allow(unexpected_cfgs)] // objc macros probe cfg(feature = "cargo-clippy"), which triggers this lint
+
+    use super::{PowerEvent, PowerState, Thermal};
+    use block::ConcreteBlock;
+    use cocoa::base::{id, nil, BOOL, NO};
+    use cocoa::foundation::NSString;
+    use core_foundation::base::{CFRelease, CFTypeRef, TCFType};
     use core_foundation::string::{CFString, CFStringRef};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::Arc;
 
     type IOPMAssertionID = u32;
     type IOReturn = i32;
@@ -19,6 +110,10 @@ mod platform {
         ) -> IOReturn;
 
         fn IOPMAssertionRelease(assertion_id: IOPMAssertionID) -> IOReturn;
+
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+
+        fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFStringRef;
     }
 
     pub struct WakeLock {
@@ -63,10 +158,70 @@ mod platform {
             self.release();
         }
     }
+
+    /// Whether the Mac runs on battery, is in Low Power Mode and how hot it is.
+    pub fn power_state() -> PowerState {
+        let on_battery = unsafe {
+            let snapshot = IOPSCopyPowerSourcesInfo();
+            if snapshot.is_null() {
+                false
+            } else {
+                let source = IOPSGetProvidingPowerSourceType(snapshot);
+                let on_battery = !source.is_null()
+                    && CFString::wrap_under_get_rule(source).to_string() == "Battery Power";
+                CFRelease(snapshot);
+                on_battery
+            }
+        };
+        unsafe {
+            let info: id = msg_send![class!(NSProcessInfo), processInfo];
+            let thermal: isize = msg_send![info, thermalState];
+            // Low Power Mode came to the Mac with macOS 12.
+            let knows_low_power: BOOL =
+                msg_send![info, respondsToSelector: sel!(isLowPowerModeEnabled)];
+            let low_power: BOOL = if knows_low_power == NO {
+                NO
+            } else {
+                msg_send![info, isLowPowerModeEnabled]
+            };
+            PowerState {
+                on_battery,
+                low_power: low_power != NO,
+                thermal: match thermal {
+                    1 => Thermal::Fair,
+                    2 => Thermal::Serious,
+                    3 => Thermal::Critical,
+                    _ => Thermal::Nominal,
+                },
+            }
+        }
+    }
+
+    /// Calls `handler` before every system sleep and after every wake, for the
+    /// rest of the process's life. It runs on the main thread, so keep it short.
+    pub fn observe_sleep(handler: impl Fn(PowerEvent) + Send + Sync + 'static) {
+        let handler = Arc::new(handler);
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let center: id = msg_send![workspace, notificationCenter];
+            for (name, event) in [
+                ("NSWorkspaceWillSleepNotification", PowerEvent::WillSleep),
+                ("NSWorkspaceDidWakeNotification", PowerEvent::DidWake),
+            ] {
+                let handler = handler.clone();
+                let block = ConcreteBlock::new(move |_notification: id| handler(event)).copy();
+                let name = NSString::alloc(nil).init_str(name);
+                // The notification center keeps the observer, and with it the block.
+                let _: id = msg_send![center, addObserverForName:name object:nil queue:nil usingBlock:&*block];
+            }
+        }
+    }
 }
 
 #[cfg(not(target_os = "macos"))]
 mod platform {
+    use super::{PowerEvent, PowerState};
+
     #[derive(Default)]
     pub struct WakeLock;
 
@@ -77,6 +232,33 @@ mod platform {
 
         pub fn release(&mut self) {}
     }
+
+    pub fn observe_sleep(_handler: impl Fn(PowerEvent) + Send + Sync + 'static) {}
+
+    /// Only battery power is known here, and only on Linux.
+    pub fn power_state() -> PowerState {
+        PowerState {
+            on_battery: on_battery(),
+            ..PowerState::default()
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn on_battery() -> bool {
+        let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+        supplies.flatten().any(|supply| {
+            let read =
+                |name: &str| std::fs::read_to_string(supply.path().join(name)).unwrap_or_default();
+            read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+        })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn on_battery() -> bool {
+        false
+    }
 }
 
-pub use platform::WakeLock;
+pub use platform::{observe_sleep, power_state, WakeLock};
@@ -0,0 +1,178 @@
+use crate::slug;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+pub const DEFAULT_PROFILE_ID: &str = "default";
+const DEFAULT_PROFILE_NAME: &str = "Personal";
+const MAX_NAME_LEN: usize = 64;
+
+/// A workspace with its own settings, secrets and meeting library.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    /// Where this profile keeps meeting data; `None` uses the app data directory.
+    #[serde(default)]
+    pub storage_dir: Option<String>,
+}
+
+impl Profile {
+    fn default_profile() -> Self {
+        Self {
+            id: DEFAULT_PROFILE_ID.to_string(),
+            name: DEFAULT_PROFILE_NAME.to_string(),
+            storage_dir: None,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_PROFILE_ID
+    }
+
+    /// Settings file for this profile. The default profile keeps the
+    /// pre-profiles location so existing installs carry over untouched.
+    pub fn settings_path(&self, config_dir: &Path) -> PathBuf {
+        if self.is_default() {
+            config_dir.join(crate::settings::SETTINGS_FILE)
+        } else {
+            config_dir
+                .join("profiles")
+                .join(&self.id)
+                .join(crate::settings::SETTINGS_FILE)
+        }
+    }
+
+    /// Root for this profile's meeting data.
+    pub fn data_dir(&self, app_data_dir: &Path) -> PathBuf {
+        match &self.storage_dir {
+            Some(dir) => PathBuf::from(dir),
+            None if self.is_default() => app_data_dir.to_path_buf(),
+            None => app_data_dir.join("profiles").join(&self.id),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+impl Default for ProfileList {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE_ID.to_string(),
+            profiles: vec![Profile::default_profile()],
+        }
+    }
+}
+
+/// Known profiles and which one is active, persisted in the app config directory.
+#[derive(Debug, Default)]
+pub struct Profiles {
+    path: Option<PathBuf>,
+    list: ProfileList,
+}
+
+impl Profiles {
+    pub fn load(path: PathBuf) -> Self {
+        let mut list: ProfileList = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        if !list.profiles.iter().any(Profile::is_default) {
+            list.profiles.insert(0, Profile::default_profile());
+        }
+        if !list.profiles.iter().any(|p| p.id == list.active) {
+            list.active = DEFAULT_PROFILE_ID.to_string();
+        }
+        Self {
+            path: Some(path),
+            list,
+        }
+    }
+
+    pub fn list(&self) -> &ProfileList {
+        &self.list
+    }
+
+    pub fn active(&self) -> &Profile {
+        self.get(&self.list.active)
+            .expect("active profile is always present")
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.list.profiles.iter().find(|p| p.id == id)
+    }
+
+    pub fn create(&mut self, name: &str, storage_dir: Option<String>) -> Result<Profile, String> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "Profile name must be 1 to {} characters",
+                MAX_NAME_LEN
+            ));
+        }
+        if self
+            .list
+            .profiles
+            .iter()
+            .any(|p| p.name.eq_ignore_ascii_case(name))
+        {
+            return Err(format!("A profile named '{}' already exists", name));
+        }
+        let storage_dir = storage_dir
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty());
+        if let Some(dir) = &storage_dir {
+            if !Path::new(dir).is_absolute() {
+                return Err("Profile storage location must be an absolute path".to_string());
+            }
+        }
+
+        let profile = Profile {
+            id: self.unique_id(name),
+            name: name.to_string(),
+            storage_dir,
+        };
+        let mut list = self.list.clone();
+        list.profiles.push(profile.clone());
+        self.save(list)?;
+        Ok(profile)
+    }
+
+    pub fn set_active(&mut self, id: &str) -> Result<Profile, String> {
+        let profile = self
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown profile: {}", id))?;
+        let mut list = self.list.clone();
+        list.active = profile.id.clone();
+        self.save(list)?;
+        Ok(profile)
+    }
+
+    /// Lowercase slug of the name, suffixed until it is unused.
+    fn unique_id(&self, name: &str) -> String {
+        slug::unique_slug(name, "profile", &[DEFAULT_PROFILE_ID], |id| {
+            self.get(id).is_some()
+        })
+    }
+
+    fn save(&mut self, list: ProfileList) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            }
+            let contents = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+            std::fs::write(path, contents)
+                .map_err(|e| format!("Failed to save profiles: {}", e))?;
+        }
+        self.list = list;
+        Ok(())
+    }
+}
@@ -0,0 +1,261 @@
+use crate::meeting_store;
+use crate::settings::{self, AdditionalVocabularyEntry, TranscriptionSettings};
+use crate::slug;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+pub const PROJECTS_FILE: &str = "projects.json";
+const MAX_NAME_LEN: usize = 64;
+const MAX_PROJECTS: usize = 200;
+
+/// A voice the recognizer should put a name to, enrolled with `enroll_speaker_rt`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterSpeaker {
+    pub label: String,
+    pub speaker_identifiers: Vec<String>,
+}
+
+/// A group of meetings that share vocabulary, speakers, an export folder and
+/// an AI notes template.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    /// Added to the custom vocabulary while recording the project's meetings.
+    #[serde(default)]
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    #[serde(default)]
+    pub speakers: Vec<RosterSpeaker>,
+    /// Folder the project's meetings are exported to instead of the usual one.
+    #[serde(default)]
+    pub export_path: Option<String>,
+    #[serde(default)]
+    pub prompt_template_id: Option<String>,
+}
+
+/// What `create_project` and `update_project` accept; ids are assigned here.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectInput {
+    pub name: String,
+    #[serde(default)]
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    #[serde(default)]
+    pub speakers: Vec<RosterSpeaker>,
+    #[serde(default)]
+    pub export_path: Option<String>,
+    #[serde(default)]
+    pub prompt_template_id: Option<String>,
+}
+
+impl Project {
+    /// Adds the project's vocabulary to `settings`, skipping terms already there.
+    pub fn apply_to(&self, settings: &mut TranscriptionSettings) {
+        settings.add_vocab(&self.additional_vocab);
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct ProjectList {
+    projects: Vec<Project>,
+    /// Project id of each assigned meeting, keyed by meeting id.
+    #[serde(default)]
+    meetings: BTreeMap<String, String>,
+}
+
+/// The active profile's projects and which meetings belong to them.
+#[derive(Debug, Default)]
+pub struct Projects {
+    path: Option<PathBuf>,
+    list: ProjectList,
+}
+
+impl Projects {
+    pub fn load(path: PathBuf) -> Self {
+        let list = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            list,
+        }
+    }
+
+    pub fn list(&self) -> &[Project] {
+        &self.list.projects
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Project> {
+        self.list.projects.iter().find(|p| p.id == id)
+    }
+
+    pub fn for_meeting(&self, meeting_id: &str) -> Option<&Project> {
+        self.list
+            .meetings
+            .get(meeting_id)
+            .and_then(|id| self.get(id))
+    }
+
+    /// Ids of the meetings assigned to project `id`.
+    pub fn meetings_in(&self, id: &str) -> HashSet<String> {
+        self.list
+            .meetings
+            .iter()
+            .filter(|(_, project_id)| *project_id == id)
+            .map(|(meeting_id, _)| meeting_id.clone())
+            .collect()
+    }
+
+    pub fn create(&mut self, input: ProjectInput) -> Result<Project, String> {
+        if self.list.projects.len() >= MAX_PROJECTS {
+            return Err(format!("At most {} projects are supported", MAX_PROJECTS));
+        }
+        let input = self.validate(input, None)?;
+        let project = Project {
+            id: self.unique_id(&input.name),
+            name: input.name,
+            additional_vocab: input.additional_vocab,
+            speakers: input.speakers,
+            export_path: input.export_path,
+            prompt_template_id: input.prompt_template_id,
+        };
+        let mut list = self.list.clone();
+        list.projects.push(project.clone());
+        self.save(list)?;
+        Ok(project)
+    }
+
+    pub fn update(&mut self, id: &str, input: ProjectInput) -> Result<Project, String> {
+        let index = self
+            .list
+            .projects
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| format!("Unknown project: {}", id))?;
+        let input = self.validate(input, Some(id))?;
+        let project = Project {
+            id: id.to_string(),
+            name: input.name,
+            additional_vocab: input.additional_vocab,
+            speakers: input.speakers,
+            export_path: input.export_path,
+            prompt_template_id: input.prompt_template_id,
+        };
+        let mut list = self.list.clone();
+        list.projects[index] = project.clone();
+        self.save(list)?;
+        Ok(project)
+    }
+
+    /// Deletes a project; its meetings are kept but no longer belong to one.
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        if self.get(id).is_none() {
+            return Err(format!("Unknown project: {}", id));
+        }
+        let mut list = self.list.clone();
+        list.projects.retain(|p| p.id != id);
+        list.meetings.retain(|_, project_id| project_id != id);
+        self.save(list)
+    }
+
+    /// Moves a meeting into a project, or out of any with `None`.
+    pub fn assign(&mut self, meeting_id: &str, project_id: Option<&str>) -> Result<(), String> {
+        meeting_store::validate_meeting_id(meeting_id)?;
+        let mut list = self.list.clone();
+        match project_id {
+            Some(project_id) => {
+                if self.get(project_id).is_none() {
+                    return Err(format!("Unknown project: {}", project_id));
+                }
+                list.meetings
+                    .insert(meeting_id.to_string(), project_id.to_string());
+            }
+            None => {
+                if list.meetings.remove(meeting_id).is_none() {
+                    return Ok(());
+                }
+            }
+        }
+        self.save(list)
+    }
+
+    /// Trims `input` and checks it, ignoring the project being updated when
+    /// looking for name clashes.
+    fn validate(&self, mut input: ProjectInput, id: Option<&str>) -> Result<ProjectInput, String> {
+        input.name = input.name.trim().to_string();
+        if input.name.is_empty() || input.name.chars().count() > MAX_NAME_LEN {
+            return Err(format!(
+                "Project name must be 1 to {} characters",
+                MAX_NAME_LEN
+            ));
+        }
+        if self
+            .list
+            .projects
+            .iter()
+            .any(|p| Some(p.id.as_str()) != id && p.name.eq_ignore_ascii_case(&input.name))
+        {
+            return Err(format!("A project named '{}' already exists", input.name));
+        }
+        settings::validate_vocab(&input.additional_vocab)
+            .map_err(|err| format!("Project '{}': {}", input.name, err))?;
+        for speaker in &mut input.speakers {
+            speaker.label = speaker.label.trim().to_string();
+        }
+        for (index, speaker) in input.speakers.iter().enumerate() {
+            if speaker.label.is_empty() || speaker.speaker_identifiers.is_empty() {
+                return Err(format!(
+                    "Project '{}': every speaker needs a name and an enrolled voice",
+                    input.name
+                ));
+            }
+            if input.speakers[..index]
+                .iter()
+                .any(|other| other.label.eq_ignore_ascii_case(&speaker.label))
+            {
+                return Err(format!(
+                    "Project '{}' lists '{}' more than once",
+                    input.name, speaker.label
+                ));
+            }
+        }
+        input.export_path = input
+            .export_path
+            .map(|path| path.trim().to_string())
+            .filter(|path| !path.is_empty());
+        if let Some(path) = &input.export_path {
+            if !Path::new(path).is_absolute() {
+                return Err("Project export folder must be an absolute path".to_string());
+            }
+        }
+        input.prompt_template_id = input
+            .prompt_template_id
+            .map(|id| id.trim().to_string())
+            .filter(|id| !id.is_empty());
+        Ok(input)
+    }
+
+    /// Lowercase slug of the name, suffixed until it is unused.
+    fn unique_id(&self, name: &str) -> String {
+        slug::unique_slug(name, "project", &[], |id| self.get(id).is_some())
+    }
+
+    fn save(&mut self, list: ProjectList) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create config directory: {}", e))?;
+            }
+            let contents = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+            std::fs::write(path, contents)
+                .map_err(|e| format!("Failed to save projects: {}", e))?;
+        }
+        self.list = list;
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! Times Jilu keeps to itself, such as evenings, weekends or focus blocks:
+//! automation may not start recordings and meeting reminders are held back.
+//! Recordings started by hand are never refused.
+
+use crate::state::AppState;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDateTime, NaiveTime, TimeZone, Weekday};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+pub const MAX_QUIET_WINDOWS: usize = 50;
+/// Quiet hours running into each other are followed this far ahead, which
+/// also ends the search when every hour of the week is quiet.
+const LOOKAHEAD_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Day {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Day {
+    fn is(self, weekday: Weekday) -> bool {
+        let day = match weekday {
+            Weekday::Mon => Day::Mon,
+            Weekday::Tue => Day::Tue,
+            Weekday::Wed => Day::Wed,
+            Weekday::Thu => Day::Thu,
+            Weekday::Fri => Day::Fri,
+            Weekday::Sat => Day::Sat,
+            Weekday::Sun => Day::Sun,
+        };
+        self == day
+    }
+}
+
+/// A stretch of local time that repeats on the given days. An `end` at or
+/// before `start` runs on past midnight, so `22:00` to `07:00` on Friday
+/// covers the small hours of Saturday; the same `start` and `end` is the
+/// whole day.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuietWindow {
+    /// Named when something is held back, e.g. `Evenings`.
+    pub label: Option<String>,
+    /// Days the window starts on; every day when empty.
+    pub days: Vec<Day>,
+    /// `HH:MM`, 24-hour.
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for QuietWindow {
+    fn default() -> Self {
+        Self {
+            label: None,
+            days: Vec::new(),
+            start: "00:00".to_string(),
+            end: "00:00".to_string(),
+        }
+    }
+}
+
+/// The quiet hours in force, as `quiet_hours_status` reports them.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietPeriod {
+    pub label: Option<String>,
+    /// When they end (RFC 3339).
+    pub until: String,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("Quiet hours time '{}' must be HH:MM", value))
+}
+
+impl QuietWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        parse_time(&self.start)?;
+        parse_time(&self.end)?;
+        Ok(())
+    }
+
+    /// Where the stretch of this window that covers `now` ends, if one does.
+    fn covering(&self, now: NaiveDateTime) -> Option<NaiveDateTime> {
+        // Settings validation rejects bad times, so this only skips windows
+        // written to the file by hand.
+        let start = parse_time(&self.start).ok()?;
+        let end = parse_time(&self.end).ok()?;
+        let mut length = end - start;
+        if length <= Duration::zero() {
+            length += Duration::days(1);
+        }
+        // A window can only still be running if it started today or yesterday.
+        [now.date(), now.date().pred_opt()?]
+            .into_iter()
+            .filter(|date| {
+                self.days.is_empty() || self.days.iter().any(|day| day.is(date.weekday()))
+            })
+            .map(|date| date.and_time(start))
+            .find(|&from| from <= now && now < from + length)
+            .map(|from| from + length)
+    }
+}
+
+/// The window `now` falls in and when the quiet ends. Windows that overlap
+/// or follow on from each other, e.g. weekday evenings running into a
+/// weekend, count as one quiet stretch.
+fn quiet_until(
+    windows: &[QuietWindow],
+    now: NaiveDateTime,
+) -> Option<(&QuietWindow, NaiveDateTime)> {
+    let (window, mut end) = windows
+        .iter()
+        .filter_map(|window| window.covering(now).map(|end| (window, end)))
+        .max_by_key(|(_, end)| *end)?;
+    while end < now + Duration::days(LOOKAHEAD_DAYS) {
+        match windows
+            .iter()
+            .filter_map(|window| window.covering(end))
+            .max()
+        {
+            Some(next) => end = next,
+            None => break,
+        }
+    }
+    Some((window, end))
+}
+
+fn label(window: &QuietWindow) -> Option<String> {
+    window
+        .label
+        .as_deref()
+        .map(str::trim)
+        .filter(|label| !label.is_empty())
+        .map(str::to_string)
+}
+
+/// The quiet hours `now` falls in.
+pub fn active(windows: &[QuietWindow], now: DateTime<Local>) -> Option<QuietPeriod> {
+    let (window, end) = quiet_until(windows, now.naive_local())?;
+    let until = Local
+        .from_local_datetime(&end)
+        .earliest()
+        .map(|end| end.to_rfc3339())
+        .unwrap_or_else(|| end.to_string());
+    Some(QuietPeriod {
+        label: label(window),
+        until,
+    })
+}
+
+/// Refuses automation during quiet hours, saying until when.
+pub fn check(windows: &[QuietWindow]) -> Result<(), String> {
+    let now = Local::now().naive_local();
+    let Some((window, end)) = quiet_until(windows, now) else {
+        return Ok(());
+    };
+    let until = if end.date() == now.date() {
+        end.format("%H:%M").to_string()
+    } else {
+        end.format("%A %H:%M").to_string()
+    };
+    Err(match label(window) {
+        Some(label) => format!("Quiet hours ({}) until {}", label, until),
+        None => format!("Quiet hours until {}", until),
+    })
+}
+
+/// The quiet hours in force now, or `None`. Reminders and anything else that
+/// would interrupt unasked hold back while they last.
+#[tauri::command]
+pub async fn quiet_hours_status(state: State<'_, AppState>) -> Result<Option<QuietPeriod>, String> {
+    let windows = state.stores.settings.lock().get().quiet_hours.clone();
+    Ok(active(&windows, Local::now()))
+}
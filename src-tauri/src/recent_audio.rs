@@ -0,0 +1,129 @@
+//! The last couple of minutes of audio a recording sent, and who the
+//! provider heard when, so a voice from the meeting can be enrolled without
+//! asking its owner to read a sample.
+
+use crate::audio::TARGET_SAMPLE_RATE;
+use std::collections::VecDeque;
+
+/// Seconds of audio kept.
+pub const KEEP_SECS: usize = 120;
+/// Pauses shorter than this between a speaker's words stay in their clip.
+const WORD_GAP_SECS: f64 = 0.5;
+
+#[derive(Debug, Default)]
+pub struct RecentAudio {
+    channels: usize,
+    /// 16-bit PCM as sent, oldest first, ending `end` seconds into the sent audio.
+    pcm: VecDeque<u8>,
+    end: f64,
+    /// `(speaker, start, end)` on the same clock, oldest first.
+    spans: VecDeque<(String, f64, f64)>,
+}
+
+impl RecentAudio {
+    /// Starts over for a recording sending `channels` interleaved channels.
+    pub fn reset(&mut self, channels: usize) {
+        *self = Self {
+            channels: channels.max(1),
+            ..Self::default()
+        };
+    }
+
+    /// Forgets everything, once the recording is over.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    fn frame_bytes(&self) -> usize {
+        2 * self.channels.max(1)
+    }
+
+    fn bytes_per_sec(&self) -> f64 {
+        (self.frame_bytes() * TARGET_SAMPLE_RATE as usize) as f64
+    }
+
+    /// Where the kept audio starts, in seconds of sent audio.
+    fn start(&self) -> f64 {
+        self.end - self.pcm.len() as f64 / self.bytes_per_sec()
+    }
+
+    /// Adds audio just sent to the provider.
+    pub fn push_audio(&mut self, pcm: &[u8]) {
+        if self.channels == 0 {
+            return;
+        }
+        self.pcm.extend(pcm);
+        self.end += pcm.len() as f64 / self.bytes_per_sec();
+        let keep = KEEP_SECS * TARGET_SAMPLE_RATE as usize * self.frame_bytes();
+        if self.pcm.len() > keep {
+            self.pcm.drain(..self.pcm.len() - keep);
+        }
+        let start = self.start();
+        while self.spans.front().is_some_and(|(_, _, end)| *end <= start) {
+            self.spans.pop_front();
+        }
+    }
+
+    /// Notes that `speaker` said a word from `start` to `end` seconds into
+    /// the sent audio.
+    pub fn push_word(&mut self, speaker: &str, start: f64, end: f64) {
+        if self.channels == 0 {
+            return;
+        }
+        if let Some((last, _, last_end)) = self.spans.back_mut() {
+            if last == speaker && start - *last_end < WORD_GAP_SECS {
+                *last_end = last_end.max(end);
+                return;
+            }
+        }
+        self.spans.push_back((speaker.to_string(), start, end));
+    }
+
+    /// Files what was heard from `from` under `to`, after a rename.
+    pub fn rename(&mut self, from: &str, to: &str) {
+        for (speaker, _, _) in &mut self.spans {
+            if speaker == from {
+                *speaker = to.to_string();
+            }
+        }
+    }
+
+    /// Up to `max_secs` of `speaker`'s latest speech that is still kept, as
+    /// one stretch of PCM in the order it was heard.
+    pub fn speaker_audio(&self, speaker: &str, max_secs: f64) -> Vec<u8> {
+        let start = self.start();
+        let mut remaining = max_secs;
+        let mut clips = Vec::new();
+        for (_, from, to) in self
+            .spans
+            .iter()
+            .rev()
+            .filter(|(label, _, _)| label == speaker)
+        {
+            if remaining <= 0.0 {
+                break;
+            }
+            let to = to.min(self.end);
+            let from = from.max(start).max(to - remaining);
+            if to > from {
+                remaining -= to - from;
+                clips.push((from, to));
+            }
+        }
+
+        let frame = self.frame_bytes();
+        let byte_at = |at: f64| {
+            let bytes = ((at - start) * self.bytes_per_sec()) as usize;
+            (bytes / frame * frame).min(self.pcm.len())
+        };
+        let mut pcm = Vec::new();
+        for (from, to) in clips.into_iter().rev() {
+            pcm.extend(self.pcm.range(byte_at(from)..byte_at(to)));
+        }
+        pcm
+    }
+}
@@ -0,0 +1,175 @@
+use crate::audio::SOURCE_SAMPLE_RATE;
+use crate::flac::FlacWriter;
+use crate::settings::{AudioFileFormat, AudioLayout, AudioRecordingSettings};
+use hound::{SampleFormat, WavSpec, WavWriter};
+use rtrb::Consumer;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+const IDLE_WAIT: Duration = Duration::from_millis(20);
+/// 100ms of [mic, system] pairs.
+const CHUNK_SAMPLES: usize = SOURCE_SAMPLE_RATE as usize / 10 * 2;
+
+/// One audio file being written.
+enum Sink {
+    Wav(WavWriter<BufWriter<File>>),
+    Flac(FlacWriter),
+}
+
+impl Sink {
+    fn create(path: &Path, format: AudioFileFormat) -> Result<Self, String> {
+        match format {
+            AudioFileFormat::Wav => {
+                let spec = WavSpec {
+                    channels: 1,
+                    sample_rate: SOURCE_SAMPLE_RATE,
+                    bits_per_sample: 16,
+                    sample_format: SampleFormat::Int,
+                };
+                WavWriter::create(path, spec)
+                    .map(Sink::Wav)
+                    .map_err(|e| format!("Failed to create WAV file: {}", e))
+            }
+            AudioFileFormat::Flac => FlacWriter::create(path, SOURCE_SAMPLE_RATE).map(Sink::Flac),
+        }
+    }
+
+    fn write(&mut self, sample: f32) -> Result<(), String> {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        match self {
+            Sink::Wav(writer) => writer
+                .write_sample(sample)
+                .map_err(|e| format!("Failed to write WAV file: {}", e)),
+            Sink::Flac(writer) => writer.write_sample(sample),
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            Sink::Wav(writer) => writer
+                .finalize()
+                .map_err(|e| format!("Failed to finalize WAV file: {}", e)),
+            Sink::Flac(writer) => writer.finalize(),
+        }
+    }
+}
+
+/// Writes what the mixer hears to audio files on its own thread, mixed into
+/// one file or with a file per source.
+pub struct Recorder {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<Result<Vec<PathBuf>, String>>>,
+}
+
+impl Recorder {
+    /// Starts writing `sources` (see `AudioMixer::tap_sources`) to files in
+    /// `dir` named after `stem`. `has_system` is false for mixers without
+    /// system audio, which then get no system file.
+    pub fn start(
+        sources: Consumer<f32>,
+        dir: &Path,
+        stem: &str,
+        settings: &AudioRecordingSettings,
+        has_system: bool,
+    ) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+        let extension = match settings.format {
+            AudioFileFormat::Wav => "wav",
+            AudioFileFormat::Flac => "flac",
+        };
+        let path = |suffix: &str| dir.join(format!("{}{}.{}", stem, suffix, extension));
+        let paths = match (settings.layout, has_system) {
+            (AudioLayout::Separate, true) => vec![path("-mic"), path("-system")],
+            (AudioLayout::Separate, false) => vec![path("-mic")],
+            (AudioLayout::Mixed, _) => vec![path("")],
+        };
+        let sinks = paths
+            .iter()
+            .map(|path| Sink::create(path, settings.format))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let mixed = settings.layout == AudioLayout::Mixed;
+        let thread = std::thread::Builder::new()
+            .name("audio-recorder".into())
+            .spawn(move || {
+                record(sources, sinks, mixed, has_system, &thread_stop)?;
+                Ok(paths)
+            })
+            .map_err(|e| format!("Failed to start audio recorder thread: {}", e))?;
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// Writes out what is left and closes the files, returning their paths.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>, String> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err("Audio recorder thread panicked".to_string()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn record(
+    mut sources: Consumer<f32>,
+    mut sinks: Vec<Sink>,
+    mixed: bool,
+    has_system: bool,
+    stop: &AtomicBool,
+) -> Result<(), String> {
+    let mut pairs = Vec::with_capacity(CHUNK_SAMPLES);
+    loop {
+        // Stopping still drains what the mixer already handed over.
+        let stopping = stop.load(Ordering::Relaxed) || sources.is_abandoned();
+        let available = sources.slots() / 2 * 2;
+        if available == 0 {
+            if stopping {
+                break;
+            }
+            std::thread::sleep(IDLE_WAIT);
+            continue;
+        }
+        let Ok(chunk) = sources.read_chunk(available.min(CHUNK_SAMPLES)) else {
+            continue;
+        };
+        let (first, second) = chunk.as_slices();
+        pairs.clear();
+        pairs.extend_from_slice(first);
+        pairs.extend_from_slice(second);
+        chunk.commit_all();
+        for pair in pairs.chunks_exact(2) {
+            let (mic, system) = (pair[0], pair[1]);
+            if !mixed {
+                sinks[0].write(mic)?;
+                if let Some(sink) = sinks.get_mut(1) {
+                    sink.write(system)?;
+                }
+            } else if has_system {
+                sinks[0].write((mic + system) * 0.5)?;
+            } else {
+                sinks[0].write(mic)?;
+            }
+        }
+    }
+    sinks.into_iter().try_for_each(Sink::finish)
+}
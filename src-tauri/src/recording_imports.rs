@@ -0,0 +1,371 @@
+//! Notices meetings recorded by Zoom or Teams on this machine and offers to
+//! import them, so a meeting Jilu missed still ends up with a transcript.
+//!
+//! The folders are polled rather than watched: recordings are rare, and Zoom
+//! writes its files slowly while converting them after the meeting. A file is
+//! offered once, after its size has stopped changing.
+
+use crate::settings::RecordingImportSettings;
+use crate::state::AppState;
+use crate::transcript::{Transcript, TranscriptTurnPayload, TurnWord};
+use crate::{
+    batch, chapters, events, formatting, locale, meeting_store, storage, transcript_export,
+};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::{info, warn};
+
+const STATE_FILE: &str = "recording-imports.json";
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// How long a file must go unchanged before it counts as finished.
+const SETTLE_TIME: Duration = Duration::from_secs(60);
+/// Audio first, since it uploads faster than the video of the same meeting.
+const MEDIA_EXTENSIONS: &[&str] = &["m4a", "mp3", "wav", "mp4", "mov", "webm"];
+/// Teams names its downloads `<title>-<yyyymmdd_hhmmss>-Meeting Recording.mp4`.
+const TEAMS_MARKER: &str = "Meeting Recording";
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordingSource {
+    Zoom,
+    Teams,
+    /// One of the extra folders in the settings.
+    Folder,
+}
+
+/// Payload of `local-recording-found`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FoundRecording {
+    pub path: String,
+    pub source: RecordingSource,
+    /// Meeting name from the folder or file name.
+    pub title: Option<String>,
+    /// When the meeting started (RFC 3339): from the name when it says,
+    /// otherwise when the file was created.
+    pub started_at: String,
+    /// When the file was last written, around when the meeting ended.
+    pub ended_at: String,
+    pub size_bytes: u64,
+}
+
+/// What has been offered already, kept across launches.
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ImportState {
+    /// Seconds since the epoch when watching began; older files were there
+    /// before and are never offered.
+    since: Option<u64>,
+    offered: BTreeSet<String>,
+}
+
+struct Candidate {
+    path: PathBuf,
+    source: RecordingSource,
+    title: Option<String>,
+    started_at: Option<DateTime<Local>>,
+}
+
+struct Watcher {
+    path: Option<PathBuf>,
+    state: ImportState,
+    /// Sizes seen on the last poll, to tell when a file has stopped growing.
+    sizes: HashMap<PathBuf, u64>,
+}
+
+impl Watcher {
+    fn load(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(STATE_FILE))
+            .map_err(|err| warn!("Offered recordings will not be remembered: {}", err))
+            .ok();
+        let state = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            state,
+            sizes: HashMap::new(),
+        }
+    }
+
+    fn save(&mut self) {
+        // Deleted files need no remembering.
+        self.state.offered.retain(|path| Path::new(path).exists());
+        let Some(path) = &self.path else { return };
+        let written = serde_json::to_string(&self.state)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(path, contents).map_err(|e| e.to_string()));
+        if let Err(err) = written {
+            warn!("Failed to save offered recordings: {}", err);
+        }
+    }
+
+    /// Recordings that have finished since the last poll.
+    fn poll(&mut self, settings: &RecordingImportSettings) -> Vec<FoundRecording> {
+        let now = SystemTime::now();
+        let Some(since) = self.state.since else {
+            self.state.since = Some(unix_secs(now));
+            self.save();
+            return Vec::new();
+        };
+        let mut found = Vec::new();
+        let mut sizes = HashMap::new();
+        for candidate in candidates(settings) {
+            let key = candidate.path.to_string_lossy().into_owned();
+            if self.state.offered.contains(&key) {
+                continue;
+            }
+            let Ok(metadata) = std::fs::metadata(&candidate.path) else {
+                continue;
+            };
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            if unix_secs(modified) < since {
+                continue;
+            }
+            let size = metadata.len();
+            let settled = self.sizes.get(&candidate.path) == Some(&size)
+                && now.duration_since(modified).unwrap_or_default() >= SETTLE_TIME;
+            if !settled {
+                sizes.insert(candidate.path, size);
+                continue;
+            }
+            let started_at = candidate
+                .started_at
+                .or_else(|| metadata.created().ok().map(DateTime::<Local>::from))
+                .unwrap_or_else(|| DateTime::<Local>::from(modified));
+            found.push(FoundRecording {
+                path: key.clone(),
+                source: candidate.source,
+                title: candidate.title,
+                started_at: started_at.to_rfc3339(),
+                ended_at: DateTime::<Local>::from(modified).to_rfc3339(),
+                size_bytes: size,
+            });
+            self.state.offered.insert(key);
+        }
+        self.sizes = sizes;
+        if !found.is_empty() {
+            self.save();
+        }
+        found
+    }
+
+    /// Forgets when watching began, so turning it back on only offers what
+    /// is recorded from then on.
+    fn stop(&mut self) {
+        if self.state.since.take().is_some() {
+            self.sizes.clear();
+            self.save();
+        }
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn is_media(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+fn media_rank(path: &Path) -> usize {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    MEDIA_EXTENSIONS
+        .iter()
+        .position(|known| *known == ext)
+        .unwrap_or(MEDIA_EXTENSIONS.len())
+}
+
+fn entries(dir: &Path) -> impl Iterator<Item = PathBuf> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+}
+
+/// Every file that may be a recording, finished or not.
+fn candidates(settings: &RecordingImportSettings) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    if settings.zoom {
+        if let Some(root) = dirs::document_dir().map(|dir| dir.join("Zoom")) {
+            found.extend(zoom_recordings(&root));
+        }
+    }
+    if settings.teams {
+        if let Some(downloads) = dirs::download_dir() {
+            found.extend(teams_recordings(&downloads));
+        }
+    }
+    for folder in &settings.folders {
+        found.extend(
+            entries(Path::new(folder))
+                .filter(|path| is_media(path))
+                .map(|path| Candidate {
+                    title: path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned()),
+                    path,
+                    source: RecordingSource::Folder,
+                    started_at: None,
+                }),
+        );
+    }
+    found
+}
+
+/// Zoom keeps each meeting in `Documents/Zoom/<yyyy-mm-dd hh.mm.ss> <title>/`,
+/// with its audio and video side by side; the audio is enough.
+fn zoom_recordings(root: &Path) -> Vec<Candidate> {
+    entries(root)
+        .filter(|dir| dir.is_dir())
+        .filter_map(|dir| {
+            let file = entries(&dir)
+                .filter(|path| is_media(path))
+                .min_by_key(|path| media_rank(path))?;
+            let name = dir.file_name()?.to_string_lossy().into_owned();
+            let (started_at, title) = match name.split_at_checked(19) {
+                Some((stamp, title)) => (
+                    NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H.%M.%S")
+                        .ok()
+                        .and_then(|at| Local.from_local_datetime(&at).earliest()),
+                    title.trim(),
+                ),
+                None => (None, name.as_str()),
+            };
+            Some(Candidate {
+                path: file,
+                source: RecordingSource::Zoom,
+                title: (!title.is_empty()).then(|| title.to_string()),
+                started_at,
+            })
+        })
+        .collect()
+}
+
+/// Teams recordings downloaded from the meeting chat or Stream.
+fn teams_recordings(downloads: &Path) -> Vec<Candidate> {
+    entries(downloads)
+        .filter(|path| is_media(path))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_string_lossy().into_owned();
+            let rest = stem.strip_suffix(TEAMS_MARKER)?.strip_suffix('-')?;
+            let (title, stamp) = rest.rsplit_once('-')?;
+            let started_at = NaiveDateTime::parse_from_str(stamp, "%Y%m%d_%H%M%S")
+                .ok()
+                .and_then(|at| Local.from_local_datetime(&at).earliest());
+            Some(Candidate {
+                title: Some(title.trim().to_string()).filter(|title| !title.is_empty()),
+                path,
+                source: RecordingSource::Teams,
+                started_at,
+            })
+        })
+        .collect()
+}
+
+/// Polls the recording folders for as long as the app runs, announcing each
+/// finished recording with `local-recording-found`. Nothing is offered while
+/// Jilu is recording itself.
+pub fn spawn_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watcher = Watcher::load(&app);
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let state = app.state::<AppState>();
+            let settings = state.stores.settings.lock().get().recording_imports.clone();
+            if !settings.enabled {
+                watcher.stop();
+                continue;
+            }
+            if state.sessions.is_recording() {
+                continue;
+            }
+            for recording in watcher.poll(&settings) {
+                info!(source = ?recording.source, "Found a local meeting recording");
+                let _ = app.emit(events::LOCAL_RECORDING_FOUND, recording);
+            }
+        }
+    });
+}
+
+/// Transcribes a recording with Speechmatics' batch API and saves the result
+/// as the transcript of `meeting_id`, replacing any it had.
+#[tauri::command]
+pub async fn import_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    meeting_id: String,
+    api_key: String,
+) -> Result<Vec<TranscriptTurnPayload>, String> {
+    let path = PathBuf::from(path);
+    if !path.is_file() || !is_media(&path) {
+        return Err(format!("{} is not an audio or video file", path.display()));
+    }
+    meeting_store::validate_meeting_id(&meeting_id)?;
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("recording_import");
+    let settings = state.stores.settings.lock().get().clone();
+
+    let job_id = batch::submit(&api_key, &path, &settings.transcription).await?;
+    info!(job_id = %job_id, "Transcribing an imported recording");
+    let json = batch::transcript(&api_key, &job_id, "json-v2").await?;
+    let result = jilu_core::speechmatics::batch_result(&json)?;
+
+    let mut transcript = Transcript::default();
+    transcript.set_locale(locale::Locale::from_tag(&settings.locale));
+    for run in result.speaker_runs() {
+        let text = formatting::apply(
+            &run.text,
+            &settings.transcription.formatting,
+            transcript.starts_sentence(run.speaker),
+        );
+        if let Some(idx) = transcript.append_at(run.speaker, &text, run.span) {
+            let threshold = settings.transcription.confidence_threshold;
+            transcript.add_words(
+                idx,
+                run.words
+                    .iter()
+                    .filter_map(|word| TurnWord::from_word(word, threshold)),
+            );
+        }
+    }
+    let turns = transcript.turns().to_vec();
+    if turns.is_empty() {
+        return Err("No speech was found in the recording".to_string());
+    }
+
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    storage::save_transcript_turns(&dir.join(transcript_export::TRANSCRIPT_FILE), &turns)?;
+    let chapters = chapters::detect(&turns);
+    if !chapters.is_empty() {
+        if let Err(err) = storage::save_chapters(&dir.join(chapters::CHAPTERS_FILE), &chapters) {
+            warn!("{}", err);
+        }
+    }
+    Ok(turns)
+}
@@ -0,0 +1,59 @@
+use serde::Serialize;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+
+pub const REPORT_INTERVAL_SECS: u64 = 5;
+
+/// Snapshot returned by `get_resource_usage` and emitted as `resource-usage`.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct ResourceUsage {
+    pub rss_bytes: u64,
+    pub cpu_percent: f32,
+    pub recording: bool,
+    pub screen_queue_samples: usize,
+    pub mic_queue_samples: usize,
+    pub pcm_queue_bytes: usize,
+    pub dropped_input_samples: u64,
+    pub dropped_pcm_bytes: u64,
+    /// Notification sounds heard in the system audio this recording.
+    pub notification_sounds: u64,
+    pub transcript_turns: usize,
+    pub transcript_bytes: usize,
+}
+
+/// Samples memory and CPU for the current process.
+///
+/// CPU usage is measured between consecutive refreshes, so the monitor must be
+/// kept alive and sampled repeatedly; the first sample always reports 0%.
+pub struct ProcessMonitor {
+    system: System,
+    pid: Option<Pid>,
+}
+
+impl Default for ProcessMonitor {
+    fn default() -> Self {
+        Self {
+            system: System::new(),
+            pid: sysinfo::get_current_pid().ok(),
+        }
+    }
+}
+
+impl ProcessMonitor {
+    /// Returns `(rss_bytes, cpu_percent)` for this process.
+    pub fn sample(&mut self) -> (u64, f32) {
+        let Some(pid) = self.pid else {
+            return (0, 0.0);
+        };
+
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing().with_memory().with_cpu(),
+        );
+
+        self.system
+            .process(pid)
+            .map(|process| (process.memory(), process.cpu_usage()))
+            .unwrap_or((0, 0.0))
+    }
+}
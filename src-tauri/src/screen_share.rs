@@ -0,0 +1,172 @@
+//! Whether the screen is being shared, so live transcript text can be kept out
+//! of a window the rest of the call may be watching.
+//!
+//! On macOS Zoom starts a helper process for as long as it shares, which is
+//! watched for while a recording keeps transcript text hidden. Other apps give no dependable sign, so `set_screen_sharing` lets
+//! the UI or automation say when sharing starts and stops.
+
+use crate::events;
+use crate::state::AppState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tracing::info;
+
+#[cfg(target_os = "macos")]
+const DETECT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+/// Runs while Zoom shares a screen or window.
+#[cfg(target_os = "macos")]
+const ZOOM_SHARE_PROCESS: &str = "CptHost";
+
+#[derive(Debug, Default)]
+pub struct ScreenShare {
+    /// Said by `set_screen_sharing`.
+    reported: AtomicBool,
+    /// Noticed by the detector.
+    detected: AtomicBool,
+    /// Whether the detector is running.
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    watching: AtomicBool,
+}
+
+impl ScreenShare {
+    pub fn is_sharing(&self) -> bool {
+        self.reported.load(Ordering::Relaxed) || self.detected.load(Ordering::Relaxed)
+    }
+}
+
+/// Payload of `screen-share-changed`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenShareStatus {
+    pub sharing: bool,
+    /// Whether transcript text is being held back from the window.
+    pub transcript_hidden: bool,
+    pub placeholder: String,
+}
+
+pub fn status(state: &AppState) -> ScreenShareStatus {
+    let privacy = state
+        .stores
+        .settings
+        .lock()
+        .get()
+        .screen_share_privacy
+        .clone();
+    let sharing = state.services.screen_share.is_sharing();
+    ScreenShareStatus {
+        sharing,
+        transcript_hidden: sharing && privacy.hide_transcript,
+        placeholder: privacy.placeholder,
+    }
+}
+
+/// What the window shows instead of transcript text, while it must not
+/// show the text itself.
+pub fn transcript_placeholder(state: &AppState) -> Option<String> {
+    let status = status(state);
+    status.transcript_hidden.then_some(status.placeholder)
+}
+
+/// Applies `update` to the sharing state, announcing the result if sharing
+/// started or stopped.
+fn update(app: &AppHandle, update: impl FnOnce(&ScreenShare)) {
+    let state = app.state::<AppState>();
+    let share = &state.services.screen_share;
+    let was_sharing = share.is_sharing();
+    update(share);
+    if share.is_sharing() == was_sharing {
+        return;
+    }
+    let status = status(&state);
+    info!(
+        sharing = status.sharing,
+        hidden = status.transcript_hidden,
+        "Screen sharing changed"
+    );
+    let _ = app.emit(events::SCREEN_SHARE_CHANGED, status);
+}
+
+/// Only worth looking for Zoom's helper while there is transcript text to hide.
+#[cfg(target_os = "macos")]
+fn should_watch(state: &AppState) -> bool {
+    state.sessions.is_recording()
+        && state
+            .stores
+            .settings
+            .lock()
+            .get()
+            .screen_share_privacy
+            .hide_transcript
+}
+
+/// Starts watching for Zoom sharing the screen, if a recording is running with
+/// transcript hiding on and nothing is watching yet. The watch ends by itself
+/// once either stops. Call it when a recording starts or the setting changes.
+pub fn watch(app: &AppHandle) {
+    #[cfg(not(target_os = "macos"))]
+    let _ = app;
+
+    #[cfg(target_os = "macos")]
+    {
+        let state = app.state::<AppState>();
+        if !should_watch(&state)
+            || state
+                .services
+                .screen_share
+                .watching
+                .swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+        info!("Watching for Zoom screen sharing");
+        tauri::async_runtime::spawn(detect(app.clone()));
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn detect(app: AppHandle) {
+    let mut ticker = tokio::time::interval(DETECT_INTERVAL);
+    loop {
+        ticker.tick().await;
+        let state = app.state::<AppState>();
+        if !should_watch(&state) {
+            update(&app, |share| share.detected.store(false, Ordering::Relaxed));
+            let share = &state.services.screen_share;
+            share.watching.store(false, Ordering::SeqCst);
+            // A recording may have started since the check, and found this watch still running.
+            if !should_watch(&state) || share.watching.swap(true, Ordering::SeqCst) {
+                info!("Stopped watching for Zoom screen sharing");
+                return;
+            }
+        }
+        let sharing = tokio::process::Command::new("pgrep")
+            .args(["-x", ZOOM_SHARE_PROCESS])
+            .output()
+            .await
+            .is_ok_and(|output| output.status.success());
+        update(&app, |share| {
+            share.detected.store(sharing, Ordering::Relaxed)
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn get_screen_share_status(
+    state: State<'_, AppState>,
+) -> Result<ScreenShareStatus, String> {
+    Ok(status(&state))
+}
+
+/// Says whether the screen is being shared, for apps the detector cannot see.
+#[tauri::command]
+pub async fn set_screen_sharing(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    sharing: bool,
+) -> Result<ScreenShareStatus, String> {
+    update(&app, |share| {
+        share.reported.store(sharing, Ordering::Relaxed)
+    });
+    Ok(status(&state))
+}
@@ -0,0 +1,372 @@
+//! Full-text search over the saved transcripts of the active profile.
+//!
+//! The index is kept in memory. A meeting's transcript is read and split into
+//! words the first time a search needs it and again only once its file
+//! changes, so repeated searches never re-read the meeting store.
+
+use crate::meeting_store;
+use crate::state::AppState;
+use crate::transcript::{TranscriptTurnPayload, TurnSource};
+use crate::transcript_export::TRANSCRIPT_FILE;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::{AppHandle, Manager, State};
+use tracing::warn;
+
+const DEFAULT_LIMIT: usize = 50;
+/// Turns shown per meeting; `matches` still counts them all.
+const HITS_PER_MEETING: usize = 5;
+/// Bytes of context kept either side of the first match in a snippet.
+const SNIPPET_CONTEXT: usize = 80;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchFilters {
+    /// Only these meetings, e.g. the ones the frontend has narrowed down by
+    /// date or tag.
+    pub meeting_ids: Option<Vec<String>>,
+    /// Only turns by this speaker.
+    pub speaker: Option<String>,
+    /// Only turns heard on the microphone (`mic`, what you said) or in
+    /// system audio (`system`, what you heard).
+    pub source: Option<TurnSource>,
+    /// Most meetings to return; 50 when left out.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResult {
+    pub meeting_id: String,
+    /// Turns that matched, of which `hits` holds the first few.
+    pub matches: usize,
+    pub hits: Vec<SearchHit>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub turn_index: usize,
+    pub speaker: Option<String>,
+    pub source: Option<TurnSource>,
+    /// Seconds since the recording started, for jumping to the turn.
+    pub offset: Option<f64>,
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// Part of a snippet; joined in order, the parts are the snippet's text.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SnippetPart {
+    pub text: String,
+    /// Whether this part is a word the query matched.
+    pub highlight: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    /// Meetings folder the index was built from; another profile has another.
+    root: PathBuf,
+    meetings: HashMap<String, IndexedMeeting>,
+}
+
+#[derive(Debug)]
+struct IndexedMeeting {
+    modified: SystemTime,
+    turns: Vec<IndexedTurn>,
+    /// Each lowercased word, with the turns it appears in.
+    words: BTreeMap<String, BTreeSet<usize>>,
+}
+
+#[derive(Debug)]
+struct IndexedTurn {
+    speaker: Option<String>,
+    source: Option<TurnSource>,
+    text: String,
+    offset: Option<f64>,
+}
+
+impl IndexedMeeting {
+    fn load(path: &Path, modified: SystemTime) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let turns: Vec<TranscriptTurnPayload> =
+            serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        let mut words: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+        for (index, turn) in turns.iter().enumerate() {
+            for (start, end) in word_spans(&turn.text) {
+                words
+                    .entry(turn.text[start..end].to_lowercase())
+                    .or_default()
+                    .insert(index);
+            }
+        }
+        let turns = turns
+            .into_iter()
+            .map(|turn| IndexedTurn {
+                speaker: turn.speaker,
+                source: turn.source,
+                text: turn.text,
+                offset: turn.offset.or(turn.start),
+            })
+            .collect();
+        Ok(Self {
+            modified,
+            turns,
+            words,
+        })
+    }
+
+    /// Turns containing a word that starts with each of `terms`.
+    fn matching_turns(&self, terms: &[String]) -> BTreeSet<usize> {
+        let mut matching: Option<BTreeSet<usize>> = None;
+        for term in terms {
+            let turns: BTreeSet<usize> = self
+                .words
+                .range(term.clone()..)
+                .take_while(|(word, _)| word.starts_with(term.as_str()))
+                .flat_map(|(_, turns)| turns.iter().copied())
+                .collect();
+            let turns = match matching {
+                Some(matching) => matching.intersection(&turns).copied().collect(),
+                None => turns,
+            };
+            if turns.is_empty() {
+                return turns;
+            }
+            matching = Some(turns);
+        }
+        matching.unwrap_or_default()
+    }
+}
+
+impl SearchIndex {
+    /// Brings the index up to date with the transcripts saved under `root`.
+    pub fn refresh(&mut self, root: &Path) {
+        if self.root != root {
+            self.root = root.to_path_buf();
+            self.meetings.clear();
+        }
+        let Ok(entries) = std::fs::read_dir(root) else {
+            self.meetings.clear();
+            return;
+        };
+        let mut present = HashSet::new();
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(id) = entry.file_name().into_string() else {
+                continue;
+            };
+            if meeting_store::validate_meeting_id(&id).is_err() {
+                continue;
+            }
+            let path = entry.path().join(TRANSCRIPT_FILE);
+            let Ok(modified) = std::fs::metadata(&path).and_then(|meta| meta.modified()) else {
+                continue;
+            };
+            present.insert(id.clone());
+            if self
+                .meetings
+                .get(&id)
+                .is_some_and(|meeting| meeting.modified == modified)
+            {
+                continue;
+            }
+            match IndexedMeeting::load(&path, modified) {
+                Ok(meeting) => {
+                    self.meetings.insert(id, meeting);
+                }
+                Err(err) => {
+                    warn!("Not searching the transcript of {}: {}", id, err);
+                    self.meetings.remove(&id);
+                }
+            }
+        }
+        self.meetings.retain(|id, _| present.contains(id));
+    }
+
+    /// Meetings with turns containing every word of `query`, most matches
+    /// first. The last letters of a word may be left off, so `budg` finds
+    /// "budget".
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Vec<SearchResult> {
+        let mut terms: Vec<String> = word_spans(query)
+            .into_iter()
+            .map(|(start, end)| query[start..end].to_lowercase())
+            .collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let only: Option<HashSet<&str>> = filters
+            .meeting_ids
+            .as_ref()
+            .map(|ids| ids.iter().map(String::as_str).collect());
+        let speaker = filters
+            .speaker
+            .as_deref()
+            .map(str::trim)
+            .filter(|speaker| !speaker.is_empty());
+
+        let mut results: Vec<SearchResult> = self
+            .meetings
+            .iter()
+            .filter(|(id, _)| only.as_ref().is_none_or(|only| only.contains(id.as_str())))
+            .filter_map(|(id, meeting)| {
+                let turns: Vec<usize> = meeting
+                    .matching_turns(&terms)
+                    .into_iter()
+                    .filter(|&index| {
+                        let turn = &meeting.turns[index];
+                        speaker.is_none_or(|speaker| {
+                            turn.speaker
+                                .as_deref()
+                                .is_some_and(|label| label.eq_ignore_ascii_case(speaker))
+                        }) && filters
+                            .source
+                            .is_none_or(|source| turn.source == Some(source))
+                    })
+                    .collect();
+                if turns.is_empty() {
+                    return None;
+                }
+                let hits = turns
+                    .iter()
+                    .take(HITS_PER_MEETING)
+                    .map(|&index| {
+                        let turn = &meeting.turns[index];
+                        SearchHit {
+                            turn_index: index,
+                            speaker: turn.speaker.clone(),
+                            source: turn.source,
+                            offset: turn.offset,
+                            snippet: snippet(&turn.text, &terms),
+                        }
+                    })
+                    .collect();
+                Some(SearchResult {
+                    meeting_id: id.clone(),
+                    matches: turns.len(),
+                    hits,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            b.matches
+                .cmp(&a.matches)
+                .then_with(|| a.meeting_id.cmp(&b.meeting_id))
+        });
+        results.truncate(filters.limit.unwrap_or(DEFAULT_LIMIT));
+        results
+    }
+}
+
+/// Byte ranges of the words in `text`: runs of letters and digits.
+fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                spans.push((from, index));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        spans.push((from, text.len()));
+    }
+    spans
+}
+
+/// The part of `text` around its first match, with the matched words marked.
+fn snippet(text: &str, terms: &[String]) -> Vec<SnippetPart> {
+    let matched: Vec<(usize, usize)> = word_spans(text)
+        .into_iter()
+        .filter(|&(start, end)| {
+            let word = text[start..end].to_lowercase();
+            terms.iter().any(|term| word.starts_with(term.as_str()))
+        })
+        .collect();
+    let first = matched.first().map_or(0, |&(start, _)| start);
+
+    let mut from = first.saturating_sub(SNIPPET_CONTEXT);
+    while !text.is_char_boundary(from) {
+        from -= 1;
+    }
+    if from > 0 {
+        // Start on a whole word.
+        from = text[from..first]
+            .find(' ')
+            .map_or(first, |space| from + space + 1);
+    }
+    let mut to = (first + 2 * SNIPPET_CONTEXT).min(text.len());
+    while !text.is_char_boundary(to) {
+        to += 1;
+    }
+    let first_end = matched.first().map_or(0, |&(_, end)| end);
+    if to < text.len() && first_end < to {
+        to = text[first_end..to]
+            .rfind(' ')
+            .map_or(to, |space| first_end + space);
+    }
+
+    let mut parts: Vec<SnippetPart> = Vec::new();
+    let mut push = |text: &str, highlight: bool| {
+        if text.is_empty() {
+            return;
+        }
+        match parts.last_mut() {
+            Some(last) if last.highlight == highlight => last.text.push_str(text),
+            _ => parts.push(SnippetPart {
+                text: text.to_string(),
+                highlight,
+            }),
+        }
+    };
+    if from > 0 {
+        push("…", false);
+    }
+    let mut at = from;
+    for &(start, end) in matched
+        .iter()
+        .filter(|&&(start, end)| start >= from && end <= to)
+    {
+        push(&text[at..start], false);
+        push(&text[start..end], true);
+        at = end;
+    }
+    push(&text[at..to], false);
+    if to < text.len() {
+        push("…", false);
+    }
+    parts
+}
+
+/// Finds the saved meetings whose transcripts contain every word of `query`,
+/// each with snippets of its matching turns and where they are in the
+/// recording. Only meetings whose transcripts are kept in the meeting store
+/// are searched.
+#[tauri::command]
+pub async fn search_transcripts(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<Vec<SearchResult>, String> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = state.stores.profiles.lock().active().clone();
+    let root = meeting_store::meetings_root(&profile, &data_dir);
+    let index = state.services.search.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut index = index.lock();
+        index.refresh(&root);
+        index.search(&query, &filters.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("Search failed: {}", e))
+}
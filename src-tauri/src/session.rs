@@ -0,0 +1,1384 @@
+use crate::audio::{AudioMixer, MixerOptions};
+use crate::capture::{create_audio_dump, spawn_screen_capture, stop_capture};
+use crate::integrations::{announce_update, fire_hooks};
+use crate::power::WakeLock;
+use crate::session_config::SessionChange;
+use crate::settings::HookEvent;
+use crate::state::{
+    session_event_name, validate_session_id, AppState, RecordingSession, SessionState,
+    SessionWindow, Suspension, MAIN_SESSION,
+};
+use crate::storage::{
+    save_analytics, save_chapters, save_interview, save_levels, save_transcript_turns,
+};
+use crate::stream_health::StreamHealth;
+use crate::timeline::{SessionTimeline, TimelineEventKind};
+use crate::transcript::{Transcript, TranscriptTurnPayload};
+use crate::transcription::{
+    run_transcription, StartRecordingArgs, TranscriptUpdate, TranscriptionOptions,
+};
+use crate::voice_commands::VoiceCommand;
+use crate::{
+    agenda, analytics, avoid_list, captions, chapters, consent, events, hooks, interrupted,
+    interview, levels, live_transcript, locale, mark_onboarding_step, meeting_store, mute,
+    native_mic, onboarding, output_route, power, quiet_hours, recorder, refresh_tray, screen_share,
+    session_config, settings, timeline, transcript_export, voice_commands,
+};
+use jilu_core::provider::ProviderKind;
+use jilu_core::simulator::Simulator;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+const TRANSCRIPT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+const OUTPUT_ROUTE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Fails with the reason when quiet hours are on or the avoid-list rules
+/// `event` out, so anything that starts recordings on its own can skip it.
+#[tauri::command]
+pub async fn check_recording_allowed(
+    state: State<'_, AppState>,
+    event: avoid_list::EventDetails,
+) -> Result<(), String> {
+    let settings = state.stores.settings.lock();
+    quiet_hours::check(&settings.get().quiet_hours)?;
+    avoid_list::check(&settings.get().avoid_list, &event)
+}
+
+#[tauri::command]
+pub async fn start_recording(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    args: StartRecordingArgs,
+) -> Result<(), String> {
+    let session_id = args
+        .session_id
+        .clone()
+        .unwrap_or_else(|| MAIN_SESSION.to_string());
+    validate_session_id(&session_id)?;
+    let is_main = session_id == MAIN_SESSION;
+
+    let (preset, settings) = {
+        let settings = state.stores.settings.lock();
+        let settings = settings.get();
+        let preset = match &args.preset_id {
+            Some(id) => Some(
+                settings
+                    .recording_preset(id)
+                    .cloned()
+                    .ok_or_else(|| format!("Unknown recording preset '{}'", id))?,
+            ),
+            None => None,
+        };
+        (preset, settings.clone())
+    };
+    if args.automatic.unwrap_or(false) {
+        quiet_hours::check(&settings.quiet_hours)?;
+    }
+    if let Some(event) = &args.calendar_event {
+        avoid_list::check(&settings.avoid_list, event)?;
+    }
+    // The simulator needs no API key, so setup can wait.
+    if !settings.transcription.simulator.enabled {
+        state.stores.onboarding.lock().require_ready_to_record()?;
+    }
+    let mut transcription_settings = settings.transcription.clone();
+    let locale = locale::Locale::from_tag(&settings.locale);
+    let project = args
+        .meeting_id
+        .as_deref()
+        .and_then(|id| state.stores.projects.lock().for_meeting(id).cloned());
+    if let Some(project) = &project {
+        info!("Recording into project '{}'", project.name);
+        project.apply_to(&mut transcription_settings);
+    }
+    let speakers: Vec<&str> = args
+        .speaker_profile
+        .iter()
+        .chain(args.speaker_profiles.iter().flatten())
+        .map(|profile| profile.label.as_str())
+        .chain(
+            project
+                .iter()
+                .flat_map(|project| project.speakers.iter().map(|s| s.label.as_str())),
+        )
+        .collect();
+    transcription_settings.add_speaker_vocab(&speakers);
+    // A preset's vocabulary replaces everything, the project's and speakers' included.
+    if let Some(preset) = &preset {
+        info!("Recording with preset '{}'", preset.name);
+        preset.apply_to(&mut transcription_settings);
+    }
+    if let Some(labels) = &args.channel_labels {
+        settings::validate_channel_labels(labels)?;
+    }
+    if let Some(max_delay) = args.max_delay {
+        settings::validate_max_delay(max_delay)?;
+    }
+    if let Some(threshold) = args.confidence_threshold {
+        settings::validate_confidence_threshold(threshold)?;
+    }
+    let channel_labels = args
+        .channel_labels
+        .clone()
+        .or_else(|| {
+            let channels = &transcription_settings.separate_channels;
+            channels.enabled.then(|| channels.labels.clone())
+        })
+        // Only the main session hears system audio, so only it has two sources to keep apart.
+        .filter(|_| is_main)
+        .map(|labels| {
+            labels
+                .iter()
+                .map(|label| label.trim().to_string())
+                .collect::<Vec<_>>()
+        });
+    let simulator = if transcription_settings.simulator.enabled {
+        info!("Recording with the simulated provider");
+        Some(Simulator::start(transcription_settings.simulator.config.clone()).await?)
+    } else {
+        None
+    };
+
+    // Clear any stale recording state before starting
+    if let Some(stale) = state.sessions.lock().remove(&session_id) {
+        release_wake_lock(&stale.state.wake_lock);
+    }
+
+    // The main session keeps using the app-wide state the rest of the app
+    // reads; any other session gets its own.
+    let session_state = if is_main {
+        state.sessions.main.clone()
+    } else {
+        SessionState::default()
+    };
+    {
+        let mut transcript = session_state.transcript.lock();
+        transcript.clear();
+        transcript.set_locale(locale);
+    }
+    session_state.agenda.lock().reset_coverage();
+    session_state.speaking.lock().reset();
+    *session_state.is_muted.lock() = false;
+    release_wake_lock(&session_state.wake_lock);
+
+    let meeting_dir = args.meeting_id.as_deref().and_then(|id| {
+        meeting_store::meeting_dir(&app, id)
+            .map_err(|err| warn!("Session timeline and transcript will not be saved: {}", err))
+            .ok()
+    });
+    let mut session_timeline = SessionTimeline::start(
+        meeting_dir
+            .as_ref()
+            .map(|dir| dir.join(timeline::TIMELINE_FILE)),
+    );
+    session_timeline.record(TimelineEventKind::RecordingStarted, None);
+    if let (Some(session), Some(dir)) = (&args.interrupted, &meeting_dir) {
+        let path = dir.join(transcript_export::TRANSCRIPT_FILE);
+        let mut transcript = session_state.transcript.lock();
+        if let Some(gap) = interrupted::restore_transcript(&mut transcript, &path, session) {
+            info!(gap_secs = gap, "Carrying on the interrupted recording");
+            session_timeline.record(
+                TimelineEventKind::ResumedAfterRestart,
+                Some(format!("{}s", gap.round())),
+            );
+        }
+    }
+    *session_state.timeline.lock() = session_timeline;
+
+    // System audio is captured once, for the main session; other sessions
+    // only hear the microphone the frontend pushes for them.
+    let (mixer, mic_tx) = if is_main {
+        stop_capture(state.capture.screen.clone());
+        let levels = Some(session_state.levels.clone());
+        let options = MixerOptions {
+            split: channel_labels.is_some(),
+            filter_chimes: transcription_settings.filter_notification_sounds,
+        };
+        let (mixer, inputs) = AudioMixer::spawn_with_options(levels, options)?;
+        spawn_screen_capture(
+            app.clone(),
+            state.capture.screen.clone(),
+            Some(inputs.screen),
+        )?;
+        (mixer, inputs.mic)
+    } else {
+        AudioMixer::spawn_mic_only(Some(session_state.levels.clone()))?
+    };
+
+    let mixer_stats = mixer.stats();
+    let mic_tx = Arc::new(Mutex::new(mic_tx));
+    // In native mic mode the window never captures, so failing here fails the start.
+    let native_mic = if is_main && settings.microphone.native {
+        match native_mic::NativeMic::start(
+            mic_tx.clone(),
+            mixer_stats.clone(),
+            settings.microphone.device.clone(),
+        ) {
+            Ok(mic) => Some(mic),
+            Err(err) => {
+                stop_capture(state.capture.screen.clone());
+                return Err(err);
+            }
+        }
+    } else {
+        None
+    };
+    let source_filter = mixer.filter();
+    // Like the tray's mute, spoken commands act on the main session.
+    let voice_commands = (is_main && settings.voice_commands.enabled)
+        .then(|| {
+            let app = app.clone();
+            voice_commands::VoiceCommands::start(
+                &settings.voice_commands,
+                mixer.tap_mic(),
+                move |command| handle_voice_command(&app, command),
+            )
+            .map_err(|err| warn!("Voice commands disabled: {}", err))
+            .ok()
+        })
+        .flatten();
+    let audio_recording = args
+        .record_audio
+        .clone()
+        .unwrap_or_else(|| settings.audio_recording.clone());
+    let recorder = if audio_recording.enabled {
+        let dir = match &meeting_dir {
+            Some(dir) => dir.clone(),
+            None => app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+                .join("recordings"),
+        };
+        let stem = format!("recording-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        match recorder::Recorder::start(mixer.tap_sources(), &dir, &stem, &audio_recording, is_main)
+        {
+            Ok(recorder) => {
+                info!("Saving the recording's audio to {}", dir.display());
+                Some(recorder)
+            }
+            Err(err) => {
+                warn!("Audio will not be saved: {}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let (change_tx, change_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (suspend_tx, suspend_rx) = tokio::sync::watch::channel(None);
+
+    {
+        let wake_lock = WakeLock::acquire("Meeting Transcriber is recording")
+            .map(Some)
+            .map_err(|err| {
+                warn!("Failed to acquire wake lock: {}", err);
+                err
+            })
+            .unwrap_or(None);
+        let mut guard = session_state.wake_lock.lock();
+        *guard = wake_lock;
+    }
+
+    let dump_audio = args
+        .debug_audio_dump
+        .unwrap_or(transcription_settings.debug_audio_dump);
+    let meeting_id = args.meeting_id.clone();
+    let meeting_title = args.meeting_title.clone();
+    let dump_name = meeting_id.clone().unwrap_or_else(|| session_id.clone());
+    let interview_config = args
+        .interview_mode
+        .or(preset.as_ref().and_then(|preset| preset.interview_mode))
+        .unwrap_or(false)
+        .then(|| interview::InterviewConfig {
+            local_speaker: match &channel_labels {
+                // Everything heard on the microphone is the local user.
+                Some(labels) => labels.first().cloned(),
+                None => args
+                    .speaker_profile
+                    .as_ref()
+                    .map(|profile| profile.label.trim().to_string())
+                    .filter(|label| !label.is_empty()),
+            },
+        })
+        // Q&A documents are only kept for the main session.
+        .filter(|_| is_main);
+    if is_main {
+        *state.sessions.interview.lock() = interview_config.clone();
+    }
+    let mut options = TranscriptionOptions::resolve(args, &transcription_settings);
+    if dump_audio {
+        options.audio_dump = create_audio_dump(&app, &dump_name, mixer.channels())
+            .map_err(|err| warn!("Audio dump disabled: {}", err))
+            .ok();
+    }
+    options.interview = interview_config;
+    if channel_labels.is_some() {
+        info!("Transcribing the microphone and system audio as separate channels");
+    }
+    options.channel_labels = channel_labels;
+    options.roster = project.map(|project| project.speakers).unwrap_or_default();
+    if let Some(preset) = &preset {
+        options.diarization = preset.diarization.unwrap_or(true);
+        options.max_speakers = preset.max_speakers;
+        // Goes with the preset's endpoint, which only Speechmatics uses.
+        if options.provider == ProviderKind::Speechmatics {
+            options.tls = preset.tls.clone();
+        }
+    }
+    if let Some(simulator) = simulator {
+        // The simulator speaks the Speechmatics protocol.
+        options.provider = ProviderKind::Speechmatics;
+        options.rt_url = Some(simulator.url().to_string());
+        options.auth = settings::RtAuth::ApiKey;
+        options.tls = None;
+        options.simulator = Some(simulator);
+    }
+    options.silence_watchdog = settings.silence_watchdog;
+    options.speaker_cues = settings.speaker_cues;
+    // Only the main session writes these, so extra sessions cannot clobber them.
+    if is_main {
+        options.live_transcript = live_transcript::LiveTranscriptFile::create(
+            &settings.live_transcript,
+            meeting_title.as_deref(),
+            locale,
+        )
+        .map_err(|err| warn!("Live transcript file disabled: {}", err))
+        .ok()
+        .flatten();
+        options.captions = captions::CaptionOutput::start(&settings.caption_output)
+            .await
+            .map_err(|err| warn!("Caption output disabled: {}", err))
+            .ok()
+            .flatten();
+    }
+    let transcript_path = meeting_dir.map(|dir| dir.join(transcript_export::TRANSCRIPT_FILE));
+    options.transcript_path = transcript_path.clone();
+    let mut health = StreamHealth::new(options.audio_batch_ms);
+    health.audio_dump_path = options
+        .audio_dump
+        .as_ref()
+        .map(|dump| dump.path().to_string_lossy().into_owned());
+    *session_state.stream_health.lock() = health;
+    let telemetry = state.services.telemetry.clone();
+    telemetry.lock().record_feature("recording");
+    if !is_main {
+        telemetry.lock().record_feature("multi_session");
+        info!(session = %session_id, "Starting additional recording session");
+    }
+
+    let session_window = SessionWindow {
+        window,
+        session_id: session_id.clone(),
+    };
+    let task_state = session_state.clone();
+    let tuning = options.tuning.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        if let Err(err) = run_transcription(
+            options,
+            mixer,
+            stop_rx,
+            change_rx,
+            suspend_rx,
+            session_window.clone(),
+            task_state,
+        )
+        .await
+        {
+            telemetry.lock().record_error("transcription");
+            let _ = session_window.emit(events::RECORDING_ERROR, err.clone());
+            let _ = session_window.emit(events::RECORDING_ENDED, ());
+        }
+    });
+
+    let autosave = transcript_path.clone().map(|path| {
+        tauri::async_runtime::spawn(autosave_transcript(path, session_state.transcript.clone()))
+    });
+    let route_watch = is_main.then(|| {
+        tauri::async_runtime::spawn(watch_output_route(
+            app.clone(),
+            session_state.timeline.clone(),
+        ))
+    });
+    state.sessions.lock().insert(
+        session_id.clone(),
+        RecordingSession {
+            mic_tx,
+            native_mic,
+            mixer_stats,
+            source_filter,
+            voice_commands,
+            recorder,
+            stop_tx: Some(stop_tx),
+            change_tx,
+            tuning,
+            suspend_tx,
+            task,
+            autosave,
+            route_watch,
+            transcript_path,
+            meeting_id: meeting_id.clone(),
+            meeting_title: meeting_title.clone(),
+            state: session_state,
+        },
+    );
+
+    if let (true, Some(id)) = (is_main, &meeting_id) {
+        let preset_id = preset.as_ref().map(|preset| preset.id.as_str());
+        interrupted::remember(&app, id, meeting_title.as_deref(), preset_id);
+    }
+    fire_hooks(
+        &app,
+        HookEvent::RecordingStarted,
+        hooks::HookPayload {
+            meeting_id,
+            meeting_title,
+            ..Default::default()
+        },
+    );
+    refresh_tray(&app);
+    screen_share::watch(&app);
+
+    Ok(())
+}
+
+/// Writes the transcript into the meeting folder while it grows, so it
+/// survives even if the window that would normally save it is gone.
+async fn autosave_transcript(path: std::path::PathBuf, transcript: Arc<Mutex<Transcript>>) {
+    let mut interval = tokio::time::interval(TRANSCRIPT_AUTOSAVE_INTERVAL);
+    interval.tick().await;
+    let mut saved_len = 0;
+    loop {
+        interval.tick().await;
+        let turns = {
+            let transcript = transcript.lock();
+            let len = transcript.text().len();
+            if len == saved_len {
+                continue;
+            }
+            saved_len = len;
+            transcript.turns().to_vec()
+        };
+        if let Err(err) = save_transcript_turns(&path, &turns) {
+            warn!("Transcript autosave failed: {}", err);
+        }
+    }
+}
+
+/// Reports the output device system audio plays to when the recording
+/// starts and whenever it changes, as `output-route-changed`.
+async fn watch_output_route(app: AppHandle, timeline: Arc<Mutex<SessionTimeline>>) {
+    let mut interval = tokio::time::interval(OUTPUT_ROUTE_POLL_INTERVAL);
+    let mut last: Option<output_route::OutputRoute> = None;
+    loop {
+        interval.tick().await;
+        let route = match tauri::async_runtime::spawn_blocking(output_route::current).await {
+            Ok(Ok(route)) => route,
+            Ok(Err(err)) => {
+                warn!("Not following the output device: {}", err);
+                return;
+            }
+            Err(_) => return,
+        };
+        if last.as_ref() == Some(&route) {
+            continue;
+        }
+        match &route.warning {
+            Some(warning) if !route.capturable => {
+                warn!(device = %route.device, "Output may not be captured: {}", warning)
+            }
+            _ => info!(device = %route.device, transport = ?route.transport, "Output device"),
+        }
+        timeline.lock().record(
+            TimelineEventKind::OutputRouteChanged,
+            Some(route.device.clone()),
+        );
+        let _ = app.emit(events::OUTPUT_ROUTE_CHANGED, &route);
+        last = Some(route);
+    }
+}
+
+/// Stops session `session_id`, or the main session when none is given.
+#[tauri::command]
+pub async fn stop_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let session_id = session_id.unwrap_or_else(|| MAIN_SESSION.to_string());
+    let is_main = session_id == MAIN_SESSION;
+    if is_main {
+        stop_capture(state.capture.screen.clone());
+    }
+
+    let session_opt = state.sessions.lock().remove(&session_id);
+    if let Some(mut session) = session_opt {
+        if is_main {
+            interrupted::forget(&app);
+        }
+        if let Some(stop) = session.stop_tx.take() {
+            let _ = stop.send(());
+        }
+        // Nothing said from here on should change the recording.
+        drop(session.voice_commands.take());
+        let _ = session.task.await;
+        if let Some(recorder) = session.recorder.take() {
+            match recorder.finish() {
+                Ok(paths) => {
+                    for path in paths {
+                        info!("Saved recording audio to {}", path.display());
+                    }
+                }
+                Err(err) => warn!("Failed to save recording audio: {}", err),
+            }
+        }
+        if let Some(mic) = session.native_mic.take() {
+            mic.stop();
+        }
+        if let Some(autosave) = session.autosave.take() {
+            autosave.abort();
+        }
+        if let Some(route_watch) = session.route_watch.take() {
+            route_watch.abort();
+        }
+        let session_state = &session.state;
+        release_wake_lock(&session_state.wake_lock);
+        let chimes = session.mixer_stats.snapshot().chimes;
+        if chimes > 0 {
+            info!("Heard {} notification sounds in the system audio", chimes);
+        }
+        if is_main {
+            if let Some(share) = state.services.share.lock().take() {
+                share.stop();
+                let _ = app.emit(events::SHARING_STOPPED, ());
+            }
+            if let Some(pairing) = state.services.pairing.lock().take() {
+                pairing.stop();
+                let _ = app.emit(events::PAIRING_STOPPED, ());
+            }
+        }
+        session_state
+            .timeline
+            .lock()
+            .record(TimelineEventKind::RecordingStopped, None);
+        if let Some(path) = &session.transcript_path {
+            let turns = session_state.transcript.lock().turns().to_vec();
+            if let Err(err) = save_transcript_turns(path, &turns) {
+                warn!("{}", err);
+            }
+            // What is saved alongside leaves out the same speech as the
+            // transcript, and is left out with it when consent can't be read.
+            let turns = match consent::redact(&path.with_file_name(consent::CONSENT_FILE), &turns) {
+                Ok(turns) => turns,
+                Err(err) => {
+                    warn!("{}", err);
+                    Cow::Borrowed(&[][..])
+                }
+            };
+            let speaking = session_state.speaking.lock().report();
+            if !speaking.speakers.is_empty() {
+                if let Err(err) =
+                    save_analytics(&path.with_file_name(analytics::ANALYTICS_FILE), &speaking)
+                {
+                    warn!("{}", err);
+                }
+            }
+            let levels = session_state.levels.lock().history();
+            if !levels.mic.is_empty() {
+                if let Err(err) = save_levels(&path.with_file_name(levels::LEVELS_FILE), &levels) {
+                    warn!("{}", err);
+                }
+            }
+            let chapters = chapters::detect(&turns);
+            if !chapters.is_empty() {
+                if let Err(err) =
+                    save_chapters(&path.with_file_name(chapters::CHAPTERS_FILE), &chapters)
+                {
+                    warn!("{}", err);
+                }
+            }
+            if let Some(config) = state.sessions.interview.lock().as_ref().filter(|_| is_main) {
+                let document = config.segment(&turns);
+                if let Err(err) =
+                    save_interview(&path.with_file_name(interview::INTERVIEW_FILE), &document)
+                {
+                    warn!("{}", err);
+                }
+            }
+        }
+        fire_hooks(
+            &app,
+            HookEvent::RecordingStopped,
+            hooks::HookPayload {
+                meeting_id: session.meeting_id.clone(),
+                meeting_title: session.meeting_title.clone(),
+                transcript_path: session
+                    .transcript_path
+                    .as_ref()
+                    .filter(|path| path.exists())
+                    .map(|path| path.to_string_lossy().into_owned()),
+                summary: None,
+            },
+        );
+        if !session_state.transcript.lock().text().trim().is_empty() {
+            // Getting a transcript back proves capture permissions and the whole pipeline work.
+            for step in [
+                onboarding::OnboardingStep::Permissions,
+                onboarding::OnboardingStep::TestRecording,
+            ] {
+                mark_onboarding_step(&app, &state, step);
+            }
+        }
+        if !state.sessions.is_recording()
+            && std::mem::take(&mut state.services.update.lock().deferred)
+        {
+            announce_update(&app);
+        }
+        refresh_tray(&app);
+        Ok(())
+    } else {
+        if is_main {
+            release_wake_lock(&state.sessions.main.wake_lock);
+        }
+        Err("Not recording".to_string())
+    }
+}
+
+/// The recording the app went away in the middle of, so the frontend can
+/// offer to carry on with it at launch.
+#[tauri::command]
+pub async fn get_interrupted_session(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<interrupted::InterruptedSession>, String> {
+    if state.sessions.is_recording() {
+        return Ok(None);
+    }
+    Ok(interrupted::load(&app))
+}
+
+#[tauri::command]
+pub async fn discard_interrupted_session(app: AppHandle) -> Result<(), String> {
+    interrupted::forget(&app);
+    Ok(())
+}
+
+/// Starts capture and a new provider session for the interrupted recording,
+/// continuing its meeting's transcript after a gap marker rather than
+/// starting a new meeting. `args` are those `start_recording` takes; the
+/// meeting and preset come from the interrupted recording.
+#[tauri::command]
+pub async fn resume_interrupted_session(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    args: StartRecordingArgs,
+) -> Result<interrupted::InterruptedSession, String> {
+    if state.sessions.is_recording() {
+        return Err("Already recording".to_string());
+    }
+    let session =
+        interrupted::load(&app).ok_or_else(|| "No interrupted recording to resume".to_string())?;
+    let args = StartRecordingArgs {
+        meeting_id: Some(session.meeting_id.clone()),
+        meeting_title: args.meeting_title.or_else(|| session.meeting_title.clone()),
+        preset_id: session.preset_id.clone(),
+        session_id: None,
+        interrupted: Some(session.clone()),
+        ..args
+    };
+    start_recording(app, window, state, args).await?;
+    Ok(session)
+}
+
+/// Ids of the sessions currently recording.
+#[tauri::command]
+pub async fn list_recording_sessions(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let mut ids: Vec<String> = state.sessions.lock().keys().cloned().collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// Q&A pairs for the current or last recording, which must have been started in interview mode.
+#[tauri::command]
+pub async fn get_interview_qa(
+    state: State<'_, AppState>,
+) -> Result<interview::InterviewDocument, String> {
+    let config = state
+        .sessions
+        .interview
+        .lock()
+        .clone()
+        .ok_or_else(|| "The last recording was not an interview".to_string())?;
+    Ok(config.segment(state.sessions.main.transcript.lock().turns()))
+}
+
+/// Renders the interview as `markdown` or `json`.
+#[tauri::command]
+pub async fn export_interview(
+    state: State<'_, AppState>,
+    format: String,
+) -> Result<String, String> {
+    let document = get_interview_qa(state).await?;
+    match format.as_str() {
+        "markdown" | "md" => Ok(interview::render_markdown(&document)),
+        "json" => serde_json::to_string_pretty(&document).map_err(|e| e.to_string()),
+        other => Err(format!("Unknown interview format '{}'", other)),
+    }
+}
+
+/// Carries on the current recording in another language. The provider is
+/// restarted behind the scenes and the transcript continues where it was.
+#[tauri::command]
+pub async fn switch_language(
+    state: State<'_, AppState>,
+    language: String,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let language = language.trim().to_string();
+    settings::validate_language(&language)?;
+    let sessions = state.sessions.lock();
+    let session = sessions
+        .get(session_id.as_deref().unwrap_or(MAIN_SESSION))
+        .ok_or_else(|| "Not currently recording".to_string())?;
+    session
+        .change_tx
+        .send(SessionChange::Language(language))
+        .map_err(|_| "Recording is no longer active".to_string())
+}
+
+/// Stops sending audio and closes the provider session, so nothing more is
+/// billed, while the recording itself carries on. Unlike muting, not even
+/// silence is streamed. `resume_recording` opens a new provider session and
+/// the transcript continues where it stopped.
+#[tauri::command]
+pub async fn pause_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let session_id = session_id.as_deref().unwrap_or(MAIN_SESSION);
+    {
+        let sessions = state.sessions.lock();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| "Not currently recording".to_string())?;
+        if session.suspension() == Some(Suspension::Paused) {
+            return Ok(());
+        }
+        info!(session = %session_id, "Pausing recording");
+        session.suspend_tx.send_replace(Some(Suspension::Paused));
+        save_progress(session);
+    }
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("pause_recording");
+    if session_id == MAIN_SESSION {
+        refresh_tray(&app);
+    }
+    Ok(())
+}
+
+/// Reconnects a session that was paused, by `pause_recording` or for system
+/// sleep. The outcome arrives as `recording-resumed` or
+/// `recording-resume-failed`.
+#[tauri::command]
+pub async fn resume_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let session_id = session_id.as_deref().unwrap_or(MAIN_SESSION);
+    {
+        let sessions = state.sessions.lock();
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| "Not currently recording".to_string())?;
+        // Notify even if nothing is paused, so a failed resume can be retried.
+        session
+            .suspend_tx
+            .send_modify(|suspension| *suspension = None);
+    }
+    if session_id == MAIN_SESSION {
+        refresh_tray(&app);
+    }
+    Ok(())
+}
+
+/// Saves the transcript a paused session has so far, in case it is never
+/// resumed.
+fn save_progress(session: &RecordingSession) {
+    if let Some(path) = &session.transcript_path {
+        let turns = session.state.transcript.lock().turns().to_vec();
+        if let Err(err) = save_transcript_turns(path, &turns) {
+            warn!("{}", err);
+        }
+    }
+}
+
+/// What the frontend needs to offer resuming a recording after wake.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ResumeOffer {
+    meeting_id: Option<String>,
+    meeting_title: Option<String>,
+}
+
+/// Pauses every session before the Mac sleeps, saving what it has so far,
+/// and offers to resume the ones it paused once it wakes. Sessions the user
+/// paused stay paused.
+pub fn handle_power_event(app_handle: &AppHandle, event: power::PowerEvent) {
+    let state = app_handle.state::<AppState>();
+    let sessions = state.sessions.lock();
+    match event {
+        power::PowerEvent::WillSleep => {
+            for (id, session) in sessions.iter() {
+                if session.suspension().is_some() {
+                    continue;
+                }
+                info!(session = %id, "Suspending recording for system sleep");
+                session.suspend_tx.send_replace(Some(Suspension::Sleep));
+                save_progress(session);
+            }
+        }
+        power::PowerEvent::DidWake => {
+            for (id, session) in sessions
+                .iter()
+                .filter(|(_, session)| session.suspension() == Some(Suspension::Sleep))
+            {
+                let offer = ResumeOffer {
+                    meeting_id: session.meeting_id.clone(),
+                    meeting_title: session.meeting_title.clone(),
+                };
+                let _ = app_handle.emit(&session_event_name(id, events::RESUME_OFFERED), offer);
+            }
+        }
+    }
+}
+
+/// Replaces the agenda with items parsed from `text`, e.g. calendar event notes.
+#[tauri::command]
+pub async fn set_agenda(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<Vec<agenda::AgendaItem>, String> {
+    let items = {
+        let mut agenda = state.sessions.main.agenda.lock();
+        *agenda = agenda::Agenda::parse(&text);
+        // Anything already said in this recording counts towards the new items.
+        let transcript = state.sessions.main.transcript.lock();
+        for turn in transcript.turns() {
+            agenda.observe(&turn.text, turn.start);
+        }
+        agenda.items().to_vec()
+    };
+    let _ = app.emit(events::AGENDA_CHANGED, &items);
+    Ok(items)
+}
+
+#[tauri::command]
+pub async fn get_agenda(state: State<'_, AppState>) -> Result<Vec<agenda::AgendaItem>, String> {
+    Ok(state.sessions.main.agenda.lock().items().to_vec())
+}
+
+/// Lets the host tick an item off, or reopen one the matcher got wrong.
+#[tauri::command]
+pub async fn set_agenda_item_covered(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+    covered: bool,
+) -> Result<agenda::AgendaItem, String> {
+    let (item, items) = {
+        let mut agenda = state.sessions.main.agenda.lock();
+        let item = agenda.set_covered(id, covered)?;
+        (item, agenda.items().to_vec())
+    };
+    let _ = app.emit(events::AGENDA_CHANGED, &items);
+    Ok(item)
+}
+
+/// Adds a typed note to session `session_id`'s transcript at the current
+/// point of the recording. It is saved and exported with the spoken turns,
+/// but guests, captions and subtitles never see it.
+#[tauri::command]
+pub async fn add_manual_note(
+    window: Window,
+    state: State<'_, AppState>,
+    text: String,
+    session_id: Option<String>,
+) -> Result<TranscriptTurnPayload, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err("Note is empty".to_string());
+    }
+    let session_id = session_id.unwrap_or_else(|| MAIN_SESSION.to_string());
+    if state.sessions.lock().get(&session_id).is_none() {
+        return Err("Not currently recording".to_string());
+    }
+    let session = state.sessions.state(Some(&session_id))?;
+    let (offset, at) = {
+        let health = session.stream_health.lock();
+        let offset = health.elapsed();
+        (offset, offset.and_then(|secs| health.wall_clock(secs)))
+    };
+    let (note, update) = {
+        let mut transcript = session.transcript.lock();
+        let index = transcript.add_note(text, offset, at);
+        (
+            transcript.turns()[index].clone(),
+            TranscriptUpdate::from_turn(&state, &transcript, index),
+        )
+    };
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("manual_notes");
+    let _ = SessionWindow { window, session_id }.emit(events::TRANSCRIPT_UPDATE, update);
+    Ok(note)
+}
+
+/// Calls speaker `old_label` `new_label` from now on, e.g. `S1` once someone
+/// recognises the voice: in the turns so far and in everything the provider
+/// labels `old_label` later. The whole transcript is sent again as a
+/// `transcript-update` from turn 0. Returns how many turns were renamed.
+#[tauri::command]
+pub async fn rename_speaker(
+    window: Window,
+    state: State<'_, AppState>,
+    old_label: String,
+    new_label: String,
+    session_id: Option<String>,
+) -> Result<usize, String> {
+    let (old_label, new_label) = (old_label.trim(), new_label.trim());
+    if old_label.is_empty() || new_label.is_empty() {
+        return Err("Speaker names cannot be empty".to_string());
+    }
+    let session_id = session_id.unwrap_or_else(|| MAIN_SESSION.to_string());
+    if state.sessions.lock().get(&session_id).is_none() {
+        return Err("Not currently recording".to_string());
+    }
+    if old_label == new_label {
+        return Ok(0);
+    }
+    let session = state.sessions.state(Some(&session_id))?;
+    let (renamed, update) = {
+        let mut transcript = session.transcript.lock();
+        let renamed = transcript.rename_speaker(old_label, new_label);
+        (renamed, TranscriptUpdate::from_turn(&state, &transcript, 0))
+    };
+    session.speaking.lock().rename(old_label, new_label);
+    session.recent_audio.lock().rename(old_label, new_label);
+    info!(renamed, "Renamed a speaker");
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("speaker_rename");
+    let _ = SessionWindow { window, session_id }.emit(events::TRANSCRIPT_UPDATE, update);
+    Ok(renamed)
+}
+
+#[tauri::command]
+pub async fn get_stream_health(state: State<'_, AppState>) -> Result<StreamHealth, String> {
+    Ok(state.sessions.main.stream_health.lock().clone())
+}
+
+#[tauri::command]
+pub async fn mute_recording(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let mut is_muted = state.sessions.main.is_muted.lock();
+    let was_muted = std::mem::replace(&mut *is_muted, true);
+    drop(is_muted);
+
+    if !was_muted {
+        record_mute_change(&state, true);
+        refresh_tray(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unmute_recording(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let mut is_muted = state.sessions.main.is_muted.lock();
+    let was_muted = std::mem::replace(&mut *is_muted, false);
+    drop(is_muted);
+
+    if was_muted {
+        record_mute_change(&state, false);
+        refresh_tray(&app);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn toggle_mute(
+    state: State<'_, AppState>,
+    window: Window,
+    session_id: Option<String>,
+) -> Result<bool, String> {
+    let session_id = session_id.unwrap_or_else(|| MAIN_SESSION.to_string());
+    let new_state = toggle_session_mute(&state, &session_id)?;
+    if session_id == MAIN_SESSION {
+        refresh_tray(window.app_handle());
+    }
+    let _ = SessionWindow { window, session_id }.emit(events::MUTE_STATUS_CHANGED, new_state);
+
+    Ok(new_state)
+}
+
+pub fn toggle_session_mute(state: &AppState, session_id: &str) -> Result<bool, String> {
+    let session = state.sessions.state(Some(session_id))?;
+    let mut is_muted = session.is_muted.lock();
+    *is_muted = !*is_muted;
+    let new_state = *is_muted;
+    drop(is_muted);
+
+    record_session_mute_change(state, session_id, &session, new_state);
+    Ok(new_state)
+}
+
+/// Acts on a command heard by the main session's voice command listener.
+fn handle_voice_command(app: &AppHandle, command: VoiceCommand) {
+    info!(?command, "Voice command");
+    let state = app.state::<AppState>();
+    match command {
+        VoiceCommand::Bookmark => {
+            state
+                .sessions
+                .main
+                .timeline
+                .lock()
+                .record(TimelineEventKind::Bookmarked, Some("voice".to_string()));
+        }
+        VoiceCommand::Mute | VoiceCommand::Unmute => {
+            set_main_muted(app, command == VoiceCommand::Mute);
+        }
+    }
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("voice_commands");
+    let _ = app.emit(events::VOICE_COMMAND, command);
+}
+
+/// Mutes or unmutes the main session on behalf of something other than the
+/// frontend, which then hears about it through `mute-status-changed`.
+pub fn set_main_muted(app: &AppHandle, muted: bool) {
+    let state = app.state::<AppState>();
+    let was_muted = std::mem::replace(&mut *state.sessions.main.is_muted.lock(), muted);
+    if was_muted != muted {
+        record_mute_change(&state, muted);
+        let _ = app.emit(events::MUTE_STATUS_CHANGED, muted);
+        refresh_tray(app);
+    }
+}
+
+fn record_mute_change(state: &AppState, muted: bool) {
+    record_session_mute_change(state, MAIN_SESSION, &state.sessions.main.clone(), muted);
+}
+
+fn record_session_mute_change(
+    state: &AppState,
+    session_id: &str,
+    session: &SessionState,
+    muted: bool,
+) {
+    let mode = *session.mute_mode.lock();
+    match state.sessions.lock().get(session_id) {
+        Some(recording) => apply_mute(recording, muted, mode),
+        None => return,
+    }
+    let (kind, detail) = if muted {
+        (TimelineEventKind::Muted, Some(mode.label().to_string()))
+    } else {
+        (TimelineEventKind::Unmuted, None)
+    };
+    session.timeline.lock().record(kind, detail);
+}
+
+/// Points the session's mixer at the sources `mode` keeps while `muted`.
+fn apply_mute(recording: &RecordingSession, muted: bool, mode: mute::MuteMode) {
+    let (exclude_mic, exclude_system) = if muted {
+        mode.excluded_sources()
+    } else {
+        (false, false)
+    };
+    recording.source_filter.set(exclude_mic, exclude_system);
+}
+
+/// Chooses what muting does. Takes effect at once if the session is muted.
+#[tauri::command]
+pub async fn set_mute_mode(
+    state: State<'_, AppState>,
+    mode: mute::MuteMode,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let session = state.sessions.state(session_id.as_deref())?;
+    change_mute_mode(&state, &session, session_id.as_deref(), mode);
+    Ok(())
+}
+
+fn change_mute_mode(
+    state: &AppState,
+    session: &SessionState,
+    session_id: Option<&str>,
+    mode: mute::MuteMode,
+) {
+    let previous = std::mem::replace(&mut *session.mute_mode.lock(), mode);
+    if previous == mode {
+        return;
+    }
+    let muted = *session.is_muted.lock();
+    if let Some(recording) = state
+        .sessions
+        .lock()
+        .get(session_id.unwrap_or(MAIN_SESSION))
+    {
+        apply_mute(recording, muted, mode);
+        if muted {
+            session
+                .timeline
+                .lock()
+                .record(TimelineEventKind::Muted, Some(mode.label().to_string()));
+        }
+    }
+}
+
+/// Changes settings of a running recording without stopping it, returning
+/// the names of those applied. A new vocabulary needs a new provider
+/// session; `vocab-updated` or `vocab-update-failed` says how that went.
+#[tauri::command]
+pub async fn update_session_config(
+    state: State<'_, AppState>,
+    update: session_config::SessionConfigUpdate,
+    session_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    update.validate()?;
+    let session = state.sessions.state(session_id.as_deref())?;
+    let mut applied = {
+        let sessions = state.sessions.lock();
+        let recording = sessions
+            .get(session_id.as_deref().unwrap_or(MAIN_SESSION))
+            .ok_or_else(|| "Not currently recording".to_string())?;
+        let mut applied = recording.tuning.lock().apply(&update);
+        if let Some(vocab) = &update.additional_vocab {
+            recording
+                .change_tx
+                .send(SessionChange::Vocab(vocab.clone()))
+                .map_err(|_| "Recording is no longer active".to_string())?;
+            applied.push("additionalVocab");
+        }
+        applied
+    };
+    if let Some(mode) = update.mute_mode {
+        change_mute_mode(&state, &session, session_id.as_deref(), mode);
+        applied.push("muteMode");
+    }
+    if !applied.is_empty() {
+        session
+            .timeline
+            .lock()
+            .record(TimelineEventKind::ConfigChanged, Some(applied.join(", ")));
+    }
+    Ok(applied.into_iter().map(str::to_string).collect())
+}
+
+#[tauri::command]
+pub async fn get_mute_mode(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<mute::MuteMode, String> {
+    let session = state.sessions.state(session_id.as_deref())?;
+    let mode = *session.mute_mode.lock();
+    Ok(mode)
+}
+
+#[tauri::command]
+pub async fn get_mute_status(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(*state.sessions.main.is_muted.lock())
+}
+
+fn release_wake_lock(wake_lock_state: &Arc<Mutex<Option<WakeLock>>>) {
+    let mut guard = wake_lock_state.lock();
+    if let Some(mut lock) = guard.take() {
+        lock.release();
+    }
+}
+
+pub struct WakeLockGuard {
+    pub wake_lock_state: Arc<Mutex<Option<WakeLock>>>,
+}
+
+impl Drop for WakeLockGuard {
+    fn drop(&mut self) {
+        release_wake_lock(&self.wake_lock_state);
+    }
+}
+
+/// Keeps the main session going after its window is closed. The hidden
+/// webview may be suspended, so the backend takes over the microphone.
+pub fn continue_recording_headless(app_handle: &AppHandle) {
+    info!("Main window closed while recording; continuing in the background");
+    if let Err(err) = move_mic_to_backend(app_handle) {
+        warn!("Microphone stays with the hidden window: {}", err);
+    }
+    refresh_tray(app_handle);
+}
+
+/// Starts native capture for the main session, if it is recording and the
+/// window still feeds its mic. The window stops on `mic-capture-moved`.
+pub fn move_mic_to_backend(app_handle: &AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let device = state.stores.settings.lock().get().microphone.device.clone();
+    let mut sessions = state.sessions.lock();
+    let Some(session) = sessions.get_mut(MAIN_SESSION) else {
+        return Ok(());
+    };
+    if session.native_mic.is_none() {
+        let mic = native_mic::NativeMic::start(
+            session.mic_tx.clone(),
+            session.mixer_stats.clone(),
+            device,
+        )?;
+        session.native_mic = Some(mic);
+        let _ = app_handle.emit(events::MIC_CAPTURE_MOVED, ());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_config::LiveTuning;
+    use crate::state::Suspension;
+
+    fn recording(state: SessionState) -> RecordingSession {
+        RecordingSession {
+            mic_tx: Arc::new(Mutex::new(rtrb::RingBuffer::new(16).0)),
+            native_mic: None,
+            mixer_stats: Default::default(),
+            source_filter: Default::default(),
+            voice_commands: None,
+            recorder: None,
+            stop_tx: None,
+            change_tx: tokio::sync::mpsc::unbounded_channel().0,
+            tuning: Arc::new(Mutex::new(LiveTuning::new(&[], Duration::ZERO))),
+            suspend_tx: tokio::sync::watch::channel(None).0,
+            task: tauri::async_runtime::spawn(async {}),
+            autosave: None,
+            route_watch: None,
+            transcript_path: None,
+            meeting_id: None,
+            meeting_title: None,
+            state,
+        }
+    }
+
+    fn logged(session: &SessionState) -> Vec<(TimelineEventKind, Option<String>)> {
+        session
+            .timeline
+            .lock()
+            .events()
+            .iter()
+            .map(|event| (event.kind, event.detail.clone()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sessions_are_found_by_id() {
+        let state = AppState::default();
+        assert!(!state.sessions.is_recording());
+        let main = state.sessions.state(None).unwrap();
+        let named = state.sessions.state(Some(MAIN_SESSION)).unwrap();
+        assert!(Arc::ptr_eq(&main.transcript, &named.transcript));
+        assert!(state.sessions.state(Some("room")).is_err());
+
+        let room = SessionState::default();
+        state
+            .sessions
+            .lock()
+            .insert("room".to_string(), recording(room.clone()));
+        assert!(state.sessions.is_recording());
+        let found = state.sessions.state(Some("room")).unwrap();
+        assert!(Arc::ptr_eq(&found.transcript, &room.transcript));
+        assert!(!Arc::ptr_eq(&found.transcript, &main.transcript));
+    }
+
+    #[tokio::test]
+    async fn muting_a_recording_is_logged_with_its_mode() {
+        let state = AppState::default();
+        let main = state.sessions.main.clone();
+        state
+            .sessions
+            .lock()
+            .insert(MAIN_SESSION.to_string(), recording(main.clone()));
+
+        assert_eq!(toggle_session_mute(&state, MAIN_SESSION), Ok(true));
+        assert!(*main.is_muted.lock());
+        assert_eq!(toggle_session_mute(&state, MAIN_SESSION), Ok(false));
+        assert_eq!(
+            logged(&main),
+            [
+                (TimelineEventKind::Muted, Some("mic".to_string())),
+                (TimelineEventKind::Unmuted, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn muting_without_a_recording_only_flips_the_flag() {
+        let state = AppState::default();
+        assert_eq!(toggle_session_mute(&state, MAIN_SESSION), Ok(true));
+        assert!(logged(&state.sessions.main).is_empty());
+        assert!(toggle_session_mute(&state, "room").is_err());
+    }
+
+    #[tokio::test]
+    async fn changing_mute_mode_while_muted_is_logged() {
+        let state = AppState::default();
+        let main = state.sessions.main.clone();
+        state
+            .sessions
+            .lock()
+            .insert(MAIN_SESSION.to_string(), recording(main.clone()));
+
+        // Unmuted, the new mode only applies to the next mute.
+        change_mute_mode(&state, &main, None, mute::MuteMode::System);
+        assert!(logged(&main).is_empty());
+
+        toggle_session_mute(&state, MAIN_SESSION).unwrap();
+        change_mute_mode(&state, &main, None, mute::MuteMode::Transcription);
+        change_mute_mode(&state, &main, None, mute::MuteMode::Transcription);
+        assert_eq!(
+            logged(&main),
+            [
+                (TimelineEventKind::Muted, Some("system".to_string())),
+                (TimelineEventKind::Muted, Some("transcription".to_string())),
+            ]
+        );
+        assert_eq!(*main.mute_mode.lock(), mute::MuteMode::Transcription);
+    }
+
+    #[tokio::test]
+    async fn suspension_follows_pause_and_resume() {
+        let session = recording(SessionState::default());
+        assert_eq!(session.suspension(), None);
+        session.suspend_tx.send_replace(Some(Suspension::Paused));
+        assert_eq!(session.suspension(), Some(Suspension::Paused));
+        session.suspend_tx.send_replace(None);
+        assert_eq!(session.suspension(), None);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::mute::MuteMode;
+use crate::settings::{self, AdditionalVocabularyEntry};
+use jilu_core::provider::SessionConfig;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Settings of a running recording that `update_session_config` can change.
+/// Fields left out stay as they are.
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionConfigUpdate {
+    /// Replaces the custom vocabulary. The provider only takes it at the start
+    /// of a session, so a new one takes over, as for a language switch.
+    pub additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
+    /// Replaces the words that mark the timeline when said. Without it, a new
+    /// vocabulary becomes the watch list, as at the start of a recording.
+    pub keywords: Option<Vec<String>>,
+    pub mute_mode: Option<MuteMode>,
+    pub partial_min_interval_ms: Option<u64>,
+}
+
+impl SessionConfigUpdate {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(vocab) = &self.additional_vocab {
+            settings::validate_vocab(vocab)?;
+        }
+        if self
+            .keywords
+            .as_ref()
+            .is_some_and(|keywords| keywords.len() > settings::MAX_VOCAB_ENTRIES)
+        {
+            return Err(format!(
+                "Keyword watches are limited to {} entries",
+                settings::MAX_VOCAB_ENTRIES
+            ));
+        }
+        if self
+            .partial_min_interval_ms
+            .is_some_and(|ms| ms > settings::MAX_PARTIAL_MIN_INTERVAL_MS)
+        {
+            return Err(format!(
+                "Partial interval must be at most {} ms",
+                settings::MAX_PARTIAL_MIN_INTERVAL_MS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What the result reader checks as it goes, so it can change mid-session.
+#[derive(Debug, Clone)]
+pub struct LiveTuning {
+    pub keywords: Arc<[String]>,
+    pub partial_min_interval: Duration,
+}
+
+impl LiveTuning {
+    pub fn new(vocab: &[AdditionalVocabularyEntry], partial_min_interval: Duration) -> Self {
+        Self {
+            keywords: keywords(vocab.iter().map(|entry| entry.content.as_str())),
+            partial_min_interval,
+        }
+    }
+
+    /// Takes the parts of `update` that apply here, returning their names.
+    pub fn apply(&mut self, update: &SessionConfigUpdate) -> Vec<&'static str> {
+        let mut applied = Vec::new();
+        if let Some(list) = &update.keywords {
+            self.keywords = keywords(list.iter().map(String::as_str));
+            applied.push("keywords");
+        } else if let Some(vocab) = &update.additional_vocab {
+            self.keywords = keywords(vocab.iter().map(|entry| entry.content.as_str()));
+            applied.push("keywords");
+        }
+        if let Some(ms) = update.partial_min_interval_ms {
+            self.partial_min_interval = Duration::from_millis(ms);
+            applied.push("partialMinIntervalMs");
+        }
+        applied
+    }
+}
+
+fn keywords<'a>(words: impl Iterator<Item = &'a str>) -> Arc<[String]> {
+    words
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Changes that need a new provider session. The recording carries on in it
+/// and the old one finishes delivering its last results.
+#[derive(Debug, Clone)]
+pub enum SessionChange {
+    Language(String),
+    Vocab(Vec<AdditionalVocabularyEntry>),
+}
+
+impl SessionChange {
+    pub fn apply(&self, config: &mut SessionConfig) {
+        match self {
+            Self::Language(language) => config.language = language.clone(),
+            Self::Vocab(vocab) => config.additional_vocab = vocab.clone(),
+        }
+    }
+}
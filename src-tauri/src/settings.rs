@@ -0,0 +1,1227 @@
+use crate::avoid_list::{self, AvoidRule};
+use crate::locale;
+use crate::normalize::ExportNormalizationSettings;
+use crate::quiet_hours::{self, QuietWindow};
+pub use jilu_core::formatting::FormattingSettings;
+pub use jilu_core::provider::{ProviderKind, WebSocketSettings};
+pub use jilu_core::simulator::SimulatorConfig;
+use jilu_core::speechmatics::MAX_DELAY_RANGE;
+pub use jilu_core::speechmatics::{AdditionalVocabularyEntry, OperatingPoint, RtAuth};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+pub const CURRENT_VERSION: u32 = 1;
+
+pub const DEFAULT_PARTIAL_MIN_INTERVAL_MS: u64 = 150;
+pub const DEFAULT_AUDIO_BATCH_MS: u64 = 100;
+const DEFAULT_MAX_DELAY_SECS: f32 = 1.5;
+const DEFAULT_STOP_DRAIN_TIMEOUT_MS: u64 = 2_500;
+const DEFAULT_NEW_MEETING_SHORTCUT: &str = "CommandOrControl+Shift+M";
+const DEFAULT_LOG_LEVEL: &str = "info";
+pub const DEFAULT_LANGUAGE: &str = "en";
+pub const MAX_VOCAB_ENTRIES: usize = 1000;
+pub const MAX_PARTIAL_MIN_INTERVAL_MS: u64 = 5_000;
+const MAX_AUDIO_BATCH_MS: u64 = 1_000;
+const MAX_CHANNEL_LABEL_LEN: usize = 40;
+const MAX_STOP_DRAIN_TIMEOUT_MS: u64 = 30_000;
+const MAX_MONTHLY_ALLOWANCE_HOURS: f64 = 10_000.0;
+const DEFAULT_CURRENCY: &str = "USD";
+const MAX_HOURLY_RATE: f64 = 10_000.0;
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 30;
+const MAX_HOOK_TIMEOUT_SECS: u64 = 600;
+const DEFAULT_SILENCE_TIMEOUT_SECS: u64 = 10 * 60;
+const MIN_SILENCE_TIMEOUT_SECS: u64 = 30;
+const MAX_SILENCE_TIMEOUT_SECS: u64 = 4 * 60 * 60;
+const MAX_RECORDING_PRESETS: usize = 50;
+const MAX_CUE_PEOPLE: usize = 20;
+const DEFAULT_OSC_ADDRESS: &str = "/jilu/caption";
+const DEFAULT_WAKE_WORD: &str = "Jilu";
+const DEFAULT_SHARE_PLACEHOLDER: &str = "Transcript hidden while your screen is shared";
+const MAX_WAKE_WORDS: usize = 3;
+/// Speechmatics accepts speaker limits in this range.
+const MAX_SPEAKERS_RANGE: std::ops::RangeInclusive<u32> = 2..=100;
+
+/// Everything the backend persists about how the app should behave.
+///
+/// Secrets (API keys) are not stored here; they stay in the secure store.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub version: u32,
+    pub transcription: TranscriptionSettings,
+    pub shortcuts: ShortcutSettings,
+    pub log_level: String,
+    /// UI language, which also decides how the backend renders transcript text.
+    pub locale: String,
+    pub automation: AutomationSettings,
+    pub updates: UpdateSettings,
+    pub silence_watchdog: SilenceWatchdogSettings,
+    pub speaker_cues: SpeakerCueSettings,
+    pub live_transcript: LiveTranscriptSettings,
+    pub caption_output: CaptionOutputSettings,
+    pub voice_commands: VoiceCommandSettings,
+    pub microphone: MicrophoneSettings,
+    pub audio_recording: AudioRecordingSettings,
+    pub recording_presets: Vec<RecordingPreset>,
+    /// Meetings that are never recorded, checked whenever a recording is
+    /// started for a calendar event.
+    pub avoid_list: Vec<AvoidRule>,
+    pub export_normalization: ExportNormalizationSettings,
+    pub recording_imports: RecordingImportSettings,
+    pub screen_share_privacy: ScreenSharePrivacySettings,
+    /// When automation may not start recordings and reminders stay quiet.
+    pub quiet_hours: Vec<QuietWindow>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_VERSION,
+            transcription: TranscriptionSettings::default(),
+            shortcuts: ShortcutSettings::default(),
+            log_level: DEFAULT_LOG_LEVEL.to_string(),
+            locale: locale::DEFAULT_LOCALE.to_string(),
+            automation: AutomationSettings::default(),
+            updates: UpdateSettings::default(),
+            silence_watchdog: SilenceWatchdogSettings::default(),
+            speaker_cues: SpeakerCueSettings::default(),
+            live_transcript: LiveTranscriptSettings::default(),
+            caption_output: CaptionOutputSettings::default(),
+            voice_commands: VoiceCommandSettings::default(),
+            microphone: MicrophoneSettings::default(),
+            audio_recording: AudioRecordingSettings::default(),
+            recording_presets: Vec::new(),
+            avoid_list: Vec::new(),
+            export_normalization: ExportNormalizationSettings::default(),
+            recording_imports: RecordingImportSettings::default(),
+            screen_share_privacy: ScreenSharePrivacySettings::default(),
+            quiet_hours: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TranscriptionSettings {
+    /// Service recordings stream to.
+    pub provider: ProviderKind,
+    /// Speechmatics realtime endpoint; `None` uses the built-in default.
+    pub rt_url: Option<String>,
+    /// Deepgram streaming endpoint, e.g. a self-hosted one; `None` uses Deepgram's.
+    pub deepgram_url: Option<String>,
+    /// How recordings authenticate; `apiKey` skips `mp.speechmatics.com`
+    /// for networks that block it.
+    pub auth: RtAuth,
+    /// Speechmatics language code, e.g. `en`, `de` or a bilingual pack like `cmn_en`.
+    pub language: String,
+    /// Optional language pack domain, e.g. `bilingual-en` for Spanish/English meetings.
+    pub domain: Option<String>,
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    /// Vocabulary that only goes out when its speaker is attached to the recording.
+    pub speaker_vocab: Vec<SpeakerVocabulary>,
+    /// `standard` transcribes for less than the default `enhanced`.
+    pub operating_point: OperatingPoint,
+    /// Seconds Speechmatics may hold a word back to get it right; lower
+    /// makes captions snappier and slightly less accurate.
+    pub max_delay: f32,
+    /// Show words as they are heard, before they are final.
+    pub enable_partials: bool,
+    /// Words recognised with less confidence than this (0 to 1) are
+    /// highlighted for review; `None` highlights none.
+    pub confidence_threshold: Option<f64>,
+    pub partial_min_interval_ms: u64,
+    pub audio_batch_ms: u64,
+    /// Longest a stop waits for the provider to confirm it has the last of
+    /// the audio before ending the stream; stops sooner once it has.
+    pub stop_drain_timeout_ms: u64,
+    pub debug_audio_dump: bool,
+    /// Message sizes and buffering on the realtime connection.
+    pub websocket: WebSocketSettings,
+    pub formatting: FormattingSettings,
+    /// Hours included in the Speechmatics plan each month, for showing what is left.
+    pub monthly_allowance_hours: Option<f64>,
+    pub costs: CostSettings,
+    pub simulator: SimulatorSettings,
+    pub separate_channels: SeparateChannelSettings,
+    /// Silence notification chimes in the system audio before they reach the
+    /// transcript, at the cost of the transcript running about a second later.
+    pub filter_notification_sounds: bool,
+    /// Send audio in larger batches on battery, in Low Power Mode or when the
+    /// machine runs hot.
+    pub power_aware_batching: bool,
+}
+
+/// Terms a speaker tends to use, such as a client's product names. A speaker
+/// is attached through the recording's enrolled profile or the project roster.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeakerVocabulary {
+    /// Matched against speaker labels, ignoring case.
+    pub speaker: String,
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+}
+
+/// Sends the microphone and system audio to Speechmatics as two channels, so
+/// turns are attributed by where they were heard instead of by voice.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SeparateChannelSettings {
+    pub enabled: bool,
+    /// Speaker labels for the microphone and system audio, in that order.
+    pub labels: Vec<String>,
+}
+
+impl Default for SeparateChannelSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            labels: vec!["Me".to_string(), "Others".to_string()],
+        }
+    }
+}
+
+/// Replays a scripted meeting instead of calling Speechmatics, so the app can
+/// be worked on and shown without an API key or spending credit.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulatorSettings {
+    pub enabled: bool,
+    pub config: SimulatorConfig,
+}
+
+/// What streamed audio costs, for estimating each meeting's share.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CostSettings {
+    pub currency: String,
+    /// Price per hour of audio, keyed by provider: `speechmatics`,
+    /// `deepgram`, or the host of a self-hosted realtime endpoint.
+    pub hourly_rates: BTreeMap<String, f64>,
+}
+
+impl Default for CostSettings {
+    fn default() -> Self {
+        Self {
+            currency: DEFAULT_CURRENCY.to_string(),
+            hourly_rates: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for TranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            provider: ProviderKind::default(),
+            rt_url: None,
+            deepgram_url: None,
+            auth: RtAuth::default(),
+            language: DEFAULT_LANGUAGE.to_string(),
+            domain: None,
+            additional_vocab: Vec::new(),
+            speaker_vocab: Vec::new(),
+            operating_point: OperatingPoint::default(),
+            max_delay: DEFAULT_MAX_DELAY_SECS,
+            enable_partials: true,
+            confidence_threshold: None,
+            partial_min_interval_ms: DEFAULT_PARTIAL_MIN_INTERVAL_MS,
+            audio_batch_ms: DEFAULT_AUDIO_BATCH_MS,
+            stop_drain_timeout_ms: DEFAULT_STOP_DRAIN_TIMEOUT_MS,
+            debug_audio_dump: false,
+            websocket: WebSocketSettings::default(),
+            formatting: FormattingSettings::default(),
+            monthly_allowance_hours: None,
+            costs: CostSettings::default(),
+            simulator: SimulatorSettings::default(),
+            separate_channels: SeparateChannelSettings::default(),
+            filter_notification_sounds: false,
+            power_aware_batching: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShortcutSettings {
+    pub new_meeting: Option<String>,
+    pub mute: Option<String>,
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        Self {
+            new_meeting: Some(DEFAULT_NEW_MEETING_SHORTCUT.to_string()),
+            mute: None,
+        }
+    }
+}
+
+/// Moments in a meeting's life that can trigger user scripts.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HookEvent {
+    RecordingStarted,
+    RecordingStopped,
+    SummaryReady,
+}
+
+/// A program to run for an event. It is executed directly, not through a shell.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HookConfig {
+    pub event: HookEvent,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AutomationSettings {
+    pub hooks: Vec<HookConfig>,
+    /// Hooks still running after this long are killed.
+    pub timeout_secs: u64,
+}
+
+impl Default for AutomationSettings {
+    fn default() -> Self {
+        Self {
+            hooks: Vec::new(),
+            timeout_secs: DEFAULT_HOOK_TIMEOUT_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UpdateSettings {
+    /// Check for new releases in the background. Manual checks always work.
+    pub auto_check: bool,
+}
+
+impl Default for UpdateSettings {
+    fn default() -> Self {
+        Self { auto_check: true }
+    }
+}
+
+/// What to do when a recording has heard nothing from either source for a while.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SilenceWatchdogSettings {
+    pub enabled: bool,
+    /// Seconds of silence before warning that the meeting may be over.
+    pub timeout_secs: u64,
+    /// Stop the recording if the silence goes on after the warning.
+    pub auto_stop: bool,
+}
+
+impl Default for SilenceWatchdogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            timeout_secs: DEFAULT_SILENCE_TIMEOUT_SECS,
+            auto_stop: false,
+        }
+    }
+}
+
+/// Sound or haptic cues while recording, so someone who cannot watch the
+/// transcript can still tell who has the floor.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeakerCueSettings {
+    pub enabled: bool,
+    /// Cue whenever a different speaker starts; otherwise only for `people`.
+    pub every_change: bool,
+    /// Speaker labels, such as enrolled names, with a distinct cue of their own.
+    pub people: Vec<String>,
+    pub sound: bool,
+    /// Trackpad feedback, on Macs with a Force Touch trackpad.
+    pub haptic: bool,
+}
+
+impl Default for SpeakerCueSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            every_change: true,
+            people: Vec::new(),
+            sound: true,
+            haptic: false,
+        }
+    }
+}
+
+/// A file the main recording's finished turns are appended to as they
+/// arrive, for `tail -F` or other tools following along.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiveTranscriptSettings {
+    /// Absolute path; `None` turns the file off. It is emptied when a recording starts.
+    pub path: Option<String>,
+    pub format: LiveTranscriptFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LiveTranscriptFormat {
+    /// `[00:01:23] [Speaker 1]: text`, one turn per line.
+    #[default]
+    Text,
+    /// One transcript turn object per line.
+    Jsonl,
+}
+
+/// Offers meetings recorded by Zoom or Teams on this machine for import.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingImportSettings {
+    pub enabled: bool,
+    /// Zoom's local recordings in `Documents/Zoom`.
+    pub zoom: bool,
+    /// Teams recordings saved to `Downloads`.
+    pub teams: bool,
+    /// Other folders whose audio and video files are offered too; absolute paths.
+    pub folders: Vec<String>,
+}
+
+impl Default for RecordingImportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            zoom: true,
+            teams: true,
+            folders: Vec::new(),
+        }
+    }
+}
+
+/// Keeps live transcript text out of the window while the screen is shared.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenSharePrivacySettings {
+    pub hide_transcript: bool,
+    /// Shown where the transcript would be.
+    pub placeholder: String,
+}
+
+impl Default for ScreenSharePrivacySettings {
+    fn default() -> Self {
+        Self {
+            hide_transcript: false,
+            placeholder: DEFAULT_SHARE_PLACEHOLDER.to_string(),
+        }
+    }
+}
+
+/// Live captions of the main recording for a room screen or stream.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CaptionOutputSettings {
+    /// `host:port` that gets each caption as an OSC message over UDP.
+    pub osc_target: Option<String>,
+    /// OSC address the messages are sent to. Arguments are the speaker and the text.
+    pub osc_address: String,
+    /// Serves a caption page for OBS browser sources, plus `/caption.txt`
+    /// and `/caption.json`, on this port.
+    pub http_port: Option<u16>,
+    /// Lets other machines on the network load the captions, not just this Mac.
+    pub http_lan: bool,
+}
+
+impl Default for CaptionOutputSettings {
+    fn default() -> Self {
+        Self {
+            osc_target: None,
+            osc_address: DEFAULT_OSC_ADDRESS.to_string(),
+            http_port: None,
+            http_lan: false,
+        }
+    }
+}
+
+impl CaptionOutputSettings {
+    fn validate(&self) -> Result<(), String> {
+        if let Some(target) = &self.osc_target {
+            let valid = target
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+            if !valid {
+                return Err(format!("OSC target '{}' must look like host:port", target));
+            }
+        }
+        if !self.osc_address.starts_with('/')
+            || self
+                .osc_address
+                .contains(|c: char| c.is_whitespace() || "#*,?[]{}".contains(c))
+        {
+            return Err(format!("Invalid OSC address '{}'", self.osc_address));
+        }
+        if self.http_port == Some(0) {
+            return Err("Caption server port cannot be 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Spoken commands such as "Jilu, bookmark this", heard on the microphone by
+/// an on-device recognizer while the main session records.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VoiceCommandSettings {
+    pub enabled: bool,
+    /// Said before each command, so ordinary talk does not trigger one.
+    pub wake_word: String,
+}
+
+impl Default for VoiceCommandSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wake_word: DEFAULT_WAKE_WORD.to_string(),
+        }
+    }
+}
+
+/// Where the main recording's microphone audio comes from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MicrophoneSettings {
+    /// Capture the mic in the backend instead of having the window push it.
+    pub native: bool,
+    /// Input to record, as named by `list_native_mic_devices`; `None` follows
+    /// the system default.
+    pub device: Option<String>,
+}
+
+/// Keeps the recording's audio next to its transcript, for review or
+/// transcribing again.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioRecordingSettings {
+    pub enabled: bool,
+    pub format: AudioFileFormat,
+    pub layout: AudioLayout,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFileFormat {
+    #[default]
+    Wav,
+    /// Lossless, at about half the size.
+    Flac,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AudioLayout {
+    /// One file with the microphone and system audio mixed.
+    #[default]
+    Mixed,
+    /// A file for the microphone and one for system audio.
+    Separate,
+}
+
+/// A named bundle of per-meeting options, picked by `id` when a recording
+/// starts. Fields left unset fall back to the regular settings.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingPreset {
+    pub id: String,
+    pub name: String,
+    /// Speechmatics endpoint; recordings streamed to Deepgram ignore it.
+    pub rt_url: Option<String>,
+    pub language: Option<String>,
+    /// Only used together with `language`, since domains belong to a language pack.
+    pub domain: Option<String>,
+    /// `Some(false)` turns speaker diarization off, e.g. for dictation.
+    pub diarization: Option<bool>,
+    pub max_speakers: Option<u32>,
+    /// Replaces the custom vocabulary rather than adding to it.
+    pub additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
+    pub interview_mode: Option<bool>,
+    /// Export the meeting as soon as the recording stops.
+    pub auto_export: Option<bool>,
+    /// AI notes template used to enhance the meeting afterwards.
+    pub prompt_template_id: Option<String>,
+    /// Client certificate and trust roots for `rt_url`, e.g. a private gateway.
+    pub tls: Option<TlsSettings>,
+}
+
+/// Mutual TLS for a realtime endpoint. All paths point at PEM files.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TlsSettings {
+    pub client_cert_path: Option<String>,
+    /// Unencrypted PKCS#8 key for `client_cert_path`.
+    pub client_key_path: Option<String>,
+    /// Certificate authorities to trust on top of the system's.
+    pub ca_bundle_path: Option<String>,
+}
+
+impl TlsSettings {
+    fn validate(&self) -> Result<(), String> {
+        if self.client_cert_path.is_some() != self.client_key_path.is_some() {
+            return Err("a client certificate and its key must be set together".to_string());
+        }
+        for path in [
+            &self.client_cert_path,
+            &self.client_key_path,
+            &self.ca_bundle_path,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if !Path::new(path).is_absolute() {
+                return Err(format!("TLS file '{}' must be an absolute path", path));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RecordingPreset {
+    /// Layers the preset's provider options over `settings`.
+    pub fn apply_to(&self, settings: &mut TranscriptionSettings) {
+        if let Some(url) = &self.rt_url {
+            settings.rt_url = Some(url.clone());
+        }
+        if let Some(language) = &self.language {
+            settings.language = language.clone();
+            settings.domain = self.domain.clone();
+        }
+        if let Some(vocab) = &self.additional_vocab {
+            settings.additional_vocab = vocab.clone();
+        }
+    }
+
+    fn validate(&self, transcription: &TranscriptionSettings) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err(format!("Recording preset '{}' needs a name", self.id));
+        }
+        if self.domain.is_some() && self.language.is_none() {
+            return Err(format!(
+                "Recording preset '{}' sets a domain without a language",
+                self.name
+            ));
+        }
+        if let Some(max_speakers) = self.max_speakers {
+            if !MAX_SPEAKERS_RANGE.contains(&max_speakers) {
+                return Err(format!(
+                    "Recording preset '{}': speaker limit must be between {} and {}",
+                    self.name,
+                    MAX_SPEAKERS_RANGE.start(),
+                    MAX_SPEAKERS_RANGE.end()
+                ));
+            }
+        }
+        if let Some(tls) = &self.tls {
+            if self.rt_url.is_none() {
+                return Err(format!(
+                    "Recording preset '{}' sets TLS options without a realtime URL",
+                    self.name
+                ));
+            }
+            tls.validate()
+                .map_err(|err| format!("Recording preset '{}': {}", self.name, err))?;
+        }
+        let mut merged = transcription.clone();
+        self.apply_to(&mut merged);
+        merged
+            .validate()
+            .map_err(|err| format!("Recording preset '{}': {}", self.name, err))
+    }
+}
+
+impl TranscriptionSettings {
+    /// The endpoint of the chosen provider, if not its default.
+    pub fn endpoint(&self) -> Option<String> {
+        match self.provider {
+            ProviderKind::Speechmatics => self.rt_url.clone(),
+            ProviderKind::Deepgram => self.deepgram_url.clone(),
+        }
+    }
+
+    /// Adds `entries` to the vocabulary, skipping terms already there.
+    pub fn add_vocab(&mut self, entries: &[AdditionalVocabularyEntry]) {
+        for entry in entries {
+            let known = self.additional_vocab.iter().any(|existing| {
+                existing
+                    .content
+                    .trim()
+                    .eq_ignore_ascii_case(entry.content.trim())
+            });
+            if !known {
+                self.additional_vocab.push(entry.clone());
+            }
+        }
+        self.additional_vocab.truncate(MAX_VOCAB_ENTRIES);
+    }
+
+    /// Adds the vocabulary of each of `speakers` that has some.
+    pub fn add_speaker_vocab(&mut self, speakers: &[&str]) {
+        let entries: Vec<_> = self
+            .speaker_vocab
+            .iter()
+            .filter(|group| {
+                speakers
+                    .iter()
+                    .any(|speaker| speaker.trim().eq_ignore_ascii_case(group.speaker.trim()))
+            })
+            .flat_map(|group| group.additional_vocab.iter().cloned())
+            .collect();
+        self.add_vocab(&entries);
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for url in [&self.rt_url, &self.deepgram_url].into_iter().flatten() {
+            let parsed =
+                reqwest::Url::parse(url).map_err(|e| format!("Invalid realtime URL: {}", e))?;
+            if !matches!(parsed.scheme(), "ws" | "wss") {
+                return Err("Realtime URL must use ws:// or wss://".to_string());
+            }
+        }
+        validate_language(&self.language)?;
+        validate_vocab(&self.additional_vocab)?;
+        for group in &self.speaker_vocab {
+            if group.speaker.trim().is_empty() {
+                return Err("Speaker vocabulary needs a speaker".to_string());
+            }
+            validate_vocab(&group.additional_vocab)?;
+        }
+        validate_max_delay(self.max_delay)?;
+        if let Some(threshold) = self.confidence_threshold {
+            validate_confidence_threshold(threshold)?;
+        }
+        self.simulator.config.validate()?;
+        self.websocket.validate()?;
+        validate_channel_labels(&self.separate_channels.labels)?;
+        if self.partial_min_interval_ms > MAX_PARTIAL_MIN_INTERVAL_MS {
+            return Err(format!(
+                "Partial interval must be at most {} ms",
+                MAX_PARTIAL_MIN_INTERVAL_MS
+            ));
+        }
+        if !(10..=MAX_AUDIO_BATCH_MS).contains(&self.audio_batch_ms) {
+            return Err(format!(
+                "Audio batch must be between 10 and {} ms",
+                MAX_AUDIO_BATCH_MS
+            ));
+        }
+        if self.stop_drain_timeout_ms > MAX_STOP_DRAIN_TIMEOUT_MS {
+            return Err(format!(
+                "Stop drain timeout must be at most {} ms",
+                MAX_STOP_DRAIN_TIMEOUT_MS
+            ));
+        }
+        if let Some(hours) = self.monthly_allowance_hours {
+            if !(hours > 0.0 && hours <= MAX_MONTHLY_ALLOWANCE_HOURS) {
+                return Err(format!(
+                    "Monthly allowance must be between 0 and {} hours",
+                    MAX_MONTHLY_ALLOWANCE_HOURS
+                ));
+            }
+        }
+        let currency = self.costs.currency.trim();
+        if currency.is_empty() || currency.len() > 8 {
+            return Err(format!("Invalid currency '{}'", self.costs.currency));
+        }
+        for (provider, rate) in &self.costs.hourly_rates {
+            if provider.trim().is_empty() {
+                return Err("Hourly rates need a provider name".to_string());
+            }
+            if !(0.0..=MAX_HOURLY_RATE).contains(rate) {
+                return Err(format!(
+                    "Hourly rate for '{}' must be between 0 and {}",
+                    provider, MAX_HOURLY_RATE
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), String> {
+        self.transcription.validate()?;
+        locale::validate_locale(&self.locale)?;
+        for shortcut in [&self.shortcuts.new_meeting, &self.shortcuts.mute]
+            .into_iter()
+            .flatten()
+        {
+            shortcut
+                .parse::<tauri_plugin_global_shortcut::Shortcut>()
+                .map_err(|e| format!("Invalid shortcut '{}': {:?}", shortcut, e))?;
+        }
+        if !(1..=MAX_HOOK_TIMEOUT_SECS).contains(&self.automation.timeout_secs) {
+            return Err(format!(
+                "Hook timeout must be between 1 and {} seconds",
+                MAX_HOOK_TIMEOUT_SECS
+            ));
+        }
+        if self
+            .automation
+            .hooks
+            .iter()
+            .any(|hook| hook.command.trim().is_empty())
+        {
+            return Err("Automation hooks need a command".to_string());
+        }
+        if !(MIN_SILENCE_TIMEOUT_SECS..=MAX_SILENCE_TIMEOUT_SECS)
+            .contains(&self.silence_watchdog.timeout_secs)
+        {
+            return Err(format!(
+                "Silence timeout must be between {} and {} seconds",
+                MIN_SILENCE_TIMEOUT_SECS, MAX_SILENCE_TIMEOUT_SECS
+            ));
+        }
+        if self.speaker_cues.people.len() > MAX_CUE_PEOPLE {
+            return Err(format!(
+                "Speaker cues are limited to {} people",
+                MAX_CUE_PEOPLE
+            ));
+        }
+        if self
+            .speaker_cues
+            .people
+            .iter()
+            .any(|person| person.trim().is_empty())
+        {
+            return Err("Speaker cue names cannot be empty".to_string());
+        }
+        if let Some(path) = &self.live_transcript.path {
+            if !Path::new(path).is_absolute() {
+                return Err(format!(
+                    "Live transcript file '{}' must be an absolute path",
+                    path
+                ));
+            }
+        }
+        self.caption_output.validate()?;
+        let wake_words = self.voice_commands.wake_word.split_whitespace().count();
+        if wake_words == 0 || wake_words > MAX_WAKE_WORDS {
+            return Err(format!(
+                "The voice command wake word must be 1 to {} words",
+                MAX_WAKE_WORDS
+            ));
+        }
+        if self.recording_presets.len() > MAX_RECORDING_PRESETS {
+            return Err(format!(
+                "Recording presets are limited to {}",
+                MAX_RECORDING_PRESETS
+            ));
+        }
+        for (index, preset) in self.recording_presets.iter().enumerate() {
+            if preset.id.trim().is_empty() {
+                return Err("Recording presets need an id".to_string());
+            }
+            if self.recording_presets[..index]
+                .iter()
+                .any(|other| other.id == preset.id)
+            {
+                return Err(format!("Duplicate recording preset id '{}'", preset.id));
+            }
+            preset.validate(&self.transcription)?;
+        }
+        if self.avoid_list.len() > avoid_list::MAX_AVOID_RULES {
+            return Err(format!(
+                "The avoid-list is limited to {} rules",
+                avoid_list::MAX_AVOID_RULES
+            ));
+        }
+        for rule in &self.avoid_list {
+            rule.validate()?;
+        }
+        self.export_normalization.validate()?;
+        if let Some(folder) = self
+            .recording_imports
+            .folders
+            .iter()
+            .find(|folder| !Path::new(folder).is_absolute())
+        {
+            return Err(format!(
+                "Recording import folder '{}' must be an absolute path",
+                folder
+            ));
+        }
+        if self.quiet_hours.len() > quiet_hours::MAX_QUIET_WINDOWS {
+            return Err(format!(
+                "Quiet hours are limited to {} windows",
+                quiet_hours::MAX_QUIET_WINDOWS
+            ));
+        }
+        for window in &self.quiet_hours {
+            window.validate()?;
+        }
+        if self.screen_share_privacy.placeholder.trim().is_empty() {
+            return Err("The screen share placeholder cannot be empty".to_string());
+        }
+        if self.log_level.trim().is_empty() {
+            return Err("Log level cannot be empty".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn recording_preset(&self, id: &str) -> Option<&RecordingPreset> {
+        self.recording_presets.iter().find(|preset| preset.id == id)
+    }
+}
+
+/// Language codes are short identifiers like `en`, `ar_en` or `cmn_en`.
+pub fn validate_language(language: &str) -> Result<(), String> {
+    let valid = !language.is_empty()
+        && language.len() <= 16
+        && language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid language code '{}'", language))
+    }
+}
+
+pub fn validate_max_delay(max_delay: f32) -> Result<(), String> {
+    if MAX_DELAY_RANGE.contains(&max_delay) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Max delay must be between {} and {} seconds",
+            MAX_DELAY_RANGE.start(),
+            MAX_DELAY_RANGE.end()
+        ))
+    }
+}
+
+pub fn validate_confidence_threshold(threshold: f64) -> Result<(), String> {
+    if (0.0..=1.0).contains(&threshold) {
+        Ok(())
+    } else {
+        Err("Confidence threshold must be between 0 and 1".to_string())
+    }
+}
+
+/// Channel labels name the microphone and then system audio, and tell turns apart.
+pub fn validate_channel_labels(labels: &[String]) -> Result<(), String> {
+    let [mic, system] = labels else {
+        return Err("Channel labels must name the microphone and system audio".to_string());
+    };
+    let (mic, system) = (mic.trim(), system.trim());
+    if mic.is_empty() || system.is_empty() {
+        return Err("Channel labels cannot be empty".to_string());
+    }
+    if mic.eq_ignore_ascii_case(system) {
+        return Err("Channel labels must differ".to_string());
+    }
+    if mic.len() > MAX_CHANNEL_LABEL_LEN || system.len() > MAX_CHANNEL_LABEL_LEN {
+        return Err(format!(
+            "Channel labels must be at most {} characters",
+            MAX_CHANNEL_LABEL_LEN
+        ));
+    }
+    Ok(())
+}
+
+pub fn validate_vocab(entries: &[AdditionalVocabularyEntry]) -> Result<(), String> {
+    if entries.len() > MAX_VOCAB_ENTRIES {
+        return Err(format!(
+            "Custom vocabulary is limited to {} entries",
+            MAX_VOCAB_ENTRIES
+        ));
+    }
+    if entries.iter().any(|entry| entry.content.trim().is_empty()) {
+        return Err("Custom vocabulary entries cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Upgrades a stored document to `CURRENT_VERSION`.
+///
+/// `MIGRATIONS[n]` turns a version `n` document into version `n + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v0_to_v1];
+
+/// Files written before settings were versioned already have the v1 layout.
+fn migrate_v0_to_v1(_value: &mut Value) {}
+
+fn migrate(value: &mut Value) -> Result<(), String> {
+    let Some(object) = value.as_object_mut() else {
+        return Err("Settings file is not a JSON object".to_string());
+    };
+    let mut version = object.get("version").and_then(Value::as_u64).unwrap_or(0) as usize;
+    if version > CURRENT_VERSION as usize {
+        return Err(format!(
+            "Settings version {} is newer than this app supports",
+            version
+        ));
+    }
+    while version < MIGRATIONS.len() {
+        MIGRATIONS[version](value);
+        version += 1;
+    }
+    value["version"] = Value::from(CURRENT_VERSION);
+    Ok(())
+}
+
+/// JSON merge patch (RFC 7386): objects merge recursively and `null` removes
+/// a key, which resets it to its default on deserialize.
+fn merge_patch(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Default::default());
+            }
+            let target = target.as_object_mut().expect("target is an object");
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.remove(&key);
+                } else {
+                    merge_patch(target.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        other => *target = other,
+    }
+}
+
+/// Settings loaded from and saved to the app config directory.
+#[derive(Debug, Default)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: Settings,
+}
+
+impl SettingsStore {
+    /// Loads settings, falling back to defaults if the file is missing or unusable.
+    /// An unreadable file is kept aside as `settings.json.bak` instead of being overwritten;
+    /// a readable one with some invalid fields loses only those fields.
+    pub fn load(path: PathBuf) -> Self {
+        let settings = match read_settings(&path) {
+            Ok(Some(settings)) => settings,
+            Ok(None) => Settings::default(),
+            Err(err) => {
+                tracing::warn!("Ignoring stored settings: {}", err);
+                let _ = std::fs::rename(&path, path.with_extension("json.bak"));
+                Settings::default()
+            }
+        };
+        Self {
+            path: Some(path),
+            settings,
+        }
+    }
+
+    pub fn get(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Applies a merge patch, validates the result and persists it.
+    pub fn update(&mut self, patch: Value) -> Result<Settings, String> {
+        let mut value = serde_json::to_value(&self.settings).map_err(|e| e.to_string())?;
+        merge_patch(&mut value, patch);
+        value["version"] = Value::from(CURRENT_VERSION);
+
+        let updated: Settings =
+            serde_json::from_value(value).map_err(|e| format!("Invalid settings: {}", e))?;
+        updated.validate()?;
+
+        if let Some(path) = &self.path {
+            write_settings(path, &updated)?;
+        }
+        self.settings = updated.clone();
+        Ok(updated)
+    }
+}
+
+fn read_settings(path: &Path) -> Result<Option<Settings>, String> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("Failed to read settings: {}", err)),
+    };
+    let mut value: Value =
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to parse settings: {}", e))?;
+    migrate(&mut value)?;
+    if let Some(settings) = valid_settings(&value) {
+        return Ok(Some(settings));
+    }
+
+    let Value::Object(stored) = value else {
+        return Err("Settings file is not a JSON object".to_string());
+    };
+    let mut kept = serde_json::to_value(Settings::default()).map_err(|e| e.to_string())?;
+    let mut reset = Vec::new();
+    keep_valid_fields(&mut kept, "", stored, &mut reset);
+    let settings = valid_settings(&kept).ok_or("Invalid settings")?;
+    tracing::warn!(
+        "Reset invalid settings to their defaults: {}",
+        reset.join(", ")
+    );
+    // The next save overwrites the file, so keep what was there by hand.
+    let _ = std::fs::copy(path, path.with_extension("json.bak"));
+    Ok(Some(settings))
+}
+
+fn valid_settings(value: &Value) -> Option<Settings> {
+    serde_json::from_value::<Settings>(value.clone())
+        .ok()
+        .filter(|settings| settings.validate().is_ok())
+}
+
+/// Copies `stored` into the object at `pointer` in `kept` one field at a time,
+/// skipping any field the settings would not validate with. An object that
+/// fails as a whole is tried field by field, so one bad option doesn't cost
+/// its whole section. Skipped fields are listed in `reset`.
+fn keep_valid_fields(
+    kept: &mut Value,
+    pointer: &str,
+    stored: serde_json::Map<String, Value>,
+    reset: &mut Vec<String>,
+) {
+    for (key, value) in stored {
+        let field = format!("{}/{}", pointer, key.replace('~', "~0").replace('/', "~1"));
+        let Some(parent) = kept.pointer_mut(pointer).and_then(Value::as_object_mut) else {
+            return;
+        };
+        let previous = parent.insert(key.clone(), value.clone());
+        if valid_settings(kept).is_some() {
+            continue;
+        }
+        let parent = kept
+            .pointer_mut(pointer)
+            .and_then(Value::as_object_mut)
+            .expect("parent is an object");
+        match previous {
+            Some(previous) => parent.insert(key.clone(), previous),
+            None => parent.remove(&key),
+        };
+        match value {
+            Value::Object(fields) if parent.get(&key).is_some_and(Value::is_object) => {
+                keep_valid_fields(kept, &field, fields, reset)
+            }
+            _ => reset.push(field.trim_start_matches('/').replace('/', ".")),
+        }
+    }
+}
+
+/// Writes via a temporary file so a crash mid-write never leaves a truncated file.
+fn write_settings(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, contents).map_err(|e| format!("Failed to save settings: {}", e))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("Failed to save settings: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings_file(name: &str, contents: &Value) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("jilu-settings-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SETTINGS_FILE);
+        std::fs::write(&path, contents.to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn unversioned_files_read_as_v1() {
+        let mut value = json!({ "logLevel": "debug", "transcription": { "audioBatchMs": 200 } });
+        migrate(&mut value).unwrap();
+        assert_eq!(value["version"], json!(CURRENT_VERSION));
+        let settings: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(settings.log_level, "debug");
+        assert_eq!(settings.transcription.audio_batch_ms, 200);
+    }
+
+    #[test]
+    fn newer_and_malformed_files_are_refused() {
+        let mut newer = json!({ "version": CURRENT_VERSION + 1 });
+        assert!(migrate(&mut newer).is_err());
+        let mut list = json!([1, 2]);
+        assert!(migrate(&mut list).is_err());
+    }
+
+    #[test]
+    fn invalid_fields_reset_alone() {
+        let path = settings_file(
+            "invalid",
+            &json!({
+                "version": 1,
+                "logLevel": "debug",
+                "transcription": {
+                    "rtUrl": "wss://rt.example.com/v2",
+                    "audioBatchMs": 5000,
+                    "partialMinIntervalMs": 300,
+                    "maxDelay": "soon"
+                },
+                "voiceCommands": { "enabled": true, "wakeWord": "" }
+            }),
+        );
+        let settings = read_settings(&path).unwrap().unwrap();
+        let transcription = &settings.transcription;
+        assert_eq!(
+            transcription.rt_url.as_deref(),
+            Some("wss://rt.example.com/v2")
+        );
+        assert_eq!(transcription.audio_batch_ms, DEFAULT_AUDIO_BATCH_MS);
+        assert_eq!(transcription.partial_min_interval_ms, 300);
+        assert_eq!(transcription.max_delay, DEFAULT_MAX_DELAY_SECS);
+        assert_eq!(settings.log_level, "debug");
+        assert!(settings.voice_commands.enabled);
+        assert_eq!(settings.voice_commands.wake_word, DEFAULT_WAKE_WORD);
+        // What was there by hand is kept aside before the next save.
+        assert!(path.with_extension("json.bak").exists());
+    }
+
+    #[test]
+    fn fields_that_only_validate_together_are_kept() {
+        let path = settings_file(
+            "together",
+            &json!({
+                "version": 1,
+                "transcription": { "audioBatchMs": 0 },
+                "recordingPresets": [{
+                    "id": "gateway",
+                    "name": "Gateway",
+                    "rtUrl": "wss://gateway.example.com/v2",
+                    "tls": { "clientCertPath": "/etc/jilu/cert.pem", "clientKeyPath": "/etc/jilu/key.pem" }
+                }]
+            }),
+        );
+        let settings = read_settings(&path).unwrap().unwrap();
+        assert_eq!(
+            settings.transcription.audio_batch_ms,
+            DEFAULT_AUDIO_BATCH_MS
+        );
+        let preset = settings.recording_preset("gateway").unwrap();
+        assert!(preset
+            .tls
+            .as_ref()
+            .is_some_and(|tls| tls.client_key_path.is_some()));
+    }
+
+    #[test]
+    fn valid_files_load_untouched() {
+        let path = settings_file("valid", &json!({ "version": 1, "logLevel": "warn" }));
+        let settings = read_settings(&path).unwrap().unwrap();
+        assert_eq!(settings.log_level, "warn");
+        assert!(!path.with_extension("json.bak").exists());
+    }
+}
@@ -0,0 +1,482 @@
+use crate::consent::MeetingConsent;
+use crate::locale::Locale;
+use crate::transcript::{Transcript, TranscriptTurnPayload};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::time::Instant;
+use tokio_native_tls::TlsAcceptor;
+
+pub const DEFAULT_TTL_MINUTES: u64 = 2 * 60;
+pub const MAX_TTL_MINUTES: u64 = 12 * 60;
+const MAX_VIEWERS: usize = 20;
+const TOKEN_BYTES: usize = 24;
+/// Holds the link's token once a guest has opened it, so it leaves the address bar.
+const COOKIE: &str = "jilu_guest";
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Comments sent on a quiet stream so proxies and browsers keep it open.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// The token is in the link, so the page must not leak it through referrers or caches.
+pub const SECURITY_HEADERS: &str = "Cache-Control: no-store\r\n\
+    Referrer-Policy: no-referrer\r\n\
+    X-Content-Type-Options: nosniff\r\n\
+    X-Frame-Options: DENY\r\n\
+    Content-Security-Policy: default-src 'none'; script-src 'unsafe-inline'; \
+    style-src 'unsafe-inline'; connect-src 'self'\r\n";
+const PAGE: &str = include_str!("share_page.html");
+
+/// What the frontend shows about the active guest link.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareStatus {
+    pub url: String,
+    /// SHA-256 of the link's self-signed certificate, for guests to check
+    /// against the browser's warning.
+    pub fingerprint: String,
+    pub expires_at: String,
+    pub viewers: usize,
+}
+
+/// A read-only link to the live transcript, served over TLS on the local
+/// network interface until it expires or is stopped. Guests see finished
+/// turns, not partials.
+pub struct ShareSession {
+    url: String,
+    fingerprint: String,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    viewers: Arc<AtomicUsize>,
+    stop_tx: watch::Sender<bool>,
+}
+
+/// Everything a connection needs; cloned into each one.
+#[derive(Clone)]
+struct Server {
+    token: Arc<str>,
+    transcript: Arc<Mutex<Transcript>>,
+    consent: Option<PathBuf>,
+    viewers: Arc<AtomicUsize>,
+    stop_rx: watch::Receiver<bool>,
+    deadline: Instant,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct GuestTurn {
+    speaker: Option<String>,
+    text: String,
+}
+
+impl GuestTurn {
+    /// Whether guests are kept from seeing `turn`. Notes and declined
+    /// attendees' speech stay private; an empty turn in their place keeps the
+    /// viewer's turn indices in step.
+    fn hides(turn: &TranscriptTurnPayload, consent: &MeetingConsent) -> bool {
+        turn.note || consent.excludes(turn)
+    }
+
+    fn of(turn: &TranscriptTurnPayload, locale: Locale, consent: &MeetingConsent) -> Self {
+        if Self::hides(turn, consent) {
+            return Self {
+                speaker: None,
+                text: String::new(),
+            };
+        }
+        Self {
+            speaker: turn
+                .speaker
+                .as_deref()
+                .map(|speaker| locale.speaker_name(speaker).into_owned()),
+            text: turn.text.clone(),
+        }
+    }
+
+    /// Whether this is what guests should now see of `turn`.
+    fn shows(
+        &self,
+        turn: &TranscriptTurnPayload,
+        locale: Locale,
+        consent: &MeetingConsent,
+    ) -> bool {
+        if Self::hides(turn, consent) {
+            return self.speaker.is_none() && self.text.is_empty();
+        }
+        self.text == turn.text
+            && self.speaker.as_deref()
+                == turn
+                    .speaker
+                    .as_deref()
+                    .map(|speaker| locale.speaker_name(speaker))
+                    .as_deref()
+    }
+}
+
+/// Payload of the stream's `transcript` events: the turns from `turn_offset` on.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestUpdate {
+    turn_offset: usize,
+    turns: Vec<GuestTurn>,
+}
+
+/// Tracks what one viewer has been sent, to send only what changed since.
+pub struct TranscriptFeed {
+    /// The recording meeting's consent file, read on every update.
+    consent_path: Option<PathBuf>,
+    /// The turns as the viewer has them.
+    sent: Vec<GuestTurn>,
+}
+
+impl TranscriptFeed {
+    pub fn new(consent_path: Option<PathBuf>) -> Self {
+        Self {
+            consent_path,
+            sent: Vec::new(),
+        }
+    }
+
+    /// The turns from the first one the viewer has out of date, or `None`
+    /// when nothing changed. Fails when the meeting's consent can't be read,
+    /// and the viewer should then be sent nothing more.
+    pub fn update(
+        &mut self,
+        transcript: &Mutex<Transcript>,
+    ) -> Result<Option<GuestUpdate>, String> {
+        // Read before taking the transcript, which results are waiting on.
+        let consent = match &self.consent_path {
+            Some(path) => MeetingConsent::load(path)?,
+            None => MeetingConsent::default(),
+        };
+        let transcript = transcript.lock();
+        let locale = transcript.locale();
+        let turns = transcript.turns();
+        // Besides new text, a rename or a change in consent can touch any
+        // turn, and a new recording shortens the transcript.
+        let turn_offset = turns
+            .iter()
+            .zip(&self.sent)
+            .position(|(turn, sent)| !sent.shows(turn, locale, &consent))
+            .unwrap_or_else(|| turns.len().min(self.sent.len()));
+        if turn_offset == turns.len() && turn_offset == self.sent.len() {
+            return Ok(None);
+        }
+        self.sent.truncate(turn_offset);
+        self.sent.extend(
+            turns[turn_offset..]
+                .iter()
+                .map(|turn| GuestTurn::of(turn, locale, &consent)),
+        );
+        Ok(Some(GuestUpdate {
+            turn_offset,
+            turns: self.sent[turn_offset..].to_vec(),
+        }))
+    }
+}
+
+impl ShareSession {
+    /// Opens the listener and returns the session along with the server
+    /// future, which finishes once the link has expired or been stopped.
+    pub async fn start(
+        transcript: Arc<Mutex<Transcript>>,
+        consent: Option<PathBuf>,
+        ttl: Duration,
+    ) -> Result<(Self, impl Future<Output = ()>), String> {
+        let token = new_token()?;
+        // Only the interface the link names listens, not every one the machine has.
+        let host = lan_address().unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let listener = TcpListener::bind((host, 0))
+            .await
+            .map_err(|e| format!("Failed to open sharing server: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("Failed to open sharing server: {}", e))?
+            .port();
+        let (acceptor, fingerprint) = tls_acceptor(host)?;
+        let url = format!("https://{}/live/{}", SocketAddr::new(host, port), token);
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(ttl)
+                .map_err(|e| format!("Invalid link lifetime: {}", e))?;
+
+        let (stop_tx, stop_rx) = watch::channel(false);
+        let viewers = Arc::new(AtomicUsize::new(0));
+        let server = Server {
+            token: token.into(),
+            transcript,
+            consent,
+            viewers: viewers.clone(),
+            stop_rx,
+            deadline: Instant::now() + ttl,
+        };
+        let session = Self {
+            url,
+            fingerprint,
+            expires_at,
+            viewers,
+            stop_tx,
+        };
+        Ok((session, server.run(listener, acceptor)))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn status(&self) -> ShareStatus {
+        ShareStatus {
+            url: self.url.clone(),
+            fingerprint: self.fingerprint.clone(),
+            expires_at: self.expires_at.to_rfc3339(),
+            viewers: self.viewers.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Closes the listener and every open guest stream.
+    pub fn stop(self) {
+        let _ = self.stop_tx.send(true);
+    }
+}
+
+impl Server {
+    async fn run(self, listener: TcpListener, acceptor: TlsAcceptor) {
+        let acceptor = Arc::new(acceptor);
+        let mut stop_rx = self.stop_rx.clone();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let server = self.clone();
+                        let acceptor = acceptor.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = server.accept(stream, &acceptor).await {
+                                tracing::debug!("Guest connection ended: {}", err);
+                            }
+                        });
+                    }
+                    Err(err) => tracing::warn!("Sharing server failed to accept: {}", err),
+                },
+                _ = tokio::time::sleep_until(self.deadline) => break,
+                _ = stop_rx.changed() => break,
+            }
+        }
+    }
+
+    async fn accept(self, stream: TcpStream, acceptor: &TlsAcceptor) -> std::io::Result<()> {
+        let stream = match tokio::time::timeout(REQUEST_TIMEOUT, acceptor.accept(stream)).await {
+            Ok(Ok(stream)) => stream,
+            // Browsers that have not accepted the certificate yet hang up here.
+            Ok(Err(err)) => return Err(std::io::Error::other(err)),
+            Err(_) => return Ok(()),
+        };
+        self.handle(stream).await
+    }
+
+    async fn handle<S: AsyncRead + AsyncWrite + Unpin>(self, mut stream: S) -> std::io::Result<()> {
+        let request = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+            Ok(request) => request?,
+            Err(_) => return Ok(()),
+        };
+        let Some(path) = request_path(&request) else {
+            return respond(&mut stream, "405 Method Not Allowed", "text/plain", "").await;
+        };
+        let opened = path
+            .strip_prefix("/live/")
+            .and_then(|rest| strip_token(rest, &self.token))
+            == Some("");
+        if opened {
+            return self.admit(stream).await;
+        }
+        let admitted = cookie(&request, COOKIE)
+            .is_some_and(|value| strip_token(value, &self.token) == Some(""));
+        match path {
+            "/live/" if admitted => {
+                respond(&mut stream, "200 OK", "text/html; charset=utf-8", PAGE).await
+            }
+            "/live/events" if admitted => self.stream_events(stream).await,
+            // Wrong tokens look exactly like unknown paths.
+            _ => respond(&mut stream, "404 Not Found", "text/plain", "Not found").await,
+        }
+    }
+
+    /// Swaps the token in the link for a cookie and sends the guest on to the page.
+    async fn admit<S: AsyncWrite + Unpin>(&self, mut stream: S) -> std::io::Result<()> {
+        // Lax, since the link is usually opened from another app or site.
+        let response = format!(
+            "HTTP/1.1 303 See Other\r\nLocation: /live/\r\nSet-Cookie: {}={}; Path=/live; Max-Age={}; Secure; HttpOnly; SameSite=Lax\r\nContent-Length: 0\r\nConnection: close\r\n{}\r\n",
+            COOKIE,
+            self.token,
+            self.deadline.saturating_duration_since(Instant::now()).as_secs(),
+            SECURITY_HEADERS
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    async fn stream_events<S: AsyncWrite + Unpin>(self, mut stream: S) -> std::io::Result<()> {
+        if self.viewers.fetch_add(1, Ordering::Relaxed) >= MAX_VIEWERS {
+            self.viewers.fetch_sub(1, Ordering::Relaxed);
+            return respond(
+                &mut stream,
+                "503 Service Unavailable",
+                "text/plain",
+                "Too many viewers",
+            )
+            .await;
+        }
+        let result = self.write_events(&mut stream).await;
+        self.viewers.fetch_sub(1, Ordering::Relaxed);
+        result
+    }
+
+    async fn write_events<S: AsyncWrite + Unpin>(&self, stream: &mut S) -> std::io::Result<()> {
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n{}\r\n",
+            SECURITY_HEADERS
+        );
+        stream.write_all(head.as_bytes()).await?;
+
+        let mut stop_rx = self.stop_rx.clone();
+        let mut feed = TranscriptFeed::new(self.consent.clone());
+        let mut last_write = Instant::now();
+        let mut poll = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = poll.tick() => {}
+                _ = tokio::time::sleep_until(self.deadline) => break,
+                _ = stop_rx.changed() => break,
+            }
+            let update = feed.update(&self.transcript);
+            let update = match update {
+                Ok(update) => update,
+                Err(err) => {
+                    tracing::warn!("Ending a guest stream: {}", err);
+                    break;
+                }
+            };
+            if let Some(update) = update {
+                let data = serde_json::to_string(&update).unwrap_or_default();
+                stream
+                    .write_all(format!("event: transcript\ndata: {}\n\n", data).as_bytes())
+                    .await?;
+                last_write = Instant::now();
+            } else if last_write.elapsed() >= KEEPALIVE_INTERVAL {
+                stream.write_all(b": keepalive\n\n").await?;
+                last_write = Instant::now();
+            }
+        }
+        stream.write_all(b"event: ended\ndata: {}\n\n").await?;
+        stream.shutdown().await
+    }
+}
+
+/// A self-signed certificate for `host` and its SHA-256 fingerprint.
+pub fn tls_acceptor(host: IpAddr) -> Result<(TlsAcceptor, String), String> {
+    use sha2::Digest;
+
+    let certified = rcgen::generate_simple_self_signed(vec![host.to_string()])
+        .map_err(|e| format!("Failed to create the link certificate: {}", e))?;
+    let fingerprint = sha2::Sha256::digest(certified.cert.der())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":");
+    let identity = native_tls::Identity::from_pkcs8(
+        certified.cert.pem().as_bytes(),
+        certified.key_pair.serialize_pem().as_bytes(),
+    )
+    .map_err(|e| format!("Failed to load the link certificate: {}", e))?;
+    let acceptor = native_tls::TlsAcceptor::new(identity)
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    Ok((acceptor.into(), fingerprint))
+}
+
+pub fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    request.lines().skip(1).find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+pub fn cookie<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+    header(request, "cookie")?.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// Reads up to the end of the request headers; guests never send a body.
+pub async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 || buffer.len() + read > MAX_REQUEST_BYTES {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+/// Path of a `GET` request, without its query string.
+pub fn request_path(request: &str) -> Option<&str> {
+    let mut parts = request.lines().next()?.split_whitespace();
+    if parts.next()? != "GET" {
+        return None;
+    }
+    let target = parts.next()?;
+    Some(target.split(['?', '#']).next().unwrap_or(target))
+}
+
+/// What follows `token` at the start of `path`, compared in constant time.
+pub fn strip_token<'a>(path: &'a str, token: &str) -> Option<&'a str> {
+    let candidate = path.get(..token.len())?;
+    let difference = candidate
+        .bytes()
+        .zip(token.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+    (difference == 0).then(|| &path[token.len()..])
+}
+
+pub async fn respond<S: AsyncWrite + Unpin>(
+    stream: &mut S,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        SECURITY_HEADERS,
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+pub fn new_token() -> Result<String, String> {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to create link token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// The address other machines on the network reach us at. Connecting a UDP
+/// socket only picks a route; nothing is sent.
+pub fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then_some(ip)
+}
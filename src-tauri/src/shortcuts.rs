@@ -0,0 +1,277 @@
+use crate::apply_settings_patch;
+use crate::state::AppState;
+use crate::{events, settings};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::warn;
+
+/// Whether one configured shortcut is bound, and why not when it is not.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutStatus {
+    /// `newMeeting` or `mute`, as in the settings.
+    pub action: String,
+    pub shortcut: String,
+    pub registered: bool,
+    /// Why the shortcut is not bound, or may not fire although it is.
+    pub error: Option<String>,
+}
+
+/// Writes `shortcut` the way the global shortcut plugin reads it on every
+/// platform. Modifier names from other platforms are accepted: `Cmd` and `⌘`
+/// become `CommandOrControl`, so a shortcut set up on a Mac means Ctrl on
+/// Windows and Linux; `Option` and `⌥` become `Alt`; `Win` and `Meta` become
+/// `Super`. Only function keys may be bound without a modifier.
+pub fn normalize(shortcut: &str) -> Result<String, String> {
+    const ORDER: [&str; 5] = ["CommandOrControl", "Control", "Super", "Alt", "Shift"];
+    let mut modifiers: Vec<&str> = Vec::new();
+    let mut key: Option<String> = None;
+    for part in shortcut.split('+').map(str::trim) {
+        let modifier = match part.to_lowercase().as_str() {
+            "commandorcontrol" | "commandorctrl" | "cmdorctrl" | "cmdorcontrol" | "cmd"
+            | "command" | "⌘" => "CommandOrControl",
+            "control" | "ctrl" | "⌃" => "Control",
+            "super" | "win" | "windows" | "meta" => "Super",
+            "alt" | "option" | "opt" | "⌥" => "Alt",
+            "shift" | "⇧" => "Shift",
+            "" => return Err(format!("Invalid shortcut format: '{}'", shortcut)),
+            _ => {
+                if key.is_some() {
+                    return Err(format!("Shortcut '{}' has more than one key", shortcut));
+                }
+                key = Some(if part.chars().count() == 1 {
+                    part.to_uppercase()
+                } else {
+                    part.to_string()
+                });
+                continue;
+            }
+        };
+        if !modifiers.contains(&modifier) {
+            modifiers.push(modifier);
+        }
+    }
+    let key = key.ok_or_else(|| format!("Shortcut '{}' has no key", shortcut))?;
+    let function_key = key.len() > 1
+        && key.starts_with(['F', 'f'])
+        && key[1..].chars().all(|c| c.is_ascii_digit());
+    if modifiers.is_empty() && !function_key {
+        return Err(format!(
+            "Shortcut '{}' needs a modifier such as Ctrl or Alt",
+            shortcut
+        ));
+    }
+    modifiers.sort_by_key(|modifier| ORDER.iter().position(|known| known == modifier));
+    modifiers.push(&key);
+    let normalized = modifiers.join("+");
+    normalized
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
+    Ok(normalized)
+}
+
+/// Why global shortcuts may not fire in this session although they bind.
+fn session_caveat() -> Option<String> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let wayland = std::env::var("XDG_SESSION_TYPE").is_ok_and(|kind| kind == "wayland")
+        || std::env::var_os("WAYLAND_DISPLAY").is_some();
+    wayland.then(|| {
+        "Wayland only passes global shortcuts to X11 windows; bind `xdg-open jilu://record` \
+         in your desktop's keyboard settings instead"
+            .to_string()
+    })
+}
+
+/// Returns the normalized shortcut, which is what gets saved.
+#[tauri::command]
+pub async fn register_global_shortcut(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    shortcut: String,
+) -> Result<String, String> {
+    let shortcut = normalize(&shortcut)?;
+    apply_settings_patch(
+        &app,
+        &state,
+        serde_json::json!({ "shortcuts": { "newMeeting": shortcut } }),
+    )?;
+    Ok(shortcut)
+}
+
+/// Re-registers all global shortcuts from settings. Each is bound on its
+/// own, so one another app has taken does not keep the rest from working.
+pub fn apply_shortcuts(
+    app: &AppHandle,
+    shortcuts: &settings::ShortcutSettings,
+) -> Result<(), String> {
+    let _ = app.global_shortcut().unregister_all();
+    let caveat = session_caveat();
+    let bound = [
+        (
+            "newMeeting",
+            shortcuts
+                .new_meeting
+                .as_ref()
+                .map(|shortcut| (shortcut, bind_new_meeting_shortcut(app, shortcut))),
+        ),
+        (
+            "mute",
+            shortcuts
+                .mute
+                .as_ref()
+                .map(|shortcut| (shortcut, bind_mute_shortcut(app, shortcut))),
+        ),
+    ];
+    let mut statuses = Vec::new();
+    for (action, binding) in bound {
+        let Some((shortcut, result)) = binding else {
+            continue;
+        };
+        if let Err(err) = &result {
+            warn!(action, "{}", err);
+        }
+        statuses.push(ShortcutStatus {
+            action: action.to_string(),
+            shortcut: shortcut.clone(),
+            registered: result.is_ok(),
+            error: result.err().or_else(|| caveat.clone()),
+        });
+    }
+    let failed: Vec<String> = statuses
+        .iter()
+        .filter(|status| !status.registered)
+        .filter_map(|status| status.error.clone())
+        .collect();
+    if let Some(state) = app.try_state::<AppState>() {
+        *state.services.shortcuts.lock() = statuses;
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(failed.join("; "))
+    }
+}
+
+fn bind_new_meeting_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let shortcut_obj: Shortcut = normalize(shortcut)?
+        .parse()
+        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
+
+    app.global_shortcut()
+        .on_shortcut(shortcut_obj, move |app, _shortcut, event| {
+            // Windows, macOS and X11 all report the release as well.
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+                let _ = window.emit(events::GLOBAL_SHORTCUT_TRIGGERED, ());
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut {}: {:?}", shortcut, e))?;
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unregister_global_shortcut(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    app.global_shortcut()
+        .unregister_all()
+        .map_err(|e| format!("Failed to unregister shortcuts: {:?}", e))?;
+    state
+        .services
+        .shortcuts
+        .lock()
+        .iter_mut()
+        .for_each(|status| status.registered = false);
+    Ok(())
+}
+
+/// Returns the normalized shortcut, which is what gets saved.
+#[tauri::command]
+pub async fn register_mute_shortcut(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    shortcut: String,
+) -> Result<String, String> {
+    let shortcut = normalize(&shortcut)?;
+    apply_settings_patch(
+        &app,
+        &state,
+        serde_json::json!({ "shortcuts": { "mute": shortcut } }),
+    )?;
+    Ok(shortcut)
+}
+
+fn bind_mute_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let shortcut_obj: Shortcut = normalize(shortcut)?
+        .parse()
+        .map_err(|e| format!("Invalid shortcut format: {:?}", e))?;
+
+    app.global_shortcut()
+        .on_shortcut(shortcut_obj, move |app, _shortcut, event| {
+            // Acting on the release too would undo every mute at once.
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.emit(events::MUTE_SHORTCUT_TRIGGERED, ());
+            }
+        })
+        .map_err(|e| format!("Failed to register mute shortcut {}: {:?}", shortcut, e))?;
+
+    Ok(())
+}
+
+/// Which configured shortcuts are bound, for settings to point out the ones
+/// that are not.
+#[tauri::command]
+pub async fn get_shortcut_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<ShortcutStatus>, String> {
+    Ok(state.services.shortcuts.lock().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers_from_any_platform_are_normalized() {
+        assert_eq!(
+            normalize("cmd+shift+m").as_deref(),
+            Ok("CommandOrControl+Shift+M")
+        );
+        assert_eq!(
+            normalize("⌥ + ⌘ + k").as_deref(),
+            Ok("CommandOrControl+Alt+K")
+        );
+        assert_eq!(
+            normalize("Shift+Win+Ctrl+J").as_deref(),
+            Ok("Control+Super+Shift+J")
+        );
+        assert_eq!(normalize("Option+Opt+R").as_deref(), Ok("Alt+R"));
+    }
+
+    #[test]
+    fn only_function_keys_go_without_a_modifier() {
+        assert_eq!(normalize("F5").as_deref(), Ok("F5"));
+        assert!(normalize("m").is_err());
+        assert!(normalize("Ctrl+Shift").is_err());
+    }
+
+    #[test]
+    fn malformed_shortcuts_are_refused() {
+        assert!(normalize("").is_err());
+        assert!(normalize("Ctrl++M").is_err());
+        assert!(normalize("Ctrl+A+B").is_err());
+    }
+}
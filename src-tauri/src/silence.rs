@@ -0,0 +1,82 @@
+use crate::audio::TARGET_SAMPLE_RATE;
+use crate::device_test::SILENCE_PEAK;
+use crate::settings::SilenceWatchdogSettings;
+use serde::Serialize;
+
+/// How long an auto-stop waits after the warning, so someone can still speak up.
+pub const AUTO_STOP_GRACE_SECS: u64 = 60;
+
+/// Payload of `silence-warning`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceWarning {
+    pub silent_secs: u64,
+    /// Set when the recording will stop by itself unless sound returns.
+    pub auto_stop_in_secs: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum SilenceEvent {
+    Warning(SilenceWarning),
+    /// Sound came back after a warning.
+    Cleared,
+    AutoStop,
+}
+
+/// Watches the mixed audio for a recording that has gone quiet, e.g. because
+/// the meeting ended and nobody stopped it. Counts audio rather than wall time,
+/// so stalls in capture do not look like silence.
+#[derive(Debug)]
+pub struct SilenceWatchdog {
+    settings: SilenceWatchdogSettings,
+    silent_samples: u64,
+    warned: bool,
+    stop_requested: bool,
+}
+
+impl SilenceWatchdog {
+    pub fn new(settings: SilenceWatchdogSettings) -> Self {
+        Self {
+            settings,
+            silent_samples: 0,
+            warned: false,
+            stop_requested: false,
+        }
+    }
+
+    /// Feeds one frame of 16-bit little-endian PCM at the target rate.
+    pub fn observe(&mut self, pcm: &[u8]) -> Option<SilenceEvent> {
+        if !self.settings.enabled {
+            return None;
+        }
+        let threshold = (SILENCE_PEAK * f32::from(i16::MAX)) as u16;
+        let loud = pcm
+            .chunks_exact(2)
+            .any(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]).unsigned_abs() > threshold);
+        if loud {
+            self.silent_samples = 0;
+            self.stop_requested = false;
+            return std::mem::take(&mut self.warned).then_some(SilenceEvent::Cleared);
+        }
+
+        self.silent_samples += (pcm.len() / 2) as u64;
+        let silent_secs = self.silent_samples / u64::from(TARGET_SAMPLE_RATE);
+        let timeout = self.settings.timeout_secs;
+        if !self.warned && silent_secs >= timeout {
+            self.warned = true;
+            return Some(SilenceEvent::Warning(SilenceWarning {
+                silent_secs,
+                auto_stop_in_secs: self.settings.auto_stop.then_some(AUTO_STOP_GRACE_SECS),
+            }));
+        }
+        if self.warned
+            && self.settings.auto_stop
+            && !self.stop_requested
+            && silent_secs >= timeout + AUTO_STOP_GRACE_SECS
+        {
+            self.stop_requested = true;
+            return Some(SilenceEvent::AutoStop);
+        }
+        None
+    }
+}
@@ -0,0 +1,66 @@
+//! Ids made from names people type, shared by profiles and projects.
+
+/// Lowercase ASCII slug of `name`, e.g. `Client Work` becomes `client-work`.
+/// `fallback` stands in when nothing is left of the name or the slug is one of
+/// `reserved`. Suffixed with `-2`, `-3` and so on until `taken` says it is free.
+pub fn unique_slug(
+    name: &str,
+    fallback: &str,
+    reserved: &[&str],
+    taken: impl Fn(&str) -> bool,
+) -> String {
+    let slug = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_ascii_lowercase();
+    let base = if slug.is_empty() || reserved.contains(&slug.as_str()) {
+        fallback.to_string()
+    } else {
+        slug
+    };
+
+    let mut id = base.clone();
+    let mut n = 2;
+    while taken(&id) {
+        id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_become_lowercase_slugs() {
+        let free = |_: &str| false;
+        assert_eq!(
+            unique_slug("Client Work", "project", &[], free),
+            "client-work"
+        );
+        assert_eq!(
+            unique_slug("  Q3 / Planning!! ", "project", &[], free),
+            "q3-planning"
+        );
+        assert_eq!(
+            unique_slug("Café Ünïcode", "project", &[], free),
+            "caf-n-code"
+        );
+        assert_eq!(unique_slug("日本", "project", &[], free), "project");
+        assert_eq!(
+            unique_slug("Default", "profile", &["default"], free),
+            "profile"
+        );
+    }
+
+    #[test]
+    fn taken_slugs_are_suffixed() {
+        let taken = ["work", "work-2", "profile"];
+        let taken = |id: &str| taken.contains(&id);
+        assert_eq!(unique_slug("Work", "profile", &[], taken), "work-3");
+        assert_eq!(unique_slug("", "profile", &[], taken), "profile-2");
+    }
+}
@@ -0,0 +1,160 @@
+use crate::audio::{self, AudioBatcher, AudioMixer, FRAME_MS, SOURCE_SAMPLE_RATE};
+use crate::resources::ProcessMonitor;
+use crate::transcript::Transcript;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_SOAK_HOURS: f64 = 8.0;
+/// Memory the process may grow by over a simulated session, beyond the transcript itself.
+const RSS_GROWTH_BUDGET_BYTES: u64 = 64 * 1024 * 1024;
+const FEED_CHUNK_SAMPLES: usize = SOURCE_SAMPLE_RATE as usize / 10;
+const FRAMES_PER_FINAL: u64 = 2_000 / FRAME_MS;
+const FRAMES_PER_SAMPLE: u64 = 60_000 / FRAME_MS;
+const BATCH_MS: u64 = 100;
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct SoakReport {
+    pub simulated_seconds: f64,
+    pub wall_seconds: f64,
+    pub frames: u64,
+    pub batches: u64,
+    pub dropped_input_samples: u64,
+    pub dropped_pcm_bytes: u64,
+    pub transcript_bytes: usize,
+    pub start_rss_bytes: u64,
+    pub peak_rss_bytes: u64,
+    pub end_rss_bytes: u64,
+    pub passed: bool,
+}
+
+/// Pushes `hours` of synthetic audio through the mixer, batcher and transcript
+/// as fast as possible and checks that memory stays bounded.
+///
+/// Blocking; run it off the async runtime.
+pub fn run(hours: f64) -> Result<SoakReport, String> {
+    let total_samples = (hours.max(0.0) * 3600.0 * SOURCE_SAMPLE_RATE as f64) as u64;
+    let mut monitor = ProcessMonitor::default();
+    let start_rss = monitor.sample().0;
+    let started = Instant::now();
+
+    let (mut mixer, inputs) = AudioMixer::spawn(None)?;
+    let stats = mixer.stats();
+    let feeder = std::thread::Builder::new()
+        .name("jilu-soak-feeder".into())
+        .spawn(move || feed_synthetic_audio(inputs, total_samples))
+        .map_err(|e| format!("Failed to spawn soak feeder: {}", e))?;
+
+    let mut batcher = AudioBatcher::new(BATCH_MS);
+    let mut transcript = Transcript::default();
+    let mut report = SoakReport {
+        start_rss_bytes: start_rss,
+        peak_rss_bytes: start_rss,
+        ..Default::default()
+    };
+
+    loop {
+        while let Some(frame) = mixer.pop_frame() {
+            report.frames += 1;
+            if batcher.push(&frame).is_some() {
+                report.batches += 1;
+            }
+            if report.frames.is_multiple_of(FRAMES_PER_FINAL) {
+                let speaker = if (report.frames / FRAMES_PER_FINAL).is_multiple_of(3) {
+                    "S1"
+                } else {
+                    "S2"
+                };
+                transcript.append(Some(speaker), "the quick brown fox jumps over the lazy dog");
+            }
+            if report.frames.is_multiple_of(FRAMES_PER_SAMPLE) {
+                report.peak_rss_bytes = report.peak_rss_bytes.max(monitor.sample().0);
+            }
+        }
+
+        if mixer.is_finished() {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    if batcher.flush().is_some() {
+        report.batches += 1;
+    }
+    mixer.stop();
+    let _ = feeder.join();
+
+    let stats = stats.snapshot();
+    let end_rss = monitor.sample().0;
+    report.simulated_seconds = report.frames as f64 * FRAME_MS as f64 / 1000.0;
+    report.wall_seconds = started.elapsed().as_secs_f64();
+    report.dropped_input_samples = stats.dropped_input_samples;
+    report.dropped_pcm_bytes = stats.dropped_pcm_bytes;
+    report.transcript_bytes = transcript.heap_bytes();
+    report.peak_rss_bytes = report.peak_rss_bytes.max(end_rss);
+    report.end_rss_bytes = end_rss;
+
+    let budget = RSS_GROWTH_BUDGET_BYTES + report.transcript_bytes as u64;
+    report.passed = report.peak_rss_bytes.saturating_sub(start_rss) <= budget;
+    Ok(report)
+}
+
+/// Writes a 440 Hz tone to both mixer inputs, waiting for queue space instead of dropping.
+fn feed_synthetic_audio(mut inputs: audio::MixerInputs, total_samples: u64) {
+    let mut chunk = vec![0.0f32; FEED_CHUNK_SAMPLES];
+    let mut written = 0u64;
+    let step = 2.0 * std::f32::consts::PI * 440.0 / SOURCE_SAMPLE_RATE as f32;
+
+    while written < total_samples {
+        let len = (total_samples - written).min(FEED_CHUNK_SAMPLES as u64) as usize;
+        for (i, sample) in chunk[..len].iter_mut().enumerate() {
+            let n = (written + i as u64) % SOURCE_SAMPLE_RATE as u64;
+            *sample = (n as f32 * step).sin() * 0.25;
+        }
+
+        for producer in [&mut inputs.screen, &mut inputs.mic] {
+            while producer.slots() < len {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            audio::push_samples(producer, &chunk[..len]);
+        }
+        written += len as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_bounded(report: &SoakReport, hours: f64) {
+        let expected = hours * 3600.0;
+        assert!(
+            (report.simulated_seconds - expected).abs() < 1.0,
+            "simulated {} s of {} s",
+            report.simulated_seconds,
+            expected
+        );
+        assert_eq!(report.dropped_input_samples, 0);
+        assert_eq!(report.dropped_pcm_bytes, 0);
+        let growth = report.peak_rss_bytes.saturating_sub(report.start_rss_bytes);
+        assert!(
+            growth <= RSS_GROWTH_BUDGET_BYTES + report.transcript_bytes as u64,
+            "memory grew by {} bytes with a {} byte transcript",
+            growth,
+            report.transcript_bytes
+        );
+        assert!(report.passed);
+    }
+
+    #[test]
+    fn a_short_session_stays_bounded() {
+        let report = run(0.05).unwrap();
+        assert_bounded(&report, 0.05);
+    }
+
+    /// The full simulated workday; slow, so run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore]
+    fn an_eight_hour_session_stays_bounded() {
+        let report = run(DEFAULT_SOAK_HOURS).unwrap();
+        assert_bounded(&report, DEFAULT_SOAK_HOURS);
+    }
+}
@@ -0,0 +1,56 @@
+use jilu_core::speechmatics::SpeakersResultEntry;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const SPEAKERS_FILE: &str = "speakers.json";
+
+/// Voice identifiers Speechmatics returned for one speaker of a meeting,
+/// ready to be saved as a profile or project speaker.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedSpeaker {
+    /// `S1`, `S2`, ... or the label of a known speaker.
+    pub label: String,
+    pub speaker_identifiers: Vec<String>,
+    /// When the recognition session ended (RFC 3339). Labels like `S1` only
+    /// mean the same person among entries with the same time.
+    pub captured_at: String,
+}
+
+/// Speakers in a `SpeakersResult`; entries without identifiers are skipped.
+pub fn from_entries(entries: Vec<SpeakersResultEntry>) -> Vec<CapturedSpeaker> {
+    let captured_at = chrono::Utc::now().to_rfc3339();
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let speaker_identifiers: Vec<String> = entry
+                .speaker_identifiers
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|id| !id.is_empty())
+                .collect();
+            (!speaker_identifiers.is_empty()).then(|| CapturedSpeaker {
+                label: entry.label.unwrap_or_default(),
+                speaker_identifiers,
+                captured_at: captured_at.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn load(path: &Path) -> Result<Vec<CapturedSpeaker>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to read meeting speakers: {}", e)),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// Adds `speakers` to those already saved, so every recording made into the
+/// meeting keeps its own.
+pub fn append(path: &Path, speakers: &[CapturedSpeaker]) -> Result<(), String> {
+    let mut saved = load(path)?;
+    saved.extend_from_slice(speakers);
+    let contents = serde_json::to_string_pretty(&saved).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save meeting speakers: {}", e))
+}
@@ -0,0 +1,35 @@
+use serde::Serialize;
+use std::time::Instant;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StartupPhase {
+    pub name: &'static str,
+    /// Milliseconds since the app state was created.
+    pub at_ms: f64,
+}
+
+/// Timeline of startup milestones, emitted as `startup-profile` in debug builds.
+#[derive(Debug, Serialize, Clone)]
+pub struct StartupProfile {
+    #[serde(skip)]
+    started: Instant,
+    pub phases: Vec<StartupPhase>,
+}
+
+impl Default for StartupProfile {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            phases: Vec::new(),
+        }
+    }
+}
+
+impl StartupProfile {
+    pub fn mark(&mut self, name: &'static str) {
+        self.phases.push(StartupPhase {
+            name,
+            at_ms: self.started.elapsed().as_secs_f64() * 1000.0,
+        });
+    }
+}
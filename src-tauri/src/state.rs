@@ -0,0 +1,221 @@
+use crate::audio::{MixerStats, SourceFilter};
+use crate::capture::CaptureHandle;
+use crate::power::WakeLock;
+use crate::session_config::{LiveTuning, SessionChange};
+use crate::settings::SettingsStore;
+use crate::stream_health::StreamHealth;
+use crate::timeline::SessionTimeline;
+use crate::transcript::Transcript;
+use crate::{
+    agenda, analytics, contacts, device_test, interview, launch, levels, mute, native_mic,
+    onboarding, pairing, profiles, projects, recent_audio, recorder, resources, screen_share,
+    search, share, shortcuts, startup, telemetry, updater, voice_commands,
+};
+use parking_lot::{Mutex, MutexGuard};
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use tauri::{Emitter, Manager, State, Window};
+use tokio::sync::oneshot;
+
+/// Session id used when a command does not name one; the main window records into it.
+pub const MAIN_SESSION: &str = "main";
+
+/// Managed app state, grouped by the subsystem that owns each part.
+#[derive(Default)]
+pub struct AppState {
+    pub sessions: SessionManager,
+    pub capture: CaptureState,
+    pub stores: Stores,
+    pub services: Services,
+}
+
+/// Running recordings, and what the main one writes to.
+#[derive(Default)]
+pub struct SessionManager {
+    /// Running recordings by session id. Most of the time this only holds `MAIN_SESSION`.
+    running: Mutex<HashMap<String, RecordingSession>>,
+    /// State of the main session. It outlives the recording, so commands that
+    /// take no session id keep working on it after it stops.
+    pub main: SessionState,
+    /// Interview settings of the current or last recording; `None` when it was not an interview.
+    pub interview: Arc<Mutex<Option<interview::InterviewConfig>>>,
+}
+
+impl SessionManager {
+    pub fn lock(&self) -> MutexGuard<'_, HashMap<String, RecordingSession>> {
+        self.running.lock()
+    }
+
+    /// State of session `id`, or of the main session when `id` is `None`.
+    pub fn state(&self, id: Option<&str>) -> Result<SessionState, String> {
+        match id.filter(|id| *id != MAIN_SESSION) {
+            None => Ok(self.main.clone()),
+            Some(id) => self
+                .running
+                .lock()
+                .get(id)
+                .map(|session| session.state.clone())
+                .ok_or_else(|| format!("No recording session '{}'", id)),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        !self.running.lock().is_empty()
+    }
+}
+
+/// Audio taken in outside of a session's own inputs.
+#[derive(Default)]
+pub struct CaptureState {
+    /// ScreenCaptureKit stream feeding the main session.
+    pub screen: Arc<Mutex<Option<CaptureHandle>>>,
+    /// Collects mic chunks for `measure_mic_level` while not recording.
+    pub mic_probe: Arc<Mutex<Option<device_test::LevelMeter>>>,
+}
+
+/// What is kept on disk for the active profile.
+#[derive(Default)]
+pub struct Stores {
+    pub settings: Arc<Mutex<SettingsStore>>,
+    pub profiles: Arc<Mutex<profiles::Profiles>>,
+    pub onboarding: Arc<Mutex<onboarding::Onboarding>>,
+    pub contacts: Arc<Mutex<contacts::ContactBook>>,
+    pub projects: Arc<Mutex<projects::Projects>>,
+}
+
+/// Background work and hand-offs that outlive any one recording.
+#[derive(Default)]
+pub struct Services {
+    pub telemetry: Arc<Mutex<telemetry::Telemetry>>,
+    pub update: Arc<Mutex<updater::PendingUpdate>>,
+    pub startup_profile: Arc<Mutex<startup::StartupProfile>>,
+    /// Built on the first resource sample rather than at launch.
+    pub process_monitor: Arc<Mutex<Option<resources::ProcessMonitor>>>,
+    /// Intent from this process's own command line, held until the frontend asks for it.
+    pub pending_launch: Arc<Mutex<Option<launch::LaunchRequest>>>,
+    /// Callbacks for a quick capture the frontend is still starting.
+    pub quick_capture: Arc<Mutex<Option<launch::XCallback>>>,
+    /// Where a `jilu://transcript` request wants the transcript, while the user decides.
+    pub transcript_request: Arc<Mutex<Option<String>>>,
+    /// Guest link to the live transcript, while one is open.
+    pub share: Arc<Mutex<Option<share::ShareSession>>>,
+    pub pairing: Arc<Mutex<Option<pairing::PairingSession>>>,
+    /// Cancel flag of the bulk export in progress.
+    pub bulk_export: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+    pub screen_share: Arc<screen_share::ScreenShare>,
+    /// Built up by the first transcript search and kept current after that.
+    pub search: Arc<Mutex<search::SearchIndex>>,
+    /// How binding each configured global shortcut went.
+    pub shortcuts: Arc<Mutex<Vec<shortcuts::ShortcutStatus>>>,
+    /// Whether a tray icon is up that a hidden window can be brought back from.
+    pub tray_available: Arc<AtomicBool>,
+}
+
+/// What a recording session's task writes to. Each session has its own; the
+/// main session's is kept in `SessionManager::main`.
+#[derive(Clone, Default)]
+pub struct SessionState {
+    pub transcript: Arc<Mutex<Transcript>>,
+    pub is_muted: Arc<Mutex<bool>>,
+    pub mute_mode: Arc<Mutex<mute::MuteMode>>,
+    pub stream_health: Arc<Mutex<StreamHealth>>,
+    pub timeline: Arc<Mutex<SessionTimeline>>,
+    pub agenda: Arc<Mutex<agenda::Agenda>>,
+    /// Speaking metrics of the current or last recording.
+    pub speaking: Arc<Mutex<analytics::SpeakingTracker>>,
+    /// Audio levels of the current or last recording.
+    pub levels: Arc<Mutex<levels::LevelRecorder>>,
+    /// The last of the audio sent while recording, for enrolling voices heard in it.
+    pub recent_audio: Arc<Mutex<recent_audio::RecentAudio>>,
+    pub wake_lock: Arc<Mutex<Option<WakeLock>>>,
+}
+
+/// Session ids end up in event names, so they stay short and plain.
+pub fn validate_session_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 32
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid session id '{}'", id))
+    }
+}
+
+/// The window a session reports to. Events from sessions other than the main
+/// one are sent as `session/<id>/<event>`, so the main window's listeners
+/// only ever see their own recording.
+#[derive(Clone)]
+pub struct SessionWindow {
+    pub window: Window,
+    pub session_id: String,
+}
+
+/// Name under which `event` is sent for session `session_id`.
+pub fn session_event_name<'a>(session_id: &str, event: &'a str) -> Cow<'a, str> {
+    if session_id == MAIN_SESSION {
+        Cow::Borrowed(event)
+    } else {
+        Cow::Owned(format!("session/{}/{}", session_id, event))
+    }
+}
+
+impl SessionWindow {
+    pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) -> tauri::Result<()> {
+        self.window
+            .emit(&session_event_name(&self.session_id, event), payload)
+    }
+
+    pub fn app_state(&self) -> State<'_, AppState> {
+        self.window.state::<AppState>()
+    }
+}
+
+/// Why a recording has closed its provider connection while it carries on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suspension {
+    /// Ahead of system sleep; after wake the frontend offers to resume.
+    Sleep,
+    /// By `pause_recording`, until `resume_recording`.
+    Paused,
+}
+
+pub struct RecordingSession {
+    pub mic_tx: Arc<Mutex<rtrb::Producer<f32>>>,
+    /// Set once the main window is closed and the backend records the mic itself.
+    pub native_mic: Option<native_mic::NativeMic>,
+    pub mixer_stats: Arc<MixerStats>,
+    /// Takes sources out of the mix while muted in a mode that excludes them.
+    pub source_filter: Arc<SourceFilter>,
+    /// Listens for spoken commands on the main session's microphone.
+    pub voice_commands: Option<voice_commands::VoiceCommands>,
+    /// Writes the session's audio to disk, when asked to.
+    pub recorder: Option<recorder::Recorder>,
+    pub stop_tx: Option<oneshot::Sender<()>>,
+    /// Asks the transcription task to continue in a new provider session.
+    pub change_tx: tokio::sync::mpsc::UnboundedSender<SessionChange>,
+    /// Keyword watches and partial rate the result reader follows.
+    pub tuning: Arc<Mutex<LiveTuning>>,
+    /// Set while the session is paused, by the user or for system sleep.
+    pub suspend_tx: tokio::sync::watch::Sender<Option<Suspension>>,
+    pub task: tauri::async_runtime::JoinHandle<()>,
+    pub autosave: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Follows the output device system audio plays to, for the main session.
+    pub route_watch: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Where the final transcript is kept for `jilu export`, when recording into a meeting.
+    pub transcript_path: Option<std::path::PathBuf>,
+    pub meeting_id: Option<String>,
+    pub meeting_title: Option<String>,
+    pub state: SessionState,
+}
+
+impl RecordingSession {
+    pub fn suspension(&self) -> Option<Suspension> {
+        *self.suspend_tx.borrow()
+    }
+}
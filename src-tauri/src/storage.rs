@@ -0,0 +1,690 @@
+use crate::state::AppState;
+use crate::transcript::{TranscriptTurnPayload, TurnSource};
+use crate::{
+    analytics, bulk_export, chapters, consent, costs, events, formatting, interview, levels,
+    locale, meeting_package, meeting_store, normalize, speaker_ids, timeline, transcript_export,
+    usage,
+};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct FullTranscript {
+    text: String,
+    turns: Vec<TranscriptTurnPayload>,
+}
+
+pub fn save_transcript_turns(
+    path: &std::path::Path,
+    turns: &[TranscriptTurnPayload],
+) -> Result<(), String> {
+    if turns.is_empty() {
+        return Ok(());
+    }
+    let turns = consent::redact(&path.with_file_name(consent::CONSENT_FILE), turns)
+        .map_err(|e| format!("Not saving the transcript: {}", e))?;
+    let contents = serde_json::to_string(&turns).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save meeting transcript: {}", e))
+}
+
+pub fn save_interview(
+    path: &std::path::Path,
+    document: &interview::InterviewDocument,
+) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(document).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save interview: {}", e))
+}
+
+pub fn save_analytics(
+    path: &std::path::Path,
+    analytics: &analytics::MeetingAnalytics,
+) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(analytics).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save meeting analytics: {}", e))
+}
+
+pub fn save_chapters(path: &std::path::Path, chapters: &[chapters::Chapter]) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(chapters).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save chapters: {}", e))
+}
+
+pub fn save_levels(path: &std::path::Path, levels: &levels::LevelHistory) -> Result<(), String> {
+    let contents = serde_json::to_string(levels).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save audio levels: {}", e))
+}
+
+/// Loudness of the microphone and system audio over a meeting, for drawing
+/// who was loud when and where it went quiet. Without `meeting_id` this is the
+/// current or last recording.
+#[tauri::command]
+pub async fn get_level_history(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting_id: Option<String>,
+) -> Result<levels::LevelHistory, String> {
+    let Some(meeting_id) = meeting_id else {
+        return Ok(state.sessions.main.levels.lock().history());
+    };
+    let live = state
+        .sessions
+        .lock()
+        .values()
+        .find(|session| session.meeting_id.as_ref() == Some(&meeting_id))
+        .map(|session| session.state.levels.clone());
+    if let Some(levels) = live {
+        return Ok(levels.lock().history());
+    }
+
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    let contents = std::fs::read_to_string(dir.join(levels::LEVELS_FILE))
+        .map_err(|_| format!("No audio levels saved for meeting {}", meeting_id))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Failed to read audio levels: {}", e))
+}
+
+/// What every export takes from the settings: the locale, the transcript
+/// formatting and, when it is on, export normalization.
+pub fn export_settings(
+    state: &AppState,
+) -> (
+    locale::Locale,
+    formatting::FormattingSettings,
+    Option<normalize::Normalizer>,
+) {
+    let settings = state.stores.settings.lock();
+    let settings = settings.get();
+    (
+        locale::Locale::from_tag(&settings.locale),
+        settings.transcription.formatting.clone(),
+        normalize::Normalizer::new(&settings.export_normalization, &settings.locale),
+    )
+}
+
+/// Writes a transcript to `path` as txt, srt, vtt or json. Subtitles get a
+/// cue per turn led by its speaker, to go with a video of the meeting.
+/// Without `meeting_id` this is the transcript of session `session_id`, or
+/// of the current or last recording. With `source`, only the turns heard on
+/// the microphone or in system audio are written.
+#[tauri::command]
+pub async fn export_transcript(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    format: String,
+    path: String,
+    meeting_id: Option<String>,
+    session_id: Option<String>,
+    source: Option<TurnSource>,
+) -> Result<(), String> {
+    let format: transcript_export::ExportFormat = format.parse()?;
+    let path = std::path::PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err("Export path must be an absolute path".to_string());
+    }
+    let (mut turns, mut chapters) = match meeting_id {
+        Some(meeting_id) => {
+            let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+            let contents = std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE))
+                .map_err(|_| format!("No transcript saved for meeting {}", meeting_id))?;
+            let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to read transcript: {}", e))?;
+            (turns, saved_chapters(&dir)?)
+        }
+        None => {
+            let session = state.sessions.state(session_id.as_deref())?;
+            let turns = session.transcript.lock().turns().to_vec();
+            // Saved transcripts leave out attendees who declined; so must this.
+            let recording_meeting = state
+                .sessions
+                .lock()
+                .get(session_id.as_deref().unwrap_or(crate::state::MAIN_SESSION))
+                .and_then(|session| session.meeting_id.clone());
+            let turns = match recording_meeting {
+                Some(meeting_id) => {
+                    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+                    consent::redact(&dir.join(consent::CONSENT_FILE), &turns)?.into_owned()
+                }
+                None => turns,
+            };
+            let chapters = chapters::detect(&turns);
+            (turns, chapters)
+        }
+    };
+
+    if let Some(source) = source {
+        transcript_export::keep_source(&mut turns, &mut chapters, source);
+    }
+
+    let (locale, formatting, normalizer) = export_settings(&state);
+    formatting::apply_to_turns(&mut turns, &formatting);
+    if let Some(normalizer) = &normalizer {
+        normalizer.apply_to_turns(&mut turns);
+    }
+    let rendered = transcript_export::render(&turns, &chapters, format, locale)?;
+    std::fs::write(&path, rendered)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Exports every meeting of the active profile dated within `range` into a
+/// new folder under `destination`: one folder per meeting and a
+/// `manifest.json` index. `meetings` adds what only the frontend knows, such
+/// as titles and notes. Sends `bulk-export-progress` after each meeting;
+/// `cancel_bulk_export` stops it between meetings.
+#[tauri::command]
+pub async fn export_all_meetings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    range: Option<bulk_export::ExportRange>,
+    format: String,
+    destination: String,
+    meetings: Option<Vec<bulk_export::MeetingInfo>>,
+) -> Result<bulk_export::Manifest, String> {
+    let format: transcript_export::ExportFormat = format.parse()?;
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = state.stores.profiles.lock().active().clone();
+    let (locale, formatting, normalizer) = export_settings(&state);
+    let job = bulk_export::BulkExport {
+        meetings_dir: meeting_store::meetings_root(&profile, &data_dir),
+        destination: std::path::PathBuf::from(destination),
+        format,
+        range: range.unwrap_or_default(),
+        locale,
+        formatting,
+        normalizer,
+        infos: meetings.unwrap_or_default(),
+    };
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut running = state.services.bulk_export.lock();
+        if running.is_some() {
+            return Err("An export is already running".to_string());
+        }
+        *running = Some(cancel.clone());
+    }
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("bulk_export");
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        job.run(&cancel, |progress| {
+            let _ = app.emit(events::BULK_EXPORT_PROGRESS, progress);
+        })
+    })
+    .await
+    .map_err(|e| format!("Export failed: {}", e))
+    .and_then(|result| result);
+    state.services.bulk_export.lock().take();
+    result
+}
+
+/// Writes one meeting as a self-contained folder under `destination` for
+/// people who don't use Jilu: audio, transcripts, summary, action items and
+/// an `index.html` that plays the audio in step with the transcript.
+#[tauri::command]
+pub async fn export_meeting_package(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting: meeting_package::PackageInfo,
+    destination: String,
+) -> Result<meeting_package::PackageManifest, String> {
+    let meeting_dir = meeting_store::meeting_dir(&app, &meeting.id)?;
+    let (locale, formatting, normalizer) = export_settings(&state);
+    let package = meeting_package::MeetingPackage {
+        meeting_dir,
+        destination: std::path::PathBuf::from(destination),
+        locale,
+        formatting,
+        normalizer,
+        info: meeting,
+    };
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("meeting_package");
+    tauri::async_runtime::spawn_blocking(move || package.write())
+        .await
+        .map_err(|e| format!("Export failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn cancel_bulk_export(state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(cancel) = state.services.bulk_export.lock().as_ref() {
+        cancel.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+/// Transcript turn texts as exports write them, with dates, times and
+/// amounts in the export locale. Unchanged when normalization is off.
+#[tauri::command]
+pub async fn normalize_export_text(
+    state: State<'_, AppState>,
+    texts: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let normalizer = {
+        let settings = state.stores.settings.lock();
+        let settings = settings.get();
+        normalize::Normalizer::new(&settings.export_normalization, &settings.locale)
+    };
+    Ok(match normalizer {
+        Some(normalizer) => texts.iter().map(|text| normalizer.apply(text)).collect(),
+        None => texts,
+    })
+}
+
+/// Per-speaker filler counts, speaking pace and pauses. Without a meeting id,
+/// or for the meeting being recorded, this is the live recording's numbers;
+/// older meetings without saved metrics get an estimate from their transcript.
+#[tauri::command]
+pub async fn get_meeting_analytics(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting_id: Option<String>,
+) -> Result<analytics::MeetingAnalytics, String> {
+    let Some(meeting_id) = meeting_id else {
+        return Ok(state.sessions.main.speaking.lock().report());
+    };
+    let live = state
+        .sessions
+        .lock()
+        .values()
+        .find(|session| session.meeting_id.as_ref() == Some(&meeting_id))
+        .map(|session| session.state.speaking.clone());
+    if let Some(speaking) = live {
+        return Ok(speaking.lock().report());
+    }
+
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    saved_meeting_analytics(&dir, &meeting_id)
+}
+
+/// Voice identifiers captured for a meeting's speakers, for turning unknown
+/// speakers into profiles after the meeting.
+#[tauri::command]
+pub async fn get_meeting_speakers(
+    app: AppHandle,
+    meeting_id: String,
+) -> Result<Vec<speaker_ids::CapturedSpeaker>, String> {
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    speaker_ids::load(&dir.join(speaker_ids::SPEAKERS_FILE))
+}
+
+/// Analytics saved with a finished meeting, or worked out from its saved transcript.
+pub fn saved_meeting_analytics(
+    dir: &std::path::Path,
+    meeting_id: &str,
+) -> Result<analytics::MeetingAnalytics, String> {
+    if let Ok(contents) = std::fs::read_to_string(dir.join(analytics::ANALYTICS_FILE)) {
+        return serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to read meeting analytics: {}", e));
+    }
+    let contents = std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE))
+        .map_err(|_| format!("No saved transcript for meeting {}", meeting_id))?;
+    let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to read meeting transcript: {}", e))?;
+    Ok(analytics::from_turns(&turns))
+}
+
+/// Sections of a finished meeting's transcript, titled by topic. Meetings
+/// recorded before chapters were saved have them worked out on request.
+#[tauri::command]
+pub async fn get_meeting_chapters(
+    app: AppHandle,
+    meeting_id: String,
+) -> Result<Vec<chapters::Chapter>, String> {
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    saved_chapters(&dir)
+}
+
+/// Replaces a finished meeting's chapters with ones worked out elsewhere, e.g.
+/// by the AI endpoint, returning them with their recording offsets filled in.
+/// Chapters must start at distinct turns of the saved transcript, in order.
+#[tauri::command]
+pub async fn save_meeting_chapters(
+    app: AppHandle,
+    meeting_id: String,
+    chapters: Vec<chapters::Chapter>,
+) -> Result<Vec<chapters::Chapter>, String> {
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    let contents = std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE))
+        .map_err(|_| format!("No saved transcript for meeting {}", meeting_id))?;
+    let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to read meeting transcript: {}", e))?;
+    let chapters = chapters::with_offsets(chapters, &turns)?;
+    save_chapters(&dir.join(chapters::CHAPTERS_FILE), &chapters)?;
+    Ok(chapters)
+}
+
+/// Chapters saved with a meeting, or detected from its saved transcript.
+/// Short meetings have none.
+pub fn saved_chapters(dir: &std::path::Path) -> Result<Vec<chapters::Chapter>, String> {
+    if let Ok(contents) = std::fs::read_to_string(dir.join(chapters::CHAPTERS_FILE)) {
+        return serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to read chapters: {}", e));
+    }
+    let Ok(contents) = std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE)) else {
+        return Ok(Vec::new());
+    };
+    let turns: Vec<TranscriptTurnPayload> = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to read meeting transcript: {}", e))?;
+    Ok(chapters::detect(&turns))
+}
+
+/// Timeline for a stored meeting, or for the current session when no id is given.
+#[tauri::command]
+pub async fn get_session_timeline(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    meeting_id: Option<String>,
+) -> Result<Vec<timeline::TimelineEvent>, String> {
+    match meeting_id {
+        Some(id) => {
+            let path = meeting_store::meeting_dir(&app, &id)?.join(timeline::TIMELINE_FILE);
+            timeline::load(&path)
+        }
+        None => Ok(state.sessions.main.timeline.lock().events().to_vec()),
+    }
+}
+
+/// This month's Speechmatics usage on `api_key`, and the hours left of the
+/// allowance set in settings, so running out is no surprise mid-meeting.
+#[tauri::command]
+pub async fn get_provider_usage(
+    state: State<'_, AppState>,
+    api_key: String,
+) -> Result<usage::ProviderUsage, String> {
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err("No Speechmatics API key configured".to_string());
+    }
+    let allowance = state
+        .stores
+        .settings
+        .lock()
+        .get()
+        .transcription
+        .monthly_allowance_hours;
+    usage::fetch(api_key, allowance).await
+}
+
+/// Audio streamed for a meeting and its estimated transcription cost; `None`
+/// until something has been recorded into it.
+#[tauri::command]
+pub async fn get_meeting_cost(
+    app: AppHandle,
+    meeting_id: String,
+) -> Result<Option<costs::MeetingCost>, String> {
+    let dir = meeting_store::meeting_dir(&app, &meeting_id)?;
+    costs::load(&dir)
+}
+
+/// Audio hours and estimated transcription cost summed over meetings, for
+/// billing them on: all of them, one project's, or those recorded between
+/// `since` and `until` (`YYYY-MM-DD`, inclusive).
+#[tauri::command]
+pub async fn get_usage_stats(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    project_id: Option<String>,
+    since: Option<String>,
+    until: Option<String>,
+) -> Result<costs::UsageStats, String> {
+    let parse_date = |value: Option<String>| {
+        value
+            .map(|value| {
+                chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                    .map_err(|_| format!("Invalid date '{}'; expected YYYY-MM-DD", value))
+            })
+            .transpose()
+    };
+    let meeting_ids = match &project_id {
+        Some(id) => {
+            let projects = state.stores.projects.lock();
+            if projects.get(id).is_none() {
+                return Err(format!("Unknown project: {}", id));
+            }
+            Some(projects.meetings_in(id))
+        }
+        None => None,
+    };
+    let filter = costs::UsageFilter {
+        meeting_ids,
+        since: parse_date(since)?,
+        until: parse_date(until)?,
+    };
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let profile = state.stores.profiles.lock().active().clone();
+    let currency = state
+        .stores
+        .settings
+        .lock()
+        .get()
+        .transcription
+        .costs
+        .currency
+        .clone();
+    Ok(costs::summarize(
+        &meeting_store::meetings_root(&profile, &data_dir),
+        &filter,
+        &currency,
+    ))
+}
+
+#[tauri::command]
+pub async fn create_directory(path: String) -> Result<(), String> {
+    std::fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))
+}
+
+#[tauri::command]
+pub async fn write_file(path: String, content: String) -> Result<(), String> {
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))
+}
+
+#[tauri::command]
+pub async fn read_file(path: String) -> Result<String, String> {
+    std::fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
+}
+
+#[tauri::command]
+pub async fn rename_directory(old_path: String, new_path: String) -> Result<(), String> {
+    if !std::path::Path::new(&old_path).exists() {
+        return Err("Source directory does not exist".to_string());
+    }
+
+    if std::path::Path::new(&new_path).exists() {
+        return Err("Destination directory already exists".to_string());
+    }
+
+    std::fs::rename(&old_path, &new_path).map_err(|e| format!("Failed to rename directory: {}", e))
+}
+
+#[tauri::command]
+pub async fn directory_exists(path: String) -> Result<bool, String> {
+    Ok(std::path::Path::new(&path).exists())
+}
+
+#[tauri::command]
+pub async fn get_home_directory() -> Result<String, String> {
+    dirs::home_dir()
+        .and_then(|path| path.to_str().map(|s| s.to_string()))
+        .ok_or_else(|| "Failed to get home directory".to_string())
+}
+
+/// The transcript rendered as text, a paragraph per turn led by its speaker.
+/// Callers that need speakers and turn boundaries should use
+/// `get_transcript_turns`.
+#[tauri::command]
+pub async fn get_transcript(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<String, String> {
+    let session = state.sessions.state(session_id.as_deref())?;
+    let text = session.transcript.lock().text().to_string();
+    Ok(text)
+}
+
+/// The transcript's turns, with speakers and timings. `from` skips the turns
+/// before it, for callers that already have them.
+#[tauri::command]
+pub async fn get_transcript_turns(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+    from: Option<usize>,
+) -> Result<Vec<TranscriptTurnPayload>, String> {
+    let session = state.sessions.state(session_id.as_deref())?;
+    let transcript = session.transcript.lock();
+    let turns = transcript.turns();
+    Ok(turns[from.unwrap_or(0).min(turns.len())..].to_vec())
+}
+
+#[tauri::command]
+pub async fn get_full_transcript(
+    state: State<'_, AppState>,
+    session_id: Option<String>,
+) -> Result<FullTranscript, String> {
+    let session = state.sessions.state(session_id.as_deref())?;
+    let transcript = session.transcript.lock();
+    Ok(FullTranscript {
+        text: transcript.text().to_string(),
+        turns: transcript.turns().to_vec(),
+    })
+}
+
+#[tauri::command]
+pub async fn save_transcript(
+    state: State<'_, AppState>,
+    filename: String,
+) -> Result<String, String> {
+    let transcript = state.sessions.main.transcript.lock().text().to_string();
+
+    let path = std::env::current_dir()
+        .map_err(|e| e.to_string())?
+        .join(&filename);
+
+    std::fs::write(&path, transcript.as_bytes())
+        .map_err(|e| format!("Failed to save file: {}", e))?;
+
+    Ok(format!("Transcript saved to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Transcript;
+    use std::path::PathBuf;
+
+    fn meeting_folder(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("jilu-storage-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn turns() -> Vec<TranscriptTurnPayload> {
+        let mut transcript = Transcript::default();
+        transcript.append(Some("S1"), "Shall we start with the budget?");
+        transcript.append(Some("S2"), "Yes, the numbers came in yesterday.");
+        transcript.append(Some("S1"), "Great.");
+        transcript.turns().to_vec()
+    }
+
+    fn saved_turns(dir: &std::path::Path) -> Vec<TranscriptTurnPayload> {
+        let contents =
+            std::fs::read_to_string(dir.join(transcript_export::TRANSCRIPT_FILE)).unwrap();
+        serde_json::from_str(&contents).unwrap()
+    }
+
+    #[test]
+    fn transcripts_round_trip_through_the_meeting_folder() {
+        let dir = meeting_folder("round-trip");
+        let turns = turns();
+        save_transcript_turns(&dir.join(transcript_export::TRANSCRIPT_FILE), &turns).unwrap();
+        let saved = saved_turns(&dir);
+        assert_eq!(saved.len(), turns.len());
+        for (saved, turn) in saved.iter().zip(&turns) {
+            assert_eq!(saved.speaker, turn.speaker);
+            assert_eq!(saved.text, turn.text);
+        }
+    }
+
+    #[test]
+    fn empty_transcripts_are_not_written() {
+        let dir = meeting_folder("empty");
+        let path = dir.join(transcript_export::TRANSCRIPT_FILE);
+        save_transcript_turns(&path, &[]).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn declined_speech_is_not_saved() {
+        let dir = meeting_folder("declined");
+        let consent = serde_json::json!({
+            "excludeDeclined": true,
+            "attendees": [{
+                "email": "sam@example.com",
+                "name": "Sam",
+                "status": "declined",
+                "speaker": "S2",
+                "updatedAt": "2024-05-01T09:00:00Z"
+            }]
+        });
+        std::fs::write(dir.join(consent::CONSENT_FILE), consent.to_string()).unwrap();
+        save_transcript_turns(&dir.join(transcript_export::TRANSCRIPT_FILE), &turns()).unwrap();
+        let saved = saved_turns(&dir);
+        assert!(!saved.is_empty());
+        assert!(saved
+            .iter()
+            .all(|turn| turn.speaker.as_deref() == Some("S1")));
+    }
+
+    #[test]
+    fn unreadable_consent_saves_nothing() {
+        let dir = meeting_folder("unreadable-consent");
+        std::fs::write(dir.join(consent::CONSENT_FILE), "{ not json").unwrap();
+        let path = dir.join(transcript_export::TRANSCRIPT_FILE);
+        assert!(save_transcript_turns(&path, &turns()).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn older_meetings_get_analytics_from_their_transcript() {
+        let dir = meeting_folder("analytics");
+        assert!(saved_meeting_analytics(&dir, "m1").is_err());
+
+        save_transcript_turns(&dir.join(transcript_export::TRANSCRIPT_FILE), &turns()).unwrap();
+        let worked_out = saved_meeting_analytics(&dir, "m1").unwrap();
+        assert_eq!(worked_out.speakers.len(), 2);
+        assert!(!worked_out.word_timings);
+
+        // Analytics saved when the meeting ended win over the transcript.
+        let saved = analytics::MeetingAnalytics {
+            speakers: Vec::new(),
+            word_timings: true,
+        };
+        save_analytics(&dir.join(analytics::ANALYTICS_FILE), &saved).unwrap();
+        let loaded = saved_meeting_analytics(&dir, "m1").unwrap();
+        assert!(loaded.speakers.is_empty());
+        assert!(loaded.word_timings);
+    }
+
+    #[test]
+    fn meetings_without_chapters_work_them_out() {
+        let dir = meeting_folder("chapters");
+        assert!(saved_chapters(&dir).unwrap().is_empty());
+        save_transcript_turns(&dir.join(transcript_export::TRANSCRIPT_FILE), &turns()).unwrap();
+        // Too short to change topic.
+        assert!(saved_chapters(&dir).unwrap().is_empty());
+        std::fs::write(dir.join(chapters::CHAPTERS_FILE), "not json").unwrap();
+        assert!(saved_chapters(&dir).is_err());
+    }
+}
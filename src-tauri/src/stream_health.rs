@@ -0,0 +1,231 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Latency samples kept per kind; percentiles cover roughly the last few minutes.
+const MAX_LATENCY_SAMPLES: usize = 512;
+/// Sent batches remembered for mapping transcript times back to capture times.
+const MAX_TRACKED_BATCHES: usize = 1_200;
+/// How far sent audio may slip against the recording clock before a new anchor is kept.
+const CLOCK_DRIFT_SECS: f64 = 0.25;
+
+#[derive(Debug, Serialize, Clone, Copy, Default)]
+pub struct LatencyPercentiles {
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LatencyWindow {
+    samples: VecDeque<f64>,
+    count: u64,
+    max_ms: f64,
+}
+
+impl LatencyWindow {
+    fn push(&mut self, latency_ms: f64) {
+        if self.samples.len() == MAX_LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency_ms);
+        self.count += 1;
+        self.max_ms = self.max_ms.max(latency_ms);
+    }
+
+    fn percentiles(&self) -> LatencyPercentiles {
+        let mut sorted: Vec<f64> = self.samples.iter().copied().collect();
+        sorted.sort_by(f64::total_cmp);
+        let at = |p: f64| -> f64 {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+            sorted[idx]
+        };
+        LatencyPercentiles {
+            count: self.count,
+            p50_ms: at(0.5),
+            p90_ms: at(0.9),
+            p99_ms: at(0.99),
+            max_ms: self.max_ms,
+        }
+    }
+}
+
+/// Span of provider audio time covered by one sent batch, with when its
+/// first and last frames left the mixer.
+#[derive(Debug, Clone, Copy)]
+struct SentBatch {
+    audio_start: f64,
+    audio_end: f64,
+    first_frame_at: Instant,
+    sent_at: Instant,
+}
+
+/// Counters for the audio stream sent to the transcription provider.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct StreamHealth {
+    pub batch_ms: u64,
+    pub messages_sent: u64,
+    pub bytes_sent: u64,
+    pub audio_ms_sent: u64,
+    pub muted_frames: u64,
+    /// Times the connection dropped and was opened again mid-recording.
+    pub reconnects: u64,
+    /// How long the oldest frame in each batch waited before it was sent; this
+    /// is the latency cost of batching.
+    pub avg_batch_delay_ms: f64,
+    pub max_batch_delay_ms: f64,
+    /// Audio captured → partial emitted to the UI.
+    pub partial_latency: LatencyPercentiles,
+    /// Audio captured → final emitted to the UI.
+    pub final_latency: LatencyPercentiles,
+    /// WAV copy of the sent audio when the debug dump is enabled.
+    pub audio_dump_path: Option<String>,
+    #[serde(skip)]
+    audio_secs_sent: f64,
+    #[serde(skip)]
+    sent: VecDeque<SentBatch>,
+    /// End of the latest final, in seconds of sent audio.
+    #[serde(skip)]
+    final_end: f64,
+    #[serde(skip)]
+    partial_window: LatencyWindow,
+    #[serde(skip)]
+    final_window: LatencyWindow,
+    #[serde(skip)]
+    started_at: Option<Instant>,
+    /// Wall-clock time of `started_at`.
+    #[serde(skip)]
+    started_wall: Option<DateTime<Utc>>,
+    /// `(stream secs, recording secs)` pairs marking where the sent audio jumps
+    /// ahead of the recording, e.g. after a muted stretch that was never sent.
+    #[serde(skip)]
+    clock_anchors: Vec<(f64, f64)>,
+}
+
+impl StreamHealth {
+    pub fn new(batch_ms: u64) -> Self {
+        Self {
+            batch_ms,
+            started_at: Some(Instant::now()),
+            started_wall: Some(Utc::now()),
+            ..Default::default()
+        }
+    }
+
+    pub fn record_send(&mut self, bytes: usize, audio: Duration, batch_delay: Duration) {
+        let delay_ms = batch_delay.as_secs_f64() * 1000.0;
+        self.messages_sent += 1;
+        self.bytes_sent += bytes as u64;
+        self.audio_ms_sent += audio.as_millis() as u64;
+        self.avg_batch_delay_ms += (delay_ms - self.avg_batch_delay_ms) / self.messages_sent as f64;
+        self.max_batch_delay_ms = self.max_batch_delay_ms.max(delay_ms);
+
+        let sent_at = Instant::now();
+        let audio_start = self.audio_secs_sent;
+        self.audio_secs_sent += audio.as_secs_f64();
+        if self.sent.len() == MAX_TRACKED_BATCHES {
+            self.sent.pop_front();
+        }
+        let first_frame_at = sent_at.checked_sub(batch_delay).unwrap_or(sent_at);
+        self.sent.push_back(SentBatch {
+            audio_start,
+            audio_end: self.audio_secs_sent,
+            first_frame_at,
+            sent_at,
+        });
+
+        if let Some(started_at) = self.started_at {
+            let recording = first_frame_at
+                .saturating_duration_since(started_at)
+                .as_secs_f64();
+            let drifted = self.clock_anchors.last().is_none_or(|(stream, anchored)| {
+                ((recording - audio_start) - (anchored - stream)).abs() > CLOCK_DRIFT_SECS
+            });
+            if drifted {
+                self.clock_anchors.push((audio_start, recording));
+            }
+        }
+    }
+
+    /// Records a partial shown for audio up to `audio_end` seconds into the stream.
+    pub fn record_partial(&mut self, audio_end: f64) {
+        if let Some(latency) = self.latency_for(audio_end) {
+            self.partial_window.push(latency);
+            self.partial_latency = self.partial_window.percentiles();
+        }
+    }
+
+    /// Records a final shown for audio up to `audio_end` seconds into the stream.
+    ///
+    /// Finals never move backwards, so batches before this point are dropped.
+    pub fn record_final(&mut self, audio_end: f64) {
+        self.final_end = self.final_end.max(audio_end);
+        if let Some(latency) = self.latency_for(audio_end) {
+            self.final_window.push(latency);
+            self.final_latency = self.final_window.percentiles();
+        }
+        while self
+            .sent
+            .front()
+            .is_some_and(|batch| batch.audio_end < audio_end)
+        {
+            self.sent.pop_front();
+        }
+    }
+
+    /// How far into the sent audio the transcript is final.
+    pub fn final_end(&self) -> f64 {
+        self.final_end
+    }
+
+    /// Converts a provider timestamp (seconds of sent audio) into seconds since
+    /// the recording started, counting stretches that were never sent.
+    pub fn recording_offset(&self, stream_secs: f64) -> f64 {
+        let idx = self
+            .clock_anchors
+            .partition_point(|(stream, _)| *stream <= stream_secs);
+        match idx.checked_sub(1).map(|idx| self.clock_anchors[idx]) {
+            Some((stream, recording)) => recording + (stream_secs - stream),
+            None => stream_secs,
+        }
+    }
+
+    /// Seconds since the recording started.
+    pub fn elapsed(&self) -> Option<f64> {
+        self.started_at
+            .map(|started_at| started_at.elapsed().as_secs_f64())
+    }
+
+    /// Wall-clock time (RFC 3339) of a point `recording_secs` into the recording.
+    pub fn wall_clock(&self, recording_secs: f64) -> Option<String> {
+        let started = self.started_wall?;
+        let at = started + chrono::Duration::milliseconds((recording_secs * 1000.0) as i64);
+        Some(at.to_rfc3339_opts(SecondsFormat::Millis, true))
+    }
+
+    fn latency_for(&self, audio_end: f64) -> Option<f64> {
+        let idx = self
+            .sent
+            .partition_point(|batch| batch.audio_end < audio_end);
+        let batch = self.sent.get(idx)?;
+        if audio_end < batch.audio_start {
+            return None;
+        }
+
+        let span = batch.audio_end - batch.audio_start;
+        let fraction = if span > 0.0 {
+            (audio_end - batch.audio_start) / span
+        } else {
+            1.0
+        };
+        let captured_at = batch.first_frame_at
+            + (batch.sent_at - batch.first_frame_at).mul_f64(fraction.clamp(0.0, 1.0));
+        Some(captured_at.elapsed().as_secs_f64() * 1000.0)
+    }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "telemetry.json";
+pub const REPORT_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Persisted telemetry preferences. Off unless the user opts in and provides an endpoint.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    #[serde(default)]
+    pub endpoint: Option<String>,
+}
+
+/// Exactly what is uploaded: app/platform identifiers and counters keyed by
+/// fixed names, never transcript text, titles or other user content.
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct TelemetryReport {
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub features: BTreeMap<&'static str, u64>,
+    pub errors: BTreeMap<&'static str, u64>,
+}
+
+impl TelemetryReport {
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Counters accumulated between uploads.
+///
+/// Names are `&'static str` so call sites can only report fixed categories.
+#[derive(Debug, Default)]
+pub struct Telemetry {
+    path: Option<PathBuf>,
+    settings: TelemetrySettings,
+    features: BTreeMap<&'static str, u64>,
+    errors: BTreeMap<&'static str, u64>,
+}
+
+impl Telemetry {
+    pub fn load(path: PathBuf) -> Self {
+        let settings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        Self {
+            path: Some(path),
+            settings,
+            ..Default::default()
+        }
+    }
+
+    pub fn settings(&self) -> &TelemetrySettings {
+        &self.settings
+    }
+
+    pub fn update_settings(&mut self, settings: TelemetrySettings) -> Result<(), String> {
+        if let Some(path) = &self.path {
+            save_settings(path, &settings)?;
+        }
+        if !settings.enabled {
+            self.features.clear();
+            self.errors.clear();
+        }
+        self.settings = settings;
+        Ok(())
+    }
+
+    /// Endpoint to upload to, if the user has opted in.
+    pub fn active_endpoint(&self) -> Option<&str> {
+        if !self.settings.enabled {
+            return None;
+        }
+        self.settings
+            .endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+    }
+
+    pub fn record_feature(&mut self, feature: &'static str) {
+        if self.settings.enabled {
+            *self.features.entry(feature).or_default() += 1;
+        }
+    }
+
+    pub fn record_error(&mut self, category: &'static str) {
+        if self.settings.enabled {
+            *self.errors.entry(category).or_default() += 1;
+        }
+    }
+
+    pub fn preview(&self) -> TelemetryReport {
+        TelemetryReport {
+            app_version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            features: self.features.clone(),
+            errors: self.errors.clone(),
+        }
+    }
+
+    /// Takes the pending report, resetting the counters.
+    pub fn take_report(&mut self) -> TelemetryReport {
+        let report = self.preview();
+        self.features.clear();
+        self.errors.clear();
+        report
+    }
+
+    /// Puts counters back after a failed upload.
+    pub fn restore(&mut self, report: TelemetryReport) {
+        for (name, count) in report.features {
+            *self.features.entry(name).or_default() += count;
+        }
+        for (name, count) in report.errors {
+            *self.errors.entry(name).or_default() += count;
+        }
+    }
+}
+
+fn save_settings(path: &Path, settings: &TelemetrySettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+    let contents = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    std::fs::write(path, contents).map_err(|e| format!("Failed to save telemetry settings: {}", e))
+}
@@ -0,0 +1,124 @@
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+pub const TIMELINE_FILE: &str = "timeline.jsonl";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    RecordingStarted,
+    Connected,
+    Disconnected,
+    Muted,
+    Unmuted,
+    CaptureError,
+    ProviderError,
+    KeywordHit,
+    /// A moment marked to come back to; the detail says how it was marked.
+    Bookmarked,
+    LanguageChanged,
+    /// Settings changed mid-recording; the detail lists which.
+    ConfigChanged,
+    /// System audio started playing to another device; the detail names it.
+    OutputRouteChanged,
+    /// Nothing heard from either source for the watchdog's timeout.
+    SilenceDetected,
+    /// The connection was closed ahead of system sleep.
+    Suspended,
+    /// The user paused the recording; nothing was sent until it resumed.
+    Paused,
+    Resumed,
+    /// The connection dropped mid-recording and was opened again.
+    Reconnected,
+    /// The app restarted mid-recording and the recording carried on in the
+    /// same meeting; the detail says how long the gap was.
+    ResumedAfterRestart,
+    RecordingStopped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimelineEvent {
+    /// Wall-clock time (RFC 3339, UTC).
+    pub at: String,
+    /// Milliseconds since the recording started.
+    pub offset_ms: u64,
+    pub kind: TimelineEventKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Event log for the current recording.
+///
+/// Events are kept in memory and, when the session belongs to a meeting,
+/// appended to that meeting's `timeline.jsonl` as they happen so the log
+/// survives a crash.
+#[derive(Debug)]
+pub struct SessionTimeline {
+    path: Option<PathBuf>,
+    started: Instant,
+    events: Vec<TimelineEvent>,
+}
+
+impl Default for SessionTimeline {
+    fn default() -> Self {
+        Self::start(None)
+    }
+}
+
+impl SessionTimeline {
+    pub fn start(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            started: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, kind: TimelineEventKind, detail: Option<String>) {
+        let event = TimelineEvent {
+            at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            kind,
+            detail,
+        };
+
+        if let Some(path) = &self.path {
+            if let Err(err) = append_event(path, &event) {
+                tracing::warn!("Failed to write timeline event: {}", err);
+            }
+        }
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[TimelineEvent] {
+        &self.events
+    }
+}
+
+fn append_event(path: &Path, event: &TimelineEvent) -> Result<(), String> {
+    let mut line = serde_json::to_string(event).map_err(|e| e.to_string())?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .map_err(|e| e.to_string())
+}
+
+/// Reads a stored timeline, skipping lines that fail to parse (e.g. a torn final write).
+pub fn load(path: &Path) -> Result<Vec<TimelineEvent>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read timeline: {}", e))?;
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
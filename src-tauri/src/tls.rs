@@ -0,0 +1,33 @@
+use crate::settings::TlsSettings;
+use tokio_tungstenite::Connector;
+
+/// TLS connector for a realtime endpoint that wants a client certificate or
+/// is signed by a private CA. The system's trust roots still apply.
+pub fn connector(settings: &TlsSettings) -> Result<Connector, String> {
+    let mut builder = native_tls::TlsConnector::builder();
+    if let (Some(cert_path), Some(key_path)) =
+        (&settings.client_cert_path, &settings.client_key_path)
+    {
+        let identity = native_tls::Identity::from_pkcs8(&read(cert_path)?, &read(key_path)?)
+            .map_err(|e| format!("Invalid client certificate or key: {}", e))?;
+        builder.identity(identity);
+    }
+    if let Some(bundle_path) = &settings.ca_bundle_path {
+        let certificates = native_tls::Certificate::stack_from_pem(&read(bundle_path)?)
+            .map_err(|e| format!("Invalid CA bundle {}: {}", bundle_path, e))?;
+        if certificates.is_empty() {
+            return Err(format!("CA bundle {} has no certificates", bundle_path));
+        }
+        for certificate in certificates {
+            builder.add_root_certificate(certificate);
+        }
+    }
+    let connector = builder
+        .build()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+    Ok(Connector::NativeTls(connector))
+}
+
+fn read(path: &str) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
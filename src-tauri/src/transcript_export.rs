@@ -0,0 +1,247 @@
+use crate::chapters::Chapter;
+use crate::launch;
+use crate::locale::Locale;
+use crate::transcript::{TranscriptTurnPayload, TurnSource};
+use std::fmt::Write;
+
+pub const TRANSCRIPT_FILE: &str = "transcript.json";
+/// Recordings at least this long get a table of contents above their chapters.
+const CONTENTS_MIN_SECS: f64 = 60.0 * 60.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Txt,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Txt => "txt",
+            ExportFormat::Srt => "srt",
+            ExportFormat::Vtt => "vtt",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    /// Whether each turn becomes a timed subtitle cue.
+    pub fn is_subtitles(self) -> bool {
+        matches!(self, ExportFormat::Srt | ExportFormat::Vtt)
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "txt" | "text" => Ok(ExportFormat::Txt),
+            "srt" => Ok(ExportFormat::Srt),
+            "vtt" | "webvtt" => Ok(ExportFormat::Vtt),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(format!(
+                "Unknown export format '{}'; expected txt, srt, vtt or json",
+                other
+            )),
+        }
+    }
+}
+
+/// Narrows an export to the turns heard in `source`. Chapters move to their
+/// first remaining turn, and go when none remain.
+pub fn keep_source(
+    turns: &mut Vec<TranscriptTurnPayload>,
+    chapters: &mut Vec<Chapter>,
+    source: TurnSource,
+) {
+    // How many kept turns come before each turn, and in total.
+    let mut kept_before = Vec::with_capacity(turns.len() + 1);
+    let mut kept = 0;
+    for turn in turns.iter() {
+        kept_before.push(kept);
+        kept += usize::from(turn.source == Some(source));
+    }
+    kept_before.push(kept);
+    let starts: Vec<usize> = chapters
+        .iter()
+        .map(|chapter| kept_before[chapter.start_turn.min(turns.len())])
+        .collect();
+    let mut index = 0;
+    chapters.retain_mut(|chapter| {
+        chapter.start_turn = starts[index];
+        let end = starts.get(index + 1).copied().unwrap_or(kept);
+        index += 1;
+        chapter.start_turn < end
+    });
+    turns.retain(|turn| turn.source == Some(source));
+}
+
+/// Renders `turns` as `format`. Text exports put each chapter's title above
+/// its first turn; the other formats leave chapters out.
+pub fn render(
+    turns: &[TranscriptTurnPayload],
+    chapters: &[Chapter],
+    format: ExportFormat,
+    locale: Locale,
+) -> Result<String, String> {
+    match format {
+        ExportFormat::Txt => Ok(render_text(turns, chapters, locale)),
+        ExportFormat::Srt | ExportFormat::Vtt => render_cues(turns, locale, format),
+        ExportFormat::Json => serde_json::to_string_pretty(turns).map_err(|e| e.to_string()),
+    }
+}
+
+fn render_text(turns: &[TranscriptTurnPayload], chapters: &[Chapter], locale: Locale) -> String {
+    let mut out = contents(turns, chapters);
+    for (index, turn) in turns.iter().enumerate() {
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        push_heading(&mut out, chapters, index);
+        if let Some(prefix) = turn.prefix(locale) {
+            out.push_str(&prefix);
+        }
+        out.push_str(&turn.text);
+    }
+    out.push('\n');
+    out
+}
+
+/// Plain text with each turn preceded by a link to its moment in the recording.
+/// Turns without a recording offset are written without one.
+pub fn render_linked_text(
+    turns: &[TranscriptTurnPayload],
+    chapters: &[Chapter],
+    meeting_id: &str,
+    locale: Locale,
+) -> String {
+    let mut out = contents(turns, chapters);
+    for (index, turn) in turns.iter().enumerate() {
+        if !out.is_empty() {
+            out.push_str("\n\n");
+        }
+        push_heading(&mut out, chapters, index);
+        if let Some(offset) = turn.offset {
+            let _ = write!(
+                out,
+                "[{}]({}) ",
+                clock_timestamp(offset),
+                launch::citation_url(meeting_id, offset)
+            );
+        }
+        if let Some(prefix) = turn.prefix(locale) {
+            out.push_str(&prefix);
+        }
+        out.push_str(&turn.text);
+    }
+    out.push('\n');
+    out
+}
+
+/// One SRT or WebVTT cue per turn, led by its speaker. Turns recorded
+/// without timings cannot be placed and are an error; typed notes were never
+/// said and get no cue.
+fn render_cues(
+    turns: &[TranscriptTurnPayload],
+    locale: Locale,
+    format: ExportFormat,
+) -> Result<String, String> {
+    let vtt = format == ExportFormat::Vtt;
+    let mut out = String::new();
+    if vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (index, turn) in turns.iter().filter(|turn| !turn.note).enumerate() {
+        let (Some(start), Some(end)) = (turn.start, turn.end) else {
+            return Err("Transcript has no timing information; export it as txt instead".into());
+        };
+        let _ = writeln!(out, "{}", index + 1);
+        let _ = writeln!(
+            out,
+            "{} --> {}",
+            cue_timestamp(start, vtt),
+            cue_timestamp(end, vtt)
+        );
+        let mut text = String::new();
+        if let Some(speaker) = &turn.speaker {
+            text.push_str(&locale.speaker_label(speaker));
+        }
+        text.push_str(&turn.text);
+        if vtt {
+            text = escape_vtt(&text);
+        }
+        let _ = writeln!(out, "{}", text);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// WebVTT cue text treats `<` and `&` as markup.
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// SRT subtitles converted to WebVTT, for providers that only write SRT.
+pub fn srt_to_vtt(srt: &str) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for line in srt.lines() {
+        if line.contains(" --> ") {
+            out.push_str(&line.replace(',', "."));
+        } else {
+            out.push_str(&escape_vtt(line));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Chapter list for recordings of an hour or more; empty otherwise.
+fn contents(turns: &[TranscriptTurnPayload], chapters: &[Chapter]) -> String {
+    let length = turns
+        .iter()
+        .filter_map(|turn| turn.end.or(turn.offset))
+        .fold(0.0, f64::max);
+    if chapters.len() < 2 || length < CONTENTS_MIN_SECS {
+        return String::new();
+    }
+    let mut out = String::from("Contents\n");
+    for chapter in chapters {
+        match chapter.offset {
+            Some(offset) => {
+                let _ = write!(out, "\n- {} {}", clock_timestamp(offset), chapter.title);
+            }
+            None => {
+                let _ = write!(out, "\n- {}", chapter.title);
+            }
+        }
+    }
+    out
+}
+
+fn push_heading(out: &mut String, chapters: &[Chapter], turn: usize) {
+    if let Some(chapter) = chapters.iter().find(|c| c.start_turn == turn) {
+        let _ = write!(out, "## {}\n\n", chapter.title);
+    }
+}
+
+pub fn clock_timestamp(seconds: f64) -> String {
+    let secs = seconds.max(0.0) as u64;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// `00:01:02,500` for SRT, `00:01:02.500` for WebVTT.
+fn cue_timestamp(seconds: f64, vtt: bool) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        if vtt { '.' } else { ',' },
+        millis % 1000
+    )
+}
@@ -0,0 +1,1844 @@
+use crate::audio::{AudioBatcher, AudioMixer, TARGET_SAMPLE_RATE};
+use crate::consent::{self, MeetingConsent};
+use crate::session::WakeLockGuard;
+use crate::session_config::{LiveTuning, SessionChange};
+use crate::settings::{
+    AdditionalVocabularyEntry, FormattingSettings, OperatingPoint, TranscriptionSettings,
+    WebSocketSettings,
+};
+use crate::state::{AppState, SessionState, SessionWindow, Suspension, MAIN_SESSION};
+use crate::storage::save_transcript_turns;
+use crate::stream_health::StreamHealth;
+use crate::timeline::{SessionTimeline, TimelineEventKind};
+use crate::transcript::{Transcript, TranscriptTurnPayload, TurnSource, TurnWord};
+use crate::{
+    agenda, analytics, audio, audio_dump, avoid_list, captions, costs, cues, events, formatting,
+    interrupted, interview, language_check, levels, live_transcript, power, projects, recent_audio,
+    screen_share, settings, silence, speaker_ids, tls,
+};
+use futures_util::{SinkExt, StreamExt};
+use jilu_core::provider::{
+    self, EventDecoder, FinalResult, ProviderEvent, ProviderKind, RealtimeProvider, SessionConfig,
+};
+use jilu_core::simulator::Simulator;
+use jilu_core::speechmatics::{
+    self, AudioFormat, EndOfStreamMessage, KnownSpeaker, RtAuth, RtReader, RtWriter,
+    SpeakerDiarizationConfig, SpeakersResultEntry, SpeakersResultMessage, SpeechmaticsConfig,
+    TranscriptionConfig,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+/// How long results still in flight are awaited once the audio has ended. The
+/// frontend gives up on `recording-ended` after 10s, so this stays well below.
+const FINAL_RESULTS_TIMEOUT: Duration = Duration::from_secs(5);
+/// Attempts to reconnect, with a doubling wait, before a dropped connection
+/// ends the recording.
+const MAX_RECONNECT_ATTEMPTS: u32 = 6;
+const RECONNECT_BASE_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(15);
+/// Sent audio kept for resending what a dropped connection had not finalized.
+const REPLAY_SECS: f64 = 20.0;
+/// Audio captured while reconnecting is held for at most this long; the
+/// oldest goes first.
+const HELD_AUDIO_SECS: f64 = 60.0;
+/// How often a recording checks battery and thermal state.
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Seconds of a speaker's speech `enroll_current_speaker` uses by default.
+const DEFAULT_ENROLL_SECS: f64 = 30.0;
+/// Any less speech is too little to tell a voice by.
+const MIN_ENROLL_SECS: f64 = 5.0;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRecordingArgs {
+    #[serde(alias = "apiKey")]
+    pub api_key: String,
+    #[serde(alias = "additionalVocab", alias = "additional_vocab")]
+    pub additional_vocab: Option<Vec<AdditionalVocabularyEntry>>,
+    #[serde(alias = "speakerProfile", alias = "speaker_profile")]
+    pub speaker_profile: Option<SpeakerProfileArg>,
+    /// Teammates' enrolled voices, so they are labelled by name too.
+    #[serde(default, alias = "speakerProfiles", alias = "speaker_profiles")]
+    pub speaker_profiles: Option<Vec<SpeakerProfileArg>>,
+    #[serde(default, alias = "rtUrl", alias = "rt_url")]
+    pub rt_url: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(
+        default,
+        alias = "partialMinIntervalMs",
+        alias = "partial_min_interval_ms"
+    )]
+    pub partial_min_interval_ms: Option<u64>,
+    #[serde(default, alias = "audioBatchMs", alias = "audio_batch_ms")]
+    pub audio_batch_ms: Option<u64>,
+    /// Speechmatics accuracy tier; defaults to the transcription settings.
+    #[serde(default, alias = "operatingPoint", alias = "operating_point")]
+    pub operating_point: Option<OperatingPoint>,
+    /// Seconds the provider may hold a word back, between 0.7 and 4.
+    #[serde(default, alias = "maxDelay", alias = "max_delay")]
+    pub max_delay: Option<f32>,
+    #[serde(default, alias = "enablePartials", alias = "enable_partials")]
+    pub enable_partials: Option<bool>,
+    /// Words recognised with less confidence than this, from 0 to 1, are
+    /// marked for review; defaults to the transcription settings.
+    #[serde(default, alias = "confidenceThreshold", alias = "confidence_threshold")]
+    pub confidence_threshold: Option<f64>,
+    #[serde(default, alias = "meetingId", alias = "meeting_id")]
+    pub meeting_id: Option<String>,
+    #[serde(default, alias = "meetingTitle", alias = "meeting_title")]
+    pub meeting_title: Option<String>,
+    #[serde(default, alias = "debugAudioDump", alias = "debug_audio_dump")]
+    pub debug_audio_dump: Option<bool>,
+    /// Detect the local user's questions and segment the transcript into Q&A pairs.
+    #[serde(default, alias = "interviewMode", alias = "interview_mode")]
+    pub interview_mode: Option<bool>,
+    /// Recording preset to start from; explicit arguments still win over it.
+    #[serde(default, alias = "presetId", alias = "preset_id")]
+    pub preset_id: Option<String>,
+    /// Records alongside the main session under this id; defaults to the main session.
+    #[serde(default, alias = "sessionId", alias = "session_id")]
+    pub session_id: Option<String>,
+    /// Calendar event being recorded, refused if the avoid-list covers it.
+    #[serde(default, alias = "calendarEvent", alias = "calendar_event")]
+    pub calendar_event: Option<avoid_list::EventDetails>,
+    /// Started by a link, script or automation rather than by hand; refused
+    /// during quiet hours.
+    #[serde(default)]
+    pub automatic: Option<bool>,
+    /// Saves the audio as well; defaults to the audio recording settings.
+    #[serde(default, alias = "recordAudio", alias = "record_audio")]
+    pub record_audio: Option<settings::AudioRecordingSettings>,
+    /// Sends the microphone and system audio as separate channels with these
+    /// labels, in that order, and labels turns by channel. Defaults to the
+    /// separate channel settings.
+    #[serde(default, alias = "channelLabels", alias = "channel_labels")]
+    pub channel_labels: Option<Vec<String>>,
+    /// Set by `resume_interrupted_session` to carry on the meeting's saved transcript.
+    #[serde(skip)]
+    pub interrupted: Option<interrupted::InterruptedSession>,
+}
+
+/// Per-recording settings: stored `Settings`, overridden by anything in `StartRecordingArgs`.
+pub struct TranscriptionOptions {
+    pub provider: ProviderKind,
+    /// The provider's key.
+    pub api_key: String,
+    pub additional_vocab: Vec<AdditionalVocabularyEntry>,
+    pub speaker_profile: Option<SpeakerProfileArg>,
+    pub speaker_profiles: Vec<SpeakerProfileArg>,
+    /// Other enrolled voices, from the meeting's project.
+    pub roster: Vec<projects::RosterSpeaker>,
+    pub rt_url: Option<String>,
+    pub auth: RtAuth,
+    /// Client certificate and extra CAs for `rt_url`, from the recording preset.
+    pub tls: Option<settings::TlsSettings>,
+    pub websocket: WebSocketSettings,
+    pub language: String,
+    pub domain: Option<String>,
+    pub operating_point: OperatingPoint,
+    pub max_delay: f32,
+    pub partials: bool,
+    pub confidence_threshold: Option<f64>,
+    pub diarization: bool,
+    pub max_speakers: Option<u32>,
+    pub tuning: Arc<Mutex<LiveTuning>>,
+    pub audio_batch_ms: u64,
+    /// Lengthen batches while saving power; see `power::PowerPolicy`.
+    pub power_aware: bool,
+    pub stop_drain_timeout: Duration,
+    pub audio_dump: Option<audio_dump::AudioDump>,
+    pub interview: Option<interview::InterviewConfig>,
+    pub formatting: FormattingSettings,
+    pub silence_watchdog: settings::SilenceWatchdogSettings,
+    pub speaker_cues: settings::SpeakerCueSettings,
+    pub live_transcript: Option<live_transcript::LiveTranscriptFile>,
+    /// Runs for as long as the recording does.
+    pub captions: Option<captions::CaptionOutput>,
+    /// Where the meeting's transcript is kept; written once more with the
+    /// last results before `recording-ended`.
+    pub transcript_path: Option<std::path::PathBuf>,
+    /// Rates for the estimated cost saved next to the transcript.
+    pub costs: settings::CostSettings,
+    /// Local stand-in for the provider that `rt_url` points at, when simulating.
+    pub simulator: Option<Simulator>,
+    /// Microphone and system audio labels, when they are sent as separate
+    /// channels. The mixer must then come from `AudioMixer::spawn_split`.
+    pub channel_labels: Option<Vec<String>>,
+}
+
+impl TranscriptionOptions {
+    pub fn resolve(args: StartRecordingArgs, settings: &TranscriptionSettings) -> Self {
+        let additional_vocab = args
+            .additional_vocab
+            .unwrap_or_else(|| settings.additional_vocab.clone());
+        let partial_min_interval = Duration::from_millis(
+            args.partial_min_interval_ms
+                .unwrap_or(settings.partial_min_interval_ms),
+        );
+        Self {
+            provider: settings.provider,
+            api_key: args.api_key,
+            tuning: Arc::new(Mutex::new(LiveTuning::new(
+                &additional_vocab,
+                partial_min_interval,
+            ))),
+            additional_vocab,
+            speaker_profile: args.speaker_profile,
+            speaker_profiles: args.speaker_profiles.unwrap_or_default(),
+            roster: Vec::new(),
+            rt_url: args.rt_url.or_else(|| settings.endpoint()),
+            auth: settings.auth,
+            tls: None,
+            websocket: settings.websocket,
+            language: args.language.unwrap_or_else(|| settings.language.clone()),
+            domain: settings.domain.clone(),
+            operating_point: args.operating_point.unwrap_or(settings.operating_point),
+            max_delay: args.max_delay.unwrap_or(settings.max_delay),
+            partials: args.enable_partials.unwrap_or(settings.enable_partials),
+            confidence_threshold: args.confidence_threshold.or(settings.confidence_threshold),
+            diarization: true,
+            max_speakers: None,
+            audio_batch_ms: args.audio_batch_ms.unwrap_or(settings.audio_batch_ms),
+            power_aware: settings.power_aware_batching,
+            stop_drain_timeout: Duration::from_millis(settings.stop_drain_timeout_ms),
+            audio_dump: None,
+            interview: None,
+            formatting: settings.formatting.clone(),
+            silence_watchdog: settings::SilenceWatchdogSettings::default(),
+            speaker_cues: settings::SpeakerCueSettings::default(),
+            live_transcript: None,
+            captions: None,
+            transcript_path: None,
+            costs: settings.costs.clone(),
+            simulator: None,
+            channel_labels: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerProfileArg {
+    pub label: String,
+    #[serde(alias = "speakerIdentifiers")]
+    pub speaker_identifiers: Vec<String>,
+}
+
+/// The enrolled voices to listen for, each label once. Labels are compared
+/// ignoring case and the first spelling is kept; identifiers given under the
+/// same label are pooled, so someone enrolled twice is still one speaker and
+/// their turns merge.
+fn known_speakers(profiles: impl IntoIterator<Item = (String, Vec<String>)>) -> Vec<KnownSpeaker> {
+    let mut speakers: Vec<KnownSpeaker> = Vec::new();
+    for (label, identifiers) in profiles {
+        let label = label.trim();
+        if label.is_empty() {
+            continue;
+        }
+        let index = match speakers
+            .iter()
+            .position(|speaker| speaker.label.eq_ignore_ascii_case(label))
+        {
+            Some(index) => index,
+            None => {
+                speakers.push(KnownSpeaker {
+                    label: label.to_string(),
+                    speaker_identifiers: Vec::new(),
+                });
+                speakers.len() - 1
+            }
+        };
+        let known = &mut speakers[index].speaker_identifiers;
+        for identifier in identifiers {
+            if !known.contains(&identifier) {
+                known.push(identifier);
+            }
+        }
+    }
+    speakers.retain(|speaker| !speaker.speaker_identifiers.is_empty());
+    speakers
+}
+
+/// Payload of `transcript-update`.
+///
+/// Partial updates carry the words heard so far in `text`. Final updates are
+/// deltas: `turns` holds only the turns that changed or were appended,
+/// starting at `turn_offset` in the full turn list, and `delta_text` is the
+/// rendering of just those turns. Use `get_full_transcript` to resync.
+#[derive(Debug, Serialize, Clone)]
+pub(crate) struct TranscriptUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delta_text: Option<String>,
+    is_partial: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turns: Option<Vec<TranscriptTurnPayload>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_turns: Option<usize>,
+}
+
+impl TranscriptUpdate {
+    fn partial(text: String) -> Self {
+        Self {
+            text: Some(text),
+            delta_text: None,
+            is_partial: true,
+            turns: None,
+            turn_offset: None,
+            total_turns: None,
+        }
+    }
+
+    /// The turns from `offset` on. While the screen is shared the window only
+    /// learns that text arrived; it catches up from `get_full_transcript`
+    /// afterwards.
+    pub(crate) fn from_turn(state: &AppState, transcript: &Transcript, offset: usize) -> Self {
+        match screen_share::transcript_placeholder(state) {
+            Some(placeholder) => Self::partial(placeholder),
+            None => Self {
+                text: None,
+                delta_text: Some(transcript.text_from(offset).to_string()),
+                is_partial: false,
+                turns: Some(transcript.turns()[offset..].to_vec()),
+                turn_offset: Some(offset),
+                total_turns: Some(transcript.turns().len()),
+            },
+        }
+    }
+}
+
+/// Payload of `recording-reconnecting`.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ReconnectAttempt {
+    attempt: u32,
+    max_attempts: u32,
+    delay_ms: u64,
+}
+
+/// Audio a new connection may need after the last one dropped: the last of
+/// what was sent, and what was captured while there was no connection.
+struct ReplayBuffer {
+    channels: usize,
+    /// Keeps a longer stretch of what was sent, for enrolling voices.
+    recent_audio: Arc<Mutex<recent_audio::RecentAudio>>,
+    /// `(start, end, batch)` in seconds of sent audio, oldest first.
+    sent: VecDeque<(f64, f64, Vec<u8>)>,
+    sent_end: f64,
+    /// Batches not sent yet, with when their first frame was captured.
+    held: VecDeque<(Vec<u8>, Instant)>,
+    held_secs: f64,
+}
+
+impl ReplayBuffer {
+    fn new(channels: usize, recent_audio: Arc<Mutex<recent_audio::RecentAudio>>) -> Self {
+        Self {
+            channels: channels.max(1),
+            recent_audio,
+            sent: VecDeque::new(),
+            sent_end: 0.0,
+            held: VecDeque::new(),
+            held_secs: 0.0,
+        }
+    }
+
+    fn secs(&self, batch: &[u8]) -> f64 {
+        audio::pcm_duration(batch.len() / self.channels).as_secs_f64()
+    }
+
+    fn record_sent(&mut self, batch: Vec<u8>) {
+        self.recent_audio.lock().push_audio(&batch);
+        let start = self.sent_end;
+        self.sent_end += self.secs(&batch);
+        self.sent.push_back((start, self.sent_end, batch));
+        while self
+            .sent
+            .front()
+            .is_some_and(|(start, _, _)| self.sent_end - start > REPLAY_SECS)
+        {
+            self.sent.pop_front();
+        }
+    }
+
+    /// Where resending starts to cover everything after `final_end`, and the
+    /// batches to resend from there.
+    fn after(&self, final_end: f64) -> (f64, Vec<Vec<u8>>) {
+        let tail = self.sent.iter().filter(|(_, end, _)| *end > final_end);
+        let start = tail
+            .clone()
+            .next()
+            .map_or(self.sent_end, |(start, _, _)| *start);
+        (start, tail.map(|(_, _, batch)| batch.clone()).collect())
+    }
+
+    fn hold(&mut self, batch: Vec<u8>, captured_at: Instant) {
+        self.held_secs += self.secs(&batch);
+        self.held.push_back((batch, captured_at));
+        while self.held_secs > HELD_AUDIO_SECS {
+            let Some((dropped, _)) = self.held.pop_front() else {
+                break;
+            };
+            self.held_secs -= self.secs(&dropped);
+        }
+    }
+
+    fn take_held(&mut self) -> Option<(Vec<u8>, Instant)> {
+        let (batch, captured_at) = self.held.pop_front()?;
+        self.held_secs -= self.secs(&batch);
+        Some((batch, captured_at))
+    }
+
+    fn return_held(&mut self, batch: Vec<u8>, captured_at: Instant) {
+        self.held_secs += self.secs(&batch);
+        self.held.push_front((batch, captured_at));
+    }
+}
+
+/// Coalesces partial transcripts so the webview sees at most one per interval,
+/// always the most recent one.
+struct PartialThrottle {
+    min_interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<String>,
+}
+
+impl PartialThrottle {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last_emit: None,
+            pending: None,
+        }
+    }
+
+    /// Returns the text if it may be emitted now, otherwise holds it as pending.
+    fn offer(&mut self, text: String) -> Option<String> {
+        let now = Instant::now();
+        match self.last_emit {
+            Some(last) if now.duration_since(last) < self.min_interval => {
+                self.pending = Some(text);
+                None
+            }
+            _ => {
+                self.last_emit = Some(now);
+                self.pending = None;
+                Some(text)
+            }
+        }
+    }
+
+    fn deadline(&self) -> Option<Instant> {
+        self.pending.as_ref()?;
+        self.last_emit.map(|last| last + self.min_interval)
+    }
+
+    fn take_pending(&mut self) -> Option<String> {
+        let text = self.pending.take()?;
+        self.last_emit = Some(Instant::now());
+        Some(text)
+    }
+
+    fn discard(&mut self) {
+        self.pending = None;
+    }
+}
+
+async fn sleep_until_opt(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// What a result reader needs; cloned for each recognition session of a recording.
+#[derive(Clone)]
+struct ResultReader {
+    window: SessionWindow,
+    transcript: Arc<Mutex<Transcript>>,
+    timeline: Arc<Mutex<SessionTimeline>>,
+    health: Arc<Mutex<StreamHealth>>,
+    agenda: Arc<Mutex<agenda::Agenda>>,
+    speaking: Arc<Mutex<analytics::SpeakingTracker>>,
+    /// Tells whether the microphone or system audio carried a turn.
+    levels: Arc<Mutex<levels::LevelRecorder>>,
+    /// Where each speaker's words fall in the sent audio, for enrolling them.
+    recent_audio: Arc<Mutex<recent_audio::RecentAudio>>,
+    /// Empty unless the sources go out as separate channels; the microphone's
+    /// label comes first.
+    channel_labels: Vec<String>,
+    question_tracker: Option<Arc<Mutex<interview::QuestionTracker>>>,
+    /// Shared by every recognition session, and started over on a language switch.
+    language_check: Arc<Mutex<language_check::LanguageCheck>>,
+    tuning: Arc<Mutex<LiveTuning>>,
+    formatting: FormattingSettings,
+    /// Words below it are marked `low_confidence`.
+    confidence_threshold: Option<f64>,
+    /// Shared by every recognition session, so a language switch is not a speaker change.
+    speaker_cues: Option<Arc<Mutex<cues::SpeakerCues>>>,
+    live_transcript: Arc<Mutex<Option<live_transcript::LiveTranscriptFile>>>,
+    captions: Option<captions::CaptionFeed>,
+    /// The meeting's consent file, read again with each final result since
+    /// attendees can decline mid-meeting.
+    consent_path: Option<std::path::PathBuf>,
+    /// Consent as last read; `None` while it can't be, which keeps speech off
+    /// the captions and out of the live transcript file.
+    consent: Arc<Mutex<Option<MeetingConsent>>>,
+    /// Where `SpeakersResult` identifiers are saved, when recording into a meeting.
+    speakers_path: Option<std::path::PathBuf>,
+    /// Latest acknowledged audio message; each session gets its own channel.
+    audio_acks: Option<tokio::sync::watch::Sender<u64>>,
+    /// Whether the provider acknowledges audio, or only its results show
+    /// that the connection is alive.
+    acknowledges_audio: bool,
+    /// Seconds of audio sent before this session started. Provider timings
+    /// restart at zero for each session, so this is added to them.
+    time_offset: f64,
+}
+
+/// A recognition session's socket, the task reading its results and the
+/// audio messages it has acknowledged.
+type Recognition = (
+    RtWriter,
+    tauri::async_runtime::JoinHandle<()>,
+    tokio::sync::watch::Receiver<u64>,
+);
+
+/// Connects to the provider, starts recognition and spawns the task that
+/// reads results into the transcript.
+async fn open_recognition(
+    provider: &dyn RealtimeProvider,
+    config: &SessionConfig,
+    reader: ResultReader,
+) -> Result<Recognition, String> {
+    let (write, read) = provider.connect(config).await?;
+    let (acks_tx, acks) = tokio::sync::watch::channel(0);
+    let reader = ResultReader {
+        audio_acks: Some(acks_tx),
+        ..reader
+    };
+    let read_handle = tauri::async_runtime::spawn(reader.run(read, provider.decoder(config)));
+    Ok((write, read_handle, acks))
+}
+
+/// Waits until the provider has acknowledged audio message `seq_no`, giving
+/// up after `timeout`. Returns whether it caught up.
+async fn wait_for_audio_acks(
+    acks: &mut tokio::sync::watch::Receiver<u64>,
+    seq_no: u64,
+    timeout: Duration,
+) -> bool {
+    tokio::time::timeout(timeout, acks.wait_for(|acked| *acked >= seq_no))
+        .await
+        .is_ok_and(|caught_up| caught_up.is_ok())
+}
+
+impl ResultReader {
+    fn shift(&self, span: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        span.map(|(start, end)| (start + self.time_offset, end + self.time_offset))
+    }
+
+    /// The channel `turn` came in on, or else whichever source was louder
+    /// while it was spoken.
+    fn turn_source(&self, turn: &TranscriptTurnPayload) -> Option<TurnSource> {
+        let channel = self
+            .channel_labels
+            .iter()
+            .position(|label| turn.speaker.as_deref() == Some(label.as_str()));
+        if let Some(channel) = channel {
+            return Some(match channel {
+                0 => TurnSource::Mic,
+                _ => TurnSource::System,
+            });
+        }
+        let (start, end) = turn.offset.zip(turn.end_offset)?;
+        self.levels.lock().dominant_source(start, end)
+    }
+
+    fn emit_partial(&self, text: String) {
+        if let Some(captions) = &self.captions {
+            // Partials have no speaker yet, so nobody's may be left out.
+            let shown = self
+                .consent
+                .lock()
+                .as_ref()
+                .is_some_and(|consent| !consent.excludes_anyone());
+            if shown {
+                captions.set_partial(&text);
+            }
+        }
+        let text = screen_share::transcript_placeholder(&self.window.app_state()).unwrap_or(text);
+        let _ = self
+            .window
+            .emit(events::TRANSCRIPT_UPDATE, TranscriptUpdate::partial(text));
+    }
+
+    /// Keeps the identifiers of this session's speakers, so unknown voices
+    /// can be enrolled after the meeting.
+    fn save_speakers(&self, entries: Vec<SpeakersResultEntry>) {
+        let speakers = speaker_ids::from_entries(entries);
+        if speakers.is_empty() {
+            return;
+        }
+        if let Some(path) = &self.speakers_path {
+            if let Err(err) = speaker_ids::append(path, &speakers) {
+                warn!("{}", err);
+            }
+        }
+        let _ = self.window.emit(events::SPEAKERS_RESULT, speakers);
+    }
+
+    /// Takes in a final result: the transcript and everything that follows
+    /// along with it.
+    fn apply_final(&self, mut result: FinalResult) {
+        // Read before taking the transcript, so the file is not read under its lock.
+        let consent = load_consent(self.consent_path.as_deref());
+        self.consent.lock().clone_from(&consent);
+        let mut transcript = self.transcript.lock();
+        // Speakers renamed during the recording keep their new name.
+        for word in &mut result.words {
+            if let Some(name) = word
+                .speaker
+                .as_deref()
+                .and_then(|speaker| transcript.speaker_name(speaker))
+            {
+                word.speaker = Some(name.to_string());
+            }
+        }
+        // Transcript::append only touches the last turn or pushes a new
+        // one, so the first index it reports is the lowest changed.
+        let mut first_changed: Option<usize> = None;
+        let mut covered_items = Vec::new();
+        let mut speaker_cues = Vec::new();
+
+        // Word by word, before formatting drops any fillers.
+        {
+            let mut speaking = self.speaking.lock();
+            let mut recent_audio = self.recent_audio.lock();
+            for word in &result.words {
+                if let Some((start, end)) = self.shift(word.span) {
+                    speaking.observe(word.speaker.as_deref(), &word.text, start, end);
+                    if let Some(speaker) = word.speaker.as_deref() {
+                        recent_audio.push_word(speaker, start, end);
+                    }
+                }
+            }
+        }
+
+        let keywords = self.tuning.lock().keywords.clone();
+        for run in result.speaker_runs() {
+            let text = formatting::apply(
+                &run.text,
+                &self.formatting,
+                transcript.starts_sentence(run.speaker),
+            );
+            let span = self.shift(run.span);
+            if let Some(idx) = transcript.append_at(run.speaker, &text, span) {
+                first_changed.get_or_insert(idx);
+                transcript.add_words(
+                    idx,
+                    run.words
+                        .iter()
+                        .filter_map(|word| TurnWord::from_word(word, self.confidence_threshold)),
+                );
+                if let Some(cues) = &self.speaker_cues {
+                    speaker_cues.extend(cues.lock().observe(run.speaker));
+                }
+                if let Some((start, end)) = span {
+                    let health = self.health.lock();
+                    let start = health.recording_offset(start);
+                    let end = health.recording_offset(end);
+                    transcript.mark_offset(idx, start, health.wall_clock(start));
+                    transcript.mark_end(idx, end, health.wall_clock(end));
+                }
+                let source = self.turn_source(&transcript.turns()[idx]);
+                transcript.set_source(idx, source);
+            }
+            record_keyword_hits(&self.timeline, &keywords, &text);
+            covered_items.extend(
+                self.agenda
+                    .lock()
+                    .observe(&text, span.map(|(start, _)| start)),
+            );
+        }
+
+        if first_changed.is_none() {
+            if let Some(meta_text) = result.transcript.as_deref() {
+                let cleaned = formatting::apply(
+                    meta_text,
+                    &self.formatting,
+                    transcript.starts_sentence(None),
+                );
+                if !cleaned.is_empty() {
+                    if let Some(idx) = transcript.append(None, &cleaned) {
+                        first_changed.get_or_insert(idx);
+                    }
+                }
+            }
+        }
+
+        let mismatch = self.language_check.lock().observe(
+            &result.words,
+            self.time_offset,
+            transcript.locale(),
+        );
+        if let Some(mismatch) = mismatch {
+            warn!(
+                language = %mismatch.language,
+                suggested = ?mismatch.suggested_language,
+                "Audio may not be in the transcription language"
+            );
+            let _ = self
+                .window
+                .emit(events::LANGUAGE_MISMATCH_SUSPECTED, mismatch);
+        }
+
+        let Some(offset) = first_changed else {
+            return;
+        };
+        if let (Some(captions), Some(last)) = (&self.captions, transcript.turns().last()) {
+            match &consent {
+                Some(consent) if !consent.excludes(last) => {
+                    captions.set_final(last, transcript.locale())
+                }
+                _ => captions.clear(),
+            }
+        }
+        write_live_transcript(
+            &self.live_transcript,
+            transcript.turns(),
+            false,
+            consent.as_ref(),
+        );
+        let update = TranscriptUpdate::from_turn(&self.window.app_state(), &transcript, offset);
+        let questions = self
+            .question_tracker
+            .as_ref()
+            .map(|tracker| tracker.lock().completed(transcript.turns()))
+            .unwrap_or_default();
+        drop(transcript);
+        let _ = self.window.emit(events::TRANSCRIPT_UPDATE, update);
+        for question in questions {
+            let _ = self.window.emit(events::INTERVIEW_QUESTION, question);
+        }
+        for item in covered_items {
+            let _ = self.window.emit(events::AGENDA_ITEM_COVERED, item);
+        }
+        if let Some(cues) = &self.speaker_cues {
+            for cue in speaker_cues {
+                cues.lock().play(self.window.window.app_handle(), &cue);
+                let _ = self.window.emit(events::SPEAKER_CUE, cue);
+            }
+        }
+        if let Some(end) = result.end.map(|end| end + self.time_offset) {
+            self.health.lock().record_final(end);
+        }
+    }
+
+    async fn run(self, mut read: RtReader, mut decoder: Box<dyn EventDecoder>) {
+        let mut partials = PartialThrottle::new(self.tuning.lock().partial_min_interval);
+        // Audio end time of the partial held back by the throttle.
+        let mut pending_partial_end: Option<f64> = None;
+        let mut finished = false;
+        let mut events = Vec::new();
+        decoder.opened(&mut events);
+        loop {
+            for event in events.drain(..) {
+                match event {
+                    ProviderEvent::Error(error) => {
+                        self.window
+                            .app_state()
+                            .services
+                            .telemetry
+                            .lock()
+                            .record_error("provider");
+                        self.timeline
+                            .lock()
+                            .record(TimelineEventKind::ProviderError, Some(error.clone()));
+                        let _ = self.window.emit(events::RECORDING_ERROR, error);
+                    }
+                    ProviderEvent::AudioAcked(seq_no) => {
+                        if let Some(acks) = &self.audio_acks {
+                            acks.send_replace(seq_no);
+                        }
+                    }
+                    ProviderEvent::Started => {
+                        self.timeline
+                            .lock()
+                            .record(TimelineEventKind::Connected, None);
+                    }
+                    ProviderEvent::Partial { text, end } => {
+                        let sentence_start = self.transcript.lock().ends_sentence();
+                        let text = formatting::apply(&text, &self.formatting, sentence_start);
+                        if text.is_empty() {
+                            continue;
+                        }
+                        let audio_end = end.map(|end| end + self.time_offset);
+                        partials.min_interval = self.tuning.lock().partial_min_interval;
+                        if let Some(text) = partials.offer(text) {
+                            self.emit_partial(text);
+                            pending_partial_end = None;
+                            if let Some(end) = audio_end {
+                                self.health.lock().record_partial(end);
+                            }
+                        } else {
+                            pending_partial_end = audio_end;
+                        }
+                    }
+                    ProviderEvent::Final(result) => {
+                        // A final supersedes whatever partial is still queued.
+                        partials.discard();
+                        pending_partial_end = None;
+                        self.apply_final(result);
+                        // Without acknowledgements, results are the sign the
+                        // connection is alive.
+                        if let (Some(acks), false) = (&self.audio_acks, self.acknowledges_audio) {
+                            acks.send_modify(|_| {});
+                        }
+                    }
+                    ProviderEvent::Speakers(speakers) => self.save_speakers(speakers),
+                    ProviderEvent::Finished => finished = true,
+                }
+            }
+            if finished {
+                break;
+            }
+
+            let flush_at = partials.deadline();
+            let msg = tokio::select! {
+                msg = read.next() => msg,
+                _ = sleep_until_opt(flush_at) => {
+                    if let Some(text) = partials.take_pending() {
+                        self.emit_partial(text);
+                        if let Some(end) = pending_partial_end.take() {
+                            self.health.lock().record_partial(end);
+                        }
+                    }
+                    continue;
+                }
+            };
+            let Some(msg) = msg else { break };
+            if let Ok(Message::Text(text)) = msg {
+                decoder.decode(&text, &mut events);
+            }
+        }
+
+        if !finished {
+            self.timeline.lock().record(
+                TimelineEventKind::Disconnected,
+                Some("Connection closed before the provider finished".to_string()),
+            );
+        }
+    }
+}
+
+pub async fn run_transcription(
+    options: TranscriptionOptions,
+    mut mixer: AudioMixer,
+    mut stop_rx: oneshot::Receiver<()>,
+    mut change_rx: tokio::sync::mpsc::UnboundedReceiver<SessionChange>,
+    mut suspend_rx: tokio::sync::watch::Receiver<Option<Suspension>>,
+    window: SessionWindow,
+    session: SessionState,
+) -> Result<(), String> {
+    let TranscriptionOptions {
+        provider: provider_kind,
+        api_key,
+        additional_vocab,
+        speaker_profile,
+        speaker_profiles,
+        roster,
+        rt_url,
+        auth,
+        tls,
+        websocket,
+        language,
+        domain,
+        operating_point,
+        max_delay,
+        partials,
+        confidence_threshold,
+        diarization,
+        max_speakers,
+        tuning,
+        audio_batch_ms,
+        power_aware,
+        stop_drain_timeout,
+        mut audio_dump,
+        interview,
+        formatting,
+        silence_watchdog,
+        speaker_cues,
+        live_transcript,
+        captions: caption_output,
+        transcript_path,
+        costs: cost_settings,
+        simulator,
+        channel_labels,
+    } = options;
+    // Empty unless the sources go out as separate channels.
+    let channel_labels = channel_labels.unwrap_or_default();
+    let by_channel = !channel_labels.is_empty();
+    let live_transcript = Arc::new(Mutex::new(live_transcript));
+    let consent_path = transcript_path
+        .as_ref()
+        .map(|path| path.with_file_name(consent::CONSENT_FILE));
+    let SessionState {
+        transcript,
+        is_muted,
+        mute_mode,
+        stream_health,
+        timeline,
+        agenda,
+        speaking,
+        levels,
+        recent_audio,
+        wake_lock,
+        ..
+    } = session;
+    let _wake_lock_guard = WakeLockGuard {
+        wake_lock_state: wake_lock,
+    };
+
+    // Labels must be unique; the local user's own spelling comes first.
+    let speakers = known_speakers(
+        speaker_profile
+            .into_iter()
+            .chain(speaker_profiles)
+            .map(|profile| (profile.label, profile.speaker_identifiers))
+            .chain(
+                roster
+                    .into_iter()
+                    .map(|speaker| (speaker.label, speaker.speaker_identifiers)),
+            ),
+    );
+    let mut config = SessionConfig {
+        domain,
+        additional_vocab,
+        partials,
+        max_delay,
+        operating_point,
+        diarization,
+        max_speakers,
+        known_speakers: speakers,
+        // Channels take the place of voices when they are diarized.
+        capture_speakers: diarization && !by_channel,
+        channels: channel_labels.clone(),
+        ..SessionConfig::new(language)
+    };
+
+    let mut reader = ResultReader {
+        window: window.clone(),
+        transcript: transcript.clone(),
+        timeline: timeline.clone(),
+        health: stream_health.clone(),
+        agenda,
+        speaking,
+        levels,
+        recent_audio: recent_audio.clone(),
+        channel_labels: channel_labels.clone(),
+        question_tracker: interview
+            .map(|config| Arc::new(Mutex::new(interview::QuestionTracker::new(config)))),
+        language_check: Arc::new(Mutex::new(language_check::LanguageCheck::new(
+            &config.language,
+        ))),
+        tuning,
+        formatting,
+        confidence_threshold,
+        speaker_cues: cues::SpeakerCues::new(speaker_cues).map(|cues| Arc::new(Mutex::new(cues))),
+        live_transcript: live_transcript.clone(),
+        captions: caption_output.as_ref().map(captions::CaptionOutput::feed),
+        consent: Arc::new(Mutex::new(load_consent(consent_path.as_deref()))),
+        consent_path: consent_path.clone(),
+        speakers_path: transcript_path
+            .as_ref()
+            .map(|path| path.with_file_name(speaker_ids::SPEAKERS_FILE)),
+        audio_acks: None,
+        acknowledges_audio: true,
+        time_offset: 0.0,
+    };
+    // Built once, so bad certificate files fail the start rather than a later reconnect.
+    let connector = tls.as_ref().map(tls::connector).transpose()?;
+    let provider = provider_kind.provider(api_key, rt_url.clone(), auth, connector, websocket);
+    reader.acknowledges_audio = provider.acknowledges_audio();
+    let uplink = Uplink {
+        provider: provider.as_ref(),
+        channels: &channel_labels,
+        max_audio_bytes: websocket.max_audio_message_bytes,
+    };
+    // Keeps the session open while muted audio leaves it quiet.
+    let keep_alive_message = provider.keep_alive();
+    let mut keep_alive = tokio::time::interval_at(
+        tokio::time::Instant::now() + provider::KEEP_ALIVE_INTERVAL,
+        provider::KEEP_ALIVE_INTERVAL,
+    );
+    keep_alive.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let (mut write, mut read_handle, mut audio_acks) =
+        open_recognition(provider.as_ref(), &config, reader.clone()).await?;
+    // Sessions replaced by a language switch, still delivering their last results.
+    let mut retired = Vec::new();
+
+    // Counts binary audio messages actually sent; u64 so long sessions never wrap.
+    let mut seq_no: u64 = 0;
+    let mut power_policy = power::PowerPolicy::new(
+        if power_aware {
+            power::power_state()
+        } else {
+            power::PowerState::default()
+        },
+        audio_batch_ms,
+    );
+    if power_policy.mode != power::PowerMode::Normal {
+        info!(
+            ?power_policy,
+            "Saving power from the start of the recording"
+        );
+        let _ = window.emit(events::POWER_POLICY_CHANGED, power_policy);
+    }
+    let mut power_check = tokio::time::interval_at(
+        tokio::time::Instant::now() + POWER_CHECK_INTERVAL,
+        POWER_CHECK_INTERVAL,
+    );
+    let mut batcher = AudioBatcher::new(power_policy.batch_ms);
+    let mut watchdog = silence::SilenceWatchdog::new(silence_watchdog);
+    // Set when the socket dies under us and could not be opened again.
+    let mut connection_lost = false;
+    recent_audio.lock().reset(channel_labels.len());
+    let mut replay = ReplayBuffer::new(channel_labels.len(), recent_audio.clone());
+    // Reconnects since the provider last acknowledged audio.
+    let mut reconnect_attempts = 0;
+
+    'audio: loop {
+        let mut lost = false;
+        tokio::select! {
+          _ = &mut stop_rx => break,
+          Some(change) = change_rx.recv() => {
+              // Finish the current session with everything captured so far, then carry on
+              // in a new one; the old session's last finals land in the same transcript.
+              if let Some((batch, waited)) = batcher.flush() {
+                  if let Ok(sent) = send_audio_batch(&mut write, uplink, batch, waited, &stream_health, &mut audio_dump, &mut replay)
+                      .await
+                  {
+                      seq_no += sent;
+                  }
+              }
+              change.apply(&mut config);
+              reader.time_offset = stream_health.lock().audio_ms_sent as f64 / 1000.0;
+              match open_recognition(provider.as_ref(), &config, reader.clone()).await
+              {
+                  Ok((new_write, new_read_handle, new_acks)) => {
+                      let mut old_write = std::mem::replace(&mut write, new_write);
+                      audio_acks = new_acks;
+                      let old_seq_no = std::mem::replace(&mut seq_no, 0);
+                      retired.push(std::mem::replace(&mut read_handle, new_read_handle));
+                      let ending = provider.end_of_stream(&channel_labels, old_seq_no);
+                      tauri::async_runtime::spawn(async move {
+                          end_stream(&mut old_write, ending).await;
+                      });
+                      match change {
+                          SessionChange::Language(language) => {
+                              info!(%language, "Switched transcription language");
+                              reader.language_check.lock().restart(&language);
+                              timeline.lock().record(
+                                  TimelineEventKind::LanguageChanged,
+                                  Some(language.clone()),
+                              );
+                              let _ = window.emit(events::LANGUAGE_CHANGED, language);
+                          }
+                          SessionChange::Vocab(vocab) => {
+                              info!(entries = vocab.len(), "Updated custom vocabulary");
+                              let _ = window.emit(events::VOCAB_UPDATED, vocab.len());
+                          }
+                      }
+                  }
+                  Err(err) => match change {
+                      SessionChange::Language(_) => {
+                          warn!("Language switch failed, keeping the current session: {}", err);
+                          let _ = window.emit(events::LANGUAGE_SWITCH_FAILED, err);
+                      }
+                      SessionChange::Vocab(_) => {
+                          warn!("Vocabulary update failed, keeping the current session: {}", err);
+                          let _ = window.emit(events::VOCAB_UPDATE_FAILED, err);
+                      }
+                  },
+              }
+              continue;
+          }
+          Ok(()) = suspend_rx.changed() => {
+              let Some(suspension) = *suspend_rx.borrow_and_update() else {
+                  continue;
+              };
+              // Closing the connection ends billing, and during sleep it would die
+              // silently anyway; the session stays, so the same transcript carries on.
+              if let Some((batch, waited)) = batcher.flush() {
+                  if let Ok(sent) = send_audio_batch(&mut write, uplink, batch, waited, &stream_health, &mut audio_dump, &mut replay)
+                      .await
+                  {
+                      seq_no += sent;
+                  }
+              }
+              end_stream(&mut write, provider.end_of_stream(&channel_labels, seq_no)).await;
+              match suspension {
+                  Suspension::Sleep => {
+                      info!("Recording suspended for system sleep");
+                      timeline.lock().record(TimelineEventKind::Suspended, None);
+                      let _ = window.emit(events::RECORDING_SUSPENDED, ());
+                  }
+                  Suspension::Paused => {
+                      info!("Recording paused");
+                      timeline.lock().record(TimelineEventKind::Paused, None);
+                      let _ = window.emit(events::RECORDING_PAUSED, ());
+                  }
+              }
+
+              // Audio that arrives before a resume is dropped.
+              loop {
+                  tokio::select! {
+                      _ = &mut stop_rx => break 'audio,
+                      changed = suspend_rx.changed() => {
+                          if changed.is_err() {
+                              break 'audio;
+                          }
+                          if suspend_rx.borrow_and_update().is_some() {
+                              continue;
+                          }
+                          reader.time_offset = stream_health.lock().audio_ms_sent as f64 / 1000.0;
+                          match open_recognition(provider.as_ref(), &config, reader.clone()).await
+                          {
+                              Ok((new_write, new_read_handle, new_acks)) => {
+                                  write = new_write;
+                                  audio_acks = new_acks;
+                                  seq_no = 0;
+                                  retired.push(std::mem::replace(&mut read_handle, new_read_handle));
+                                  info!(?suspension, "Recording resumed");
+                                  timeline.lock().record(TimelineEventKind::Resumed, None);
+                                  let _ = window.emit(events::RECORDING_RESUMED, ());
+                                  break;
+                              }
+                              Err(err) => {
+                                  warn!("Failed to resume recording: {}", err);
+                                  let _ = window.emit(events::RECORDING_RESUME_FAILED, err);
+                              }
+                          }
+                      }
+                      _ = mixer.frame_ready() => {
+                          while mixer.pop_frame().is_some() {}
+                          if mixer.is_finished() {
+                              break 'audio;
+                          }
+                      }
+                  }
+              }
+              continue;
+          }
+          acked = audio_acks.changed() => {
+              if acked.is_ok() {
+                  reconnect_attempts = 0;
+                  continue;
+              }
+              // The reader lets go of the channel once the connection has closed.
+              lost = true;
+          }
+          _ = power_check.tick(), if power_aware => {
+              let policy = power::PowerPolicy::new(power::power_state(), audio_batch_ms);
+              if (policy.mode, policy.batch_ms) != (power_policy.mode, power_policy.batch_ms) {
+                  info!(?policy, "Power policy changed");
+                  batcher.set_batch_ms(policy.batch_ms);
+                  let _ = window.emit(events::POWER_POLICY_CHANGED, policy);
+              }
+              power_policy = policy;
+              continue;
+          }
+          _ = keep_alive.tick(), if keep_alive_message.is_some() => {
+              if let Some(message) = keep_alive_message.clone() {
+                  lost = write.send(message).await.is_err();
+              }
+              if !lost {
+                  continue;
+              }
+          }
+          _ = mixer.frame_ready() => {},
+        }
+
+        while !lost {
+            let Some(pcm) = mixer.pop_frame() else {
+                break;
+            };
+            if let Some(event) = watchdog.observe(&pcm) {
+                report_silence(&window, &timeline, event);
+            }
+            if *is_muted.lock() && mute_mode.lock().pauses_transcription() {
+                stream_health.lock().muted_frames += 1;
+                write_audio_dump(&mut audio_dump, &pcm);
+                continue;
+            }
+
+            if let Some((batch, waited)) = batcher.push(&pcm) {
+                match send_audio_batch(
+                    &mut write,
+                    uplink,
+                    batch,
+                    waited,
+                    &stream_health,
+                    &mut audio_dump,
+                    &mut replay,
+                )
+                .await
+                {
+                    Ok(sent) => seq_no += sent,
+                    Err(batch) => {
+                        replay.hold(batch, Instant::now() - waited);
+                        lost = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        if lost {
+            warn!("Transcription connection lost; reconnecting");
+            let reconnected = loop {
+                reconnect_attempts += 1;
+                if reconnect_attempts > MAX_RECONNECT_ATTEMPTS {
+                    break false;
+                }
+                let delay = RECONNECT_BASE_DELAY
+                    .saturating_mul(1 << (reconnect_attempts - 1))
+                    .min(RECONNECT_MAX_DELAY);
+                let _ = window.emit(
+                    events::RECORDING_RECONNECTING,
+                    ReconnectAttempt {
+                        attempt: reconnect_attempts,
+                        max_attempts: MAX_RECONNECT_ATTEMPTS,
+                        delay_ms: delay.as_millis() as u64,
+                    },
+                );
+
+                // Keeps capturing while it waits; the audio goes out once connected.
+                let retry_at = tokio::time::Instant::now() + delay;
+                loop {
+                    tokio::select! {
+                        _ = &mut stop_rx => {
+                            connection_lost = true;
+                            break 'audio;
+                        }
+                        _ = tokio::time::sleep_until(retry_at) => break,
+                        _ = mixer.frame_ready() => {
+                            while let Some(pcm) = mixer.pop_frame() {
+                                if let Some(event) = watchdog.observe(&pcm) {
+                                    report_silence(&window, &timeline, event);
+                                }
+                                if *is_muted.lock() && mute_mode.lock().pauses_transcription() {
+                                    stream_health.lock().muted_frames += 1;
+                                    write_audio_dump(&mut audio_dump, &pcm);
+                                    continue;
+                                }
+                                if let Some((batch, waited)) = batcher.push(&pcm) {
+                                    replay.hold(batch, Instant::now() - waited);
+                                }
+                            }
+                            if mixer.is_finished() {
+                                connection_lost = true;
+                                break 'audio;
+                            }
+                        }
+                    }
+                }
+
+                // The new session starts where the transcript stopped being
+                // final, so nothing is transcribed twice or skipped.
+                let (resume_at, unfinished) = replay.after(stream_health.lock().final_end());
+                reader.time_offset = resume_at;
+                let (new_write, new_read_handle, new_acks) =
+                    match open_recognition(provider.as_ref(), &config, reader.clone()).await {
+                        Ok(recognition) => recognition,
+                        Err(err) => {
+                            warn!(attempt = reconnect_attempts, "Reconnect failed: {}", err);
+                            continue;
+                        }
+                    };
+                write = new_write;
+                audio_acks = new_acks;
+                seq_no = 0;
+                retired.push(std::mem::replace(&mut read_handle, new_read_handle));
+
+                let mut resent = true;
+                for batch in unfinished {
+                    let Some(sent) = write_audio(&mut write, uplink, batch).await else {
+                        resent = false;
+                        break;
+                    };
+                    seq_no += sent;
+                }
+                while resent {
+                    let Some((batch, captured_at)) = replay.take_held() else {
+                        break;
+                    };
+                    match send_audio_batch(
+                        &mut write,
+                        uplink,
+                        batch,
+                        captured_at.elapsed(),
+                        &stream_health,
+                        &mut audio_dump,
+                        &mut replay,
+                    )
+                    .await
+                    {
+                        Ok(sent) => seq_no += sent,
+                        Err(batch) => {
+                            replay.return_held(batch, captured_at);
+                            resent = false;
+                            break;
+                        }
+                    }
+                }
+                if resent {
+                    break true;
+                }
+            };
+
+            if !reconnected {
+                warn!("Giving up on the transcription connection");
+                timeline.lock().record(
+                    TimelineEventKind::Disconnected,
+                    Some("Could not reconnect".to_string()),
+                );
+                let _ = window.emit(
+                    events::RECORDING_ERROR,
+                    "Lost the connection to the transcription service".to_string(),
+                );
+                connection_lost = true;
+                break 'audio;
+            }
+            stream_health.lock().reconnects += 1;
+            info!(attempts = reconnect_attempts, "Transcription reconnected");
+            timeline.lock().record(TimelineEventKind::Reconnected, None);
+            let _ = window.emit(events::RECORDING_RECONNECTED, ());
+            continue;
+        }
+
+        if mixer.is_finished() {
+            break;
+        }
+    }
+    mixer.stop();
+
+    // Frames the mixer produced before it stopped are still in its buffer.
+    if !connection_lost {
+        let muted = *is_muted.lock() && mute_mode.lock().pauses_transcription();
+        while let Some(pcm) = mixer.pop_frame() {
+            if muted {
+                stream_health.lock().muted_frames += 1;
+                write_audio_dump(&mut audio_dump, &pcm);
+                continue;
+            }
+            if let Some((batch, waited)) = batcher.push(&pcm) {
+                match send_audio_batch(
+                    &mut write,
+                    uplink,
+                    batch,
+                    waited,
+                    &stream_health,
+                    &mut audio_dump,
+                    &mut replay,
+                )
+                .await
+                {
+                    Ok(sent) => seq_no += sent,
+                    Err(_) => {
+                        connection_lost = true;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    if let Some((batch, waited)) = batcher.flush() {
+        if let Ok(sent) = send_audio_batch(
+            &mut write,
+            uplink,
+            batch,
+            waited,
+            &stream_health,
+            &mut audio_dump,
+            &mut replay,
+        )
+        .await
+        {
+            seq_no += sent;
+        }
+    }
+    if let Some(dump) = audio_dump.take() {
+        if let Err(err) = dump.finish() {
+            warn!("{}", err);
+        }
+    }
+
+    if connection_lost {
+        warn!("Transcription connection lost; collecting the results still in flight");
+    } else if provider.acknowledges_audio()
+        && !wait_for_audio_acks(&mut audio_acks, seq_no, stop_drain_timeout).await
+    {
+        warn!(
+            seq_no,
+            acked = *audio_acks.borrow(),
+            "Provider had not acknowledged all audio before the stream was ended"
+        );
+    }
+
+    // Even on a broken socket this is worth trying: if only our side of the
+    // connection failed, the provider may still flush its last finals.
+    end_stream(&mut write, provider.end_of_stream(&channel_labels, seq_no)).await;
+    let deadline = tokio::time::Instant::now() + FINAL_RESULTS_TIMEOUT;
+    for mut handle in retired.into_iter().chain(std::iter::once(read_handle)) {
+        if tokio::time::timeout_at(deadline, &mut handle)
+            .await
+            .is_err()
+        {
+            handle.abort();
+            timeline.lock().record(
+                TimelineEventKind::Disconnected,
+                Some("Gave up waiting for final results".to_string()),
+            );
+        }
+    }
+
+    // Audio is only kept for enrolling voices while recording.
+    recent_audio.lock().clear();
+
+    // The session may not be stopped for a while after a lost connection, so
+    // keep what arrived now rather than on the next autosave.
+    let turns = transcript.lock().turns().to_vec();
+    if let Some(path) = &transcript_path {
+        if let Err(err) = save_transcript_turns(path, &turns) {
+            warn!("{}", err);
+        }
+    }
+    write_live_transcript(
+        &live_transcript,
+        &turns,
+        true,
+        load_consent(consent_path.as_deref()).as_ref(),
+    );
+    // Simulated meetings cost nothing.
+    let cost_dir = transcript_path
+        .as_deref()
+        .and_then(std::path::Path::parent)
+        .filter(|_| simulator.is_none());
+    if let Some(dir) = cost_dir {
+        let audio_secs = stream_health.lock().audio_ms_sent as f64 / 1000.0;
+        let billed_as = costs::provider_for(provider_kind, rt_url.as_deref());
+        if let Err(err) = costs::record(dir, &billed_as, audio_secs, &cost_settings) {
+            warn!("{}", err);
+        }
+    }
+    let _ = window.emit(events::RECORDING_ENDED, ());
+    Ok(())
+}
+
+/// Tells the frontend about a quiet recording; on `silence-auto-stop` it stops
+/// the recording the usual way, so the meeting is saved as normal.
+fn report_silence(
+    window: &SessionWindow,
+    timeline: &Mutex<SessionTimeline>,
+    event: silence::SilenceEvent,
+) {
+    match event {
+        silence::SilenceEvent::Warning(warning) => {
+            warn!(
+                silent_secs = warning.silent_secs,
+                "No audio from any source"
+            );
+            timeline.lock().record(
+                TimelineEventKind::SilenceDetected,
+                Some(format!("{}s", warning.silent_secs)),
+            );
+            let _ = window.emit(events::SILENCE_WARNING, warning);
+        }
+        silence::SilenceEvent::Cleared => {
+            let _ = window.emit(events::SILENCE_CLEARED, ());
+        }
+        silence::SilenceEvent::AutoStop => {
+            info!("Stopping the recording after prolonged silence");
+            let _ = window.emit(events::SILENCE_AUTO_STOP, ());
+        }
+    }
+}
+
+fn record_keyword_hits(timeline: &Mutex<SessionTimeline>, keywords: &[String], text: &str) {
+    if keywords.is_empty() {
+        return;
+    }
+    let lowered = text.to_lowercase();
+    for keyword in keywords.iter().filter(|k| lowered.contains(k.as_str())) {
+        timeline
+            .lock()
+            .record(TimelineEventKind::KeywordHit, Some(keyword.clone()));
+    }
+}
+
+/// Where a session's audio goes, what its channels are called, and how much
+/// of it fits in one message.
+#[derive(Clone, Copy)]
+struct Uplink<'a> {
+    provider: &'a dyn RealtimeProvider,
+    channels: &'a [String],
+    max_audio_bytes: usize,
+}
+
+/// Sends a session's closing `messages`, then closes the socket.
+async fn end_stream(write: &mut RtWriter, messages: Vec<Message>) {
+    for message in messages {
+        let _ = write.send(message).await;
+    }
+    let _ = write.close().await;
+}
+
+/// Sends `batch` in the messages the provider expects for it, cut into
+/// pieces that fit the uplink's message size. Returns how many pieces went
+/// out, each one audio message to acknowledge, or `None` if a write failed.
+async fn write_audio<S>(write: &mut S, uplink: Uplink<'_>, batch: Vec<u8>) -> Option<u64>
+where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    let pieces = audio::split_pcm(batch, uplink.max_audio_bytes, uplink.channels.len());
+    let count = pieces.len() as u64;
+    for piece in pieces {
+        for message in uplink.provider.audio(piece, uplink.channels) {
+            if write.send(message).await.is_err() {
+                return None;
+            }
+        }
+    }
+    Some(count)
+}
+
+/// Sends audio for the first time, keeping it in `replay` in case the
+/// connection drops. Returns the audio messages sent, or hands the batch back
+/// when the write failed.
+async fn send_audio_batch<S>(
+    write: &mut S,
+    uplink: Uplink<'_>,
+    batch: Vec<u8>,
+    waited: Duration,
+    stream_health: &Mutex<StreamHealth>,
+    audio_dump: &mut Option<audio_dump::AudioDump>,
+    replay: &mut ReplayBuffer,
+) -> Result<u64, Vec<u8>>
+where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    let Some(sent) = write_audio(write, uplink, batch.clone()).await else {
+        warn!("WebSocket write failed");
+        return Err(batch);
+    };
+    write_audio_dump(audio_dump, &batch);
+    let duration = audio::pcm_duration(batch.len() / uplink.channels.len().max(1));
+    stream_health
+        .lock()
+        .record_send(batch.len(), duration, waited);
+    replay.record_sent(batch);
+    Ok(sent)
+}
+
+/// The consent saved at `path`, or `None` when it can't be read. Without a
+/// meeting nobody has declined.
+fn load_consent(path: Option<&std::path::Path>) -> Option<MeetingConsent> {
+    let Some(path) = path else {
+        return Some(MeetingConsent::default());
+    };
+    MeetingConsent::load(path)
+        .map_err(|err| warn!("{}", err))
+        .ok()
+}
+
+/// Appends finished turns to the live transcript file, giving up on it after
+/// the first failure. Nothing is written while `consent` can't be read.
+fn write_live_transcript(
+    live: &Mutex<Option<live_transcript::LiveTranscriptFile>>,
+    turns: &[TranscriptTurnPayload],
+    finished: bool,
+    consent: Option<&MeetingConsent>,
+) {
+    let Some(consent) = consent else {
+        return;
+    };
+    let mut live = live.lock();
+    if let Some(file) = live.as_mut() {
+        if let Err(err) = file.write_final(turns, finished, consent) {
+            warn!("{}; disabling live transcript file", err);
+            *live = None;
+        }
+    }
+}
+
+fn write_audio_dump(audio_dump: &mut Option<audio_dump::AudioDump>, pcm: &[u8]) {
+    if let Some(dump) = audio_dump {
+        if let Err(err) = dump.write_pcm(pcm) {
+            warn!("{}; disabling audio dump", err);
+            *audio_dump = None;
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn enroll_speaker_rt(
+    state: State<'_, AppState>,
+    api_key: String,
+    samples: Vec<f32>,
+    sample_rate: u32,
+) -> Result<Vec<String>, String> {
+    if samples.is_empty() {
+        return Err("No samples provided".to_string());
+    }
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("speaker_enrollment");
+    let pcm = audio::resample_to_pcm16(&samples, sample_rate, TARGET_SAMPLE_RATE);
+    speaker_identifiers(&state, &api_key, &pcm).await
+}
+
+/// Enrolls speaker `label` of a running recording from their last `seconds`
+/// of speech in it, 30 by default, so nobody has to read a sample. Returns
+/// identifiers as `enroll_speaker_rt` does.
+#[tauri::command]
+pub async fn enroll_current_speaker(
+    state: State<'_, AppState>,
+    api_key: String,
+    label: String,
+    seconds: Option<f64>,
+    session_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("Speaker label cannot be empty".to_string());
+    }
+    let seconds = seconds.unwrap_or(DEFAULT_ENROLL_SECS);
+    let keep_secs = recent_audio::KEEP_SECS as f64;
+    if !(MIN_ENROLL_SECS..=keep_secs).contains(&seconds) {
+        return Err(format!(
+            "Enrollment takes between {} and {} seconds of speech",
+            MIN_ENROLL_SECS, keep_secs
+        ));
+    }
+    let session_id = session_id.unwrap_or_else(|| MAIN_SESSION.to_string());
+    if state.sessions.lock().get(&session_id).is_none() {
+        return Err("Not currently recording".to_string());
+    }
+    let pcm = {
+        let session = state.sessions.state(Some(&session_id))?;
+        let recent_audio = session.recent_audio.lock();
+        if recent_audio.channels() > 1 {
+            return Err(
+                "Voices are not enrolled while the microphone and system audio are separate channels"
+                    .to_string(),
+            );
+        }
+        recent_audio.speaker_audio(label, seconds)
+    };
+    let heard = audio::pcm_duration(pcm.len()).as_secs_f64();
+    if heard < MIN_ENROLL_SECS {
+        return Err(format!(
+            "Heard only {:.0} seconds of {} in the last {} minutes; enrolling needs {}",
+            heard,
+            label,
+            recent_audio::KEEP_SECS / 60,
+            MIN_ENROLL_SECS
+        ));
+    }
+    state
+        .services
+        .telemetry
+        .lock()
+        .record_feature("speaker_enrollment_live");
+    speaker_identifiers(&state, &api_key, &pcm).await
+}
+
+/// Runs `pcm` past Speechmatics on its own and returns the identifiers of
+/// the voices in it, at the Speechmatics endpoint in settings.
+async fn speaker_identifiers(
+    state: &AppState,
+    api_key: &str,
+    pcm: &[u8],
+) -> Result<Vec<String>, String> {
+    let transcription = state.stores.settings.lock().get().transcription.clone();
+
+    let ws_stream = speechmatics::open(
+        api_key,
+        transcription.rt_url.as_deref(),
+        transcription.auth,
+        None,
+        Some(transcription.websocket.config()),
+    )
+    .await
+    .map_err(|e| format!("Failed to connect to Speechmatics RT: {}", e))?;
+    let (mut write, mut read) = ws_stream.split();
+
+    let config = SpeechmaticsConfig {
+        message: "StartRecognition".to_string(),
+        transcription_config: TranscriptionConfig {
+            language: transcription.language,
+            domain: transcription.domain,
+            enable_partials: false,
+            operating_point: "enhanced".to_string(),
+            max_delay: 1.5,
+            diarization: Some("speaker".to_string()),
+            speaker_diarization_config: Some(SpeakerDiarizationConfig {
+                get_speakers: Some(true),
+                speakers: None,
+                max_speakers: None,
+            }),
+            additional_vocab: None,
+            channel_diarization_labels: None,
+        },
+        audio_format: AudioFormat {
+            format_type: "raw".to_string(),
+            encoding: "pcm_s16le".to_string(),
+            sample_rate: TARGET_SAMPLE_RATE,
+        },
+    };
+
+    let config_msg =
+        serde_json::to_string(&config).map_err(|e| format!("Failed to encode config: {}", e))?;
+    write
+        .send(Message::Text(config_msg))
+        .await
+        .map_err(|e| format!("Failed to send config: {}", e))?;
+
+    let mut seq_no: u64 = 0;
+    for chunk in pcm.chunks(320) {
+        if write.send(Message::Binary(chunk.to_vec())).await.is_err() {
+            return Err("Failed to stream audio to Speechmatics".to_string());
+        }
+        seq_no += 1;
+    }
+
+    let end_of_stream = EndOfStreamMessage {
+        message: "EndOfStream".to_string(),
+        last_seq_no: seq_no,
+    };
+
+    if let Ok(payload) = serde_json::to_string(&end_of_stream) {
+        let _ = write.send(Message::Text(payload)).await;
+    }
+
+    let mut identifiers: Vec<String> = Vec::new();
+    while let Some(msg) = read.next().await {
+        if let Ok(Message::Text(text)) = msg {
+            if let Ok(parsed) = serde_json::from_str::<SpeakersResultMessage>(&text) {
+                if parsed.message == "SpeakersResult" {
+                    if let Some(entries) = parsed.speakers {
+                        for entry in entries {
+                            if let Some(ids) = entry.speaker_identifiers {
+                                identifiers.extend(ids.into_iter().filter(|id| !id.is_empty()));
+                            }
+                        }
+                    }
+                    break;
+                }
+
+                if let Some(err) = parsed.error {
+                    return Err(format!("Speechmatics error: {}", err));
+                }
+            }
+        }
+    }
+
+    if identifiers.is_empty() {
+        return Err("Speechmatics did not return any speaker identifiers.".to_string());
+    }
+
+    Ok(identifiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ten minutes of a stereo recording, far past every cap below.
+    const STREAM_SECS: usize = 10 * 60;
+    const BATCH_MS: usize = 100;
+    const CHANNELS: usize = 2;
+
+    fn batch() -> Vec<u8> {
+        vec![1; TARGET_SAMPLE_RATE as usize * BATCH_MS / 1000 * 2 * CHANNELS]
+    }
+
+    fn bytes_for(secs: f64) -> usize {
+        (secs * f64::from(TARGET_SAMPLE_RATE)) as usize * 2 * CHANNELS
+    }
+
+    #[test]
+    fn replay_buffer_keeps_only_the_documented_audio() {
+        let recent_audio = Arc::new(Mutex::new(recent_audio::RecentAudio::default()));
+        recent_audio.lock().reset(CHANNELS);
+        let mut replay = ReplayBuffer::new(CHANNELS, recent_audio.clone());
+        let batch_secs = BATCH_MS as f64 / 1000.0;
+        let captured_at = Instant::now();
+        for _ in 0..STREAM_SECS * 1000 / BATCH_MS {
+            replay.record_sent(batch());
+            replay.hold(batch(), captured_at);
+
+            let sent_secs = replay.sent.back().map(|(_, end, _)| *end).unwrap_or(0.0)
+                - replay
+                    .sent
+                    .front()
+                    .map(|(start, _, _)| *start)
+                    .unwrap_or(0.0);
+            assert!(sent_secs <= REPLAY_SECS + 1e-6);
+            let sent_bytes: usize = replay.sent.iter().map(|(_, _, batch)| batch.len()).sum();
+            assert!(sent_bytes <= bytes_for(REPLAY_SECS));
+
+            assert!(replay.held_secs <= HELD_AUDIO_SECS + 1e-6);
+            let held_bytes: usize = replay.held.iter().map(|(batch, _)| batch.len()).sum();
+            assert!(held_bytes <= bytes_for(HELD_AUDIO_SECS));
+        }
+        assert!((replay.sent_end - STREAM_SECS as f64).abs() < 1e-6);
+        let (_, resend) = replay.after(0.0);
+        assert!(resend.len() as f64 * batch_secs <= REPLAY_SECS + 1e-6);
+
+        // Everything was said by one speaker, so their audio is all that's kept.
+        let mut recent = recent_audio.lock();
+        recent.push_word("S1", 0.0, replay.sent_end);
+        let kept = recent.speaker_audio("S1", f64::MAX).len();
+        assert_eq!(kept, bytes_for(recent_audio::KEEP_SECS as f64));
+    }
+
+    #[test]
+    fn transcript_grows_with_its_text_and_is_released() {
+        let mut transcript = Transcript::default();
+        // A final every two seconds, alternating speakers now and then.
+        for index in 0..STREAM_SECS / 2 {
+            let speaker = if index % 3 == 0 { "S1" } else { "S2" };
+            transcript.append(Some(speaker), "the quick brown fox jumps over the lazy dog");
+        }
+        let turns = transcript.turns();
+        let text_bytes = transcript.text().len()
+            + turns
+                .iter()
+                .map(|turn| turn.text.len() + turn.speaker.as_ref().map_or(0, String::len))
+                .sum::<usize>();
+        let turn_bytes = turns.len()
+            * (std::mem::size_of::<TranscriptTurnPayload>() + std::mem::size_of::<usize>());
+        // Vectors at most double their capacity, so nothing is retained
+        // beyond twice what the transcript actually holds.
+        assert!(transcript.heap_bytes() <= 2 * (text_bytes + turn_bytes));
+
+        transcript.clear();
+        assert_eq!(transcript.heap_bytes(), 0);
+    }
+}
@@ -0,0 +1,101 @@
+//! What the tray icon shows, and what is left on desktops without a tray.
+//!
+//! Trays differ by platform. Windows opens the menu on a right click and
+//! Jilu on a left one; macOS opens the menu on either. Linux trays go through
+//! AppIndicator, which reports no clicks and shows no tooltip, so there the
+//! menu has to carry everything, including what the app is doing.
+
+use crate::state::{AppState, Suspension, MAIN_SESSION};
+use tauri::image::Image;
+
+const RECORDING_COLOUR: [u8; 3] = [0xe5, 0x39, 0x35];
+const MUTED_COLOUR: [u8; 3] = [0xf5, 0xa6, 0x23];
+const PAUSED_COLOUR: [u8; 3] = [0x9e, 0x9e, 0x9e];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayState {
+    Idle,
+    Recording,
+    Muted,
+    Paused,
+}
+
+impl TrayState {
+    pub fn of(state: &AppState) -> Self {
+        let suspension = state
+            .sessions
+            .lock()
+            .get(MAIN_SESSION)
+            .and_then(|session| session.suspension());
+        if !state.sessions.is_recording() {
+            TrayState::Idle
+        } else if suspension == Some(Suspension::Paused) {
+            TrayState::Paused
+        } else if *state.sessions.main.is_muted.lock() {
+            TrayState::Muted
+        } else {
+            TrayState::Recording
+        }
+    }
+
+    pub fn describe(self) -> Option<&'static str> {
+        match self {
+            TrayState::Idle => None,
+            TrayState::Recording => Some("Recording"),
+            TrayState::Muted => Some("Recording, muted"),
+            TrayState::Paused => Some("Recording paused"),
+        }
+    }
+
+    fn badge(self) -> Option<[u8; 3]> {
+        match self {
+            TrayState::Idle => None,
+            TrayState::Recording => Some(RECORDING_COLOUR),
+            TrayState::Muted => Some(MUTED_COLOUR),
+            TrayState::Paused => Some(PAUSED_COLOUR),
+        }
+    }
+}
+
+/// `base` with a dot in its lower right corner while recording: red, amber
+/// while muted or grey while paused. A white ring keeps the dot visible on dark and light
+/// panels alike.
+pub fn icon(base: &Image<'_>, state: TrayState) -> Image<'static> {
+    let (width, height) = (base.width(), base.height());
+    let mut rgba = base.rgba().to_vec();
+    if let Some([r, g, b]) = state.badge() {
+        let radius = width.min(height) as f32 * 0.22;
+        let ring = (radius * 0.2).max(1.0);
+        let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+        for y in 0..height {
+            for x in 0..width {
+                let distance = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+                let pixel = if distance <= radius - ring {
+                    [r, g, b, 0xff]
+                } else if distance <= radius {
+                    [0xff; 4]
+                } else {
+                    continue;
+                };
+                let at = ((y * width + x) * 4) as usize;
+                if let Some(target) = rgba.get_mut(at..at + 4) {
+                    target.copy_from_slice(&pixel);
+                }
+            }
+        }
+    }
+    Image::new_owned(rgba, width, height)
+}
+
+/// Whether a tray icon, once built, will be seen. Linux needs a
+/// StatusNotifier host for that, which KDE, Xfce, Cinnamon and Ubuntu's
+/// GNOME have but stock GNOME only gains through an extension; the icon is
+/// built there all the same and never shown. This is a guess from the
+/// desktop's name, as asking the session bus would take a D-Bus client.
+pub fn host_likely() -> bool {
+    if !cfg!(target_os = "linux") {
+        return true;
+    }
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    !desktop.eq_ignore_ascii_case("GNOME")
+}
@@ -0,0 +1,104 @@
+use serde::Serialize;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use tauri_plugin_updater::Update;
+
+pub const BUCKET_FILE: &str = "rollout-bucket";
+/// Give startup some room before the first background check.
+pub const INITIAL_DELAY_SECS: u64 = 120;
+pub const CHECK_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+/// What the frontend is told about an available release.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    /// Release notes from the manifest, shown as the changelog.
+    pub notes: Option<String>,
+    pub rollout_percent: u8,
+}
+
+impl UpdateInfo {
+    pub fn from_update(update: &Update) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            notes: update.body.clone(),
+            rollout_percent: rollout_percent(&update.raw_json),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// The update found by the last check, and whether announcing it was put off.
+#[derive(Default)]
+pub struct PendingUpdate {
+    pub update: Option<Update>,
+    /// Set when an update arrived mid-recording; it is announced once the recording stops.
+    pub deferred: bool,
+    pub installing: bool,
+}
+
+/// Share of installs a release is offered to, from an optional `rollout`
+/// field in the manifest. Releases without one go to everybody.
+pub fn rollout_percent(manifest: &serde_json::Value) -> u8 {
+    manifest
+        .get("rollout")
+        .and_then(serde_json::Value::as_u64)
+        .map_or(100, |percent| percent.min(100) as u8)
+}
+
+/// Whether this install's bucket falls inside the release's rollout.
+pub fn in_rollout(update: &Update, bucket: u8) -> bool {
+    bucket < rollout_percent(&update.raw_json)
+}
+
+/// This install's rollout bucket in `0..100`, picked once and kept in `path`
+/// so an install does not drift in and out of a staged release.
+pub fn load_or_create_bucket(path: &Path) -> u8 {
+    if let Some(bucket) = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| contents.trim().parse::<u8>().ok())
+        .filter(|bucket| *bucket < 100)
+    {
+        return bucket;
+    }
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    let seed = format!("{}:{}:{}", nanos, std::process::id(), path.display());
+    let bucket = (fnv1a(seed.as_bytes()) % 100) as u8;
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(err) = std::fs::write(path, bucket.to_string()) {
+        tracing::warn!("Failed to save rollout bucket: {}", err);
+    }
+    bucket
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Builds without a signing key cannot verify downloads, so they never update.
+pub fn is_configured(app: &AppHandle) -> bool {
+    app.config()
+        .plugins
+        .0
+        .get("updater")
+        .and_then(|config| config.get("pubkey"))
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(|key| !key.trim().is_empty())
+}
@@ -0,0 +1,86 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use jilu_core::http::{self, RetryPolicy};
+use serde::{Deserialize, Serialize};
+
+const USAGE_URL: &str = "https://asr.api.speechmatics.com/v2/usage";
+
+/// Hours transcribed on the account this calendar month (UTC), and what is
+/// left of the allowance when one is configured.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    /// First day of the month, `YYYY-MM-DD`.
+    pub since: String,
+    /// Today, `YYYY-MM-DD`.
+    pub until: String,
+    pub used_hours: f64,
+    pub realtime_hours: f64,
+    pub batch_hours: f64,
+    pub allowance_hours: Option<f64>,
+    /// Never below zero; `None` without an allowance.
+    pub remaining_hours: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageResponse {
+    #[serde(default)]
+    summary: Vec<UsageSummary>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageSummary {
+    #[serde(default)]
+    mode: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+    #[serde(default)]
+    duration_hrs: f64,
+}
+
+/// Asks the Speechmatics usage API how much of this month has been used.
+pub async fn fetch(api_key: &str, allowance_hours: Option<f64>) -> Result<ProviderUsage, String> {
+    let today = Utc::now().date_naive();
+    let since = NaiveDate::from_ymd_opt(today.year(), today.month(), 1).unwrap_or(today);
+    let (since, until) = (
+        since.format("%Y-%m-%d").to_string(),
+        today.format("%Y-%m-%d").to_string(),
+    );
+
+    let request = http::client()
+        .get(USAGE_URL)
+        .query(&[("since", since.as_str()), ("until", until.as_str())])
+        .bearer_auth(api_key);
+    let response = http::send(request, RetryPolicy::STANDARD)
+        .await
+        .map_err(|e| format!("Failed to reach Speechmatics: {}", e))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+        return Err("Speechmatics rejected the API key".to_string());
+    }
+    if !status.is_success() {
+        return Err(format!("Speechmatics usage request failed: {}", status));
+    }
+    let body: UsageResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid usage response: {}", e))?;
+
+    let (mut realtime_hours, mut batch_hours) = (0.0, 0.0);
+    for entry in body.summary.iter().filter(|e| e.kind == "transcription") {
+        if entry.mode == "batch" {
+            batch_hours += entry.duration_hrs;
+        } else {
+            realtime_hours += entry.duration_hrs;
+        }
+    }
+    let used_hours = realtime_hours + batch_hours;
+    Ok(ProviderUsage {
+        since,
+        until,
+        used_hours,
+        realtime_hours,
+        batch_hours,
+        allowance_hours,
+        remaining_hours: allowance_hours.map(|allowance| (allowance - used_hours).max(0.0)),
+    })
+}
@@ -0,0 +1,429 @@
+use crate::settings::VoiceCommandSettings;
+use parking_lot::Mutex;
+use rtrb::Consumer;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// 100ms of microphone audio at the mixer's rate.
+const CHUNK_SAMPLES: usize = 4_800;
+const IDLE_WAIT: Duration = Duration::from_millis(20);
+/// Recognition starts over this often, so the text it rescans stays short.
+const TASK_LENGTH: Duration = Duration::from_secs(60);
+/// How soon a recognition that stopped by itself is started again.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+/// Words allowed between the wake word and the command, as in "Jilu, please mute".
+const MAX_FILLER_WORDS: usize = 1;
+
+/// Payload of `voice-command`.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoiceCommand {
+    Bookmark,
+    Mute,
+    Unmute,
+}
+
+impl VoiceCommand {
+    /// The command starting at `words[0]`, and how many words it took.
+    /// `Err` when the words so far could still become a command.
+    fn parse(words: &[String]) -> Result<Option<(Self, usize)>, ()> {
+        match words {
+            [] => Err(()),
+            [first, ..] if first == "bookmark" => Ok(Some((Self::Bookmark, 1))),
+            [first, ..] if first == "mute" => Ok(Some((Self::Mute, 1))),
+            [first, ..] if first == "unmute" => Ok(Some((Self::Unmute, 1))),
+            [first] if first == "un" => Err(()),
+            [first, second, ..] if first == "un" && second == "mute" => Ok(Some((Self::Unmute, 2))),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Finds commands in the growing text of one recognition task. The whole
+/// text is passed on every update, so it remembers how far it has acted.
+struct CommandMatcher {
+    wake_word: Vec<String>,
+    /// Words already acted on.
+    consumed: usize,
+}
+
+impl CommandMatcher {
+    fn new(wake_word: &str) -> Self {
+        Self {
+            wake_word: words(wake_word),
+            consumed: 0,
+        }
+    }
+
+    /// Hints that help the recognizer spell the wake word.
+    fn phrases(wake_word: &str) -> Vec<String> {
+        ["bookmark this", "mute", "unmute"]
+            .iter()
+            .map(|command| format!("{} {}", wake_word, command))
+            .chain(std::iter::once(wake_word.to_string()))
+            .collect()
+    }
+
+    fn feed(&mut self, text: &str) -> Vec<VoiceCommand> {
+        let words = words(text);
+        let mut commands = Vec::new();
+        let mut index = self.consumed;
+        while index + self.wake_word.len() <= words.len() {
+            if words[index..index + self.wake_word.len()] != self.wake_word[..] {
+                index += 1;
+                continue;
+            }
+            let after = index + self.wake_word.len();
+            let mut found = None;
+            for start in after..=(after + MAX_FILLER_WORDS).min(words.len()) {
+                match VoiceCommand::parse(&words[start..]) {
+                    Ok(Some((command, len))) => {
+                        found = Some((command, start + len));
+                        break;
+                    }
+                    Ok(None) => {}
+                    // Wait for the next words before deciding.
+                    Err(()) => return commands,
+                }
+            }
+            match found {
+                Some((command, end)) => {
+                    commands.push(command);
+                    self.consumed = end;
+                    index = end;
+                }
+                None => index = after,
+            }
+        }
+        commands
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Listens for "<wake word> bookmark this", "<wake word> mute" and
+/// "<wake word> unmute" on a tap of the microphone, using the system's
+/// on-device speech recognizer so nothing leaves the Mac. Stops when dropped
+/// or when the tap goes away.
+pub struct VoiceCommands {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl VoiceCommands {
+    pub fn start(
+        settings: &VoiceCommandSettings,
+        mic: Consumer<f32>,
+        on_command: impl Fn(VoiceCommand) + Send + Sync + 'static,
+    ) -> Result<Self, String> {
+        let wake_word = settings.wake_word.trim().to_string();
+        let on_command: Arc<dyn Fn(VoiceCommand) + Send + Sync> = Arc::new(on_command);
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        // The recognizer is not `Send`, so it lives and dies on its own thread.
+        // Opening it may wait on the permission prompt, so nobody waits for it.
+        let thread = std::thread::Builder::new()
+            .name("voice-commands".into())
+            .spawn(move || match platform::Recognizer::new() {
+                Ok(recognizer) => {
+                    tracing::info!("Listening for voice commands");
+                    listen(recognizer, mic, &wake_word, on_command, &thread_stop);
+                }
+                Err(err) => tracing::warn!("Voice commands disabled: {}", err),
+            })
+            .map_err(|e| format!("Failed to start voice command thread: {}", e))?;
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for VoiceCommands {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn listen(
+    mut recognizer: platform::Recognizer,
+    mut mic: Consumer<f32>,
+    wake_word: &str,
+    on_command: Arc<dyn Fn(VoiceCommand) + Send + Sync>,
+    stop: &AtomicBool,
+) {
+    let phrases = CommandMatcher::phrases(wake_word);
+    let mut samples = Vec::with_capacity(CHUNK_SAMPLES);
+    let mut task_started: Option<Instant> = None;
+    while !stop.load(Ordering::Relaxed) {
+        let restart = task_started.is_none_or(|at| {
+            at.elapsed() >= TASK_LENGTH
+                || (recognizer.task_ended() && at.elapsed() >= RESTART_DELAY)
+        });
+        if restart {
+            let matcher = Mutex::new(CommandMatcher::new(wake_word));
+            let on_command = on_command.clone();
+            recognizer.begin(&phrases, move |text| {
+                for command in matcher.lock().feed(text) {
+                    on_command(command);
+                }
+            });
+            task_started = Some(Instant::now());
+        }
+
+        if mic.slots() < CHUNK_SAMPLES {
+            if mic.is_abandoned() {
+                break;
+            }
+            std::thread::sleep(IDLE_WAIT);
+            continue;
+        }
+        let Ok(chunk) = mic.read_chunk(CHUNK_SAMPLES) else {
+            continue;
+        };
+        let (first, second) = chunk.as_slices();
+        samples.clear();
+        samples.extend_from_slice(first);
+        samples.extend_from_slice(second);
+        chunk.commit_all();
+        recognizer.append(&samples);
+    }
+    recognizer.end();
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    #![allow(unexpected_cfgs)] // objc macros probe cfg(feature = "cargo-clippy"), which triggers this lint
+
+    use crate::audio::SOURCE_SAMPLE_RATE;
+    use block::ConcreteBlock;
+    use cocoa::base::{id, nil, BOOL, NO, YES};
+    use cocoa::foundation::{NSArray, NSString};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    const SF_AUTHORIZATION_STATUS_NOT_DETERMINED: isize = 0;
+    const SF_AUTHORIZATION_STATUS_AUTHORIZED: isize = 3;
+    const AV_AUDIO_PCM_FORMAT_FLOAT32: usize = 1;
+
+    /// `SFSpeechRecognizer` for the system language, fed mono float samples
+    /// at the mixer's rate through one recognition task at a time.
+    pub struct Recognizer {
+        recognizer: id,
+        format: id,
+        request: id,
+        task: id,
+        ended: Arc<AtomicBool>,
+    }
+
+    impl Recognizer {
+        pub fn new() -> Result<Self, String> {
+            authorize()?;
+            unsafe {
+                let recognizer: id = msg_send![class!(SFSpeechRecognizer), new];
+                if recognizer == nil {
+                    return Err("Speech recognition does not support the system language".into());
+                }
+                let on_device: BOOL = msg_send![recognizer, supportsOnDeviceRecognition];
+                if on_device == NO {
+                    let _: () = msg_send![recognizer, release];
+                    return Err(
+                        "On-device speech recognition is not available for the system language"
+                            .into(),
+                    );
+                }
+                // Results would otherwise be delivered on the main thread.
+                let queue: id = msg_send![class!(NSOperationQueue), new];
+                let _: () = msg_send![recognizer, setQueue: queue];
+                let _: () = msg_send![queue, release];
+
+                let format: id = msg_send![class!(AVAudioFormat), alloc];
+                let format: id = msg_send![
+                    format,
+                    initWithCommonFormat: AV_AUDIO_PCM_FORMAT_FLOAT32
+                    sampleRate: f64::from(SOURCE_SAMPLE_RATE)
+                    channels: 1u32
+                    interleaved: NO
+                ];
+                Ok(Self {
+                    recognizer,
+                    format,
+                    request: nil,
+                    task: nil,
+                    ended: Arc::new(AtomicBool::new(false)),
+                })
+            }
+        }
+
+        /// Ends any running task and starts a new one that passes all text
+        /// recognized so far to `on_text` on every update.
+        pub fn begin(&mut self, phrases: &[String], on_text: impl Fn(&str) + 'static) {
+            self.end();
+            let ended = Arc::new(AtomicBool::new(false));
+            self.ended = ended.clone();
+            unsafe {
+                let pool: id = msg_send![class!(NSAutoreleasePool), new];
+                let request: id = msg_send![class!(SFSpeechAudioBufferRecognitionRequest), new];
+                let _: () = msg_send![request, setShouldReportPartialResults: YES];
+                let _: () = msg_send![request, setRequiresOnDeviceRecognition: YES];
+                let strings: Vec<id> = phrases
+                    .iter()
+                    .map(|phrase| NSString::alloc(nil).init_str(phrase))
+                    .collect();
+                let array = NSArray::arrayWithObjects(nil, &strings);
+                let _: () = msg_send![request, setContextualStrings: array];
+                for string in strings {
+                    let _: () = msg_send![string, release];
+                }
+
+                let handler = ConcreteBlock::new(move |result: id, error: id| {
+                    if result != nil {
+                        let best: id = msg_send![result, bestTranscription];
+                        let text: id = msg_send![best, formattedString];
+                        on_text(&nsstring_to_string(text));
+                        let is_final: BOOL = msg_send![result, isFinal];
+                        if is_final == YES {
+                            ended.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    if error != nil {
+                        ended.store(true, Ordering::Relaxed);
+                    }
+                })
+                .copy();
+                let task: id = msg_send![
+                    self.recognizer,
+                    recognitionTaskWithRequest: request
+                    resultHandler: &*handler
+                ];
+                let _: () = msg_send![task, retain];
+                self.request = request;
+                self.task = task;
+                let _: () = msg_send![pool, drain];
+            }
+        }
+
+        pub fn append(&self, samples: &[f32]) {
+            if self.request == nil || samples.is_empty() {
+                return;
+            }
+            unsafe {
+                let buffer: id = msg_send![class!(AVAudioPCMBuffer), alloc];
+                let buffer: id = msg_send![
+                    buffer,
+                    initWithPCMFormat: self.format
+                    frameCapacity: samples.len() as u32
+                ];
+                if buffer == nil {
+                    return;
+                }
+                let channels: *const *mut f32 = msg_send![buffer, floatChannelData];
+                std::ptr::copy_nonoverlapping(samples.as_ptr(), *channels, samples.len());
+                let _: () = msg_send![buffer, setFrameLength: samples.len() as u32];
+                let _: () = msg_send![self.request, appendAudioPCMBuffer: buffer];
+                let _: () = msg_send![buffer, release];
+            }
+        }
+
+        /// True once the running task has finished or failed by itself.
+        pub fn task_ended(&self) -> bool {
+            self.ended.load(Ordering::Relaxed)
+        }
+
+        pub fn end(&mut self) {
+            if self.task == nil {
+                return;
+            }
+            unsafe {
+                let _: () = msg_send![self.request, endAudio];
+                let _: () = msg_send![self.task, cancel];
+                let _: () = msg_send![self.task, release];
+                let _: () = msg_send![self.request, release];
+            }
+            self.task = nil;
+            self.request = nil;
+        }
+    }
+
+    impl Drop for Recognizer {
+        fn drop(&mut self) {
+            self.end();
+            unsafe {
+                let _: () = msg_send![self.format, release];
+                let _: () = msg_send![self.recognizer, release];
+            }
+        }
+    }
+
+    fn authorize() -> Result<(), String> {
+        let mut status: isize =
+            unsafe { msg_send![class!(SFSpeechRecognizer), authorizationStatus] };
+        if status == SF_AUTHORIZATION_STATUS_NOT_DETERMINED {
+            let (tx, rx) = std::sync::mpsc::channel();
+            let block = ConcreteBlock::new(move |status: isize| {
+                let _ = tx.send(status);
+            })
+            .copy();
+            unsafe {
+                let _: () = msg_send![class!(SFSpeechRecognizer), requestAuthorization: &*block];
+            }
+            status = rx.recv().map_err(|_| {
+                "Failed to receive speech recognition permission result".to_string()
+            })?;
+        }
+        if status == SF_AUTHORIZATION_STATUS_AUTHORIZED {
+            Ok(())
+        } else {
+            Err("Speech recognition permission not granted".to_string())
+        }
+    }
+
+    fn nsstring_to_string(ns: id) -> String {
+        if ns == nil {
+            return String::new();
+        }
+        unsafe {
+            let bytes: *const c_char = msg_send![ns, UTF8String];
+            if bytes.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(bytes).to_string_lossy().into_owned()
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub struct Recognizer;
+
+    impl Recognizer {
+        pub fn new() -> Result<Self, String> {
+            Err("Voice commands are only available on macOS".to_string())
+        }
+
+        pub fn begin(&mut self, _phrases: &[String], _on_text: impl Fn(&str) + 'static) {}
+
+        pub fn append(&self, _samples: &[f32]) {}
+
+        pub fn task_ended(&self) -> bool {
+            false
+        }
+
+        pub fn end(&mut self) {}
+    }
+}
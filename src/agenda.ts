@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { db } from "./database";
+import { Meeting } from "./types";
+
+export interface AgendaItem {
+  id: number;
+  text: string;
+  covered: boolean;
+  coveredAt: number | null;
+  manual: boolean;
+}
+
+/** Hands the agenda to the backend, which emits `agenda-item-covered` as items come up. */
+export async function loadAgenda(text: string): Promise<AgendaItem[]> {
+  return invoke<AgendaItem[]>("set_agenda", { text });
+}
+
+/** Saves a manually entered agenda on the meeting and starts tracking it. */
+export async function setMeetingAgenda(meeting: Meeting, text: string): Promise<AgendaItem[]> {
+  meeting.agenda = text.trim() || undefined;
+  meeting.updatedAt = new Date();
+  await db.saveMeeting(meeting);
+  return loadAgenda(text);
+}
+
+export async function getAgenda(): Promise<AgendaItem[]> {
+  return invoke<AgendaItem[]>("get_agenda");
+}
+
+export async function setAgendaItemCovered(id: number, covered: boolean): Promise<AgendaItem> {
+  return invoke<AgendaItem>("set_agenda_item_covered", { id, covered });
+}
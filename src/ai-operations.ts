@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { marked } from "marked";
 import DOMPurify from "dompurify";
 import { getCurrentMeeting, updateMeetingInList } from "./state";
@@ -306,6 +307,11 @@ async function performEnhancement(
     await db.saveMeeting(meeting);
 
     updateMeetingInList(meeting);
+    invoke("notify_summary_ready", {
+      meetingId: meeting.id,
+      title: meeting.title,
+      summary: normalizedEnhanced,
+    }).catch((error) => console.error("Failed to run summary hooks:", error));
 
     if (isCurrentMeeting) {
       if (elements.enhancedContent) {
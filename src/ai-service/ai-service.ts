@@ -1,9 +1,10 @@
 import OpenAI from "openai";
-import type { TranscriptTurn } from "../types";
+import type { TranscriptChapter, TranscriptTurn } from "../types";
 import { estimateTokens } from "./token-utils";
 import { enhanceNotes } from "./enhance";
 import { isContextWindowError } from "./errors";
 import { generateTitle as generateTitleHelper } from "./title";
+import { generateChapters as generateChaptersHelper } from "./chapters";
 import { chatWithTranscript as chatWithTranscriptHelper } from "./chat";
 import { prepareTranscriptForPrompt } from "./transcript-format";
 
@@ -77,6 +78,11 @@ export class AIService {
     }
   }
 
+  /** Whether an AI endpoint has been set up, so AI features can be tried at all. */
+  isConfigured(): boolean {
+    return this.client !== null;
+  }
+
   private ensureClient(): OpenAI {
     if (!this.client) {
       throw new Error("OpenAI API key not set");
@@ -147,6 +153,14 @@ export class AIService {
     return generateTitleHelper(deps, prepared.transcriptWithLegend, personalNotes);
   }
 
+  async generateChapters(transcriptTurns: TranscriptTurn[]): Promise<TranscriptChapter[]> {
+    const client = this.ensureClient();
+    return generateChaptersHelper(
+      { client, model: this.model, maxPromptTokens: this.maxPromptTokens, estimateTokens },
+      transcriptTurns
+    );
+  }
+
   async chatWithTranscript(
     transcript: string,
     personalNotes: string,
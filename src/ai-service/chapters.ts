@@ -0,0 +1,86 @@
+import type OpenAI from "openai";
+import type { TranscriptChapter, TranscriptTurn } from "../types";
+import { buildChatCompletionParams } from "./chat-options";
+import { extractFirstChoiceText } from "./response-content";
+
+interface ChapterDeps {
+  client: OpenAI;
+  model: string;
+  maxPromptTokens: number;
+  estimateTokens: (text: string) => number;
+}
+
+const MAX_CHAPTERS = 24;
+/** Room left in the prompt for the instructions around the transcript. */
+const PROMPT_OVERHEAD_TOKENS = 1000;
+
+/**
+ * One numbered line per turn. Turns are cut short evenly when the whole
+ * transcript would not fit in the prompt; the opening words of a turn are
+ * usually enough to tell its topic.
+ */
+function numberedTurns(deps: ChapterDeps, turns: TranscriptTurn[]): string {
+  const lines = turns.map((turn, index) => `[${index}] ${turn.text.replace(/\s+/g, " ").trim()}`);
+  const budget = deps.maxPromptTokens - PROMPT_OVERHEAD_TOKENS;
+  const full = lines.join("\n");
+  if (deps.estimateTokens(full) <= budget) {
+    return full;
+  }
+  // About four characters to a token, as estimateTokens assumes.
+  const perTurn = Math.max(40, Math.floor((budget * 4) / lines.length));
+  return lines.map((line) => line.slice(0, perTurn)).join("\n");
+}
+
+/** Chapters from the model's reply, or `null` if the reply is not a list of them. */
+function parseChapters(content: string, turnCount: number): TranscriptChapter[] | null {
+  const cleaned = content.replace(/^```json\s*/i, "").replace(/^```\s*/i, "").replace(/```$/i, "").trim();
+  let parsed: any;
+  try {
+    parsed = JSON.parse(cleaned);
+  } catch {
+    return null;
+  }
+  const list = Array.isArray(parsed) ? parsed : Array.isArray(parsed?.chapters) ? parsed.chapters : null;
+  if (!list) return null;
+  const chapters: TranscriptChapter[] = [];
+  for (const item of list) {
+    const startTurn = Number(item?.startTurn);
+    const title = typeof item?.title === "string" ? item.title.trim() : "";
+    const previous = chapters[chapters.length - 1];
+    if (!Number.isInteger(startTurn) || startTurn < 0 || startTurn >= turnCount || !title) continue;
+    if (previous && startTurn <= previous.startTurn) continue;
+    chapters.push({ title, startTurn });
+  }
+  if (chapters.length < 2) return [];
+  chapters[0].startTurn = 0;
+  return chapters.slice(0, MAX_CHAPTERS);
+}
+
+/**
+ * Splits a long transcript into chapters where the topic changes, titled in
+ * the meeting's own language. Returns none when the meeting stays on one
+ * subject, and throws when the reply can't be used.
+ */
+export async function generateChapters(deps: ChapterDeps, turns: TranscriptTurn[]): Promise<TranscriptChapter[]> {
+  const response = await deps.client.chat.completions.create(
+    buildChatCompletionParams(
+      deps.model,
+      [
+        {
+          role: "system",
+          content: `You divide meeting transcripts into chapters where the conversation moves to a new topic. Reply with only JSON: {"chapters": [{"startTurn": <turn number>, "title": "<2-5 word title>"}]}. The first chapter starts at turn 0. Use at most ${MAX_CHAPTERS} chapters, and {"chapters": []} if the meeting stays on one topic. Write titles in the language of the transcript.`,
+        },
+        {
+          role: "user",
+          content: `Transcript, one numbered turn per line:\n\n${numberedTurns(deps, turns)}`,
+        },
+      ],
+      1500
+    )
+  );
+  const chapters = parseChapters(extractFirstChoiceText(response), turns.length);
+  if (!chapters) {
+    throw new Error("The model did not reply with a list of chapters");
+  }
+  return chapters;
+}
@@ -0,0 +1,79 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { db } from "./database";
+
+export type BulkExportFormat = "txt" | "srt" | "vtt" | "json";
+
+/** Inclusive `YYYY-MM-DD` bounds; leave either out for an open range. */
+export interface ExportRange {
+  from?: string;
+  to?: string;
+}
+
+/** Payload of `bulk-export-progress`, sent after each meeting. */
+export interface BulkExportProgress {
+  done: number;
+  total: number;
+  meetingId: string;
+}
+
+export interface BulkExportManifest {
+  exportedAt: string;
+  root: string;
+  format: string;
+  range: ExportRange;
+  cancelled: boolean;
+  meetings: {
+    id: string;
+    title: string | null;
+    date: string | null;
+    folder: string;
+    files: string[];
+  }[];
+  skipped: { id: string; reason: string }[];
+}
+
+function notesFor(personalNotes: string, enhancedNotes: string): string | null {
+  const sections = [personalNotes, enhancedNotes].map((notes) => notes?.trim()).filter(Boolean);
+  return sections.length > 0 ? sections.join("\n\n---\n\n") : null;
+}
+
+/**
+ * Exports every meeting in `range` to a new folder under `destination`, with
+ * a `manifest.json` index. Titles, dates and notes come from this device's
+ * meeting list; transcripts and recording data from the meeting store.
+ */
+export async function exportAllMeetings(
+  range: ExportRange | null,
+  format: BulkExportFormat,
+  destination: string,
+  onProgress?: (progress: BulkExportProgress) => void,
+): Promise<BulkExportManifest> {
+  const meetings = (await db.getAllMeetings()).map((meeting) => ({
+    id: meeting.id,
+    title: meeting.title,
+    date: meeting.date.toISOString(),
+    notes: notesFor(meeting.personalNotes, meeting.enhancedNotes),
+  }));
+  let unlisten: UnlistenFn | undefined;
+  if (onProgress) {
+    unlisten = await listen<BulkExportProgress>("bulk-export-progress", (event) => {
+      onProgress(event.payload);
+    });
+  }
+  try {
+    return await invoke<BulkExportManifest>("export_all_meetings", {
+      range,
+      format,
+      destination,
+      meetings,
+    });
+  } finally {
+    unlisten?.();
+  }
+}
+
+/** Stops a running export after the meeting it is on; the manifest says it was cancelled. */
+export function cancelBulkExport(): Promise<void> {
+  return invoke("cancel_bulk_export");
+}
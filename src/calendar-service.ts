@@ -10,16 +10,17 @@ import type {
   Person,
 } from "./types";
 import { db } from "./database";
+import { syncMeetingAttendees } from "./contacts";
 import { getCurrentMeeting, getIsRecording } from "./state";
-import { normalizeEmail } from "./utils";
+import { addDays, normalizeEmail } from "./utils";
 import { ensureNotificationPermission, sendNotification } from "./notifications";
 import {
   NOTIFICATION_EXTRA_TYPE_CALENDAR_EVENT,
   NOTIFICATION_EXTRA_TYPE_MEETING,
 } from "./notification-types";
+import { quietHoursStatus } from "./settings";
 
 const TEAMS_SEPARATOR = "________________________________________________________________________________";
-const DAY_IN_MS = 24 * 60 * 60 * 1000;
 const isDev = import.meta.env.DEV;
 const SYNC_LOOKBACK_DAYS = 30;
 const SYNC_LOOKAHEAD_DAYS = 60;
@@ -365,9 +366,15 @@ class CalendarSyncService {
         return;
       }
 
-      await this.sendUpcomingNotifications(upcomingEvents);
+      // Reminders wait out quiet hours; a recording left running still gets its nudge.
+      const quiet = await quietHoursStatus().catch(() => null);
+      if (!quiet) {
+        await this.sendUpcomingNotifications(upcomingEvents);
+      }
       await this.sendStopNotifications(stopMeetings);
-      await this.sendCompletionNotifications(completionCandidates);
+      if (!quiet) {
+        await this.sendCompletionNotifications(completionCandidates);
+      }
     } catch (error) {
       console.error("Reminder check failed:", error);
     } finally {
@@ -570,9 +577,9 @@ class CalendarSyncService {
     try {
       // Fetch events for a broader window so auto-sync keeps everything in view
       const now = new Date();
-      const rangeStart = new Date(now.getTime() - SYNC_LOOKBACK_DAYS * DAY_IN_MS);
+      const rangeStart = addDays(now, -SYNC_LOOKBACK_DAYS);
       rangeStart.setHours(0, 0, 0, 0);
-      const rangeEnd = new Date(now.getTime() + SYNC_LOOKAHEAD_DAYS * DAY_IN_MS);
+      const rangeEnd = addDays(now, SYNC_LOOKAHEAD_DAYS);
       rangeEnd.setHours(23, 59, 59, 999);
 
       const events = await invoke<CalendarEvent[]>("fetch_calendar_events", {
@@ -652,10 +659,11 @@ class CalendarSyncService {
       const existingEndTime = existing.endTime ? new Date(existing.endTime) : null;
 
       // Update existing meeting if event details changed
+      // Compare instants: the backend's RFC 3339 strings are not in toISOString's format.
       const hasChanged = 
         existing.title !== event.title ||
-        existingStartTime?.toISOString() !== event.start_time ||
-        existingEndTime?.toISOString() !== event.end_time ||
+        existingStartTime?.getTime() !== new Date(event.start_time).getTime() ||
+        existingEndTime?.getTime() !== new Date(event.end_time).getTime() ||
         existing.location !== event.location ||
         existing.calendarName !== event.calendar_name;
 
@@ -681,6 +689,9 @@ class CalendarSyncService {
         existing.updatedAt = new Date();
         
         await db.saveMeeting(existing);
+        if (participantsChanged || hasChanged) {
+          await syncMeetingAttendees(existing);
+        }
       }
     }
   }
@@ -910,6 +921,7 @@ class CalendarSyncService {
       title: event.title,
       startTime: event.start_time,
       endTime: event.end_time,
+      timeZone: event.time_zone ?? undefined,
       calendarId: event.calendar_id,
       calendarName: event.calendar_name,
       location: event.location,
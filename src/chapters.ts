@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core";
+import { aiService } from "./ai-service";
+import { TranscriptChapter, TranscriptTurn } from "./types";
+
+/** Transcripts shorter than this stay in one piece, as the backend leaves them. */
+const MIN_CHAPTER_WORDS = 1500;
+
+/**
+ * Topic sections of a finished meeting's transcript. Short meetings, and
+ * meetings the backend has no transcript for, have none; errors are only logged.
+ */
+export async function getMeetingChapters(meetingId: string): Promise<TranscriptChapter[]> {
+  try {
+    return await invoke<TranscriptChapter[]>("get_meeting_chapters", { meetingId });
+  } catch (error) {
+    console.warn("Failed to load meeting chapters:", error);
+    return [];
+  }
+}
+
+/**
+ * Chapters for a meeting that just finished recording. With an AI endpoint
+ * set up, the model finds where the topic changes and its chapters replace the
+ * backend's word-based ones; without one, or if that fails, those are kept.
+ */
+export async function detectMeetingChapters(
+  meetingId: string,
+  transcript: TranscriptTurn[]
+): Promise<TranscriptChapter[]> {
+  const words = transcript.reduce((count, turn) => count + turn.text.split(/\s+/).filter(Boolean).length, 0);
+  if (aiService.isConfigured() && words >= MIN_CHAPTER_WORDS) {
+    try {
+      const chapters = await aiService.generateChapters(transcript);
+      return await invoke<TranscriptChapter[]>("save_meeting_chapters", { meetingId, chapters });
+    } catch (error) {
+      console.warn("AI chapters failed; keeping word-based chapters:", error);
+    }
+  }
+  return getMeetingChapters(meetingId);
+}
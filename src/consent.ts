@@ -0,0 +1,63 @@
+import { invoke } from "@tauri-apps/api/core";
+import { TranscriptTurn } from "./types";
+
+export type ConsentStatus = "requested" | "granted" | "declined";
+
+export interface AttendeeConsent {
+  email: string;
+  name: string | null;
+  status: ConsentStatus;
+  /** Label their speech is diarized under, e.g. `S2`; their name is used without it. */
+  speaker: string | null;
+  updatedAt: string;
+}
+
+export interface MeetingConsent {
+  /** Leave declined attendees' speech out of the saved transcript and exports. */
+  excludeDeclined: boolean;
+  attendees: AttendeeConsent[];
+}
+
+export interface ConsentUpdate {
+  email: string;
+  name?: string | null;
+  status: ConsentStatus;
+  /** Replaces the speaker label; an empty string clears it. */
+  speaker?: string;
+}
+
+export async function getMeetingConsent(meetingId: string): Promise<MeetingConsent> {
+  return invoke<MeetingConsent>("get_meeting_consent", { meetingId });
+}
+
+export async function setAttendeeConsent(meetingId: string, update: ConsentUpdate): Promise<MeetingConsent> {
+  return invoke<MeetingConsent>("set_attendee_consent", { meetingId, update });
+}
+
+export async function setConsentExclusion(meetingId: string, excludeDeclined: boolean): Promise<MeetingConsent> {
+  return invoke<MeetingConsent>("set_consent_exclusion", { meetingId, excludeDeclined });
+}
+
+/**
+ * `turns` without declined attendees' speech, when the meeting excludes it.
+ * When the consent can't be read, all speech is dropped and only typed notes are kept.
+ */
+export async function withoutDeclinedSpeech(meetingId: string, turns: TranscriptTurn[]): Promise<TranscriptTurn[]> {
+  let consent: MeetingConsent;
+  try {
+    consent = await getMeetingConsent(meetingId);
+  } catch (error) {
+    console.warn("Failed to read meeting consent:", error);
+    return turns.filter((turn) => turn.note);
+  }
+  if (!consent.excludeDeclined) {
+    return turns;
+  }
+  const declined = new Set(
+    consent.attendees
+      .filter((attendee) => attendee.status === "declined")
+      .map((attendee) => (attendee.speaker ?? attendee.name ?? "").trim().toLowerCase())
+      .filter((label) => label.length > 0)
+  );
+  return turns.filter((turn) => !turn.speaker || !declined.has(turn.speaker.trim().toLowerCase()));
+}
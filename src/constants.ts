@@ -48,7 +48,9 @@ export const GLOBAL_SHORTCUT_DEBOUNCE_MS = 100;
 
 // Local Storage Keys
 export const STORAGE_KEY_SPEECHMATICS_API = "speechmatics_api_key";
+export const STORAGE_KEY_DEEPGRAM_API = "deepgram_api_key";
 export const STORAGE_KEY_SPEECHMATICS_URL = "speechmatics_url";
+export const STORAGE_KEY_DEBUG_AUDIO_DUMP = "debug_audio_dump";
 export const STORAGE_KEY_OPENAI_API = "openai_api_key";
 export const STORAGE_KEY_OPENAI_ENDPOINT = "openai_endpoint";
 export const STORAGE_KEY_OPENAI_MODEL = "openai_model";
@@ -60,6 +62,8 @@ export const STORAGE_KEY_EXPORT_PATH = "export_path";
 export const STORAGE_KEY_DEFAULT_TEMPLATE = "default_template_id";
 export const STORAGE_KEY_CALENDAR_SETTINGS = "calendar_settings";
 export const STORAGE_KEY_APP_MODE = "app_mode";
+export const STORAGE_KEY_INTERVIEW_MODE = "interview_mode";
+export const STORAGE_KEY_DISMISSED_DUPLICATES = "dismissed_duplicate_meetings";
 export const STORAGE_KEY_PEOPLE_SECTION_EXPANDED = "people_section_expanded";
 
 export const DEFAULT_SPEECHMATICS_URL = "wss://eu2.rt.speechmatics.com/v2";
@@ -0,0 +1,68 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Meeting } from "./types";
+
+/** One meeting in a contact's history, as returned by `get_contact_history`. */
+export interface ContactMeeting {
+  meetingId: string;
+  title: string;
+  date: string;
+  /** Missing when the meeting was not recorded or no speaker had the contact's name. */
+  speakingSecs: number | null;
+  words: number | null;
+  /** Share of all speaking time in the meeting, from 0 to 1. */
+  talkShare: number | null;
+}
+
+export interface TalkTrend {
+  meetings: number;
+  overallShare: number;
+  /** Average over their five latest meetings with speaking data. */
+  recentShare: number;
+}
+
+export interface ContactHistory {
+  email: string;
+  name: string | null;
+  notes: string;
+  /** Newest first. */
+  meetings: ContactMeeting[];
+  talkTrend: TalkTrend | null;
+}
+
+/**
+ * Tells the backend who was invited to `meeting`. Errors are only logged so a
+ * failure never gets in the way of saving the meeting itself.
+ */
+export async function syncMeetingAttendees(meeting: Meeting): Promise<void> {
+  const attendees = (meeting.participants ?? [])
+    .filter((participant) => participant.email)
+    .map((participant) => ({ email: participant.email, name: participant.name }));
+  try {
+    await invoke("record_meeting_attendees", {
+      args: {
+        meetingId: meeting.id,
+        title: meeting.title,
+        date: new Date(meeting.startTime ?? meeting.date).toISOString(),
+        attendees,
+      },
+    });
+  } catch (error) {
+    console.warn("Failed to update contact history:", error);
+  }
+}
+
+export async function forgetMeetingAttendees(meetingId: string): Promise<void> {
+  try {
+    await invoke("forget_meeting_attendees", { meetingId });
+  } catch (error) {
+    console.warn("Failed to update contact history:", error);
+  }
+}
+
+export function getContactHistory(email: string): Promise<ContactHistory> {
+  return invoke<ContactHistory>("get_contact_history", { email });
+}
+
+export function setContactNotes(email: string, notes: string): Promise<void> {
+  return invoke("set_contact_notes", { email, notes });
+}
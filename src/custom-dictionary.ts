@@ -8,6 +8,7 @@ import {
   parseSoundsLikeInput,
 } from "./utils/text";
 import { CUSTOM_DICTIONARY_LIMIT } from "./constants";
+import { syncCustomDictionaryToBackend } from "./settings";
 
 export { CUSTOM_DICTIONARY_LIMIT };
 
@@ -26,9 +27,11 @@ export interface UpsertDictionaryResult {
   isNew: boolean;
 }
 
+/** `speakers` replaces an existing term's speakers; leaving it out keeps them. */
 export async function upsertCustomDictionaryEntry(
   content: string,
-  soundsLike: string[] = []
+  soundsLike: string[] = [],
+  speakers?: string[]
 ): Promise<UpsertDictionaryResult> {
   const sanitizedContent = sanitizeDictionaryContent(content);
   if (!sanitizedContent) {
@@ -47,9 +50,11 @@ export async function upsertCustomDictionaryEntry(
       ...existing,
       content: sanitizedContent,
       soundsLike: mergedSounds,
+      speakers: speakers ? sanitizeSoundsLike(speakers) : existing.speakers,
       updatedAt: new Date(),
     };
     await db.saveCustomDictionaryEntry(entry);
+    await syncCustomDictionaryToBackend();
     return { entry, isNew: false };
   }
 
@@ -65,16 +70,18 @@ export async function upsertCustomDictionaryEntry(
     id: generateId(),
     content: sanitizedContent,
     soundsLike: sanitizedSounds,
+    speakers: sanitizeSoundsLike(speakers),
     createdAt: now,
     updatedAt: now,
   };
   await db.saveCustomDictionaryEntry(entry);
+  await syncCustomDictionaryToBackend();
   return { entry, isNew: true };
 }
 
 export async function updateCustomDictionaryEntry(
   id: string,
-  updates: { content?: string; soundsLike?: string[] }
+  updates: { content?: string; soundsLike?: string[]; speakers?: string[] }
 ): Promise<CustomDictionaryEntry> {
   const existing = await db.getCustomDictionaryEntry(id);
   if (!existing) {
@@ -103,17 +110,24 @@ export async function updateCustomDictionaryEntry(
     soundsLike = sanitizeSoundsLike(updates.soundsLike);
   }
 
+  const speakers = Array.isArray(updates.speakers)
+    ? sanitizeSoundsLike(updates.speakers)
+    : existing.speakers;
+
   const entry: CustomDictionaryEntry = {
     ...existing,
     content,
     soundsLike,
+    speakers,
     updatedAt: new Date(),
   };
 
   await db.saveCustomDictionaryEntry(entry);
+  await syncCustomDictionaryToBackend();
   return entry;
 }
 
 export async function deleteCustomDictionaryEntry(id: string): Promise<void> {
   await db.deleteCustomDictionaryEntry(id);
+  await syncCustomDictionaryToBackend();
 }
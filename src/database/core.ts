@@ -3,7 +3,6 @@
  */
 
 import {
-  DB_NAME,
   DB_VERSION,
   STORE_NAME,
   TASKS_STORE_NAME,
@@ -11,6 +10,7 @@ import {
   PEOPLE_STORE,
   CUSTOM_DICTIONARY_STORE,
 } from "../constants";
+import { profileDatabaseName } from "../profile";
 
 export class DatabaseCore {
   private db: IDBDatabase | null = null;
@@ -41,7 +41,8 @@ export class DatabaseCore {
 
   private openDatabase(version?: number): Promise<IDBDatabase> {
     return new Promise((resolve, reject) => {
-      const request = version ? indexedDB.open(DB_NAME, version) : indexedDB.open(DB_NAME);
+      const name = profileDatabaseName();
+      const request = version ? indexedDB.open(name, version) : indexedDB.open(name);
 
       request.onerror = () => reject(request.error);
       request.onsuccess = () => resolve(request.result);
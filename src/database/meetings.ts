@@ -91,11 +91,15 @@ export async function deleteAllMeetings(): Promise<void> {
 
 export async function searchMeetings(
   query: string,
-  options: { fullContent?: boolean } = {}
+  options: {
+    fullContent?: boolean;
+    /** Meetings whose transcripts are known to match, e.g. from the backend's index; transcripts aren't scanned here when given. */
+    transcriptMatches?: Set<string>;
+  } = {}
 ): Promise<Meeting[]> {
   const allMeetings = await getAllMeetings();
   const lowerQuery = query.toLowerCase();
-  const { fullContent = false } = options;
+  const { fullContent = false, transcriptMatches } = options;
   const includesQuery = (value?: string) =>
     typeof value === "string" && value.toLowerCase().includes(lowerQuery);
 
@@ -108,10 +112,12 @@ export async function searchMeetings(
       return false;
     }
 
-    const transcriptText = transcriptTurnsToText(meeting.transcript).toLowerCase();
+    const transcriptMatch = transcriptMatches
+      ? transcriptMatches.has(meeting.id)
+      : transcriptTurnsToText(meeting.transcript).toLowerCase().includes(lowerQuery);
 
     return (
-      transcriptText.includes(lowerQuery) ||
+      transcriptMatch ||
       includesQuery(meeting.personalNotes) ||
       includesQuery(meeting.enhancedNotes) ||
       meeting.participants?.some(participant =>
@@ -38,6 +38,7 @@ export const elements: DOMElements = {
   customDictionaryForm: null,
   customDictionaryTermInput: null,
   customDictionarySoundsLikeInput: null,
+  customDictionarySpeakersInput: null,
   customDictionaryCancelBtn: null,
   calendarSyncEnabled: null,
   requestCalendarPermissionBtn: null,
@@ -68,6 +69,7 @@ export const elements: DOMElements = {
   startRecordingBtn: null,
   stopRecordingBtn: null,
   muteBtn: null,
+  shareLiveBtn: null,
   inlineAudioDeviceSelect: null,
   micActivity: null,
   micActivityDot: null,
@@ -194,6 +196,7 @@ export function initializeElements() {
     customDictionaryForm: getElementById<HTMLFormElement>("custom-dictionary-form"),
     customDictionaryTermInput: getElementById<HTMLInputElement>("custom-dictionary-term-input"),
     customDictionarySoundsLikeInput: getElementById<HTMLInputElement>("custom-dictionary-sounds-like-input"),
+    customDictionarySpeakersInput: getElementById<HTMLInputElement>("custom-dictionary-speakers-input"),
     customDictionaryCancelBtn: getElementById<HTMLButtonElement>("custom-dictionary-cancel-btn"),
     calendarSyncEnabled: getElementById<HTMLInputElement>("calendar-sync-enabled"),
     requestCalendarPermissionBtn: getElementById("request-calendar-permission-btn"),
@@ -224,6 +227,7 @@ export function initializeElements() {
     startRecordingBtn: getElementById<HTMLButtonElement>("start-recording-btn"),
     stopRecordingBtn: getElementById<HTMLButtonElement>("stop-recording-btn"),
     muteBtn: getElementById<HTMLButtonElement>("mute-btn"),
+    shareLiveBtn: getElementById<HTMLButtonElement>("share-live-btn"),
     inlineAudioDeviceSelect: getElementById<HTMLSelectElement>("inline-audio-device"),
     micActivity: getElementById("mic-activity"),
     micActivityDot: getElementById("mic-activity-dot"),
@@ -0,0 +1,119 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { STORAGE_KEY_DISMISSED_DUPLICATES } from "./constants";
+import { forgetMeetingAttendees } from "./contacts";
+import { db } from "./database";
+import { isHtmlEmpty } from "./file-export/html-converter";
+import { loadMeeting } from "./meeting-operations";
+import { removeMeetingFromProject } from "./projects";
+import { getCurrentMeeting, removeMeetingFromList } from "./state";
+import { TranscriptTurn } from "./types";
+import { renderMeetingsList } from "./ui/sidebar";
+import { showToast } from "./ui/interactions";
+
+export interface DuplicateMeetings {
+  /** The earlier recording, which a merge keeps. */
+  keepId: string;
+  duplicateId: string;
+  calendarEventId: string;
+  /** Seconds between the two recordings; 0 when they overlap. */
+  gapSecs: number;
+}
+
+function pairKey(pair: DuplicateMeetings): string {
+  return `${pair.keepId}:${pair.duplicateId}`;
+}
+
+function dismissedPairs(): Set<string> {
+  try {
+    return new Set(JSON.parse(localStorage.getItem(STORAGE_KEY_DISMISSED_DUPLICATES) ?? "[]"));
+  } catch {
+    return new Set();
+  }
+}
+
+function dismissPair(pair: DuplicateMeetings): void {
+  const dismissed = dismissedPairs();
+  dismissed.add(pairKey(pair));
+  localStorage.setItem(STORAGE_KEY_DISMISSED_DUPLICATES, JSON.stringify([...dismissed]));
+}
+
+/**
+ * Looks for meetings of the same calendar event recorded twice, as happens
+ * when a broken session is started again, and offers to merge each pair.
+ * With `meetingId`, only pairs that meeting is part of are offered.
+ */
+export async function checkForDuplicateMeetings(meetingId?: string): Promise<void> {
+  const meetings = (await db.getAllMeetings())
+    .filter((meeting) => meeting.calendarEventId)
+    .map((meeting) => ({ id: meeting.id, calendarEventId: meeting.calendarEventId }));
+  if (meetings.length < 2) return;
+
+  const pairs = await invoke<DuplicateMeetings[]>("find_duplicate_meetings", { meetings });
+  const dismissed = dismissedPairs();
+  for (const pair of pairs) {
+    if (dismissed.has(pairKey(pair))) continue;
+    if (meetingId && pair.keepId !== meetingId && pair.duplicateId !== meetingId) continue;
+    const keep = await db.getMeeting(pair.keepId);
+    if (!keep) continue;
+
+    const minutes = Math.round(pair.gapSecs / 60);
+    const when =
+      pair.gapSecs === 0 ? "while the first was still running" : minutes < 1 ? "straight after the first" : `${minutes} min after the first`;
+    const merge = await ask(
+      `"${keep.title}" seems to have been recorded twice: a second recording started ${when}. Merge them into one meeting?`,
+      { title: "Duplicate Meeting", okLabel: "Merge", cancelLabel: "Keep Both" }
+    );
+    if (!merge) {
+      dismissPair(pair);
+      continue;
+    }
+    try {
+      await mergeMeetings(pair.keepId, pair.duplicateId);
+      showToast(`Merged the two recordings of "${keep.title}".`, { type: "success" });
+    } catch (error) {
+      console.error("Failed to merge meetings:", error);
+      showToast(`Failed to merge meetings: ${error}`, { type: "error", duration: 5000 });
+    }
+  }
+}
+
+/**
+ * Merges `duplicateId` into `keepId`: the backend stitches the transcripts
+ * and moves the audio, and here notes and tags are carried over before the
+ * duplicate is deleted.
+ */
+export async function mergeMeetings(keepId: string, duplicateId: string): Promise<void> {
+  const turns = await invoke<TranscriptTurn[]>("merge_meetings", { keepId, duplicateId });
+  const keep = await db.getMeeting(keepId);
+  const duplicate = await db.getMeeting(duplicateId);
+  if (!keep) throw new Error("The meeting to keep no longer exists");
+
+  keep.transcript = turns;
+  if (duplicate) {
+    if (!isHtmlEmpty(duplicate.personalNotes)) {
+      keep.personalNotes = isHtmlEmpty(keep.personalNotes)
+        ? duplicate.personalNotes
+        : `${keep.personalNotes}${duplicate.personalNotes}`;
+    }
+    if (!keep.enhancedNotes?.trim()) {
+      keep.enhancedNotes = duplicate.enhancedNotes;
+    }
+    if (duplicate.tags?.length) {
+      keep.tags = [...new Set([...(keep.tags ?? []), ...duplicate.tags])];
+    }
+  }
+  keep.updatedAt = new Date();
+  await db.saveMeeting(keep);
+
+  await db.deleteMeeting(duplicateId);
+  await forgetMeetingAttendees(duplicateId);
+  await removeMeetingFromProject(duplicateId);
+  removeMeetingFromList(duplicateId);
+  renderMeetingsList();
+
+  const currentId = getCurrentMeeting()?.id;
+  if (currentId === keepId || currentId === duplicateId) {
+    await loadMeeting(keepId);
+  }
+}
@@ -0,0 +1,37 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Version of the backend event contract this frontend was written against. */
+export const EVENT_SCHEMA_VERSION = 2;
+
+export interface EventSpec {
+  name: string;
+  /** `session` events of sessions other than the main one arrive as `session/<id>/<name>`. */
+  scope: "app" | "session";
+  /** Payload shape in TypeScript notation. */
+  payload: string;
+  description: string;
+}
+
+export interface EventSchema {
+  version: number;
+  events: EventSpec[];
+}
+
+/** Every event the backend emits, with their payload shapes. */
+export async function getEventSchema(): Promise<EventSchema> {
+  return invoke<EventSchema>("get_event_schema");
+}
+
+/** Warns in the console when the backend's events have moved on from what the listeners here expect. */
+export async function checkEventSchema(): Promise<void> {
+  try {
+    const schema = await getEventSchema();
+    if (schema.version !== EVENT_SCHEMA_VERSION) {
+      console.warn(
+        `Backend event schema is version ${schema.version}, the frontend expects ${EVENT_SCHEMA_VERSION}`
+      );
+    }
+  } catch (error) {
+    console.warn("Failed to read the event schema:", error);
+  }
+}
@@ -1,8 +1,28 @@
 import { invoke } from "@tauri-apps/api/core";
-import { Meeting } from "../types";
+import { Meeting, TranscriptTurn } from "../types";
 import { getExportSettings } from './export-settings';
+import { getMeetingProject } from '../projects';
+import { withoutDeclinedSpeech } from '../consent';
 import { htmlToMarkdown, isHtmlEmpty } from './html-converter';
-import { hasTranscriptContent, transcriptTurnsToText } from "../utils";
+import {
+  chapterContents,
+  hasTranscriptContent,
+  transcriptTurnsToLinkedText,
+  transcriptTurnsToText,
+} from "../utils";
+
+/** `turns` with dates, times and amounts written the export locale's way, when that is on. */
+async function normalizedForExport(turns: TranscriptTurn[]): Promise<TranscriptTurn[]> {
+  try {
+    const texts = await invoke<string[]>("normalize_export_text", {
+      texts: turns.map((turn) => turn.text),
+    });
+    return turns.map((turn, index) => ({ ...turn, text: texts[index] ?? turn.text }));
+  } catch (error) {
+    console.warn("Failed to normalize transcript for export:", error);
+    return turns;
+  }
+}
 
 // Sanitize meeting title to create a valid folder name
 export function sanitizeFolderName(title: string): string {
@@ -29,11 +49,11 @@ export function sanitizeFolderName(title: string): string {
 }
 
 // Check if meeting should be exported
-export function shouldExportMeeting(meeting: Meeting): boolean {
+export function shouldExportMeeting(meeting: Meeting, options: { force?: boolean } = {}): boolean {
   const settings = getExportSettings();
   
-  // Don't export if feature is disabled
-  if (!settings.enabled) {
+  // Don't export if feature is disabled, unless a recording preset asked for it
+  if (!settings.enabled && !options.force) {
     return false;
   }
 
@@ -73,8 +93,11 @@ export function generateMarkdownContent(meeting: Meeting, type: "transcript" | "
   // Add content based on type
   switch (type) {
     case "transcript":
-      if (hasTranscriptContent(meeting.transcript)) {
-        content += transcriptTurnsToText(meeting.transcript);
+      if (meeting.transcript?.some((turn) => typeof turn.offset === "number")) {
+        content += chapterContents(meeting.transcript, meeting.chapters);
+        content += transcriptTurnsToLinkedText(meeting.transcript, meeting.id, meeting.chapters);
+      } else if (hasTranscriptContent(meeting.transcript)) {
+        content += transcriptTurnsToText(meeting.transcript, meeting.chapters);
       } else {
         content += "*No transcript available*";
       }
@@ -102,19 +125,21 @@ export function generateMarkdownContent(meeting: Meeting, type: "transcript" | "
 }
 
 // Export meeting to files
-export async function exportMeeting(meeting: Meeting): Promise<void> {
-  if (!shouldExportMeeting(meeting)) {
+export async function exportMeeting(meeting: Meeting, options: { force?: boolean } = {}): Promise<void> {
+  if (!shouldExportMeeting(meeting, options)) {
     return;
   }
 
   const settings = getExportSettings();
+  // Meetings in a project go to the project's folder when it has one.
+  const exportPath = (await getMeetingProject(meeting.id))?.exportPath || settings.exportPath;
   const folderName = sanitizeFolderName(meeting.title);
-  const folderPath = `${settings.exportPath}/${folderName}`;
+  const folderPath = `${exportPath}/${folderName}`;
 
   try {
     // Check if we need to rename the folder (title changed)
     if (meeting.lastExportedFolderName && meeting.lastExportedFolderName !== folderName) {
-      const oldFolderPath = `${settings.exportPath}/${meeting.lastExportedFolderName}`;
+      const oldFolderPath = `${exportPath}/${meeting.lastExportedFolderName}`;
       const exists = await invoke<boolean>("directory_exists", { path: oldFolderPath });
       
       if (exists) {
@@ -133,8 +158,11 @@ export async function exportMeeting(meeting: Meeting): Promise<void> {
     await invoke("create_directory", { path: folderPath });
 
     // Write transcript file (only if not empty)
-    if (hasTranscriptContent(meeting.transcript)) {
-      const transcriptContent = generateMarkdownContent(meeting, "transcript");
+    const transcript = await normalizedForExport(
+      await withoutDeclinedSpeech(meeting.id, meeting.transcript ?? [])
+    );
+    if (hasTranscriptContent(transcript)) {
+      const transcriptContent = generateMarkdownContent({ ...meeting, transcript }, "transcript");
       await invoke("write_file", {
         path: `${folderPath}/transcript.md`,
         content: transcriptContent,
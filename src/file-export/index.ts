@@ -9,3 +9,6 @@ export {
   exportMeeting,
   initializeDefaultExportPath,
 } from './export-operations.ts';
+export type { TranscriptExportFormat } from './transcript-export.ts';
+export type { DocumentExportFormat } from './transcript-export.ts';
+export { exportTranscript, exportMeetingDocument } from './transcript-export.ts';
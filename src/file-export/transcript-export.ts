@@ -0,0 +1,53 @@
+import { invoke } from "@tauri-apps/api/core";
+import { Meeting, TurnSource } from "../types";
+
+export type TranscriptExportFormat = "txt" | "srt" | "vtt" | "json";
+
+/**
+ * Writes a transcript to `path` (absolute). SRT and WebVTT get one cue per
+ * turn, led by the speaker, for subtitling a video of the meeting; they fail
+ * for transcripts without timings. Without `meetingId` the current or last
+ * recording is exported. `source` keeps only the turns heard on the microphone
+ * or only those from system audio.
+ */
+export function exportTranscript(
+  format: TranscriptExportFormat,
+  path: string,
+  options: { meetingId?: string; sessionId?: string; source?: TurnSource } = {}
+): Promise<void> {
+  return invoke("export_transcript", {
+    format,
+    path,
+    meetingId: options.meetingId ?? null,
+    sessionId: options.sessionId ?? null,
+    source: options.source ?? null,
+  });
+}
+
+export type DocumentExportFormat = "docx" | "pdf";
+
+/**
+ * Saves a finished meeting to `path` (absolute) as a Word or PDF document
+ * headed by its title, date and attendees, with each turn under its
+ * speaker's name.
+ */
+export function exportMeetingDocument(
+  meeting: Meeting,
+  format: DocumentExportFormat,
+  path: string,
+  source?: TurnSource
+): Promise<void> {
+  return invoke("export_meeting_document", {
+    meeting: {
+      id: meeting.id,
+      title: meeting.title,
+      date: meeting.date.toISOString(),
+      attendees: (meeting.participants ?? [])
+        .map((participant) => participant.name || participant.email)
+        .filter((name): name is string => !!name),
+    },
+    format,
+    path,
+    source: source ?? null,
+  });
+}
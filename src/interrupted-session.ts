@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { loadMeeting } from "./meeting-operations";
+import { startRecording } from "./recording";
+import { getCurrentMeeting } from "./state";
+
+/** Payload of `get_interrupted_session`. */
+interface InterruptedSession {
+  meetingId: string;
+  meetingTitle: string | null;
+  presetId: string | null;
+  startedAt: string;
+}
+
+/**
+ * If the app was closed mid-recording, by a crash or a forced restart, offers
+ * to carry on in the same meeting; the transcript continues after a gap marker.
+ */
+export async function initializeInterruptedSession(): Promise<void> {
+  const session = await invoke<InterruptedSession | null>("get_interrupted_session").catch((error) => {
+    console.error("Failed to check for an interrupted recording:", error);
+    return null;
+  });
+  if (!session) return;
+
+  const title = session.meetingTitle?.trim() || "a meeting";
+  const resume = await ask(
+    `Jilu closed while recording "${title}". Carry on recording into the same meeting?`,
+    { title: "Resume Recording", okLabel: "Resume", cancelLabel: "Discard" }
+  );
+  if (!resume) {
+    await invoke("discard_interrupted_session");
+    return;
+  }
+
+  await loadMeeting(session.meetingId);
+  if (getCurrentMeeting()?.id !== session.meetingId) {
+    await invoke("discard_interrupted_session");
+    return;
+  }
+  await startRecording({ presetId: session.presetId, resumeInterrupted: true });
+}
@@ -0,0 +1,40 @@
+import { invoke } from "@tauri-apps/api/core";
+import { STORAGE_KEY_INTERVIEW_MODE } from "./constants";
+
+export interface QaPair {
+  question: string;
+  askedBy: string | null;
+  askedAt: number | null;
+  answer: string;
+  answeredBy: string[];
+}
+
+export interface InterviewDocument {
+  localSpeaker: string | null;
+  pairs: QaPair[];
+}
+
+/** Payload of the `interview-question` event, sent as each question turn completes. */
+export interface DetectedQuestion {
+  turnIndex: number;
+  text: string;
+  speaker: string | null;
+  start: number | null;
+}
+
+export function isInterviewMode(): boolean {
+  return localStorage.getItem(STORAGE_KEY_INTERVIEW_MODE) === "true";
+}
+
+/** Applies from the next recording. Questions are attributed to the enrolled speaker. */
+export function setInterviewMode(enabled: boolean): void {
+  localStorage.setItem(STORAGE_KEY_INTERVIEW_MODE, String(enabled));
+}
+
+export async function getInterviewQa(): Promise<InterviewDocument> {
+  return invoke<InterviewDocument>("get_interview_qa");
+}
+
+export async function exportInterview(format: "markdown" | "json"): Promise<string> {
+  return invoke<string>("export_interview", { format });
+}
@@ -0,0 +1,52 @@
+import { listen } from "@tauri-apps/api/event";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { switchLanguage } from "./recording";
+import { getIsRecording } from "./state";
+import { showToast } from "./ui/interactions";
+
+/** Payload of `language-mismatch-suspected`. */
+interface LanguageMismatch {
+  language: string;
+  suggestedLanguage: string | null;
+  averageConfidence: number | null;
+  reason: "lowConfidence" | "providerLanguage";
+}
+
+function languageName(code: string): string {
+  try {
+    return new Intl.DisplayNames([navigator.language], { type: "language" }).of(code) ?? code;
+  } catch {
+    return code;
+  }
+}
+
+/**
+ * The backend warns when the first minute of speech does not sound like the
+ * transcription language; offer to carry on in the one it suggests.
+ */
+export async function initializeLanguageMismatch(): Promise<void> {
+  await listen<LanguageMismatch>("language-mismatch-suspected", async (event) => {
+    if (!getIsRecording()) return;
+    const { language, suggestedLanguage } = event.payload;
+    const current = languageName(language);
+    if (!suggestedLanguage) {
+      showToast(
+        `This meeting may not be in ${current}. Check the transcription language, or the transcript may be unusable.`,
+        { type: "warning", duration: 10000 }
+      );
+      return;
+    }
+    const suggested = languageName(suggestedLanguage);
+    const switchNow = await ask(
+      `The speech in this meeting does not sound like ${current}. Continue transcribing in ${suggested}?\n\nThe transcript so far is kept.`,
+      { title: "Transcription Language", okLabel: `Switch to ${suggested}`, cancelLabel: `Keep ${current}` }
+    );
+    if (!switchNow) return;
+    try {
+      await switchLanguage(suggestedLanguage);
+    } catch (error) {
+      console.error("Failed to switch language:", error);
+      showToast(`Failed to switch language: ${error}`, { type: "error", duration: 5000 });
+    }
+  });
+}
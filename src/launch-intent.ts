@@ -0,0 +1,166 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { createMeetingFromCalendarEvent, createNewMeeting, loadMeeting } from "./meeting-operations";
+import { sendNotification } from "./notifications";
+import { startRecording, stopRecording, toggleMute } from "./recording";
+import { quietHoursStatus } from "./settings";
+import { getCurrentMeeting, getIsRecording, getRecordingMeetingId } from "./state";
+import { focusTranscriptOffset } from "./ui/transcript";
+
+type LaunchIntent =
+  | { action: "show" }
+  | { action: "startRecording"; eventId: string | null; presetId: string | null }
+  | { action: "quickCapture" }
+  | { action: "stopRecording" }
+  | { action: "toggleMute" }
+  | { action: "sendTranscript"; app: string }
+  | { action: "openMeeting"; meetingId: string; at: number | null };
+
+/** `confirm` is set for links that would start, stop or mute a recording. */
+type LaunchRequest = LaunchIntent & { confirm: boolean };
+
+/** Asks before a link changes the recording; any web page can open one. */
+function confirmLink(message: string, okLabel: string, cancelLabel: string): Promise<boolean> {
+  return ask(message, { title: "Jilu Link", okLabel, cancelLabel });
+}
+
+/**
+ * Lets the user know a quick capture is about to record them. A link, or a
+ * launch without notifications, has to be agreed to in a dialog instead;
+ * returns whether to go ahead.
+ */
+async function announceQuickCapture(confirm: boolean): Promise<boolean> {
+  if (!confirm) {
+    const notified = await sendNotification("Jilu is recording", {
+      body: "A quick capture started recording a new meeting. Stop it from the menu bar or the window.",
+      tag: "quick-capture",
+    });
+    if (notified) return true;
+  }
+  return ask("Another app asked Jilu to start recording a new meeting. Start recording?", {
+    title: "Quick Capture",
+    okLabel: "Record",
+    cancelLabel: "Don't Record",
+  });
+}
+
+/** Starts recording into a fresh untitled meeting; the title is generated when it stops. */
+async function quickCapture(confirm: boolean): Promise<void> {
+  let meetingId: string | null = null;
+  let error: string | null = null;
+  try {
+    if (getIsRecording()) {
+      error = "Already recording";
+    } else if (await quietHoursStatus().catch(() => null)) {
+      // The backend refuses it anyway; this avoids an empty meeting and a notification.
+      error = "Quiet hours";
+    } else if (!(await announceQuickCapture(confirm))) {
+      error = "Declined";
+    } else {
+      await createNewMeeting();
+      if (await startRecording({ automatic: true })) {
+        meetingId = getRecordingMeetingId();
+      } else {
+        error = "Recording did not start";
+      }
+    }
+  } catch (err) {
+    error = String(err);
+  }
+  await invoke("complete_quick_capture", { meetingId, error });
+}
+
+/** Asks before another app gets the transcript; links can come from anywhere. */
+async function sendTranscript(app: string): Promise<void> {
+  let approved = false;
+  try {
+    approved = await ask(`${app} is asking for the transcript of the current recording. Send it?`, {
+      title: "Send Transcript",
+      okLabel: "Send",
+      cancelLabel: "Don't Send",
+    });
+  } finally {
+    await invoke("complete_transcript_request", { approved });
+  }
+}
+
+async function handleLaunchIntent(intent: LaunchRequest): Promise<void> {
+  if (intent.action === "sendTranscript") {
+    await sendTranscript(intent.app);
+    return;
+  }
+  if (intent.action === "quickCapture") {
+    await quickCapture(intent.confirm);
+    return;
+  }
+  if (intent.action === "openMeeting") {
+    await loadMeeting(intent.meetingId);
+    if (getCurrentMeeting()?.id !== intent.meetingId) {
+      console.warn(`Meeting ${intent.meetingId} from link not found`);
+      return;
+    }
+    if (intent.at !== null) {
+      requestAnimationFrame(() => focusTranscriptOffset(intent.at ?? 0));
+    }
+    return;
+  }
+  if (intent.action === "stopRecording") {
+    if (!getIsRecording()) return;
+    if (intent.confirm && !(await confirmLink("A link asked Jilu to stop recording. Stop now?", "Stop", "Keep Recording"))) {
+      return;
+    }
+    await stopRecording();
+    return;
+  }
+  if (intent.action === "toggleMute") {
+    if (!getIsRecording()) return;
+    if (intent.confirm && !(await confirmLink("A link asked Jilu to mute or unmute the recording. Go ahead?", "Toggle Mute", "Leave It"))) {
+      return;
+    }
+    await toggleMute();
+    return;
+  }
+  if (intent.action !== "startRecording") return;
+  if (getIsRecording()) {
+    console.warn("Ignoring launch request to record: already recording");
+    return;
+  }
+  // The backend refuses it anyway; this just avoids creating an empty meeting.
+  const quiet = await quietHoursStatus().catch(() => null);
+  if (quiet) {
+    console.info(`Ignoring launch request to record: quiet hours until ${quiet.until}`);
+    return;
+  }
+  if (intent.confirm && !(await confirmLink("A link asked Jilu to start recording. Start now?", "Record", "Don't Record"))) {
+    return;
+  }
+
+  try {
+    if (intent.eventId) {
+      const meeting = await createMeetingFromCalendarEvent(intent.eventId, { activate: true });
+      if (!meeting) return;
+    } else {
+      await createNewMeeting();
+    }
+    await startRecording({ presetId: intent.presetId, automatic: true });
+  } catch (error) {
+    console.error("Failed to handle launch request:", error);
+  }
+}
+
+/** Acts on this launch's command line, then on requests forwarded by later launches. */
+export async function initializeLaunchIntents(): Promise<void> {
+  await listen<LaunchRequest>("launch-intent", (event) => {
+    void handleLaunchIntent(event.payload);
+  });
+
+  try {
+    const initial = await invoke<LaunchRequest | null>("take_launch_intent");
+    if (initial) {
+      await handleLaunchIntent(initial);
+    }
+  } catch (error) {
+    console.error("Failed to read launch request:", error);
+  }
+}
@@ -1,14 +1,14 @@
-import { listen } from "@tauri-apps/api/event";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { db } from "./database";
-import { TranscriptData } from "./types";
+import { ScreenShareStatus, TranscriptData } from "./types";
 import { initializeElements, elements } from "./dom";
 import { loadApiKeys, setupSettingsListeners, initializeExportSettings } from "./ui/settings";
 import { setupSidebarListeners } from "./ui/sidebar";
 import { setupTabListeners } from "./ui/tabs";
 import { setupChatListeners } from "./ui/chat";
 import { loadMeetings, createNewMeeting, setupMeetingListeners } from "./meeting-operations";
-import { setupRecordingListeners, updateTranscript, handleRecordingError, onRecordingEnded, handleAudioLevel, updateRecordingUI, toggleMute } from "./recording";
+import { setupRecordingListeners, updateTranscript, handleRecordingError, onRecordingEnded, handleAudioLevel, updateRecordingUI, toggleMute, onMicCaptureMoved, stopRecording, OutputRoute, applyScreenShareStatus, pauseInactivityMonitor } from "./recording";
 import { setupAIListeners } from "./ai-operations";
 import { initializeTags } from "./ui/tags";
 import { initializeTemplates } from "./prompt-templates";
@@ -24,12 +24,26 @@ import { initializeTranscriptUI } from "./ui/transcript";
 import { initializeTranscriptContextMenu } from "./ui/transcript-context-menu";
 import { setupTabFind } from "./ui/find";
 import { refreshAudioDeviceOptions } from "./audio-devices";
-import { initializeSidebarToggle } from "./ui/interactions";
+import { initializeSidebarToggle, showToast } from "./ui/interactions";
 import { initializeIcons } from "./init-icons";
 import { initializeOnboarding } from "./ui/onboarding";
 import { loadTasks } from "./tasks";
+import { migrateLegacySettings } from "./settings";
+import { setIsMuted } from "./state";
+import { initializeProfile } from "./profile";
+import { initializeLaunchIntents } from "./launch-intent";
+import { initializeUpdates } from "./updates";
+import { initializeSilenceWatchdog } from "./silence-watchdog";
+import { initializeSystemSleep } from "./system-sleep";
+import { initializePowerPolicy } from "./power-policy";
+import { initializeLanguageMismatch } from "./language-mismatch";
+import { initializeShareLink } from "./share-link";
+import { initializeInterruptedSession } from "./interrupted-session";
+import { initializeRecordingImports } from "./recording-imports";
+import { checkEventSchema } from "./events";
 
 async function init() {
+  await initializeProfile();
   await db.init();
   initializeElements();
   initializeIcons();
@@ -63,14 +77,69 @@ async function init() {
     handleRecordingError(event.payload);
   });
 
+  // The backend keeps recording through a dropped connection and resends what was missed.
+  await listen<{ attempt: number; maxAttempts: number }>("recording-reconnecting", (event) => {
+    if (event.payload.attempt === 1) {
+      showToast("Lost the connection to transcription. Reconnecting…", { type: "warning", duration: 5000 });
+    }
+  });
+
+  await listen("recording-reconnected", () => {
+    showToast("Reconnected. The transcript carries on where it left off.", { type: "success" });
+  });
+
+  await listen("recording-paused", () => {
+    pauseInactivityMonitor();
+    showToast("Recording paused. No audio is sent until you resume.", { type: "info" });
+  });
+
   await listen("recording-ended", () => {
     onRecordingEnded();
   });
 
   await listen<boolean>("mute-status-changed", (event) => {
+    // The tray and voice commands toggle mute in the backend, so keep local state in step.
+    setIsMuted(event.payload);
     updateMuteStatus(event.payload);
   });
 
+  await listen<string>("voice-command", (event) => {
+    if (event.payload === "bookmark") {
+      showToast("Bookmarked", { type: "success", duration: 1500 });
+    }
+  });
+
+  // Only routes the recording cannot hear are worth interrupting for.
+  await listen<OutputRoute>("output-route-changed", (event) => {
+    const route = event.payload;
+    if (!route.capturable && route.warning) {
+      showToast(`${route.device}: ${route.warning}`, { type: "warning", duration: 8000 });
+    }
+  });
+
+  // Transcript text stays out of the window while the screen is shared, if the settings say so.
+  await listen<ScreenShareStatus>("screen-share-changed", (event) => {
+    void applyScreenShareStatus(event.payload);
+  });
+  void invoke<ScreenShareStatus>("get_screen_share_status").then(applyScreenShareStatus);
+
+  // The captured display slept or was unplugged; system audio still comes through another.
+  await listen("capture-retargeted", () => {
+    showToast("Display disconnected or asleep; still capturing system audio from another display.", {
+      type: "info",
+      duration: 5000,
+    });
+  });
+
+  // The main window was closed mid-recording and the backend took over the mic.
+  await listen("mic-capture-moved", () => {
+    onMicCaptureMoved();
+  });
+
+  await listen("recording-stopped-in-background", () => {
+    void stopRecording({ stoppedByBackend: true });
+  });
+
   await listen<number>("audio-level", (event) => {
     handleAudioLevel(event.payload ?? 0);
   });
@@ -111,36 +180,19 @@ async function init() {
     }, 100);
   });
 
-  await registerGlobalShortcutOnStartup();
-  await registerMuteShortcutOnStartup();
-}
-
-async function registerGlobalShortcutOnStartup() {
-  const savedShortcut = localStorage.getItem("global_shortcut");
-  const shortcut = savedShortcut || "CommandOrControl+Shift+M";
-  
-  try {
-    await invoke("register_global_shortcut", { shortcut });
-    if (import.meta.env.DEV) {
-      console.log("Global shortcut registered on startup:", shortcut);
-    }
-  } catch (error) {
-    console.error("Failed to register global shortcut on startup:", error);
-  }
-}
-
-async function registerMuteShortcutOnStartup() {
-  const savedShortcut = localStorage.getItem("mute_shortcut");
-  if (savedShortcut) {
-    try {
-      await invoke("register_mute_shortcut", { shortcut: savedShortcut });
-      if (import.meta.env.DEV) {
-        console.log("Mute shortcut registered on startup:", savedShortcut);
-      }
-    } catch (error) {
-      console.error("Failed to register mute shortcut on startup:", error);
-    }
-  }
+  // Shortcuts are registered by the backend from its settings store.
+  await migrateLegacySettings();
+  await initializeLaunchIntents();
+  await initializeUpdates();
+  await initializeSilenceWatchdog();
+  await initializeSystemSleep();
+  await initializePowerPolicy();
+  await initializeLanguageMismatch();
+  await initializeShareLink();
+  await initializeRecordingImports();
+  await checkEventSchema();
+  // Last, since it may hold on a dialog and then start recording.
+  await initializeInterruptedSession();
 }
 
 function setupEventListeners() {
@@ -0,0 +1,11 @@
+import { invoke } from "@tauri-apps/api/core";
+import { TranscriptTurn } from "./types";
+
+/**
+ * Adds a typed note to the transcript at the current point of the recording.
+ * It arrives in `transcript-update` like any final turn, marked `note`, and
+ * is saved and exported along with what was said.
+ */
+export async function addManualNote(text: string, sessionId?: string): Promise<TranscriptTurn> {
+  return invoke<TranscriptTurn>("add_manual_note", { text, sessionId });
+}
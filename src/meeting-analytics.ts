@@ -0,0 +1,39 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface SpeakerAnalytics {
+  speaker: string | null;
+  words: number;
+  speakingSecs: number;
+  wordsPerMinute: number | null;
+  fillerCount: number;
+  /** Count per filler, e.g. `{ um: 4, "you know": 2 }`. */
+  fillers: Record<string, number>;
+  pauseCount: number;
+  meanPauseSecs: number | null;
+  longestPauseSecs: number | null;
+}
+
+export interface MeetingAnalytics {
+  speakers: SpeakerAnalytics[];
+  /** False for estimates from older meetings saved without word timings. */
+  wordTimings: boolean;
+}
+
+/** Coaching metrics for a meeting; without an id, for the current or last recording. */
+export async function getMeetingAnalytics(meetingId?: string): Promise<MeetingAnalytics> {
+  return invoke<MeetingAnalytics>("get_meeting_analytics", { meetingId: meetingId ?? null });
+}
+
+/** Loudness over a recording as RMS from 0 to 1, one point per `bucketMs`. */
+export interface LevelHistory {
+  bucketMs: number;
+  /** Your microphone. */
+  mic: number[];
+  /** Everyone else, via system audio; empty when only a microphone was recorded. */
+  system: number[];
+}
+
+/** Audio levels saved with a meeting; without an id, for the current or last recording. */
+export async function getLevelHistory(meetingId?: string): Promise<LevelHistory> {
+  return invoke<LevelHistory>("get_level_history", { meetingId: meetingId ?? null });
+}
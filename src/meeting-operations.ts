@@ -1,6 +1,9 @@
 import { CalendarAttendee, Meeting, MeetingParticipant, Person } from "./types";
 import { db } from "./database";
+import { forgetMeetingAttendees, syncMeetingAttendees } from "./contacts";
+import { removeMeetingFromProject } from "./projects";
 import { exportMeeting } from "./file-export";
+import { describeMeetingCost, getMeetingCost } from "./provider-usage";
 import { 
   getCurrentMeeting, 
   setCurrentMeeting, 
@@ -77,6 +80,7 @@ export function renderMeetingView() {
     elements.meetingSeriesIndicator.hidden = true;
   }
   void updateMeetingSeriesIndicator(currentMeeting);
+  void updateMeetingCost(currentMeeting.id);
   
   initializeNotesEditor(currentMeeting.personalNotes);
   renderPeoplePanel(currentMeeting);
@@ -135,6 +139,21 @@ export function renderMeetingView() {
   }
 }
 
+/** Shows how much audio the meeting streamed and, with rates set, what it cost. */
+async function updateMeetingCost(meetingId: string): Promise<void> {
+  const label = document.getElementById("meeting-cost");
+  if (!label) return;
+  label.hidden = true;
+  try {
+    const cost = await getMeetingCost(meetingId);
+    if (!cost || getCurrentMeeting()?.id !== meetingId) return;
+    label.textContent = `· ${describeMeetingCost(cost)}`;
+    label.hidden = false;
+  } catch (error) {
+    console.warn("Failed to load meeting cost:", error);
+  }
+}
+
 async function updateMeetingSeriesIndicator(meeting: Meeting): Promise<void> {
   const meetingSeriesIndicator = elements.meetingSeriesIndicator;
   const meetingSeriesButton = elements.meetingSeriesButton;
@@ -222,6 +241,8 @@ export async function deleteMeeting() {
   if (!userConfirmed) return;
 
   await db.deleteMeeting(currentMeeting.id);
+  await forgetMeetingAttendees(currentMeeting.id);
+  await removeMeetingFromProject(currentMeeting.id);
   removeMeetingFromList(currentMeeting.id);
   
   setCurrentMeeting(null);
@@ -284,6 +305,7 @@ export async function createMeetingFromCalendarEvent(
     date: new Date(eventSnapshot.startTime),
     transcript: [],
     personalNotes: buildPersonalNotesFromAgenda(eventSnapshot.notes),
+    agenda: eventSnapshot.notes?.trim() || undefined,
     enhancedNotes: "",
     participants: participantList,
     tags: undefined,
@@ -301,6 +323,7 @@ export async function createMeetingFromCalendarEvent(
   };
 
   await db.saveMeeting(meeting);
+  await syncMeetingAttendees(meeting);
   addMeetingToList(meeting);
   renderMeetingsList();
 
@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+import { htmlToMarkdown, isHtmlEmpty } from "./file-export/html-converter";
+import { Meeting } from "./types";
+
+export interface MeetingPackageManifest {
+  exportedAt: string;
+  id: string;
+  title: string | null;
+  root: string;
+  files: string[];
+  actionItems: string[];
+  /** Why the audio was left out, when it was. */
+  audioWithheld: string | null;
+}
+
+/**
+ * Writes `meeting` to a new folder under `destination` that someone without
+ * Jilu can open: the audio, the transcript as text, JSON and subtitles, the
+ * summary and its action items, and an `index.html` that plays the audio in
+ * step with the transcript.
+ */
+export function exportMeetingPackage(meeting: Meeting, destination: string): Promise<MeetingPackageManifest> {
+  return invoke<MeetingPackageManifest>("export_meeting_package", {
+    meeting: {
+      id: meeting.id,
+      title: meeting.title,
+      date: meeting.date.toISOString(),
+      summary: meeting.enhancedNotes?.trim() || null,
+      notes: isHtmlEmpty(meeting.personalNotes) ? null : htmlToMarkdown(meeting.personalNotes),
+    },
+    destination,
+  });
+}
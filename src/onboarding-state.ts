@@ -0,0 +1,23 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type OnboardingStep = "permissions" | "apiKey" | "calendar" | "testRecording";
+
+export interface OnboardingState {
+  completed: OnboardingStep[];
+  nextStep: OnboardingStep | null;
+  readyToRecord: boolean;
+  isComplete: boolean;
+}
+
+export async function getOnboardingState(): Promise<OnboardingState> {
+  return invoke<OnboardingState>("get_onboarding_state");
+}
+
+/** Records a finished setup step; failures are logged since progress is advisory outside recording. */
+export async function completeOnboardingStep(step: OnboardingStep): Promise<void> {
+  try {
+    await invoke<OnboardingState>("complete_step", { step });
+  } catch (error) {
+    console.error(`Failed to record onboarding step ${step}:`, error);
+  }
+}
@@ -0,0 +1,32 @@
+import { listen } from "@tauri-apps/api/event";
+import { showToast } from "./ui/interactions";
+
+/** Payload of `power-policy-changed`. */
+export interface PowerPolicy {
+  mode: "normal" | "saving" | "hot";
+  batchMs: number;
+  onBattery: boolean;
+  lowPower: boolean;
+  thermal: "nominal" | "fair" | "serious" | "critical";
+}
+
+function describePolicy(policy: PowerPolicy): string {
+  switch (policy.mode) {
+    case "hot":
+      return "Your Mac is running hot, so Jilu is sending audio less often. Captions may lag slightly.";
+    case "saving":
+      return policy.lowPower
+        ? "Low Power Mode is on, so Jilu is sending audio less often. Captions may lag slightly."
+        : "On battery, so Jilu is sending audio less often. Captions may lag slightly.";
+    default:
+      return "Sending audio at the usual rate again.";
+  }
+}
+
+/** The backend lengthens audio batches to save power; this says so while it does. */
+export async function initializePowerPolicy(): Promise<void> {
+  await listen<PowerPolicy>("power-policy-changed", (event) => {
+    const policy = event.payload;
+    showToast(describePolicy(policy), { type: policy.mode === "hot" ? "warning" : "info", duration: 5000 });
+  });
+}
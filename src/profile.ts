@@ -0,0 +1,106 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import {
+  DB_NAME,
+  STORAGE_KEY_CALENDAR_SETTINGS,
+  STORAGE_KEY_DEFAULT_TEMPLATE,
+  STORAGE_KEY_EXPORT_ENABLED,
+  STORAGE_KEY_EXPORT_PATH,
+  STORAGE_KEY_OPENAI_ENDPOINT,
+  STORAGE_KEY_OPENAI_MODEL,
+} from "./constants";
+
+const DEFAULT_PROFILE_ID = "default";
+const STORAGE_KEY_LOADED_PROFILE = "loaded_profile_id";
+
+/** localStorage entries that belong to a profile rather than the whole app. */
+const PROFILE_SCOPED_KEYS = [
+  STORAGE_KEY_OPENAI_ENDPOINT,
+  STORAGE_KEY_OPENAI_MODEL,
+  STORAGE_KEY_CALENDAR_SETTINGS,
+  STORAGE_KEY_EXPORT_ENABLED,
+  STORAGE_KEY_EXPORT_PATH,
+  STORAGE_KEY_DEFAULT_TEMPLATE,
+];
+
+export interface Profile {
+  id: string;
+  name: string;
+  storageDir: string | null;
+}
+
+export interface ProfileList {
+  active: string;
+  profiles: Profile[];
+}
+
+let activeProfileId = DEFAULT_PROFILE_ID;
+
+function stashedKey(profileId: string, key: string): string {
+  return `profile:${profileId}:${key}`;
+}
+
+/**
+ * Swaps profile-scoped localStorage entries when the backend's active profile
+ * differs from the one this webview last loaded. Must run before anything reads settings.
+ */
+export async function initializeProfile(): Promise<void> {
+  try {
+    const { active } = await listProfiles();
+    activeProfileId = active;
+  } catch (error) {
+    console.error("Failed to load profiles, using the default profile:", error);
+    activeProfileId = DEFAULT_PROFILE_ID;
+  }
+
+  const loaded = localStorage.getItem(STORAGE_KEY_LOADED_PROFILE) || DEFAULT_PROFILE_ID;
+  if (loaded !== activeProfileId) {
+    for (const key of PROFILE_SCOPED_KEYS) {
+      const current = localStorage.getItem(key);
+      if (current === null) {
+        localStorage.removeItem(stashedKey(loaded, key));
+      } else {
+        localStorage.setItem(stashedKey(loaded, key), current);
+      }
+
+      const next = localStorage.getItem(stashedKey(activeProfileId, key));
+      if (next === null) {
+        localStorage.removeItem(key);
+      } else {
+        localStorage.setItem(key, next);
+      }
+    }
+    localStorage.setItem(STORAGE_KEY_LOADED_PROFILE, activeProfileId);
+  }
+
+  await listen<Profile>("profile-changed", () => {
+    window.location.reload();
+  });
+}
+
+export function getActiveProfileId(): string {
+  return activeProfileId;
+}
+
+/** Key for a per-profile secret; the default profile keeps the original names. */
+export function profileScopedKey(key: string): string {
+  return activeProfileId === DEFAULT_PROFILE_ID ? key : stashedKey(activeProfileId, key);
+}
+
+/** Each profile gets its own meeting library. */
+export function profileDatabaseName(): string {
+  return activeProfileId === DEFAULT_PROFILE_ID ? DB_NAME : `${DB_NAME}-${activeProfileId}`;
+}
+
+export async function listProfiles(): Promise<ProfileList> {
+  return invoke<ProfileList>("list_profiles");
+}
+
+export async function createProfile(name: string, storageDir?: string | null): Promise<Profile> {
+  return invoke<Profile>("create_profile", { name, storageDir: storageDir ?? null });
+}
+
+/** The app reloads once the backend emits `profile-changed`. */
+export async function switchProfile(profileId: string): Promise<Profile> {
+  return invoke<Profile>("switch_profile", { profileId });
+}
@@ -0,0 +1,59 @@
+import { invoke } from "@tauri-apps/api/core";
+import { VocabularyEntry } from "./settings";
+
+export interface RosterSpeaker {
+  label: string;
+  speakerIdentifiers: string[];
+}
+
+/** Meetings in a project record with its vocabulary and speakers, and export and enhance with its folder and template. */
+export interface Project {
+  id: string;
+  name: string;
+  additionalVocab: VocabularyEntry[];
+  speakers: RosterSpeaker[];
+  exportPath: string | null;
+  promptTemplateId: string | null;
+}
+
+export type ProjectInput = Omit<Project, "id">;
+
+export function listProjects(): Promise<Project[]> {
+  return invoke<Project[]>("list_projects");
+}
+
+export function createProject(project: ProjectInput): Promise<Project> {
+  return invoke<Project>("create_project", { project });
+}
+
+export function updateProject(projectId: string, project: ProjectInput): Promise<Project> {
+  return invoke<Project>("update_project", { projectId, project });
+}
+
+export function deleteProject(projectId: string): Promise<void> {
+  return invoke("delete_project", { projectId });
+}
+
+/** Moves a meeting into a project, or out of its project when `projectId` is null. */
+export function setMeetingProject(meetingId: string, projectId: string | null): Promise<void> {
+  return invoke("set_meeting_project", { meetingId, projectId });
+}
+
+/** Forgets a deleted meeting's project; errors are only logged. */
+export async function removeMeetingFromProject(meetingId: string): Promise<void> {
+  try {
+    await setMeetingProject(meetingId, null);
+  } catch (error) {
+    console.warn("Failed to remove meeting from its project:", error);
+  }
+}
+
+/** The meeting's project, or null when it has none or the lookup fails. */
+export async function getMeetingProject(meetingId: string): Promise<Project | null> {
+  try {
+    return await invoke<Project | null>("get_meeting_project", { meetingId });
+  } catch (error) {
+    console.warn("Failed to look up meeting project:", error);
+    return null;
+  }
+}
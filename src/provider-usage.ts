@@ -0,0 +1,98 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getSpeechmaticsApiKey } from "./secure-storage";
+
+/** This calendar month's Speechmatics usage, as returned by `get_provider_usage`. */
+export interface ProviderUsage {
+  since: string;
+  until: string;
+  usedHours: number;
+  realtimeHours: number;
+  batchHours: number;
+  allowanceHours: number | null;
+  remainingHours: number | null;
+}
+
+/** Usage for the stored API key, or null when no key is configured. */
+export async function getProviderUsage(): Promise<ProviderUsage | null> {
+  const apiKey = await getSpeechmaticsApiKey();
+  if (!apiKey) {
+    return null;
+  }
+  return invoke<ProviderUsage>("get_provider_usage", { apiKey });
+}
+
+function formatHours(hours: number): string {
+  return `${hours.toFixed(1)} h`;
+}
+
+export function describeProviderUsage(usage: ProviderUsage): string {
+  const used = `${formatHours(usage.usedHours)} used this month`;
+  if (usage.remainingHours === null || usage.allowanceHours === null) {
+    return used;
+  }
+  return `${formatHours(usage.remainingHours)} left of ${formatHours(usage.allowanceHours)} (${used})`;
+}
+
+/** Audio streamed for one meeting and its estimated cost, from `get_meeting_cost`. */
+export interface MeetingCost {
+  currency: string;
+  providers: Record<string, { audioSecs: number; cost: number | null }>;
+  updatedAt: string;
+}
+
+/** Totals across meetings, from `get_usage_stats`. */
+export interface UsageStats {
+  currency: string;
+  audioHours: number;
+  estimatedCost: number;
+  /** Hours with no rate set, or priced in another currency. */
+  unpricedHours: number;
+  providers: { provider: string; audioHours: number; estimatedCost: number }[];
+  meetings: {
+    meetingId: string;
+    audioHours: number;
+    estimatedCost: number | null;
+    currency: string;
+    updatedAt: string;
+  }[];
+}
+
+export interface UsageStatsFilter {
+  projectId?: string;
+  /** Inclusive `YYYY-MM-DD` bounds. */
+  since?: string;
+  until?: string;
+}
+
+/** Null until something has been recorded into the meeting. */
+export function getMeetingCost(meetingId: string): Promise<MeetingCost | null> {
+  return invoke<MeetingCost | null>("get_meeting_cost", { meetingId });
+}
+
+export function getUsageStats(filter: UsageStatsFilter = {}): Promise<UsageStats> {
+  return invoke<UsageStats>("get_usage_stats", {
+    projectId: filter.projectId ?? null,
+    since: filter.since ?? null,
+    until: filter.until ?? null,
+  });
+}
+
+function formatCost(amount: number, currency: string): string {
+  try {
+    return new Intl.NumberFormat(undefined, { style: "currency", currency }).format(amount);
+  } catch {
+    // Not an ISO currency code; show it as written.
+    return `${amount.toFixed(2)} ${currency}`;
+  }
+}
+
+export function describeMeetingCost(cost: MeetingCost): string {
+  const providers = Object.values(cost.providers);
+  const hours = providers.reduce((sum, provider) => sum + provider.audioSecs, 0) / 3600;
+  const priced = providers.filter((provider) => provider.cost !== null);
+  if (priced.length === 0) {
+    return `${formatHours(hours)} transcribed`;
+  }
+  const amount = priced.reduce((sum, provider) => sum + (provider.cost ?? 0), 0);
+  return `${formatHours(hours)} transcribed, about ${formatCost(amount, cost.currency)}`;
+}
@@ -0,0 +1,124 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { calendarService } from "./calendar-service";
+import { db } from "./database";
+import { createMeetingFromCalendarEvent, loadMeeting } from "./meeting-operations";
+import { getSpeechmaticsApiKey } from "./secure-storage";
+import { addMeetingToList, getCurrentMeeting } from "./state";
+import { CalendarEventInstance, Meeting, TranscriptTurn } from "./types";
+import { renderMeetingsList } from "./ui/sidebar";
+import { showToast } from "./ui/interactions";
+import { generateId, hasTranscriptContent } from "./utils";
+
+/** Payload of `local-recording-found`. */
+interface FoundRecording {
+  path: string;
+  source: "zoom" | "teams" | "folder";
+  title: string | null;
+  startedAt: string;
+  endedAt: string;
+  sizeBytes: number;
+}
+
+const SOURCE_NAMES: Record<FoundRecording["source"], string> = {
+  zoom: "Zoom",
+  teams: "Teams",
+  folder: "A new",
+};
+
+/**
+ * The backend watches Zoom's and Teams' recording folders (when turned on in
+ * settings) and announces each new recording; we offer to transcribe it into
+ * the meeting of the calendar event it overlaps, or a new meeting.
+ */
+export async function initializeRecordingImports(): Promise<void> {
+  await listen<FoundRecording>("local-recording-found", (event) => {
+    void offerImport(event.payload);
+  });
+}
+
+/** The calendar event that overlaps the recording the most, if any does. */
+async function findOverlappingEvent(start: Date, end: Date): Promise<CalendarEventInstance | null> {
+  const events = await calendarService.getEventsForRange(start, end).catch(() => []);
+  let best: CalendarEventInstance | null = null;
+  let bestOverlap = 0;
+  for (const event of events) {
+    const overlap =
+      Math.min(end.getTime(), new Date(event.endTime).getTime()) -
+      Math.max(start.getTime(), new Date(event.startTime).getTime());
+    if (overlap > bestOverlap) {
+      best = event;
+      bestOverlap = overlap;
+    }
+  }
+  return best;
+}
+
+async function offerImport(found: FoundRecording): Promise<void> {
+  const startedAt = new Date(found.startedAt);
+  const endedAt = new Date(found.endedAt);
+  const event = await findOverlappingEvent(startedAt, endedAt);
+  const fileName = found.path.split(/[\\/]/).pop() ?? found.path;
+  const title = event?.title || found.title || fileName;
+
+  const importIt = await ask(
+    `${SOURCE_NAMES[found.source]} recording of "${title}" was saved. Import it and transcribe it with Speechmatics?`,
+    { title: "Import Recording", okLabel: "Import", cancelLabel: "Not Now" }
+  );
+  if (!importIt) return;
+
+  const apiKey = await getSpeechmaticsApiKey();
+  if (!apiKey) {
+    showToast("Add a Speechmatics API key in Settings to transcribe recordings.", {
+      type: "error",
+      duration: 5000,
+    });
+    return;
+  }
+
+  let meeting = event ? await createMeetingFromCalendarEvent(event.id, { activate: false }) : null;
+  // Never replace a transcript Jilu recorded live.
+  if (!meeting || hasTranscriptContent(meeting.transcript)) {
+    meeting = await createImportedMeeting(title, startedAt);
+  }
+
+  showToast(`Transcribing "${title}"…`, { type: "info", duration: 5000 });
+  try {
+    const turns = await invoke<TranscriptTurn[]>("import_recording", {
+      path: found.path,
+      meetingId: meeting.id,
+      apiKey,
+    });
+    // Reload in case the meeting was edited while the job ran.
+    const latest = (await db.getMeeting(meeting.id)) ?? meeting;
+    latest.transcript = turns;
+    latest.updatedAt = new Date();
+    await db.saveMeeting(latest);
+    if (getCurrentMeeting()?.id === latest.id) {
+      await loadMeeting(latest.id);
+    }
+    showToast(`Imported "${title}".`, { type: "success" });
+  } catch (error) {
+    console.error("Failed to import recording:", error);
+    showToast(`Failed to import "${title}": ${error}`, { type: "error", duration: 5000 });
+  }
+}
+
+async function createImportedMeeting(title: string, date: Date): Promise<Meeting> {
+  const now = new Date();
+  const meeting: Meeting = {
+    id: generateId(),
+    title,
+    date,
+    transcript: [],
+    personalNotes: "",
+    enhancedNotes: "",
+    createdAt: now,
+    updatedAt: now,
+  };
+  await db.saveMeeting(meeting);
+  addMeetingToList(meeting);
+  renderMeetingsList();
+  return meeting;
+}
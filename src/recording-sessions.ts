@@ -0,0 +1,119 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
+import { TranscriptData } from "./types";
+import { getRealtimeApiKey } from "./secure-storage";
+import { getSettings } from "./settings";
+
+/** Id of the session the main window records into. */
+export const MAIN_SESSION_ID = "main";
+
+const MIC_BUFFER_SIZE = 1024;
+
+interface ExtraSession {
+  context: AudioContext;
+  processor: ScriptProcessorNode;
+  stream: MediaStream;
+}
+
+/** Capture for sessions recording alongside the main one, keyed by session id. */
+const extraSessions = new Map<string, ExtraSession>();
+
+export interface ExtraSessionOptions {
+  /** Letters, digits, `-` and `_`; also used in the session's event names. */
+  sessionId: string;
+  /** Input device to record, e.g. a second USB microphone in a breakout room. */
+  deviceId: string;
+  meetingId?: string;
+  meetingTitle?: string;
+  presetId?: string;
+}
+
+/** Name under which the backend sends `event` for session `sessionId`. */
+export function sessionEventName(sessionId: string, event: string): string {
+  return sessionId === MAIN_SESSION_ID ? event : `session/${sessionId}/${event}`;
+}
+
+/**
+ * Starts an independent recording from another input device. Only the
+ * microphone is recorded; system audio always goes to the main session.
+ */
+export async function startExtraSession(options: ExtraSessionOptions): Promise<void> {
+  const { sessionId } = options;
+  if (sessionId === MAIN_SESSION_ID || extraSessions.has(sessionId)) {
+    throw new Error(`Session "${sessionId}" is already recording`);
+  }
+  const { transcription } = await getSettings();
+  const apiKey = (await getRealtimeApiKey(transcription.provider)) ?? "";
+  if (!apiKey && !transcription.simulator.enabled) {
+    const provider = transcription.provider === "deepgram" ? "Deepgram" : "Speechmatics";
+    throw new Error(`Add your ${provider} API key to start recording`);
+  }
+
+  const stream = await navigator.mediaDevices.getUserMedia({
+    audio: {
+      deviceId: { exact: options.deviceId },
+      channelCount: 1,
+      sampleRate: 48_000,
+      echoCancellation: false,
+      noiseSuppression: false,
+      autoGainControl: false,
+    },
+  });
+  const context = new AudioContext({ sampleRate: 48_000 });
+  const source = context.createMediaStreamSource(stream);
+  const processor = context.createScriptProcessor(MIC_BUFFER_SIZE, 1, 1);
+  const silent = context.createGain();
+  silent.gain.value = 0;
+
+  try {
+    await invoke("start_recording", {
+      args: {
+        apiKey,
+        sessionId,
+        meetingId: options.meetingId,
+        meetingTitle: options.meetingTitle,
+        presetId: options.presetId,
+      },
+    });
+  } catch (error) {
+    stream.getTracks().forEach((track) => track.stop());
+    await context.close().catch(() => undefined);
+    throw error;
+  }
+
+  processor.onaudioprocess = (event) => {
+    const samples = Array.from(event.inputBuffer.getChannelData(0));
+    invoke("push_mic_audio_chunk", { samples, sessionId }).catch((err) => {
+      console.error(`Failed to push audio for session ${sessionId}`, err);
+    });
+  };
+  source.connect(processor);
+  processor.connect(silent);
+  silent.connect(context.destination);
+  extraSessions.set(sessionId, { context, processor, stream });
+}
+
+export async function stopExtraSession(sessionId: string): Promise<void> {
+  const session = extraSessions.get(sessionId);
+  extraSessions.delete(sessionId);
+  if (session) {
+    session.processor.onaudioprocess = null;
+    session.stream.getTracks().forEach((track) => track.stop());
+    await session.context.close().catch(() => undefined);
+  }
+  await invoke("stop_recording", { sessionId });
+}
+
+/** Follows a session's transcript updates, which work like the main session's `transcript-update`. */
+export function onSessionTranscript(
+  sessionId: string,
+  handler: (update: TranscriptData) => void,
+): Promise<UnlistenFn> {
+  return listen<TranscriptData>(sessionEventName(sessionId, "transcript-update"), (event) => {
+    handler(event.payload);
+  });
+}
+
+export function listRecordingSessions(): Promise<string[]> {
+  return invoke<string[]>("list_recording_sessions");
+}
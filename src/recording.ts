@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import { TranscriptData, Meeting } from "./types";
+import { TranscriptData, FullTranscript, Meeting, ScreenShareStatus, TranscriptTurn } from "./types";
 import {
   getCurrentMeeting,
   setIsRecording,
@@ -16,17 +16,24 @@ import { saveMeetingChanges, createNewMeeting, loadMeeting } from "./meeting-ope
 import { aiService } from "./ai-service";
 import { renderTranscript, handlePartialTranscriptUpdate, resetTranscriptAutoScroll } from "./ui/transcript";
 import { transcriptTextToTurns, transcriptTurnsToText, hasTranscriptContent } from "./utils";
-import { getCustomDictionaryEntries, CUSTOM_DICTIONARY_LIMIT } from "./custom-dictionary";
 import { refreshAudioDeviceOptions, getSavedAudioDevice, setSavedAudioDevice, syncSelectedAudioDevice } from "./audio-devices";
 import { autoEnhanceNotesForMeeting } from "./ai-operations";
 import { getDefaultTemplateId } from "./prompt-templates";
 import { showToast } from "./ui/interactions";
 import { getStoredSpeakerProfile } from "./speaker-id";
-import { DEFAULT_SPEECHMATICS_URL, STORAGE_KEY_SPEECHMATICS_URL } from "./constants";
 import { openOnboarding } from "./ui/onboarding";
 import { db } from "./database";
 import { renderMeetingsList } from "./ui/sidebar";
-import { getSpeechmaticsApiKey } from "./secure-storage";
+import { getRealtimeApiKey } from "./secure-storage";
+import { isInterviewMode } from "./interview";
+import { loadAgenda } from "./agenda";
+import { getRecordingPreset, getSettings, RecordingPreset, VocabularyEntry } from "./settings";
+import { exportMeeting } from "./file-export/export-operations";
+import { updateShareLinkButton } from "./share-link";
+import { getMeetingProject, Project, RosterSpeaker } from "./projects";
+import { detectMeetingChapters } from "./chapters";
+import { checkForDuplicateMeetings } from "./duplicate-meetings";
+import { withoutDeclinedSpeech } from "./consent";
 
 let micContext: AudioContext | null = null;
 let micProcessor: ScriptProcessorNode | null = null;
@@ -37,6 +44,10 @@ let activeMicDeviceId: string | null = null;
 let micStopRequested = false;
 let micRecoveryInProgress = false;
 let stopRecordingInProgress = false;
+let activePreset: RecordingPreset | null = null;
+let activeProject: Project | null = null;
+/** The recording's turns as the backend numbers them, declined speech included, so deltas line up. */
+let liveTurns: { meetingId: string; turns: TranscriptTurn[] } | null = null;
 const micTrackEndHandlers = new Map<MediaStreamTrack, () => void>();
 const SOUND_DETECTION_THRESHOLD = 0.02;
 const MIC_ACTIVITY_BOOST = 3;
@@ -201,6 +212,68 @@ async function restartMicCapture(deviceId?: string | null) {
   await startMicCapture(deviceId);
 }
 
+export interface AudioLevelReport {
+  peak: number;
+  rms: number;
+  samples: number;
+  silent: boolean;
+}
+
+/** Opens the selected microphone briefly and reports what the backend heard. */
+export async function measureMicLevel(durationMs = 2000): Promise<AudioLevelReport> {
+  if (getIsRecording()) {
+    throw new Error("Stop the recording before testing the microphone");
+  }
+  await startMicCapture();
+  try {
+    return await invoke<AudioLevelReport>("measure_mic_level", { durationMs });
+  } finally {
+    stopMicCapture();
+  }
+}
+
+/** Continues the live recording in another language without losing the transcript. */
+export async function switchLanguage(language: string): Promise<void> {
+  await invoke("switch_language", { language });
+}
+
+/**
+ * Renames a speaker in the live transcript, e.g. `S1` to `Alice`, and keeps
+ * the new name for whatever they say later. The transcript comes back in
+ * full through `transcript-update`.
+ */
+export async function renameSpeaker(oldLabel: string, newLabel: string, sessionId?: string): Promise<number> {
+  return invoke<number>("rename_speaker", { oldLabel, newLabel, sessionId });
+}
+
+/** The output device system audio plays to; payload of `output-route-changed`. */
+export interface OutputRoute {
+  device: string;
+  transport:
+    | "builtIn"
+    | "usb"
+    | "hdmi"
+    | "displayPort"
+    | "thunderbolt"
+    | "bluetooth"
+    | "airPlay"
+    | "aggregate"
+    | "virtual"
+    | "other";
+  /** False when the recording may not hear what plays there, e.g. AirPlay. */
+  capturable: boolean;
+  warning?: string;
+}
+
+export async function getOutputRoute(): Promise<OutputRoute> {
+  return invoke<OutputRoute>("get_output_route");
+}
+
+/** Plays a tone through the output device and checks system audio capture picks it up. */
+export async function playTestTone(): Promise<{ systemAudio: AudioLevelReport; heard: boolean }> {
+  return invoke("play_test_tone");
+}
+
 async function refreshMicDeviceOptions() {
   await refreshAudioDeviceOptions([
     elements.inlineAudioDeviceSelect,
@@ -208,18 +281,32 @@ async function refreshMicDeviceOptions() {
   ]);
 }
 
-export async function startRecording() {
-  const speechmaticsKey = await getSpeechmaticsApiKey();
-  const storedSpeechmaticsUrl = (localStorage.getItem(STORAGE_KEY_SPEECHMATICS_URL) || "").trim();
-  const speechmaticsUrl = storedSpeechmaticsUrl || DEFAULT_SPEECHMATICS_URL;
-  if (!storedSpeechmaticsUrl) {
-    localStorage.setItem(STORAGE_KEY_SPEECHMATICS_URL, speechmaticsUrl);
-  }
-
-  if (!speechmaticsKey) {
+/**
+ * Resolves to whether the recording actually started. `resumeInterrupted`
+ * carries on the recording the app was closed in the middle of, in the
+ * current meeting, which must be that recording's.
+ */
+export async function startRecording(
+  options: {
+    presetId?: string | null;
+    resumeInterrupted?: boolean;
+    /** Teammates' enrolled voices, labelled by name alongside your own. */
+    speakerProfiles?: RosterSpeaker[];
+    /** Started by a link or script rather than by hand; refused in quiet hours. */
+    automatic?: boolean;
+  } = {}
+): Promise<boolean> {
+  const { transcription } = await getSettings();
+  const apiKey = await getRealtimeApiKey(transcription.provider);
+
+  if (!apiKey && !transcription.simulator.enabled) {
+    if (transcription.provider === "deepgram") {
+      showToast("Add your Deepgram API key in Settings to start recording.", { type: "warning" });
+      return false;
+    }
     showToast("Add your Speechmatics API key to start recording. Opening setup now!", { type: "warning" });
     openOnboarding();
-    return;
+    return false;
   }
 
   let currentMeeting = getCurrentMeeting();
@@ -229,13 +316,11 @@ export async function startRecording() {
   }
 
   try {
-    const customDictionaryEntries = await getCustomDictionaryEntries();
-    const truncatedDictionary = customDictionaryEntries.slice(0, CUSTOM_DICTIONARY_LIMIT);
-    const additionalVocabPayload = truncatedDictionary.map((entry) => ({
-      content: entry.content,
-      sounds_like: entry.soundsLike.length ? entry.soundsLike : undefined,
-    }));
-
+    const preset = options.presetId ? await getRecordingPreset(options.presetId) : null;
+    if (options.presetId && !preset) {
+      showToast(`Recording preset "${options.presetId}" not found`, { type: "error" });
+      return false;
+    }
     const storedSpeakerProfile = getStoredSpeakerProfile();
     const speakerProfile =
       storedSpeakerProfile && storedSpeakerProfile.identifiers.length > 0
@@ -249,12 +334,24 @@ export async function startRecording() {
       console.debug("Starting Speechmatics RT with speaker profile:", speakerProfile);
     }
 
-    await startMicCapture();
+    // In native mic mode the backend records the mic itself.
+    const { microphone } = await getSettings();
+    if (!microphone.native) {
+      await startMicCapture();
+    }
     const startArgs = {
-      apiKey: speechmaticsKey,
-      additionalVocab: additionalVocabPayload,
+      apiKey: apiKey ?? "",
       speakerProfile: speakerProfile ?? undefined,
-      rtUrl: speechmaticsUrl,
+      speakerProfiles: options.speakerProfiles,
+      meetingId: currentMeeting?.id,
+      meetingTitle: currentMeeting?.title,
+      interviewMode: preset?.interviewMode ?? isInterviewMode(),
+      presetId: preset?.id,
+      automatic: options.automatic,
+      // Checked against the avoid-list; the backend refuses meetings on it.
+      calendarEvent: currentMeeting?.calendarEventId
+        ? (await db.getCalendarEvent(currentMeeting.calendarEventId)) ?? undefined
+        : undefined,
     };
 
     if (import.meta.env.DEV) {
@@ -264,11 +361,18 @@ export async function startRecording() {
       });
     }
 
-    await invoke("start_recording", {
+    await loadAgenda(currentMeeting?.agenda ?? "");
+    await invoke(options.resumeInterrupted ? "resume_interrupted_session" : "start_recording", {
       args: startArgs,
     });
     setIsRecording(true);
     setIsMuted(false); // Ensure unmuted when starting
+    activePreset = preset;
+    activeProject = currentMeeting ? await getMeetingProject(currentMeeting.id) : null;
+    const templateId = preset?.promptTemplateId ?? activeProject?.promptTemplateId;
+    if (currentMeeting && templateId && !currentMeeting.promptTemplateId) {
+      currentMeeting.promptTemplateId = templateId;
+    }
     // Store which meeting is being recorded
     if (currentMeeting) {
       setRecordingMeetingId(currentMeeting.id);
@@ -281,14 +385,19 @@ export async function startRecording() {
     startInactivityMonitor();
 
     if (currentMeeting && elements.transcriptContent) {
-      currentMeeting.transcript = [];
+      // A resumed recording carries on from the transcript saved before the gap.
+      currentMeeting.transcript = options.resumeInterrupted
+        ? (await invoke<FullTranscript>("get_full_transcript")).turns.map(tidyTurn)
+        : [];
       resetTranscriptAutoScroll();
       renderTranscript(elements.transcriptContent, currentMeeting.transcript);
     }
+    return true;
   } catch (error) {
     console.error("Failed to start recording:", error);
     stopMicCapture();
     showToast(`Failed to start recording: ${error}`, { type: "error", duration: 5000 });
+    return false;
   }
 }
 
@@ -298,6 +407,8 @@ let inactivityTimerId: number | null = null;
 let lastTranscriptReceivedAt = 0;
 let recordingMeetingEndTime: number | null = null;
 let autoStopInProgress = false;
+// Finals the backend held back while the screen was shared.
+let transcriptMissedWhileHidden = false;
 
 const INACTIVITY_TIMEOUT_MS = 60 * 1000;
 const INACTIVITY_CHECK_INTERVAL_MS = 10 * 1000;
@@ -309,6 +420,11 @@ export function setupRecordingEndedListener() {
   });
 }
 
+/** The backend records the mic itself once the window is closed; stop feeding it from here. */
+export function onMicCaptureMoved() {
+  stopMicCapture();
+}
+
 export function onRecordingEnded() {
   stopMicCapture();
   if (recordingEndedResolve) {
@@ -357,6 +473,16 @@ function startInactivityMonitor() {
   }, INACTIVITY_CHECK_INTERVAL_MS);
 }
 
+/** No transcripts arrive while the recording is paused, so don't treat that as the meeting ending. */
+export function pauseInactivityMonitor() {
+  clearInactivityMonitor();
+}
+
+export function resumeInactivityMonitor() {
+  lastTranscriptReceivedAt = Date.now();
+  startInactivityMonitor();
+}
+
 function clearInactivityMonitor() {
   if (inactivityTimerId !== null) {
     window.clearInterval(inactivityTimerId);
@@ -410,7 +536,12 @@ async function checkRecordingInactivity(): Promise<void> {
   }
 }
 
-export async function stopRecording() {
+/**
+ * Stops the recording and finishes the meeting. `stoppedByBackend` is set
+ * when the tray already stopped it, so only the finishing steps run.
+ */
+export async function stopRecording(options: { stoppedByBackend?: boolean } = {}) {
+  const { stoppedByBackend = false } = options;
   if (stopRecordingInProgress) {
     return;
   }
@@ -418,21 +549,35 @@ export async function stopRecording() {
   stopRecordingInProgress = true;
   updateStopRecordingButton(getIsRecording());
   const recordingMeetingId = getRecordingMeetingId();
+  const preset = activePreset;
+  const project = activeProject;
+  activePreset = null;
+  activeProject = null;
   try {
     clearInactivityMonitor();
-    setupRecordingEndedListener();
     stopMicCapture();
-    
-    await invoke("stop_recording");
+
+    if (!stoppedByBackend) {
+      setupRecordingEndedListener();
+      await invoke("stop_recording");
+    }
     setIsRecording(false);
     stopRecordingInProgress = false;
     await updateRecordingUI();
 
     // Wait for backend to finish processing all final transcripts
+    if (!stoppedByBackend) {
+      try {
+        await waitForRecordingEnd();
+      } catch (error) {
+        console.error("Recording end event timeout:", error);
+      }
+    }
+    // Stopped while the screen was shared: the last finals never reached us.
     try {
-      await waitForRecordingEnd();
+      await catchUpHiddenTranscript();
     } catch (error) {
-      console.error("Recording end event timeout:", error);
+      console.error("Failed to fetch the held back transcript:", error);
     }
 
     // Only clear recording meeting ID after all final transcripts have been processed
@@ -443,8 +588,16 @@ export async function stopRecording() {
       handlePartialTranscriptUpdate();
     }
 
-    // Auto-generate title if still untitled
     const currentMeeting = getCurrentMeeting();
+    if (currentMeeting && recordingMeetingId && currentMeeting.id === recordingMeetingId) {
+      const chapters = await detectMeetingChapters(recordingMeetingId, currentMeeting.transcript);
+      if (chapters.length > 0) {
+        currentMeeting.chapters = chapters;
+        await saveMeetingChanges();
+      }
+    }
+
+    // Auto-generate title if still untitled
     if (currentMeeting && currentMeeting.title === "Untitled Meeting" && hasTranscriptContent(currentMeeting.transcript)) {
       try {
         const title = await aiService.generateTitle(
@@ -460,8 +613,23 @@ export async function stopRecording() {
       }
     }
 
+    if (preset?.autoExport && currentMeeting && currentMeeting.id === recordingMeetingId) {
+      try {
+        await exportMeeting(currentMeeting, { force: true });
+      } catch (error) {
+        console.error("Failed to export meeting:", error);
+      }
+    }
+
     if (recordingMeetingId) {
-      void autoEnhanceNotesForMeeting(recordingMeetingId, getDefaultTemplateId());
+      void autoEnhanceNotesForMeeting(
+        recordingMeetingId,
+        preset?.promptTemplateId ?? project?.promptTemplateId ?? getDefaultTemplateId()
+      );
+      // A session restarted after it broke leaves the meeting in two parts.
+      void checkForDuplicateMeetings(recordingMeetingId).catch((error) =>
+        console.error("Failed to look for duplicate meetings:", error)
+      );
     }
   } catch (error) {
     stopRecordingInProgress = false;
@@ -481,6 +649,48 @@ export async function toggleMute() {
   }
 }
 
+/**
+ * Closes the transcription connection without ending the recording, so no
+ * audio is sent or billed until `resumeRecording`. `recording-resumed` says
+ * when the transcript carries on.
+ */
+export async function pauseRecording(sessionId?: string): Promise<void> {
+  await invoke("pause_recording", { sessionId });
+}
+
+export async function resumeRecording(sessionId?: string): Promise<void> {
+  await invoke("resume_recording", { sessionId });
+}
+
+/**
+ * What muting does: drop the mic, drop system audio, or pause transcription
+ * while audio is still captured locally.
+ */
+export type MuteMode = "mic" | "system" | "transcription";
+
+export async function getMuteMode(): Promise<MuteMode> {
+  return await invoke<MuteMode>("get_mute_mode");
+}
+
+export async function setMuteMode(mode: MuteMode) {
+  await invoke("set_mute_mode", { mode });
+}
+
+/** Settings of the live recording that can change without restarting it; omitted ones stay. */
+export interface SessionConfigUpdate {
+  /** Takes over in a new provider session; watch `vocab-updated` / `vocab-update-failed`. */
+  additionalVocab?: VocabularyEntry[];
+  /** Defaults to the new vocabulary when only that is given. */
+  keywords?: string[];
+  muteMode?: MuteMode;
+  partialMinIntervalMs?: number;
+}
+
+/** Applies `update` to the running recording and returns the names of the settings changed. */
+export async function updateSessionConfig(update: SessionConfigUpdate): Promise<string[]> {
+  return await invoke<string[]>("update_session_config", { update });
+}
+
 function setMicActivityVisibility(visible: boolean) {
   if (elements.micActivity) {
     elements.micActivity.style.display = visible ? "inline-flex" : "none";
@@ -540,6 +750,7 @@ export async function updateRecordingUI() {
     elements.muteBtn.title = isMuted ? "Unmute microphone" : "Mute microphone";
     elements.muteBtn.setAttribute("aria-label", isMuted ? "Unmute microphone" : "Mute microphone");
   }
+  updateShareLinkButton();
 
   const shouldShowMicActivity = isRecording && isViewingRecordingMeeting;
   setMicActivityVisibility(shouldShowMicActivity);
@@ -614,7 +825,7 @@ export async function updateTranscript(data: TranscriptData) {
   const isViewingRecordingMeeting = currentMeeting?.id === recordingMeetingId;
 
   if (data.is_partial) {
-    const partialText = tidyTranscriptText(data.text);
+    const partialText = tidyTranscriptText(data.text ?? "");
     if (partialText.length > 0) {
       lastTranscriptReceivedAt = Date.now();
     }
@@ -625,17 +836,11 @@ export async function updateTranscript(data: TranscriptData) {
     }
   } else {
     // Update final transcript in the recording meeting
-    const turns =
-      data.turns?.map((turn) => ({
-        speaker: turn.speaker?.trim() || null,
-        text: tidyTranscriptText(turn.text || ""),
-      })) ??
-      transcriptTextToTurns(tidyTranscriptText(data.text)).map((turn) => ({
-        speaker: turn.speaker,
-        text: tidyTranscriptText(turn.text),
-      }));
-
-    recordingMeeting.transcript = turns;
+    const existing = liveTurns?.meetingId === recordingMeetingId ? liveTurns.turns : [];
+    const merged = await mergeFinalTranscript(existing, data);
+    liveTurns = { meetingId: recordingMeetingId, turns: merged };
+    // Saved and shown without the speech of attendees who declined.
+    recordingMeeting.transcript = await withoutDeclinedSpeech(recordingMeetingId, merged);
     recordingMeeting.updatedAt = new Date();
     lastTranscriptReceivedAt = Date.now();
     
@@ -667,6 +872,91 @@ export async function updateTranscript(data: TranscriptData) {
   }
 }
 
+/**
+ * Hides the transcript while the screen is shared, if the settings say to,
+ * and catches up on what the backend held back once sharing stops.
+ */
+export async function applyScreenShareStatus(status: ScreenShareStatus) {
+  if (elements.transcriptContent) {
+    elements.transcriptContent.hidden = status.transcriptHidden;
+  }
+  if (status.transcriptHidden) {
+    transcriptMissedWhileHidden = getIsRecording();
+    if (elements.partialTranscript) {
+      elements.partialTranscript.textContent = status.placeholder;
+      handlePartialTranscriptUpdate();
+    }
+    return;
+  }
+  if (elements.partialTranscript?.textContent === status.placeholder) {
+    elements.partialTranscript.textContent = "";
+    handlePartialTranscriptUpdate();
+  }
+  await catchUpHiddenTranscript();
+}
+
+async function catchUpHiddenTranscript() {
+  if (!transcriptMissedWhileHidden) {
+    return;
+  }
+  transcriptMissedWhileHidden = false;
+  const full = await invoke<FullTranscript>("get_full_transcript");
+  await updateTranscript({
+    delta_text: full.text,
+    is_partial: false,
+    turns: full.turns,
+    turn_offset: 0,
+    total_turns: full.turns.length,
+  });
+}
+
+function tidyTurn(turn: TranscriptTurn): TranscriptTurn {
+  return {
+    speaker: turn.speaker?.trim() || null,
+    text: tidyTranscriptText(turn.text || ""),
+    offset: typeof turn.offset === "number" ? turn.offset : null,
+    end_offset: typeof turn.end_offset === "number" ? turn.end_offset : null,
+    started_at: turn.started_at ?? null,
+    ended_at: turn.ended_at ?? null,
+    ...(typeof turn.gap_secs === "number" ? { gap_secs: turn.gap_secs } : {}),
+    ...(turn.note ? { note: true } : {}),
+    ...(turn.source ? { source: turn.source } : {}),
+    ...(turn.words?.length ? { words: turn.words } : {}),
+  };
+}
+
+/**
+ * Applies a final transcript-update. The backend sends only changed/appended
+ * turns plus their offset; if our copy has drifted, resync from the backend.
+ */
+async function mergeFinalTranscript(
+  existing: TranscriptTurn[],
+  data: TranscriptData,
+): Promise<TranscriptTurn[]> {
+  if (!data.turns) {
+    return transcriptTextToTurns(tidyTranscriptText(data.delta_text ?? "")).map((turn) => ({
+      speaker: turn.speaker,
+      text: tidyTranscriptText(turn.text),
+    }));
+  }
+
+  const incoming = data.turns.map(tidyTurn);
+  if (typeof data.turn_offset !== "number") {
+    return incoming;
+  }
+
+  const merged =
+    existing.length >= data.turn_offset
+      ? existing.slice(0, data.turn_offset).concat(incoming)
+      : null;
+  if (merged && (data.total_turns === undefined || merged.length === data.total_turns)) {
+    return merged;
+  }
+
+  const full = await invoke<FullTranscript>("get_full_transcript");
+  return full.turns.map(tidyTurn);
+}
+
 async function handleMicDeviceChange(deviceId: string) {
   const normalized = deviceId || "";
   const previousDeviceId = activeMicDeviceId;
@@ -720,7 +1010,7 @@ function setupMicSelector() {
 export function setupRecordingListeners() {
   setupMicSelector();
   elements.startRecordingBtn?.addEventListener("click", startRecording);
-  elements.stopRecordingBtn?.addEventListener("click", stopRecording);
+  elements.stopRecordingBtn?.addEventListener("click", () => stopRecording());
   elements.muteBtn?.addEventListener("click", toggleMute);
 }
 
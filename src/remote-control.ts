@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** The phone paired as a remote; set once a phone opens the pairing link. */
+export interface PairedDevice {
+  pairedAt: string;
+  userAgent: string | null;
+}
+
+/** The open pairing, as reported by `start_pairing` and `get_pairing_status`. */
+export interface PairingStatus {
+  url: string;
+  /** SHA-256 of the remote's certificate, colon-separated, to compare with the phone's warning. */
+  fingerprint: string;
+  /** QR code for the phone to scan. */
+  qrSvg: string;
+  expiresAt: string;
+  device: PairedDevice | null;
+}
+
+export async function startPairing(ttlMinutes?: number): Promise<PairingStatus> {
+  return invoke<PairingStatus>("start_pairing", { ttlMinutes });
+}
+
+export async function stopPairing(): Promise<void> {
+  return invoke("stop_pairing");
+}
+
+export async function getPairingStatus(): Promise<PairingStatus | null> {
+  return invoke<PairingStatus | null>("get_pairing_status");
+}
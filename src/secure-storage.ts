@@ -1,6 +1,12 @@
 import { Stronghold, type Store } from "@tauri-apps/plugin-stronghold";
 import { appLocalDataDir, join } from "@tauri-apps/api/path";
-import { STORAGE_KEY_OPENAI_API, STORAGE_KEY_SPEECHMATICS_API } from "./constants";
+import {
+  STORAGE_KEY_DEEPGRAM_API,
+  STORAGE_KEY_OPENAI_API,
+  STORAGE_KEY_SPEECHMATICS_API,
+} from "./constants";
+import { profileScopedKey } from "./profile";
+import type { RealtimeProvider } from "./settings";
 
 const SNAPSHOT_FILE = "secure-keys.hold";
 const CLIENT_NAME = "api-keys";
@@ -54,7 +60,8 @@ async function getStore(): Promise<{ stronghold: Stronghold; store: Store }> {
   }
 }
 
-async function setSecret(key: string, value: string): Promise<boolean> {
+async function setSecret(baseKey: string, value: string): Promise<boolean> {
+  const key = profileScopedKey(baseKey);
   try {
     const { stronghold, store } = await getStore();
     await store.insert(key, Array.from(encoder.encode(value)));
@@ -67,7 +74,8 @@ async function setSecret(key: string, value: string): Promise<boolean> {
   }
 }
 
-async function getSecret(key: string): Promise<string | null> {
+async function getSecret(baseKey: string): Promise<string | null> {
+  const key = profileScopedKey(baseKey);
   try {
     const { store } = await getStore();
     const result = await store.get(key);
@@ -80,7 +88,8 @@ async function getSecret(key: string): Promise<string | null> {
   }
 }
 
-async function deleteSecret(key: string): Promise<void> {
+async function deleteSecret(baseKey: string): Promise<void> {
+  const key = profileScopedKey(baseKey);
   try {
     const { stronghold, store } = await getStore();
     await store.remove(key);
@@ -124,6 +133,23 @@ export async function setSpeechmaticsApiKey(value: string | null | undefined): P
   await setSecret(STORAGE_KEY_SPEECHMATICS_API, value.trim());
 }
 
+export async function getDeepgramApiKey(): Promise<string | null> {
+  return getSecret(STORAGE_KEY_DEEPGRAM_API);
+}
+
+export async function setDeepgramApiKey(value: string | null | undefined): Promise<void> {
+  if (!value || !value.trim()) {
+    await deleteSecret(STORAGE_KEY_DEEPGRAM_API);
+    return;
+  }
+  await setSecret(STORAGE_KEY_DEEPGRAM_API, value.trim());
+}
+
+/** The key recordings need for `provider`. */
+export async function getRealtimeApiKey(provider: RealtimeProvider = "speechmatics"): Promise<string | null> {
+  return provider === "deepgram" ? getDeepgramApiKey() : getSpeechmaticsApiKey();
+}
+
 export async function getOpenAIApiKey(): Promise<string | null> {
   return getSecret(STORAGE_KEY_OPENAI_API);
 }
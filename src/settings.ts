@@ -0,0 +1,339 @@
+import { invoke } from "@tauri-apps/api/core";
+import { getCustomDictionaryEntries, CUSTOM_DICTIONARY_LIMIT } from "./custom-dictionary";
+import { STORAGE_KEY_DEBUG_AUDIO_DUMP, STORAGE_KEY_SPEECHMATICS_URL } from "./constants";
+import type { CalendarEvent, CalendarEventInstance } from "./types";
+
+const STORAGE_KEY_SETTINGS_MIGRATED = "backend_settings_migrated";
+
+export interface VocabularyEntry {
+  content: string;
+  sounds_like?: string[];
+}
+
+/** Vocabulary sent only when `speaker` is attached to the recording. */
+export interface SpeakerVocabulary {
+  speaker: string;
+  additionalVocab: VocabularyEntry[];
+}
+
+export interface SimulatorScriptLine {
+  speaker: string;
+  text: string;
+}
+
+export interface SimulatorConfig {
+  /** Playback speed; 2 speaks twice as fast as a person would. */
+  speed: number;
+  /** Voices the demo meeting is spread across; ignored with a script. */
+  speakers: number;
+  wordsPerMinute: number;
+  partialIntervalMs: number;
+  /** Words are finalized at least this often, and at the end of each line. */
+  finalIntervalMs: number;
+  pauseMs: number;
+  /** Start over once the script runs out. */
+  repeat: boolean;
+  /** Lines to replay instead of the demo meeting. */
+  script: SimulatorScriptLine[] | null;
+}
+
+/** Meetings never recorded; every condition a rule sets has to match. */
+export interface AvoidRule {
+  /** Calendar id or name. */
+  calendar?: string | null;
+  /** Regular expression for the event title, case-insensitive, e.g. `1:1 with HR`. */
+  titlePattern?: string | null;
+  /** Email or name of an attendee. */
+  attendee?: string | null;
+}
+
+/**
+ * Local hours repeating on `days` (every day when empty). An `end` at or
+ * before `start` runs past midnight.
+ */
+export interface QuietWindow {
+  /** E.g. `Evenings`. */
+  label?: string | null;
+  days: Array<"mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun">;
+  /** `HH:MM`, 24-hour. */
+  start: string;
+  end: string;
+}
+
+/** Quiet hours in force, from `quietHoursStatus`. */
+export interface QuietPeriod {
+  label: string | null;
+  /** When they end (ISO 8601). */
+  until: string;
+}
+
+export interface BackendSettings {
+  version: number;
+  transcription: {
+    /** Service recordings stream to; Speechmatics when unset. */
+    provider?: RealtimeProvider;
+    rtUrl: string | null;
+    /** Deepgram streaming endpoint; `null` uses Deepgram's own. */
+    deepgramUrl?: string | null;
+    /** `apiKey` authenticates without `mp.speechmatics.com`, for networks that block it. */
+    auth?: "auto" | "temporaryKey" | "apiKey";
+    language: string;
+    domain: string | null;
+    additionalVocab: VocabularyEntry[];
+    speakerVocab?: SpeakerVocabulary[];
+    /** Speechmatics accuracy tier; `standard` is cheaper. */
+    operatingPoint?: "standard" | "enhanced";
+    /** Seconds a word may be held back to get it right, 0.7 to 4; lower gives snappier captions. */
+    maxDelay?: number;
+    enablePartials?: boolean;
+    /** Words recognised with less confidence than this (0 to 1) are underlined for review. */
+    confidenceThreshold?: number | null;
+    partialMinIntervalMs: number;
+    audioBatchMs: number;
+    /** Upper bound on waiting for the provider to acknowledge the last audio on stop. */
+    stopDrainTimeoutMs?: number;
+    debugAudioDump: boolean;
+    formatting: {
+      smartQuotes: boolean;
+      capitalizeSentences: boolean;
+      removeFillers: boolean;
+    };
+    /** Hours included in the Speechmatics plan each month. */
+    monthlyAllowanceHours: number | null;
+    /** Per-hour prices for estimating what each meeting's transcription cost. */
+    costs: {
+      currency: string;
+      /** Keyed by provider: `speechmatics`, or the host of a self-hosted endpoint. */
+      hourlyRates: Record<string, number>;
+    };
+    /** Replays a scripted meeting instead of calling Speechmatics; no API key needed. */
+    simulator: {
+      enabled: boolean;
+      config: SimulatorConfig;
+    };
+    /** Sends the microphone and system audio as two channels, labelled by `labels` in that order. */
+    separateChannels: {
+      enabled: boolean;
+      labels: [string, string];
+    };
+    /** Silences notification chimes in the system audio; the transcript runs about a second behind. */
+    filterNotificationSounds: boolean;
+    /** Sends audio in larger batches on battery, in Low Power Mode or when the machine runs hot. */
+    powerAwareBatching?: boolean;
+  };
+  shortcuts: {
+    newMeeting: string | null;
+    mute: string | null;
+  };
+  logLevel: string;
+  /** UI language tag such as `de` or `pt-BR`; also used for backend-rendered transcript text. */
+  locale: string;
+  automation: {
+    hooks: AutomationHook[];
+    timeoutSecs: number;
+  };
+  updates: {
+    autoCheck: boolean;
+  };
+  silenceWatchdog: {
+    enabled: boolean;
+    timeoutSecs: number;
+    autoStop: boolean;
+  };
+  /** Sound or trackpad cues when the speaker changes, played by the backend. */
+  speakerCues: {
+    enabled: boolean;
+    everyChange: boolean;
+    /** Speaker labels, such as enrolled names, that get a distinct cue. */
+    people: string[];
+    sound: boolean;
+    haptic: boolean;
+  };
+  /** File the main recording's finished turns are appended to while it runs. */
+  liveTranscript: {
+    path: string | null;
+    format: "text" | "jsonl";
+  };
+  /** Live captions of the main recording over OSC and HTTP, for AV systems and OBS. */
+  captionOutput: {
+    /** `host:port` that receives OSC messages over UDP. */
+    oscTarget: string | null;
+    oscAddress: string;
+    httpPort: number | null;
+    httpLan: boolean;
+  };
+  /** Spoken commands, recognized on-device while the main session records. */
+  voiceCommands: {
+    enabled: boolean;
+    wakeWord: string;
+  };
+  /** Native mode records the mic in the backend instead of this window. */
+  microphone: {
+    native: boolean;
+    /** From `listNativeMicDevices`; `null` follows the system default. */
+    device: string | null;
+  };
+  /** Keeps the recording's audio next to the transcript. */
+  audioRecording: AudioRecordingSettings;
+  recordingPresets: RecordingPreset[];
+  avoidList: AvoidRule[];
+  exportNormalization: ExportNormalizationSettings;
+  /** Offers Zoom and Teams recordings saved on this machine for transcription. */
+  recordingImports: {
+    enabled: boolean;
+    zoom: boolean;
+    teams: boolean;
+    /** Extra folders to watch; absolute paths. */
+    folders: string[];
+  };
+  /** Hides the live transcript while the screen is shared. */
+  screenSharePrivacy: {
+    hideTranscript: boolean;
+    placeholder: string;
+  };
+  /** No automatic recordings or meeting reminders during these. */
+  quietHours: QuietWindow[];
+}
+
+/** Dates, times and currency amounts rewritten for the reader's locale on export. */
+export interface ExportNormalizationSettings {
+  enabled: boolean;
+  /** Tag like `de-CH` or `en-GB`; unset follows the UI language. */
+  locale?: string | null;
+  dates: boolean;
+  /** 24-hour clock outside English. */
+  times: boolean;
+  /** Separators and symbol placement; the currency is never converted. */
+  currencies: boolean;
+}
+
+export interface AudioRecordingSettings {
+  enabled: boolean;
+  format: "wav" | "flac";
+  /** `separate` writes a microphone file and a system audio file. */
+  layout: "mixed" | "separate";
+}
+
+/** Named bundle of per-meeting options; unset fields fall back to the regular settings. */
+export interface RecordingPreset {
+  id: string;
+  name: string;
+  rtUrl: string | null;
+  language: string | null;
+  domain: string | null;
+  diarization: boolean | null;
+  maxSpeakers: number | null;
+  additionalVocab: VocabularyEntry[] | null;
+  interviewMode: boolean | null;
+  autoExport: boolean | null;
+  promptTemplateId: string | null;
+  /** Client certificate and extra CAs for `rtUrl`, as absolute paths to PEM files. */
+  tls: {
+    clientCertPath: string | null;
+    clientKeyPath: string | null;
+    caBundlePath: string | null;
+  } | null;
+}
+
+export type RealtimeProvider = "speechmatics" | "deepgram";
+
+export type HookEvent = "recordingStarted" | "recordingStopped" | "summaryReady";
+
+export interface AutomationHook {
+  event: HookEvent;
+  command: string;
+  args: string[];
+  enabled: boolean;
+}
+
+type DeepPartial<T> = { [K in keyof T]?: T[K] extends object ? DeepPartial<T[K]> | null : T[K] | null };
+
+export async function getSettings(): Promise<BackendSettings> {
+  return invoke<BackendSettings>("get_settings");
+}
+
+/** Merges `patch` into the stored settings; `null` resets a field to its default. */
+export async function updateSettings(patch: DeepPartial<BackendSettings>): Promise<BackendSettings> {
+  return invoke<BackendSettings>("update_settings", { patch });
+}
+
+/** Switches the language of backend-rendered text, such as speaker prefixes in exports. */
+export async function setLocale(locale: string): Promise<BackendSettings> {
+  return invoke<BackendSettings>("set_locale", { locale });
+}
+
+/** Turns native mic mode on or off; turning it on mid-recording moves the mic at once. */
+export async function setNativeMic(enabled: boolean, device: string | null = null): Promise<BackendSettings> {
+  return invoke<BackendSettings>("set_native_mic", { enabled, device });
+}
+
+export async function listNativeMicDevices(): Promise<string[]> {
+  return invoke<string[]>("list_native_mic_devices");
+}
+
+export async function getRecordingPreset(id: string): Promise<RecordingPreset | null> {
+  const settings = await getSettings();
+  return settings.recordingPresets.find((preset) => preset.id === id) ?? null;
+}
+
+/** The quiet hours in force now, or `null`. */
+export async function quietHoursStatus(): Promise<QuietPeriod | null> {
+  return invoke<QuietPeriod | null>("quiet_hours_status");
+}
+
+/**
+ * Resolves when `event` may be recorded automatically; rejects during quiet
+ * hours or with the avoid-list rule that covers it.
+ */
+export async function checkRecordingAllowed(event: CalendarEventInstance | CalendarEvent): Promise<void> {
+  return invoke("check_recording_allowed", { event });
+}
+
+export async function syncCustomDictionaryToBackend(): Promise<void> {
+  const entries = await getCustomDictionaryEntries();
+  const additionalVocab: VocabularyEntry[] = [];
+  const bySpeaker = new Map<string, SpeakerVocabulary>();
+  for (const entry of entries.slice(0, CUSTOM_DICTIONARY_LIMIT)) {
+    const vocab = {
+      content: entry.content,
+      sounds_like: entry.soundsLike.length ? entry.soundsLike : undefined,
+    };
+    if (!entry.speakers?.length) {
+      additionalVocab.push(vocab);
+      continue;
+    }
+    for (const speaker of entry.speakers) {
+      const key = speaker.toLowerCase();
+      const group = bySpeaker.get(key) ?? { speaker, additionalVocab: [] };
+      group.additionalVocab.push(vocab);
+      bySpeaker.set(key, group);
+    }
+  }
+  await updateSettings({
+    transcription: { additionalVocab, speakerVocab: [...bySpeaker.values()] },
+  });
+}
+
+/** One-time copy of options that used to live only in localStorage into the backend store. */
+export async function migrateLegacySettings(): Promise<void> {
+  if (localStorage.getItem(STORAGE_KEY_SETTINGS_MIGRATED) === "true") return;
+
+  try {
+    await updateSettings({
+      transcription: {
+        rtUrl: localStorage.getItem(STORAGE_KEY_SPEECHMATICS_URL)?.trim() || null,
+        debugAudioDump: localStorage.getItem(STORAGE_KEY_DEBUG_AUDIO_DUMP) === "true",
+      },
+      shortcuts: {
+        newMeeting: localStorage.getItem("global_shortcut") || null,
+        mute: localStorage.getItem("mute_shortcut") || null,
+      },
+    });
+    await syncCustomDictionaryToBackend();
+    localStorage.setItem(STORAGE_KEY_SETTINGS_MIGRATED, "true");
+    // The backend store is the only copy from here on.
+    localStorage.removeItem(STORAGE_KEY_SPEECHMATICS_URL);
+  } catch (error) {
+    console.error("Failed to migrate settings to the backend:", error);
+  }
+}
@@ -0,0 +1,80 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { elements } from "./dom";
+import { getIsRecording } from "./state";
+import { showToast } from "./ui/interactions";
+
+/** The open guest link, as reported by `start_sharing` and `get_sharing_status`. */
+export interface ShareStatus {
+  url: string;
+  /** SHA-256 of the link's self-signed certificate, as browsers show it. */
+  fingerprint: string;
+  expiresAt: string;
+  viewers: number;
+}
+
+let activeShare: ShareStatus | null = null;
+
+async function copyToClipboard(text: string): Promise<boolean> {
+  try {
+    await navigator.clipboard.writeText(text);
+    return true;
+  } catch (error) {
+    console.warn("Failed to copy guest link:", error);
+    return false;
+  }
+}
+
+/** Opens a read-only guest link to the live transcript, or closes the open one. */
+export async function toggleShareLink(): Promise<void> {
+  if (activeShare) {
+    await invoke("stop_sharing");
+    return;
+  }
+  try {
+    activeShare = await invoke<ShareStatus>("start_sharing", {});
+    const expires = new Date(activeShare.expiresAt).toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+    const message = (await copyToClipboard(activeShare.url))
+      ? `Guest link copied. People on your network can follow the transcript until ${expires}.`
+      : `Guest link (until ${expires}): ${activeShare.url}`;
+    // The certificate is made for this link, so browsers warn about it once.
+    showToast(`${message} Browsers will ask guests to trust certificate ${activeShare.fingerprint.slice(0, 11)}…`, {
+      type: "success",
+      duration: 10000,
+    });
+  } catch (error) {
+    console.error("Failed to share transcript:", error);
+    showToast(`Failed to share transcript: ${error}`, { type: "error", duration: 5000 });
+  }
+  updateShareLinkButton();
+}
+
+export function updateShareLinkButton(): void {
+  const button = elements.shareLiveBtn;
+  if (!button) return;
+  const sharing = activeShare !== null;
+  button.disabled = !getIsRecording() && !sharing;
+  button.classList.toggle("active", sharing);
+  const label = sharing ? "Stop sharing the live transcript" : "Share a live transcript link";
+  button.title = label;
+  button.setAttribute("aria-label", label);
+}
+
+export async function initializeShareLink(): Promise<void> {
+  elements.shareLiveBtn?.addEventListener("click", () => void toggleShareLink());
+
+  await listen("sharing-stopped", () => {
+    if (activeShare) {
+      showToast("The guest link has been closed.", { type: "info" });
+    }
+    activeShare = null;
+    updateShareLinkButton();
+  });
+
+  try {
+    activeShare = await invoke<ShareStatus | null>("get_sharing_status");
+  } catch (error) {
+    console.error("Failed to read sharing status:", error);
+  }
+  updateShareLinkButton();
+}
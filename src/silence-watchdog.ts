@@ -0,0 +1,37 @@
+import { listen } from "@tauri-apps/api/event";
+import { sendNotification } from "./notifications";
+import { stopRecording } from "./recording";
+import { getIsRecording } from "./state";
+import { showToast } from "./ui/interactions";
+
+/** Payload of `silence-warning`. */
+export interface SilenceWarning {
+  silentSecs: number;
+  autoStopInSecs: number | null;
+}
+
+function describeSilence(warning: SilenceWarning): string {
+  const minutes = Math.round(warning.silentSecs / 60);
+  const heard = `Nothing has been heard for ${minutes} minute${minutes === 1 ? "" : "s"}.`;
+  return warning.autoStopInSecs !== null
+    ? `${heard} Recording stops in ${warning.autoStopInSecs} seconds unless someone speaks.`
+    : `${heard} Did the meeting end?`;
+}
+
+/** The backend watches for silence on both sources; this surfaces it and stops when asked. */
+export async function initializeSilenceWatchdog(): Promise<void> {
+  await listen<SilenceWarning>("silence-warning", (event) => {
+    const message = describeSilence(event.payload);
+    showToast(message, { type: "warning", duration: 10000 });
+    void sendNotification("Recording is silent", { body: message, tag: "silence-warning" });
+  });
+
+  await listen("silence-auto-stop", async () => {
+    if (!getIsRecording()) return;
+    await stopRecording();
+    void sendNotification("Recording stopped", {
+      body: "Stopped after a long silence. The transcript has been saved.",
+      tag: "silence-warning",
+    });
+  });
+}
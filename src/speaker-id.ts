@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { showToast } from "./ui/interactions";
-import { DEFAULT_SPEECHMATICS_URL, STORAGE_KEY_SPEECHMATICS_URL } from "./constants";
+import type { RosterSpeaker } from "./projects";
 
 export interface VoiceSample {
   id: string;
@@ -19,6 +19,45 @@ export interface SpeakerProfile {
   microphones?: string[];
 }
 
+/** Identifiers Speechmatics returned for a speaker at the end of a recording, also sent as `speakers-result`. */
+export interface CapturedSpeaker {
+  /** `S1`, `S2`, ... or a known speaker's label. */
+  label: string;
+  speakerIdentifiers: string[];
+  /** Labels like `S1` only refer to the same voice among entries captured at the same time. */
+  capturedAt: string;
+}
+
+/** Speakers captured in a meeting's recordings, oldest first. */
+export function getMeetingSpeakers(meetingId: string): Promise<CapturedSpeaker[]> {
+  return invoke<CapturedSpeaker[]>("get_meeting_speakers", { meetingId });
+}
+
+/** A captured voice named so that later recordings in a project recognize it. */
+export function toRosterSpeaker(speaker: CapturedSpeaker, name: string): RosterSpeaker {
+  return { label: name.trim(), speakerIdentifiers: speaker.speakerIdentifiers };
+}
+
+/**
+ * Enrolls a voice heard in the running recording, such as `S2`, from its
+ * last `seconds` of speech (30 by default, from the last two minutes). The
+ * result is named `name`, or keeps `label`, ready for a project roster or
+ * `speakerProfiles`.
+ */
+export async function enrollCurrentSpeaker(
+  label: string,
+  apiKey: string,
+  options: { name?: string; seconds?: number; sessionId?: string } = {}
+): Promise<RosterSpeaker> {
+  const speakerIdentifiers = await invoke<string[]>("enroll_current_speaker", {
+    apiKey,
+    label,
+    seconds: options.seconds ?? null,
+    sessionId: options.sessionId ?? null,
+  });
+  return { label: options.name?.trim() || label.trim(), speakerIdentifiers };
+}
+
 const SPEAKER_PROFILE_STORAGE_KEY = "speechmatics_speaker_profile";
 const VOICE_SAMPLES_STORAGE_KEY = "speechmatics_voice_samples";
 
@@ -203,7 +242,6 @@ export async function enrollSpeakerFromSamples(
 
   const identifiers = new Set<string>();
   const microphones = new Set<string>();
-  const rtUrl = localStorage.getItem(STORAGE_KEY_SPEECHMATICS_URL) || DEFAULT_SPEECHMATICS_URL;
 
   for (const sample of samples) {
     options?.onProgress?.(`Enrolling sample from ${sample.deviceLabel || "microphone"}...`);
@@ -212,7 +250,6 @@ export async function enrollSpeakerFromSamples(
       apiKey,
       samples: Array.from(decoded.samples),
       sampleRate: decoded.sampleRate,
-      rtUrl,
     });
 
     ids.forEach((id) => {
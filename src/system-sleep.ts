@@ -0,0 +1,60 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { ask } from "@tauri-apps/plugin-dialog";
+import { pauseInactivityMonitor, resumeInactivityMonitor, stopRecording } from "./recording";
+import { getIsRecording } from "./state";
+import { showToast } from "./ui/interactions";
+
+/** Payload of `resume-offered`, sent after wake for a recording paused by sleep. */
+interface ResumeOffer {
+  meetingId: string | null;
+  meetingTitle: string | null;
+}
+
+/**
+ * The backend closes the transcription connection before the Mac sleeps and
+ * keeps the session; after wake we ask whether to carry on in the same meeting.
+ */
+export async function initializeSystemSleep(): Promise<void> {
+  await listen("recording-suspended", () => {
+    pauseInactivityMonitor();
+  });
+
+  await listen<ResumeOffer>("resume-offered", async (event) => {
+    if (!getIsRecording()) return;
+    const title = event.payload.meetingTitle?.trim() || "this meeting";
+    const resume = await ask(
+      `Recording was paused while your Mac was asleep. Resume recording into "${title}"?\n\nChoosing Stop saves the transcript so far.`,
+      { title: "Resume Recording", okLabel: "Resume", cancelLabel: "Stop" }
+    );
+    if (!resume) {
+      await stopRecording();
+      return;
+    }
+    try {
+      await invoke("resume_recording");
+    } catch (error) {
+      console.error("Failed to resume recording:", error);
+      showToast(`Failed to resume recording: ${error}`, { type: "error", duration: 5000 });
+    }
+  });
+
+  await listen("recording-resumed", () => {
+    resumeInactivityMonitor();
+    showToast("Recording resumed.", { type: "success" });
+  });
+
+  await listen<string>("recording-resume-failed", async (event) => {
+    const retry = await ask(
+      `Could not reconnect to transcription: ${event.payload}\n\nTry again, or stop and keep the transcript so far?`,
+      { title: "Resume Recording", okLabel: "Try Again", cancelLabel: "Stop" }
+    );
+    if (retry) {
+      await invoke("resume_recording").catch((error) => {
+        console.error("Failed to resume recording:", error);
+      });
+    } else {
+      await stopRecording();
+    }
+  });
+}
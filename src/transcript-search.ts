@@ -0,0 +1,57 @@
+import { invoke } from "@tauri-apps/api/core";
+import { TurnSource } from "./types";
+
+export interface TranscriptSearchFilters {
+  /** Only these meetings. */
+  meetingIds?: string[];
+  /** Only turns by this speaker. */
+  speaker?: string;
+  /** Only what you said (`mic`) or only what you heard (`system`). */
+  source?: TurnSource;
+  /** Most meetings to return; 50 by default. */
+  limit?: number;
+}
+
+export interface SnippetPart {
+  text: string;
+  highlight: boolean;
+}
+
+export interface TranscriptSearchHit {
+  turnIndex: number;
+  speaker: string | null;
+  source: TurnSource | null;
+  /** Seconds since the recording started. */
+  offset: number | null;
+  snippet: SnippetPart[];
+}
+
+export interface TranscriptSearchResult {
+  meetingId: string;
+  /** Turns that matched; `hits` has the first few. */
+  matches: number;
+  hits: TranscriptSearchHit[];
+}
+
+/**
+ * Meetings whose saved transcripts have a turn containing every word of
+ * `query` (a word may be cut short, so `budg` finds "budget"), most matches
+ * first. Searched in the backend, which keeps an index of the meeting store.
+ */
+export function searchTranscripts(
+  query: string,
+  filters?: TranscriptSearchFilters
+): Promise<TranscriptSearchResult[]> {
+  return invoke<TranscriptSearchResult[]>("search_transcripts", { query, filters });
+}
+
+/** Ids of the meetings whose transcripts match `query`, or `undefined` when the backend can't search. */
+export async function transcriptMatchIds(query: string): Promise<Set<string> | undefined> {
+  try {
+    const results = await searchTranscripts(query, { limit: 10_000 });
+    return new Set(results.map((result) => result.meetingId));
+  } catch (error) {
+    console.warn("Transcript search failed; scanning transcripts instead:", error);
+    return undefined;
+  }
+}
@@ -5,6 +5,7 @@ export interface Meeting {
   title: string;
   date: Date;
   transcript: TranscriptTurn[];
+  chapters?: TranscriptChapter[]; // Topic sections of long transcripts
   personalNotes: string;
   enhancedNotes: string;
   participants?: MeetingParticipant[];
@@ -13,6 +14,7 @@ export interface Meeting {
   updatedAt: Date;
   lastExportedFolderName?: string;
   promptTemplateId?: string; // Override template for this meeting
+  agenda?: string;                // Agenda text, one item per line or bullet
   // Calendar sync fields
   calendarEventId?: string;      // Link to calendar event
   calendarName?: string;          // Which calendar it came from
@@ -43,6 +45,8 @@ export interface CustomDictionaryEntry {
   id: string;
   content: string;
   soundsLike: string[];
+  /** Speakers whose recordings get this term; empty means every recording. */
+  speakers?: string[];
   createdAt: Date;
   updatedAt: Date;
 }
@@ -73,14 +77,65 @@ export type AppMode = 'meetings' | 'calendar' | 'tasks';
 export type TaskFilter = 'all' | 'today' | 'week' | 'overdue';
 
 export interface TranscriptData {
-  text: string;
+  /** Partial updates only: the words heard so far. */
+  text?: string;
+  /** Final updates only: the rendering of `turns`, not the whole transcript. */
+  delta_text?: string;
   is_partial: boolean;
   turns?: TranscriptTurn[];
+  /** Index of `turns[0]` in the full turn list; finals only carry changed turns. */
+  turn_offset?: number;
+  total_turns?: number;
+}
+
+/** Payload of `screen-share-changed`. */
+export interface ScreenShareStatus {
+  sharing: boolean;
+  /** Transcript text is held back from the window until sharing stops. */
+  transcriptHidden: boolean;
+  placeholder: string;
+}
+
+export interface FullTranscript {
+  text: string;
+  turns: TranscriptTurn[];
 }
 
 export interface TranscriptTurn {
   speaker?: string | null;
   text: string;
+  /** Seconds since the recording started, including muted stretches. */
+  offset?: number | null;
+  /** Where the turn ends, on the same clock as `offset`. */
+  end_offset?: number | null;
+  /** Wall-clock start and end of the turn (ISO 8601). */
+  started_at?: string | null;
+  ended_at?: string | null;
+  /** Set on a turn that only marks missed audio, e.g. while the app restarted. */
+  gap_secs?: number | null;
+  /** Typed during the recording rather than spoken; `speaker` is unset. */
+  note?: boolean;
+  /** Heard on the microphone (you) or in system audio (the call), when known. */
+  source?: TurnSource;
+  /** Recognised words with the provider's confidence, before formatting. */
+  words?: TranscriptWord[];
+}
+
+export type TurnSource = "mic" | "system";
+
+export interface TranscriptWord {
+  text: string;
+  /** From 0 to 1. */
+  confidence: number;
+  /** Below the recording's confidence threshold. */
+  low_confidence?: boolean;
+}
+
+/** A titled section of a transcript, starting at `transcript[startTurn]`. */
+export interface TranscriptChapter {
+  title: string;
+  startTurn: number;
+  offset?: number | null;
 }
 
 export interface Calendar {
@@ -96,6 +151,9 @@ export interface CalendarEvent {
   title: string;
   start_time: string; // ISO 8601 timestamp
   end_time: string;   // ISO 8601 timestamp
+  time_zone: string | null; // IANA zone; null for floating events
+  start_local: string; // ISO 8601, wall-clock time in the event's zone
+  end_local: string;
   attendees: CalendarAttendee[];
   notes: string;
   location: string;
@@ -113,6 +171,8 @@ export interface CalendarEventInstance {
   title: string;
   startTime: string; // ISO 8601 timestamp
   endTime: string;
+  /** Zone the organiser scheduled the event in, when it isn't floating. */
+  timeZone?: string;
   calendarId: string;
   calendarName: string;
   location?: string;
@@ -170,6 +230,7 @@ export interface DOMElements {
   customDictionaryForm: HTMLFormElement | null;
   customDictionaryTermInput: HTMLInputElement | null;
   customDictionarySoundsLikeInput: HTMLInputElement | null;
+  customDictionarySpeakersInput: HTMLInputElement | null;
   customDictionaryCancelBtn: HTMLButtonElement | null;
   calendarSyncEnabled: HTMLInputElement | null;
   requestCalendarPermissionBtn: HTMLElement | null;
@@ -206,6 +267,7 @@ export interface DOMElements {
   startRecordingBtn: HTMLButtonElement | null;
   stopRecordingBtn: HTMLButtonElement | null;
   muteBtn: HTMLButtonElement | null;
+  shareLiveBtn: HTMLButtonElement | null;
   inlineAudioDeviceSelect: HTMLSelectElement | null;
   micActivity: HTMLElement | null;
   micActivityDot: HTMLElement | null;
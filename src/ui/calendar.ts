@@ -7,12 +7,13 @@ import { switchToMeetingsMode } from "./tasks";
 import { getDocumentIcon, getEditIcon, getMicrophoneIcon } from "../icons";
 import { startRecording } from "../recording";
 import { showToast } from "./interactions";
+import { addDays } from "../utils";
 
 const CALENDAR_FILTER_STORAGE_KEY = "calendar-filter-selection";
 const DAY_IN_MS = 24 * 60 * 60 * 1000;
-const WEEK_IN_MS = DAY_IN_MS * 7;
-const UPCOMING_WINDOW_MS = 6 * WEEK_IN_MS;
-const PAST_WINDOW_MS = 30 * DAY_IN_MS;
+const UPCOMING_WINDOW_DAYS = 6 * 7;
+const UPCOMING_WINDOW_MS = UPCOMING_WINDOW_DAYS * DAY_IN_MS;
+const PAST_WINDOW_DAYS = 30;
 const CALENDAR_UI_REFRESH_MS = 30 * 1000;
 const NEAR_EVENT_WINDOW_MS = 5 * 60 * 1000;
 const START_ICON = getMicrophoneIcon(16);
@@ -357,9 +358,10 @@ function renderCalendarGrid(): void {
   const today = startOfDay(new Date());
   const { start, end } = getCalendarWindow();
   const gridStart = getWeekStart(start);
-  const totalDays = Math.ceil((end.getTime() - gridStart.getTime()) / DAY_IN_MS) + 1;
+  // Days around a DST change are 23 or 25 hours long, so round rather than ceil.
+  const totalDays = Math.round((startOfDay(end).getTime() - gridStart.getTime()) / DAY_IN_MS) + 1;
   const totalWeeks = Math.ceil(totalDays / 7);
-  const gridEnd = new Date(gridStart.getTime() + totalWeeks * WEEK_IN_MS);
+  const gridEnd = addDays(gridStart, totalWeeks * 7);
 
   const eventsByDay = buildEventsByDay(gridStart, gridEnd);
   const selectedDayEvents = eventsByDay.get(formatDateKey(selectedDate)) ?? [];
@@ -380,7 +382,7 @@ function renderCalendarGrid(): void {
 
   const cells = Array.from({ length: totalWeeks * 7 })
     .map((_, index) => {
-      const date = new Date(gridStart.getTime() + index * DAY_IN_MS);
+      const date = addDays(gridStart, index);
       const key = formatDateKey(date);
       const dayEvents = eventsByDay.get(key) ?? [];
       const preview = dayEvents
@@ -426,7 +428,7 @@ function renderCalendarGrid(): void {
 }
 
 function changeDay(offsetDays: number): void {
-  const target = startOfDay(new Date(selectedDate.getTime() + offsetDays * DAY_IN_MS));
+  const target = startOfDay(addDays(selectedDate, offsetDays));
   setSelectedDate(target);
 }
 
@@ -531,6 +533,7 @@ function renderEventCard(
     <article class="calendar-event-card${isLinked ? " linked" : ""}${isOverlapping ? " overlapping" : ""}" data-event-id="${event.id}">
       <div class="calendar-event-timeblock">
         <span class="calendar-event-time">${formatTimeRange(start, end)}</span>
+        ${formatEventZoneChip(event, start, end)}
         ${timingChip}
         ${seriesChip}
       </div>
@@ -667,8 +670,8 @@ function updateLastSync(): void {
 
 function getCalendarWindow(): { start: Date; end: Date } {
   const now = new Date();
-  const start = startOfDay(new Date(now.getTime() - PAST_WINDOW_MS));
-  const end = new Date(now.getTime() + UPCOMING_WINDOW_MS);
+  const start = startOfDay(addDays(now, -PAST_WINDOW_DAYS));
+  const end = addDays(now, UPCOMING_WINDOW_DAYS);
   end.setHours(23, 59, 59, 999);
   return { start, end };
 }
@@ -904,12 +907,32 @@ function formatRelativeTime(target: Date): string {
   return diffMs >= 0 ? `in ${days}d` : `${days}d ago`;
 }
 
-function formatTimeRange(start: Date, end: Date): string {
-  const startText = start.toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
-  const endText = end.toLocaleTimeString([], { hour: "2-digit", minute: "2-digit" });
+function formatTimeRange(start: Date, end: Date, timeZone?: string): string {
+  const options: Intl.DateTimeFormatOptions = { hour: "2-digit", minute: "2-digit", timeZone };
+  const startText = start.toLocaleTimeString([], options);
+  const endText = end.toLocaleTimeString([], options);
   return `${startText} – ${endText}`;
 }
 
+/** The organiser's local time, for events scheduled in a zone other than ours. */
+function formatEventZoneChip(event: CalendarEventInstance, start: Date, end: Date): string {
+  const localZone = Intl.DateTimeFormat().resolvedOptions().timeZone;
+  if (!event.timeZone || event.timeZone === localZone) {
+    return "";
+  }
+  try {
+    const zoneName =
+      new Intl.DateTimeFormat([], { timeZone: event.timeZone, timeZoneName: "short" })
+        .formatToParts(start)
+        .find((part) => part.type === "timeZoneName")?.value ?? event.timeZone;
+    const range = formatTimeRange(start, end, event.timeZone);
+    return `<span class="calendar-event-chip time-zone" title="${escapeHtml(event.timeZone)}">${escapeHtml(`${range} ${zoneName}`)}</span>`;
+  } catch {
+    // Unknown zone names throw; the local time alone is still right.
+    return "";
+  }
+}
+
 function formatDayLabel(date: Date): string {
   if (isToday(date)) {
     return "Today";
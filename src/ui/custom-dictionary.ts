@@ -127,6 +127,13 @@ function renderDictionaryList(): void {
       textWrapper.appendChild(soundsSpan);
     }
 
+    if (entry.speakers?.length) {
+      const speakersSpan = document.createElement("span");
+      speakersSpan.className = "custom-dictionary-sounds-like";
+      speakersSpan.textContent = `Only with: ${entry.speakers.join(", ")}`;
+      textWrapper.appendChild(speakersSpan);
+    }
+
     const actions = document.createElement("div");
     actions.className = "custom-dictionary-item-actions";
 
@@ -158,7 +165,12 @@ function handleAddButtonClick(): void {
   startForm("add");
 }
 
-function startForm(mode: FormMode, initialTerm: string = "", soundsLike: string[] = []): void {
+function startForm(
+  mode: FormMode,
+  initialTerm: string = "",
+  soundsLike: string[] = [],
+  speakers: string[] = []
+): void {
   if (!elements.customDictionaryForm || !elements.customDictionaryTermInput) {
     return;
   }
@@ -172,6 +184,9 @@ function startForm(mode: FormMode, initialTerm: string = "", soundsLike: string[
   if (elements.customDictionarySoundsLikeInput) {
     elements.customDictionarySoundsLikeInput.value = soundsLike.join(", ");
   }
+  if (elements.customDictionarySpeakersInput) {
+    elements.customDictionarySpeakersInput.value = speakers.join(", ");
+  }
 
   const submitBtn = elements.customDictionaryForm.querySelector<HTMLButtonElement>('button[type="submit"]');
   if (submitBtn) {
@@ -205,6 +220,9 @@ function hideForm(): void {
   if (elements.customDictionarySoundsLikeInput) {
     elements.customDictionarySoundsLikeInput.value = "";
   }
+  if (elements.customDictionarySpeakersInput) {
+    elements.customDictionarySpeakersInput.value = "";
+  }
 
   highlightEditingItem();
 }
@@ -233,7 +251,7 @@ function handleListClick(event: Event): void {
     const entry = entries.find((item) => item.id === id);
     if (entry) {
       currentEditId = entry.id;
-      startForm("edit", entry.content, entry.soundsLike);
+      startForm("edit", entry.content, entry.soundsLike, entry.speakers);
     }
     return;
   }
@@ -278,6 +296,7 @@ async function handleFormSubmit(event: Event): Promise<void> {
   const term = elements.customDictionaryTermInput.value.trim();
   const soundsInput = elements.customDictionarySoundsLikeInput?.value ?? "";
   const soundsLike = parseSoundsLikeInput(soundsInput);
+  const speakers = parseSoundsLikeInput(elements.customDictionarySpeakersInput?.value);
 
   if (!term) {
     showToast("Please enter a term.", { type: "warning" });
@@ -287,14 +306,14 @@ async function handleFormSubmit(event: Event): Promise<void> {
 
   try {
     if (mode === "edit" && currentEditId) {
-      await updateCustomDictionaryEntry(currentEditId, { content: term, soundsLike });
+      await updateCustomDictionaryEntry(currentEditId, { content: term, soundsLike, speakers });
       hideForm();
       await refreshCustomDictionaryUI();
       showCustomDictionaryToast(`Updated "${term}" in the custom dictionary.`);
       return;
     }
 
-    const { isNew } = await upsertCustomDictionaryEntry(term, soundsLike);
+    const { isNew } = await upsertCustomDictionaryEntry(term, soundsLike, speakers);
     hideForm();
     await refreshCustomDictionaryUI();
 
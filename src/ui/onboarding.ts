@@ -4,9 +4,10 @@ import {
   DEFAULT_SPEECHMATICS_URL,
   STORAGE_KEY_OPENAI_ENDPOINT,
   STORAGE_KEY_OPENAI_MODEL,
-  STORAGE_KEY_SPEECHMATICS_URL,
 } from "../constants";
 import { elements } from "../dom";
+import { getSettings, updateSettings } from "../settings";
+import { completeOnboardingStep } from "../onboarding-state";
 import { calendarService } from "../calendar-service";
 import { getExportSettings, saveExportSettings, initializeDefaultExportPath } from "../file-export";
 import { refreshAudioDeviceOptions } from "../audio-devices";
@@ -370,7 +371,9 @@ async function hydrateFields() {
   calendarNotificationsToggle = onboardingRoot.querySelector<HTMLInputElement>("#onboarding-notifications-toggle");
 
   if (urlInput) {
-    const savedUrl = localStorage.getItem(STORAGE_KEY_SPEECHMATICS_URL);
+    const savedUrl = await getSettings()
+      .then(({ transcription }) => transcription.rtUrl)
+      .catch(() => null);
     urlInput.placeholder = DEFAULT_SPEECHMATICS_URL;
     urlInput.value = savedUrl && savedUrl.trim().length > 0 ? savedUrl : DEFAULT_SPEECHMATICS_URL;
   }
@@ -502,7 +505,7 @@ function closeOnboarding() {
 }
 
 function handleSkip() {
-  localStorage.setItem(STORAGE_KEY_SPEECHMATICS_URL, DEFAULT_SPEECHMATICS_URL);
+  void updateSettings({ transcription: { rtUrl: DEFAULT_SPEECHMATICS_URL } });
   if (elements.speechmaticsUrlInput) {
     elements.speechmaticsUrlInput.value = DEFAULT_SPEECHMATICS_URL;
   }
@@ -534,13 +537,16 @@ async function persistValues(): Promise<boolean> {
     return false;
   }
 
-  localStorage.setItem(STORAGE_KEY_SPEECHMATICS_URL, speechmaticsUrl);
+  await updateSettings({ transcription: { rtUrl: speechmaticsUrl } });
   if (elements.speechmaticsUrlInput) {
     elements.speechmaticsUrlInput.value = speechmaticsUrl;
   }
 
   const speechmaticsKey = (keyInput?.value || "").trim();
   await setSpeechmaticsApiKey(speechmaticsKey);
+  if (speechmaticsKey) {
+    await completeOnboardingStep("apiKey");
+  }
   if (speechmaticsKey && elements.speechmaticsKeyInput) {
     elements.speechmaticsKeyInput.value = speechmaticsKey;
   }
@@ -608,6 +614,7 @@ async function persistValues(): Promise<boolean> {
     autoStopReminder,
     notificationsEnabled,
   });
+  await completeOnboardingStep("calendar");
 
   if (calendarEnabled && !hasCalendarPermission) {
     showToast("Grant calendar permission to finish turning sync on.", { type: "info" });
@@ -795,6 +802,9 @@ export function initializeOnboarding(): void {
     const existingKey = await getSpeechmaticsApiKey();
     if (!existingKey) {
       await openOnboardingInternal();
+    } else {
+      // Keys saved before setup progress was tracked still count.
+      await completeOnboardingStep("apiKey");
     }
   })();
 }
@@ -1,4 +1,5 @@
 import { db } from "../database";
+import { syncMeetingAttendees } from "../contacts";
 import { elements } from "../dom";
 import { Meeting, MeetingParticipant, Person } from "../types";
 import { getCurrentMeeting, updateMeetingInList } from "../state";
@@ -338,6 +339,7 @@ async function persistParticipants(participants: MeetingParticipant[]): Promise<
   meeting.updatedAt = new Date();
 
   await db.saveMeeting(meeting);
+  await syncMeetingAttendees(meeting);
   updateMeetingInList(meeting);
   renderMeetingsList();
   renderPeoplePanel(meeting);
@@ -4,24 +4,44 @@ import {
   DEFAULT_SPEECHMATICS_URL,
   STORAGE_KEY_OPENAI_ENDPOINT,
   STORAGE_KEY_OPENAI_MODEL,
-  STORAGE_KEY_SPEECHMATICS_URL,
 } from "../../constants";
 import { syncSavedAudioSelection } from "./audio";
+import { getSettings, updateSettings, type RealtimeProvider } from "../../settings";
+import { describeProviderUsage, getProviderUsage } from "../../provider-usage";
+import { completeOnboardingStep } from "../../onboarding-state";
 import {
+  getDeepgramApiKey,
   getOpenAIApiKey,
   getSpeechmaticsApiKey,
   migrateLegacyApiKeys,
+  setDeepgramApiKey,
   setOpenAIApiKey,
   setSpeechmaticsApiKey,
 } from "../../secure-storage";
 
+/** Fills in "hours left this month"; failures are shown in place of the figure. */
+async function refreshProviderUsage(): Promise<void> {
+  const label = document.getElementById("speechmatics-usage");
+  if (!label) return;
+  try {
+    const usage = await getProviderUsage();
+    if (usage) {
+      label.textContent = describeProviderUsage(usage);
+    }
+  } catch (error) {
+    label.textContent = `Couldn't check usage: ${error}`;
+  }
+}
+
 export async function loadApiKeys(): Promise<void> {
   syncSavedAudioSelection();
 
   await migrateLegacyApiKeys();
 
   const speechmaticsKey = await getSpeechmaticsApiKey();
-  const speechmaticsUrl = localStorage.getItem(STORAGE_KEY_SPEECHMATICS_URL);
+  const speechmaticsUrl = await getSettings()
+    .then(({ transcription }) => transcription.rtUrl)
+    .catch(() => null);
   const openaiKey = await getOpenAIApiKey();
   const openaiEndpoint = localStorage.getItem(STORAGE_KEY_OPENAI_ENDPOINT);
   const openaiModel = localStorage.getItem(STORAGE_KEY_OPENAI_MODEL);
@@ -35,6 +55,29 @@ export async function loadApiKeys(): Promise<void> {
     elements.speechmaticsKeyInput.value = speechmaticsKey;
   }
 
+  const deepgramKeyInput = document.getElementById("deepgram-key") as HTMLInputElement | null;
+  if (deepgramKeyInput) {
+    deepgramKeyInput.value = (await getDeepgramApiKey()) ?? "";
+  }
+
+  const allowanceInput = document.getElementById("speechmatics-allowance") as HTMLInputElement | null;
+  const providerSelect = document.getElementById("realtime-provider") as HTMLSelectElement | null;
+  if (allowanceInput || providerSelect) {
+    try {
+      const { transcription } = await getSettings();
+      const allowance = transcription.monthlyAllowanceHours;
+      if (allowanceInput) {
+        allowanceInput.value = allowance === null ? "" : String(allowance);
+      }
+      if (providerSelect) {
+        providerSelect.value = transcription.provider ?? "speechmatics";
+      }
+    } catch (error) {
+      console.warn("Failed to load transcription settings:", error);
+    }
+  }
+  void refreshProviderUsage();
+
   if (elements.openaiKeyInput) {
     elements.openaiKeyInput.value = openaiKey || "";
   }
@@ -60,13 +103,26 @@ export async function saveApiSettingsFromUi(): Promise<void> {
   const openaiModel = elements.openaiModelInput?.value.trim();
   const speechmaticsUrl = elements.speechmaticsUrlInput?.value.trim();
 
-  if (speechmaticsUrl) {
-    localStorage.setItem(STORAGE_KEY_SPEECHMATICS_URL, speechmaticsUrl);
-  } else {
-    localStorage.removeItem(STORAGE_KEY_SPEECHMATICS_URL);
-  }
+  const allowanceValue = (document.getElementById("speechmatics-allowance") as HTMLInputElement | null)
+    ?.value.trim();
+  const allowance = allowanceValue ? Number(allowanceValue) : NaN;
+  const provider = (document.getElementById("realtime-provider") as HTMLSelectElement | null)?.value as
+    | RealtimeProvider
+    | undefined;
+  await updateSettings({
+    transcription: {
+      provider: provider ?? "speechmatics",
+      rtUrl: speechmaticsUrl || null,
+      monthlyAllowanceHours: Number.isFinite(allowance) && allowance > 0 ? allowance : null,
+    },
+  });
   await setSpeechmaticsApiKey(speechmaticsKey);
+  if (speechmaticsKey) {
+    await completeOnboardingStep("apiKey");
+  }
+  await setDeepgramApiKey((document.getElementById("deepgram-key") as HTMLInputElement | null)?.value.trim());
   await setOpenAIApiKey(openaiKey);
+  void refreshProviderUsage();
 
   if (openaiEndpoint) {
     localStorage.setItem(STORAGE_KEY_OPENAI_ENDPOINT, openaiEndpoint);
@@ -1,6 +1,7 @@
 import { elements } from "../../dom";
 import {
   clearMuteShortcut,
+  getShortcutStatus,
   registerGlobalShortcut,
   registerMuteShortcut,
 } from "./shortcuts";
@@ -58,6 +59,12 @@ export async function saveSettings(): Promise<void> {
     clearMuteShortcut();
   }
 
+  // Bound but unlikely to fire, e.g. on Wayland.
+  const caveat = (await getShortcutStatus().catch(() => [])).find((status) => status.error);
+  if (caveat) {
+    showToast(caveat.error!, { type: "warning", duration: 8000 });
+  }
+
   closeSettings();
   showToast("Settings saved successfully!", { type: "success" });
 }
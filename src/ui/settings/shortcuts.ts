@@ -13,14 +13,36 @@ export function loadShortcutInputs(): void {
   }
 }
 
+export interface ShortcutStatus {
+  action: "newMeeting" | "mute";
+  shortcut: string;
+  registered: boolean;
+  /** Why the shortcut is not bound, or may not fire on this desktop although it is. */
+  error: string | null;
+}
+
+/**
+ * The backend saves shortcuts in a form every platform reads, so "Cmd+Shift+M"
+ * comes back as "CommandOrControl+Shift+M"; the input shows what was saved.
+ */
 export async function registerGlobalShortcut(shortcut: string): Promise<void> {
-  await invoke("register_global_shortcut", { shortcut });
-  localStorage.setItem("global_shortcut", shortcut);
+  const saved = await invoke<string>("register_global_shortcut", { shortcut });
+  localStorage.setItem("global_shortcut", saved);
+  if (elements.globalShortcutInput) {
+    elements.globalShortcutInput.value = saved;
+  }
 }
 
 export async function registerMuteShortcut(shortcut: string): Promise<void> {
-  await invoke("register_mute_shortcut", { shortcut });
-  localStorage.setItem("mute_shortcut", shortcut);
+  const saved = await invoke<string>("register_mute_shortcut", { shortcut });
+  localStorage.setItem("mute_shortcut", saved);
+  if (elements.muteShortcutInput) {
+    elements.muteShortcutInput.value = saved;
+  }
+}
+
+export function getShortcutStatus(): Promise<ShortcutStatus[]> {
+  return invoke<ShortcutStatus[]>("get_shortcut_status");
 }
 
 export function clearMuteShortcut(): void {
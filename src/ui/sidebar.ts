@@ -3,10 +3,13 @@ import { elements } from "../dom";
 import { getMeetings, getCurrentMeeting, removeMeetingFromList, setCurrentMeeting, setMeetings } from "../state";
 import { formatDate } from "../utils";
 import { db } from "../database";
+import { forgetMeetingAttendees } from "../contacts";
+import { removeMeetingFromProject } from "../projects";
 import { ask } from "@tauri-apps/plugin-dialog";
 import { escapeHtml } from "../utils/html";
 import { loadMeeting, renderMeetingView } from "../meeting-operations";
 import { showToast } from "./interactions";
+import { transcriptMatchIds } from "../transcript-search";
 
 let selectedTags: string[] = [];
 let activeSeriesFilter: { id: string; label: string } | null = null;
@@ -139,6 +142,8 @@ async function handleDeleteMeeting(id: string) {
 
     // Delete from database
     await db.deleteMeeting(id);
+    await forgetMeetingAttendees(id);
+    await removeMeetingFromProject(id);
 
     // Update state
     removeMeetingFromList(id);
@@ -170,7 +175,8 @@ export async function handleSearch(_event?: Event) {
     const allMeetings = await db.getAllMeetings();
     setMeetings(allMeetings);
   } else {
-    const searchResults = await db.searchMeetings(query, { fullContent });
+    const transcriptMatches = fullContent ? await transcriptMatchIds(query) : undefined;
+    const searchResults = await db.searchMeetings(query, { fullContent, transcriptMatches });
     setMeetings(searchResults);
   }
 
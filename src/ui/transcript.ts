@@ -1,10 +1,12 @@
 import { elements } from "../dom";
 import { getCurrentMeeting } from "../state";
-import { TranscriptTurn } from "../types";
+import { TranscriptTurn, TranscriptWord } from "../types";
 import { transcriptTurnsToText, hasTranscriptContent } from "../utils";
 import { showToast } from "./interactions";
 
 const SCROLL_THRESHOLD = 48;
+/** How far ahead in a turn's words a token of its text is looked for. */
+const WORD_LOOKAHEAD = 4;
 
 let autoScrollEnabled = true;
 let isProgrammaticScroll = false;
@@ -115,6 +117,30 @@ function maintainAutoScroll(options?: { smooth?: boolean }): void {
   scrollTranscriptToBottom(options?.smooth ? "smooth" : "auto");
 }
 
+/**
+ * Scrolls to and highlights the turn nearest `offset` seconds into the
+ * recording, e.g. when opened from a citation link.
+ */
+export function focusTranscriptOffset(offset: number): void {
+  ensureTranscriptElements();
+  const rows = Array.from(
+    elements.transcriptContent?.querySelectorAll<HTMLElement>(".transcript-turn[data-offset]") ?? []
+  );
+  let target: HTMLElement | null = null;
+  for (const row of rows) {
+    if (Number(row.dataset.offset) > offset) break;
+    target = row;
+  }
+  target ??= rows[0] ?? null;
+  if (!target) return;
+
+  autoScrollEnabled = false;
+  showAutoScrollButton();
+  target.scrollIntoView({ block: "center", behavior: "smooth" });
+  target.classList.add("transcript-turn--cited");
+  window.setTimeout(() => target?.classList.remove("transcript-turn--cited"), 2400);
+}
+
 function getTranscriptCopyPayload(): { html: string; text: string } | null {
   const meeting = getCurrentMeeting();
   if (!meeting) {
@@ -139,7 +165,7 @@ function getTranscriptCopyPayload(): { html: string; text: string } | null {
     }
 
     const paragraph = document.createElement("p");
-    const speakerLabel = turn.speaker?.trim() || "Speaker";
+    const speakerLabel = turn.note ? "Note" : turn.speaker?.trim() || "Speaker";
     const speakerStrong = document.createElement("strong");
     speakerStrong.textContent = `${speakerLabel}: `;
     paragraph.appendChild(speakerStrong);
@@ -236,6 +262,51 @@ export function handlePartialTranscriptUpdate(): void {
   updateTranscriptActions();
 }
 
+function wordKey(word: string): string {
+  return word.toLocaleLowerCase().replace(/[^\p{L}\p{N}']/gu, "");
+}
+
+/**
+ * Writes a turn's text, underlining words the provider was unsure of. The
+ * text has been formatted since, so its words are matched up in order and
+ * any that were dropped or rewritten are skipped.
+ */
+function appendTurnText(container: HTMLElement, text: string, words?: TranscriptWord[]): void {
+  if (!words?.some((word) => word.low_confidence)) {
+    container.textContent = text;
+    return;
+  }
+
+  let next = 0;
+  for (const token of text.split(/(\s+)/)) {
+    const key = wordKey(token);
+    let match = -1;
+    if (key) {
+      for (let i = next; i < Math.min(words.length, next + WORD_LOOKAHEAD); i++) {
+        if (wordKey(words[i].text) === key) {
+          match = i;
+          break;
+        }
+      }
+    }
+    if (match < 0) {
+      container.append(token);
+      continue;
+    }
+    next = match + 1;
+    const word = words[match];
+    if (!word.low_confidence) {
+      container.append(token);
+      continue;
+    }
+    const span = document.createElement("span");
+    span.className = "transcript-word--low-confidence";
+    span.title = `Confidence ${Math.round(word.confidence * 100)}%`;
+    span.textContent = token;
+    container.appendChild(span);
+  }
+}
+
 export function renderTranscript(container: HTMLElement, transcript: TranscriptTurn[]): void {
   ensureTranscriptElements();
   container.innerHTML = "";
@@ -256,12 +327,15 @@ export function renderTranscript(container: HTMLElement, transcript: TranscriptT
     }
 
     const row = document.createElement("div");
-    row.className = "transcript-turn";
+    row.className = turn.note ? "transcript-turn transcript-turn--note" : "transcript-turn";
     row.dataset.index = index.toString();
+    if (typeof turn.offset === "number") {
+      row.dataset.offset = turn.offset.toString();
+    }
 
     const speakerSpan = document.createElement("span");
     speakerSpan.className = "transcript-speaker";
-    const speakerLabel = turn.speaker?.trim();
+    const speakerLabel = turn.note ? "Note" : turn.speaker?.trim();
     speakerSpan.textContent = speakerLabel || "Speaker";
     if (!speakerLabel) {
       speakerSpan.classList.add("transcript-speaker--unknown");
@@ -269,7 +343,7 @@ export function renderTranscript(container: HTMLElement, transcript: TranscriptT
 
     const textSpan = document.createElement("span");
     textSpan.className = "transcript-text";
-    textSpan.textContent = text;
+    appendTurnText(textSpan, text, turn.words);
 
     row.appendChild(speakerSpan);
     row.appendChild(textSpan);
@@ -0,0 +1,35 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { showToast } from "./ui/interactions";
+
+export interface UpdateInfo {
+  version: string;
+  currentVersion: string;
+  notes: string | null;
+  rolloutPercent: number;
+}
+
+export interface UpdateDownloadProgress {
+  downloaded: number;
+  total: number | null;
+}
+
+/** Checks right away, ignoring staged rollouts. Resolves to `null` when up to date. */
+export async function checkForUpdates(): Promise<UpdateInfo | null> {
+  return invoke<UpdateInfo | null>("check_for_updates");
+}
+
+/** Installs the update found by the last check and restarts the app. Refused while recording. */
+export async function installUpdate(): Promise<void> {
+  await invoke("install_update");
+}
+
+/** The backend holds `update-available` back until any recording has stopped. */
+export async function initializeUpdates(): Promise<void> {
+  await listen<UpdateInfo>("update-available", (event) => {
+    showToast(`Jilu ${event.payload.version} is available. Install it from Settings.`, {
+      type: "info",
+      duration: 8000,
+    });
+  });
+}
@@ -42,12 +42,15 @@ export {
   normalizeEmail,
   debounce,
   transcriptTurnsToText,
+  transcriptTurnsToLinkedText,
+  chapterContents,
+  citationLink,
   transcriptTextToTurns,
   hasTranscriptContent,
 } from "./utils/text";
 
 // Re-export date utilities  
-export { formatDate } from "./utils/date";
+export { formatDate, addDays } from "./utils/date";
 
 /**
  * Renders markdown to HTML in a container
@@ -21,6 +21,16 @@ export function startOfDay(date: Date): Date {
   return result;
 }
 
+/**
+ * Moves a date by whole calendar days, keeping its local time of day even
+ * across DST changes (adding 24h would not).
+ */
+export function addDays(date: Date, days: number): Date {
+  const result = new Date(date);
+  result.setDate(result.getDate() + days);
+  return result;
+}
+
 /**
  * Returns the start of the week (Monday) for a given date
  */
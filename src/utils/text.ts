@@ -2,7 +2,7 @@
  * Text processing utilities
  */
 
-import { TranscriptTurn } from "../types";
+import { TranscriptChapter, TranscriptTurn } from "../types";
 
 /**
  * Normalizes email addresses to lowercase
@@ -22,24 +22,98 @@ export function collapseWhitespace(value: string): string {
   return value.replace(/\s+/g, ' ').trim();
 }
 
+/**
+ * Link that opens a meeting at a moment in its recording
+ */
+export function citationLink(meetingId: string, offset: number): string {
+  return `jilu://meeting/${meetingId}?t=${Math.floor(Math.max(0, offset))}`;
+}
+
+function formatOffset(offset: number): string {
+  const total = Math.floor(Math.max(0, offset));
+  return [Math.floor(total / 3600), Math.floor(total / 60) % 60, total % 60]
+    .map((part) => part.toString().padStart(2, "0"))
+    .join(":");
+}
+
+/** Recordings at least this long get a table of contents above their chapters. */
+const CONTENTS_MIN_SECONDS = 60 * 60;
+
+function chapterHeading(chapters: TranscriptChapter[] | undefined, index: number): string {
+  const chapter = chapters?.find((candidate) => candidate.startTurn === index);
+  return chapter ? `## ${chapter.title}\n\n` : "";
+}
+
+/**
+ * Markdown list of the chapters for hour-plus recordings; empty otherwise
+ */
+export function chapterContents(
+  transcript: TranscriptTurn[] | undefined | null,
+  chapters: TranscriptChapter[] | undefined | null
+): string {
+  if (!transcript || !chapters || chapters.length < 2) {
+    return "";
+  }
+  const length = Math.max(0, ...transcript.map((turn) => turn.offset ?? 0));
+  if (length < CONTENTS_MIN_SECONDS) {
+    return "";
+  }
+
+  const lines = chapters.map((chapter) =>
+    typeof chapter.offset === "number"
+      ? `- ${formatOffset(chapter.offset)} ${chapter.title}`
+      : `- ${chapter.title}`
+  );
+  return `**Contents**\n\n${lines.join("\n")}\n\n`;
+}
+
+/**
+ * Converts transcript turns to markdown, prefixing each turn that has a
+ * recording offset with a link back to that moment and each chapter with a heading
+ */
+export function transcriptTurnsToLinkedText(
+  transcript: TranscriptTurn[] | undefined | null,
+  meetingId: string,
+  chapters?: TranscriptChapter[]
+): string {
+  if (!transcript || !Array.isArray(transcript)) {
+    return "";
+  }
+
+  return transcript
+    .map((turn, index) => {
+      const text = turn.text?.trim() || "";
+      if (!text) return "";
+
+      const speaker = turn.note ? "Note" : turn.speaker?.trim();
+      const line = speaker ? `**${speaker}:** ${text}` : text;
+      const heading = chapterHeading(chapters, index);
+      if (typeof turn.offset !== "number") return heading + line;
+      return `${heading}[${formatOffset(turn.offset)}](${citationLink(meetingId, turn.offset)}) ${line}`;
+    })
+    .filter(Boolean)
+    .join("\n\n");
+}
+
 /**
  * Converts transcript turns to plain text
  */
-export function transcriptTurnsToText(transcript: TranscriptTurn[] | undefined | null): string {
+export function transcriptTurnsToText(
+  transcript: TranscriptTurn[] | undefined | null,
+  chapters?: TranscriptChapter[]
+): string {
   if (!transcript || !Array.isArray(transcript) || transcript.length === 0) {
     return "";
   }
 
   return transcript
-    .map((turn) => {
+    .map((turn, index) => {
       const text = turn.text?.trim() || "";
       if (!text) return "";
       
-      const speaker = turn.speaker?.trim();
-      if (speaker) {
-        return `${speaker}: ${text}`;
-      }
-      return text;
+      const speaker = turn.note ? "Note" : turn.speaker?.trim();
+      const line = speaker ? `${speaker}: ${text}` : text;
+      return chapterHeading(chapters, index) + line;
     })
     .filter(Boolean)
     .join("\n");